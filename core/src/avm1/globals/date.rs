@@ -3,11 +3,10 @@ use crate::avm1::function::{Executable, FunctionObject};
 use crate::avm1::object::NativeObject;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::{Activation, Error, Object, ScriptObject, TObject, Value};
-use crate::locale::{get_current_date_time, get_timezone};
 use crate::string::{AvmString, StringContext};
+use chrono::FixedOffset;
 use gc_arena::Gc;
 use std::cell::Cell;
-use std::fmt;
 
 #[inline]
 fn rem_euclid_i32(lhs: f64, rhs: i32) -> i32 {
@@ -61,8 +60,8 @@ impl Date {
     }
 
     /// Create from current date and time.
-    fn now() -> Self {
-        Self(get_current_date_time().timestamp_millis() as f64)
+    fn now(activation: &Activation) -> Self {
+        Self(activation.context.current_date_time().timestamp_millis() as f64)
     }
 
     /// Get milliseconds since epoch.
@@ -163,24 +162,24 @@ impl Date {
     }
 
     /// ECMA-262 LocalTZA - Get local timezone adjustment in milliseconds.
-    fn local_tza(&self, _is_utc: bool) -> i32 {
+    fn local_tza(&self, _is_utc: bool, timezone: FixedOffset) -> i32 {
         // TODO: Honor `is_utc` flag.
-        get_timezone().local_minus_utc() * Self::MS_PER_SECOND
+        timezone.local_minus_utc() * Self::MS_PER_SECOND
     }
 
     /// ECMA-262 LocalTime - Convert from UTC to local timezone.
-    fn local(self) -> Self {
-        Self(self.0 + f64::from(self.local_tza(true)))
+    fn local(self, timezone: FixedOffset) -> Self {
+        Self(self.0 + f64::from(self.local_tza(true, timezone)))
     }
 
     /// ECMA-262 UTC - Convert from local timezone to UTC.
-    fn utc(self) -> Self {
-        Self(self.0 - f64::from(self.local_tza(false)))
+    fn utc(self, timezone: FixedOffset) -> Self {
+        Self(self.0 - f64::from(self.local_tza(false, timezone)))
     }
 
     /// Get timezone offset in minutes.
-    fn timezone_offset(&self) -> f64 {
-        (self.0 - self.local().0) / f64::from(Self::MS_PER_MINUTE)
+    fn timezone_offset(&self, timezone: FixedOffset) -> f64 {
+        (self.0 - self.local(timezone).0) / f64::from(Self::MS_PER_MINUTE)
     }
 
     /// ECMA-262 HourFromTime - Get hours (0-23).
@@ -262,12 +261,11 @@ impl Date {
 
         Self(self.0.floor())
     }
-}
 
-impl fmt::Display for Date {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    /// Format a local date as a string, as returned by `Date.toString()`.
+    fn to_local_string(self, timezone: FixedOffset) -> String {
         if !self.is_valid() {
-            return write!(f, "Invalid Date");
+            return "Invalid Date".to_string();
         }
 
         const DAYS_OF_WEEK: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
@@ -275,9 +273,8 @@ impl fmt::Display for Date {
             "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
         ];
 
-        let timezone_offset = (-self.timezone_offset()).clamp_to_i32();
-        write!(
-            f,
+        let timezone_offset = (-self.timezone_offset(timezone)).clamp_to_i32();
+        format!(
             "{} {} {} {:02}:{:02}:{:02} GMT{}{:02}{:02} {}",
             DAYS_OF_WEEK[self.week_day() as usize],
             MONTHS[self.month() as usize],
@@ -352,7 +349,7 @@ fn constructor<'gc>(
 ) -> Result<Value<'gc>, Error<'gc>> {
     let date = match args[..] {
         [] => {
-            let date = Date::now();
+            let date = Date::now(activation);
             if activation.swf_version() > 7 {
                 Date(date.time().round())
             } else {
@@ -366,7 +363,8 @@ fn constructor<'gc>(
             let minute = args.get(4).copied().unwrap_or(0.0);
             let second = args.get(5).copied().unwrap_or(0.0);
             let millisecond = args.get(6).copied().unwrap_or(0.0);
-            Date::new(year, month, date, hour, minute, second, millisecond).utc()
+            Date::new(year, month, date, hour, minute, second, millisecond)
+                .utc(activation.context.timezone())
         }
     };
     this.set_native(
@@ -382,7 +380,9 @@ fn function<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok(AvmString::new_utf8(activation.gc(), Date::now().local().to_string()).into())
+    let timezone = activation.context.timezone();
+    let date = Date::now(activation).local(timezone);
+    Ok(AvmString::new_utf8(activation.gc(), date.to_local_string(timezone)).into())
 }
 
 /// ECMA-262 Date.UTC
@@ -451,6 +451,7 @@ fn method<'gc>(
         _ => return Ok(Value::Undefined),
     };
     let date = date_ref.get();
+    let timezone = activation.context.timezone();
 
     match index {
         GET_TIME => return Ok(date.time().into()),
@@ -460,7 +461,7 @@ fn method<'gc>(
             date_ref.set(new_date);
             return Ok(new_date.time().into());
         }
-        GET_TIMEZONE_OFFSET => return Ok(date.timezone_offset().into()),
+        GET_TIMEZONE_OFFSET => return Ok(date.timezone_offset(timezone).into()),
         _ => {}
     }
 
@@ -492,12 +493,12 @@ fn method<'gc>(
             .or_else(|| (i == index).then_some(f64::NAN))
     };
 
-    let date = if is_utc { date } else { date.local() };
+    let date = if is_utc { date } else { date.local(timezone) };
 
     let set_date = |day: f64, time: f64| {
         let mut date = Date::make_date(day, time);
         if !is_utc {
-            date = date.utc();
+            date = date.utc(timezone);
         }
         date = date.clip();
         date_ref.set(date);
@@ -560,7 +561,7 @@ fn method<'gc>(
             )
             .into()
         }
-        TO_STRING => AvmString::new_utf8(activation.gc(), date.to_string()).into(),
+        TO_STRING => AvmString::new_utf8(activation.gc(), date.to_local_string(timezone)).into(),
         GET_TIME..=GET_TIMEZONE_OFFSET | SET_YEAR.. => unreachable!(), // Handled above.
     })
 }
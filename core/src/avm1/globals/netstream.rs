@@ -2,7 +2,6 @@ use crate::avm1::function::{Executable, FunctionObject};
 use crate::avm1::object::{NativeObject, Object, TObject};
 use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::{Activation, Error, ScriptObject, Value};
-use crate::avm1_stub;
 use crate::streams::NetStream;
 use crate::string::StringContext;
 
@@ -30,14 +29,12 @@ const PROTO_DECLS: &[Declaration] = declare_properties! {
 };
 
 fn get_buffer_length<'gc>(
-    activation: &mut Activation<'_, 'gc>,
+    _activation: &mut Activation<'_, 'gc>,
     this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let NativeObject::NetStream(ns) = this.native() {
-        avm1_stub!(activation, "NetStream", "bufferLength");
-
-        return Ok(ns.buffer_time().into());
+        return Ok(ns.buffer_length().into());
     }
 
     Ok(Value::Undefined)
@@ -142,8 +139,6 @@ fn set_buffer_time<'gc>(
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let NativeObject::NetStream(ns) = this.native() {
-        avm1_stub!(activation, "NetStream", "setBufferTime");
-
         let buffer_time = args
             .get(0)
             .cloned()
@@ -39,9 +39,23 @@ fn allow_insecure_domain<'gc>(
 fn load_policy_file<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm1_stub!(activation, "System.security", "loadPolicyFile");
+    let url = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?;
+    let url = url.to_utf8_lossy();
+    if !activation.context.sockets.load_policy_file(&url)
+        && !activation.context.url_policy_files.load_policy_file(&url)
+    {
+        avm1_stub!(
+            activation,
+            "System.security",
+            "loadPolicyFile",
+            "with unsupported URLs"
+        );
+    }
     Ok(Value::Undefined)
 }
 
@@ -140,11 +140,19 @@ pub fn connect<'gc>(
             .unwrap_or(&Value::Undefined)
             .coerce_to_u16(activation)?;
 
+        let movie = activation.base_clip().movie();
+
         let UpdateContext {
             sockets, navigator, ..
         } = activation.context;
 
-        sockets.connect_avm1(*navigator, this, host.to_utf8_lossy().into_owned(), port);
+        sockets.connect_avm1(
+            *navigator,
+            this,
+            &movie,
+            host.to_utf8_lossy().into_owned(),
+            port,
+        );
 
         // NOTE: At this point we do not know if the connection will succeed
         //       because connecting is an asynchronous process, so we just return true.
@@ -36,7 +36,7 @@ macro_rules! avm_debug {
 }
 
 pub mod activation;
-mod amf;
+pub mod amf;
 pub mod api_version;
 mod array;
 pub mod bytearray;
@@ -156,6 +156,23 @@ pub fn serialize_value<'gc>(
     }
 }
 
+/// Serializes a value to the bytes that `ByteArray.writeObject` would write for it.
+pub fn serialize_to_bytes(value: AmfValue, amf_version: AMFVersion) -> Option<Vec<u8>> {
+    let element = Element::new("", Rc::new(value));
+    let mut lso = Lso::new(vec![element], "", amf_version);
+    let bytes = flash_lso::write::write_to_bytes(&mut lso).ok()?;
+    // This is kind of hacky: We need to strip out the header and any padding so that we only write
+    // the value. In the future, there should be a method to do this in the flash_lso crate.
+    let element_padding = match amf_version {
+        AMFVersion::AMF0 => 8,
+        AMFVersion::AMF3 => 7,
+    };
+    Some(
+        bytes[flash_lso::write::header_length(&lso.header) + element_padding..bytes.len() - 1]
+            .to_vec(),
+    )
+}
+
 fn alias_to_class<'gc>(
     activation: &mut Activation<'_, 'gc>,
     alias: AvmString<'gc>,
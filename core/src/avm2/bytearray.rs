@@ -96,6 +96,9 @@ pub struct ByteArrayStorage {
 
     /// The encoding used when serializing/deserializing using readObject/writeObject
     object_encoding: ObjectEncoding,
+
+    /// Whether this ByteArray is passed by reference when sent to another worker.
+    shareable: bool,
 }
 
 impl ByteArrayStorage {
@@ -106,6 +109,7 @@ impl ByteArrayStorage {
             position: Cell::new(0),
             endian: Endian::Big,
             object_encoding: ObjectEncoding::Amf3,
+            shareable: false,
         }
     }
 
@@ -116,6 +120,7 @@ impl ByteArrayStorage {
             position: Cell::new(0),
             endian: Endian::Big,
             object_encoding: ObjectEncoding::Amf3,
+            shareable: false,
         }
     }
 
@@ -389,6 +394,16 @@ impl ByteArrayStorage {
         self.object_encoding = new_object_encoding;
     }
 
+    #[inline]
+    pub fn shareable(&self) -> bool {
+        self.shareable
+    }
+
+    #[inline]
+    pub fn set_shareable(&mut self, shareable: bool) {
+        self.shareable = shareable;
+    }
+
     #[inline]
     pub fn bytes_available(&self) -> usize {
         self.len().saturating_sub(self.position.get())
@@ -642,6 +642,48 @@ pub fn make_error_2097<'gc>(activation: &mut Activation<'_, 'gc>) -> Error<'gc>
     }
 }
 
+#[inline(never)]
+#[cold]
+pub fn make_error_2122<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    method: &str,
+    content_url: &str,
+) -> Error<'gc> {
+    let url = activation.caller_movie_or_root().url().to_string();
+    let err = security_error(
+        activation,
+        &format!(
+            "Error #2122: Security sandbox violation: {method}: {url} cannot access {content_url}. A policy file is required, but the checkPolicyFile flag was not set when this media was loaded."
+        ),
+        2122,
+    );
+    match err {
+        Ok(err) => Error::AvmError(err),
+        Err(err) => err,
+    }
+}
+
+#[inline(never)]
+#[cold]
+pub fn make_error_2123<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    method: &str,
+    content_url: &str,
+) -> Error<'gc> {
+    let url = activation.caller_movie_or_root().url().to_string();
+    let err = security_error(
+        activation,
+        &format!(
+            "Error #2123: Security sandbox violation: {method}: {url} cannot access {content_url}. No policy files granted access."
+        ),
+        2123,
+    );
+    match err {
+        Ok(err) => Error::AvmError(err),
+        Err(err) => err,
+    }
+}
+
 #[inline(never)]
 #[cold]
 pub fn make_error_2126<'gc>(activation: &mut Activation<'_, 'gc>) -> Error<'gc> {
@@ -169,12 +169,23 @@ pub struct SystemClasses<'gc> {
     pub font: ClassObject<'gc>,
     pub textline: ClassObject<'gc>,
     pub sampledataevent: ClassObject<'gc>,
+    pub activityevent: ClassObject<'gc>,
+    pub shaderevent: ClassObject<'gc>,
+    pub datagramsocketdataevent: ClassObject<'gc>,
+    pub worker: ClassObject<'gc>,
+    pub workerdomain: ClassObject<'gc>,
+    pub messagechannel: ClassObject<'gc>,
+    pub mutex: ClassObject<'gc>,
+    pub condition: ClassObject<'gc>,
     pub avm1movie: ClassObject<'gc>,
     pub focusevent: ClassObject<'gc>,
     pub dictionary: ClassObject<'gc>,
     pub id3info: ClassObject<'gc>,
     pub textrun: ClassObject<'gc>,
     pub sharedobject: ClassObject<'gc>,
+    pub gameinputcontrol: ClassObject<'gc>,
+    pub gameinputdevice: ClassObject<'gc>,
+    pub gameinputevent: ClassObject<'gc>,
 }
 
 #[derive(Clone, Collect)]
@@ -331,12 +342,23 @@ impl<'gc> SystemClasses<'gc> {
             font: object,
             textline: object,
             sampledataevent: object,
+            activityevent: object,
+            shaderevent: object,
+            datagramsocketdataevent: object,
+            worker: object,
+            workerdomain: object,
+            messagechannel: object,
+            mutex: object,
+            condition: object,
             avm1movie: object,
             focusevent: object,
             dictionary: object,
             id3info: object,
             textrun: object,
             sharedobject: object,
+            gameinputcontrol: object,
+            gameinputdevice: object,
+            gameinputevent: object,
         }
     }
 }
@@ -986,6 +1008,7 @@ pub fn init_native_system_classes(activation: &mut Activation<'_, '_>) {
             ("flash.events", "AsyncErrorEvent", asyncerrorevent),
             ("flash.events", "ContextMenuEvent", contextmenuevent),
             ("flash.events", "FocusEvent", focusevent),
+            ("flash.events", "GameInputEvent", gameinputevent),
             ("flash.geom", "Matrix", matrix),
             ("flash.geom", "Matrix3D", matrix3d),
             ("flash.geom", "PerspectiveProjection", perspectiveprojection),
@@ -1011,6 +1034,8 @@ pub fn init_native_system_classes(activation: &mut Activation<'_, '_>) {
             ("flash.text", "TextLineMetrics", textlinemetrics),
             ("flash.text", "TextRun", textrun),
             ("flash.text.engine", "TextLine", textline),
+            ("flash.ui", "GameInputControl", gameinputcontrol),
+            ("flash.ui", "GameInputDevice", gameinputdevice),
             ("flash.filters", "BevelFilter", bevelfilter),
             ("flash.filters", "BitmapFilter", bitmapfilter),
             ("flash.filters", "BlurFilter", blurfilter),
@@ -1027,6 +1052,18 @@ pub fn init_native_system_classes(activation: &mut Activation<'_, '_>) {
             ("flash.filters", "GradientGlowFilter", gradientglowfilter),
             ("flash.filters", "ShaderFilter", shaderfilter),
             ("flash.events", "SampleDataEvent", sampledataevent),
+            ("flash.events", "ActivityEvent", activityevent),
+            ("flash.events", "ShaderEvent", shaderevent),
+            (
+                "flash.events",
+                "DatagramSocketDataEvent",
+                datagramsocketdataevent
+            ),
+            ("flash.system", "Worker", worker),
+            ("flash.system", "WorkerDomain", workerdomain),
+            ("flash.system", "MessageChannel", messagechannel),
+            ("flash.concurrent", "Mutex", mutex),
+            ("flash.concurrent", "Condition", condition),
         ]
     );
 
@@ -5,7 +5,6 @@ pub use crate::avm2::object::date_allocator;
 use crate::avm2::object::{DateObject, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::Error;
-use crate::locale::{get_current_date_time, get_timezone};
 use crate::string::{utils as string_utils, AvmString, WStr};
 use chrono::{DateTime, Datelike, Duration, FixedOffset, LocalResult, TimeZone, Timelike, Utc};
 use num_traits::ToPrimitive;
@@ -202,7 +201,7 @@ pub fn init<'gc>(
     let timestamp = arguments.get(0).unwrap_or(&Value::Undefined);
     if timestamp != &Value::Undefined {
         if arguments.len() > 1 {
-            let timezone = get_timezone();
+            let timezone = activation.context.timezone();
 
             // We need a starting value to adjust from.
             this.set_date_time(Some(
@@ -236,7 +235,7 @@ pub fn init<'gc>(
             }
         }
     } else {
-        this.set_date_time(Some(get_current_date_time()))
+        this.set_date_time(Some(activation.context.current_date_time()))
     }
 
     Ok(Value::Undefined)
@@ -296,7 +295,7 @@ pub fn set_time<'gc>(
 
 /// Implements the `getMilliseconds` method.
 pub fn get_milliseconds<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
+    activation: &mut Activation<'_, 'gc>,
     this: Value<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
@@ -304,10 +303,8 @@ pub fn get_milliseconds<'gc>(
 
     let this = this.as_date_object().unwrap();
 
-    if let Some(date) = this
-        .date_time()
-        .map(|date| date.with_timezone(&get_timezone()))
-    {
+    let timezone = activation.context.timezone();
+    if let Some(date) = this.date_time().map(|date| date.with_timezone(&timezone)) {
         Ok((date.timestamp_subsec_millis() as f64).into())
     } else {
         Ok(f64::NAN.into())
@@ -325,7 +322,8 @@ pub fn _set_milliseconds<'gc>(
     let this = this.as_date_object().unwrap();
     let args = get_arguments_array(args);
 
-    let timestamp = DateAdjustment::new(activation, &get_timezone())
+    let timezone = activation.context.timezone();
+    let timestamp = DateAdjustment::new(activation, &timezone)
         .millisecond(args.get(0))?
         .apply(this);
     Ok(timestamp.into())
@@ -333,7 +331,7 @@ pub fn _set_milliseconds<'gc>(
 
 /// Implements the `getSeconds` method.
 pub fn get_seconds<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
+    activation: &mut Activation<'_, 'gc>,
     this: Value<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
@@ -341,10 +339,8 @@ pub fn get_seconds<'gc>(
 
     let this = this.as_date_object().unwrap();
 
-    if let Some(date) = this
-        .date_time()
-        .map(|date| date.with_timezone(&get_timezone()))
-    {
+    let timezone = activation.context.timezone();
+    if let Some(date) = this.date_time().map(|date| date.with_timezone(&timezone)) {
         Ok((date.second() as f64).into())
     } else {
         Ok(f64::NAN.into())
@@ -362,7 +358,8 @@ pub fn _set_seconds<'gc>(
     let this = this.as_date_object().unwrap();
     let args = get_arguments_array(args);
 
-    let timestamp = DateAdjustment::new(activation, &get_timezone())
+    let timezone = activation.context.timezone();
+    let timestamp = DateAdjustment::new(activation, &timezone)
         .second(args.get(0))?
         .millisecond(args.get(1))?
         .apply(this);
@@ -371,7 +368,7 @@ pub fn _set_seconds<'gc>(
 
 /// Implements `getMinutes` method.
 pub fn get_minutes<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
+    activation: &mut Activation<'_, 'gc>,
     this: Value<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
@@ -379,10 +376,8 @@ pub fn get_minutes<'gc>(
 
     let this = this.as_date_object().unwrap();
 
-    if let Some(date) = this
-        .date_time()
-        .map(|date| date.with_timezone(&get_timezone()))
-    {
+    let timezone = activation.context.timezone();
+    if let Some(date) = this.date_time().map(|date| date.with_timezone(&timezone)) {
         Ok((date.minute() as f64).into())
     } else {
         Ok(f64::NAN.into())
@@ -400,7 +395,8 @@ pub fn _set_minutes<'gc>(
     let this = this.as_date_object().unwrap();
     let args = get_arguments_array(args);
 
-    let timestamp = DateAdjustment::new(activation, &get_timezone())
+    let timezone = activation.context.timezone();
+    let timestamp = DateAdjustment::new(activation, &timezone)
         .minute(args.get(0))?
         .second(args.get(1))?
         .millisecond(args.get(2))?
@@ -410,7 +406,7 @@ pub fn _set_minutes<'gc>(
 
 /// Implements the `getHours` method.
 pub fn get_hours<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
+    activation: &mut Activation<'_, 'gc>,
     this: Value<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
@@ -418,10 +414,8 @@ pub fn get_hours<'gc>(
 
     let this = this.as_date_object().unwrap();
 
-    if let Some(date) = this
-        .date_time()
-        .map(|date| date.with_timezone(&get_timezone()))
-    {
+    let timezone = activation.context.timezone();
+    if let Some(date) = this.date_time().map(|date| date.with_timezone(&timezone)) {
         Ok((date.hour() as f64).into())
     } else {
         Ok(f64::NAN.into())
@@ -439,7 +433,8 @@ pub fn _set_hours<'gc>(
     let this = this.as_date_object().unwrap();
     let args = get_arguments_array(args);
 
-    let timestamp = DateAdjustment::new(activation, &get_timezone())
+    let timezone = activation.context.timezone();
+    let timestamp = DateAdjustment::new(activation, &timezone)
         .hour(args.get(0))?
         .minute(args.get(1))?
         .second(args.get(2))?
@@ -450,7 +445,7 @@ pub fn _set_hours<'gc>(
 
 /// Implements `getDate` method.
 pub fn get_date<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
+    activation: &mut Activation<'_, 'gc>,
     this: Value<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
@@ -458,10 +453,8 @@ pub fn get_date<'gc>(
 
     let this = this.as_date_object().unwrap();
 
-    if let Some(date) = this
-        .date_time()
-        .map(|date| date.with_timezone(&get_timezone()))
-    {
+    let timezone = activation.context.timezone();
+    if let Some(date) = this.date_time().map(|date| date.with_timezone(&timezone)) {
         Ok((date.day() as f64).into())
     } else {
         Ok(f64::NAN.into())
@@ -479,7 +472,8 @@ pub fn _set_date<'gc>(
     let this = this.as_date_object().unwrap();
     let args = get_arguments_array(args);
 
-    let timestamp = DateAdjustment::new(activation, &get_timezone())
+    let timezone = activation.context.timezone();
+    let timestamp = DateAdjustment::new(activation, &timezone)
         .day(args.get(0))?
         .apply(this);
     Ok(timestamp.into())
@@ -487,7 +481,7 @@ pub fn _set_date<'gc>(
 
 /// Implements the `getMonth` method.
 pub fn get_month<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
+    activation: &mut Activation<'_, 'gc>,
     this: Value<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
@@ -495,10 +489,8 @@ pub fn get_month<'gc>(
 
     let this = this.as_date_object().unwrap();
 
-    if let Some(date) = this
-        .date_time()
-        .map(|date| date.with_timezone(&get_timezone()))
-    {
+    let timezone = activation.context.timezone();
+    if let Some(date) = this.date_time().map(|date| date.with_timezone(&timezone)) {
         Ok((date.month0() as f64).into())
     } else {
         Ok(f64::NAN.into())
@@ -516,7 +508,8 @@ pub fn _set_month<'gc>(
     let this = this.as_date_object().unwrap();
     let args = get_arguments_array(args);
 
-    let timestamp = DateAdjustment::new(activation, &get_timezone())
+    let timezone = activation.context.timezone();
+    let timestamp = DateAdjustment::new(activation, &timezone)
         .month(args.get(0))?
         .day(args.get(1))?
         .apply(this);
@@ -525,7 +518,7 @@ pub fn _set_month<'gc>(
 
 /// Implements the `getFullYear` method.
 pub fn get_full_year<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
+    activation: &mut Activation<'_, 'gc>,
     this: Value<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
@@ -533,10 +526,8 @@ pub fn get_full_year<'gc>(
 
     let this = this.as_date_object().unwrap();
 
-    if let Some(date) = this
-        .date_time()
-        .map(|date| date.with_timezone(&get_timezone()))
-    {
+    let timezone = activation.context.timezone();
+    if let Some(date) = this.date_time().map(|date| date.with_timezone(&timezone)) {
         Ok((date.year() as f64).into())
     } else {
         Ok(f64::NAN.into())
@@ -554,7 +545,7 @@ pub fn _set_full_year<'gc>(
     let this = this.as_date_object().unwrap();
     let args = get_arguments_array(args);
 
-    let timezone = get_timezone();
+    let timezone = activation.context.timezone();
     if this.date_time().is_none() {
         this.set_date_time(Some(
             timezone
@@ -574,7 +565,7 @@ pub fn _set_full_year<'gc>(
 
 /// Implements the `getDay` method.
 pub fn get_day<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
+    activation: &mut Activation<'_, 'gc>,
     this: Value<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
@@ -582,10 +573,8 @@ pub fn get_day<'gc>(
 
     let this = this.as_date_object().unwrap();
 
-    if let Some(date) = this
-        .date_time()
-        .map(|date| date.with_timezone(&get_timezone()))
-    {
+    let timezone = activation.context.timezone();
+    if let Some(date) = this.date_time().map(|date| date.with_timezone(&timezone)) {
         Ok((date.weekday().num_days_from_sunday() as f64).into())
     } else {
         Ok(f64::NAN.into())
@@ -865,7 +854,7 @@ pub fn get_utc_day<'gc>(
 
 /// Implements the `getTimezoneOffset` method.
 pub fn get_timezone_offset<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
+    activation: &mut Activation<'_, 'gc>,
     this: Value<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
@@ -873,10 +862,8 @@ pub fn get_timezone_offset<'gc>(
 
     let this = this.as_date_object().unwrap();
 
-    if let Some(date) = this
-        .date_time()
-        .map(|date| date.with_timezone(&get_timezone()))
-    {
+    let timezone = activation.context.timezone();
+    if let Some(date) = this.date_time().map(|date| date.with_timezone(&timezone)) {
         let offset = date.offset().utc_minus_local() as f64;
         Ok((offset / 60.0).into())
     } else {
@@ -923,10 +910,8 @@ pub fn to_string<'gc>(
 
     let this = this.as_date_object().unwrap();
 
-    if let Some(date) = this
-        .date_time()
-        .map(|date| date.with_timezone(&get_timezone()))
-    {
+    let timezone = activation.context.timezone();
+    if let Some(date) = this.date_time().map(|date| date.with_timezone(&timezone)) {
         Ok(AvmString::new_utf8(
             activation.gc(),
             date.format("%a %b %-d %T GMT%z %-Y").to_string(),
@@ -968,10 +953,8 @@ pub fn to_locale_string<'gc>(
 
     let this = this.as_date_object().unwrap();
 
-    if let Some(date) = this
-        .date_time()
-        .map(|date| date.with_timezone(&get_timezone()))
-    {
+    let timezone = activation.context.timezone();
+    if let Some(date) = this.date_time().map(|date| date.with_timezone(&timezone)) {
         Ok(AvmString::new_utf8(
             activation.gc(),
             date.format("%a %b %-d %-Y %T %p").to_string(),
@@ -992,10 +975,8 @@ pub fn to_time_string<'gc>(
 
     let this = this.as_date_object().unwrap();
 
-    if let Some(date) = this
-        .date_time()
-        .map(|date| date.with_timezone(&get_timezone()))
-    {
+    let timezone = activation.context.timezone();
+    if let Some(date) = this.date_time().map(|date| date.with_timezone(&timezone)) {
         Ok(AvmString::new_utf8(activation.gc(), date.format("%T GMT%z").to_string()).into())
     } else {
         Ok("Invalid Date".into())
@@ -1012,10 +993,8 @@ pub fn to_locale_time_string<'gc>(
 
     let this = this.as_date_object().unwrap();
 
-    if let Some(date) = this
-        .date_time()
-        .map(|date| date.with_timezone(&get_timezone()))
-    {
+    let timezone = activation.context.timezone();
+    if let Some(date) = this.date_time().map(|date| date.with_timezone(&timezone)) {
         Ok(AvmString::new_utf8(activation.gc(), date.format("%T %p").to_string()).into())
     } else {
         Ok("Invalid Date".into())
@@ -1032,10 +1011,8 @@ pub fn to_date_string<'gc>(
 
     let this = this.as_date_object().unwrap();
 
-    if let Some(date) = this
-        .date_time()
-        .map(|date| date.with_timezone(&get_timezone()))
-    {
+    let timezone = activation.context.timezone();
+    if let Some(date) = this.date_time().map(|date| date.with_timezone(&timezone)) {
         Ok(AvmString::new_utf8(activation.gc(), date.format("%a %b %-d %-Y").to_string()).into())
     } else {
         Ok("Invalid Date".into())
@@ -1146,7 +1123,7 @@ pub fn parse_full_date<'gc>(
 ) -> Option<f64> {
     const DAYS: [&[u8]; 7] = [b"Sun", b"Mon", b"Tue", b"Wed", b"Thu", b"Fri", b"Sat"];
 
-    let timezone = get_timezone();
+    let timezone = activation.context.timezone();
     let mut final_time = DateAdjustment::new(activation, &timezone);
     let mut new_timezone = None;
     // The Date parser is flash is super flexible, so we need to go through each item individually and parse it to match Flash.
@@ -1,5 +1,6 @@
 //! `flash` namespace
 
+pub mod concurrent;
 pub mod crypto;
 pub mod display;
 #[allow(non_snake_case)]
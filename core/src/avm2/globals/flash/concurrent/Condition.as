@@ -1,8 +1,23 @@
 package flash.concurrent {
     [API("684")]
     public final class Condition {
-        public static const isSupported: Boolean = false;
+        public static const isSupported: Boolean = true;
 
-        public function Condition(mutex: Mutex) {}
+        public function Condition(mutex: Mutex) {
+            if (mutex == null) {
+                throw new ArgumentError("Error #2007: Parameter mutex must be non-null.", 2007);
+            }
+            this.init(mutex);
+        }
+
+        private native function init(mutex:Mutex):void;
+
+        public native function get mutex():Mutex;
+
+        public native function wait(timeout:Number = -1):Boolean;
+
+        public native function notify():void;
+
+        public native function notifyAll():void;
     }
-}
\ No newline at end of file
+}
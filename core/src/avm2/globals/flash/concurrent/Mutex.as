@@ -1,10 +1,18 @@
 package flash.concurrent {
     [API("684")]
     public final class Mutex {
-        public static const isSupported: Boolean = false;
-        
+        public static const isSupported: Boolean = true;
+
         public function Mutex() {
-            throw new Error("Error #1520: Mutex cannot be initialized.", 1520);
+            this.init();
         }
+
+        private native function init():void;
+
+        public native function lock():void;
+
+        public native function tryLock():Boolean;
+
+        public native function unlock():void;
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,99 @@
+//! `flash.concurrent.Condition` native methods
+
+use crate::avm2::activation::Activation;
+use crate::avm2::error::illegal_operation_error;
+use crate::avm2::object::Object;
+use crate::avm2::parameters::ParametersExt;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::worker::Workers;
+use std::time::Duration;
+
+/// Implements `Condition.init`, called by the constructor
+pub fn init<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let mutex = args.get_object(activation, 0, "mutex")?;
+
+    activation.context.workers.create_condition(this, mutex);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Condition.mutex`
+pub fn get_mutex<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    Workers::condition_mutex(activation, this)
+}
+
+/// Implements `Condition.wait`
+pub fn wait<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    check_owner(activation, this, "wait")?;
+
+    // Any negative timeout waits forever.
+    let timeout = args.get_f64(activation, 0)?;
+    let timeout = (timeout >= 0.0).then(|| Duration::from_secs_f64(timeout / 1000.0));
+
+    Ok(activation.context.workers.wait(this, timeout).into())
+}
+
+/// Implements `Condition.notify`
+pub fn notify<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    check_owner(activation, this, "notify")?;
+    activation.context.workers.notify(this, false);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Condition.notifyAll`
+pub fn notify_all<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    check_owner(activation, this, "notifyAll")?;
+    activation.context.workers.notify(this, true);
+
+    Ok(Value::Undefined)
+}
+
+fn check_owner<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    method: &str,
+) -> Result<(), Error<'gc>> {
+    if activation.context.workers.owns_condition_mutex(this) {
+        return Ok(());
+    }
+
+    Err(Error::AvmError(illegal_operation_error(
+        activation,
+        &format!(
+            "Error #1525: The Condition's mutex must be locked by the current worker to call {method}."
+        ),
+        1525,
+    )?))
+}
@@ -0,0 +1,62 @@
+//! `flash.concurrent.Mutex` native methods
+
+use crate::avm2::activation::Activation;
+use crate::avm2::error::illegal_operation_error;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+
+/// Implements `Mutex.init`, called by the constructor
+pub fn init<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    activation.context.workers.create_mutex(this);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Mutex.lock`
+pub fn lock<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    activation.context.workers.lock(this);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Mutex.tryLock`
+pub fn try_lock<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    Ok(activation.context.workers.try_lock(this).into())
+}
+
+/// Implements `Mutex.unlock`
+pub fn unlock<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if !activation.context.workers.unlock(this) {
+        return Err(Error::AvmError(illegal_operation_error(
+            activation,
+            "Error #1522: Mutex must be locked by the current worker.",
+            1522,
+        )?));
+    }
+
+    Ok(Value::Undefined)
+}
@@ -3,7 +3,8 @@
 use crate::avm2::activation::Activation;
 use crate::avm2::bytearray::ByteArrayStorage;
 use crate::avm2::error::{
-    argument_error, make_error_2004, make_error_2007, make_error_2008, range_error, Error2004Type,
+    argument_error, make_error_2004, make_error_2007, make_error_2008, make_error_2122,
+    make_error_2123, range_error, Error2004Type,
 };
 use crate::avm2::filters::FilterAvm2Ext;
 use crate::avm2::globals::slots::{
@@ -24,6 +25,7 @@ use crate::bitmap::{is_size_valid, operations};
 use crate::character::{Character, CompressedBitmap};
 use crate::display_object::TDisplayObject;
 use crate::ecma_conversions::round_to_even;
+use crate::policy_file::{PolicyDenial, Taint};
 use crate::swf::BlendMode;
 use gc_arena::GcCell;
 use ruffle_render::filters::Filter;
@@ -228,6 +230,26 @@ pub fn scroll<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Throws a `SecurityError` if `source` has pixels from another domain that this movie
+/// isn't allowed to read.
+fn check_taint<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    source: &IBitmapDrawable<'gc>,
+    method: &str,
+) -> Result<(), Error<'gc>> {
+    match source.taint(activation.context.url_policy_files) {
+        None => Ok(()),
+        Some(Taint {
+            url,
+            denial: PolicyDenial::NotChecked,
+        }) => Err(make_error_2122(activation, method, &url)),
+        Some(Taint {
+            url,
+            denial: PolicyDenial::NotGranted,
+        }) => Err(make_error_2123(activation, method, &url)),
+    }
+}
+
 /// Implements `BitmapData.copyPixels`.
 pub fn copy_pixels<'gc>(
     activation: &mut Activation<'_, 'gc>,
@@ -256,6 +278,11 @@ pub fn copy_pixels<'gc>(
 
         if let Some(src_bitmap) = source_bitmap.as_bitmap_data() {
             src_bitmap.check_valid(activation)?;
+            check_taint(
+                activation,
+                &IBitmapDrawable::BitmapData(src_bitmap),
+                "BitmapData.copyPixels",
+            )?;
 
             let mut alpha_source = None;
 
@@ -322,6 +349,11 @@ pub fn get_pixels<'gc>(
 
     if let Some(bitmap_data) = this.as_bitmap_data() {
         bitmap_data.check_valid(activation)?;
+        check_taint(
+            activation,
+            &IBitmapDrawable::BitmapData(bitmap_data),
+            "BitmapData.getPixels",
+        )?;
         let rectangle = args.get_object(activation, 0, "rect")?;
         let (x, y, width, height) = get_rectangle_x_y_width_height(activation, rectangle)?;
         let mut storage = ByteArrayStorage::new();
@@ -353,6 +385,11 @@ pub fn copy_pixels_to_byte_array<'gc>(
 
     if let Some(bitmap_data) = this.as_bitmap_data() {
         bitmap_data.check_valid(activation)?;
+        check_taint(
+            activation,
+            &IBitmapDrawable::BitmapData(bitmap_data),
+            "BitmapData.copyPixelsToByteArray",
+        )?;
         let rectangle = args.get_object(activation, 0, "rect")?;
         let storage = args.get_object(activation, 1, "data")?;
         let mut storage = storage.as_bytearray_mut().unwrap();
@@ -380,6 +417,11 @@ pub fn get_vector<'gc>(
 
     if let Some(bitmap_data) = this.as_bitmap_data() {
         bitmap_data.check_valid(activation)?;
+        check_taint(
+            activation,
+            &IBitmapDrawable::BitmapData(bitmap_data),
+            "BitmapData.getVector",
+        )?;
         let rectangle = args.get_object(activation, 0, "rect")?;
         let (x, y, width, height) = get_rectangle_x_y_width_height(activation, rectangle)?;
 
@@ -411,6 +453,11 @@ pub fn get_pixel<'gc>(
 
     if let Some(bitmap_data) = this.as_bitmap_data() {
         bitmap_data.check_valid(activation)?;
+        check_taint(
+            activation,
+            &IBitmapDrawable::BitmapData(bitmap_data),
+            "BitmapData.getPixel",
+        )?;
         let x = args.get_u32(activation, 0)?;
         let y = args.get_u32(activation, 1)?;
         let col = operations::get_pixel(bitmap_data, activation.context.renderer, x, y);
@@ -430,6 +477,11 @@ pub fn get_pixel32<'gc>(
 
     if let Some(bitmap_data) = this.as_bitmap_data() {
         bitmap_data.check_valid(activation)?;
+        check_taint(
+            activation,
+            &IBitmapDrawable::BitmapData(bitmap_data),
+            "BitmapData.getPixel32",
+        )?;
         let x = args.get_u32(activation, 0)?;
         let y = args.get_u32(activation, 1)?;
         let pixel = operations::get_pixel32(bitmap_data, activation.context.renderer, x, y);
@@ -979,6 +1031,8 @@ pub fn draw<'gc>(
             return Err(format!("BitmapData.draw: unexpected source {source:?}").into());
         };
 
+        check_taint(activation, &source, "BitmapData.draw")?;
+
         // If the bitmapdata is invalid, it's fine to return early, since the pixels
         // are inaccessible
         bitmap_data.check_valid(activation)?;
@@ -1060,6 +1114,8 @@ pub fn draw_with_quality<'gc>(
             return Err(format!("BitmapData.drawWithQuality: unexpected source {source:?}").into());
         };
 
+        check_taint(activation, &source, "BitmapData.drawWithQuality")?;
+
         // Unknown quality defaults to stage's quality
         let quality = if let Some(quality) = args.try_get_string(activation, 6)? {
             match quality.parse() {
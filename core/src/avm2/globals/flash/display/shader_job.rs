@@ -1,4 +1,5 @@
 use crate::avm2::bytearray::Endian;
+use crate::avm2::error::error;
 use crate::avm2::globals::slots::{
     flash_display_shader as shader_slots, flash_display_shader_input as shader_input_slots,
     flash_display_shader_job as shader_job_slots,
@@ -7,10 +8,9 @@ use crate::avm2::globals::slots::{
 use crate::avm2::parameters::ParametersExt;
 use crate::avm2::{Activation, Error, Object, TObject, Value};
 use crate::pixel_bender::PixelBenderTypeExt;
+use crate::shader_job::ShaderJobs;
 use crate::string::AvmString;
 
-use crate::avm2_stub_method;
-
 use ruffle_render::backend::{PixelBenderOutput, PixelBenderTarget};
 use ruffle_render::bitmap::PixelRegion;
 use ruffle_render::pixel_bender::{
@@ -191,14 +191,11 @@ pub fn start<'gc>(
     let this = this.as_object().unwrap();
 
     let wait_for_completion = args.get_bool(0);
-    if !wait_for_completion {
-        avm2_stub_method!(
-            activation,
-            "flash.display.ShaderJob",
-            "start",
-            "with waitForCompletion=false"
-        );
-    }
+
+    // Starting a job again discards the result of any run that's still in progress.
+    ShaderJobs::cancel(activation, this);
+    this.set_slot_no_coerce(shader_job_slots::_PROGRESS, 0.into(), activation.gc());
+
     let shader = this
         .get_slot(shader_job_slots::_SHADER)
         .as_object()
@@ -215,31 +212,83 @@ pub fn start<'gc>(
 
     let output_height = this.get_slot(shader_job_slots::_HEIGHT).as_u32();
 
-    let pixel_bender_target = if let Some(bitmap) = target.as_bitmap_data() {
-        let target_bitmap = bitmap.sync(activation.context.renderer);
-        // Perform both a GPU->CPU and CPU->GPU sync before writing to it.
-        // FIXME - are both necessary?
-        let mut target_bitmap_data = target_bitmap.write(activation.gc());
-        target_bitmap_data.update_dirty_texture(activation.context.renderer);
-
-        PixelBenderTarget::Bitmap(
-            target_bitmap_data
-                .bitmap_handle(activation.context.renderer)
-                .expect("Missing handle"),
-        )
-    } else {
-        PixelBenderTarget::Bytes {
+    let pixel_bender_target = match target.as_bitmap_data() {
+        // Asynchronous jobs render to a separate texture, leaving the target untouched
+        // until the job completes.
+        Some(bitmap) if !wait_for_completion => {
+            match activation
+                .context
+                .renderer
+                .create_empty_texture(bitmap.width(), bitmap.height())
+            {
+                Ok(texture) => PixelBenderTarget::Bitmap(texture),
+                Err(e) => {
+                    let message = format!("Error: Failed to run shader: {e}");
+                    return Err(Error::AvmError(error(activation, &message, 0)?));
+                }
+            }
+        }
+        Some(bitmap) => {
+            let target_bitmap = bitmap.sync(activation.context.renderer);
+            // Perform both a GPU->CPU and CPU->GPU sync before writing to it.
+            // FIXME - are both necessary?
+            let mut target_bitmap_data = target_bitmap.write(activation.gc());
+            target_bitmap_data.update_dirty_texture(activation.context.renderer);
+
+            PixelBenderTarget::Bitmap(
+                target_bitmap_data
+                    .bitmap_handle(activation.context.renderer)
+                    .expect("Missing handle"),
+            )
+        }
+        None => PixelBenderTarget::Bytes {
             width: output_width,
             height: output_height,
+        },
+    };
+
+    let output = match activation.context.renderer.run_pixelbender_shader(
+        shader_handle,
+        &arguments,
+        &pixel_bender_target,
+    ) {
+        Ok(output) => output,
+        Err(e) => {
+            let message = format!("Error: Failed to run shader: {e}");
+            return Err(Error::AvmError(error(activation, &message, 0)?));
         }
     };
 
-    let output = activation
-        .context
-        .renderer
-        .run_pixelbender_shader(shader_handle, &arguments, &pixel_bender_target)
-        .expect("Failed to run shader");
+    if !wait_for_completion {
+        ShaderJobs::start(activation, this, target, output);
+        return Ok(Value::Undefined);
+    }
+
+    write_output(activation, target, output);
+    this.set_slot_no_coerce(shader_job_slots::_PROGRESS, 1.into(), activation.gc());
 
+    Ok(Value::Undefined)
+}
+
+/// Implements `ShaderJob.cancel`.
+pub fn cancel<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    ShaderJobs::cancel(activation, this);
+
+    Ok(Value::Undefined)
+}
+
+/// Writes the result of running a shader to the target of a `ShaderJob`.
+pub fn write_output<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    target: Object<'gc>,
+    output: PixelBenderOutput,
+) {
     match output {
         PixelBenderOutput::Bitmap(sync_handle) => {
             let target_bitmap = target
@@ -269,6 +318,4 @@ pub fn start<'gc>(
             }
         }
     }
-
-    Ok(Value::Undefined)
 }
@@ -5,6 +5,36 @@ package flash.media {
     import flash.display.BitmapData;
 
     public final class Camera extends EventDispatcher {
+        // The instances returned by `getCamera`, by device index.
+        private static var _cameras:Array = [];
+
+        [Ruffle(NativeAccessible)]
+        private var _index:int;
+
+        private var _name:String;
+
+        [Ruffle(NativeAccessible)]
+        private var _width:int = 160;
+
+        [Ruffle(NativeAccessible)]
+        private var _height:int = 120;
+
+        [Ruffle(NativeAccessible)]
+        private var _fps:Number = 15;
+
+        [Ruffle(NativeAccessible)]
+        private var _currentFPS:Number = 0;
+
+        // -1 until the camera starts capturing.
+        [Ruffle(NativeAccessible)]
+        private var _activityLevel:Number = -1;
+
+        [Ruffle(NativeAccessible)]
+        private var _motionLevel:int = 50;
+
+        [Ruffle(NativeAccessible)]
+        private var _motionTimeout:int = 2000;
+
         [API("682")]
         public function copyToByteArray(rect:Rectangle, destination:ByteArray) {
             __ruffle__.stub_method("flash.media.Camera", "copyToByteArray");
@@ -20,11 +50,25 @@ package flash.media {
             __ruffle__.stub_method("flash.media.Camera", "drawToBitmapData");
         }
 
-        public static function getCamera(name: String = null):Camera {
-            __ruffle__.stub_method("flash.media.Camera", "getCamera");
-            return null;
+        public static function getCamera(name:String = null):Camera {
+            var names:Array = Camera.names;
+            // Cameras are requested by the string form of their index.
+            var index:int = name == null ? 0 : int(name);
+            if (index < 0 || index >= names.length) {
+                return null;
+            }
+
+            if (!_cameras[index]) {
+                var camera:Camera = new Camera();
+                camera._index = index;
+                camera._name = names[index];
+                _cameras[index] = camera;
+            }
+            return _cameras[index];
         }
 
+        private native function updateMode():void;
+
         public function setKeyFrameInterval(keyFrameInterval:int) {
             __ruffle__.stub_method("flash.media.Camera", "setKeyFrameInterval");
         }
@@ -34,11 +78,18 @@ package flash.media {
         }
 
         public function setMode(width:int, height:int, fps:Number, favorArea:Boolean = true) {
-            __ruffle__.stub_method("flash.media.Camera", "setMode");
+            if (!favorArea) {
+                __ruffle__.stub_method("flash.media.Camera", "setMode", "with favorArea = false");
+            }
+            this._width = width;
+            this._height = height;
+            this._fps = fps;
+            this.updateMode();
         }
 
         public function setMotionLevel(motionLevel:int, timeout:int = 2000) {
-            __ruffle__.stub_method("flash.media.Camera", "setMotionLevel");
+            this._motionLevel = Math.max(0, Math.min(100, motionLevel));
+            this._motionTimeout = timeout;
         }
 
         public function setQuality(bandwidth:int, quality:int) {
@@ -46,8 +97,7 @@ package flash.media {
         }
 
         public function get activityLevel(): Number {
-            __ruffle__.stub_getter("flash.media.Camera", "activityLevel");
-            return 0;
+            return this._activityLevel;
         }
 
         public function get bandwidth(): int {
@@ -56,28 +106,23 @@ package flash.media {
         }
 
         public function get currentFPS(): Number {
-            __ruffle__.stub_getter("flash.media.Camera", "currentFPS");
-            return 0;
+            return this._currentFPS;
         }
 
         public function get fps(): Number {
-            __ruffle__.stub_getter("flash.media.Camera", "fps");
-            return 0;
+            return this._fps;
         }
 
         public function get height(): int {
-            __ruffle__.stub_getter("flash.media.Camera", "height");
-            return 0;
+            return this._height;
         }
 
         public function get index(): int {
-            __ruffle__.stub_getter("flash.media.Camera", "index");
-            return 0;
+            return this._index;
         }
 
         public static function get isSupported(): Boolean {
-            __ruffle__.stub_getter("flash.media.Camera", "isSupported");
-            return false;
+            return true;
         }
 
         public function get keyFrameInterval(): int {
@@ -91,39 +136,31 @@ package flash.media {
         }
 
         public function get motionLevel(): int {
-            __ruffle__.stub_getter("flash.media.Camera", "motionLevel");
-            return 0;
+            return this._motionLevel;
         }
 
         public function get motionTimeout(): int {
-            __ruffle__.stub_getter("flash.media.Camera", "motionTimeout");
-            return 0;
+            return this._motionTimeout;
         }
 
         public function get muted(): Boolean {
-            __ruffle__.stub_getter("flash.media.Camera", "muted");
-            return true;
+            return false;
         }
 
         public function get name(): String {
-            __ruffle__.stub_getter("flash.media.Camera", "name");
-            return "";
+            return this._name;
         }
 
-        public static function get names(): Array {
-            __ruffle__.stub_getter("flash.media.Camera", "names");
-            return [];
-        }
-        
+        public static native function get names(): Array;
+
         public function get quality(): int {
             __ruffle__.stub_getter("flash.media.Camera", "quality");
             return 0;
         }
-        
+
         public function get width(): int {
-            __ruffle__.stub_getter("flash.media.Camera", "width");
-            return 0;
+            return this._width;
         }
     }
 
-}
\ No newline at end of file
+}
@@ -1,17 +1,76 @@
 package flash.media {
+    import flash.events.ActivityEvent;
     import flash.events.EventDispatcher;
+    import flash.events.SampleDataEvent;
 
     public final class Microphone extends EventDispatcher {
-        
+        // The sample rates supported by `rate`, in kHz.
+        private static const RATES:Array = [5, 8, 11, 22, 44];
+
+        // The instances returned by `getMicrophone`, by device index.
+        private static var _microphones:Array = [];
+
+        [Ruffle(NativeAccessible)]
+        private var _index:int;
+
+        private var _name:String;
+
+        [Ruffle(NativeAccessible)]
+        private var _gain:Number = 50;
+
+        [Ruffle(NativeAccessible)]
+        private var _rate:int = 8;
+
+        [Ruffle(NativeAccessible)]
+        private var _silenceLevel:Number = 10;
+
+        [Ruffle(NativeAccessible)]
+        private var _silenceTimeout:int = 2000;
+
+        // -1 until the microphone starts capturing.
+        [Ruffle(NativeAccessible)]
+        private var _activityLevel:Number = -1;
+
         [API("672")]
         public static function getEnhancedMicrophone(index:int = -1):Microphone {
             __ruffle__.stub_method("flash.media.Microphone", "getEnhancedMicrophone");
-            return new Microphone();
+            return getMicrophone(index);
         }
 
         public static function getMicrophone(index:int = -1):Microphone {
-            __ruffle__.stub_method("flash.media.Microphone", "getMicrophone");
-            return new Microphone();
+            var names:Array = Microphone.names;
+            if (index == -1) {
+                index = 0;
+            }
+            if (index < 0 || index >= names.length) {
+                return null;
+            }
+
+            if (!_microphones[index]) {
+                var microphone:Microphone = new Microphone();
+                microphone._index = index;
+                microphone._name = names[index];
+                _microphones[index] = microphone;
+            }
+            return _microphones[index];
+        }
+
+        private native function startCapture():void;
+
+        private native function stopCapture():void;
+
+        override public function addEventListener(type:String, listener:Function, useCapture:Boolean = false, priority:int = 0, useWeakReference:Boolean = false):void {
+            super.addEventListener(type, listener, useCapture, priority, useWeakReference);
+            if (type == SampleDataEvent.SAMPLE_DATA || type == ActivityEvent.ACTIVITY) {
+                this.startCapture();
+            }
+        }
+
+        override public function removeEventListener(type:String, listener:Function, useCapture:Boolean = false):void {
+            super.removeEventListener(type, listener, useCapture);
+            if (!this.hasEventListener(SampleDataEvent.SAMPLE_DATA) && !this.hasEventListener(ActivityEvent.ACTIVITY)) {
+                this.stopCapture();
+            }
         }
 
         public function setLoopBack(isLooped:Boolean=true) {
@@ -19,7 +78,10 @@ package flash.media {
         }
 
         public function setSilenceLevel(silenceLevel:Number, timeout:int = -1) {
-            __ruffle__.stub_method("flash.media.Microphone", "setSilenceLevel");
+            this._silenceLevel = Math.max(0, Math.min(100, silenceLevel));
+            if (timeout >= 0) {
+                this._silenceTimeout = timeout;
+            }
         }
 
         public function setUseEchoSuppression(isEchoSuppressed:Boolean) {
@@ -27,8 +89,7 @@ package flash.media {
         }
 
         public function get activityLevel():Number {
-            __ruffle__.stub_getter("flash.media.Microphone", "activityLevel");
-            return 0.0;
+            return this._activityLevel;
         }
 
         public function get codec():String {
@@ -79,38 +140,30 @@ package flash.media {
         }
 
         public function get gain():Number {
-            __ruffle__.stub_getter("flash.media.Microphone", "gain");
-            return 0.0;
+            return this._gain;
         }
 
         public function set gain(gain:Number) {
-            __ruffle__.stub_setter("flash.media.Microphone", "gain");
+            this._gain = Math.max(0, Math.min(100, gain));
         }
 
         public function get index():int {
-            __ruffle__.stub_getter("flash.media.Microphone", "index");
-            return 0;
+            return this._index;
         }
 
         public static function get isSupported():Boolean {
-            __ruffle__.stub_getter("flash.media.Microphone", "isSupported");
-            return false;
+            return true;
         }
 
         public function get muted():Boolean {
-            __ruffle__.stub_getter("flash.media.Microphone", "muted");
-            return true;
+            return false;
         }
 
-        public function get name():Boolean {
-            __ruffle__.stub_getter("flash.media.Microphone", "name");
-            return "";
+        public function get name():String {
+            return this._name;
         }
 
-        public static function get names():Array {
-            __ruffle__.stub_getter("flash.media.Microphone", "names");
-            return [];
-        }
+        public static native function get names():Array;
 
         public function get noiseSuppressionLevel():int {
             __ruffle__.stub_getter("flash.media.Microphone", "noiseSuppressionLevel");
@@ -122,22 +175,26 @@ package flash.media {
         }
 
         public function get rate():int {
-            __ruffle__.stub_getter("flash.media.Microphone", "rate");
-            return 0;
+            return this._rate;
         }
 
-        public function set rate(level:int) {
-            __ruffle__.stub_setter("flash.media.Microphone", "rate");
+        public function set rate(rate:int) {
+            // Unsupported rates are rounded to the closest supported one.
+            var closest:int = RATES[0];
+            for each (var supported:int in RATES) {
+                if (Math.abs(supported - rate) < Math.abs(closest - rate)) {
+                    closest = supported;
+                }
+            }
+            this._rate = closest;
         }
 
-        public function get silenceLevel():int {
-            __ruffle__.stub_getter("flash.media.Microphone", "silenceLevel");
-            return 0;
+        public function get silenceLevel():Number {
+            return this._silenceLevel;
         }
 
         public function get silenceTimeout():int {
-            __ruffle__.stub_getter("flash.media.Microphone", "silenceTimeout");
-            return 0;
+            return this._silenceTimeout;
         }
 
         public function get soundTransform():flash.media.SoundTransform {
@@ -1,5 +1,6 @@
 package flash.media {
     public class SoundLoaderContext {
+        [Ruffle(NativeAccessible)]
         public var bufferTime:Number = 1000;
         public var checkPolicyFile:Boolean = false;
 
@@ -0,0 +1,36 @@
+//! `flash.media.Camera` builtin
+
+use crate::avm2::activation::Activation;
+use crate::avm2::value::Value;
+use crate::avm2::{ArrayObject, ArrayStorage, Error};
+use crate::string::AvmString;
+
+pub fn get_names<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let names: Vec<Value<'gc>> = activation
+        .context
+        .camera
+        .device_names()
+        .into_iter()
+        .map(|name| AvmString::new_utf8(activation.gc(), name).into())
+        .collect();
+
+    Ok(ArrayObject::from_storage(activation, ArrayStorage::from_args(&names)).into())
+}
+
+pub fn update_mode<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(camera) = activation.context.cameras.get(this) {
+        camera.update_mode(activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
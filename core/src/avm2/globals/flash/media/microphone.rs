@@ -0,0 +1,47 @@
+//! `flash.media.Microphone` builtin
+
+use crate::avm2::activation::Activation;
+use crate::avm2::value::Value;
+use crate::avm2::{ArrayObject, ArrayStorage, Error};
+use crate::microphone::Microphones;
+use crate::string::AvmString;
+
+pub fn get_names<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let names: Vec<Value<'gc>> = activation
+        .context
+        .audio_input
+        .device_names()
+        .into_iter()
+        .map(|name| AvmString::new_utf8(activation.gc(), name).into())
+        .collect();
+
+    Ok(ArrayObject::from_storage(activation, ArrayStorage::from_args(&names)).into())
+}
+
+pub fn start_capture<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    Microphones::start(activation, this);
+
+    Ok(Value::Undefined)
+}
+
+pub fn stop_capture<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    Microphones::stop(activation, this);
+
+    Ok(Value::Undefined)
+}
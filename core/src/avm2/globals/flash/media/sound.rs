@@ -1,7 +1,9 @@
 //! `flash.media.Sound` builtin/prototype
 
 use crate::avm2::activation::Activation;
+use crate::avm2::error::make_error_2008;
 use crate::avm2::globals::methods::flash_media_sound as sound_methods;
+use crate::avm2::globals::slots::flash_media_sound_loader_context as sound_loader_context_slots;
 use crate::avm2::globals::slots::flash_net_url_request as url_request_slots;
 use crate::avm2::object::{EventObject, QueuedPlay, SoundChannelObject, TObject};
 use crate::avm2::parameters::ParametersExt;
@@ -12,7 +14,7 @@ use crate::backend::navigator::Request;
 use crate::character::Character;
 use crate::display_object::SoundTransform;
 use crate::{avm2_stub_getter, avm2_stub_method};
-use swf::{SoundEvent, SoundInfo};
+use swf::{AudioCompression, SoundEvent, SoundFormat, SoundInfo};
 
 pub use crate::avm2::object::sound_allocator;
 
@@ -61,6 +63,9 @@ pub fn get_bytes_total<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(sound) = this.as_sound_object() {
+        if let Some(bytes_total) = sound.bytes_total() {
+            return Ok(bytes_total.into());
+        }
         if let Some(sound_handle) = sound.sound_handle() {
             if let Some(length) = activation.context.audio.get_sound_size(sound_handle) {
                 return Ok((length).into());
@@ -72,25 +77,42 @@ pub fn get_bytes_total<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `Sound.bytesLoaded`
 pub fn get_bytes_loaded<'gc>(
     activation: &mut Activation<'_, 'gc>,
     this: Value<'gc>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    // This should have a different value from bytesTotal when the sound is loading.
-    avm2_stub_getter!(activation, "flash.media.Sound", "bytesLoaded");
-    get_bytes_total(activation, this, args)
+    let this = this.as_object().unwrap();
+
+    if let Some(sound) = this.as_sound_object() {
+        // Sounds that weren't loaded from a URL are loaded completely.
+        if sound.url().is_some() {
+            return Ok(sound.bytes_loaded().into());
+        }
+    }
+
+    get_bytes_total(activation, this.into(), args)
 }
 
 /// Implements `Sound.isBuffering`
 pub fn get_is_buffering<'gc>(
     activation: &mut Activation<'_, 'gc>,
-    _this: Value<'gc>,
+    this: Value<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm2_stub_getter!(activation, "flash.media.Sound", "isBuffering");
-    //STUB: We do not yet support network-loaded sounds.
-    Ok(false.into())
+    let this = this.as_object().unwrap();
+
+    if let Some(sound) = this.as_sound_object() {
+        // Playing sounds can run out of data in between the chunks the loader receives.
+        let is_buffering = sound.is_buffering()
+            || sound
+                .sound_handle()
+                .is_some_and(|handle| activation.context.audio.is_sound_buffering(handle));
+        return Ok(is_buffering.into());
+    }
+
+    Ok(Value::Undefined)
 }
 
 /// Implements `Sound.isURLInaccessible`
@@ -106,12 +128,16 @@ pub fn get_is_url_inaccessible<'gc>(
 
 /// Implements `Sound.url`
 pub fn get_url<'gc>(
-    activation: &mut Activation<'_, 'gc>,
-    _this: Value<'gc>,
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm2_stub_getter!(activation, "flash.media.Sound", "url");
-    //STUB: We do not yet support network-loaded sounds.
+    let this = this.as_object().unwrap();
+
+    if let Some(url) = this.as_sound_object().and_then(|sound| sound.url()) {
+        return Ok(url.into());
+    }
+
     Ok(Value::Null)
 }
 
@@ -198,23 +224,53 @@ pub fn play<'gc>(
 /// `Sound.extract`
 pub fn extract<'gc>(
     activation: &mut Activation<'_, 'gc>,
-    _this: Value<'gc>,
+    this: Value<'gc>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm2_stub_method!(activation, "flash.media.Sound", "extract");
+    let this = this.as_object().unwrap();
 
     let bytearray = args.try_get_object(activation, 0);
     let length = args.get_f64(activation, 1)?;
+    let start_position = args.get_f64(activation, 2)?;
+
+    let Some(sound_object) = this.as_sound_object() else {
+        return Ok(0.into());
+    };
+    let Some(sound_handle) = sound_object.sound_handle() else {
+        return Ok(0.into());
+    };
 
-    if let Some(bytearray) = bytearray {
-        if let Some(mut bytearray) = bytearray.as_bytearray_mut() {
+    // A negative start position continues from where the last call stopped.
+    let start = if start_position >= 0.0 {
+        start_position as u64
+    } else {
+        sound_object.extract_position()
+    };
+    let num_frames = if length > 0.0 { length as usize } else { 0 };
+
+    let mut samples = Vec::new();
+    if let Err(e) =
+        activation
+            .context
+            .audio
+            .extract_sound(sound_handle, start, num_frames, &mut samples)
+    {
+        tracing::error!("Sound.extract: Couldn't decode sound: {e}");
+    }
+    sound_object.set_extract_position(start + samples.len() as u64);
+
+    if let Some(mut bytearray) = bytearray.as_ref().and_then(|b| b.as_bytearray_mut()) {
+        for [left, right] in &samples {
+            bytearray
+                .write_float(*left)
+                .map_err(|e| e.to_avm(activation))?;
             bytearray
-                .write_bytes(vec![0u8; length.ceil() as usize].as_slice())
+                .write_float(*right)
                 .map_err(|e| e.to_avm(activation))?;
         }
     }
 
-    Ok(Value::Undefined)
+    Ok(samples.len().into())
 }
 
 /// `Sound.close`
@@ -246,17 +302,20 @@ pub fn load<'gc>(
         .get_slot(url_request_slots::_URL)
         .coerce_to_string(activation)?;
 
-    // TODO: context parameter currently unused.
-    let sound_context = args.try_get_object(activation, 1);
-    if sound_context.is_some() {
-        avm2_stub_method!(activation, "flash.media.Sound", "load", "with context");
-    }
+    // Playback starts once this many milliseconds of audio have loaded.
+    let buffer_time = match args.try_get_object(activation, 1) {
+        Some(context) => context
+            .get_slot(sound_loader_context_slots::BUFFER_TIME)
+            .as_f64(),
+        None => 1000.0,
+    };
 
     let future = activation.context.load_manager.load_sound_avm2(
         activation.context.player.clone(),
         this,
         // FIXME: Set options from the `URLRequest`.
         Request::get(url.to_string()),
+        buffer_time,
     );
     activation.context.navigator.spawn_future(future);
 
@@ -303,10 +362,68 @@ pub fn load_compressed_data_from_byte_array<'gc>(
 /// `Sound.loadPCMFromByteArray`
 pub fn load_pcm_from_byte_array<'gc>(
     activation: &mut Activation<'_, 'gc>,
-    _this: Value<'gc>,
-    _args: &[Value<'gc>],
+    this: Value<'gc>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm2_stub_method!(activation, "flash.media.Sound", "loadPCMFromByteArray");
+    let this = this.as_object().unwrap();
+
+    let bytearray = args.get_object(activation, 0, "bytes")?;
+    let num_samples = args.get_u32(activation, 1)? as usize;
+    let format = args.get_string(activation, 2)?;
+    let is_stereo = args.get_bool(3);
+    let sample_rate = args.get_f64(activation, 4)?;
+
+    let is_float = if &format == b"float" {
+        true
+    } else if &format == b"short" {
+        false
+    } else {
+        return Err(make_error_2008(activation, "format"));
+    };
+
+    let sample_rate = match sample_rate as u32 {
+        rate @ (5512 | 11025 | 22050 | 44100) if f64::from(rate) == sample_rate => rate as u16,
+        _ => return Err(make_error_2008(activation, "sampleRate")),
+    };
+
+    // The samples are converted to the 16-bit little-endian PCM the audio backend expects.
+    let num_channels = if is_stereo { 2 } else { 1 };
+    let sample_size = if is_float { 4 } else { 2 };
+    let data = {
+        let bytearray = bytearray.as_bytearray().unwrap();
+        // `numSamples` comes from the script, so don't trust it for the allocation size.
+        let capacity = (num_samples * num_channels).min(bytearray.bytes_available() / sample_size);
+        let mut data = Vec::with_capacity(capacity * 2);
+        for _ in 0..num_samples * num_channels {
+            let sample = if is_float {
+                let sample = bytearray.read_float().map_err(|e| e.to_avm(activation))?;
+                (sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16
+            } else {
+                bytearray.read_short().map_err(|e| e.to_avm(activation))?
+            };
+            data.extend_from_slice(&sample.to_le_bytes());
+        }
+        data
+    };
+
+    let format = SoundFormat {
+        compression: AudioCompression::Uncompressed,
+        sample_rate,
+        is_stereo,
+        is_16_bit: true,
+    };
+    let handle = activation
+        .context
+        .audio
+        .register_pcm(&data, format)
+        .map_err(|e| {
+            Error::RustError(format!("Failed to register sound from bytearray: {e:?}").into())
+        })?;
+
+    this.as_sound_object()
+        .unwrap()
+        .set_sound(activation.context, handle)?;
+
     Ok(Value::Undefined)
 }
 
@@ -1,6 +1,7 @@
 use crate::avm2::globals::flash::display::display_object::initialize_for_allocator;
 use crate::avm2::parameters::ParametersExt;
 use crate::avm2::{Activation, ClassObject, Error, Object, TObject, Value};
+use crate::camera::Cameras;
 use crate::display_object::{TDisplayObject, Video};
 
 pub fn video_allocator<'gc>(
@@ -79,3 +80,23 @@ pub fn attach_net_stream<'gc>(
 
     Ok(Value::Undefined)
 }
+
+pub fn attach_camera<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(video) = this.as_display_object().and_then(|dobj| dobj.as_video()) {
+        match args.try_get_object(activation, 0) {
+            Some(camera) => {
+                let camera = Cameras::acquire(activation.context, camera);
+                video.attach_camera(activation.context, camera);
+            }
+            None => video.detach(activation.context),
+        }
+    }
+
+    Ok(Value::Undefined)
+}
@@ -1,6 +1,68 @@
 package flash.net {
+    import flash.events.EventDispatcher;
+    import flash.utils.ByteArray;
+
     [API("668")] // AIR 2.0
-    public class DatagramSocket {
+    public class DatagramSocket extends EventDispatcher {
+        [Ruffle(NativeAccessible)]
+        private var _bound:Boolean = false;
+
+        [Ruffle(NativeAccessible)]
+        private var _receiving:Boolean = false;
+
+        [Ruffle(NativeAccessible)]
+        private var _localAddress:String = null;
+
+        [Ruffle(NativeAccessible)]
+        private var _localPort:int = 0;
+
+        [Ruffle(NativeAccessible)]
+        private var _remoteAddress:String = null;
+
+        [Ruffle(NativeAccessible)]
+        private var _remotePort:int = 0;
+
+        public function DatagramSocket() {
+        }
+
+        public static function get isSupported():Boolean {
+            return true;
+        }
+
+        public native function bind(localPort:int = 0, localAddress:String = "0.0.0.0"):void;
+
+        public native function connect(remoteAddress:String, remotePort:int):void;
+
+        public native function send(bytes:ByteArray, offset:uint = 0, length:uint = 0, address:String = null, port:int = 0):void;
+
+        public function receive():void {
+            this._receiving = true;
+        }
+
+        public native function close():void;
+
+        public function get bound():Boolean {
+            return this._bound;
+        }
+
+        public function get connected():Boolean {
+            return this._remoteAddress != null;
+        }
+
+        public function get localAddress():String {
+            return this._localAddress;
+        }
+
+        public function get localPort():int {
+            return this._localPort;
+        }
+
+        public function get remoteAddress():String {
+            return this._remoteAddress;
+        }
 
+        public function get remotePort():int {
+            return this._remotePort;
+        }
     }
-}
\ No newline at end of file
+}
@@ -21,13 +21,9 @@ package flash.net {
 
         }
 
-        public function appendBytes(bytes:ByteArray) {
-            stub_method("flash.net.NetStream", "appendBytes");
-        }
+        public native function appendBytes(bytes:ByteArray);
 
-        public function appendBytesAction(action:String) {
-            stub_method("flash.net.NetStream", "appendBytesAction");
-        }
+        public native function appendBytesAction(action:String);
 
         public function attach(connection:NetConnection) {
             stub_method("flash.net.NetStream", "attach");
@@ -97,9 +93,7 @@ package flash.net {
             stub_method("flash.net.NetStream", "setDRMAuthenticationCredentials");
         }
 
-        public function step(frames:int) {
-            stub_method("flash.net.NetStream", "step");
-        }
+        public native function step(frames:int);
 
         public native function togglePause();
 
@@ -121,33 +115,17 @@ package flash.net {
             stub_setter("flash.net.NetStream", "audioSampleAccess");
         }
 
-        public function get backBufferLength():Number {
-            stub_getter("flash.net.NetStream", "backBufferLength");
-            return 0.0;
-        }
+        public native function get backBufferLength():Number;
 
-        public function get backBufferTime():Number {
-            stub_getter("flash.net.NetStream", "backBufferTime");
-            return 0.0;
-        }
+        public native function get backBufferTime():Number;
 
-        public function set backBufferTime(time:Number) {
-            stub_setter("flash.net.NetStream", "backBufferTime");
-        }
+        public native function set backBufferTime(time:Number);
 
-        public function get bufferLength():Number {
-            stub_getter("flash.net.NetStream", "bufferLength");
-            return 0.0;
-        }
+        public native function get bufferLength():Number;
 
-        public function get bufferTime():Number {
-            stub_getter("flash.net.NetStream", "bufferTime");
-            return 0.0;
-        }
+        public native function get bufferTime():Number;
 
-        public function set bufferTime(time:Number) {
-            stub_setter("flash.net.NetStream", "bufferTime");
-        }
+        public native function set bufferTime(time:Number);
 
         public function get bufferTimeMax():Number {
             stub_getter("flash.net.NetStream", "bufferTimeMax");
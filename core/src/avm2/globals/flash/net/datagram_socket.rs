@@ -0,0 +1,225 @@
+//! `flash.net.DatagramSocket` native methods
+
+use crate::avm2::error::{argument_error, illegal_operation_error, io_error, range_error};
+use crate::avm2::globals::slots::flash_net_datagram_socket as datagram_socket_slots;
+use crate::avm2::parameters::ParametersExt;
+use crate::avm2::string::AvmString;
+use crate::avm2::{Activation, Error, Object, TObject, Value};
+use crate::context::UpdateContext;
+use crate::datagram_socket::{Datagram, DatagramSockets};
+
+/// Implements `DatagramSocket.bind`
+pub fn bind<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let port = args.get_i32(activation, 0)?;
+    let port: u16 = port
+        .try_into()
+        .map_err(|_| invalid_port_number(activation))?;
+    let host = args.get_string(activation, 1)?;
+
+    if activation.context.datagram_sockets.is_bound(this) {
+        return Err(Error::AvmError(illegal_operation_error(
+            activation,
+            "Error #2002: Operation attempted on invalid socket.",
+            2002,
+        )?));
+    }
+
+    bind_socket(activation, this, host.to_utf8_lossy().into_owned(), port)?;
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DatagramSocket.connect`
+pub fn connect<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let host = args.get_string(activation, 0)?;
+    let port = args.get_i32(activation, 1)?;
+    let port: u16 = match port.try_into() {
+        Ok(port) if port != 0 => port,
+        _ => return Err(invalid_port_number(activation)),
+    };
+
+    if !activation.context.datagram_sockets.is_bound(this) {
+        bind_socket(activation, this, "0.0.0.0".to_string(), 0)?;
+    }
+
+    this.set_slot_no_coerce(
+        datagram_socket_slots::_REMOTE_ADDRESS,
+        host.into(),
+        activation.gc(),
+    );
+    this.set_slot_no_coerce(
+        datagram_socket_slots::_REMOTE_PORT,
+        port.into(),
+        activation.gc(),
+    );
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DatagramSocket.send`
+pub fn send<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let bytes = args.get_object(activation, 0, "bytes")?;
+    let offset = args.get_u32(activation, 1)? as usize;
+    let length = args.get_u32(activation, 2)? as usize;
+    let address = args.try_get_string(activation, 3)?;
+    let port = args.get_i32(activation, 4)?;
+
+    let data = {
+        let bytes = bytes.as_bytearray().expect("Parameter is typed ByteArray");
+        let bytes = bytes.bytes();
+
+        // A length of 0 sends everything after the offset.
+        let end = if length == 0 {
+            bytes.len()
+        } else {
+            offset.saturating_add(length)
+        };
+        match bytes.get(offset..end) {
+            Some(data) => data.to_vec(),
+            None => {
+                return Err(Error::AvmError(range_error(
+                    activation,
+                    "Error #2006: The supplied index is out of bounds.",
+                    2006,
+                )?))
+            }
+        }
+    };
+
+    let remote_address = this.get_slot(datagram_socket_slots::_REMOTE_ADDRESS);
+    let (host, port) = match (address, remote_address) {
+        (None, Value::Null) => {
+            return Err(Error::AvmError(argument_error(
+                activation,
+                "Error #2004: One of the parameters is invalid.",
+                2004,
+            )?));
+        }
+        (None, remote_address) => {
+            let port = this.get_slot(datagram_socket_slots::_REMOTE_PORT).as_i32();
+            (remote_address.coerce_to_string(activation)?, port)
+        }
+        (Some(address), Value::Null) => (address, port),
+        // Connected sockets can only send to their remote address.
+        (Some(_), _) => {
+            return Err(Error::AvmError(illegal_operation_error(
+                activation,
+                "Error #2002: Operation attempted on invalid socket.",
+                2002,
+            )?));
+        }
+    };
+    let port: u16 = match port.try_into() {
+        Ok(port) if port != 0 => port,
+        _ => return Err(invalid_port_number(activation)),
+    };
+
+    if !activation.context.datagram_sockets.is_bound(this) {
+        bind_socket(activation, this, "0.0.0.0".to_string(), 0)?;
+    }
+
+    activation.context.datagram_sockets.send(
+        this,
+        Datagram {
+            data,
+            host: host.to_utf8_lossy().into_owned(),
+            port,
+        },
+    );
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DatagramSocket.close`
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if !activation.context.datagram_sockets.is_bound(this) {
+        return Err(invalid_socket_error(activation));
+    }
+
+    activation.context.datagram_sockets.close(this);
+    DatagramSockets::reset(activation, this);
+
+    Ok(Value::Undefined)
+}
+
+fn bind_socket<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    host: String,
+    port: u16,
+) -> Result<(), Error<'gc>> {
+    let UpdateContext {
+        datagram_sockets,
+        navigator,
+        ..
+    } = activation.context;
+
+    let addr = match datagram_sockets.bind(*navigator, this, host, port) {
+        Ok(addr) => addr,
+        Err(e) => {
+            tracing::warn!("Failed to bind DatagramSocket: {e}");
+            return Err(invalid_socket_error(activation));
+        }
+    };
+
+    let local_address = AvmString::new_utf8(activation.gc(), addr.ip().to_string());
+    this.set_slot_no_coerce(datagram_socket_slots::_BOUND, true.into(), activation.gc());
+    this.set_slot_no_coerce(
+        datagram_socket_slots::_LOCAL_ADDRESS,
+        local_address.into(),
+        activation.gc(),
+    );
+    this.set_slot_no_coerce(
+        datagram_socket_slots::_LOCAL_PORT,
+        addr.port().into(),
+        activation.gc(),
+    );
+
+    Ok(())
+}
+
+fn invalid_socket_error<'gc>(activation: &mut Activation<'_, 'gc>) -> Error<'gc> {
+    match io_error(
+        activation,
+        "Error #2002: Operation attempted on invalid socket.",
+        2002,
+    ) {
+        Ok(err) => Error::AvmError(err),
+        Err(e) => e,
+    }
+}
+
+fn invalid_port_number<'gc>(activation: &mut Activation<'_, 'gc>) -> Error<'gc> {
+    match range_error(
+        activation,
+        "Error #2003: Invalid socket port number specified.",
+        2003,
+    ) {
+        Ok(err) => Error::AvmError(err),
+        Err(e) => e,
+    }
+}
@@ -1,6 +1,7 @@
-use crate::avm2::error::{make_error_2004, Error2004Type};
+use crate::avm2::error::{make_error_2004, make_error_2008, Error2004Type};
 use crate::avm2::parameters::ParametersExt;
 use crate::avm2::{Activation, Error, TObject, Value};
+use crate::streams::AppendBytesAction;
 
 pub use crate::avm2::object::netstream_allocator as net_stream_allocator;
 
@@ -32,6 +33,52 @@ pub fn get_bytes_total<'gc>(
     Ok(Value::Undefined)
 }
 
+pub fn append_bytes<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(ns) = this.as_netstream() {
+        let bytearray = args.get_object(activation, 0, "bytes")?;
+        let mut data = bytearray
+            .as_bytearray()
+            .expect("Parameter must be a bytearray!")
+            .bytes()
+            .to_vec();
+
+        ns.append_bytes(activation.context, &mut data);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn append_bytes_action<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(ns) = this.as_netstream() {
+        let action = args.get_string(activation, 0)?;
+        let action = if &action == b"resetBegin" {
+            AppendBytesAction::ResetBegin
+        } else if &action == b"resetSeek" {
+            AppendBytesAction::ResetSeek
+        } else if &action == b"endSequence" {
+            AppendBytesAction::EndSequence
+        } else {
+            return Err(make_error_2008(activation, "action"));
+        };
+
+        ns.append_bytes_action(activation.context, action);
+    }
+
+    Ok(Value::Undefined)
+}
+
 pub fn play<'gc>(
     activation: &mut Activation<'_, 'gc>,
     this: Value<'gc>,
@@ -144,6 +191,107 @@ pub fn seek<'gc>(
     Ok(Value::Undefined)
 }
 
+pub fn step<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(ns) = this.as_netstream() {
+        let frames = args.get_i32(activation, 0)?;
+        ns.step(activation.context, frames);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_back_buffer_length<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(ns) = this.as_netstream() {
+        return Ok(ns.back_buffer_length().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_back_buffer_time<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(ns) = this.as_netstream() {
+        return Ok(ns.back_buffer_time().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_back_buffer_time<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(ns) = this.as_netstream() {
+        let back_buffer_time = args.get_f64(activation, 0)?;
+        ns.set_back_buffer_time(activation.gc(), back_buffer_time);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_buffer_length<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(ns) = this.as_netstream() {
+        return Ok(ns.buffer_length().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_buffer_time<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(ns) = this.as_netstream() {
+        return Ok(ns.buffer_time().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_buffer_time<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(ns) = this.as_netstream() {
+        let buffer_time = args.get_f64(activation, 0)?;
+        ns.set_buffer_time(activation.gc(), buffer_time);
+    }
+
+    Ok(Value::Undefined)
+}
+
 pub fn get_time<'gc>(
     _activation: &mut Activation<'_, 'gc>,
     this: Value<'gc>,
@@ -30,6 +30,7 @@ fn spawn_fetch<'gc>(
         activation.context.player.clone(),
         loader_object,
         request,
+        activation.caller_movie_or_root(),
     );
     activation.context.navigator.spawn_future(future);
     Ok(Value::Undefined)
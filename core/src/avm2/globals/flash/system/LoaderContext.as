@@ -7,6 +7,7 @@ package flash.system {
         [Ruffle(NativeAccessible)]
         public var applicationDomain : ApplicationDomain;
 
+        [Ruffle(NativeAccessible)]
         public var checkPolicyFile : Boolean;
         [API("674")]
         public var imageDecodingPolicy : String;
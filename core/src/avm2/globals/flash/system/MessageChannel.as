@@ -7,5 +7,19 @@ package flash.system {
         public function MessageChannel() {
             super();
         }
+
+        public native function get messageAvailable():Boolean;
+
+        public native function get state():String;
+
+        public native function send(arg:*, queueLimit:int = -1):void;
+
+        public native function receive(blockUntilReceived:Boolean = false):*;
+
+        public native function close():void;
+
+        override public function toString():String {
+            return "[object MessageChannel]";
+        }
     }
 }
@@ -1,10 +1,18 @@
 package flash.system {
+    import flash.utils.ByteArray;
+
     [API("680")] // the docs say 682, that's wrong
     public final class WorkerDomain {
-        public static const isSupported: Boolean = false;
+        public static const isSupported: Boolean = Worker.isSupported;
 
         public function WorkerDomain() {
             throw new ArgumentError("Error #2012: WorkerDomain$ class cannot be instantiated.", 2012)
         }
+
+        public static native function get current():WorkerDomain;
+
+        public native function createWorker(swf:ByteArray, giveAppPrivileges:Boolean = false):Worker;
+
+        public native function listWorkers():Vector.<Worker>;
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,75 @@
+//! `flash.system.MessageChannel` native methods
+
+use crate::avm2::activation::Activation;
+use crate::avm2::parameters::ParametersExt;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::string::AvmString;
+use crate::worker::Workers;
+
+/// Implements `MessageChannel.messageAvailable`
+pub fn get_message_available<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    Ok(activation.context.workers.message_available(this).into())
+}
+
+/// Implements `MessageChannel.state`
+pub fn get_state<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let state = activation.context.workers.channel_state(this);
+    Ok(AvmString::new_utf8(activation.gc(), state).into())
+}
+
+/// Implements `MessageChannel.send`
+pub fn send<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let value = args.get_value(0);
+    let queue_limit = args.get_i32(activation, 1)?;
+
+    if !Workers::send(activation, this, value, queue_limit)? {
+        tracing::warn!("MessageChannel.send: ignoring message sent to a closed channel");
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `MessageChannel.receive`
+pub fn receive<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let block_until_received = args.get_bool(0);
+
+    Workers::receive(activation, this, block_until_received)
+}
+
+/// Implements `MessageChannel.close`
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    activation.context.workers.close(this);
+
+    Ok(Value::Undefined)
+}
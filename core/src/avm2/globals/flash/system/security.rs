@@ -1,6 +1,7 @@
 //! `flash.system.Security` native methods
 
 use crate::avm2::activation::Activation;
+use crate::avm2::parameters::ParametersExt;
 use crate::avm2::value::Value;
 use crate::avm2::Error;
 use crate::avm2_stub_method;
@@ -72,9 +73,20 @@ pub fn allow_insecure_domain<'gc>(
 pub fn load_policy_file<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _this: Value<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm2_stub_method!(activation, "flash.system.Security", "loadPolicyFile");
+    let url = args.get_string(activation, 0)?;
+    let url = url.to_utf8_lossy();
+    if !activation.context.sockets.load_policy_file(&url)
+        && !activation.context.url_policy_files.load_policy_file(&url)
+    {
+        avm2_stub_method!(
+            activation,
+            "flash.system.Security",
+            "loadPolicyFile",
+            "with unsupported URLs"
+        );
+    }
     Ok(Value::Undefined)
 }
 
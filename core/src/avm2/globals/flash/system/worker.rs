@@ -0,0 +1,122 @@
+//! `flash.system.Worker` native methods
+
+use crate::avm2::activation::Activation;
+use crate::avm2::error::illegal_operation_error;
+use crate::avm2::parameters::ParametersExt;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::string::AvmString;
+use crate::worker::{is_supported, Workers};
+
+/// Implements `Worker.isSupported`
+pub fn get_is_supported<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(is_supported().into())
+}
+
+/// Implements `Worker.current`
+pub fn get_current<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Workers::current(activation)?.into())
+}
+
+/// Implements `Worker.isPrimordial`
+pub fn get_is_primordial<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    Ok(activation.context.workers.is_primordial(this).into())
+}
+
+/// Implements `Worker.state`
+pub fn get_state<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let state = activation.context.workers.worker_state(this);
+    Ok(AvmString::new_utf8(activation.gc(), state).into())
+}
+
+/// Implements `Worker.createMessageChannel`
+pub fn create_message_channel<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let receiver = args.get_object(activation, 0, "receiver")?;
+
+    Workers::create_channel(activation, this, receiver)
+}
+
+/// Implements `Worker.getSharedProperty`
+pub fn get_shared_property<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let key = args.get_string(activation, 0)?;
+
+    Workers::get_shared_property(activation, this, &key.to_utf8_lossy())
+}
+
+/// Implements `Worker.setSharedProperty`
+pub fn set_shared_property<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let key = args.get_string(activation, 0)?;
+    let value = args.get_value(1);
+
+    Workers::set_shared_property(activation, this, key.to_utf8_lossy().into_owned(), value)?;
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Worker.start`
+pub fn start<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if !activation.context.workers.start(this) {
+        return Err(Error::AvmError(illegal_operation_error(
+            activation,
+            "Error #1513: Worker can only be started once.",
+            1513,
+        )?));
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Worker.terminate`
+pub fn terminate<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    Ok(activation.context.workers.terminate(this).into())
+}
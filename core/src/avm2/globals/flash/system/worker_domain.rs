@@ -0,0 +1,51 @@
+//! `flash.system.WorkerDomain` native methods
+
+use crate::avm2::activation::Activation;
+use crate::avm2::object::VectorObject;
+use crate::avm2::parameters::ParametersExt;
+use crate::avm2::value::Value;
+use crate::avm2::vector::VectorStorage;
+use crate::avm2::{Error, TObject};
+use crate::worker::Workers;
+
+/// Implements `WorkerDomain.current`
+pub fn get_current<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Workers::worker_domain(activation)?.into())
+}
+
+/// Implements `WorkerDomain.createWorker`
+pub fn create_worker<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let swf = args.get_object(activation, 0, "swf")?;
+    let swf = swf
+        .as_bytearray()
+        .map(|bytes| bytes.bytes().to_vec())
+        .unwrap_or_default();
+
+    // `giveAppPrivileges` only matters for AIR applications.
+    Ok(Workers::create_worker(activation, swf)?.into())
+}
+
+/// Implements `WorkerDomain.listWorkers`
+pub fn list_workers<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let workers = Workers::list(activation)?;
+
+    let storage = VectorStorage::from_values(
+        workers.into_iter().map(Value::from).collect(),
+        false,
+        Some(activation.avm2().classes().worker.inner_class_definition()),
+    );
+
+    Ok(VectorObject::from_vector(storage, activation)?.into())
+}
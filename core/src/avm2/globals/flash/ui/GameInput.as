@@ -3,16 +3,18 @@ package flash.ui {
 
     [API("688")]
     public final class GameInput extends EventDispatcher {
-        public static function get isSupported():Boolean {
-            return false;
+        public function GameInput() {
+            this.init();
         }
 
-        public static function get numDevices():int {
-            return 0;
-        }
+        private native function init():void;
 
-        public static function getDeviceAt(index:int):GameInputDevice {
-            throw new RangeError("Error #1506: The specified range is invalid.", 1506);
+        public static function get isSupported():Boolean {
+            return true;
         }
+
+        public static native function get numDevices():int;
+
+        public static native function getDeviceAt(index:int):GameInputDevice;
     }
-}
\ No newline at end of file
+}
@@ -1,10 +1,42 @@
 package flash.ui {
     import flash.events.EventDispatcher;
 
+    [Ruffle(Abstract)]
     [API("688")]
     public dynamic class GameInputControl extends EventDispatcher {
-        public function GameInputControl() {
-            throw new ArgumentError("Error #2012: GameInputControl$ class cannot be instantiated.", 2012)
+        [Ruffle(NativeAccessible)]
+        private var _device:GameInputDevice;
+
+        [Ruffle(NativeAccessible)]
+        private var _id:String;
+
+        [Ruffle(NativeAccessible)]
+        private var _minValue:Number;
+
+        [Ruffle(NativeAccessible)]
+        private var _maxValue:Number;
+
+        [Ruffle(NativeAccessible)]
+        private var _value:Number = 0;
+
+        public function get device():GameInputDevice {
+            return this._device;
+        }
+
+        public function get id():String {
+            return this._id;
+        }
+
+        public function get minValue():Number {
+            return this._minValue;
+        }
+
+        public function get maxValue():Number {
+            return this._maxValue;
+        }
+
+        public function get value():Number {
+            return this._value;
         }
     }
-}
\ No newline at end of file
+}
@@ -1,11 +1,82 @@
 package flash.ui {
+    import __ruffle__.stub_method;
+
+    import flash.utils.ByteArray;
+
     // The AS3 docs say this is only available in AIR 3.7.
     // That was determined to be a lie.
+    [Ruffle(Abstract)]
     [API("688")]
     public final class GameInputDevice {
         // Specifies the maximum size for the buffer used to cache sampled
         // control values. If `startCachingSamples` returns samples that
         // require more memory than you specify, it throws a memory error.
         public static const MAX_BUFFER_SIZE:int = 32000;
+
+        [Ruffle(NativeAccessible)]
+        private var _id:String;
+
+        [Ruffle(NativeAccessible)]
+        private var _name:String;
+
+        [Ruffle(NativeAccessible)]
+        private var _controls:Array = [];
+
+        // Controls only dispatch change events while their device is enabled.
+        [Ruffle(NativeAccessible)]
+        private var _enabled:Boolean = false;
+
+        private var _sampleInterval:int = 0;
+
+        public function get enabled():Boolean {
+            return this._enabled;
+        }
+
+        public function set enabled(value:Boolean):void {
+            this._enabled = value;
+        }
+
+        public function get id():String {
+            return this._id;
+        }
+
+        public function get name():String {
+            return this._name;
+        }
+
+        public function get numControls():int {
+            return this._controls.length;
+        }
+
+        public function get sampleInterval():int {
+            return this._sampleInterval;
+        }
+
+        public function set sampleInterval(value:int):void {
+            if (value < 0) {
+                throw new RangeError("Error #2027: Parameter sampleInterval must be a non-negative number; got " + value + ".", 2027);
+            }
+            this._sampleInterval = value;
+        }
+
+        public function getControlAt(index:int):GameInputControl {
+            if (index < 0 || index >= this._controls.length) {
+                throw new RangeError("Error #2006: The supplied index is out of bounds.", 2006);
+            }
+            return this._controls[index];
+        }
+
+        public function getCachedSamples(data:ByteArray, append:Boolean = false):int {
+            stub_method("flash.ui.GameInputDevice", "getCachedSamples");
+            return 0;
+        }
+
+        public function startCachingSamples(numSamples:int, controls:Vector.<String>):void {
+            stub_method("flash.ui.GameInputDevice", "startCachingSamples");
+        }
+
+        public function stopCachingSamples():void {
+            stub_method("flash.ui.GameInputDevice", "stopCachingSamples");
+        }
     }
 }
@@ -0,0 +1,49 @@
+//! `flash.ui.GameInput` builtin
+
+use crate::avm2::activation::Activation;
+use crate::avm2::error::range_error;
+use crate::avm2::parameters::ParametersExt;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+
+pub fn get_num_devices<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(activation.context.game_input.len().into())
+}
+
+pub fn get_device_at<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let index = args.get_i32(activation, 0)?;
+
+    let device = usize::try_from(index)
+        .ok()
+        .and_then(|index| activation.context.game_input.get(index));
+
+    match device {
+        Some(device) => Ok(device.into()),
+        // Unlike `make_error_1506`, GameInput words this message differently.
+        None => Err(Error::AvmError(range_error(
+            activation,
+            "Error #1506: The specified range is invalid.",
+            1506,
+        )?)),
+    }
+}
+
+pub fn init<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    activation.context.game_input.register_instance(this);
+
+    Ok(Value::Undefined)
+}
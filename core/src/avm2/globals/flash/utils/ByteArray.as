@@ -24,6 +24,11 @@ package flash.utils {
 		public native function get position():uint;
 		public native function set position(value:uint):void;
 
+		[API("682")]
+		public native function get shareable():Boolean;
+		[API("682")]
+		public native function set shareable(value:Boolean):void;
+
 		public function ByteArray() {
 			this.objectEncoding = _defaultObjectEncoding;
 		}
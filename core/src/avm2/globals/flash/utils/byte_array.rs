@@ -1,5 +1,3 @@
-use std::rc::Rc;
-
 use crate::avm2::activation::Activation;
 use crate::avm2::bytearray::{Endian, ObjectEncoding};
 use crate::avm2::error::make_error_2008;
@@ -13,7 +11,7 @@ use encoding_rs::Encoding;
 use encoding_rs::UTF_8;
 use flash_lso::amf0::read::AMF0Decoder;
 use flash_lso::amf3::read::AMF3Decoder;
-use flash_lso::types::{AMFVersion, Element};
+use flash_lso::types::AMFVersion;
 use ruffle_wstr::WString;
 
 /// Writes a single byte to the bytearray
@@ -865,21 +863,10 @@ pub fn write_object<'gc>(
         )
         .unwrap_or(flash_lso::types::Value::Undefined);
 
-        let element = Element::new("", Rc::new(amf));
-        let mut lso = flash_lso::types::Lso::new(vec![element], "", amf_version);
-        let bytes =
-            flash_lso::write::write_to_bytes(&mut lso).map_err(|_| "Failed to serialize object")?;
-        // This is kind of hacky: We need to strip out the header and any padding so that we only write
-        // the value. In the future, there should be a method to do this in the flash_lso crate.
-        let element_padding = match amf_version {
-            AMFVersion::AMF0 => 8,
-            AMFVersion::AMF3 => 7,
-        };
+        let bytes = crate::avm2::amf::serialize_to_bytes(amf, amf_version)
+            .ok_or("Failed to serialize object")?;
         bytearray
-            .write_bytes(
-                &bytes[flash_lso::write::header_length(&lso.header) + element_padding
-                    ..bytes.len() - 1],
-            )
+            .write_bytes(&bytes)
             .map_err(|e| e.to_avm(activation))?;
     }
 
@@ -921,3 +908,32 @@ pub fn set_object_encoding<'gc>(
 
     Ok(Value::Undefined)
 }
+
+pub fn get_shareable<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(bytearray) = this.as_bytearray() {
+        return Ok(bytearray.shareable().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_shareable<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(mut bytearray) = this.as_bytearray_mut() {
+        let shareable = args.get_bool(0);
+        bytearray.set_shareable(shareable);
+    }
+
+    Ok(Value::Undefined)
+}
@@ -18,6 +18,7 @@ use gc_arena::{
     Collect, Gc, GcWeak, Mutation,
 };
 use id3::{Tag, TagLike};
+use std::cell::Cell;
 use std::io::Cursor;
 use swf::SoundInfo;
 
@@ -38,6 +39,11 @@ pub fn sound_allocator<'gc>(
                 queued_plays: Vec::new(),
             }),
             id3: Lock::new(None),
+            extract_position: Cell::new(0),
+            url: Lock::new(None),
+            bytes_loaded: Cell::new(0),
+            bytes_total: Cell::new(None),
+            is_buffering: Cell::new(false),
         },
     ))
     .into())
@@ -71,6 +77,21 @@ pub struct SoundObjectData<'gc> {
 
     /// ID3Info Object
     id3: Lock<Option<Object<'gc>>>,
+
+    /// The sample frame the next `Sound.extract` call continues from.
+    extract_position: Cell<u64>,
+
+    /// The URL this sound is loaded from, once loading has started.
+    url: Lock<Option<AvmString<'gc>>>,
+
+    /// The number of bytes loaded by `Sound.load` so far.
+    bytes_loaded: Cell<usize>,
+
+    /// The size of the file loaded by `Sound.load`, if known.
+    bytes_total: Cell<Option<usize>>,
+
+    /// Whether playback is waiting for more data to load.
+    is_buffering: Cell<bool>,
 }
 
 const _: () = assert!(std::mem::offset_of!(SoundObjectData, base) == 0);
@@ -167,6 +188,46 @@ impl<'gc> SoundObject<'gc> {
         unlock!(Gc::write(mc, self.0), SoundObjectData, id3).set(id3);
     }
 
+    pub fn extract_position(self) -> u64 {
+        self.0.extract_position.get()
+    }
+
+    pub fn set_extract_position(self, position: u64) {
+        self.0.extract_position.set(position);
+    }
+
+    pub fn url(self) -> Option<AvmString<'gc>> {
+        self.0.url.get()
+    }
+
+    pub fn set_url(self, mc: &Mutation<'gc>, url: Option<AvmString<'gc>>) {
+        unlock!(Gc::write(mc, self.0), SoundObjectData, url).set(url);
+    }
+
+    pub fn bytes_loaded(self) -> usize {
+        self.0.bytes_loaded.get()
+    }
+
+    pub fn set_bytes_loaded(self, bytes_loaded: usize) {
+        self.0.bytes_loaded.set(bytes_loaded);
+    }
+
+    pub fn bytes_total(self) -> Option<usize> {
+        self.0.bytes_total.get()
+    }
+
+    pub fn set_bytes_total(self, bytes_total: Option<usize>) {
+        self.0.bytes_total.set(bytes_total);
+    }
+
+    pub fn is_buffering(self) -> bool {
+        self.0.is_buffering.get()
+    }
+
+    pub fn set_is_buffering(self, is_buffering: bool) {
+        self.0.is_buffering.set(is_buffering);
+    }
+
     pub fn read_and_call_id3_event(self, activation: &mut Activation<'_, 'gc>, bytes: &[u8]) {
         let id3 = activation
             .avm2()
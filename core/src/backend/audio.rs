@@ -1,7 +1,7 @@
 use crate::{
     avm1::{NativeObject, Object as Avm1Object, TObject as _},
     avm2::{Avm2, EventObject as Avm2EventObject, SoundChannelObject},
-    buffer::Substream,
+    buffer::{Buffer, Substream},
     context::UpdateContext,
     display_object::{self, DisplayObject, MovieClip, TDisplayObject},
 };
@@ -18,6 +18,9 @@ pub mod swf {
     };
 }
 
+/// The sample rate of the samples returned by `AudioBackend::extract_sound`.
+pub const EXTRACT_SAMPLE_RATE: u32 = 44100;
+
 #[cfg(feature = "audio")]
 mod mixer;
 #[cfg(feature = "audio")]
@@ -93,6 +96,25 @@ pub trait AudioBackend: Downcast {
     /// Registers MP3 audio from an external source.
     fn register_mp3(&mut self, data: &[u8]) -> Result<SoundHandle, DecodeError>;
 
+    /// Registers MP3 audio from an external source that is still loading.
+    ///
+    /// `data` must contain at least one complete MP3 frame. The loader keeps appending
+    /// to it, and sounds started before loading completes play what is loaded so far.
+    /// When they run out of data, they play silence until `buffer_time` more
+    /// milliseconds of audio have loaded.
+    fn register_mp3_stream(
+        &mut self,
+        data: Buffer,
+        buffer_time: f64,
+    ) -> Result<SoundHandle, DecodeError>;
+
+    /// Marks an MP3 registered with `register_mp3_stream` as completely loaded.
+    fn finish_mp3_stream(&mut self, sound: SoundHandle) -> Result<(), DecodeError>;
+
+    /// Returns whether a playing instance of an MP3 registered with `register_mp3_stream`
+    /// is waiting for more data to load.
+    fn is_sound_buffering(&self, sound: SoundHandle) -> bool;
+
     /// Plays a sound.
     fn start_sound(
         &mut self,
@@ -153,6 +175,39 @@ pub trait AudioBackend: Downcast {
     /// Get the sound format that a given sound was added with.
     fn get_sound_format(&self, sound: SoundHandle) -> Option<&swf::SoundFormat>;
 
+    /// Decodes part of a registered sound into 44.1 kHz stereo samples, as used by `Sound.extract`.
+    ///
+    /// Appends up to `num_frames` sample frames, starting at sample frame `start`, to `samples`.
+    /// Fewer frames are appended if the sound ends before that.
+    fn extract_sound(
+        &mut self,
+        sound: SoundHandle,
+        start: u64,
+        num_frames: usize,
+        samples: &mut Vec<[f32; 2]>,
+    ) -> Result<(), DecodeError>;
+
+    /// Registers a sound from uncompressed little-endian PCM data.
+    ///
+    /// This is used for sounds created from raw samples, such as with `Sound.loadPCMFromByteArray`.
+    fn register_pcm(
+        &mut self,
+        data: &[u8],
+        format: swf::SoundFormat,
+    ) -> Result<SoundHandle, RegisterError> {
+        let bytes_per_sample_frame =
+            (if format.is_stereo { 2 } else { 1 }) * (if format.is_16_bit { 2 } else { 1 });
+        self.register_sound(&swf::Sound {
+            id: 0,
+            num_samples: (data.len() / bytes_per_sample_frame) as u32,
+            format: swf::SoundFormat {
+                compression: swf::AudioCompression::Uncompressed,
+                ..format
+            },
+            data,
+        })
+    }
+
     /// Set the volume transform for a sound instance.
     fn set_sound_transform(&mut self, instance: SoundInstanceHandle, transform: SoundTransform);
 
@@ -258,6 +313,31 @@ impl AudioBackend for NullAudioBackend {
         }))
     }
 
+    fn register_mp3_stream(
+        &mut self,
+        data: Buffer,
+        _buffer_time: f64,
+    ) -> Result<SoundHandle, DecodeError> {
+        Ok(self.sounds.insert(NullSound {
+            size: data.len() as u32,
+            duration: 0.0,
+            format: swf::SoundFormat {
+                compression: swf::AudioCompression::Mp3,
+                sample_rate: 44100,
+                is_stereo: true,
+                is_16_bit: true,
+            },
+        }))
+    }
+
+    fn finish_mp3_stream(&mut self, _sound: SoundHandle) -> Result<(), DecodeError> {
+        Ok(())
+    }
+
+    fn is_sound_buffering(&self, _sound: SoundHandle) -> bool {
+        false
+    }
+
     fn start_sound(
         &mut self,
         _sound: SoundHandle,
@@ -307,6 +387,16 @@ impl AudioBackend for NullAudioBackend {
         self.sounds.get(sound).map(|s| &s.format)
     }
 
+    fn extract_sound(
+        &mut self,
+        _sound: SoundHandle,
+        _start: u64,
+        _num_frames: usize,
+        _samples: &mut Vec<[f32; 2]>,
+    ) -> Result<(), DecodeError> {
+        Ok(())
+    }
+
     fn set_sound_transform(&mut self, _instance: SoundInstanceHandle, _transform: SoundTransform) {}
 
     fn get_sound_peak(&mut self, _instance: SoundInstanceHandle) -> Option<[f32; 2]> {
@@ -332,6 +422,134 @@ impl Default for NullAudioBackend {
     }
 }
 
+#[derive(Debug, Error)]
+pub enum AudioInputError {
+    #[error("No audio input device with index {0}")]
+    NoSuchDevice(usize),
+
+    #[error("Couldn't start capturing audio: {0}")]
+    CaptureFailed(String),
+}
+
+/// A source of captured audio, such as a microphone.
+///
+/// Devices are identified by their index in `device_names`.
+pub trait AudioInputBackend: Downcast {
+    /// Returns the names of the available input devices.
+    fn device_names(&self) -> Vec<String>;
+
+    /// Starts capturing mono audio from a device at the given sample rate.
+    ///
+    /// If the device is already capturing, it is restarted with the new sample rate.
+    fn start_capture(&mut self, device: usize, sample_rate: u32) -> Result<(), AudioInputError>;
+
+    /// Stops capturing audio from a device.
+    fn stop_capture(&mut self, device: usize);
+
+    /// Appends the samples captured by a device since the last call, from -1.0 to 1.0.
+    ///
+    /// `elapsed` is the time passed since the last call in milliseconds.
+    /// Backends that capture in real time may ignore it.
+    fn take_samples(&mut self, device: usize, elapsed: f64, samples: &mut Vec<f32>);
+}
+
+impl_downcast!(AudioInputBackend);
+
+/// Audio input backend without any devices.
+#[derive(Default)]
+pub struct NullAudioInputBackend;
+
+impl NullAudioInputBackend {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl AudioInputBackend for NullAudioInputBackend {
+    fn device_names(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn start_capture(&mut self, device: usize, _sample_rate: u32) -> Result<(), AudioInputError> {
+        Err(AudioInputError::NoSuchDevice(device))
+    }
+
+    fn stop_capture(&mut self, _device: usize) {}
+
+    fn take_samples(&mut self, _device: usize, _elapsed: f64, _samples: &mut Vec<f32>) {}
+}
+
+/// Audio input backend with a single device that generates a sine tone.
+///
+/// The tone advances along with the time given to `take_samples`, which makes it
+/// suitable for deterministic tests.
+pub struct ToneAudioInputBackend {
+    frequency: f64,
+    amplitude: f32,
+
+    /// The sample rate of the current capture, if capturing.
+    sample_rate: Option<u32>,
+
+    /// The number of samples generated so far, including fractions of a sample.
+    position: f64,
+}
+
+impl ToneAudioInputBackend {
+    pub const DEVICE_NAME: &'static str = "Tone Generator";
+
+    pub fn new(frequency: f64, amplitude: f32) -> Self {
+        Self {
+            frequency,
+            amplitude,
+            sample_rate: None,
+            position: 0.0,
+        }
+    }
+
+    /// Changes the amplitude of the generated tone, e.g. to simulate silence.
+    pub fn set_amplitude(&mut self, amplitude: f32) {
+        self.amplitude = amplitude;
+    }
+}
+
+impl AudioInputBackend for ToneAudioInputBackend {
+    fn device_names(&self) -> Vec<String> {
+        vec![Self::DEVICE_NAME.to_string()]
+    }
+
+    fn start_capture(&mut self, device: usize, sample_rate: u32) -> Result<(), AudioInputError> {
+        if device != 0 {
+            return Err(AudioInputError::NoSuchDevice(device));
+        }
+
+        self.sample_rate = Some(sample_rate);
+        self.position = 0.0;
+        Ok(())
+    }
+
+    fn stop_capture(&mut self, device: usize) {
+        if device == 0 {
+            self.sample_rate = None;
+        }
+    }
+
+    fn take_samples(&mut self, device: usize, elapsed: f64, samples: &mut Vec<f32>) {
+        let Some(sample_rate) = self.sample_rate.filter(|_| device == 0) else {
+            return;
+        };
+        let sample_rate = f64::from(sample_rate);
+
+        let start = self.position.floor();
+        self.position += elapsed * sample_rate / 1000.0;
+        let end = self.position.floor();
+
+        let step = std::f64::consts::TAU * self.frequency / sample_rate;
+        samples.extend(
+            (start as u64..end as u64).map(|i| (i as f64 * step).sin() as f32 * self.amplitude),
+        );
+    }
+}
+
 #[derive(Collect)]
 #[collect(no_drop)]
 pub struct AudioManager<'gc> {
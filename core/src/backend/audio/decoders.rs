@@ -8,13 +8,17 @@ mod mp3;
 #[cfg(feature = "nellymoser")]
 mod nellymoser;
 mod pcm;
+#[cfg(feature = "speex")]
+mod speex;
 
 pub use adpcm::AdpcmDecoder;
 #[cfg(feature = "mp3")]
-pub use mp3::{mp3_metadata, Mp3Decoder};
+pub use mp3::{mp3_metadata, Mp3Decoder, Mp3LoadedFrames, Mp3StreamDecoder, Mp3StreamStatus};
 #[cfg(feature = "nellymoser")]
 pub use nellymoser::NellymoserDecoder;
 pub use pcm::PcmDecoder;
+#[cfg(feature = "speex")]
+pub use speex::SpeexDecoder;
 
 use crate::backend::audio::{SoundStreamInfo, SoundStreamWrapping};
 use crate::buffer::{Slice, Substream, SubstreamChunksIter};
@@ -86,6 +90,8 @@ pub fn make_decoder<R: 'static + Read + Send + Sync>(
         AudioCompression::Nellymoser => {
             Box::new(NellymoserDecoder::new(data, format.sample_rate.into()))
         }
+        #[cfg(feature = "speex")]
+        AudioCompression::Speex => Box::new(SpeexDecoder::new(data)),
         _ => return Err(Error::UnhandledCompression(format.compression)),
     };
     Ok(decoder)
@@ -1,5 +1,8 @@
 use crate::backend::audio::decoders::{Decoder, Mp3Metadata, SeekableDecoder};
+use crate::buffer::Buffer;
 use std::io::{Cursor, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use symphonia::{
     core::{
         self, audio, codecs, errors,
@@ -23,6 +26,9 @@ pub enum Error {
 
     #[error("Invalid channels")]
     InvalidChannels,
+
+    #[error("No MP3 frames found")]
+    NoFrames,
 }
 
 pub struct Mp3Decoder {
@@ -199,3 +205,468 @@ pub fn mp3_metadata(data: &std::sync::Arc<[u8]>) -> Result<Mp3Metadata, Error> {
         sample_rate,
     })
 }
+
+/// The bitrates of MPEG-1 Layer III frames, in kbps, by bitrate index.
+const MPEG1_BITRATES: [u32; 15] = [
+    0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+];
+
+/// The bitrates of MPEG-2 and MPEG-2.5 Layer III frames, in kbps, by bitrate index.
+const MPEG2_BITRATES: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+/// The header of an MP3 frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct FrameHeader {
+    sample_rate: u32,
+    num_channels: u8,
+
+    /// The length of the frame in bytes, including the header.
+    frame_len: usize,
+
+    /// The number of sample frames in the frame.
+    num_samples: u32,
+}
+
+impl FrameHeader {
+    /// Parses the header of an MPEG Layer III frame.
+    ///
+    /// Free-format frames aren't supported.
+    fn parse(bytes: [u8; 4]) -> Option<Self> {
+        let header = u32::from_be_bytes(bytes);
+        if header >> 21 != 0x7FF {
+            return None;
+        }
+
+        // 0 is MPEG-2.5, 1 is reserved, 2 is MPEG-2, 3 is MPEG-1.
+        let version = (header >> 19) & 0b11;
+        // 1 is Layer III.
+        let layer = (header >> 17) & 0b11;
+        let bitrate_index = ((header >> 12) & 0b1111) as usize;
+        let sample_rate_index = ((header >> 10) & 0b11) as usize;
+        let padding = ((header >> 9) & 1) as usize;
+        let channel_mode = (header >> 6) & 0b11;
+
+        if version == 1 || layer != 1 || !(1..15).contains(&bitrate_index) || sample_rate_index == 3
+        {
+            return None;
+        }
+
+        let (bitrates, sample_rates, num_samples) = match version {
+            3 => (&MPEG1_BITRATES, [44100, 48000, 32000], 1152),
+            2 => (&MPEG2_BITRATES, [22050, 24000, 16000], 576),
+            _ => (&MPEG2_BITRATES, [11025, 12000, 8000], 576),
+        };
+        let bitrate = bitrates[bitrate_index] * 1000;
+        let sample_rate = sample_rates[sample_rate_index];
+        let frame_len = (num_samples / 8 * bitrate / sample_rate) as usize + padding;
+
+        Some(Self {
+            sample_rate,
+            num_channels: if channel_mode == 0b11 { 1 } else { 2 },
+            frame_len,
+            num_samples,
+        })
+    }
+}
+
+/// Finds the first frame at or after `pos` that is completely contained in `data`.
+///
+/// ID3v2 tags and other junk between frames are skipped.
+/// Returns the start of the frame and its header, or `None` if more data is needed.
+fn find_frame(data: &[u8], mut pos: usize) -> Option<(usize, FrameHeader)> {
+    while pos + 4 <= data.len() {
+        let bytes = &data[pos..];
+        if bytes.starts_with(b"ID3") {
+            if bytes.len() < 10 {
+                return None;
+            }
+            // The tag size is a synchsafe integer, excluding the header and footer.
+            let size = bytes[6..10]
+                .iter()
+                .fold(0, |size, b| (size << 7) | usize::from(b & 0x7F));
+            let has_footer = bytes[5] & 0x10 != 0;
+            pos += 10 + size + if has_footer { 10 } else { 0 };
+            continue;
+        }
+
+        if let Some(header) = FrameHeader::parse([bytes[0], bytes[1], bytes[2], bytes[3]]) {
+            return (bytes.len() >= header.frame_len).then_some((pos, header));
+        }
+        pos += 1;
+    }
+    None
+}
+
+/// Counts the complete frames of an MP3 as it loads.
+///
+/// Each call to `update` only scans the data appended since the previous call.
+#[derive(Debug, Default)]
+pub struct Mp3LoadedFrames {
+    /// The offset to look for the next frame at.
+    position: usize,
+
+    /// The number of sample frames in the frames found so far.
+    num_sample_frames: u32,
+
+    /// The sample rate of the first frame.
+    sample_rate: Option<u32>,
+}
+
+impl Mp3LoadedFrames {
+    /// Scans the newly loaded part of `data`, which must start with the data seen previously.
+    ///
+    /// Returns the sample rate and length of the complete frames loaded so far.
+    pub fn update(&mut self, data: &[u8]) -> Result<Mp3Metadata, Error> {
+        while let Some((start, header)) = find_frame(data, self.position) {
+            self.sample_rate.get_or_insert(header.sample_rate);
+            self.num_sample_frames += header.num_samples;
+            self.position = start + header.frame_len;
+        }
+        let sample_rate = self.sample_rate.ok_or(Error::NoFrames)?;
+        Ok(Mp3Metadata {
+            num_sample_frames: self.num_sample_frames,
+            sample_rate: sample_rate as u16,
+        })
+    }
+}
+
+/// State shared between an MP3 that is still loading and the decoders playing it.
+#[derive(Debug, Default)]
+pub struct Mp3StreamStatus {
+    /// Set once all data has been loaded, to let playing instances end.
+    pub complete: AtomicBool,
+
+    /// Set while a playing instance has run out of loaded data and is waiting for more.
+    pub buffering: AtomicBool,
+}
+
+/// Decodes an MP3 that is still being loaded into a `Buffer`.
+///
+/// Unlike `Mp3Decoder`, this doesn't end when it catches up with the loaded data.
+/// Instead, it starts buffering: it outputs silence until `buffer_sample_frames` more
+/// sample frames have loaded, and only ends once the stream is complete and all frames
+/// were decoded.
+pub struct Mp3StreamDecoder {
+    data: Buffer,
+    status: Arc<Mp3StreamStatus>,
+
+    /// The number of sample frames that must be loaded ahead to resume after running out of data.
+    buffer_sample_frames: u32,
+
+    /// The length of `data` when we last checked whether we could stop buffering.
+    buffered_len: Option<usize>,
+
+    /// The offset in `data` to look for the next frame at.
+    position: usize,
+
+    /// The timestamp of the next frame, in sample frames.
+    timestamp: u64,
+
+    /// The number of decoded sample frames to drop, to seek to a position within a frame.
+    skip_sample_frames: u64,
+
+    decoder: Box<dyn codecs::Decoder>,
+    sample_buf: audio::SampleBuffer<i16>,
+    cur_sample: usize,
+    sample_rate: u16,
+    num_channels: u8,
+    stream_ended: bool,
+}
+
+impl Mp3StreamDecoder {
+    /// Creates a decoder for the MP3 in `data`.
+    ///
+    /// At least one complete frame must be loaded, to determine the format of the MP3.
+    pub fn new(
+        data: Buffer,
+        status: Arc<Mp3StreamStatus>,
+        buffer_sample_frames: u32,
+    ) -> Result<Self, Error> {
+        let (_, header) = find_frame(&data.to_full_slice().data(), 0).ok_or(Error::NoFrames)?;
+        let channels = if header.num_channels == 1 {
+            audio::Channels::FRONT_LEFT
+        } else {
+            audio::Channels::FRONT_LEFT | audio::Channels::FRONT_RIGHT
+        };
+
+        let mut codec_params = codecs::CodecParameters::new();
+        codec_params
+            .for_codec(codecs::CODEC_TYPE_MP3)
+            .with_sample_rate(header.sample_rate)
+            .with_channels(channels);
+        let decoder = symphonia::default::get_codecs().make(&codec_params, &Default::default())?;
+
+        Ok(Self {
+            data,
+            status,
+            buffer_sample_frames,
+            buffered_len: None,
+            position: 0,
+            timestamp: 0,
+            skip_sample_frames: 0,
+            decoder,
+            sample_buf: audio::SampleBuffer::new(
+                0,
+                audio::SignalSpec::new(header.sample_rate, channels),
+            ),
+            cur_sample: 0,
+            sample_rate: header.sample_rate as u16,
+            num_channels: header.num_channels,
+            stream_ended: false,
+        })
+    }
+
+    /// Returns whether playback should wait for more data, updating the shared status.
+    ///
+    /// Once the decoder runs out of data, it waits until `buffer_sample_frames` more
+    /// sample frames are loaded, or until loading completes.
+    fn is_buffering(&mut self, complete: bool) -> bool {
+        let Some(buffered_len) = self.buffered_len else {
+            return false;
+        };
+        if !complete {
+            let slice = self.data.to_full_slice();
+            let data = slice.data();
+            if data.len() == buffered_len {
+                return true;
+            }
+            self.buffered_len = Some(data.len());
+
+            let mut position = self.position;
+            let mut loaded_sample_frames = 0;
+            while let Some((start, header)) = find_frame(&data, position) {
+                loaded_sample_frames += header.num_samples;
+                position = start + header.frame_len;
+                if loaded_sample_frames >= self.buffer_sample_frames {
+                    break;
+                }
+            }
+            if loaded_sample_frames < self.buffer_sample_frames {
+                return true;
+            }
+        }
+        self.buffered_len = None;
+        self.status.buffering.store(false, Ordering::Release);
+        false
+    }
+
+    /// Decodes the next loaded frame into the sample buffer.
+    ///
+    /// Returns `false` if no frame is loaded yet.
+    fn next_frame(&mut self) -> bool {
+        // Check this before looking at the data, so that frames appended
+        // right before loading completes aren't missed.
+        let complete = self.status.complete.load(Ordering::Acquire);
+        if self.is_buffering(complete) {
+            return false;
+        }
+
+        loop {
+            let frame = {
+                let slice = self.data.to_full_slice();
+                let data = slice.data();
+                find_frame(&data, self.position).map(|(start, header)| {
+                    let packet: Box<[u8]> = data[start..start + header.frame_len].into();
+                    (start, header, packet)
+                })
+            };
+            let Some((start, header, packet)) = frame else {
+                self.stream_ended = complete;
+                if !complete {
+                    // Ran out of data, so wait for more to load.
+                    self.buffered_len = Some(self.data.len());
+                    self.status.buffering.store(true, Ordering::Release);
+                }
+                return false;
+            };
+
+            self.position = start + header.frame_len;
+            let num_samples = u64::from(header.num_samples);
+            let packet =
+                formats::Packet::new_from_boxed_slice(0, self.timestamp, num_samples, packet);
+            self.timestamp += num_samples;
+
+            // Frames well before the seek target don't need decoding.
+            // The frame right before it is still decoded, as it may hold data for the next one.
+            if self.skip_sample_frames >= 2 * num_samples {
+                self.skip_sample_frames -= num_samples;
+                continue;
+            }
+
+            self.cur_sample = 0;
+            match self.decoder.decode(&packet) {
+                Ok(decoded) => {
+                    if self.sample_buf.capacity() < decoded.capacity() {
+                        // Ensure our buffer has enough space for the decoded samples.
+                        self.sample_buf = audio::SampleBuffer::new(
+                            decoded.capacity() as core::units::Duration,
+                            *decoded.spec(),
+                        );
+                    }
+                    self.sample_buf.copy_interleaved_ref(decoded);
+                    return true;
+                }
+                // Decode errors are not fatal.
+                Err(errors::Error::DecodeError(_)) => (),
+                Err(_) => {
+                    self.stream_ended = true;
+                    return false;
+                }
+            }
+        }
+    }
+}
+
+impl Iterator for Mp3StreamDecoder {
+    type Item = [i16; 2];
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.cur_sample >= self.sample_buf.len() {
+                if self.stream_ended {
+                    return None;
+                }
+                if !self.next_frame() {
+                    // Play silence while waiting for more data.
+                    return (!self.stream_ended).then_some([0, 0]);
+                }
+            }
+
+            let sample_buf = self.sample_buf.samples();
+            let samples = if self.num_channels == 2 {
+                let samples = [sample_buf[self.cur_sample], sample_buf[self.cur_sample + 1]];
+                self.cur_sample += 2;
+                samples
+            } else {
+                let sample = sample_buf[self.cur_sample];
+                self.cur_sample += 1;
+                [sample, sample]
+            };
+
+            if self.skip_sample_frames > 0 {
+                self.skip_sample_frames -= 1;
+                continue;
+            }
+            return Some(samples);
+        }
+    }
+}
+
+impl Decoder for Mp3StreamDecoder {
+    #[inline]
+    fn num_channels(&self) -> u8 {
+        self.num_channels
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> u16 {
+        self.sample_rate
+    }
+}
+
+impl SeekableDecoder for Mp3StreamDecoder {
+    fn reset(&mut self) {
+        self.seek_to_sample_frame(0);
+    }
+
+    fn seek_to_sample_frame(&mut self, frame: u32) {
+        // MP3 frames can't be located without scanning from the start,
+        // so restart and drop everything before the desired position.
+        self.position = 0;
+        self.timestamp = 0;
+        self.skip_sample_frames = frame.into();
+        self.decoder.reset();
+        self.sample_buf.clear();
+        self.cur_sample = 0;
+        self.stream_ended = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An MPEG-1 Layer III header: 128 kbps, 44.1 kHz, no padding, joint stereo.
+    const HEADER: [u8; 4] = [0xFF, 0xFB, 0x90, 0x44];
+
+    fn frames(count: usize) -> Vec<u8> {
+        let mut data = Vec::new();
+        for _ in 0..count {
+            data.extend_from_slice(&HEADER);
+            data.resize(data.len() + 413, 0);
+        }
+        data
+    }
+
+    #[test]
+    fn parse_frame_header() {
+        assert_eq!(
+            FrameHeader::parse(HEADER),
+            Some(FrameHeader {
+                sample_rate: 44100,
+                num_channels: 2,
+                frame_len: 417,
+                num_samples: 1152,
+            })
+        );
+        // Layer II isn't supported.
+        assert_eq!(FrameHeader::parse([0xFF, 0xFD, 0x90, 0x44]), None);
+    }
+
+    #[test]
+    fn loaded_metadata_skips_id3_and_partial_frames() {
+        let mut data = b"ID3\x04\x00\x00\x00\x00\x00\x05hello".to_vec();
+        data.extend(frames(3));
+        data.truncate(data.len() - 1);
+
+        assert!(Mp3LoadedFrames::default().update(&data[..200]).is_err());
+
+        let mut frames = Mp3LoadedFrames::default();
+        let metadata = frames.update(&data[..600]).unwrap();
+        assert_eq!(metadata.sample_rate, 44100);
+        assert_eq!(metadata.num_sample_frames, 1152);
+
+        // Only the newly loaded data is scanned.
+        let metadata = frames.update(&data).unwrap();
+        assert_eq!(metadata.sample_rate, 44100);
+        assert_eq!(metadata.num_sample_frames, 2 * 1152);
+    }
+
+    #[test]
+    fn stream_decoder_buffers_after_running_out_of_data() {
+        let mut data = Buffer::from(frames(1));
+        let status = Arc::new(Mp3StreamStatus::default());
+        let mut decoder = Mp3StreamDecoder::new(data.clone(), status.clone(), 2 * 1152).unwrap();
+
+        assert_eq!(decoder.by_ref().take(1152).count(), 1152);
+        assert!(!status.buffering.load(Ordering::Acquire));
+
+        // Silence is played once the loaded frame runs out.
+        assert_eq!(decoder.next(), Some([0, 0]));
+        assert!(status.buffering.load(Ordering::Acquire));
+
+        // Playback resumes once enough data is buffered.
+        data.extend_from_slice(&frames(1));
+        assert_eq!(decoder.next(), Some([0, 0]));
+        assert!(status.buffering.load(Ordering::Acquire));
+        data.extend_from_slice(&frames(1));
+        decoder.next();
+        assert!(!status.buffering.load(Ordering::Acquire));
+
+        // The stream ends once loading completes and all frames are played.
+        status.complete.store(true, Ordering::Release);
+        assert_eq!(decoder.count(), 2 * 1152 - 1);
+    }
+
+    #[test]
+    fn stream_decoder_seeks() {
+        let data = Buffer::from(frames(3));
+        let status = Arc::new(Mp3StreamStatus::default());
+        status.complete.store(true, Ordering::Release);
+        let mut decoder = Mp3StreamDecoder::new(data, status, 0).unwrap();
+
+        decoder.seek_to_sample_frame(2 * 1152 + 100);
+        assert_eq!(decoder.count(), 1152 - 100);
+    }
+}
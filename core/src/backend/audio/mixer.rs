@@ -1,7 +1,9 @@
 use super::decoders::{self, AdpcmDecoder, Decoder, PcmDecoder, SeekableDecoder};
-use super::{SoundHandle, SoundInstanceHandle, SoundStreamInfo, SoundTransform};
+use super::{
+    SoundHandle, SoundInstanceHandle, SoundStreamInfo, SoundTransform, EXTRACT_SAMPLE_RATE,
+};
 use crate::backend::audio::{DecodeError, RegisterError};
-use crate::buffer::Substream;
+use crate::buffer::{Buffer, Substream};
 use crate::tag_utils::SwfSlice;
 use slotmap::SlotMap;
 use std::io::Cursor;
@@ -70,6 +72,25 @@ pub struct AudioMixer {
 
     /// The last two windows of output samples.
     output_memory: Arc<RwLock<CircBuf>>,
+
+    /// The decoder used by the last call to `extract_sound`.
+    ///
+    /// Callers usually extract consecutive parts of a sound, so the decoder is kept around
+    /// to avoid decoding the sound from the start every time.
+    extractor: Option<SoundExtractor>,
+}
+
+/// A sound being decoded by `AudioMixer::extract_sound`.
+struct SoundExtractor {
+    sound: SoundHandle,
+
+    /// The position of `stream` in sample frames at `EXTRACT_SAMPLE_RATE`.
+    position: u64,
+
+    /// The number of bytes of the sound that had loaded when `stream` was created.
+    loaded_len: usize,
+
+    stream: Box<dyn Stream>,
 }
 
 /// An audio stream.
@@ -159,6 +180,29 @@ struct Sound {
     /// `skip_sample_frames` indicates how many sample frames to skip to bypass the delay.
     /// This is `0` unless `format.compression` is `AudioCompression::Mp3`.
     skip_sample_frames: u16,
+
+    /// The data of an external MP3 that is still loading.
+    ///
+    /// While this is set, `data` is empty and `num_sample_frames` is `0`.
+    loading: Option<LoadingMp3>,
+}
+
+/// An external MP3 that is still loading, registered with `AudioMixer::register_mp3_stream`.
+struct LoadingMp3 {
+    /// The data loaded so far.
+    data: Buffer,
+
+    /// Whether loading has completed, and whether a playing instance is waiting for data.
+    #[cfg(feature = "mp3")]
+    status: Arc<decoders::Mp3StreamStatus>,
+
+    /// The number of sample frames that must be loaded ahead to resume after running out of data.
+    #[cfg(feature = "mp3")]
+    buffer_sample_frames: u32,
+
+    /// The frames found in `data` so far, which determine the duration of the sound.
+    #[cfg(feature = "mp3")]
+    frames: Mutex<decoders::Mp3LoadedFrames>,
 }
 
 /// An actively playing instance of a sound.
@@ -245,6 +289,7 @@ impl AudioMixer {
             num_output_channels,
             output_sample_rate,
             output_memory: Arc::new(RwLock::new(CircBuf::new())),
+            extractor: None,
         }
     }
 
@@ -326,26 +371,58 @@ impl AudioMixer {
                 data,
                 format.sample_rate.into(),
             )),
+            #[cfg(feature = "speex")]
+            AudioCompression::Speex => Box::new(decoders::SpeexDecoder::new(data)),
             _ => return Err(decoders::Error::UnhandledCompression(format.compression)),
         };
         Ok(decoder)
     }
 
     /// Transforms a `Stream` into a new `Stream` that matches the output sample rate.
-    fn make_resampler(&self, mut stream: impl Stream) -> impl Stream {
+    fn make_resampler(&self, stream: impl Stream) -> impl Stream {
+        Self::resample(stream, self.output_sample_rate)
+    }
+
+    /// Transforms a `Stream` into a new `Stream` with the given sample rate.
+    fn resample(mut stream: impl Stream, sample_rate: u32) -> impl Stream {
         // TODO: Allow interpolator to be user-configurable?
         let left = stream.next();
         let right = stream.next();
         let interpolator = dasp::interpolate::linear::Linear::new(left, right);
-        let sample_rate = stream.source_sample_rate().into();
+        let source_sample_rate = stream.source_sample_rate().into();
         ConverterStream(dasp::signal::interpolate::Converter::from_hz_to_hz(
             stream,
             interpolator,
-            sample_rate,
-            self.output_sample_rate.into(),
+            source_sample_rate,
+            sample_rate.into(),
         ))
     }
 
+    /// Creates a `Stream` that decodes a whole sound at `EXTRACT_SAMPLE_RATE`.
+    fn make_extract_stream(sound: &Sound) -> Result<Box<dyn Stream>, DecodeError> {
+        // Sounds that are still loading are decoded up to the data loaded so far.
+        let (data, num_sample_frames): (Arc<[u8]>, _) = match &sound.loading {
+            Some(loading) => (Arc::from(&*loading.data.to_full_slice().data()), None),
+            None => (Arc::clone(&sound.data), Some(sound.num_sample_frames)),
+        };
+        let data = Cursor::new(ArcAsRef(data));
+        let decoder = Self::make_seekable_decoder(&sound.format, data)?;
+        let settings = swf::SoundInfo {
+            event: swf::SoundEvent::Start,
+            in_sample: None,
+            out_sample: None,
+            num_loops: 1,
+            envelope: None,
+        };
+        let stream = EventSoundStream::new_with_settings(
+            decoder,
+            &settings,
+            num_sample_frames,
+            sound.skip_sample_frames,
+        );
+        Ok(Box::new(Self::resample(stream, EXTRACT_SAMPLE_RATE)))
+    }
+
     /// Creates a `Stream` for an "event" that decodes and resamples the audio stream to the
     /// output format.
     ///
@@ -363,7 +440,7 @@ impl AudioMixer {
         let stream = EventSoundStream::new_with_settings(
             decoder,
             settings,
-            sound.num_sample_frames,
+            Some(sound.num_sample_frames),
             sound.skip_sample_frames,
         );
         // Resample the stream to the output sample rate.
@@ -395,6 +472,40 @@ impl AudioMixer {
         Ok(Box::new(stream))
     }
 
+    /// Creates a `Stream` that decodes and resamples an MP3 that is still loading.
+    ///
+    /// Start points and loops are applied as with `AudioMixer::make_stream_from_event_sound`,
+    /// except that the end of the sound isn't known until it has loaded.
+    #[cfg(feature = "mp3")]
+    fn make_stream_from_loading_mp3(
+        &self,
+        loading: &LoadingMp3,
+        settings: &swf::SoundInfo,
+    ) -> Result<Box<dyn Stream>, DecodeError> {
+        let decoder = decoders::Mp3StreamDecoder::new(
+            loading.data.clone(),
+            Arc::clone(&loading.status),
+            loading.buffer_sample_frames,
+        )?;
+        let stream = EventSoundStream::new_with_settings(Box::new(decoder), settings, None, 0);
+        let stream = self.make_resampler(stream);
+        if let Some(envelope) = &settings.envelope {
+            let envelope_signal = EnvelopeSignal::new(&envelope[..], self.output_sample_rate);
+            Ok(Box::new(MulAmpStream::new(stream, envelope_signal)))
+        } else {
+            Ok(Box::new(stream))
+        }
+    }
+
+    #[cfg(not(feature = "mp3"))]
+    fn make_stream_from_loading_mp3(
+        &self,
+        _loading: &LoadingMp3,
+        _settings: &swf::SoundInfo,
+    ) -> Result<Box<dyn Stream>, DecodeError> {
+        Err(decoders::Error::UnhandledCompression(AudioCompression::Mp3))
+    }
+
     /// Creates a `Stream` that decodes and resamples a timeline "stream" sound.
     fn make_stream_from_swf_slice<'a>(
         &self,
@@ -522,6 +633,7 @@ impl AudioMixer {
             data: Arc::from(data),
             num_sample_frames: swf_sound.num_samples,
             skip_sample_frames,
+            loading: None,
         };
         Ok(self.sounds.insert(sound))
     }
@@ -542,6 +654,7 @@ impl AudioMixer {
             data,
             num_sample_frames: metadata.num_sample_frames,
             skip_sample_frames: 0,
+            loading: None,
         };
         Ok(self.sounds.insert(sound))
     }
@@ -551,6 +664,97 @@ impl AudioMixer {
         Err(decoders::Error::UnhandledCompression(AudioCompression::Mp3))
     }
 
+    /// Registers an external MP3 that is still loading with the audio mixer.
+    ///
+    /// `data` must contain at least one complete MP3 frame. Instances that run out of data
+    /// wait for `buffer_time` more milliseconds of audio to load before resuming.
+    #[cfg(feature = "mp3")]
+    pub fn register_mp3_stream(
+        &mut self,
+        data: Buffer,
+        buffer_time: f64,
+    ) -> Result<SoundHandle, DecodeError> {
+        let mut frames = decoders::Mp3LoadedFrames::default();
+        let metadata = frames.update(&data.to_full_slice().data())?;
+        let buffer_sample_frames = (buffer_time * f64::from(metadata.sample_rate) / 1000.0) as u32;
+        let sound = Sound {
+            format: swf::SoundFormat {
+                compression: AudioCompression::Mp3,
+                sample_rate: metadata.sample_rate,
+                is_stereo: true,
+                is_16_bit: true,
+            },
+            data: Arc::new([]),
+            num_sample_frames: 0,
+            skip_sample_frames: 0,
+            loading: Some(LoadingMp3 {
+                data,
+                status: Default::default(),
+                buffer_sample_frames,
+                frames: Mutex::new(frames),
+            }),
+        };
+        Ok(self.sounds.insert(sound))
+    }
+
+    #[cfg(not(feature = "mp3"))]
+    pub fn register_mp3_stream(
+        &mut self,
+        _data: Buffer,
+        _buffer_time: f64,
+    ) -> Result<SoundHandle, DecodeError> {
+        Err(decoders::Error::UnhandledCompression(AudioCompression::Mp3))
+    }
+
+    /// Marks an MP3 registered with `register_mp3_stream` as completely loaded.
+    ///
+    /// Afterwards, the sound behaves like one registered with `register_mp3`.
+    #[cfg(feature = "mp3")]
+    pub fn finish_mp3_stream(&mut self, handle: SoundHandle) -> Result<(), DecodeError> {
+        let Some(sound) = self.sounds.get_mut(handle) else {
+            return Ok(());
+        };
+        let Some(loading) = sound.loading.take() else {
+            return Ok(());
+        };
+
+        loading
+            .status
+            .complete
+            .store(true, std::sync::atomic::Ordering::Release);
+        let data: Arc<[u8]> = Arc::from(&*loading.data.to_full_slice().data());
+        let metadata = decoders::mp3_metadata(&data)?;
+        sound.format.sample_rate = metadata.sample_rate;
+        sound.data = data;
+        sound.num_sample_frames = metadata.num_sample_frames;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "mp3"))]
+    pub fn finish_mp3_stream(&mut self, _handle: SoundHandle) -> Result<(), DecodeError> {
+        Ok(())
+    }
+
+    /// Returns whether an instance of an MP3 registered with `register_mp3_stream`
+    /// has run out of loaded data, and is waiting for more.
+    #[cfg(feature = "mp3")]
+    pub fn is_sound_buffering(&self, handle: SoundHandle) -> bool {
+        self.sounds
+            .get(handle)
+            .and_then(|sound| sound.loading.as_ref())
+            .is_some_and(|loading| {
+                loading
+                    .status
+                    .buffering
+                    .load(std::sync::atomic::Ordering::Acquire)
+            })
+    }
+
+    #[cfg(not(feature = "mp3"))]
+    pub fn is_sound_buffering(&self, _handle: SoundHandle) -> bool {
+        false
+    }
+
     /// Starts a timeline audio stream.
     pub fn start_stream(
         &mut self,
@@ -581,7 +785,9 @@ impl AudioMixer {
         let sound = &self.sounds[sound_handle];
         let data = Cursor::new(ArcAsRef(Arc::clone(&sound.data)));
         // Create a stream that decodes and resamples the sound.
-        let stream = if sound.skip_sample_frames == 0
+        let stream = if let Some(loading) = &sound.loading {
+            self.make_stream_from_loading_mp3(loading, settings)?
+        } else if sound.skip_sample_frames == 0
             && settings.in_sample.is_none()
             && settings.out_sample.is_none()
             && settings.num_loops <= 1
@@ -668,6 +874,19 @@ impl AudioMixer {
     /// Returns `None` if the sound is not registered or invalid.
     pub fn get_sound_duration(&self, sound: SoundHandle) -> Option<f64> {
         if let Some(sound) = self.sounds.get(sound) {
+            #[cfg(feature = "mp3")]
+            if let Some(loading) = &sound.loading {
+                // The duration grows with every frame that's loaded.
+                let metadata = loading
+                    .frames
+                    .lock()
+                    .expect("Cannot be called reentrant")
+                    .update(&loading.data.to_full_slice().data())
+                    .ok()?;
+                let num_sample_frames: f64 = metadata.num_sample_frames.into();
+                return Some(num_sample_frames * 1000.0 / f64::from(metadata.sample_rate));
+            }
+
             // AS duration does not subtract `skip_sample_frames`.
             let num_sample_frames: f64 = sound.num_sample_frames.into();
             let sample_rate: f64 = sound.format.sample_rate.into();
@@ -679,7 +898,66 @@ impl AudioMixer {
     }
 
     pub fn get_sound_size(&self, sound: SoundHandle) -> Option<u32> {
-        self.sounds.get(sound).map(|s| s.data.len() as u32)
+        self.sounds.get(sound).map(|s| match &s.loading {
+            Some(loading) => loading.data.len() as u32,
+            None => s.data.len() as u32,
+        })
+    }
+
+    /// Decodes up to `num_frames` sample frames of a registered sound, starting at `start`.
+    ///
+    /// The samples are appended to `samples` at `EXTRACT_SAMPLE_RATE`. Sounds that are still
+    /// loading are decoded up to the data loaded so far.
+    pub fn extract_sound(
+        &mut self,
+        handle: SoundHandle,
+        start: u64,
+        num_frames: usize,
+        samples: &mut Vec<[f32; 2]>,
+    ) -> Result<(), DecodeError> {
+        let Some(sound) = self.sounds.get(handle) else {
+            return Ok(());
+        };
+        let loaded_len = match &sound.loading {
+            Some(loading) => loading.data.len(),
+            None => sound.data.len(),
+        };
+
+        // Decoders can't seek backwards, so start over when extracting an earlier part.
+        // The stream of a loading sound ends with the data it was created with, so also
+        // start over once more has loaded.
+        let extractor = match self.extractor.take() {
+            Some(extractor)
+                if extractor.sound == handle
+                    && extractor.position <= start
+                    && extractor.loaded_len == loaded_len =>
+            {
+                extractor
+            }
+            _ => SoundExtractor {
+                sound: handle,
+                position: 0,
+                loaded_len,
+                stream: Self::make_extract_stream(sound)?,
+            },
+        };
+        let extractor = self.extractor.insert(extractor);
+
+        while extractor.position < start && !extractor.stream.is_exhausted() {
+            extractor.stream.next();
+            extractor.position += 1;
+        }
+
+        for _ in 0..num_frames {
+            if extractor.stream.is_exhausted() {
+                break;
+            }
+            let [left, right] = extractor.stream.next();
+            samples.push([f32::from(left) / 32768.0, f32::from(right) / 32768.0]);
+            extractor.position += 1;
+        }
+
+        Ok(())
     }
 
     pub fn get_sound_format(&self, sound: SoundHandle) -> Option<&swf::SoundFormat> {
@@ -792,7 +1070,7 @@ impl EventSoundStream {
     fn new_with_settings(
         decoder: Box<dyn SeekableDecoder>,
         settings: &swf::SoundInfo,
-        num_sample_frames: u32,
+        num_sample_frames: Option<u32>,
         skip_sample_frames: u16,
     ) -> Self {
         let skip_sample_frames: u32 = skip_sample_frames.into();
@@ -803,14 +1081,14 @@ impl EventSoundStream {
         let end_sample_frame = settings
             .out_sample
             .map(|n| (f64::from(n) / sample_divisor) as u32)
-            .unwrap_or(num_sample_frames)
-            + skip_sample_frames;
+            .or(num_sample_frames)
+            .map(|end| end + skip_sample_frames);
 
         let mut stream = Self {
             decoder,
             num_loops: settings.num_loops,
             start_sample_frame,
-            end_sample_frame: Some(end_sample_frame),
+            end_sample_frame,
             cur_sample_frame: start_sample_frame,
             skip_sample_frames,
             is_exhausted: false,
@@ -1078,6 +1356,25 @@ macro_rules! impl_audio_mixer_backend {
             self.$mixer.register_mp3(data)
         }
 
+        #[inline]
+        fn register_mp3_stream(
+            &mut self,
+            data: $crate::buffer::Buffer,
+            buffer_time: f64,
+        ) -> Result<SoundHandle, DecodeError> {
+            self.$mixer.register_mp3_stream(data, buffer_time)
+        }
+
+        #[inline]
+        fn finish_mp3_stream(&mut self, sound: SoundHandle) -> Result<(), DecodeError> {
+            self.$mixer.finish_mp3_stream(sound)
+        }
+
+        #[inline]
+        fn is_sound_buffering(&self, sound: SoundHandle) -> bool {
+            self.$mixer.is_sound_buffering(sound)
+        }
+
         #[inline]
         fn start_stream(
             &mut self,
@@ -1135,6 +1432,17 @@ macro_rules! impl_audio_mixer_backend {
             self.$mixer.get_sound_format(sound)
         }
 
+        #[inline]
+        fn extract_sound(
+            &mut self,
+            sound: SoundHandle,
+            start: u64,
+            num_frames: usize,
+            samples: &mut Vec<[f32; 2]>,
+        ) -> Result<(), DecodeError> {
+            self.$mixer.extract_sound(sound, start, num_frames, samples)
+        }
+
         #[inline]
         fn set_sound_transform(
             &mut self,
@@ -1164,3 +1472,47 @@ macro_rules! impl_audio_mixer_backend {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_consecutive_parts() {
+        let mut mixer = AudioMixer::new(2, 44100);
+        let data: Vec<u8> = (0..100i16)
+            .flat_map(|i| [i * 100, -i * 100])
+            .flat_map(i16::to_le_bytes)
+            .collect();
+        let handle = mixer
+            .register_sound(&swf::Sound {
+                id: 0,
+                format: swf::SoundFormat {
+                    compression: AudioCompression::Uncompressed,
+                    sample_rate: 44100,
+                    is_stereo: true,
+                    is_16_bit: true,
+                },
+                num_samples: 100,
+                data: &data,
+            })
+            .unwrap();
+
+        let expected = |i: i16| [f32::from(i * 100) / 32768.0, f32::from(-i * 100) / 32768.0];
+
+        let mut samples = Vec::new();
+        mixer.extract_sound(handle, 0, 10, &mut samples).unwrap();
+        mixer.extract_sound(handle, 10, 10, &mut samples).unwrap();
+        assert_eq!(samples, (0..20).map(expected).collect::<Vec<_>>());
+
+        // Extracting an earlier part decodes the sound again.
+        samples.clear();
+        mixer.extract_sound(handle, 5, 1, &mut samples).unwrap();
+        assert_eq!(samples, [expected(5)]);
+
+        // Extraction stops at the end of the sound.
+        samples.clear();
+        mixer.extract_sound(handle, 90, 50, &mut samples).unwrap();
+        assert_eq!(samples.len(), 10);
+    }
+}
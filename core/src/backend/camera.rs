@@ -0,0 +1,205 @@
+//! Video capture backends, used by `flash.media.Camera`.
+
+use downcast_rs::{impl_downcast, Downcast};
+use ruffle_render::bitmap::{Bitmap, BitmapFormat};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CameraError {
+    #[error("No camera with index {0}")]
+    NoSuchDevice(usize),
+
+    #[error("Couldn't start capturing video: {0}")]
+    CaptureFailed(String),
+}
+
+/// The resolution and frame rate a camera captures at.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraMode {
+    pub width: u32,
+    pub height: u32,
+
+    /// The maximum number of frames per second.
+    pub fps: f64,
+}
+
+impl CameraMode {
+    /// The largest width Flash Player captures at.
+    pub const MAX_WIDTH: u32 = 1920;
+
+    /// The largest height Flash Player captures at.
+    pub const MAX_HEIGHT: u32 = 1080;
+
+    /// Whether this mode is within the maximum capture size.
+    pub fn fits_max_size(&self) -> bool {
+        self.width <= Self::MAX_WIDTH && self.height <= Self::MAX_HEIGHT
+    }
+}
+
+/// A source of captured video frames, such as a webcam.
+///
+/// Devices are identified by their index in `device_names`.
+pub trait CameraBackend: Downcast {
+    /// Returns the names of the available cameras.
+    fn device_names(&self) -> Vec<String>;
+
+    /// Starts capturing from a device in the mode closest to the requested one.
+    ///
+    /// If the device is already capturing, it is restarted in the new mode.
+    /// Returns the mode the device actually captures in.
+    fn start_capture(&mut self, device: usize, mode: CameraMode)
+        -> Result<CameraMode, CameraError>;
+
+    /// Stops capturing from a device.
+    fn stop_capture(&mut self, device: usize);
+
+    /// Returns the most recent frame captured by a device since the last call, if any.
+    ///
+    /// Frames are returned as opaque RGBA bitmaps in the size of the capture mode.
+    /// `elapsed` is the time passed since the last call in milliseconds.
+    /// Backends that capture in real time may ignore it.
+    fn take_frame(&mut self, device: usize, elapsed: f64) -> Option<Bitmap>;
+}
+
+impl_downcast!(CameraBackend);
+
+/// Camera backend without any devices.
+#[derive(Default)]
+pub struct NullCameraBackend;
+
+impl NullCameraBackend {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl CameraBackend for NullCameraBackend {
+    fn device_names(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn start_capture(
+        &mut self,
+        device: usize,
+        _mode: CameraMode,
+    ) -> Result<CameraMode, CameraError> {
+        Err(CameraError::NoSuchDevice(device))
+    }
+
+    fn stop_capture(&mut self, _device: usize) {}
+
+    fn take_frame(&mut self, _device: usize, _elapsed: f64) -> Option<Bitmap> {
+        None
+    }
+}
+
+/// Camera backend with a single device that shows a synthetic test pattern.
+///
+/// The pattern consists of eight vertical color bars with a white block moving
+/// across them, advancing one step per frame. Frames are generated along with
+/// the time given to `take_frame`, which makes it suitable for deterministic tests.
+#[derive(Default)]
+pub struct TestPatternCameraBackend {
+    /// The mode of the current capture, if capturing.
+    mode: Option<CameraMode>,
+
+    /// The time passed since the last frame, in milliseconds.
+    time_since_frame: f64,
+
+    /// The number of frames generated so far.
+    frame: u32,
+}
+
+impl TestPatternCameraBackend {
+    pub const DEVICE_NAME: &'static str = "Test Pattern";
+
+    /// The colors of the bars, from left to right.
+    const BARS: [[u8; 3]; 8] = [
+        [255, 255, 255],
+        [255, 255, 0],
+        [0, 255, 255],
+        [0, 255, 0],
+        [255, 0, 255],
+        [255, 0, 0],
+        [0, 0, 255],
+        [0, 0, 0],
+    ];
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn generate_frame(&self, width: u32, height: u32) -> Bitmap {
+        // The moving block is a quarter of the frame in each dimension.
+        let block_width = (width / 4).max(1);
+        let block_height = (height / 4).max(1);
+        let block_x = (self.frame % 4) * block_width;
+        let block_y = (height - block_height) / 2;
+
+        let mut data = Vec::with_capacity(width as usize * height as usize * 4);
+        for y in 0..height {
+            for x in 0..width {
+                let in_block = (block_x..block_x + block_width).contains(&x)
+                    && (block_y..block_y + block_height).contains(&y);
+                let [r, g, b] = if in_block {
+                    [255, 255, 255]
+                } else {
+                    Self::BARS[(x * 8 / width) as usize]
+                };
+                data.extend_from_slice(&[r, g, b, 255]);
+            }
+        }
+
+        Bitmap::new(width, height, BitmapFormat::Rgba, data)
+    }
+}
+
+impl CameraBackend for TestPatternCameraBackend {
+    fn device_names(&self) -> Vec<String> {
+        vec![Self::DEVICE_NAME.to_string()]
+    }
+
+    fn start_capture(
+        &mut self,
+        device: usize,
+        mode: CameraMode,
+    ) -> Result<CameraMode, CameraError> {
+        if device != 0 {
+            return Err(CameraError::NoSuchDevice(device));
+        }
+
+        let mode = CameraMode {
+            width: mode.width.clamp(1, CameraMode::MAX_WIDTH),
+            height: mode.height.clamp(1, CameraMode::MAX_HEIGHT),
+            fps: mode.fps.max(1.0),
+        };
+        self.mode = Some(mode);
+        // The first frame is available right away.
+        self.time_since_frame = 1000.0 / mode.fps;
+        self.frame = 0;
+        Ok(mode)
+    }
+
+    fn stop_capture(&mut self, device: usize) {
+        if device == 0 {
+            self.mode = None;
+        }
+    }
+
+    fn take_frame(&mut self, device: usize, elapsed: f64) -> Option<Bitmap> {
+        let mode = self.mode.filter(|_| device == 0)?;
+
+        let frame_duration = 1000.0 / mode.fps;
+        self.time_since_frame += elapsed;
+        if self.time_since_frame < frame_duration {
+            return None;
+        }
+
+        // Frames that were missed are dropped, only the latest one is returned.
+        let frames = (self.time_since_frame / frame_duration).floor();
+        self.time_since_frame -= frames * frame_duration;
+        self.frame = self.frame.wrapping_add(frames as u32);
+
+        Some(self.generate_frame(mode.width, mode.height))
+    }
+}
@@ -1,5 +1,6 @@
 //! Browser-related platform functions
 
+use crate::datagram_socket::{Datagram, DatagramSocketAction, DatagramSocketHandle};
 use crate::loader::Error;
 use crate::socket::{ConnectionState, SocketAction, SocketHandle};
 use crate::string::WStr;
@@ -13,6 +14,7 @@ use std::fmt::Display;
 use std::fs::File;
 use std::future::Future;
 use std::io::Read;
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::time::Duration;
@@ -301,6 +303,24 @@ pub trait NavigatorBackend: Downcast {
         receiver: Receiver<Vec<u8>>,
         sender: Sender<SocketAction>,
     );
+
+    /// Handle a request to bind a UDP socket to a local address
+    ///
+    /// Returns the address the socket was bound to, or an error if binding failed.
+    ///
+    /// Datagrams to send are received through `receiver`. Use [DatagramSocketAction::Data]
+    /// to send received datagrams to AVM side, and [DatagramSocketAction::Close] to close
+    /// the socket on AVM side.
+    ///
+    /// When the Sender of the Receiver is dropped then this task should end.
+    fn bind_datagram_socket(
+        &mut self,
+        host: String,
+        port: u16,
+        handle: DatagramSocketHandle,
+        receiver: Receiver<Datagram>,
+        sender: Sender<DatagramSocketAction>,
+    ) -> std::io::Result<SocketAddr>;
 }
 impl_downcast!(NavigatorBackend);
 
@@ -445,6 +465,17 @@ impl NavigatorBackend for NullNavigatorBackend {
             .try_send(SocketAction::Connect(handle, ConnectionState::Failed))
             .expect("working channel send");
     }
+
+    fn bind_datagram_socket(
+        &mut self,
+        _host: String,
+        _port: u16,
+        _handle: DatagramSocketHandle,
+        _receiver: Receiver<Datagram>,
+        _sender: Sender<DatagramSocketAction>,
+    ) -> std::io::Result<SocketAddr> {
+        Err(std::io::ErrorKind::Unsupported.into())
+    }
 }
 
 // The following functions are helper functions used in different
@@ -1,5 +1,8 @@
 use crate::avm2::{Object as Avm2Object, Value as Avm2Value};
-use crate::display_object::{DisplayObject, DisplayObjectWeak, TDisplayObject};
+use crate::display_object::{
+    DisplayObject, DisplayObjectWeak, TDisplayObject, TDisplayObjectContainer,
+};
+use crate::policy_file::{Taint, UrlPolicyFiles};
 use bitflags::bitflags;
 use gc_arena::{Collect, Mutation};
 use ruffle_render::backend::RenderBackend;
@@ -208,6 +211,11 @@ pub struct BitmapData<'gc> {
     // so we need a separate 'disposed' flag.
     disposed: bool,
 
+    /// Set when the pixels were loaded from another domain without a policy file allowing
+    /// access to them. Such bitmaps can be displayed, but their pixels can't be read.
+    #[collect(require_static)]
+    taint: Option<Taint>,
+
     /// The bitmap handle for this data.
     ///
     /// This is lazily initialized; a value of `None` indicates that
@@ -250,6 +258,7 @@ mod wrapper {
     use crate::avm2::{Object as Avm2Object, Value as Avm2Value};
     use crate::context::RenderContext;
     use crate::display_object::DisplayObjectWeak;
+    use crate::policy_file::Taint;
     use gc_arena::{Collect, GcCell, Mutation};
     use ruffle_render::backend::RenderBackend;
     use ruffle_render::bitmap::{BitmapHandle, PixelRegion, PixelSnapping};
@@ -308,6 +317,7 @@ mod wrapper {
                     height: 0,
                     transparency: false,
                     disposed: true,
+                    taint: None,
                     bitmap_handle: None,
                     avm2_object: None,
                     display_objects: vec![],
@@ -331,6 +341,7 @@ mod wrapper {
                 height: data.height,
                 transparency: data.transparency,
                 disposed: data.disposed,
+                taint: data.taint.clone(),
                 bitmap_handle: None,
                 avm2_object: None,
                 display_objects: vec![],
@@ -449,6 +460,10 @@ mod wrapper {
             self.0.read().transparency
         }
 
+        pub fn taint(&self) -> Option<Taint> {
+            self.0.read().taint.clone()
+        }
+
         pub fn check_valid(
             &self,
             activation: &mut crate::avm2::Activation<'_, 'gc>,
@@ -555,6 +570,7 @@ impl std::fmt::Debug for BitmapData<'_> {
             .field("height", &self.height)
             .field("transparency", &self.transparency)
             .field("disposed", &self.disposed)
+            .field("taint", &self.taint)
             .field("bitmap_handle", &self.bitmap_handle)
             .finish()
     }
@@ -571,6 +587,7 @@ impl<'gc> BitmapData<'gc> {
             height,
             transparency,
             disposed: false,
+            taint: None,
             bitmap_handle: None,
             avm2_object: None,
             display_objects: vec![],
@@ -594,6 +611,7 @@ impl<'gc> BitmapData<'gc> {
             bitmap_handle: None,
             avm2_object: None,
             disposed: false,
+            taint: None,
             dirty_state: DirtyState::Clean,
             display_objects: vec![],
             #[cfg(feature = "egui")]
@@ -605,6 +623,10 @@ impl<'gc> BitmapData<'gc> {
         self.disposed
     }
 
+    pub fn set_taint(&mut self, taint: Option<Taint>) {
+        self.taint = taint;
+    }
+
     pub fn dispose(&mut self) {
         self.width = 0;
         self.height = 0;
@@ -798,10 +820,36 @@ impl IBitmapDrawable<'_> {
             IBitmapDrawable::DisplayObject(o) => o.bounds(),
         }
     }
+
+    /// Returns the taint of the first tainted bitmap this contains, if any.
+    ///
+    /// Nothing is ever tainted when URL policy files aren't enforced.
+    pub fn taint(&self, policy_files: &UrlPolicyFiles) -> Option<Taint> {
+        fn display_object_taint(object: DisplayObject<'_>) -> Option<Taint> {
+            if let Some(bitmap) = object.as_bitmap() {
+                bitmap.bitmap_data_wrapper().taint()
+            } else if let Some(container) = object.as_container() {
+                container.iter_render_list().find_map(display_object_taint)
+            } else {
+                None
+            }
+        }
+
+        if !policy_files.is_enabled() {
+            return None;
+        }
+
+        match self {
+            IBitmapDrawable::BitmapData(bmd) => bmd.taint(),
+            IBitmapDrawable::DisplayObject(o) => display_object_taint(*o),
+        }
+    }
 }
 
+/// Copies RGBA pixels read back from a texture, with rows of `buffer_width` bytes,
+/// into the given area of a bitmap.
 #[instrument(level = "debug", skip_all)]
-fn copy_pixels_to_bitmapdata(
+pub fn copy_pixels_to_bitmapdata(
     write: &mut BitmapData,
     buffer: &[u8],
     buffer_width: u32,
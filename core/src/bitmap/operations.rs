@@ -13,8 +13,10 @@ use gc_arena::Mutation;
 use ruffle_render::backend::RenderBackend;
 use ruffle_render::bitmap::{PixelRegion, PixelSnapping};
 use ruffle_render::commands::{CommandHandler, CommandList, RenderBlendMode};
-use ruffle_render::filters::Filter;
+use ruffle_render::filters::{Filter, ShaderFilter};
 use ruffle_render::matrix::Matrix;
+use ruffle_render::pixel_bender::interpreter::{self, PixelBenderImage};
+use ruffle_render::pixel_bender::{ImageInputTexture, PixelBenderShaderArgument, ShaderMode};
 use ruffle_render::quality::StageQuality;
 use ruffle_render::transform::Transform;
 use std::cell::{Ref, RefMut};
@@ -1242,6 +1244,19 @@ pub fn apply_filter<'gc>(
             return;
         }
 
+        if let Filter::ShaderFilter(filter) = filter {
+            apply_shader_filter_on_cpu(
+                context.gc(),
+                context.renderer,
+                source,
+                target,
+                source_region,
+                dest_region,
+                filter,
+            );
+            return;
+        }
+
         // Until we support these filters, treat this like a copy
         copy_on_cpu(
             context.gc(),
@@ -1277,6 +1292,76 @@ pub fn apply_filter<'gc>(
     }
 }
 
+/// Runs a shader filter with the CPU interpreter, for renderers that can't run it themselves.
+///
+/// Like the wgpu backend, the source region is bound to the first image input of the shader.
+fn apply_shader_filter_on_cpu<'gc>(
+    context: &Mutation<'gc>,
+    renderer: &mut dyn RenderBackend,
+    source: BitmapDataWrapper<'gc>,
+    dest: BitmapDataWrapper<'gc>,
+    source_region: PixelRegion,
+    dest_region: PixelRegion,
+    mut filter: ShaderFilter<'static>,
+) {
+    let (width, height) = (source_region.width(), source_region.height());
+    let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+    {
+        let source_read = source.read_area(source_region, renderer);
+        for y in 0..height {
+            for x in 0..width {
+                let color =
+                    source_read.get_pixel32_raw(source_region.x_min + x, source_region.y_min + y);
+                rgba.extend([color.red(), color.green(), color.blue(), color.alpha()]);
+            }
+        }
+    }
+    let source_image = PixelBenderImage::from_rgba8(width, height, &rgba);
+
+    for arg in &mut filter.shader_args {
+        if let PixelBenderShaderArgument::ImageInput { texture, .. } = arg {
+            *texture = Some(ImageInputTexture::Bytes {
+                width,
+                height,
+                channels: 4,
+                bytes: source_image.to_f32_bytes(),
+            });
+            // Only bind the first input from the source texture
+            break;
+        }
+    }
+
+    let output = match interpreter::run_shader(
+        filter.shader.0.parsed_shader(),
+        ShaderMode::Filter,
+        &filter.shader_args,
+        width,
+        height,
+        |_| Ok(PixelBenderImage::new(width, height, 4)),
+    ) {
+        Ok(output) => output.to_rgba8(),
+        Err(e) => {
+            tracing::error!("BitmapData.applyFilter: Failed to run shader filter: {e}");
+            return;
+        }
+    };
+
+    let dest = dest.sync(renderer);
+    let mut write = dest.write(context);
+    let opaque = !write.transparency();
+    for y in 0..dest_region.height() {
+        for x in 0..dest_region.width() {
+            let i = (y * width + x) as usize * 4;
+            let mut color = Color::argb(output[i + 3], output[i], output[i + 1], output[i + 2]);
+            if opaque {
+                color = color.with_alpha(255);
+            }
+            write.set_pixel32_raw(dest_region.x_min + x, dest_region.y_min + y, color);
+        }
+    }
+    write.set_cpu_dirty(context, dest_region);
+}
+
 #[allow(clippy::too_many_arguments)]
 fn copy_on_cpu<'gc>(
     context: &Mutation<'gc>,
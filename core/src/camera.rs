@@ -0,0 +1,368 @@
+//! Video capture, as exposed through `flash.media.Camera`.
+
+use crate::avm2::globals::slots::flash_media_camera as camera_slots;
+use crate::avm2::{Activation, Avm2, EventObject, Object as Avm2Object, TObject};
+use crate::backend::camera::CameraMode;
+use crate::context::UpdateContext;
+use crate::string::AvmString;
+use gc_arena::{Collect, GcCell, Mutation};
+use ruffle_render::bitmap::{Bitmap, BitmapInfo, PixelRegion};
+use std::fmt;
+
+/// Returns the percentage of pixels that changed noticeably between two RGBA frames.
+fn frame_motion(previous: &Bitmap, current: &Bitmap) -> f64 {
+    if previous.width() != current.width() || previous.height() != current.height() {
+        return 100.0;
+    }
+
+    let pixels = previous.data().len() / 4;
+    if pixels == 0 {
+        return 0.0;
+    }
+
+    let luma = |p: &[u8]| (i32::from(p[0]) * 3 + i32::from(p[1]) * 6 + i32::from(p[2])) / 10;
+    let changed = previous
+        .data()
+        .chunks_exact(4)
+        .zip(current.data().chunks_exact(4))
+        .filter(|(a, b)| (luma(a) - luma(b)).abs() > 16)
+        .count();
+
+    changed as f64 * 100.0 / pixels as f64
+}
+
+/// A camera device, shared between its `flash.media.Camera` object and the videos it's attached to.
+#[derive(Clone, Collect, Copy)]
+#[collect(no_drop)]
+pub struct Camera<'gc>(GcCell<'gc, CameraData<'gc>>);
+
+impl fmt::Debug for Camera<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Camera")
+            .field("ptr", &self.0.as_ptr())
+            .finish()
+    }
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+struct CameraData<'gc> {
+    /// The `flash.media.Camera` instance.
+    object: Avm2Object<'gc>,
+
+    /// The index of the camera device.
+    #[collect(require_static)]
+    device: usize,
+
+    /// The mode the device is capturing in, if capturing.
+    #[collect(require_static)]
+    mode: Option<CameraMode>,
+
+    /// The last captured frame, uploaded to the renderer.
+    #[collect(require_static)]
+    frame: Option<BitmapInfo>,
+
+    /// The last captured frame, kept to detect motion in the next one.
+    #[collect(require_static)]
+    previous_bitmap: Option<Bitmap>,
+
+    /// Whether there is motion above the motion level, as last reported by an `activity` event.
+    #[collect(require_static)]
+    active: bool,
+
+    /// For how long there has been no motion above the motion level, in milliseconds.
+    #[collect(require_static)]
+    still_for: f64,
+
+    /// The number of videos this camera is attached to.
+    #[collect(require_static)]
+    videos: usize,
+}
+
+impl<'gc> Camera<'gc> {
+    pub fn object(self) -> Avm2Object<'gc> {
+        self.0.read().object
+    }
+
+    /// The last frame captured by this camera.
+    pub fn last_frame(self) -> Option<BitmapInfo> {
+        self.0.read().frame.clone()
+    }
+
+    fn requested_mode(self) -> CameraMode {
+        let object = self.object();
+        let width = object.get_slot(camera_slots::_WIDTH).as_i32();
+        let height = object.get_slot(camera_slots::_HEIGHT).as_i32();
+        CameraMode {
+            width: width.clamp(0, CameraMode::MAX_WIDTH as i32) as u32,
+            height: height.clamp(0, CameraMode::MAX_HEIGHT as i32) as u32,
+            fps: object.get_slot(camera_slots::_FPS).as_f64(),
+        }
+    }
+
+    fn set_mode(self, mc: &Mutation<'gc>, mode: CameraMode) {
+        self.0.write(mc).mode = Some(mode);
+
+        let object = self.object();
+        object.set_slot_no_coerce(camera_slots::_WIDTH, mode.width.into(), mc);
+        object.set_slot_no_coerce(camera_slots::_HEIGHT, mode.height.into(), mc);
+        object.set_slot_no_coerce(camera_slots::_FPS, mode.fps.into(), mc);
+    }
+
+    /// Restarts capturing in the mode set on the `Camera` object.
+    ///
+    /// Does nothing if this camera isn't capturing.
+    pub fn update_mode(self, context: &mut UpdateContext<'gc>) {
+        if self.0.read().mode.is_some() {
+            self.apply_mode(context);
+        }
+    }
+
+    fn apply_mode(self, context: &mut UpdateContext<'gc>) {
+        let device = self.0.read().device;
+        match context.camera.start_capture(device, self.requested_mode()) {
+            Ok(mode) if !mode.fits_max_size() => {
+                tracing::warn!(
+                    "Camera {device} captures in an unsupported size of {}x{}",
+                    mode.width,
+                    mode.height
+                );
+                context.camera.stop_capture(device);
+                self.0.write(context.gc()).mode = None;
+            }
+            Ok(mode) => {
+                self.set_mode(context.gc(), mode);
+                let object = self.object();
+                if object.get_slot(camera_slots::_ACTIVITY_LEVEL).as_f64() < 0.0 {
+                    object.set_slot_no_coerce(
+                        camera_slots::_ACTIVITY_LEVEL,
+                        0.into(),
+                        context.gc(),
+                    );
+                }
+            }
+            Err(e) => tracing::warn!("Couldn't start camera {device}: {e}"),
+        }
+    }
+
+    /// Processes the frame captured since the last frame of the movie, if any.
+    fn update(self, context: &mut UpdateContext<'gc>, elapsed: f64) {
+        let (device, mode) = {
+            let read = self.0.read();
+            match read.mode {
+                Some(mode) => (read.device, mode),
+                None => return,
+            }
+        };
+        let object = self.object();
+
+        let motion_level = object.get_slot(camera_slots::_MOTION_LEVEL).as_f64();
+        let motion_timeout = object.get_slot(camera_slots::_MOTION_TIMEOUT).as_f64();
+
+        let bitmap = context.camera.take_frame(device, elapsed);
+        let mut write = self.0.write(context.gc());
+
+        let activity_level = bitmap.as_ref().map(|bitmap| {
+            write
+                .previous_bitmap
+                .as_ref()
+                .map_or(0.0, |previous| frame_motion(previous, bitmap))
+        });
+
+        if let Some(bitmap) = bitmap {
+            let (width, height) = (bitmap.width(), bitmap.height());
+            let (Ok(frame_width), Ok(frame_height)) = (u16::try_from(width), u16::try_from(height))
+            else {
+                tracing::error!("Camera frame of {width}x{height} is too large");
+                return;
+            };
+            let reuse = write
+                .frame
+                .as_ref()
+                .filter(|f| f.width == frame_width && f.height == frame_height);
+
+            let result = match reuse {
+                Some(frame) => context
+                    .renderer
+                    .update_texture(
+                        &frame.handle,
+                        bitmap.clone(),
+                        PixelRegion::for_whole_size(width, height),
+                    )
+                    .map(|_| frame.clone()),
+                None => context
+                    .renderer
+                    .register_bitmap(bitmap.clone())
+                    .map(|handle| BitmapInfo {
+                        handle,
+                        width: frame_width,
+                        height: frame_height,
+                    }),
+            };
+
+            match result {
+                Ok(frame) => write.frame = Some(frame),
+                Err(e) => tracing::error!("Couldn't upload camera frame: {e}"),
+            }
+            write.previous_bitmap = Some(bitmap);
+        }
+
+        let activating = match activity_level {
+            Some(level) if level > motion_level => {
+                write.still_for = 0.0;
+                (!write.active).then_some(true)
+            }
+            _ => {
+                write.still_for += elapsed;
+                (write.active && write.still_for >= motion_timeout).then_some(false)
+            }
+        };
+        if let Some(activating) = activating {
+            write.active = activating;
+        }
+        drop(write);
+
+        if let Some(level) = activity_level {
+            object.set_slot_no_coerce(camera_slots::_ACTIVITY_LEVEL, level.into(), context.gc());
+            object.set_slot_no_coerce(camera_slots::_CURRENT_FPS, mode.fps.into(), context.gc());
+        }
+
+        if let Some(activating) = activating {
+            let mut activation = Activation::from_nothing(context);
+            let event_class = activation.avm2().classes().activityevent;
+            let event_type = AvmString::new_utf8(activation.gc(), "activity");
+            let event = EventObject::from_class_and_args(
+                &mut activation,
+                event_class,
+                &[
+                    event_type.into(),
+                    false.into(),
+                    false.into(),
+                    activating.into(),
+                ],
+            );
+            Avm2::dispatch_event(activation.context, event, object);
+        }
+    }
+}
+
+/// All cameras that are attached to a video, and so are capturing.
+#[derive(Collect, Default)]
+#[collect(no_drop)]
+pub struct Cameras<'gc> {
+    cameras: Vec<Camera<'gc>>,
+}
+
+impl<'gc> Cameras<'gc> {
+    /// Returns the camera of a `Camera` object that is about to be attached to a video,
+    /// starting to capture if it isn't already.
+    ///
+    /// Every call must be balanced by a call to `release` once the video is detached.
+    pub fn acquire(context: &mut UpdateContext<'gc>, object: Avm2Object<'gc>) -> Camera<'gc> {
+        let camera = match context.cameras.get(object) {
+            Some(camera) => camera,
+            None => {
+                let device = object
+                    .get_slot(camera_slots::_INDEX)
+                    .as_i32()
+                    .try_into()
+                    .unwrap_or_default();
+
+                let camera = Camera(GcCell::new(
+                    context.gc(),
+                    CameraData {
+                        object,
+                        device,
+                        mode: None,
+                        frame: None,
+                        previous_bitmap: None,
+                        active: false,
+                        still_for: 0.0,
+                        videos: 0,
+                    },
+                ));
+                context.cameras.cameras.push(camera);
+                camera.apply_mode(context);
+                camera
+            }
+        };
+
+        camera.0.write(context.gc()).videos += 1;
+        camera
+    }
+
+    /// Detaches a camera from a video, stopping the capture once no video uses it anymore.
+    pub fn release(context: &mut UpdateContext<'gc>, camera: Camera<'gc>) {
+        let mut write = camera.0.write(context.gc());
+        write.videos = write.videos.saturating_sub(1);
+        if write.videos > 0 {
+            return;
+        }
+
+        let device = write.device;
+        let was_capturing = write.mode.take().is_some();
+        drop(write);
+
+        context
+            .cameras
+            .cameras
+            .retain(|c| !GcCell::ptr_eq(c.0, camera.0));
+        if was_capturing {
+            context.camera.stop_capture(device);
+        }
+
+        let object = camera.object();
+        object.set_slot_no_coerce(camera_slots::_ACTIVITY_LEVEL, (-1).into(), context.gc());
+        object.set_slot_no_coerce(camera_slots::_CURRENT_FPS, 0.into(), context.gc());
+    }
+
+    /// Returns the camera of a `Camera` object, if it's attached to a video.
+    pub fn get(&self, object: Avm2Object<'gc>) -> Option<Camera<'gc>> {
+        self.cameras
+            .iter()
+            .find(|camera| Avm2Object::ptr_eq(camera.object(), object))
+            .copied()
+    }
+
+    /// Captures new frames for every camera that is capturing.
+    pub fn update(context: &mut UpdateContext<'gc>) {
+        let elapsed = 1000.0 / *context.frame_rate;
+        let cameras = context.cameras.cameras.clone();
+        for camera in cameras {
+            camera.update(context, elapsed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::camera::{CameraBackend, TestPatternCameraBackend};
+
+    #[test]
+    fn test_pattern_motion() {
+        let mut backend = TestPatternCameraBackend::new();
+        let mode = CameraMode {
+            width: 160,
+            height: 120,
+            fps: 10.0,
+        };
+        assert_eq!(backend.start_capture(0, mode).unwrap(), mode);
+
+        let first = backend
+            .take_frame(0, 0.0)
+            .expect("First frame is immediate");
+        assert_eq!((first.width(), first.height()), (160, 120));
+
+        // Frames only arrive at the capture frame rate.
+        assert!(backend.take_frame(0, 50.0).is_none());
+        let second = backend
+            .take_frame(0, 50.0)
+            .expect("Second frame after 100ms");
+
+        // The moving block covers 1/16th of the frame, and moved by its own width.
+        assert_eq!(frame_motion(&first, &first), 0.0);
+        let level = frame_motion(&first, &second);
+        assert!(level > 6.0 && level < 13.0, "unexpected motion {level}");
+    }
+}
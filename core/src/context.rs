@@ -14,25 +14,33 @@ use crate::avm2::Activation as Avm2Activation;
 use crate::avm2::TObject as _;
 use crate::avm2::{Avm2, Object as Avm2Object, SoundChannelObject};
 use crate::backend::{
-    audio::{AudioBackend, AudioManager, SoundHandle, SoundInstanceHandle},
+    audio::{AudioBackend, AudioInputBackend, AudioManager, SoundHandle, SoundInstanceHandle},
+    camera::CameraBackend,
     log::LogBackend,
     navigator::NavigatorBackend,
     storage::StorageBackend,
     ui::UiBackend,
 };
+use crate::camera::Cameras;
 use crate::context_menu::ContextMenuState;
+use crate::datagram_socket::DatagramSockets;
 use crate::display_object::{EditText, MovieClip, SoundTransform, Stage};
 use crate::external::ExternalInterface;
 use crate::focus_tracker::FocusTracker;
 use crate::frame_lifecycle::FramePhase;
+use crate::game_input::GameInputDevices;
 use crate::input::InputManager;
 use crate::library::Library;
 use crate::loader::LoadManager;
 use crate::local_connection::LocalConnections;
+use crate::locale::{get_current_date_time, get_mock_date_time, get_timezone};
+use crate::microphone::Microphones;
 use crate::net_connection::NetConnections;
 use crate::player::PostFrameCallback;
 use crate::player::{MouseData, Player};
+use crate::policy_file::UrlPolicyFiles;
 use crate::prelude::*;
+use crate::shader_job::ShaderJobs;
 use crate::socket::Sockets;
 use crate::streams::StreamManager;
 use crate::string::{AvmString, StringContext};
@@ -40,6 +48,8 @@ use crate::stub::StubCollection;
 use crate::tag_utils::{SwfMovie, SwfSlice};
 use crate::timer::Timers;
 use crate::vminterface::Instantiator;
+use crate::worker::Workers;
+use chrono::{DateTime, FixedOffset, Utc};
 use core::fmt;
 use gc_arena::{Collect, Mutation};
 use rand::rngs::SmallRng;
@@ -72,6 +82,9 @@ pub struct UpdateContext<'gc> {
     /// A collection of stubs encountered during this movie.
     pub stub_tracker: &'gc mut StubCollection,
 
+    /// The URL policy files (`crossdomain.xml`) fetched or registered so far.
+    pub url_policy_files: &'gc mut UrlPolicyFiles,
+
     /// The library containing character definitions for this SWF.
     /// Used to instantiate a `DisplayObject` of a given ID.
     pub library: &'gc mut Library<'gc>,
@@ -92,6 +105,12 @@ pub struct UpdateContext<'gc> {
     /// The audio backend, used by display objects and AVM to play audio.
     pub audio: &'gc mut dyn AudioBackend,
 
+    /// The audio input backend, used by microphones.
+    pub audio_input: &'gc mut dyn AudioInputBackend,
+
+    /// The camera backend, used by `flash.media.Camera`.
+    pub camera: &'gc mut dyn CameraBackend,
+
     /// The audio manager, managing all actively playing sounds.
     pub audio_manager: &'gc mut AudioManager<'gc>,
 
@@ -178,6 +197,10 @@ pub struct UpdateContext<'gc> {
     /// The instant at which the SWF was launched.
     pub start_time: Instant,
 
+    /// Whether the player uses a deterministic clock,
+    /// which only advances along with the timers, and a fixed random seed.
+    pub deterministic: bool,
+
     /// The instant at which the current update started.
     pub update_start: Instant,
 
@@ -213,11 +236,29 @@ pub struct UpdateContext<'gc> {
 
     pub sockets: &'gc mut Sockets<'gc>,
 
+    /// Bound `flash.net.DatagramSocket` instances.
+    pub datagram_sockets: &'gc mut DatagramSockets<'gc>,
+
     /// List of active NetConnection instances.
     pub net_connections: &'gc mut NetConnections<'gc>,
 
     pub local_connections: &'gc mut LocalConnections<'gc>,
 
+    /// Game controllers available through `flash.ui.GameInput`.
+    pub game_input: &'gc mut GameInputDevices<'gc>,
+
+    /// Microphones that are currently capturing.
+    pub microphones: &'gc mut Microphones<'gc>,
+
+    /// Cameras returned by `Camera.getCamera`.
+    pub cameras: &'gc mut Cameras<'gc>,
+
+    /// ActionScript workers and the channels between them.
+    pub workers: &'gc mut Workers<'gc>,
+
+    /// Shader jobs running asynchronously.
+    pub shader_jobs: &'gc mut ShaderJobs<'gc>,
+
     /// Dynamic root for allowing handles to GC objects to exist outside of the GC.
     pub dynamic_root: gc_arena::DynamicRootSet<'gc>,
 
@@ -433,6 +474,9 @@ impl<'gc> UpdateContext<'gc> {
 
         // Clean up the stage before loading another root movie.
         self.sockets.close_all();
+        self.datagram_sockets.close_all();
+        self.workers.reset();
+        *self.shader_jobs = ShaderJobs::default();
         self.timers.remove_all();
 
         self.set_root_movie(movie);
@@ -450,6 +494,36 @@ impl<'gc> UpdateContext<'gc> {
     pub fn avm_trace(&self, message: &str) {
         self.log.avm_trace(&message.replace('\r', "\n"));
     }
+
+    /// The time that has passed since the SWF was launched, as seen by `getTimer`.
+    pub fn elapsed_time(&self) -> Duration {
+        if self.deterministic {
+            Duration::from_secs_f64(self.timers.elapsed_millis() / 1000.0)
+        } else {
+            Instant::now().duration_since(self.start_time)
+        }
+    }
+
+    /// The current date and time, as seen by `Date`.
+    pub fn current_date_time(&self) -> DateTime<Utc> {
+        if self.deterministic {
+            get_mock_date_time() + self.elapsed_time()
+        } else {
+            get_current_date_time()
+        }
+    }
+
+    /// The local timezone, as seen by `Date`.
+    ///
+    /// Deterministic players are always in UTC, so that dates don't depend on
+    /// the machine they run on.
+    pub fn timezone(&self) -> FixedOffset {
+        if self.deterministic {
+            FixedOffset::east_opt(0).expect("UTC is a valid timezone")
+        } else {
+            get_timezone()
+        }
+    }
 }
 
 /// A queued ActionScript call.
@@ -0,0 +1,277 @@
+//! UDP sockets, as exposed through `flash.net.DatagramSocket`.
+
+use crate::avm2::bytearray::ByteArrayStorage;
+use crate::avm2::globals::slots::flash_net_datagram_socket as datagram_socket_slots;
+use crate::avm2::object::ByteArrayObject;
+use crate::avm2::{Activation, Avm2, EventObject, Object as Avm2Object, TObject, Value};
+use crate::backend::navigator::NavigatorBackend;
+use crate::context::UpdateContext;
+use crate::string::AvmString;
+
+use async_channel::{unbounded, Receiver, Sender};
+use gc_arena::Collect;
+use slotmap::{new_key_type, SlotMap};
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+
+new_key_type! {
+    pub struct DatagramSocketHandle;
+}
+
+/// A datagram sent or received through a `DatagramSocket`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Datagram {
+    pub data: Vec<u8>,
+
+    /// The destination host of a sent datagram, or the source of a received one.
+    pub host: String,
+
+    /// The destination port of a sent datagram, or the source of a received one.
+    pub port: u16,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DatagramSocketAction {
+    Data(DatagramSocketHandle, Datagram),
+    Close(DatagramSocketHandle),
+}
+
+struct DatagramSocket<'gc> {
+    /// The `flash.net.DatagramSocket` instance.
+    object: Avm2Object<'gc>,
+
+    sender: Sender<Datagram>,
+
+    /// Datagrams received before `receive` was called, up to `MAX_PENDING_DATAGRAMS`.
+    pending: Vec<Datagram>,
+}
+
+/// The maximum number of datagrams kept for a socket that isn't receiving yet.
+///
+/// Further datagrams are dropped, like the OS would when its receive buffer is full.
+const MAX_PENDING_DATAGRAMS: usize = 256;
+
+/// Returns whether a datagram from the given host came from the connected remote address.
+fn is_same_host(connected: &str, host: &str) -> bool {
+    match (connected.parse::<IpAddr>(), host.parse::<IpAddr>()) {
+        (Ok(connected), Ok(host)) => connected == host,
+        _ => connected == host,
+    }
+}
+
+/// Manages the collection of bound DatagramSockets.
+pub struct DatagramSockets<'gc> {
+    sockets: SlotMap<DatagramSocketHandle, DatagramSocket<'gc>>,
+
+    receiver: Receiver<DatagramSocketAction>,
+    sender: Sender<DatagramSocketAction>,
+}
+
+unsafe impl Collect for DatagramSockets<'_> {
+    fn trace(&self, cc: &gc_arena::Collection) {
+        for (_, socket) in self.sockets.iter() {
+            socket.object.trace(cc)
+        }
+    }
+}
+
+impl<'gc> DatagramSockets<'gc> {
+    pub fn empty() -> Self {
+        let (sender, receiver) = unbounded();
+
+        Self {
+            sockets: SlotMap::with_key(),
+            receiver,
+            sender,
+        }
+    }
+
+    fn find(&self, object: Avm2Object<'gc>) -> Option<DatagramSocketHandle> {
+        self.sockets
+            .iter()
+            .find(|(_, socket)| Avm2Object::ptr_eq(socket.object, object))
+            .map(|(handle, _)| handle)
+    }
+
+    pub fn is_bound(&self, object: Avm2Object<'gc>) -> bool {
+        self.find(object).is_some()
+    }
+
+    /// Binds a `DatagramSocket` object to a local address.
+    ///
+    /// Returns the address the socket was actually bound to.
+    pub fn bind(
+        &mut self,
+        backend: &mut dyn NavigatorBackend,
+        object: Avm2Object<'gc>,
+        host: String,
+        port: u16,
+    ) -> io::Result<SocketAddr> {
+        let (sender, receiver) = unbounded();
+        let handle = self.sockets.insert(DatagramSocket {
+            object,
+            sender,
+            pending: Vec::new(),
+        });
+
+        let result =
+            backend.bind_datagram_socket(host, port, handle, receiver, self.sender.clone());
+        if result.is_err() {
+            self.sockets.remove(handle);
+        }
+        result
+    }
+
+    /// Sends a datagram from a bound `DatagramSocket` object.
+    pub fn send(&mut self, object: Avm2Object<'gc>, datagram: Datagram) {
+        let Some(handle) = self.find(object) else {
+            return;
+        };
+
+        // We use an unbounded channel, so this should only ever error if the channel is closed
+        // (the receiver was dropped)
+        if let Err(e) = self.sockets[handle].sender.try_send(datagram) {
+            tracing::error!("Failed to send datagram: {:?}", e);
+        }
+    }
+
+    /// Closes a `DatagramSocket` object, if it's bound.
+    pub fn close(&mut self, object: Avm2Object<'gc>) {
+        if let Some(handle) = self.find(object) {
+            // NOTE: By dropping the sender, the backend task will close automatically.
+            self.sockets.remove(handle);
+        }
+    }
+
+    pub fn close_all(&mut self) {
+        self.sockets.clear();
+    }
+
+    pub fn update_sockets(context: &mut UpdateContext<'gc>) {
+        let mut closed = vec![];
+
+        while let Ok(action) = context.datagram_sockets.receiver.try_recv() {
+            match action {
+                DatagramSocketAction::Data(handle, datagram) => {
+                    if let Some(socket) = context.datagram_sockets.sockets.get_mut(handle) {
+                        if socket.pending.len() < MAX_PENDING_DATAGRAMS {
+                            socket.pending.push(datagram);
+                        } else {
+                            tracing::warn!(
+                                "Dropping datagram from {}, too many are pending",
+                                datagram.host
+                            );
+                        }
+                    }
+                }
+                DatagramSocketAction::Close(handle) => {
+                    if let Some(socket) = context.datagram_sockets.sockets.remove(handle) {
+                        closed.push(socket.object);
+                    }
+                }
+            }
+        }
+
+        // Datagrams are only dispatched once `receive` has been called.
+        let mut received = vec![];
+        for (_, socket) in context.datagram_sockets.sockets.iter_mut() {
+            if !socket.pending.is_empty()
+                && socket
+                    .object
+                    .get_slot(datagram_socket_slots::_RECEIVING)
+                    .coerce_to_boolean()
+            {
+                received.push((socket.object, std::mem::take(&mut socket.pending)));
+            }
+        }
+
+        let mut activation = Activation::from_nothing(context);
+        for (object, datagrams) in received {
+            for datagram in datagrams {
+                Self::dispatch_data(&mut activation, object, datagram);
+            }
+        }
+
+        for object in closed {
+            Self::reset(&mut activation, object);
+            let close_evt = EventObject::bare_default_event(activation.context, "close");
+            Avm2::dispatch_event(activation.context, close_evt, object);
+        }
+    }
+
+    /// Resets the state of a `DatagramSocket` object after it was closed.
+    pub fn reset(activation: &mut Activation<'_, 'gc>, object: Avm2Object<'gc>) {
+        let mc = activation.gc();
+        object.set_slot_no_coerce(datagram_socket_slots::_BOUND, false.into(), mc);
+        object.set_slot_no_coerce(datagram_socket_slots::_RECEIVING, false.into(), mc);
+        object.set_slot_no_coerce(datagram_socket_slots::_LOCAL_ADDRESS, Value::Null, mc);
+        object.set_slot_no_coerce(datagram_socket_slots::_LOCAL_PORT, 0.into(), mc);
+        object.set_slot_no_coerce(datagram_socket_slots::_REMOTE_ADDRESS, Value::Null, mc);
+        object.set_slot_no_coerce(datagram_socket_slots::_REMOTE_PORT, 0.into(), mc);
+    }
+
+    fn dispatch_data(
+        activation: &mut Activation<'_, 'gc>,
+        object: Avm2Object<'gc>,
+        datagram: Datagram,
+    ) {
+        // Connected sockets only receive datagrams from their remote address.
+        let remote_address = object.get_slot(datagram_socket_slots::_REMOTE_ADDRESS);
+        if !matches!(remote_address, Value::Null) {
+            let remote_port = object
+                .get_slot(datagram_socket_slots::_REMOTE_PORT)
+                .as_i32();
+            let Ok(remote_address) = remote_address.coerce_to_string(activation) else {
+                return;
+            };
+            if !is_same_host(&remote_address.to_string(), &datagram.host)
+                || remote_port != i32::from(datagram.port)
+            {
+                return;
+            }
+        }
+
+        let storage = ByteArrayStorage::from_vec(datagram.data);
+        let data = match ByteArrayObject::from_storage(activation, storage) {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::error!("Couldn't create datagram ByteArray: {e:?}");
+                return;
+            }
+        };
+
+        let event_class = activation.avm2().classes().datagramsocketdataevent;
+        let event_type = AvmString::new_utf8(activation.gc(), "data");
+        let src_address = AvmString::new_utf8(activation.gc(), datagram.host);
+        let dst_address = object.get_slot(datagram_socket_slots::_LOCAL_ADDRESS);
+        let dst_port = object.get_slot(datagram_socket_slots::_LOCAL_PORT);
+        let event = EventObject::from_class_and_args(
+            activation,
+            event_class,
+            &[
+                event_type.into(),
+                false.into(),
+                false.into(),
+                src_address.into(),
+                datagram.port.into(),
+                dst_address,
+                dst_port,
+                data.into(),
+            ],
+        );
+        Avm2::dispatch_event(activation.context, event, object);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_same_host;
+
+    #[test]
+    fn same_host() {
+        assert!(is_same_host("127.0.0.1", "127.0.0.1"));
+        assert!(is_same_host("::1", "0:0:0:0:0:0:0:1"));
+        assert!(!is_same_host("192.168.0.1", "192.168.0.2"));
+        assert!(is_same_host("localhost", "localhost"));
+    }
+}
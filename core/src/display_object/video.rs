@@ -5,6 +5,7 @@ use crate::avm2::{
     Activation as Avm2Activation, Object as Avm2Object, StageObject as Avm2StageObject,
     Value as Avm2Value,
 };
+use crate::camera::{Camera, Cameras};
 use crate::context::{RenderContext, UpdateContext};
 use crate::display_object::{DisplayObjectBase, DisplayObjectPtr};
 use crate::prelude::*;
@@ -101,7 +102,7 @@ pub enum VideoSource<'gc> {
     /// particular character. If you need to mutate the video source, consider
     /// reallocating a new source for your specific video instead.
     ///
-    /// This warning does not apply to `NetStream` or `Unconnected` videos,
+    /// This warning does not apply to `NetStream`, `Camera` or `Unconnected` videos,
     /// which are never aliased.
     Swf {
         /// The video stream definition.
@@ -119,6 +120,11 @@ pub enum VideoSource<'gc> {
         /// The stream the video is downloaded from.
         stream: NetStream<'gc>,
     },
+    /// An attached camera.
+    Camera {
+        /// The camera the video is captured from.
+        camera: Camera<'gc>,
+    },
     Unconnected,
 }
 
@@ -185,11 +191,38 @@ impl<'gc> Video<'gc> {
     ///
     /// Existing video state related to the old video stream will be dropped.
     pub fn attach_netstream(self, context: &mut UpdateContext<'gc>, stream: NetStream<'gc>) {
+        self.replace_source(context, VideoSource::NetStream { stream });
+    }
+
+    /// Convert this Video into a camera sourced video.
+    ///
+    /// The camera must have been acquired with `Cameras::acquire`.
+    /// Existing video state related to the old video stream will be dropped.
+    pub fn attach_camera(self, context: &mut UpdateContext<'gc>, camera: Camera<'gc>) {
+        self.replace_source(context, VideoSource::Camera { camera });
+    }
+
+    /// Disconnect this Video from its source, so that it stops displaying anything.
+    pub fn detach(self, context: &mut UpdateContext<'gc>) {
+        self.replace_source(context, VideoSource::Unconnected);
+    }
+
+    fn replace_source(self, context: &mut UpdateContext<'gc>, source: VideoSource<'gc>) {
         let mut video = self.0.write(context.gc());
 
-        video.source = GcCell::new(context.gc(), VideoSource::NetStream { stream });
+        let old_source = std::mem::replace(&mut video.source, GcCell::new(context.gc(), source));
         video.stream = VideoStream::Uninstantiated(0);
         video.keyframes = BTreeSet::new();
+        drop(video);
+
+        // A camera stops capturing once it's no longer attached to any video.
+        let old_camera = match &*old_source.read() {
+            VideoSource::Camera { camera } => Some(*camera),
+            _ => None,
+        };
+        if let Some(camera) = old_camera {
+            Cameras::release(context, camera);
+        }
     }
 
     /// Preload frame data from an SWF.
@@ -210,6 +243,7 @@ impl<'gc> Video<'gc> {
                 frames.insert(tag.frame_num.into(), (subslice.start, subslice.end));
             }
             VideoSource::NetStream { .. } => {}
+            VideoSource::Camera { .. } => {}
             VideoSource::Unconnected { .. } => {}
         }
     }
@@ -238,6 +272,7 @@ impl<'gc> Video<'gc> {
         let num_frames = match &*read.source.read() {
             VideoSource::Swf { streamdef, .. } => streamdef.num_frames as usize,
             VideoSource::NetStream { .. } => return,
+            VideoSource::Camera { .. } => return,
             VideoSource::Unconnected { .. } => return,
         };
 
@@ -326,6 +361,7 @@ impl<'gc> Video<'gc> {
                 }
             },
             VideoSource::NetStream { .. } => return,
+            VideoSource::Camera { .. } => return,
             VideoSource::Unconnected { .. } => return,
         };
 
@@ -427,6 +463,7 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
                 }
             }
             VideoSource::NetStream { .. } => return,
+            VideoSource::Camera { .. } => return,
             VideoSource::Unconnected { .. } => return,
         };
 
@@ -488,6 +525,7 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
         match &*self.0.read().source.read() {
             VideoSource::Swf { streamdef, .. } => streamdef.id,
             VideoSource::NetStream { .. } => 0,
+            VideoSource::Camera { .. } => 0,
             VideoSource::Unconnected { .. } => 0,
         }
     }
@@ -533,6 +571,9 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
                 stream.last_decoded_bitmap(),
                 None,
             ),
+            VideoSource::Camera { camera } => {
+                (false, None, read.movie.version(), camera.last_frame(), None)
+            }
             VideoSource::Unconnected { .. } => return context.transform_stack.pop(),
         };
 
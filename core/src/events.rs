@@ -32,11 +32,31 @@ pub enum PlayerEvent {
         delta: MouseWheelDelta,
     },
     GamepadButtonDown {
+        device: u32,
         button: GamepadButton,
     },
     GamepadButtonUp {
+        device: u32,
         button: GamepadButton,
     },
+    /// An analog axis of a gamepad moved.
+    ///
+    /// Sticks range from -1.0 to 1.0 (with positive Y pointing down),
+    /// and triggers range from 0.0 to 1.0. Frontends should report the
+    /// triggers with both this event and the button events.
+    GamepadAxisMotion {
+        device: u32,
+        axis: GamepadAxis,
+        value: f64,
+    },
+    /// A gamepad was connected. The device id is chosen by the frontend,
+    /// and must stay the same for all events of this gamepad until it is disconnected.
+    GamepadConnected {
+        device: u32,
+    },
+    GamepadDisconnected {
+        device: u32,
+    },
     TextInput {
         codepoint: char,
     },
@@ -803,6 +823,50 @@ pub enum GamepadButton {
     DPadRight,
 }
 
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    LeftTrigger2,
+    RightTrigger2,
+}
+
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub enum GamepadStick {
+    Left,
+    Right,
+}
+
+impl GamepadStick {
+    /// Returns the stick an axis belongs to, and whether it's the X axis.
+    pub fn from_axis(axis: GamepadAxis) -> Option<(Self, bool)> {
+        match axis {
+            GamepadAxis::LeftStickX => Some((Self::Left, true)),
+            GamepadAxis::LeftStickY => Some((Self::Left, false)),
+            GamepadAxis::RightStickX => Some((Self::Right, true)),
+            GamepadAxis::RightStickY => Some((Self::Right, false)),
+            GamepadAxis::LeftTrigger2 | GamepadAxis::RightTrigger2 => None,
+        }
+    }
+}
+
+/// How the movement of a gamepad stick is turned into other input.
+///
+/// The deadzone is the distance from the center (from 0.0 to 1.0)
+/// that the stick has to be moved before it has any effect.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum GamepadStickMapping {
+    /// Hold down the arrow keys in the direction the stick is pushed.
+    ArrowKeys { deadzone: f64 },
+
+    /// Move a virtual mouse cursor, at up to `speed` pixels per second.
+    /// The South button (A on Xbox controllers) is the left mouse button.
+    VirtualMouse { deadzone: f64, speed: f64 },
+}
+
 pub struct ParseEnumError;
 
 impl FromStr for GamepadButton {
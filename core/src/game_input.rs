@@ -0,0 +1,326 @@
+//! Game controllers, as exposed through `flash.ui.GameInput`.
+
+use crate::avm2::globals::slots::flash_ui_game_input_control as control_slots;
+use crate::avm2::globals::slots::flash_ui_game_input_device as device_slots;
+use crate::avm2::object::{scriptobject_allocator, WeakObject};
+use crate::avm2::{
+    Activation, ArrayObject, ArrayStorage, Avm2, Error as Avm2Error, EventObject,
+    Object as Avm2Object, TObject, Value as Avm2Value,
+};
+use crate::context::UpdateContext;
+use crate::events::{GamepadAxis, GamepadButton, PlayerEvent};
+use crate::string::AvmString;
+use gc_arena::Collect;
+
+/// A single control of a game input device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Control {
+    Axis(GamepadAxis),
+    Button(GamepadButton),
+}
+
+/// The controls of every device, in the order they are returned by `getControlAt`.
+///
+/// This follows the layout AIR uses for XInput controllers: the sticks come first,
+/// followed by the buttons. Triggers are buttons with an analog value.
+const CONTROLS: [Control; 18] = [
+    Control::Axis(GamepadAxis::LeftStickX),
+    Control::Axis(GamepadAxis::LeftStickY),
+    Control::Axis(GamepadAxis::RightStickX),
+    Control::Axis(GamepadAxis::RightStickY),
+    Control::Button(GamepadButton::South),
+    Control::Button(GamepadButton::East),
+    Control::Button(GamepadButton::West),
+    Control::Button(GamepadButton::North),
+    Control::Button(GamepadButton::LeftTrigger),
+    Control::Button(GamepadButton::RightTrigger),
+    Control::Button(GamepadButton::LeftTrigger2),
+    Control::Button(GamepadButton::RightTrigger2),
+    Control::Button(GamepadButton::Select),
+    Control::Button(GamepadButton::Start),
+    Control::Button(GamepadButton::DPadUp),
+    Control::Button(GamepadButton::DPadDown),
+    Control::Button(GamepadButton::DPadLeft),
+    Control::Button(GamepadButton::DPadRight),
+];
+
+impl Control {
+    fn from_axis(axis: GamepadAxis) -> Self {
+        match axis {
+            GamepadAxis::LeftTrigger2 => Control::Button(GamepadButton::LeftTrigger2),
+            GamepadAxis::RightTrigger2 => Control::Button(GamepadButton::RightTrigger2),
+            _ => Control::Axis(axis),
+        }
+    }
+
+    fn index(self) -> usize {
+        CONTROLS
+            .iter()
+            .position(|control| *control == self)
+            .expect("All controls should be listed")
+    }
+
+    fn id(self) -> String {
+        let index = self.index();
+        match self {
+            Control::Axis(_) => format!("AXIS_{index}"),
+            Control::Button(_) => format!("BUTTON_{index}"),
+        }
+    }
+
+    fn min_value(self) -> f64 {
+        match self {
+            Control::Axis(_) => -1.0,
+            Control::Button(_) => 0.0,
+        }
+    }
+
+    fn max_value(self) -> f64 {
+        1.0
+    }
+}
+
+fn is_analog(button: GamepadButton) -> bool {
+    matches!(
+        button,
+        GamepadButton::LeftTrigger2 | GamepadButton::RightTrigger2
+    )
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+struct GameInputDevice<'gc> {
+    /// The id the frontend uses for this device.
+    id: u32,
+
+    /// The `flash.ui.GameInputDevice` instance of this device.
+    object: Avm2Object<'gc>,
+
+    /// The `flash.ui.GameInputControl` instances of this device, in `CONTROLS` order.
+    controls: Vec<Avm2Object<'gc>>,
+}
+
+/// All connected game input devices, in the order they were connected.
+#[derive(Collect, Default)]
+#[collect(no_drop)]
+pub struct GameInputDevices<'gc> {
+    devices: Vec<GameInputDevice<'gc>>,
+
+    /// `flash.ui.GameInput` instances created since the last frame.
+    ///
+    /// Like in AIR, these get a `deviceAdded` event for every device
+    /// that was already connected when they were created.
+    new_instances: Vec<Avm2Object<'gc>>,
+
+    /// `flash.ui.GameInput` instances that were told about the connected devices,
+    /// and get `deviceAdded` and `deviceRemoved` events when they change.
+    instances: Vec<WeakObject<'gc>>,
+}
+
+impl<'gc> GameInputDevices<'gc> {
+    pub fn len(&self) -> usize {
+        self.devices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.devices.is_empty()
+    }
+
+    /// Returns the `flash.ui.GameInputDevice` object of the device at the given index.
+    pub fn get(&self, index: usize) -> Option<Avm2Object<'gc>> {
+        self.devices.get(index).map(|device| device.object)
+    }
+
+    pub fn register_instance(&mut self, game_input: Avm2Object<'gc>) {
+        self.new_instances.push(game_input);
+    }
+
+    /// Dispatches `deviceAdded` events for the already connected devices
+    /// to the `GameInput` instances created since the last call.
+    pub fn announce_devices(context: &mut UpdateContext<'gc>) {
+        if context.game_input.new_instances.is_empty() {
+            return;
+        }
+
+        let instances = std::mem::take(&mut context.game_input.new_instances);
+        let devices: Vec<_> = context
+            .game_input
+            .devices
+            .iter()
+            .map(|d| d.object)
+            .collect();
+
+        let mut activation = Activation::from_nothing(context);
+        for instance in instances {
+            for device in &devices {
+                let event = Self::device_event(&mut activation, "deviceAdded", *device);
+                Avm2::dispatch_event(activation.context, event, instance);
+            }
+            activation
+                .context
+                .game_input
+                .instances
+                .push(instance.downgrade());
+        }
+    }
+
+    /// Updates the devices with a gamepad event.
+    ///
+    /// Other events are ignored.
+    pub fn handle_event(context: &mut UpdateContext<'gc>, event: PlayerEvent) {
+        match event {
+            PlayerEvent::GamepadConnected { device } => Self::connect(context, device),
+            PlayerEvent::GamepadDisconnected { device } => Self::disconnect(context, device),
+            PlayerEvent::GamepadAxisMotion {
+                device,
+                axis,
+                value,
+            } => Self::set_value(context, device, Control::from_axis(axis), value),
+            // The triggers are driven by their analog axis instead.
+            PlayerEvent::GamepadButtonDown { device, button } if !is_analog(button) => {
+                Self::set_value(context, device, Control::Button(button), 1.0)
+            }
+            PlayerEvent::GamepadButtonUp { device, button } if !is_analog(button) => {
+                Self::set_value(context, device, Control::Button(button), 0.0)
+            }
+            _ => {}
+        }
+    }
+
+    fn connect(context: &mut UpdateContext<'gc>, id: u32) {
+        if context.game_input.devices.iter().any(|d| d.id == id) {
+            return;
+        }
+
+        let mut activation = Activation::from_nothing(context);
+        let device = match Self::create_device(&mut activation, id) {
+            Ok(device) => device,
+            Err(e) => {
+                tracing::error!("Couldn't create GameInputDevice: {e:?}");
+                return;
+            }
+        };
+
+        let object = device.object;
+        activation.context.game_input.devices.push(device);
+        Self::dispatch_device_event(&mut activation, "deviceAdded", object);
+    }
+
+    fn disconnect(context: &mut UpdateContext<'gc>, id: u32) {
+        let devices = &mut context.game_input.devices;
+        let Some(index) = devices.iter().position(|d| d.id == id) else {
+            return;
+        };
+
+        let device = devices.remove(index);
+        let mut activation = Activation::from_nothing(context);
+        Self::dispatch_device_event(&mut activation, "deviceRemoved", device.object);
+    }
+
+    fn set_value(context: &mut UpdateContext<'gc>, id: u32, control: Control, value: f64) {
+        let Some(device) = context.game_input.devices.iter().find(|d| d.id == id) else {
+            return;
+        };
+
+        let control_object = device.controls[control.index()];
+        let enabled = device
+            .object
+            .get_slot(device_slots::_ENABLED)
+            .coerce_to_boolean();
+        let value = value.clamp(control.min_value(), control.max_value());
+
+        if control_object.get_slot(control_slots::_VALUE).as_f64() == value {
+            return;
+        }
+        control_object.set_slot_no_coerce(control_slots::_VALUE, value.into(), context.gc());
+
+        // Controls only report changes while their device is enabled.
+        if enabled {
+            let change_event = EventObject::bare_default_event(context, "change");
+            Avm2::dispatch_event(context, change_event, control_object);
+        }
+    }
+
+    fn create_device(
+        activation: &mut Activation<'_, 'gc>,
+        id: u32,
+    ) -> Result<GameInputDevice<'gc>, Avm2Error<'gc>> {
+        let device_class = activation.avm2().classes().gameinputdevice;
+        let control_class = activation.avm2().classes().gameinputcontrol;
+
+        let object = scriptobject_allocator(device_class, activation)?;
+        device_class.call_init(object.into(), &[], activation)?;
+
+        let id_string = AvmString::new_utf8(activation.gc(), id.to_string());
+        object.set_slot(device_slots::_ID, id_string.into(), activation)?;
+        object.set_slot(device_slots::_NAME, "Gamepad".into(), activation)?;
+
+        let mut controls = Vec::with_capacity(CONTROLS.len());
+        for control in CONTROLS {
+            let control_object = scriptobject_allocator(control_class, activation)?;
+            control_class.call_init(control_object.into(), &[], activation)?;
+
+            let control_id = AvmString::new_utf8(activation.gc(), control.id());
+            control_object.set_slot(control_slots::_DEVICE, object.into(), activation)?;
+            control_object.set_slot(control_slots::_ID, control_id.into(), activation)?;
+            control_object.set_slot(
+                control_slots::_MIN_VALUE,
+                control.min_value().into(),
+                activation,
+            )?;
+            control_object.set_slot(
+                control_slots::_MAX_VALUE,
+                control.max_value().into(),
+                activation,
+            )?;
+            controls.push(control_object);
+        }
+
+        let values: Vec<Avm2Value<'gc>> = controls.iter().map(|c| (*c).into()).collect();
+        let controls_array =
+            ArrayObject::from_storage(activation, ArrayStorage::from_args(&values));
+        object.set_slot(device_slots::_CONTROLS, controls_array.into(), activation)?;
+
+        Ok(GameInputDevice {
+            id,
+            object,
+            controls,
+        })
+    }
+
+    fn device_event(
+        activation: &mut Activation<'_, 'gc>,
+        event_type: &'static str,
+        device: Avm2Object<'gc>,
+    ) -> EventObject<'gc> {
+        let event_class = activation.avm2().classes().gameinputevent;
+        let event_type = AvmString::new_utf8(activation.gc(), event_type);
+
+        EventObject::from_class_and_args(
+            activation,
+            event_class,
+            &[event_type.into(), false.into(), false.into(), device.into()],
+        )
+    }
+
+    /// Dispatches a device event to every `GameInput` instance that's still alive.
+    fn dispatch_device_event(
+        activation: &mut Activation<'_, 'gc>,
+        event_type: &'static str,
+        device: Avm2Object<'gc>,
+    ) {
+        let event = Self::device_event(activation, event_type, device);
+
+        let gc = activation.gc();
+        let instances = &mut activation.context.game_input.instances;
+        instances.retain(|instance| instance.upgrade(gc).is_some());
+        let instances: Vec<_> = instances
+            .iter()
+            .filter_map(|instance| instance.upgrade(gc))
+            .collect();
+
+        for instance in instances {
+            Avm2::dispatch_event(activation.context, event, instance);
+        }
+    }
+}
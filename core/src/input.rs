@@ -1,4 +1,7 @@
-use crate::events::{GamepadButton, KeyCode, MouseButton, PlayerEvent, TextControlCode};
+use crate::events::{
+    GamepadButton, GamepadStick, GamepadStickMapping, KeyCode, MouseButton, PlayerEvent,
+    TextControlCode,
+};
 use chrono::{DateTime, TimeDelta, Utc};
 use std::collections::{HashMap, HashSet};
 
@@ -27,10 +30,32 @@ pub struct InputManager {
 
     /// A map from gamepad buttons to key codes.
     gamepad_button_mapping: HashMap<GamepadButton, KeyCode>,
+
+    /// How the movement of each gamepad stick is turned into other input.
+    gamepad_stick_mapping: HashMap<GamepadStick, GamepadStickMapping>,
+
+    /// The last reported position of each gamepad stick.
+    stick_positions: HashMap<GamepadStick, StickState>,
+
+    /// The last known position of the mouse cursor in the viewport,
+    /// which is also moved by the virtual mouse.
+    mouse_position: (f64, f64),
+}
+
+#[derive(Default)]
+struct StickState {
+    x: f64,
+    y: f64,
+
+    /// The arrow keys held down by this stick, for the X and Y axes.
+    keys: [Option<KeyCode>; 2],
 }
 
 impl InputManager {
-    pub fn new(gamepad_button_mapping: HashMap<GamepadButton, KeyCode>) -> Self {
+    pub fn new(
+        gamepad_button_mapping: HashMap<GamepadButton, KeyCode>,
+        gamepad_stick_mapping: HashMap<GamepadStick, GamepadStickMapping>,
+    ) -> Self {
         Self {
             keys_down: HashSet::new(),
             keys_toggled: HashSet::new(),
@@ -39,6 +64,9 @@ impl InputManager {
             last_text_control: None,
             last_click: None,
             gamepad_button_mapping,
+            gamepad_stick_mapping,
+            stick_positions: HashMap::new(),
+            mouse_position: (0.0, 0.0),
         }
     }
 
@@ -67,33 +95,150 @@ impl InputManager {
         }
     }
 
-    pub fn map_input_event(&mut self, event: PlayerEvent) -> Option<PlayerEvent> {
-        // Optionally transform gamepad button events into key events.
+    pub fn has_virtual_mouse(&self) -> bool {
+        self.gamepad_stick_mapping
+            .values()
+            .any(|mapping| matches!(mapping, GamepadStickMapping::VirtualMouse { .. }))
+    }
+
+    /// Transform gamepad events into the key and mouse events they're mapped to.
+    ///
+    /// Gamepad events that aren't mapped to anything produce no events.
+    pub fn map_gamepad_event(&mut self, event: PlayerEvent) -> Vec<PlayerEvent> {
+        let (x, y) = self.mouse_position;
         match event {
-            PlayerEvent::GamepadButtonDown { button } => {
-                if let Some(key_code) = self.gamepad_button_mapping.get(&button) {
-                    Some(PlayerEvent::KeyDown {
-                        key_code: *key_code,
-                        key_char: None,
-                    })
+            PlayerEvent::GamepadButtonDown {
+                button: GamepadButton::South,
+                ..
+            } if self.has_virtual_mouse() => vec![PlayerEvent::MouseDown {
+                x,
+                y,
+                button: MouseButton::Left,
+                index: None,
+            }],
+            PlayerEvent::GamepadButtonUp {
+                button: GamepadButton::South,
+                ..
+            } if self.has_virtual_mouse() => vec![PlayerEvent::MouseUp {
+                x,
+                y,
+                button: MouseButton::Left,
+            }],
+            PlayerEvent::GamepadButtonDown { button, .. } => self
+                .gamepad_button_mapping
+                .get(&button)
+                .map(|key_code| PlayerEvent::KeyDown {
+                    key_code: *key_code,
+                    key_char: None,
+                })
+                .into_iter()
+                .collect(),
+            PlayerEvent::GamepadButtonUp { button, .. } => self
+                .gamepad_button_mapping
+                .get(&button)
+                .map(|key_code| PlayerEvent::KeyUp {
+                    key_code: *key_code,
+                    key_char: None,
+                })
+                .into_iter()
+                .collect(),
+            PlayerEvent::GamepadAxisMotion { axis, value, .. } => {
+                let Some((stick, is_x)) = GamepadStick::from_axis(axis) else {
+                    return vec![];
+                };
+
+                let state = self.stick_positions.entry(stick).or_default();
+                if is_x {
+                    state.x = value;
                 } else {
-                    // Just ignore this event.
-                    None
+                    state.y = value;
                 }
-            }
-            PlayerEvent::GamepadButtonUp { button } => {
-                if let Some(key_code) = self.gamepad_button_mapping.get(&button) {
-                    Some(PlayerEvent::KeyUp {
-                        key_code: *key_code,
-                        key_char: None,
-                    })
-                } else {
-                    // Just ignore this event.
-                    None
+
+                match self.gamepad_stick_mapping.get(&stick) {
+                    Some(GamepadStickMapping::ArrowKeys { deadzone }) => {
+                        let (negative, positive) = if is_x {
+                            (KeyCode::LEFT, KeyCode::RIGHT)
+                        } else {
+                            (KeyCode::UP, KeyCode::DOWN)
+                        };
+                        let key = if value < -deadzone {
+                            Some(negative)
+                        } else if value > *deadzone {
+                            Some(positive)
+                        } else {
+                            None
+                        };
+
+                        let held_key = &mut state.keys[usize::from(!is_x)];
+                        if *held_key == key {
+                            return vec![];
+                        }
+
+                        let mut events = vec![];
+                        if let Some(key_code) = held_key.take() {
+                            events.push(PlayerEvent::KeyUp {
+                                key_code,
+                                key_char: None,
+                            });
+                        }
+                        if let Some(key_code) = key {
+                            events.push(PlayerEvent::KeyDown {
+                                key_code,
+                                key_char: None,
+                            });
+                        }
+                        *held_key = key;
+                        events
+                    }
+                    // The virtual mouse is moved over time, in `update_virtual_mouse`.
+                    Some(GamepadStickMapping::VirtualMouse { .. }) | None => vec![],
                 }
             }
-            _ => Some(event),
+            _ => vec![],
+        }
+    }
+
+    /// Move the virtual mouse cursor according to the sticks mapped to it.
+    ///
+    /// `dt` is the time passed since the last update in milliseconds, and the cursor
+    /// is kept within a viewport of the given size.
+    pub fn update_virtual_mouse(
+        &mut self,
+        dt: f64,
+        width: f64,
+        height: f64,
+    ) -> Option<PlayerEvent> {
+        let (mut dx, mut dy) = (0.0, 0.0);
+        for (stick, mapping) in &self.gamepad_stick_mapping {
+            let GamepadStickMapping::VirtualMouse { deadzone, speed } = *mapping else {
+                continue;
+            };
+            let Some(state) = self.stick_positions.get(stick) else {
+                continue;
+            };
+
+            // Rescale the distance outside the deadzone, so that the cursor starts moving slowly.
+            let distance = state.x.hypot(state.y);
+            if distance <= deadzone {
+                continue;
+            }
+            let scale = ((distance - deadzone) / (1.0 - deadzone)).min(1.0) / distance;
+            dx += state.x * scale * speed * dt / 1000.0;
+            dy += state.y * scale * speed * dt / 1000.0;
+        }
+
+        if dx == 0.0 && dy == 0.0 {
+            return None;
+        }
+
+        let (x, y) = self.mouse_position;
+        let x = (x + dx).clamp(0.0, width);
+        let y = (y + dy).clamp(0.0, height);
+        if (x, y) == self.mouse_position {
+            return None;
         }
+
+        Some(PlayerEvent::MouseMove { x, y })
     }
 
     pub fn handle_event(&mut self, event: &PlayerEvent) {
@@ -117,11 +262,16 @@ impl InputManager {
                 button,
                 index,
             } => {
+                self.mouse_position = (x, y);
                 self.toggle_key(button.into());
                 self.add_key(button.into());
                 self.update_last_click(x, y, index);
             }
-            PlayerEvent::MouseUp { button, .. } => self.remove_key(button.into()),
+            PlayerEvent::MouseUp { x, y, button } => {
+                self.mouse_position = (x, y);
+                self.remove_key(button.into());
+            }
+            PlayerEvent::MouseMove { x, y } => self.mouse_position = (x, y),
             _ => {}
         }
     }
@@ -190,3 +340,113 @@ impl InputManager {
         buttons
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::GamepadAxis;
+
+    fn axis_motion(axis: GamepadAxis, value: f64) -> PlayerEvent {
+        PlayerEvent::GamepadAxisMotion {
+            device: 0,
+            axis,
+            value,
+        }
+    }
+
+    fn key_codes(events: &[PlayerEvent]) -> Vec<(bool, KeyCode)> {
+        events
+            .iter()
+            .map(|event| match *event {
+                PlayerEvent::KeyDown { key_code, .. } => (true, key_code),
+                PlayerEvent::KeyUp { key_code, .. } => (false, key_code),
+                _ => panic!("Expected a key event, got {event:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn stick_to_arrow_keys() {
+        let mut input = InputManager::new(
+            HashMap::new(),
+            HashMap::from([(
+                GamepadStick::Left,
+                GamepadStickMapping::ArrowKeys { deadzone: 0.5 },
+            )]),
+        );
+
+        let events = input.map_gamepad_event(axis_motion(GamepadAxis::LeftStickX, 0.4));
+        assert_eq!(key_codes(&events), vec![]);
+
+        let events = input.map_gamepad_event(axis_motion(GamepadAxis::LeftStickX, 0.6));
+        assert_eq!(key_codes(&events), vec![(true, KeyCode::RIGHT)]);
+
+        let events = input.map_gamepad_event(axis_motion(GamepadAxis::LeftStickX, 0.9));
+        assert_eq!(key_codes(&events), vec![]);
+
+        let events = input.map_gamepad_event(axis_motion(GamepadAxis::LeftStickY, -1.0));
+        assert_eq!(key_codes(&events), vec![(true, KeyCode::UP)]);
+
+        let events = input.map_gamepad_event(axis_motion(GamepadAxis::LeftStickX, -0.6));
+        assert_eq!(
+            key_codes(&events),
+            vec![(false, KeyCode::RIGHT), (true, KeyCode::LEFT)]
+        );
+
+        let events = input.map_gamepad_event(axis_motion(GamepadAxis::LeftStickY, 0.0));
+        assert_eq!(key_codes(&events), vec![(false, KeyCode::UP)]);
+
+        // The right stick isn't mapped.
+        let events = input.map_gamepad_event(axis_motion(GamepadAxis::RightStickX, 1.0));
+        assert_eq!(key_codes(&events), vec![]);
+    }
+
+    #[test]
+    fn stick_to_virtual_mouse() {
+        let mut input = InputManager::new(
+            HashMap::new(),
+            HashMap::from([(
+                GamepadStick::Right,
+                GamepadStickMapping::VirtualMouse {
+                    deadzone: 0.2,
+                    speed: 100.0,
+                },
+            )]),
+        );
+        input.handle_event(&PlayerEvent::MouseMove { x: 50.0, y: 50.0 });
+
+        input.map_gamepad_event(axis_motion(GamepadAxis::RightStickX, 0.1));
+        assert!(input.update_virtual_mouse(1000.0, 100.0, 100.0).is_none());
+
+        input.map_gamepad_event(axis_motion(GamepadAxis::RightStickX, 1.0));
+        let Some(PlayerEvent::MouseMove { x, y }) = input.update_virtual_mouse(100.0, 100.0, 100.0)
+        else {
+            panic!("Expected the virtual mouse to move");
+        };
+        assert_eq!((x, y), (60.0, 50.0));
+        input.handle_event(&PlayerEvent::MouseMove { x, y });
+
+        // The cursor stays within the viewport.
+        let Some(PlayerEvent::MouseMove { x, .. }) =
+            input.update_virtual_mouse(1000.0, 100.0, 100.0)
+        else {
+            panic!("Expected the virtual mouse to move");
+        };
+        assert_eq!(x, 100.0);
+        input.handle_event(&PlayerEvent::MouseMove { x, y });
+
+        let events = input.map_gamepad_event(PlayerEvent::GamepadButtonDown {
+            device: 0,
+            button: GamepadButton::South,
+        });
+        assert!(matches!(
+            events[..],
+            [PlayerEvent::MouseDown {
+                x: 100.0,
+                y: 50.0,
+                button: MouseButton::Left,
+                ..
+            }]
+        ));
+    }
+}
@@ -24,15 +24,18 @@ mod avm2;
 mod binary_data;
 pub mod bitmap;
 pub mod buffer;
+mod camera;
 mod character;
 pub mod context;
 pub mod context_menu;
+pub mod datagram_socket;
 mod drawing;
 mod ecma_conversions;
 pub mod events;
 pub mod focus_tracker;
 mod font;
 mod frame_lifecycle;
+mod game_input;
 mod html;
 mod input;
 mod library;
@@ -40,11 +43,14 @@ pub mod limits;
 pub mod loader;
 mod local_connection;
 mod locale;
+mod microphone;
 mod net_connection;
 pub mod pixel_bender;
 mod player;
+mod policy_file;
 mod prelude;
 pub mod sandbox;
+mod shader_job;
 pub mod socket;
 mod streams;
 pub mod string;
@@ -52,6 +58,7 @@ pub mod tag_utils;
 pub mod timer;
 mod types;
 mod vminterface;
+pub mod worker;
 mod xml;
 
 pub mod backend;
@@ -8,7 +8,7 @@ use crate::avm2::bytearray::ByteArrayStorage;
 use crate::avm2::globals::flash::utils::byte_array::strip_bom;
 use crate::avm2::object::{
     ByteArrayObject, EventObject as Avm2EventObject, FileReferenceObject, LoaderInfoObject,
-    LoaderStream, TObject as _,
+    LoaderStream, SoundObject, TObject as _,
 };
 use crate::avm2::{
     Activation as Avm2Activation, Avm2, BitmapDataObject, Domain as Avm2Domain,
@@ -19,6 +19,7 @@ use crate::backend::navigator::{ErrorResponse, OwnedFuture, Request, SuccessResp
 use crate::backend::ui::DialogResultFuture;
 use crate::bitmap::bitmap_data::Color;
 use crate::bitmap::bitmap_data::{BitmapData, BitmapDataWrapper};
+use crate::buffer::Buffer;
 use crate::context::{ActionQueue, ActionType, UpdateContext};
 use crate::display_object::{
     DisplayObject, MovieClip, TDisplayObject, TDisplayObjectContainer, TInteractiveObject,
@@ -27,6 +28,7 @@ use crate::events::ClipEvent;
 use crate::frame_lifecycle::catchup_display_object_to_frame;
 use crate::limits::ExecutionLimit;
 use crate::player::{Player, PostFrameCallback};
+use crate::policy_file::{movie_domain, MetaPolicy, PolicyDenial, PolicyFile, Taint};
 use crate::streams::NetStream;
 use crate::string::AvmString;
 use crate::tag_utils::SwfMovie;
@@ -515,6 +517,7 @@ impl<'gc> LoadManager<'gc> {
         player: Weak<Mutex<Player>>,
         target_object: Avm2Object<'gc>,
         request: Request,
+        movie: Arc<SwfMovie>,
     ) -> OwnedFuture<(), Error> {
         let loader = Loader::LoadURLLoader {
             self_handle: None,
@@ -522,7 +525,7 @@ impl<'gc> LoadManager<'gc> {
         };
         let handle = self.add_loader(loader);
         let loader = self.get_loader_mut(handle).unwrap();
-        loader.load_url_loader(player, request)
+        loader.load_url_loader(player, request, movie)
     }
 
     /// Kick off an AVM1 audio load.
@@ -552,6 +555,7 @@ impl<'gc> LoadManager<'gc> {
         player: Weak<Mutex<Player>>,
         target_object: Avm2Object<'gc>,
         request: Request,
+        buffer_time: f64,
     ) -> OwnedFuture<(), Error> {
         let loader = Loader::SoundAvm2 {
             self_handle: None,
@@ -559,7 +563,7 @@ impl<'gc> LoadManager<'gc> {
         };
         let handle = self.add_loader(loader);
         let loader = self.get_loader_mut(handle).unwrap();
-        loader.sound_loader_avm2(player, request)
+        loader.sound_loader_avm2(player, request, buffer_time)
     }
 
     pub fn load_netstream(
@@ -1013,6 +1017,92 @@ impl<'gc> Loader<'gc> {
         }
     }
 
+    /// Checks whether the given movie may access the data at `url`.
+    ///
+    /// Cross-domain data is only accessible if the movie asked for a policy file with
+    /// `check_policy_file`, and a policy file on the server allows the movie's domain.
+    /// Policy files are fetched the first time they're needed, then cached.
+    async fn check_url_policy(
+        player: &Arc<Mutex<Player>>,
+        movie: &SwfMovie,
+        url: &Url,
+        check_policy_file: bool,
+    ) -> Result<(), PolicyDenial> {
+        let policy_urls = player.lock().unwrap().mutate_with_update_context(|uc| {
+            uc.url_policy_files
+                .needs_policy_file(movie, url)
+                .then(|| uc.url_policy_files.policy_file_urls(url))
+        });
+        let Some(policy_urls) = policy_urls else {
+            return Ok(());
+        };
+        if !check_policy_file {
+            return Err(PolicyDenial::NotChecked);
+        }
+
+        let domain = movie_domain(movie);
+        for (i, policy_url) in policy_urls.into_iter().enumerate() {
+            let Some(policy) = Self::fetch_policy_file(player, policy_url).await else {
+                // Without a master policy file, the meta-policy is `master-only`.
+                if i == 0 {
+                    return Err(PolicyDenial::NotGranted);
+                }
+                continue;
+            };
+
+            // Only the master policy file can restrict the other ones.
+            let meta_policy = if i == 0 {
+                policy.meta_policy().unwrap_or(MetaPolicy::MasterOnly)
+            } else {
+                MetaPolicy::All
+            };
+            if meta_policy == MetaPolicy::None {
+                return Err(PolicyDenial::NotGranted);
+            }
+            if policy.allows_url(domain.as_deref()) {
+                return Ok(());
+            }
+            if meta_policy == MetaPolicy::MasterOnly {
+                return Err(PolicyDenial::NotGranted);
+            }
+        }
+
+        Err(PolicyDenial::NotGranted)
+    }
+
+    /// Fetches the policy file at the given URL, unless it was fetched already.
+    async fn fetch_policy_file(player: &Arc<Mutex<Player>>, url: Url) -> Option<PolicyFile> {
+        let cached = player
+            .lock()
+            .unwrap()
+            .mutate_with_update_context(|uc| uc.url_policy_files.get(&url).cloned());
+        if let Some(policy) = cached {
+            return policy;
+        }
+
+        let fetch = player
+            .lock()
+            .unwrap()
+            .navigator()
+            .fetch(Request::get(url.to_string()));
+        let policy = match Self::wait_for_full_response(fetch).await {
+            Ok((body, _, _, _)) => match PolicyFile::parse(&body) {
+                Ok(policy) => Some(policy),
+                Err(e) => {
+                    tracing::warn!("Invalid policy file {}: {}", url, e);
+                    None
+                }
+            },
+            Err(_) => None,
+        };
+
+        player
+            .lock()
+            .unwrap()
+            .mutate_with_update_context(|uc| uc.url_policy_files.insert(url, policy.clone()));
+        policy
+    }
+
     /// Construct a future for the root movie loader.
     fn root_movie_loader(
         &mut self,
@@ -1197,6 +1287,30 @@ impl<'gc> Loader<'gc> {
                     return Ok(());
                 }
                 Ok((body, url, status, redirected)) => {
+                    // Images from another domain are tainted, unless a policy file allows
+                    // the loading movie to access them.
+                    let mut taint = None;
+                    if matches!(
+                        ContentType::sniff(&body),
+                        ContentType::Gif | ContentType::Jpeg | ContentType::Png
+                    ) {
+                        let requester = player.lock().unwrap().mutate_with_update_context(|uc| {
+                            Loader::movie_loader_requester(handle, uc)
+                        });
+                        if let (Some((movie, check_policy_file)), Ok(url)) =
+                            (requester, Url::parse(&url))
+                        {
+                            taint =
+                                Self::check_url_policy(&player, &movie, &url, check_policy_file)
+                                    .await
+                                    .err()
+                                    .map(|denial| Taint {
+                                        url: url.to_string(),
+                                        denial,
+                                    });
+                        }
+                    }
+
                     player.lock().unwrap().mutate_with_update_context(|uc| {
                         Loader::movie_loader_data(
                             handle,
@@ -1206,6 +1320,7 @@ impl<'gc> Loader<'gc> {
                             status,
                             redirected,
                             loader_url,
+                            taint,
                         )
                     })?;
                 }
@@ -1279,7 +1394,7 @@ impl<'gc> Loader<'gc> {
             return Ok(());
         }
 
-        Loader::movie_loader_data(handle, uc, &bytes, "file:///".into(), 0, false, None)
+        Loader::movie_loader_data(handle, uc, &bytes, "file:///".into(), 0, false, None, None)
     }
 
     fn form_loader(
@@ -1533,6 +1648,7 @@ impl<'gc> Loader<'gc> {
         &mut self,
         player: Weak<Mutex<Player>>,
         request: Request,
+        movie: Arc<SwfMovie>,
     ) -> OwnedFuture<(), Error> {
         let handle = match self {
             Loader::LoadURLLoader { self_handle, .. } => {
@@ -1546,6 +1662,40 @@ impl<'gc> Loader<'gc> {
             .expect("Could not upgrade weak reference to player");
 
         Box::pin(async move {
+            // Cross-domain data is only loaded if a policy file allows it.
+            let url = player
+                .lock()
+                .unwrap()
+                .navigator()
+                .resolve_url(request.url());
+            if let Ok(url) = url {
+                if Self::check_url_policy(&player, &movie, &url, true)
+                    .await
+                    .is_err()
+                {
+                    return player.lock().unwrap().update(|uc| {
+                        let target = match uc.load_manager.get_loader(handle) {
+                            Some(&Loader::LoadURLLoader { target_object, .. }) => target_object,
+                            None => return Err(Error::Cancelled),
+                            _ => unreachable!(),
+                        };
+
+                        let mut activation = Avm2Activation::from_nothing(uc);
+                        let message = format!(
+                            "Error #2048: Security sandbox violation: {} cannot load data from {}.",
+                            movie.url(),
+                            url
+                        );
+                        let message = AvmString::new_utf8(activation.gc(), message);
+                        let security_error_evt =
+                            Avm2EventObject::security_error_event(&mut activation, message, 2048);
+                        Avm2::dispatch_event(activation.context, security_error_evt, target);
+
+                        Ok(())
+                    });
+                }
+            }
+
             let fetch = player.lock().unwrap().navigator().fetch(request);
             let response = Self::wait_for_full_response(fetch).await;
 
@@ -1758,6 +1908,7 @@ impl<'gc> Loader<'gc> {
         &mut self,
         player: Weak<Mutex<Player>>,
         request: Request,
+        buffer_time: f64,
     ) -> OwnedFuture<(), Error> {
         let handle = match self {
             Loader::SoundAvm2 { self_handle, .. } => {
@@ -1770,71 +1921,134 @@ impl<'gc> Loader<'gc> {
             .upgrade()
             .expect("Could not upgrade weak reference to player");
 
-        Box::pin(async move {
-            let fetch = player.lock().unwrap().navigator().fetch(request);
-            let response = Self::wait_for_full_response(fetch).await;
+        fn get_sound_object<'gc>(
+            uc: &UpdateContext<'gc>,
+            handle: LoaderHandle,
+        ) -> Result<SoundObject<'gc>, Error> {
+            match uc.load_manager.get_loader(handle) {
+                Some(&Loader::SoundAvm2 { target_object, .. }) => {
+                    Ok(target_object.as_sound_object().expect("Not a sound object"))
+                }
+                None => Err(Error::Cancelled),
+                _ => Err(Error::NotSoundLoader),
+            }
+        }
 
-            player.lock().unwrap().update(|uc| {
-                let loader = uc.load_manager.get_loader(handle);
-                let sound_object = match loader {
-                    Some(&Loader::SoundAvm2 { target_object, .. }) => target_object,
-                    None => return Err(Error::Cancelled),
-                    _ => return Err(Error::NotSoundLoader),
-                };
+        fn report_error(uc: &mut UpdateContext<'_>, handle: LoaderHandle) -> Result<(), Error> {
+            let sound_object = get_sound_object(uc, handle)?;
+            sound_object.set_is_buffering(false);
 
-                match response {
-                    Ok((body, _, _, _)) => {
-                        let handle = uc.audio.register_mp3(&body)?;
-                        if let Err(e) = sound_object
-                            .as_sound_object()
-                            .expect("Not a sound object")
-                            .set_sound(uc, handle)
-                        {
-                            tracing::error!("Encountered AVM2 error when setting sound: {}", e);
-                        }
+            let mut activation = Avm2Activation::from_nothing(uc);
 
-                        let total_len = body.len();
+            // FIXME: Match the exact error message generated by Flash.
+            let io_error_evt = Avm2EventObject::io_error_event(
+                &mut activation,
+                "Error #2032: Stream Error".into(),
+                2032,
+            );
 
-                        // FIXME - the "open" event should be fired earlier, and not fired in case of ioerror.
-                        let mut activation = Avm2Activation::from_nothing(uc);
-                        let open_evt =
-                            Avm2EventObject::bare_default_event(activation.context, "open");
-                        Avm2::dispatch_event(activation.context, open_evt, sound_object);
+            Avm2::dispatch_event(uc, io_error_evt, sound_object.into());
+            Ok(())
+        }
 
-                        // FIXME - As in load_url_loader, we should fire "progress" events as we receive data,
-                        // not just at the end
-                        let progress_evt = Avm2EventObject::progress_event(
-                            &mut activation,
-                            "progress",
-                            total_len,
-                            total_len,
-                        );
+        Box::pin(async move {
+            let fetch = player.lock().unwrap().navigator().fetch(request);
+            let mut response = match fetch.await {
+                Ok(response) => response,
+                Err(_) => return player.lock().unwrap().update(|uc| report_error(uc, handle)),
+            };
+
+            let expected_length = response
+                .expected_length()
+                .ok()
+                .flatten()
+                .map(|len| len as usize);
+            let url = response.url().to_string();
 
-                        Avm2::dispatch_event(activation.context, progress_evt, sound_object);
+            player.lock().unwrap().update(|uc| -> Result<(), Error> {
+                let sound_object = get_sound_object(uc, handle)?;
+                sound_object.set_url(uc.gc(), Some(AvmString::new_utf8(uc.gc(), url)));
+                sound_object.set_bytes_total(expected_length);
+                sound_object.set_is_buffering(true);
 
-                        sound_object
-                            .as_sound_object()
-                            .expect("Not a sound object")
-                            .read_and_call_id3_event(&mut activation, body.as_slice());
+                let open_evt = Avm2EventObject::bare_default_event(uc, "open");
+                Avm2::dispatch_event(uc, open_evt, sound_object.into());
+                Ok(())
+            })?;
 
-                        let complete_evt =
-                            Avm2EventObject::bare_default_event(activation.context, "complete");
-                        Avm2::dispatch_event(activation.context, complete_evt, sound_object);
+            // The sound is registered as soon as its first frame arrives,
+            // and starts playing once `buffer_time` milliseconds are loaded.
+            let mut data = Buffer::new();
+            let mut sound_handle = None;
+            let mut is_playing = false;
+
+            loop {
+                let chunk = match response.next_chunk().await {
+                    Ok(Some(chunk)) => chunk,
+                    Ok(None) => break,
+                    Err(_) => return player.lock().unwrap().update(|uc| report_error(uc, handle)),
+                };
+
+                player.lock().unwrap().update(|uc| -> Result<(), Error> {
+                    let sound_object = get_sound_object(uc, handle)?;
+                    data.extend_from_slice(&chunk);
+                    let bytes_loaded = data.len();
+                    sound_object.set_bytes_loaded(bytes_loaded);
+
+                    if sound_handle.is_none() {
+                        sound_handle = uc.audio.register_mp3_stream(data.clone(), buffer_time).ok();
+                    }
+                    if let Some(sound_handle) = sound_handle.filter(|_| is_playing) {
+                        // Playback may have caught up with the loaded data since the last chunk.
+                        sound_object.set_is_buffering(uc.audio.is_sound_buffering(sound_handle));
+                    } else if let Some(sound_handle) = sound_handle {
+                        let duration = uc.audio.get_sound_duration(sound_handle);
+                        if duration.is_some_and(|duration| duration >= buffer_time) {
+                            if let Err(e) = sound_object.set_sound(uc, sound_handle) {
+                                tracing::error!("Encountered AVM2 error when setting sound: {}", e);
+                            }
+                            sound_object.set_is_buffering(false);
+                            is_playing = true;
+                        }
                     }
-                    Err(_err) => {
-                        let mut activation = Avm2Activation::from_nothing(uc);
 
-                        // FIXME: Match the exact error message generated by Flash.
-                        let io_error_evt = Avm2EventObject::io_error_event(
-                            &mut activation,
-                            "Error #2032: Stream Error".into(),
-                            2032,
-                        );
+                    let mut activation = Avm2Activation::from_nothing(uc);
+                    let progress_evt = Avm2EventObject::progress_event(
+                        &mut activation,
+                        "progress",
+                        bytes_loaded,
+                        expected_length.unwrap_or(bytes_loaded),
+                    );
+                    Avm2::dispatch_event(uc, progress_evt, sound_object.into());
+                    Ok(())
+                })?;
+            }
 
-                        Avm2::dispatch_event(uc, io_error_evt, sound_object);
+            player.lock().unwrap().update(|uc| -> Result<(), Error> {
+                let sound_object = get_sound_object(uc, handle)?;
+                let body = data.to_full_slice();
+                let body = body.data();
+
+                let sound_handle = match sound_handle {
+                    Some(sound_handle) => {
+                        uc.audio.finish_mp3_stream(sound_handle)?;
+                        sound_handle
+                    }
+                    None => uc.audio.register_mp3(&body)?,
+                };
+                if !is_playing {
+                    if let Err(e) = sound_object.set_sound(uc, sound_handle) {
+                        tracing::error!("Encountered AVM2 error when setting sound: {}", e);
                     }
                 }
+                sound_object.set_bytes_total(Some(body.len()));
+                sound_object.set_is_buffering(false);
 
+                let mut activation = Avm2Activation::from_nothing(uc);
+                sound_object.read_and_call_id3_event(&mut activation, &body);
+
+                let complete_evt = Avm2EventObject::bare_default_event(uc, "complete");
+                Avm2::dispatch_event(uc, complete_evt, sound_object.into());
                 Ok(())
             })
         })
@@ -1919,6 +2133,38 @@ impl<'gc> Loader<'gc> {
         })
     }
 
+    /// Returns the movie that started a movie loader, and whether it set
+    /// `LoaderContext.checkPolicyFile`.
+    ///
+    /// Only AVM2 loaders are known to come from a given movie.
+    fn movie_loader_requester(
+        handle: LoaderHandle,
+        uc: &mut UpdateContext<'gc>,
+    ) -> Option<(Arc<SwfMovie>, bool)> {
+        use crate::avm2::globals::slots::flash_system_loader_context as loader_context_slots;
+
+        let Some(Loader::Movie {
+            vm_data:
+                MovieLoaderVMData::Avm2 {
+                    loader_info,
+                    context,
+                    ..
+                },
+            ..
+        }) = uc.load_manager.get_loader(handle)
+        else {
+            return None;
+        };
+
+        let movie = loader_info.loader()?.as_display_object()?.movie();
+        let check_policy_file = context.is_some_and(|context| {
+            context
+                .get_slot(loader_context_slots::CHECK_POLICY_FILE)
+                .coerce_to_boolean()
+        });
+        Some((movie, check_policy_file))
+    }
+
     /// Report a movie loader start event to script code.
     fn movie_loader_start(handle: LoaderHandle, uc: &mut UpdateContext<'gc>) -> Result<(), Error> {
         let me = uc.load_manager.get_loader_mut(handle);
@@ -1961,6 +2207,7 @@ impl<'gc> Loader<'gc> {
     }
 
     /// Load data into a movie loader.
+    #[allow(clippy::too_many_arguments)]
     fn movie_loader_data(
         handle: LoaderHandle,
         uc: &mut UpdateContext<'gc>,
@@ -1969,6 +2216,7 @@ impl<'gc> Loader<'gc> {
         status: u16,
         redirected: bool,
         loader_url: Option<String>,
+        taint: Option<Taint>,
     ) -> Result<(), Error> {
         let sniffed_type = ContentType::sniff(data);
         let length = data.len();
@@ -1976,7 +2224,7 @@ impl<'gc> Loader<'gc> {
         if sniffed_type == ContentType::Unknown {
             if let Ok(data) = extract_swz(data) {
                 return Self::movie_loader_data(
-                    handle, uc, &data, url, status, redirected, loader_url,
+                    handle, uc, &data, url, status, redirected, loader_url, taint,
                 );
             }
         }
@@ -2149,12 +2397,13 @@ impl<'gc> Loader<'gc> {
                 let bitmap = ruffle_render::utils::decode_define_bits_jpeg(data, None)?;
 
                 let transparency = true;
-                let bitmap_data = BitmapData::new_with_pixels(
+                let mut bitmap_data = BitmapData::new_with_pixels(
                     bitmap.width(),
                     bitmap.height(),
                     transparency,
                     bitmap.as_colors().map(Color::from).collect(),
                 );
+                bitmap_data.set_taint(taint);
                 let bitmapdata_wrapper =
                     BitmapDataWrapper::new(GcCell::new(activation.gc(), bitmap_data));
                 let bitmapdata_class = activation.context.avm2.classes().bitmapdata;
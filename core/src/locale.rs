@@ -7,16 +7,22 @@ const MOCK_TIME: bool = cfg!(any(test, feature = "deterministic"));
 
 pub fn get_current_date_time() -> DateTime<Utc> {
     if MOCK_TIME {
-        get_timezone()
-            .with_ymd_and_hms(2001, 2, 3, 4, 5, 6)
-            .single()
-            .expect("Unambiguous mock time")
-            .into()
+        get_mock_date_time()
     } else {
         Utc::now()
     }
 }
 
+/// The fixed date and time used by tests and by deterministic players.
+pub fn get_mock_date_time() -> DateTime<Utc> {
+    FixedOffset::east_opt(20700)
+        .expect("Unambiguous mock timezone")
+        .with_ymd_and_hms(2001, 2, 3, 4, 5, 6)
+        .single()
+        .expect("Unambiguous mock time")
+        .into()
+}
+
 pub fn get_timezone() -> FixedOffset {
     if MOCK_TIME {
         FixedOffset::east_opt(20700).expect("Unambiguous mock timezone")
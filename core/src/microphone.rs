@@ -0,0 +1,279 @@
+//! Audio capture, as exposed through `flash.media.Microphone`.
+
+use crate::avm2::bytearray::ByteArrayStorage;
+use crate::avm2::globals::slots::flash_media_microphone as microphone_slots;
+use crate::avm2::object::ByteArrayObject;
+use crate::avm2::{Activation, Avm2, EventObject, Object as Avm2Object, TObject};
+use crate::context::UpdateContext;
+use crate::string::AvmString;
+use gc_arena::Collect;
+
+/// Converts a `Microphone.rate` value in kHz to a sample rate in Hz.
+fn sample_rate(rate: i32) -> u32 {
+    match rate {
+        5 => 5512,
+        8 => 8000,
+        11 => 11025,
+        22 => 22050,
+        _ => 44100,
+    }
+}
+
+/// Returns the activity level of a block of samples, from 0 to 100.
+fn activity_level(samples: &[f32]) -> f64 {
+    let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    (f64::from(peak) * 100.0).clamp(0.0, 100.0)
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+struct ActiveMicrophone<'gc> {
+    /// The `flash.media.Microphone` instance.
+    object: Avm2Object<'gc>,
+
+    /// The index of the audio input device.
+    #[collect(require_static)]
+    device: usize,
+
+    /// The sample rate the device is capturing at, in Hz.
+    #[collect(require_static)]
+    sample_rate: u32,
+
+    /// The number of samples dispatched so far.
+    #[collect(require_static)]
+    position: f64,
+
+    /// Whether the input is above the silence level, as last reported by an `activity` event.
+    #[collect(require_static)]
+    active: bool,
+
+    /// For how long the input has been at or below the silence level, in milliseconds.
+    #[collect(require_static)]
+    silent_for: f64,
+}
+
+/// All microphones that are currently capturing audio.
+#[derive(Collect, Default)]
+#[collect(no_drop)]
+pub struct Microphones<'gc> {
+    active: Vec<ActiveMicrophone<'gc>>,
+}
+
+impl<'gc> Microphones<'gc> {
+    /// Starts capturing audio for a `Microphone` object, if it isn't already.
+    pub fn start(activation: &mut Activation<'_, 'gc>, object: Avm2Object<'gc>) {
+        let microphones = &activation.context.microphones.active;
+        if microphones
+            .iter()
+            .any(|m| Avm2Object::ptr_eq(m.object, object))
+        {
+            return;
+        }
+
+        let device = object
+            .get_slot(microphone_slots::_INDEX)
+            .as_i32()
+            .try_into()
+            .unwrap_or_default();
+        let sample_rate = sample_rate(object.get_slot(microphone_slots::_RATE).as_i32());
+
+        if let Err(e) = activation
+            .context
+            .audio_input
+            .start_capture(device, sample_rate)
+        {
+            tracing::warn!("Couldn't start microphone {device}: {e}");
+            return;
+        }
+
+        object.set_slot_no_coerce(microphone_slots::_ACTIVITY_LEVEL, 0.into(), activation.gc());
+        activation
+            .context
+            .microphones
+            .active
+            .push(ActiveMicrophone {
+                object,
+                device,
+                sample_rate,
+                position: 0.0,
+                active: false,
+                silent_for: 0.0,
+            });
+    }
+
+    /// Stops capturing audio for a `Microphone` object.
+    pub fn stop(activation: &mut Activation<'_, 'gc>, object: Avm2Object<'gc>) {
+        let microphones = &mut activation.context.microphones.active;
+        let Some(index) = microphones
+            .iter()
+            .position(|m| Avm2Object::ptr_eq(m.object, object))
+        else {
+            return;
+        };
+
+        let microphone = microphones.remove(index);
+        activation
+            .context
+            .audio_input
+            .stop_capture(microphone.device);
+        object.set_slot_no_coerce(
+            microphone_slots::_ACTIVITY_LEVEL,
+            (-1).into(),
+            activation.gc(),
+        );
+    }
+
+    /// Dispatches the audio captured during the last frame to every active microphone.
+    pub fn update(context: &mut UpdateContext<'gc>) {
+        if context.microphones.active.is_empty() {
+            return;
+        }
+
+        let elapsed = 1000.0 / *context.frame_rate;
+        let mut samples = Vec::new();
+
+        // Events may start or stop microphones, so iterate over a snapshot.
+        let objects: Vec<_> = context
+            .microphones
+            .active
+            .iter()
+            .map(|m| m.object)
+            .collect();
+        for object in objects {
+            let Some(microphone) = context
+                .microphones
+                .active
+                .iter_mut()
+                .find(|m| Avm2Object::ptr_eq(m.object, object))
+            else {
+                continue;
+            };
+
+            let sample_rate = sample_rate(object.get_slot(microphone_slots::_RATE).as_i32());
+            if sample_rate != microphone.sample_rate {
+                if let Err(e) = context
+                    .audio_input
+                    .start_capture(microphone.device, sample_rate)
+                {
+                    tracing::warn!("Couldn't change microphone rate: {e}");
+                }
+                microphone.sample_rate = sample_rate;
+            }
+
+            samples.clear();
+            context
+                .audio_input
+                .take_samples(microphone.device, elapsed, &mut samples);
+
+            let gain = (object.get_slot(microphone_slots::_GAIN).as_f64() / 50.0) as f32;
+            for sample in &mut samples {
+                *sample = (*sample * gain).clamp(-1.0, 1.0);
+            }
+
+            let level = activity_level(&samples);
+            let silence_level = object.get_slot(microphone_slots::_SILENCE_LEVEL).as_f64();
+            let silence_timeout = object.get_slot(microphone_slots::_SILENCE_TIMEOUT).as_f64();
+
+            let activating = if level > silence_level {
+                microphone.silent_for = 0.0;
+                (!microphone.active).then_some(true)
+            } else {
+                microphone.silent_for += elapsed;
+                (microphone.active && microphone.silent_for >= silence_timeout).then_some(false)
+            };
+            if let Some(activating) = activating {
+                microphone.active = activating;
+            }
+
+            let position = microphone.position;
+            microphone.position += samples.len() as f64;
+
+            object.set_slot_no_coerce(
+                microphone_slots::_ACTIVITY_LEVEL,
+                level.into(),
+                context.gc(),
+            );
+
+            let mut activation = Activation::from_nothing(context);
+            if let Some(activating) = activating {
+                let event_class = activation.avm2().classes().activityevent;
+                let event_type = AvmString::new_utf8(activation.gc(), "activity");
+                let event = EventObject::from_class_and_args(
+                    &mut activation,
+                    event_class,
+                    &[
+                        event_type.into(),
+                        false.into(),
+                        false.into(),
+                        activating.into(),
+                    ],
+                );
+                Avm2::dispatch_event(activation.context, event, object);
+            }
+
+            if !samples.is_empty() {
+                Self::dispatch_sample_data(&mut activation, object, position, &samples);
+            }
+        }
+    }
+
+    fn dispatch_sample_data(
+        activation: &mut Activation<'_, 'gc>,
+        object: Avm2Object<'gc>,
+        position: f64,
+        samples: &[f32],
+    ) {
+        let mut storage = ByteArrayStorage::new();
+        for sample in samples {
+            storage
+                .write_float(*sample)
+                .expect("Writing to a new ByteArray shouldn't fail");
+        }
+        storage.set_position(0);
+
+        let data = match ByteArrayObject::from_storage(activation, storage) {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::error!("Couldn't create microphone sample data: {e:?}");
+                return;
+            }
+        };
+
+        let event_class = activation.avm2().classes().sampledataevent;
+        let event_type = AvmString::new_utf8(activation.gc(), "sampleData");
+        let event = EventObject::from_class_and_args(
+            activation,
+            event_class,
+            &[
+                event_type.into(),
+                false.into(),
+                false.into(),
+                position.into(),
+                data.into(),
+            ],
+        );
+        Avm2::dispatch_event(activation.context, event, object);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::audio::{AudioInputBackend, ToneAudioInputBackend};
+
+    #[test]
+    fn tone_activity_level() {
+        let mut backend = ToneAudioInputBackend::new(440.0, 0.5);
+        backend.start_capture(0, 8000).unwrap();
+
+        let mut samples = Vec::new();
+        backend.take_samples(0, 100.0, &mut samples);
+        assert_eq!(samples.len(), 800);
+        assert!((activity_level(&samples) - 50.0).abs() < 1.0);
+
+        samples.clear();
+        backend.set_amplitude(0.0);
+        backend.take_samples(0, 100.0, &mut samples);
+        assert_eq!(activity_level(&samples), 0.0);
+    }
+}
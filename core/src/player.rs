@@ -9,37 +9,44 @@ use crate::avm2::object::{EventObject as Avm2EventObject, Object as Avm2Object};
 use crate::avm2::{Activation as Avm2Activation, Avm2, CallStack};
 use crate::backend::ui::FontDefinition;
 use crate::backend::{
-    audio::{AudioBackend, AudioManager},
+    audio::{AudioBackend, AudioInputBackend, AudioManager, NullAudioInputBackend},
+    camera::{CameraBackend, NullCameraBackend},
     log::LogBackend,
     navigator::{NavigatorBackend, Request},
     storage::StorageBackend,
     ui::{MouseCursor, UiBackend},
 };
+use crate::camera::Cameras;
 use crate::compatibility_rules::CompatibilityRules;
 use crate::config::Letterbox;
 use crate::context::{ActionQueue, ActionType, RenderContext, UpdateContext};
 use crate::context_menu::{
     BuiltInItemFlags, ContextMenuCallback, ContextMenuItem, ContextMenuState,
 };
+use crate::datagram_socket::DatagramSockets;
 use crate::display_object::Avm2MousePick;
 use crate::display_object::{
     EditText, InteractiveObject, Stage, StageAlign, StageDisplayState, StageScaleMode,
     TInteractiveObject, WindowMode,
 };
-use crate::events::GamepadButton;
 use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult, KeyCode, MouseButton, PlayerEvent};
+use crate::events::{GamepadButton, GamepadStick, GamepadStickMapping};
 use crate::external::{ExternalInterface, ExternalInterfaceProvider, NullFsCommandProvider};
 use crate::external::{FsCommandProvider, Value as ExternalValue};
 use crate::focus_tracker::NavigationDirection;
 use crate::frame_lifecycle::{run_all_phases_avm2, FramePhase};
+use crate::game_input::GameInputDevices;
 use crate::input::InputManager;
 use crate::library::Library;
 use crate::limits::ExecutionLimit;
 use crate::loader::{LoadBehavior, LoadManager};
 use crate::local_connection::LocalConnections;
-use crate::locale::get_current_date_time;
+use crate::locale::{get_current_date_time, get_mock_date_time};
+use crate::microphone::Microphones;
 use crate::net_connection::NetConnections;
+use crate::policy_file::UrlPolicyFiles;
 use crate::prelude::*;
+use crate::shader_job::ShaderJobs;
 use crate::socket::Sockets;
 use crate::streams::StreamManager;
 use crate::string::{AvmStringInterner, StringContext};
@@ -47,6 +54,7 @@ use crate::stub::StubCollection;
 use crate::tag_utils::SwfMovie;
 use crate::timer::Timers;
 use crate::vminterface::Instantiator;
+use crate::worker::{SharedWorker, Workers};
 use crate::DefaultFont;
 use gc_arena::lock::GcRefLock;
 use gc_arena::{Collect, DynamicRootSet, Mutation, Rootable};
@@ -185,11 +193,28 @@ struct GcRootData<'gc> {
 
     sockets: Sockets<'gc>,
 
+    datagram_sockets: DatagramSockets<'gc>,
+
     /// List of active NetConnection objects.
     net_connections: NetConnections<'gc>,
 
     local_connections: LocalConnections<'gc>,
 
+    /// Connected game controllers.
+    game_input: GameInputDevices<'gc>,
+
+    /// Microphones that are currently capturing.
+    microphones: Microphones<'gc>,
+
+    /// Cameras returned by `Camera.getCamera`.
+    cameras: Cameras<'gc>,
+
+    /// ActionScript workers and the channels between them.
+    workers: Workers<'gc>,
+
+    /// Shader jobs running asynchronously.
+    shader_jobs: ShaderJobs<'gc>,
+
     /// Dynamic root for allowing handles to GC objects to exist outside of the GC.
     dynamic_root: DynamicRootSet<'gc>,
 
@@ -229,8 +254,14 @@ impl<'gc> GcRootData<'gc> {
         &mut AudioManager<'gc>,
         &mut StreamManager<'gc>,
         &mut Sockets<'gc>,
+        &mut DatagramSockets<'gc>,
         &mut NetConnections<'gc>,
         &mut LocalConnections<'gc>,
+        &mut GameInputDevices<'gc>,
+        &mut Microphones<'gc>,
+        &mut Cameras<'gc>,
+        &mut Workers<'gc>,
+        &mut ShaderJobs<'gc>,
         &mut Vec<PostFrameCallback<'gc>>,
         &mut MouseData<'gc>,
         DynamicRootSet<'gc>,
@@ -253,8 +284,14 @@ impl<'gc> GcRootData<'gc> {
             &mut self.audio_manager,
             &mut self.stream_manager,
             &mut self.sockets,
+            &mut self.datagram_sockets,
             &mut self.net_connections,
             &mut self.local_connections,
+            &mut self.game_input,
+            &mut self.microphones,
+            &mut self.cameras,
+            &mut self.workers,
+            &mut self.shader_jobs,
             &mut self.post_frame_callbacks,
             &mut self.mouse_data,
             self.dynamic_root,
@@ -265,6 +302,8 @@ impl<'gc> GcRootData<'gc> {
 type GcArena = gc_arena::Arena<Rootable![GcRoot<'_>]>;
 
 type Audio = Box<dyn AudioBackend>;
+type AudioInput = Box<dyn AudioInputBackend>;
+type Camera = Box<dyn CameraBackend>;
 type Navigator = Box<dyn NavigatorBackend>;
 type Renderer = Box<dyn RenderBackend>;
 type Storage = Box<dyn StorageBackend>;
@@ -304,6 +343,8 @@ pub struct Player {
 
     renderer: Renderer,
     audio: Audio,
+    audio_input: AudioInput,
+    camera: Camera,
     navigator: Navigator,
     storage: Storage,
     log: Log,
@@ -324,6 +365,8 @@ pub struct Player {
 
     stub_tracker: StubCollection,
 
+    url_policy_files: UrlPolicyFiles,
+
     /// A time budget for executing frames.
     /// Gained by passage of time between host frames, spent by executing SWF frames.
     /// This is how we support custom SWF framerates
@@ -331,6 +374,9 @@ pub struct Player {
     frame_accumulator: f64,
     recent_run_frame_timings: VecDeque<f64>,
 
+    /// The number of times `run_frame` has been called since the player was created.
+    frames_run: u64,
+
     /// Faked time passage for fooling hand-written busy-loop FPS limiters.
     time_offset: u32,
 
@@ -356,6 +402,9 @@ pub struct Player {
     /// The instant at which the SWF was launched.
     start_time: Instant,
 
+    /// Whether the clock and the random number generator are deterministic.
+    deterministic: bool,
+
     /// The maximum amount of time that can be called before a `Error::ExecutionTimeout`
     /// is raised. This defaults to 15 seconds but can be changed.
     max_execution_duration: Duration,
@@ -509,6 +558,8 @@ impl Player {
             return;
         }
 
+        self.update_virtual_mouse(dt);
+
         self.frame_accumulator += dt;
         let frame_time = self.frame_time(1000.0);
 
@@ -555,16 +606,20 @@ impl Player {
         }
 
         // Adjust playback speed for next frame to stay in sync with timeline audio tracks ("stream" sounds).
-        let cur_frame_offset = self.frame_accumulator;
-        self.frame_accumulator += self.mutate_with_update_context(|context| {
-            context
-                .audio_manager
-                .audio_skew_time(context.audio, cur_frame_offset)
-                * 1000.0
-        });
+        // A deterministic player can't depend on the timing of the audio device.
+        if !self.deterministic {
+            let cur_frame_offset = self.frame_accumulator;
+            self.frame_accumulator += self.mutate_with_update_context(|context| {
+                context
+                    .audio_manager
+                    .audio_skew_time(context.audio, cur_frame_offset)
+                    * 1000.0
+            });
+        }
 
         self.update_sockets();
         self.update_net_connections();
+        self.update_workers();
         self.update_timers(dt);
         self.update(|context| {
             StreamManager::tick(context, dt);
@@ -929,6 +984,21 @@ impl Player {
         self.mutate_with_update_context(|context| context.stage.movie_size().1)
     }
 
+    /// Converts a position in the viewport to a position on the stage, both in pixels.
+    pub fn viewport_to_stage_position(&mut self, x: f64, y: f64) -> (f64, f64) {
+        let inverse_view_matrix =
+            self.mutate_with_update_context(|context| context.stage.inverse_view_matrix());
+        let position = inverse_view_matrix * Point::from_pixels(x, y);
+        (position.x.to_pixels(), position.y.to_pixels())
+    }
+
+    /// Converts a position on the stage to a position in the viewport, both in pixels.
+    pub fn stage_to_viewport_position(&mut self, x: f64, y: f64) -> (f64, f64) {
+        let view_matrix = self.mutate_with_update_context(|context| context.stage.view_matrix());
+        let position = view_matrix * Point::from_pixels(x, y);
+        (position.x.to_pixels(), position.y.to_pixels())
+    }
+
     pub fn viewport_dimensions(&mut self) -> ViewportDimensions {
         self.mutate_with_update_context(|context| context.renderer.viewport_dimensions())
     }
@@ -1006,13 +1076,49 @@ impl Player {
             | PlayerEvent::MouseDown { .. }
             | PlayerEvent::MouseLeave
             | PlayerEvent::MouseWheel { .. }
-            | PlayerEvent::GamepadButtonDown { .. }
-            | PlayerEvent::GamepadButtonUp { .. }
             | PlayerEvent::TextInput { .. }
             | PlayerEvent::TextControl { .. } => self.handle_input_event(event),
+            PlayerEvent::GamepadButtonDown { .. }
+            | PlayerEvent::GamepadButtonUp { .. }
+            | PlayerEvent::GamepadAxisMotion { .. } => {
+                self.handle_game_input_event(event);
+
+                // Transform gamepad events into the key and mouse events they're mapped to.
+                let mut handled = false;
+                for event in self.input.map_gamepad_event(event) {
+                    handled |= self.handle_input_event(event);
+                }
+                handled
+            }
+            PlayerEvent::GamepadConnected { .. } | PlayerEvent::GamepadDisconnected { .. } => {
+                self.handle_game_input_event(event);
+                false
+            }
         }
     }
 
+    /// Move the virtual mouse cursor controlled by a gamepad stick, if any.
+    fn update_virtual_mouse(&mut self, dt: f64) {
+        if !self.input.has_virtual_mouse() {
+            return;
+        }
+
+        let dimensions = self.viewport_dimensions();
+        if let Some(event) =
+            self.input
+                .update_virtual_mouse(dt, dimensions.width.into(), dimensions.height.into())
+        {
+            self.handle_input_event(event);
+        }
+    }
+
+    /// Update the devices exposed through `flash.ui.GameInput`.
+    fn handle_game_input_event(&mut self, event: PlayerEvent) {
+        self.mutate_with_update_context(|context| {
+            GameInputDevices::handle_event(context, event);
+        });
+    }
+
     fn handle_focus_event(&mut self, event: PlayerEvent) -> bool {
         if let PlayerEvent::FocusLost = event {
             self.mutate_with_update_context(|context| {
@@ -1027,7 +1133,6 @@ impl Player {
     /// Input event handling is a complicated affair, involving several different
     /// concerns that need to resolve with specific priority.
     ///
-    /// 0. Transform gamepad button events into key events.
     /// 1. (In `avm_debug` builds)
     ///    If Ctrl-Alt-V is pressed, dump all AVM1 variables in the player.
     ///    If Ctrl-Alt-D is pressed, toggle debug output for AVM1 and AVM2.
@@ -1048,9 +1153,6 @@ impl Player {
     ///    second wave of event processing.
     fn handle_input_event(&mut self, event: PlayerEvent) -> bool {
         let mut player_event_handled = false;
-        let Some(event) = self.input.map_input_event(event) else {
-            return false;
-        };
 
         let prev_mouse_buttons = self.input.get_mouse_down_buttons();
         self.input.handle_event(&event);
@@ -1903,6 +2005,7 @@ impl Player {
 
     #[instrument(level = "debug", skip_all)]
     pub fn run_frame(&mut self) {
+        self.frames_run += 1;
         let frame_time = self.frame_time(750_000_000.0);
         let frame_time = Duration::from_nanos(frame_time as u64);
         let (mut execution_limit, may_execute_while_streaming) = match self.load_behavior {
@@ -1923,11 +2026,17 @@ impl Player {
         }
 
         self.update(|context| {
+            // Jobs started since the last frame complete before this frame's scripts run.
+            ShaderJobs::update(context);
+
             // TODO: Is this order correct?
             run_all_phases_avm2(context);
             Avm1::run_frame(context);
             AudioManager::update_sounds(context);
             LocalConnections::update_connections(context);
+            GameInputDevices::announce_devices(context);
+            Microphones::update(context);
+            Cameras::update(context);
 
             // Only run the current list of callbacks - any callbacks added during callback execution
             // will be run at the end of the *next* frame.
@@ -2008,6 +2117,14 @@ impl Player {
         &mut self.audio
     }
 
+    pub fn audio_input_mut(&mut self) -> &mut AudioInput {
+        &mut self.audio_input
+    }
+
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        &mut self.camera
+    }
+
     pub fn navigator(&self) -> &Navigator {
         &self.navigator
     }
@@ -2021,6 +2138,11 @@ impl Player {
         self.frame_rate
     }
 
+    /// The number of frames that have been run since the player was created.
+    pub fn frames_run(&self) -> u64 {
+        self.frames_run
+    }
+
     pub fn renderer(&self) -> &Renderer {
         &self.renderer
     }
@@ -2170,8 +2292,14 @@ impl Player {
                 audio_manager,
                 stream_manager,
                 sockets,
+                datagram_sockets,
                 net_connections,
                 local_connections,
+                game_input,
+                microphones,
+                cameras,
+                workers,
+                shader_jobs,
                 post_frame_callbacks,
                 mouse_data,
                 dynamic_root,
@@ -2184,6 +2312,8 @@ impl Player {
                 rng: &mut this.rng,
                 renderer: this.renderer.deref_mut(),
                 audio: this.audio.deref_mut(),
+                audio_input: this.audio_input.deref_mut(),
+                camera: this.camera.deref_mut(),
                 navigator: this.navigator.deref_mut(),
                 ui: this.ui.deref_mut(),
                 action_queue,
@@ -2212,6 +2342,7 @@ impl Player {
                 avm2,
                 external_interface,
                 start_time: this.start_time,
+                deterministic: this.deterministic,
                 update_start: Instant::now(),
                 max_execution_duration: this.max_execution_duration,
                 focus_tracker: stage.focus_tracker(),
@@ -2223,10 +2354,17 @@ impl Player {
                 actions_since_timeout_check: &mut this.actions_since_timeout_check,
                 frame_phase: &mut this.frame_phase,
                 stub_tracker: &mut this.stub_tracker,
+                url_policy_files: &mut this.url_policy_files,
                 stream_manager,
                 sockets,
+                datagram_sockets,
                 net_connections,
                 local_connections,
+                game_input,
+                microphones,
+                cameras,
+                workers,
+                shader_jobs,
                 dynamic_root,
                 post_frame_callbacks,
             };
@@ -2339,6 +2477,7 @@ impl Player {
     pub fn update_sockets(&mut self) {
         self.mutate_with_update_context(|context| {
             Sockets::update_sockets(context);
+            DatagramSockets::update_sockets(context);
         })
     }
 
@@ -2349,6 +2488,13 @@ impl Player {
         })
     }
 
+    /// Deliver events between ActionScript workers.
+    pub fn update_workers(&mut self) {
+        self.mutate_with_update_context(|context| {
+            Workers::update_workers(context);
+        })
+    }
+
     /// Returns whether this player consumes mouse wheel events.
     /// Used by web to prevent scrolling.
     pub fn should_prevent_scrolling(&mut self) -> bool {
@@ -2428,6 +2574,8 @@ pub struct PlayerBuilder {
 
     // Backends
     audio: Option<Audio>,
+    audio_input: Option<AudioInput>,
+    camera: Option<Camera>,
     log: Option<Log>,
     navigator: Option<Navigator>,
     renderer: Option<Renderer>,
@@ -2452,6 +2600,7 @@ pub struct PlayerBuilder {
     spoofed_url: Option<String>,
     compatibility_rules: CompatibilityRules,
     gamepad_button_mapping: HashMap<GamepadButton, KeyCode>,
+    gamepad_stick_mapping: HashMap<GamepadStick, GamepadStickMapping>,
     player_version: Option<u8>,
     player_runtime: PlayerRuntime,
     quality: StageQuality,
@@ -2462,6 +2611,10 @@ pub struct PlayerBuilder {
     #[cfg(feature = "known_stubs")]
     stub_report_output: Option<std::path::PathBuf>,
     avm2_optimizer_enabled: bool,
+    deterministic: bool,
+    socket_policy_files: bool,
+    url_policy_files: bool,
+    worker: Option<Arc<SharedWorker>>,
 }
 
 impl PlayerBuilder {
@@ -2475,6 +2628,8 @@ impl PlayerBuilder {
             movie: None,
 
             audio: None,
+            audio_input: None,
+            camera: None,
             log: None,
             navigator: None,
             renderer: None,
@@ -2503,6 +2658,7 @@ impl PlayerBuilder {
             spoofed_url: None,
             compatibility_rules: CompatibilityRules::default(),
             gamepad_button_mapping: HashMap::new(),
+            gamepad_stick_mapping: HashMap::new(),
             player_version: None,
             player_runtime: PlayerRuntime::default(),
             quality: StageQuality::High,
@@ -2513,6 +2669,10 @@ impl PlayerBuilder {
             #[cfg(feature = "known_stubs")]
             stub_report_output: None,
             avm2_optimizer_enabled: true,
+            deterministic: false,
+            socket_policy_files: false,
+            url_policy_files: false,
+            worker: None,
         }
     }
 
@@ -2530,6 +2690,20 @@ impl PlayerBuilder {
         self
     }
 
+    /// Sets the audio input backend of the player, used for microphones.
+    #[inline]
+    pub fn with_audio_input(mut self, audio_input: impl 'static + AudioInputBackend) -> Self {
+        self.audio_input = Some(Box::new(audio_input));
+        self
+    }
+
+    /// Sets the camera backend of the player.
+    #[inline]
+    pub fn with_camera(mut self, camera: impl 'static + CameraBackend) -> Self {
+        self.camera = Some(Box::new(camera));
+        self
+    }
+
     /// Sets the audio backend of the player.
     #[inline]
     pub fn with_boxed_audio(mut self, audio: Box<dyn AudioBackend>) -> Self {
@@ -2708,6 +2882,14 @@ impl PlayerBuilder {
         self
     }
 
+    pub fn with_gamepad_stick_mapping(
+        mut self,
+        mapping: HashMap<GamepadStick, GamepadStickMapping>,
+    ) -> Self {
+        self.gamepad_stick_mapping = mapping;
+        self
+    }
+
     #[cfg(feature = "known_stubs")]
     /// Sets the output path for the stub report. When set, the player
     /// will write the report to this path and exit the process.
@@ -2721,6 +2903,36 @@ impl PlayerBuilder {
         self
     }
 
+    /// Makes the player deterministic, like in tests: `getTimer` and `Date` follow the time
+    /// given to the timers instead of the system clock, and random numbers use a fixed seed.
+    pub fn with_deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    /// Requires sockets to be allowed by a socket policy file before connecting, like in
+    /// Flash Player. The policy file is requested from port 843, then from the servers given
+    /// to `Security.loadPolicyFile`, and finally from the destination port itself.
+    pub fn with_socket_policy_files(mut self, socket_policy_files: bool) -> Self {
+        self.socket_policy_files = socket_policy_files;
+        self
+    }
+
+    /// Requires cross-domain URL loads to be allowed by a policy file (`crossdomain.xml`),
+    /// like in Flash Player. Loads that aren't allowed fail with a security error, and
+    /// images loaded without `LoaderContext.checkPolicyFile` can't be drawn.
+    pub fn with_url_policy_files(mut self, url_policy_files: bool) -> Self {
+        self.url_policy_files = url_policy_files;
+        self
+    }
+
+    /// Runs the movie as a background worker, rather than as the primordial worker.
+    #[inline]
+    pub(crate) fn with_worker(mut self, worker: Arc<SharedWorker>) -> Self {
+        self.worker = Some(worker);
+        self
+    }
+
     fn create_gc_root<'gc>(
         gc_context: &'gc Mutation<'gc>,
         player_version: u8,
@@ -2769,8 +2981,14 @@ impl PlayerBuilder {
             unbound_text_fields: Vec::new(),
             stream_manager: StreamManager::new(),
             sockets: Sockets::empty(),
+            datagram_sockets: DatagramSockets::empty(),
             net_connections: NetConnections::default(),
             local_connections: LocalConnections::empty(),
+            game_input: GameInputDevices::default(),
+            microphones: Microphones::default(),
+            cameras: Cameras::default(),
+            workers: Workers::default(),
+            shader_jobs: ShaderJobs::default(),
             dynamic_root: DynamicRootSet::new(gc_context),
             post_frame_callbacks: Vec::new(),
         };
@@ -2810,6 +3028,12 @@ impl PlayerBuilder {
         let video = self
             .video
             .unwrap_or_else(|| Box::new(null::NullVideoBackend::new()));
+        let audio_input = self
+            .audio_input
+            .unwrap_or_else(|| Box::new(NullAudioInputBackend::new()));
+        let camera = self
+            .camera
+            .unwrap_or_else(|| Box::new(NullCameraBackend::new()));
 
         let player_version = self.player_version.unwrap_or(NEWEST_PLAYER_VERSION);
 
@@ -2821,6 +3045,8 @@ impl PlayerBuilder {
             Mutex::new(Player {
                 // Backends
                 audio,
+                audio_input,
+                camera,
                 log,
                 navigator,
                 renderer,
@@ -2838,21 +3064,27 @@ impl PlayerBuilder {
                 frame_phase: Default::default(),
                 frame_accumulator: 0.0,
                 recent_run_frame_timings: VecDeque::with_capacity(10),
+                frames_run: 0,
                 start_time: Instant::now(),
+                deterministic: self.deterministic,
                 time_offset: 0,
                 time_til_next_timer: None,
                 max_execution_duration: self.max_execution_duration,
                 actions_since_timeout_check: 0,
 
                 // Input
-                input: InputManager::new(self.gamepad_button_mapping),
+                input: InputManager::new(self.gamepad_button_mapping, self.gamepad_stick_mapping),
                 mouse_in_stage: true,
                 mouse_position: Point::ZERO,
                 mouse_cursor: MouseCursor::Arrow,
                 mouse_cursor_needs_check: false,
 
                 // Misc. state
-                rng: SmallRng::seed_from_u64(get_current_date_time().timestamp_millis() as u64),
+                rng: SmallRng::seed_from_u64(if self.deterministic {
+                    get_mock_date_time().timestamp_millis() as u64
+                } else {
+                    get_current_date_time().timestamp_millis() as u64
+                }),
                 system: SystemProperties::new(),
                 page_url: self.page_url.clone(),
                 transform_stack: TransformStack::new(),
@@ -2870,6 +3102,7 @@ impl PlayerBuilder {
                 spoofed_url: self.spoofed_url.clone(),
                 compatibility_rules: self.compatibility_rules.clone(),
                 stub_tracker: StubCollection::new(),
+                url_policy_files: UrlPolicyFiles::default(),
                 #[cfg(feature = "egui")]
                 debug_ui: Default::default(),
 
@@ -2935,6 +3168,14 @@ impl PlayerBuilder {
                 .set_optimizer_enabled(self.avm2_optimizer_enabled);
             Avm2::load_player_globals(context).expect("Unable to load AVM2 globals");
 
+            context
+                .sockets
+                .set_check_policy_files(self.socket_policy_files);
+            context.url_policy_files.set_enabled(self.url_policy_files);
+            if let Some(worker) = self.worker.clone() {
+                *context.workers = Workers::new(worker);
+            }
+
             let stage = context.stage;
             stage.set_align(context, self.align);
             stage.set_forced_align(context, self.forced_align);
@@ -0,0 +1,443 @@
+//! Cross-domain policy files, see
+//! https://www.adobe.com/devnet-docs/acrobatetk/tools/AppSec/CrossDomain_PolicyFile_Specification.pdf
+
+use crate::sandbox::SandboxType;
+use crate::tag_utils::SwfMovie;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::HashMap;
+use thiserror::Error;
+use url::Url;
+
+/// The port Flash asks for the socket master policy file.
+pub const SOCKET_MASTER_POLICY_PORT: u16 = 843;
+
+/// The request sent to a socket policy server, terminated by a null byte.
+pub const SOCKET_POLICY_FILE_REQUEST: &[u8] = b"<policy-file-request/>\0";
+
+/// The path of the master policy file of an HTTP server.
+pub const URL_MASTER_POLICY_PATH: &str = "/crossdomain.xml";
+
+#[derive(Error, Debug)]
+pub enum PolicyFileError {
+    #[error("Couldn't parse policy file: {0}")]
+    Xml(#[from] quick_xml::Error),
+
+    #[error("Couldn't parse policy file attribute: {0}")]
+    Attribute(#[from] quick_xml::events::attributes::AttrError),
+
+    #[error("Root element is not <cross-domain-policy>")]
+    InvalidRoot,
+}
+
+/// The meta-policy of a host, set by `<site-control>` in its master policy file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetaPolicy {
+    /// No policy files are allowed on this host, not even the master one.
+    None,
+
+    /// Only the master policy file is allowed.
+    MasterOnly,
+
+    /// Policy files are allowed anywhere on this host.
+    All,
+}
+
+impl MetaPolicy {
+    fn parse(value: &str) -> Self {
+        match value {
+            "none" => Self::None,
+            "master-only" => Self::MasterOnly,
+            // `by-content-type` and `by-ftp-filename` only restrict HTTP and FTP policy files.
+            _ => Self::All,
+        }
+    }
+}
+
+/// A range of ports, as listed in the `to-ports` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PortRange {
+    start: u16,
+    end: u16,
+}
+
+impl PortRange {
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value == "*" {
+            return Some(Self {
+                start: 0,
+                end: u16::MAX,
+            });
+        }
+
+        match value.split_once('-') {
+            Some((start, end)) => Some(Self {
+                start: start.trim().parse().ok()?,
+                end: end.trim().parse().ok()?,
+            }),
+            None => {
+                let port = value.parse().ok()?;
+                Some(Self {
+                    start: port,
+                    end: port,
+                })
+            }
+        }
+    }
+
+    fn contains(&self, port: u16) -> bool {
+        (self.start..=self.end).contains(&port)
+    }
+}
+
+/// A single `<allow-access-from>` rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AllowAccessFrom {
+    domain: String,
+
+    /// The ports a socket may connect to. Socket policies without `to-ports` grant nothing.
+    to_ports: Option<Vec<PortRange>>,
+}
+
+/// A parsed `<cross-domain-policy>` document.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PolicyFile {
+    allow_access_from: Vec<AllowAccessFrom>,
+    meta_policy: Option<MetaPolicy>,
+}
+
+impl PolicyFile {
+    pub fn parse(data: &[u8]) -> Result<Self, PolicyFileError> {
+        // Socket policy servers terminate the document with a null byte.
+        let data = data.split(|&b| b == 0).next().unwrap_or_default();
+
+        let mut reader = Reader::from_reader(data);
+        reader.config_mut().trim_text(true);
+
+        let mut policy = PolicyFile::default();
+        let mut depth = 0;
+        loop {
+            match reader.read_event()? {
+                Event::Start(e) => {
+                    if depth == 0 && e.name().as_ref() != b"cross-domain-policy" {
+                        return Err(PolicyFileError::InvalidRoot);
+                    } else if depth == 1 {
+                        policy.parse_rule(&e)?;
+                    }
+                    depth += 1;
+                }
+                Event::Empty(e) => {
+                    if depth == 0 {
+                        // An empty `<cross-domain-policy/>` grants nothing.
+                        if e.name().as_ref() != b"cross-domain-policy" {
+                            return Err(PolicyFileError::InvalidRoot);
+                        }
+                    } else if depth == 1 {
+                        policy.parse_rule(&e)?;
+                    }
+                }
+                Event::End(_) => depth -= 1,
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        Ok(policy)
+    }
+
+    fn parse_rule(&mut self, element: &BytesStart) -> Result<(), PolicyFileError> {
+        let attribute = |name: &[u8]| -> Result<Option<String>, PolicyFileError> {
+            match element.try_get_attribute(name)? {
+                Some(attribute) => Ok(Some(attribute.unescape_value()?.into_owned())),
+                None => Ok(None),
+            }
+        };
+
+        match element.name().as_ref() {
+            b"allow-access-from" => {
+                let Some(domain) = attribute(b"domain")? else {
+                    return Ok(());
+                };
+                let to_ports = attribute(b"to-ports")?.map(|ports| {
+                    ports
+                        .split(',')
+                        .filter_map(PortRange::parse)
+                        .collect::<Vec<_>>()
+                });
+                self.allow_access_from.push(AllowAccessFrom {
+                    domain: domain.trim().to_ascii_lowercase(),
+                    to_ports,
+                });
+            }
+            b"site-control" => {
+                if let Some(value) = attribute(b"permitted-cross-domain-policies")? {
+                    self.meta_policy = Some(MetaPolicy::parse(value.trim()));
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// The meta-policy set by `<site-control>`, if any.
+    ///
+    /// When it's missing, HTTP servers default to `master-only` since Flash Player 10,
+    /// while socket policy servers default to `all`.
+    pub fn meta_policy(&self) -> Option<MetaPolicy> {
+        self.meta_policy
+    }
+
+    /// Returns whether a movie from the given domain may open a socket to the given port.
+    ///
+    /// Movies without a domain (such as local files) are only granted access by `domain="*"`.
+    pub fn allows_socket(&self, domain: Option<&str>, port: u16) -> bool {
+        self.allow_access_from.iter().any(|rule| {
+            domain_matches(&rule.domain, domain)
+                && rule
+                    .to_ports
+                    .as_ref()
+                    .is_some_and(|ports| ports.iter().any(|range| range.contains(port)))
+        })
+    }
+
+    /// Returns whether a movie from the given domain may load data from the server
+    /// serving this policy file.
+    ///
+    /// Unlike socket policies, `to-ports` is ignored here.
+    pub fn allows_url(&self, domain: Option<&str>) -> bool {
+        self.allow_access_from
+            .iter()
+            .any(|rule| domain_matches(&rule.domain, domain))
+    }
+}
+
+/// Why a movie isn't allowed to access data loaded from another domain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyDenial {
+    /// The data was loaded without `checkPolicyFile`, so no policy file was consulted.
+    NotChecked,
+
+    /// No policy file grants access to the movie's domain.
+    NotGranted,
+}
+
+/// Marks data loaded from another domain that the loading movie isn't allowed to access.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Taint {
+    /// The URL the data was loaded from.
+    pub url: String,
+
+    pub denial: PolicyDenial,
+}
+
+/// Returns the domain of a movie, as matched against `allow-access-from` rules.
+pub fn movie_domain(movie: &SwfMovie) -> Option<String> {
+    Url::parse(movie.url())
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_owned))
+        .filter(|domain| !domain.is_empty())
+}
+
+/// The URL policy files (`crossdomain.xml`) known to a player.
+///
+/// Policy files are fetched by the loader the first time they're needed, and cached for
+/// the lifetime of the player, like in Flash Player.
+#[derive(Default)]
+pub struct UrlPolicyFiles {
+    /// Whether cross-domain loads have to be allowed by a policy file.
+    enabled: bool,
+
+    /// Policy files registered with `Security.loadPolicyFile`, by origin.
+    registered: HashMap<String, Vec<Url>>,
+
+    /// Policy files fetched so far, or `None` if they were missing or invalid.
+    cache: HashMap<Url, Option<PolicyFile>>,
+}
+
+impl UrlPolicyFiles {
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Handles a `Security.loadPolicyFile` call, registering a policy file to consult
+    /// for loads from its origin.
+    ///
+    /// Returns `false` if the URL doesn't point to an HTTP policy file.
+    pub fn load_policy_file(&mut self, url: &str) -> bool {
+        let Ok(url) = Url::parse(url) else {
+            return false;
+        };
+        if !matches!(url.scheme(), "http" | "https") {
+            return false;
+        }
+
+        let urls = self
+            .registered
+            .entry(url.origin().ascii_serialization())
+            .or_default();
+        if !urls.contains(&url) {
+            urls.push(url);
+        }
+        true
+    }
+
+    /// Returns whether loading `url` from the given movie has to be allowed by a policy file.
+    pub fn needs_policy_file(&self, movie: &SwfMovie, url: &Url) -> bool {
+        if !self.enabled || !matches!(url.scheme(), "http" | "https") {
+            return false;
+        }
+
+        // Trusted local movies and AIR applications aren't restricted by policy files.
+        if matches!(
+            movie.sandbox_type(),
+            SandboxType::LocalTrusted | SandboxType::Application
+        ) {
+            return false;
+        }
+
+        Url::parse(movie.url()).map_or(true, |movie_url| movie_url.origin() != url.origin())
+    }
+
+    /// Returns the policy files that may allow loading `url`, in the order they are consulted.
+    ///
+    /// The master policy file always comes first, as its meta-policy decides whether
+    /// the other ones are considered at all.
+    pub fn policy_file_urls(&self, url: &Url) -> Vec<Url> {
+        let Ok(master) = url.join(URL_MASTER_POLICY_PATH) else {
+            return vec![];
+        };
+
+        let mut urls = vec![master];
+        if let Some(registered) = self.registered.get(&url.origin().ascii_serialization()) {
+            for policy_url in registered {
+                // A policy file only grants access to its own directory and the ones below it.
+                let directory = policy_url
+                    .path()
+                    .rsplit_once('/')
+                    .map_or("", |(directory, _)| directory);
+                if url.path().starts_with(&format!("{directory}/")) && !urls.contains(policy_url) {
+                    urls.push(policy_url.clone());
+                }
+            }
+        }
+        urls
+    }
+
+    /// Returns the cached policy file at the given URL, if it was fetched already.
+    pub fn get(&self, url: &Url) -> Option<&Option<PolicyFile>> {
+        self.cache.get(url)
+    }
+
+    pub fn insert(&mut self, url: Url, policy: Option<PolicyFile>) {
+        self.cache.insert(url, policy);
+    }
+}
+
+/// Returns whether a `domain` attribute matches the domain of a movie.
+fn domain_matches(pattern: &str, domain: Option<&str>) -> bool {
+    if pattern == "*" {
+        return true;
+    }
+
+    let Some(domain) = domain else {
+        return false;
+    };
+    let domain = domain.to_ascii_lowercase();
+
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => {
+            domain == suffix
+                || domain
+                    .strip_suffix(suffix)
+                    .is_some_and(|subdomain| subdomain.ends_with('.'))
+        }
+        None => domain == pattern,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_socket_policy() {
+        let policy = PolicyFile::parse(
+            b"<?xml version=\"1.0\"?>
+            <!DOCTYPE cross-domain-policy SYSTEM \"/xml/dtds/cross-domain-policy.dtd\">
+            <cross-domain-policy>
+                <site-control permitted-cross-domain-policies=\"master-only\"/>
+                <allow-access-from domain=\"*.example.com\" to-ports=\"507,516-523\"/>
+                <allow-access-from domain=\"10.0.0.1\" to-ports=\"*\"/>
+                <allow-access-from domain=\"nowhere.com\"/>
+            </cross-domain-policy>\0",
+        )
+        .unwrap();
+
+        assert_eq!(policy.meta_policy(), Some(MetaPolicy::MasterOnly));
+        assert!(policy.allows_socket(Some("example.com"), 507));
+        assert!(policy.allows_socket(Some("www.Example.com"), 520));
+        assert!(!policy.allows_socket(Some("www.example.com"), 524));
+        assert!(!policy.allows_socket(Some("badexample.com"), 507));
+        assert!(policy.allows_socket(Some("10.0.0.1"), 12345));
+        assert!(!policy.allows_socket(Some("nowhere.com"), 507));
+        assert!(!policy.allows_socket(None, 507));
+    }
+
+    #[test]
+    fn parse_wildcard_and_invalid_policies() {
+        let policy = PolicyFile::parse(
+            b"<cross-domain-policy><allow-access-from domain=\"*\" to-ports=\"1000-2000\" /></cross-domain-policy>",
+        )
+        .unwrap();
+        assert!(policy.allows_socket(None, 1500));
+        assert!(!policy.allows_socket(None, 843));
+
+        let policy = PolicyFile::parse(b"<cross-domain-policy/>").unwrap();
+        assert!(!policy.allows_socket(Some("example.com"), 80));
+        assert_eq!(policy.meta_policy(), None);
+
+        assert!(PolicyFile::parse(b"<html><body>Not found</body></html>").is_err());
+    }
+
+    #[test]
+    fn url_policies() {
+        let policy = PolicyFile::parse(
+            b"<cross-domain-policy>
+                <allow-access-from domain=\"*.example.com\"/>
+                <allow-access-from domain=\"sockets.com\" to-ports=\"507\"/>
+            </cross-domain-policy>",
+        )
+        .unwrap();
+        assert!(policy.allows_url(Some("www.example.com")));
+        assert!(policy.allows_url(Some("sockets.com")));
+        assert!(!policy.allows_url(Some("other.com")));
+        assert!(!policy.allows_url(None));
+
+        let mut policy_files = UrlPolicyFiles::default();
+        let movie = SwfMovie::from_loaded_image("http://example.com/movie.swf".to_string(), 0);
+        let same_origin = Url::parse("http://example.com/image.png").unwrap();
+        let cross_domain = Url::parse("http://cdn.com/images/image.png").unwrap();
+        assert!(!policy_files.needs_policy_file(&movie, &cross_domain));
+
+        policy_files.set_enabled(true);
+        assert!(!policy_files.needs_policy_file(&movie, &same_origin));
+        assert!(policy_files.needs_policy_file(&movie, &cross_domain));
+
+        assert!(policy_files.load_policy_file("http://cdn.com/images/policy.xml"));
+        assert!(policy_files.load_policy_file("http://cdn.com/other/policy.xml"));
+        assert!(!policy_files.load_policy_file("xmlsocket://cdn.com:843"));
+        assert_eq!(
+            policy_files.policy_file_urls(&cross_domain),
+            vec![
+                Url::parse("http://cdn.com/crossdomain.xml").unwrap(),
+                Url::parse("http://cdn.com/images/policy.xml").unwrap(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,137 @@
+//! Shader jobs started with `ShaderJob.start(false)`, which complete on a later frame.
+
+use crate::avm2::globals::flash::display::shader_job::write_output;
+use crate::avm2::globals::slots::flash_display_shader_job as shader_job_slots;
+use crate::avm2::{Activation, Avm2, EventObject, Object as Avm2Object, TObject, Value};
+use crate::bitmap::bitmap_data::copy_pixels_to_bitmapdata;
+use crate::context::UpdateContext;
+use crate::string::AvmString;
+use gc_arena::Collect;
+use ruffle_render::backend::PixelBenderOutput;
+use ruffle_render::bitmap::PixelRegion;
+
+#[derive(Collect)]
+#[collect(no_drop)]
+struct PendingShaderJob<'gc> {
+    /// The `flash.display.ShaderJob` instance.
+    job: Avm2Object<'gc>,
+
+    /// The `BitmapData`, `ByteArray` or `Vector.<Number>` that receives the result.
+    target: Avm2Object<'gc>,
+
+    /// The output of the shader, which isn't written to the target until the job completes.
+    ///
+    /// Bitmap targets are rendered to a separate texture, whose sync handle is resolved
+    /// once the job completes.
+    #[collect(require_static)]
+    output: PixelBenderOutput,
+}
+
+/// All shader jobs that have been started asynchronously and haven't completed yet.
+#[derive(Collect, Default)]
+#[collect(no_drop)]
+pub struct ShaderJobs<'gc> {
+    pending: Vec<PendingShaderJob<'gc>>,
+}
+
+impl<'gc> ShaderJobs<'gc> {
+    /// Queues the output of a shader job, which completes on the next frame.
+    pub fn start(
+        activation: &mut Activation<'_, 'gc>,
+        job: Avm2Object<'gc>,
+        target: Avm2Object<'gc>,
+        output: PixelBenderOutput,
+    ) {
+        activation
+            .context
+            .shader_jobs
+            .pending
+            .push(PendingShaderJob {
+                job,
+                target,
+                output,
+            });
+    }
+
+    /// Cancels a running shader job, discarding its result. Does nothing if the job isn't running.
+    pub fn cancel(activation: &mut Activation<'_, 'gc>, job: Avm2Object<'gc>) {
+        let pending = &mut activation.context.shader_jobs.pending;
+        pending.retain(|p| !Avm2Object::ptr_eq(p.job, job));
+    }
+
+    /// Completes every pending job, writing its result and dispatching a `complete` event.
+    pub fn update(context: &mut UpdateContext<'gc>) {
+        if context.shader_jobs.pending.is_empty() {
+            return;
+        }
+
+        // Jobs started by `complete` handlers complete on the next frame.
+        let pending = std::mem::take(&mut context.shader_jobs.pending);
+        let mut activation = Activation::from_nothing(context);
+        for PendingShaderJob {
+            job,
+            target,
+            output,
+        } in pending
+        {
+            write_job_output(&mut activation, target, output);
+            job.set_slot_no_coerce(shader_job_slots::_PROGRESS, 1.into(), activation.gc());
+
+            let (bitmap, byte_array, vector) = if target.as_bitmap_data().is_some() {
+                (target.into(), Value::Null, Value::Null)
+            } else if target.as_bytearray().is_some() {
+                (Value::Null, target.into(), Value::Null)
+            } else {
+                (Value::Null, Value::Null, target.into())
+            };
+            let event_class = activation.avm2().classes().shaderevent;
+            let event_type = AvmString::new_utf8(activation.gc(), "complete");
+            let event = EventObject::from_class_and_args(
+                &mut activation,
+                event_class,
+                &[
+                    event_type.into(),
+                    false.into(),
+                    false.into(),
+                    bitmap,
+                    byte_array,
+                    vector,
+                ],
+            );
+            Avm2::dispatch_event(activation.context, event, job);
+        }
+    }
+}
+
+/// Writes the output of a completed job to its target.
+///
+/// If the output of a bitmap target can't be read back, the target is left untouched,
+/// but the job still completes so that scripts waiting for it don't hang.
+fn write_job_output<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    target: Avm2Object<'gc>,
+    output: PixelBenderOutput,
+) {
+    let (sync_handle, bitmap) = match (output, target.as_bitmap_data()) {
+        (PixelBenderOutput::Bitmap(sync_handle), Some(bitmap)) => (sync_handle, bitmap),
+        (output, _) => {
+            write_output(activation, target, output);
+            return;
+        }
+    };
+
+    let bitmap = bitmap.sync(activation.context.renderer);
+    let mut write = bitmap.write(activation.gc());
+    // The target may have been disposed while the job was running.
+    let region = PixelRegion::for_whole_size(write.width(), write.height());
+    let result = activation.context.renderer.resolve_sync_handle(
+        sync_handle,
+        Box::new(|buffer, buffer_width| {
+            copy_pixels_to_bitmapdata(&mut write, buffer, buffer_width, region)
+        }),
+    );
+    match result {
+        Ok(()) => write.set_cpu_dirty(activation.gc(), region),
+        Err(e) => tracing::error!("ShaderJob: Failed to read the shader output: {e}"),
+    }
+}
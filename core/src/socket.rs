@@ -6,7 +6,12 @@ use crate::avm2::object::{EventObject, SocketObject};
 use crate::avm2::{Activation as Avm2Activation, Avm2};
 use crate::backend::navigator::NavigatorBackend;
 use crate::context::UpdateContext;
+use crate::policy_file::{
+    movie_domain, MetaPolicy, PolicyFile, SOCKET_MASTER_POLICY_PORT, SOCKET_POLICY_FILE_REQUEST,
+};
+use crate::sandbox::SandboxType;
 use crate::string::AvmString;
+use crate::tag_utils::SwfMovie;
 
 use async_channel::{unbounded, Receiver, Sender as AsyncSender, Sender};
 use gc_arena::Collect;
@@ -14,8 +19,13 @@ use ruffle_macros::istr;
 use slotmap::{new_key_type, SlotMap};
 use std::{
     cell::{Cell, RefCell},
+    collections::{HashMap, HashSet, VecDeque},
     time::Duration,
 };
+use url::Url;
+
+/// How long to wait for a policy server to answer, before asking the next one.
+const POLICY_FILE_TIMEOUT: Duration = Duration::from_secs(3);
 
 new_key_type! {
     pub struct SocketHandle;
@@ -28,6 +38,17 @@ enum SocketKind<'gc> {
     Avm1(Avm1Object<'gc>),
 }
 
+impl SocketKind<'_> {
+    fn set_handle(self, handle: SocketHandle) -> Option<SocketHandle> {
+        match self {
+            SocketKind::Avm2(target) => target.set_handle(handle),
+            SocketKind::Avm1(target) => {
+                XmlSocket::cast(target.into()).and_then(|target| target.set_handle(handle))
+            }
+        }
+    }
+}
+
 #[derive(Collect)]
 #[collect(no_drop)]
 struct Socket<'gc> {
@@ -46,6 +67,97 @@ impl<'gc> Socket<'gc> {
     }
 }
 
+/// A connection that waits for a socket policy file to allow it.
+struct PolicyCheck {
+    /// The domain of the movie opening the socket, if it has one.
+    domain: Option<String>,
+
+    host: String,
+    port: u16,
+    timeout: Duration,
+
+    /// The port of the policy server currently asked.
+    policy_port: u16,
+
+    /// The policy servers to ask next, if the current one doesn't allow the connection.
+    remaining_ports: VecDeque<u16>,
+
+    /// The policy file received so far.
+    buffer: Vec<u8>,
+}
+
+/// What to do with a connection after an action of its policy server.
+#[derive(Debug, PartialEq, Eq)]
+enum PolicyCheckStep {
+    /// The policy server accepted the connection, ask it for a policy file.
+    Request,
+    /// The policy file isn't complete yet.
+    Pending,
+    /// The policy file allows the connection.
+    Allowed,
+    /// Ask the policy server on the given port next.
+    Next(u16),
+    /// No policy server allows the connection.
+    Denied,
+}
+
+impl PolicyCheck {
+    /// Handles an action of the connection to the current policy server.
+    fn update(&mut self, action: SocketAction) -> PolicyCheckStep {
+        let policy = match action {
+            SocketAction::Connect(_, ConnectionState::Connected) => {
+                return PolicyCheckStep::Request;
+            }
+            SocketAction::Data(_, data) => {
+                self.buffer.extend(data);
+                // The policy file is complete once the server sends a null byte.
+                if !self.buffer.contains(&0) {
+                    return PolicyCheckStep::Pending;
+                }
+                Some(PolicyFile::parse(&self.buffer))
+            }
+            // The server closed the connection without sending a complete policy file.
+            SocketAction::Connect(_, _) | SocketAction::Close(_) => None,
+        };
+
+        match policy {
+            Some(Ok(policy)) => {
+                // Unlike HTTP servers, socket policy servers allow all policy files
+                // when their master policy file has no `<site-control>`.
+                let meta_policy = if self.policy_port == SOCKET_MASTER_POLICY_PORT {
+                    policy.meta_policy().unwrap_or(MetaPolicy::All)
+                } else {
+                    MetaPolicy::All
+                };
+                if meta_policy != MetaPolicy::All {
+                    self.remaining_ports.clear();
+                }
+                if meta_policy != MetaPolicy::None
+                    && policy.allows_socket(self.domain.as_deref(), self.port)
+                {
+                    return PolicyCheckStep::Allowed;
+                }
+            }
+            Some(Err(e)) => tracing::warn!(
+                "Invalid socket policy file from {}:{}: {}",
+                self.host,
+                self.policy_port,
+                e
+            ),
+            None => {}
+        }
+
+        match self.remaining_ports.pop_front() {
+            Some(policy_port) => {
+                self.policy_port = policy_port;
+                self.buffer.clear();
+                PolicyCheckStep::Next(policy_port)
+            }
+            None => PolicyCheckStep::Denied,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum ConnectionState {
     Connected,
@@ -66,6 +178,18 @@ pub struct Sockets<'gc> {
 
     receiver: Receiver<SocketAction>,
     sender: Sender<SocketAction>,
+
+    /// Whether connections have to be allowed by a socket policy file, like in Flash Player.
+    check_policy_files: bool,
+
+    /// Connections waiting for a policy file.
+    policy_checks: HashMap<SocketHandle, PolicyCheck>,
+
+    /// Policy servers registered with `Security.loadPolicyFile`, by host.
+    policy_file_ports: HashMap<String, Vec<u16>>,
+
+    /// Destinations already allowed by a policy file, by movie domain.
+    allowed: HashSet<(Option<String>, String, u16)>,
 }
 
 unsafe impl Collect for Sockets<'_> {
@@ -84,29 +208,138 @@ impl<'gc> Sockets<'gc> {
             sockets: SlotMap::with_key(),
             receiver,
             sender,
+            check_policy_files: false,
+            policy_checks: HashMap::new(),
+            policy_file_ports: HashMap::new(),
+            allowed: HashSet::new(),
         }
     }
 
-    pub fn connect_avm2(
+    pub fn set_check_policy_files(&mut self, check_policy_files: bool) {
+        self.check_policy_files = check_policy_files;
+    }
+
+    /// Registers a policy server to ask before connecting to the given host,
+    /// as done by `Security.loadPolicyFile("xmlsocket://host:port")`.
+    pub fn add_policy_file(&mut self, host: String, port: u16) {
+        let ports = self.policy_file_ports.entry(host).or_default();
+        if !ports.contains(&port) {
+            ports.push(port);
+        }
+    }
+
+    /// Handles a `Security.loadPolicyFile` call.
+    ///
+    /// Returns `false` if the URL doesn't point to a socket policy server.
+    pub fn load_policy_file(&mut self, url: &str) -> bool {
+        let Ok(url) = Url::parse(url) else {
+            return false;
+        };
+        if url.scheme() != "xmlsocket" {
+            return false;
+        }
+
+        if let (Some(host), Some(port)) = (url.host_str(), url.port()) {
+            self.add_policy_file(host.to_string(), port);
+        } else {
+            tracing::warn!("Invalid socket policy file URL: {}", url);
+        }
+        true
+    }
+
+    /// Opens a connection for the given target, or asks for a policy file first if needed.
+    fn connect(
         &mut self,
         backend: &mut dyn NavigatorBackend,
-        target: SocketObject<'gc>,
+        target: SocketKind<'gc>,
+        movie: &SwfMovie,
+        host: &str,
+        port: u16,
+        timeout: Duration,
+    ) -> SocketHandle {
+        let host = sanitize_host(host).to_string();
+        let domain = movie_domain(movie);
+
+        // Trusted local movies and AIR applications aren't restricted by policy files.
+        let needs_policy_file = self.check_policy_files
+            && !matches!(
+                movie.sandbox_type(),
+                SandboxType::LocalTrusted | SandboxType::Application
+            )
+            && !self.allowed.contains(&(domain.clone(), host.clone(), port));
+
+        if !needs_policy_file {
+            return self.open(backend, target, host, port, timeout);
+        }
+
+        // Flash asks the master policy server first, then the ones registered with
+        // `Security.loadPolicyFile`, and finally the destination port itself.
+        let mut remaining_ports: VecDeque<u16> = self
+            .policy_file_ports
+            .get(&host)
+            .cloned()
+            .unwrap_or_default()
+            .into();
+        if port != SOCKET_MASTER_POLICY_PORT && !remaining_ports.contains(&port) {
+            remaining_ports.push_back(port);
+        }
+
+        let handle = self.open(
+            backend,
+            target,
+            host.clone(),
+            SOCKET_MASTER_POLICY_PORT,
+            POLICY_FILE_TIMEOUT,
+        );
+        self.policy_checks.insert(
+            handle,
+            PolicyCheck {
+                domain,
+                host,
+                port,
+                timeout,
+                policy_port: SOCKET_MASTER_POLICY_PORT,
+                remaining_ports,
+                buffer: Vec::new(),
+            },
+        );
+        handle
+    }
+
+    fn open(
+        &mut self,
+        backend: &mut dyn NavigatorBackend,
+        target: SocketKind<'gc>,
         host: String,
         port: u16,
-    ) {
+        timeout: Duration,
+    ) -> SocketHandle {
         let (sender, receiver) = unbounded();
 
-        let socket = Socket::new(SocketKind::Avm2(target), sender);
+        let socket = Socket::new(target, sender);
         let handle = self.sockets.insert(socket);
 
         // NOTE: This call will send SocketAction::Connect to sender with connection status.
-        backend.connect_socket(
-            sanitize_host(&host).to_string(),
+        backend.connect_socket(host, port, timeout, handle, receiver, self.sender.clone());
+
+        handle
+    }
+
+    pub fn connect_avm2(
+        &mut self,
+        backend: &mut dyn NavigatorBackend,
+        target: SocketObject<'gc>,
+        movie: &SwfMovie,
+        host: String,
+        port: u16,
+    ) {
+        let handle = self.connect(
+            backend,
+            SocketKind::Avm2(target),
+            movie,
+            &host,
             port,
             Duration::from_millis(target.timeout().into()),
-            handle,
-            receiver,
-            self.sender.clone(),
         );
 
         if let Some(existing_handle) = target.set_handle(handle) {
@@ -120,27 +353,22 @@ impl<'gc> Sockets<'gc> {
         &mut self,
         backend: &mut dyn NavigatorBackend,
         target: Avm1Object<'gc>,
+        movie: &SwfMovie,
         host: String,
         port: u16,
     ) {
-        let (sender, receiver) = unbounded();
-
         let xml_socket = match XmlSocket::cast(target.into()) {
             Some(xml_socket) => xml_socket,
             None => return,
         };
 
-        let socket = Socket::new(SocketKind::Avm1(target), sender);
-        let handle = self.sockets.insert(socket);
-
-        // NOTE: This call will send SocketAction::Connect to sender with connection status.
-        backend.connect_socket(
-            sanitize_host(&host).to_string(),
+        let handle = self.connect(
+            backend,
+            SocketKind::Avm1(target),
+            movie,
+            &host,
             port,
             Duration::from_millis(xml_socket.timeout().into()),
-            handle,
-            receiver,
-            self.sender.clone(),
         );
 
         if let Some(existing_handle) = xml_socket.set_handle(handle) {
@@ -169,12 +397,14 @@ impl<'gc> Sockets<'gc> {
     }
 
     pub fn close_all(&mut self) {
+        self.policy_checks.clear();
         for (_, socket) in self.sockets.drain() {
             Self::close_internal(socket);
         }
     }
 
     pub fn close(&mut self, handle: SocketHandle) {
+        self.policy_checks.remove(&handle);
         if let Some(socket) = self.sockets.remove(handle) {
             Self::close_internal(socket);
         }
@@ -203,6 +433,96 @@ impl<'gc> Sockets<'gc> {
         }
     }
 
+    /// Handles an action of a connection to a policy server.
+    ///
+    /// Returns the target and destination of the connection if no policy file allowed it.
+    fn update_policy_check(
+        &mut self,
+        backend: &mut dyn NavigatorBackend,
+        handle: SocketHandle,
+        action: SocketAction,
+    ) -> Option<(SocketKind<'gc>, String, u16)> {
+        let step = self.policy_checks.get_mut(&handle)?.update(action);
+        match step {
+            PolicyCheckStep::Request => {
+                self.send(handle, SOCKET_POLICY_FILE_REQUEST.to_vec());
+                return None;
+            }
+            PolicyCheckStep::Pending => return None,
+            PolicyCheckStep::Allowed | PolicyCheckStep::Next(_) | PolicyCheckStep::Denied => {}
+        }
+
+        let check = self.policy_checks.remove(&handle)?;
+        // NOTE: Dropping the socket closes the connection to the policy server.
+        let target = self.sockets.remove(handle)?.target;
+
+        match step {
+            PolicyCheckStep::Allowed => {
+                self.allowed
+                    .insert((check.domain, check.host.clone(), check.port));
+                let handle = self.open(backend, target, check.host, check.port, check.timeout);
+                target.set_handle(handle);
+                None
+            }
+            PolicyCheckStep::Next(policy_port) => {
+                let handle = self.open(
+                    backend,
+                    target,
+                    check.host.clone(),
+                    policy_port,
+                    POLICY_FILE_TIMEOUT,
+                );
+                target.set_handle(handle);
+                self.policy_checks.insert(handle, check);
+                None
+            }
+            _ => {
+                tracing::warn!(
+                    "No socket policy file allows connecting to {}:{}",
+                    check.host,
+                    check.port
+                );
+                Some((target, check.host, check.port))
+            }
+        }
+    }
+
+    fn dispatch_security_error(
+        context: &mut UpdateContext<'gc>,
+        target: SocketKind<'gc>,
+        host: &str,
+        port: u16,
+    ) {
+        match target {
+            SocketKind::Avm2(target) => {
+                let mut activation = Avm2Activation::from_nothing(context);
+
+                let message = format!(
+                    "Error #2048: Security sandbox violation: {} cannot load data from {}:{}.",
+                    activation.context.swf.url(),
+                    host,
+                    port
+                );
+                let message = AvmString::new_utf8(activation.gc(), message);
+                let security_error_evt =
+                    EventObject::security_error_event(&mut activation, message, 2048);
+
+                Avm2::dispatch_event(activation.context, security_error_evt, target.into());
+            }
+            SocketKind::Avm1(target) => {
+                let mut activation =
+                    Avm1Activation::from_stub(context, ActivationIdentifier::root("[XMLSocket]"));
+
+                let _ = target.call_method(
+                    istr!("onConnect"),
+                    &[false.into()],
+                    &mut activation,
+                    ExecutionReason::Special,
+                );
+            }
+        }
+    }
+
     pub fn update_sockets(context: &mut UpdateContext<'gc>) {
         let mut actions = vec![];
 
@@ -211,6 +531,21 @@ impl<'gc> Sockets<'gc> {
         }
 
         for action in actions {
+            let (SocketAction::Connect(handle, _)
+            | SocketAction::Data(handle, _)
+            | SocketAction::Close(handle)) = action;
+            if context.sockets.policy_checks.contains_key(&handle) {
+                let UpdateContext {
+                    sockets, navigator, ..
+                } = &mut *context;
+                if let Some((target, host, port)) =
+                    sockets.update_policy_check(*navigator, handle, action)
+                {
+                    Self::dispatch_security_error(context, target, &host, port);
+                }
+                continue;
+            }
+
             match action {
                 SocketAction::Connect(handle, ConnectionState::Connected) => {
                     let target = match context.sockets.sockets.get(handle) {
@@ -422,7 +757,204 @@ fn sanitize_host(host: &str) -> &str {
 
 #[cfg(test)]
 mod tests {
-    use super::sanitize_host;
+    use super::*;
+
+    /// A connection from `www.example.com` to `example.com:1000`, waiting for the
+    /// master policy server. `policy_ports` were registered with `Security.loadPolicyFile`.
+    fn policy_check(policy_ports: &[u16]) -> PolicyCheck {
+        let mut remaining_ports: VecDeque<u16> = policy_ports.iter().copied().collect();
+        remaining_ports.push_back(1000);
+        PolicyCheck {
+            domain: Some("www.example.com".to_string()),
+            host: "example.com".to_string(),
+            port: 1000,
+            timeout: Duration::from_secs(20),
+            policy_port: SOCKET_MASTER_POLICY_PORT,
+            remaining_ports,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Runs the actions of the current policy server, and returns the step of each one.
+    fn run(check: &mut PolicyCheck, actions: Vec<SocketAction>) -> Vec<PolicyCheckStep> {
+        actions
+            .into_iter()
+            .map(|action| check.update(action))
+            .collect()
+    }
+
+    fn connected() -> SocketAction {
+        SocketAction::Connect(SocketHandle::default(), ConnectionState::Connected)
+    }
+
+    fn data(data: &[u8]) -> SocketAction {
+        SocketAction::Data(SocketHandle::default(), data.to_vec())
+    }
+
+    #[test]
+    fn policy_check_allowed() {
+        let mut check = policy_check(&[]);
+        let steps = run(
+            &mut check,
+            vec![
+                connected(),
+                data(b"<cross-domain-policy><allow-access-from domain=\"*.example.com\" "),
+                data(b"to-ports=\"1000\"/></cross-domain-policy>\0"),
+            ],
+        );
+        assert_eq!(
+            steps,
+            vec![
+                PolicyCheckStep::Request,
+                PolicyCheckStep::Pending,
+                PolicyCheckStep::Allowed
+            ]
+        );
+    }
+
+    #[test]
+    fn policy_check_denied() {
+        let mut check = policy_check(&[]);
+        let steps = run(
+            &mut check,
+            vec![
+                connected(),
+                data(b"<cross-domain-policy><site-control permitted-cross-domain-policies=\"all\"/><allow-access-from domain=\"*\" to-ports=\"2000\"/></cross-domain-policy>\0"),
+            ],
+        );
+        assert_eq!(
+            steps,
+            vec![PolicyCheckStep::Request, PolicyCheckStep::Next(1000)]
+        );
+        assert_eq!(check.policy_port, 1000);
+        assert!(check.buffer.is_empty());
+
+        let steps = run(
+            &mut check,
+            vec![
+                connected(),
+                data(b"<cross-domain-policy><allow-access-from domain=\"other.com\" to-ports=\"1000\"/></cross-domain-policy>\0"),
+            ],
+        );
+        assert_eq!(
+            steps,
+            vec![PolicyCheckStep::Request, PolicyCheckStep::Denied]
+        );
+    }
+
+    #[test]
+    fn policy_check_master_only() {
+        let mut check = policy_check(&[2000]);
+        let steps = run(
+            &mut check,
+            vec![
+                connected(),
+                data(b"<cross-domain-policy><site-control permitted-cross-domain-policies=\"master-only\"/></cross-domain-policy>\0"),
+            ],
+        );
+        assert_eq!(
+            steps,
+            vec![PolicyCheckStep::Request, PolicyCheckStep::Denied]
+        );
+
+        // A meta-policy of `none` denies the connection even if the master policy allows it.
+        let mut check = policy_check(&[2000]);
+        let steps = run(
+            &mut check,
+            vec![
+                connected(),
+                data(b"<cross-domain-policy><site-control permitted-cross-domain-policies=\"none\"/><allow-access-from domain=\"*\" to-ports=\"*\"/></cross-domain-policy>\0"),
+            ],
+        );
+        assert_eq!(
+            steps,
+            vec![PolicyCheckStep::Request, PolicyCheckStep::Denied]
+        );
+    }
+
+    #[test]
+    fn policy_check_master_without_site_control() {
+        let mut check = policy_check(&[2000]);
+        let steps = run(
+            &mut check,
+            vec![
+                connected(),
+                data(b"<cross-domain-policy><allow-access-from domain=\"*\" to-ports=\"843\"/></cross-domain-policy>\0"),
+            ],
+        );
+        assert_eq!(
+            steps,
+            vec![PolicyCheckStep::Request, PolicyCheckStep::Next(2000)]
+        );
+
+        let steps = run(
+            &mut check,
+            vec![connected(), data(b"<cross-domain-policy/>\0")],
+        );
+        assert_eq!(
+            steps,
+            vec![PolicyCheckStep::Request, PolicyCheckStep::Next(1000)]
+        );
+
+        let steps = run(
+            &mut check,
+            vec![
+                connected(),
+                data(b"<cross-domain-policy><allow-access-from domain=\"*.example.com\" to-ports=\"1000\"/></cross-domain-policy>\0"),
+            ],
+        );
+        assert_eq!(
+            steps,
+            vec![PolicyCheckStep::Request, PolicyCheckStep::Allowed]
+        );
+    }
+
+    #[test]
+    fn policy_check_falls_back_to_next_port() {
+        let mut check = policy_check(&[2000]);
+
+        // The master policy server refuses the connection.
+        let steps = run(
+            &mut check,
+            vec![SocketAction::Connect(
+                SocketHandle::default(),
+                ConnectionState::Failed,
+            )],
+        );
+        assert_eq!(steps, vec![PolicyCheckStep::Next(2000)]);
+
+        // The server registered with `Security.loadPolicyFile` closes the connection
+        // before sending a complete policy file.
+        let steps = run(
+            &mut check,
+            vec![
+                connected(),
+                data(b"<cross-domain-policy>"),
+                SocketAction::Close(SocketHandle::default()),
+            ],
+        );
+        assert_eq!(
+            steps,
+            vec![
+                PolicyCheckStep::Request,
+                PolicyCheckStep::Pending,
+                PolicyCheckStep::Next(1000)
+            ]
+        );
+
+        // The destination port serves its own policy file.
+        let steps = run(
+            &mut check,
+            vec![
+                connected(),
+                data(b"<cross-domain-policy><allow-access-from domain=\"www.example.com\" to-ports=\"1000\"/></cross-domain-policy>\0"),
+            ],
+        );
+        assert_eq!(
+            steps,
+            vec![PolicyCheckStep::Request, PolicyCheckStep::Allowed]
+        );
+    }
 
     #[test]
     fn truncate_host_at_null() {
@@ -179,6 +179,21 @@ pub enum NetStreamType {
     },
 }
 
+/// An action passed to `NetStream.appendBytesAction`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AppendBytesAction {
+    /// The appended data is discontinuous; the next appended bytes start a
+    /// new FLV file, header included.
+    ResetBegin,
+
+    /// A seek has occurred; the next appended bytes start at an FLV tag.
+    ResetSeek,
+
+    /// No further data will be appended, so the remaining buffer should be
+    /// played out before the stream stops.
+    EndSequence,
+}
+
 #[derive(Clone, Debug, Collect)]
 #[collect(no_drop)]
 pub struct NetStreamData<'gc> {
@@ -218,10 +233,36 @@ pub struct NetStreamData<'gc> {
     /// Seeks are only executed on the next stream tick.
     queued_seek_time: Option<f64>,
 
-    /// The number of seconds of video data that should be buffered. This is
-    /// currently unsupported and changing it has no effect.
+    /// The number of seconds of media that must be buffered before playback
+    /// starts or resumes after the buffer ran empty.
     buffer_time: f64,
 
+    /// The number of seconds of already played media that should be kept in
+    /// the buffer for seeking backwards.
+    back_buffer_time: f64,
+
+    /// True if playback is stalled until `buffer_time` seconds of media have
+    /// been buffered, or the download has finished.
+    buffering: bool,
+
+    /// The buffer position of the first tag.
+    first_tag_offset: usize,
+
+    /// The buffer position up to which tags have been scanned for their
+    /// timestamps, which is the end of the last complete tag.
+    scan_offset: usize,
+
+    /// The timestamp of the first complete tag in the buffer, in milliseconds.
+    buffer_start_time: Option<f64>,
+
+    /// The timestamp of the last complete tag in the buffer, in milliseconds.
+    buffer_end_time: Option<f64>,
+
+    /// The next queued number of video frames to step by.
+    ///
+    /// Steps are only executed on the next stream tick.
+    queued_step: Option<i32>,
+
     /// The last decoded bitmap.
     ///
     /// Any `Video`s on the stage will display the bitmap here when attached to
@@ -252,6 +293,18 @@ pub struct NetStreamData<'gc> {
 
     /// True if the stream should play when ticked.
     playing: bool,
+
+    /// True if the stream is in data generation mode, where the buffer is fed
+    /// by `appendBytes` rather than by downloading a file.
+    data_generation: bool,
+
+    /// True if appended data should be discarded until the FLV parser is
+    /// reset, which is the case after seeking in data generation mode.
+    discard_appends: bool,
+
+    /// True if the stream time should jump to the timestamp of the next tag,
+    /// as the buffer was reset with data from an unknown point in time.
+    sync_stream_time: bool,
 }
 
 impl<'gc> NetStream<'gc> {
@@ -268,6 +321,13 @@ impl<'gc> NetStream<'gc> {
                 stream_time: 0.0,
                 queued_seek_time: None,
                 buffer_time: 0.1,
+                back_buffer_time: 30.0,
+                buffering: true,
+                first_tag_offset: 0,
+                scan_offset: 0,
+                buffer_start_time: None,
+                buffer_end_time: None,
+                queued_step: None,
                 last_decoded_bitmap: None,
                 avm_object,
                 avm2_client: None,
@@ -277,6 +337,9 @@ impl<'gc> NetStream<'gc> {
                 attached_to: None,
                 playing: false,
                 expected_length: Some(0),
+                data_generation: false,
+                discard_appends: false,
+                sync_stream_time: false,
             },
         ))
     }
@@ -322,6 +385,14 @@ impl<'gc> NetStream<'gc> {
         write.audio_stream = None;
         write.sound_instance = None;
         write.expected_length = Some(0);
+        write.discard_appends = false;
+        write.sync_stream_time = false;
+        write.buffering = true;
+        write.first_tag_offset = 0;
+        write.scan_offset = 0;
+        write.buffer_start_time = None;
+        write.buffer_end_time = None;
+        write.queued_step = None;
     }
 
     /// Set the total number of bytes expected to be downloaded.
@@ -348,14 +419,12 @@ impl<'gc> NetStream<'gc> {
     /// separate streams is not mixed together.
     pub fn load_buffer(self, context: &mut UpdateContext<'gc>, data: &mut Vec<u8>) {
         self.0.write(context.gc()).buffer.append(data);
+        self.scan_loaded_data(context);
 
         StreamManager::activate(context, self);
 
         // NOTE: The onMetaData event triggers before this event in Flash due to its streaming behavior.
-        self.trigger_status_event(
-            context,
-            vec![("code", "NetStream.Buffer.Full"), ("level", "status")],
-        );
+        self.update_buffering(context);
     }
 
     /// Indicate that the buffer has finished loading and that no further data
@@ -364,6 +433,166 @@ impl<'gc> NetStream<'gc> {
         self.0.write(context.gc()).expected_length = None;
     }
 
+    /// Append data given to `appendBytes` to the buffer.
+    ///
+    /// This only has an effect in data generation mode, and not after seeking
+    /// until the parser has been reset with `append_bytes_action`.
+    pub fn append_bytes(self, context: &mut UpdateContext<'gc>, data: &mut Vec<u8>) {
+        let mut write = self.0.write(context.gc());
+        if !write.data_generation {
+            tracing::warn!("NetStream.appendBytes called outside of data generation mode");
+            return;
+        }
+
+        if write.discard_appends {
+            return;
+        }
+
+        write.buffer.append(data);
+        drop(write);
+        self.scan_loaded_data(context);
+
+        StreamManager::activate(context, self);
+        self.update_buffering(context);
+    }
+
+    /// Apply an action given to `appendBytesAction`.
+    pub fn append_bytes_action(self, context: &mut UpdateContext<'gc>, action: AppendBytesAction) {
+        match action {
+            AppendBytesAction::ResetBegin => {
+                let playing = self.0.read().playing;
+                self.reset_buffer(context);
+
+                let mut write = self.0.write(context.gc());
+                write.playing = playing;
+                write.sync_stream_time = true;
+            }
+            AppendBytesAction::ResetSeek => {
+                self.flush_appended_data(context);
+
+                let mut write = self.0.write(context.gc());
+                write.discard_appends = false;
+                write.expected_length = Some(0);
+                write.sync_stream_time = true;
+            }
+            AppendBytesAction::EndSequence => self.finish_buffer(context),
+        }
+
+        StreamManager::activate(context, self);
+    }
+
+    /// Discard all appended data while keeping the state of the FLV parser,
+    /// so that the next appended bytes are parsed as the start of a tag.
+    fn flush_appended_data(self, context: &mut UpdateContext<'gc>) {
+        let mut write = self.0.write(context.gc());
+
+        if let Some(instance) = write.sound_instance {
+            context.audio.stop_sound(instance);
+            context.audio_manager.stop_sound(context.audio, instance);
+        }
+
+        write.buffer = Buffer::new();
+        if write.stream_type.is_some() {
+            // Tags are parsed together with the back pointer preceding them,
+            // which appended tags don't start with.
+            write.buffer.extend_from_slice(&[0; 4]);
+        }
+
+        write.offset = 0;
+        write.preload_offset = 0;
+        write.audio_stream = None;
+        write.sound_instance = None;
+        write.buffering = true;
+        write.first_tag_offset = 0;
+        write.scan_offset = 0;
+        write.buffer_start_time = None;
+        write.buffer_end_time = None;
+    }
+
+    /// Identify the container of newly loaded data if it isn't known yet, and
+    /// scan it for the range of media time that is now available.
+    fn scan_loaded_data(self, context: &mut UpdateContext<'gc>) {
+        if self.0.read().stream_type.is_none() {
+            self.sniff_stream_type(context);
+        }
+
+        Self::scan_buffered_tags(&mut self.0.write(context.gc()));
+    }
+
+    /// The number of milliseconds of media in the buffer that have yet to be
+    /// played, or `None` if the container doesn't tell us the timestamps of
+    /// the buffered data.
+    fn buffered_time(write: &NetStreamData<'gc>) -> Option<f64> {
+        match write.stream_type {
+            Some(NetStreamType::Flv { .. }) => Some(
+                write
+                    .buffer_end_time
+                    .map(|end| (end - write.stream_time).max(0.0))
+                    .unwrap_or(0.0),
+            ),
+            None => None,
+        }
+    }
+
+    /// Whether enough media has been buffered to start or resume playback.
+    ///
+    /// Data we can't read timestamps from counts as a full buffer as soon as
+    /// any of it is left to be played.
+    fn is_buffer_full(write: &NetStreamData<'gc>) -> bool {
+        if write.expected_length.is_none() {
+            return true;
+        }
+
+        match Self::buffered_time(write) {
+            Some(time) => time >= write.buffer_time * 1000.0,
+            None => write.buffer.len() > write.offset,
+        }
+    }
+
+    /// Stop buffering and fire `NetStream.Buffer.Full` once enough media has
+    /// been buffered.
+    fn update_buffering(self, context: &mut UpdateContext<'gc>) {
+        let mut write = self.0.write(context.gc());
+        if !write.buffering || !Self::is_buffer_full(&write) {
+            return;
+        }
+
+        write.buffering = false;
+        drop(write);
+
+        self.trigger_status_event(
+            context,
+            vec![("code", "NetStream.Buffer.Full"), ("level", "status")],
+        );
+    }
+
+    /// Scan tags that were loaded since the last scan, to keep track of the
+    /// range of media time that is available in the buffer.
+    ///
+    /// This also moves the stream time to the first tag if it was unknown.
+    fn scan_buffered_tags(write: &mut NetStreamData<'gc>) {
+        if !matches!(write.stream_type, Some(NetStreamType::Flv { .. })) {
+            return;
+        }
+
+        let slice = write.buffer.to_full_slice();
+        let buffer = slice.data();
+        let mut reader = FlvReader::from_parts(&buffer, write.scan_offset);
+
+        // Incomplete tags are left for the next scan.
+        while let Ok(tag) = FlvTag::parse(&mut reader) {
+            let timestamp = tag.timestamp as f64;
+            if write.sync_stream_time {
+                write.sync_stream_time = false;
+                write.stream_time = timestamp;
+            }
+
+            write.buffer_start_time.get_or_insert(timestamp);
+            write.buffer_end_time = Some(timestamp);
+            write.scan_offset = reader.stream_position().expect("valid position") as usize;
+        }
+    }
+
     pub fn report_error(self, _error: Error) {
         // TODO: Report an `asyncError` to AVM1 or 2.
     }
@@ -391,10 +620,47 @@ impl<'gc> NetStream<'gc> {
         self.0.write(mc).buffer_time = buffer_time;
     }
 
+    /// The number of seconds of media in the buffer that have yet to be played.
+    pub fn buffer_length(self) -> f64 {
+        Self::buffered_time(&self.0.read()).unwrap_or(0.0) / 1000.0
+    }
+
+    pub fn back_buffer_time(self) -> f64 {
+        self.0.read().back_buffer_time
+    }
+
+    pub fn set_back_buffer_time(self, mc: &Mutation<'gc>, back_buffer_time: f64) {
+        self.0.write(mc).back_buffer_time = back_buffer_time;
+    }
+
+    /// The number of seconds of already played media in the buffer that can be
+    /// seeked back to.
+    ///
+    /// Since the whole stream is kept in the buffer, this is only limited by
+    /// `back_buffer_time`.
+    pub fn back_buffer_length(self) -> f64 {
+        let read = self.0.read();
+
+        read.buffer_start_time
+            .map(|start| (read.stream_time - start).max(0.0) / 1000.0)
+            .unwrap_or(0.0)
+            .min(read.back_buffer_time)
+    }
+
     /// Queue a seek to be executed on the next frame tick.
     ///
     /// `offset` is in milliseconds.
     pub fn seek(self, context: &mut UpdateContext<'gc>, offset: f64, notify: bool) {
+        if self.0.read().data_generation {
+            // The data at the new position has to be appended by the movie,
+            // which must first reset the parser.
+            self.flush_appended_data(context);
+
+            let mut write = self.0.write(context.gc());
+            write.discard_appends = true;
+            write.stream_time = offset;
+        }
+
         self.0.write(context.gc()).queued_seek_time = Some(offset);
         StreamManager::activate(context, self);
 
@@ -423,6 +689,9 @@ impl<'gc> NetStream<'gc> {
     /// algorithm will need to detect out-of-buffer seeks and trigger fresh
     /// downloads.
     ///
+    /// In data generation mode, the buffer has already been flushed by `seek`,
+    /// so this only notifies the movie that it can append new data.
+    ///
     /// `offset` is in milliseconds.
     ///
     /// This function should be run during stream ticks and *not* called by AVM
@@ -433,6 +702,10 @@ impl<'gc> NetStream<'gc> {
             vec![("code", "NetStream.Seek.Notify"), ("level", "status")],
         );
 
+        if self.0.read().data_generation {
+            return;
+        }
+
         // Ensure the container stream type is known before continuing.
         if self.0.read().stream_type.is_none() && !self.sniff_stream_type(context) {
             return;
@@ -539,12 +812,124 @@ impl<'gc> NetStream<'gc> {
         }
     }
 
+    /// Queue a step by the given number of video frames, relative to the
+    /// currently displayed frame, to be executed on the next frame tick.
+    ///
+    /// The stream is paused, as stepping is meant for frame-by-frame playback.
+    pub fn step(self, context: &mut UpdateContext<'gc>, frames: i32) {
+        let mut write = self.0.write(context.gc());
+        write.queued_step = Some(write.queued_step.unwrap_or(0) + frames);
+        write.playing = false;
+        drop(write);
+
+        StreamManager::activate(context, self);
+    }
+
+    /// Step by the given number of video frames in the buffer.
+    ///
+    /// Frames that can't be decoded on their own are decoded starting from
+    /// the keyframe before them. Steps past the start or end of the buffer
+    /// stop at the first or last frame.
+    ///
+    /// This function should be run during stream ticks and *not* called by AVM
+    /// code to service step requests.
+    pub fn execute_step(self, context: &mut UpdateContext<'gc>, frames: i32) {
+        // Ensure the container stream type is known before continuing.
+        if self.0.read().stream_type.is_none() && !self.sniff_stream_type(context) {
+            return;
+        }
+
+        let mut write = self.0.write(context.gc());
+        if !matches!(write.stream_type, Some(NetStreamType::Flv { .. })) {
+            return;
+        }
+
+        let slice = write.buffer.to_full_slice();
+        let buffer = slice.data();
+
+        // The position, timestamp and keyframe flag of every video tag.
+        let mut video_tags = vec![];
+        let mut reader = FlvReader::from_parts(&buffer, write.first_tag_offset);
+        loop {
+            let position = reader.stream_position().expect("valid position") as usize;
+            let Ok(tag) = FlvTag::parse(&mut reader) else {
+                break;
+            };
+
+            if let FlvTagData::Video(video_data) = tag.data {
+                let is_keyframe = video_data.frame_type == FlvFrameType::Keyframe;
+                video_tags.push((position, tag.timestamp as f64, is_keyframe));
+            }
+        }
+
+        let Some(last_index) = video_tags.len().checked_sub(1) else {
+            return;
+        };
+
+        // The currently displayed frame is the last one that was processed.
+        let current_index = video_tags
+            .iter()
+            .rposition(|(position, _, _)| *position < write.offset);
+        let target_index = match current_index {
+            Some(index) => (index as i64 + frames as i64).clamp(0, last_index as i64) as usize,
+            None => (frames as i64 - 1).clamp(0, last_index as i64) as usize,
+        };
+        let keyframe_index = video_tags[..=target_index]
+            .iter()
+            .rposition(|(_, _, is_keyframe)| *is_keyframe)
+            .unwrap_or(0);
+
+        if let Some(sound) = write.sound_instance {
+            context.stop_sound(sound);
+            context.audio.stop_sound(sound);
+
+            write.sound_instance = None;
+            write.audio_stream = None;
+        }
+
+        for &(position, timestamp, _) in &video_tags[keyframe_index..=target_index] {
+            let mut reader = FlvReader::from_parts(&buffer, position);
+            if let Ok(FlvTag {
+                data: FlvTagData::Video(video_data),
+                ..
+            }) = FlvTag::parse(&mut reader)
+            {
+                let end = reader.stream_position().expect("valid position") as usize;
+                let tag_needs_preloading = end >= write.preload_offset;
+
+                self.flv_video_tag(
+                    context,
+                    &mut write,
+                    &slice,
+                    video_data,
+                    tag_needs_preloading,
+                );
+
+                write.offset = end;
+                write.preload_offset = max(write.offset, write.preload_offset);
+                write.stream_time = timestamp;
+            }
+        }
+
+        drop(write);
+
+        self.trigger_status_event(
+            context,
+            vec![("code", "NetStream.Step.Notify"), ("level", "status")],
+        );
+    }
+
     /// Start playing media from this NetStream.
     ///
     /// If `name` is specified, this will also trigger streaming download of
-    /// the given resource. Otherwise, the stream will play whatever data is
-    /// available in the buffer.
+    /// the given resource. Otherwise, the stream enters data generation mode
+    /// and plays whatever data is appended to the buffer.
     pub fn play(self, context: &mut UpdateContext<'gc>, name: Option<AvmString<'gc>>) {
+        if name.is_none() && !self.0.read().data_generation {
+            self.reset_buffer(context);
+            self.0.write(context.gc()).data_generation = true;
+        }
+
         if let Some(name) = name {
             let request = if let Ok(stream_url) =
                 Url::parse(context.swf.url()).and_then(|url| url.join(name.to_string().as_str()))
@@ -556,6 +941,7 @@ impl<'gc> NetStream<'gc> {
             let mut write = self.0.write(context.gc());
             write.url = Some(request.url().to_string());
             write.preload_offset = 0;
+            write.data_generation = false;
             let future = context
                 .load_manager
                 .load_netstream(context.player.clone(), self, request);
@@ -816,6 +1202,8 @@ impl<'gc> NetStream<'gc> {
                     Ok(header) => {
                         write.offset = reader.into_parts().1;
                         write.preload_offset = write.offset;
+                        write.first_tag_offset = write.offset;
+                        write.scan_offset = write.offset;
                         write.stream_type = Some(NetStreamType::Flv {
                             header,
                             video_stream: None,
@@ -1140,6 +1528,11 @@ impl<'gc> NetStream<'gc> {
             self.execute_seek(context, offset);
         }
 
+        let step = self.0.write(context.gc()).queued_step.take();
+        if let Some(frames) = step {
+            self.execute_step(context, frames);
+        }
+
         // Paused streams deactivate themselves after seek processing.
         if !self.0.read().playing {
             StreamManager::deactivate(context, self);
@@ -1151,8 +1544,13 @@ impl<'gc> NetStream<'gc> {
             return;
         }
 
-        let mut write = self.0.write(context.gc());
+        Self::scan_buffered_tags(&mut self.0.write(context.gc()));
+        self.update_buffering(context);
+        if self.0.read().buffering {
+            return;
+        }
 
+        let mut write = self.0.write(context.gc());
         self.cleanup_sound_stream(context, &mut write);
         let slice = write.buffer.to_full_slice();
         let buffer = slice.data();
@@ -1234,7 +1632,19 @@ impl<'gc> NetStream<'gc> {
             }
         }
 
-        write.stream_time = max_time;
+        let is_end_of_video = write.expected_length.is_none();
+        if buffer_underrun && !is_end_of_video {
+            // Playback stalls at the end of the buffered media until enough
+            // data has been loaded to fill the buffer again.
+            if let Some(end) = write.buffer_end_time {
+                write.stream_time = write.stream_time.max(end.min(max_time));
+            }
+
+            write.buffering = true;
+        } else {
+            write.stream_time = max_time;
+        }
+
         if let Err(e) = self.commit_sound_stream(context, &mut write) {
             //TODO: Fire an error event at AS.
             tracing::error!("Error committing sound stream: {}", e);
@@ -1242,14 +1652,11 @@ impl<'gc> NetStream<'gc> {
         drop(write);
 
         if buffer_underrun {
-            let is_end_of_video = self.0.read().expected_length.is_none();
-
-            self.trigger_status_event(
-                context,
-                vec![("code", "NetStream.Buffer.Flush"), ("level", "status")],
-            );
-
             if is_end_of_video {
+                self.trigger_status_event(
+                    context,
+                    vec![("code", "NetStream.Buffer.Flush"), ("level", "status")],
+                );
                 self.trigger_status_event(
                     context,
                     vec![("code", "NetStream.Play.Stop"), ("level", "status")],
@@ -140,6 +140,7 @@ pub struct CommonStrings<'gc> {
     pub str_rollOut: AvmString<'gc>,
     pub str_rollOver: AvmString<'gc>,
     pub str_save: AvmString<'gc>,
+    pub str_securityError: AvmString<'gc>,
     pub str_separatorBefore: AvmString<'gc>,
     pub str_status: AvmString<'gc>,
     pub str_string: AvmString<'gc>,
@@ -328,6 +329,7 @@ impl<'gc> CommonStrings<'gc> {
             str_rollOut: intern_from_static(b"rollOut"),
             str_rollOver: intern_from_static(b"rollOver"),
             str_save: intern_from_static(b"save"),
+            str_securityError: intern_from_static(b"securityError"),
             str_separatorBefore: intern_from_static(b"separatorBefore"),
             str_status: intern_from_static(b"status"),
             str_string: intern_from_static(b"string"),
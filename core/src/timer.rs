@@ -25,15 +25,19 @@ pub struct Timers<'gc> {
 
     /// The current global time.
     cur_time: u64,
+
+    /// The total time that the timers have been updated with.
+    ///
+    /// Unlike `cur_time`, this is never moved back when too many timers tick at once.
+    elapsed_time: u64,
 }
 
 impl<'gc> Timers<'gc> {
     /// Ticks all timers and runs necessary callbacks.
     pub fn update_timers(context: &mut UpdateContext<'gc>, dt: f64) -> Option<f64> {
-        context.timers.cur_time = context
-            .timers
-            .cur_time
-            .wrapping_add((dt * Self::TIMER_SCALE) as u64);
+        let dt = (dt * Self::TIMER_SCALE) as u64;
+        context.timers.cur_time = context.timers.cur_time.wrapping_add(dt);
+        context.timers.elapsed_time = context.timers.elapsed_time.saturating_add(dt);
 
         if context.timers.is_empty() {
             return None;
@@ -201,12 +205,18 @@ impl<'gc> Timers<'gc> {
     /// The scale of the timers (microseconds).
     const TIMER_SCALE: f64 = 1000.0;
 
+    /// The total time that the timers have been updated with, in milliseconds.
+    pub fn elapsed_millis(&self) -> f64 {
+        self.elapsed_time as f64 / Self::TIMER_SCALE
+    }
+
     /// Creates a new `Timers` collection.
     pub fn new() -> Self {
         Self {
             timers: Default::default(),
             timer_counter: 0,
             cur_time: 0,
+            elapsed_time: 0,
         }
     }
 
@@ -0,0 +1,1363 @@
+//! ActionScript workers, as exposed through `flash.system.Worker`.
+//!
+//! Every background worker runs its SWF in its own `Player` on its own thread, so it
+//! has its own GC arena and its own copy of every class and static. The only state
+//! shared between workers lives in this module: values are serialized to AMF3 and
+//! copied, while workers, message channels, mutexes, conditions and shareable
+//! `ByteArray`s are passed by reference, and are represented by one object in every
+//! worker that received them.
+//!
+//! The contents of a shareable `ByteArray` are copied between its shared buffer and
+//! the object of each worker at synchronization points: when a mutex is locked or
+//! unlocked, when a message is sent or received, and once per tick.
+//!
+//! Background workers trace and load URLs through the log and navigator backends of the
+//! primordial worker, which aren't thread safe, so they're handed over to it and run
+//! on its next tick.
+
+use crate::avm2::amf::{deserialize_value, serialize_to_bytes, serialize_value};
+use crate::avm2::bytearray::ByteArrayStorage;
+use crate::avm2::error::{make_error_2004, Error2004Type};
+use crate::avm2::object::{scriptobject_allocator, ByteArrayObject, ClassObject};
+use crate::avm2::{
+    Activation, Avm2, Error as Avm2Error, EventObject, Object as Avm2Object, TObject,
+    Value as Avm2Value,
+};
+use crate::backend::log::LogBackend;
+use crate::backend::navigator::{
+    url_from_relative_url, ErrorResponse, NavigationMethod, NavigatorBackend, NullExecutor,
+    NullSpawner, OwnedFuture, Request, SuccessResponse,
+};
+use crate::context::UpdateContext;
+use crate::datagram_socket::{Datagram, DatagramSocketAction, DatagramSocketHandle};
+use crate::loader::Error as LoaderError;
+use crate::player::PlayerBuilder;
+use crate::socket::{ConnectionState, SocketAction, SocketHandle};
+use crate::tag_utils::SwfMovie;
+use async_channel::{Receiver, Sender};
+use encoding_rs::Encoding;
+use flash_lso::amf3::read::AMF3Decoder;
+use flash_lso::types::AMFVersion;
+use gc_arena::Collect;
+use indexmap::IndexMap;
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::{Arc, Condvar, Mutex, Weak};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+use url::{ParseError, Url};
+
+/// How often blocked workers check whether they were terminated meanwhile.
+const BLOCKING_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Whether background workers can be started, as they need threads, which the web doesn't have.
+pub fn is_supported() -> bool {
+    cfg!(not(target_family = "wasm"))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WorkerState {
+    New,
+    Running,
+    Terminated,
+}
+
+impl WorkerState {
+    fn as_str(self) -> &'static str {
+        match self {
+            WorkerState::New => "new",
+            WorkerState::Running => "running",
+            WorkerState::Terminated => "terminated",
+        }
+    }
+}
+
+/// The state shared by a primordial worker and every background worker it led to.
+struct WorkerRegistry {
+    /// The primordial worker, which owns this registry.
+    primordial: Weak<SharedWorker>,
+
+    /// Every background worker that was started and not terminated yet.
+    running: Mutex<Vec<Arc<SharedWorker>>>,
+
+    /// Traces of background workers, printed by the primordial worker on its next tick.
+    traces: Mutex<Vec<String>>,
+
+    /// Navigator requests of background workers, made by the primordial worker on its next tick.
+    requests: Mutex<Vec<NavigatorRequest>>,
+}
+
+/// A worker, shared by every worker that has a reference to it.
+pub struct SharedWorker {
+    registry: Arc<WorkerRegistry>,
+
+    /// The worker that created this one, or `None` for the primordial worker.
+    parent: Option<Arc<SharedWorker>>,
+
+    /// The SWF run by a background worker.
+    swf: Vec<u8>,
+
+    /// The URL of the movie that created this worker.
+    url: String,
+
+    state: Mutex<WorkerState>,
+
+    shared_properties: Mutex<HashMap<String, WorkerMessage>>,
+
+    /// Events to dispatch in this worker on its next tick, with their target.
+    events: Mutex<Vec<(SharedObject, &'static str)>>,
+}
+
+impl SharedWorker {
+    fn primordial() -> Arc<Self> {
+        Arc::new_cyclic(|primordial| Self {
+            registry: Arc::new(WorkerRegistry {
+                primordial: primordial.clone(),
+                running: Mutex::new(Vec::new()),
+                traces: Mutex::new(Vec::new()),
+                requests: Mutex::new(Vec::new()),
+            }),
+            parent: None,
+            swf: Vec::new(),
+            url: String::new(),
+            state: Mutex::new(WorkerState::Running),
+            shared_properties: Mutex::new(HashMap::new()),
+            events: Mutex::new(Vec::new()),
+        })
+    }
+
+    fn is_primordial(&self) -> bool {
+        self.parent.is_none()
+    }
+
+    fn state(&self) -> WorkerState {
+        *self.state.lock().unwrap()
+    }
+
+    fn is_terminated(&self) -> bool {
+        self.state() == WorkerState::Terminated
+    }
+
+    fn post_event(&self, target: SharedObject, event_type: &'static str) {
+        if !self.is_terminated() {
+            self.events.lock().unwrap().push((target, event_type));
+        }
+    }
+
+    /// Starts running the SWF of a new worker on its own thread.
+    ///
+    /// Returns `false` if the worker was already started.
+    fn start(self: &Arc<Self>) -> bool {
+        {
+            let mut state = self.state.lock().unwrap();
+            if *state != WorkerState::New {
+                return false;
+            }
+            *state = WorkerState::Running;
+        }
+        self.registry.running.lock().unwrap().push(self.clone());
+
+        let worker = self.clone();
+        let result = thread::Builder::new()
+            .name("ActionScript worker".to_string())
+            .spawn(move || worker.run());
+        if let Err(e) = result {
+            tracing::error!("Couldn't start worker thread: {e}");
+            self.terminate();
+        }
+        true
+    }
+
+    fn run(self: Arc<Self>) {
+        let movie = match SwfMovie::from_data(&self.swf, self.url.clone(), None) {
+            Ok(movie) => movie,
+            Err(e) => {
+                tracing::error!("Couldn't load the SWF of a worker: {e}");
+                self.terminate();
+                return;
+            }
+        };
+
+        let mut executor = NullExecutor::new();
+        let navigator = WorkerNavigatorBackend {
+            registry: self.registry.clone(),
+            base_url: self.url.clone(),
+            spawner: executor.spawner(),
+        };
+        let player = PlayerBuilder::new()
+            .with_log(WorkerLogBackend(self.registry.clone()))
+            .with_navigator(navigator)
+            .with_movie(movie)
+            .with_autoplay(true)
+            .with_worker(self.clone())
+            .build();
+        if let Some(parent) = &self.parent {
+            parent.post_event(SharedObject::Worker(self.clone()), "workerState");
+        }
+
+        let mut last_tick = Instant::now();
+        while self.state() == WorkerState::Running {
+            let now = Instant::now();
+            let dt = now.duration_since(last_tick).as_secs_f64() * 1000.0;
+            last_tick = now;
+
+            let sleep = {
+                let mut player = player.lock().unwrap();
+                player.tick(dt);
+                player.time_til_next_frame()
+            };
+            executor.run();
+            thread::sleep(sleep.min(BLOCKING_POLL_INTERVAL));
+        }
+    }
+
+    /// Stops a running background worker after its current frame.
+    ///
+    /// Returns `false` if the worker wasn't running.
+    fn terminate(self: &Arc<Self>) -> bool {
+        {
+            let mut state = self.state.lock().unwrap();
+            if self.is_primordial() || *state != WorkerState::Running {
+                return false;
+            }
+            *state = WorkerState::Terminated;
+        }
+        self.events.lock().unwrap().clear();
+        self.registry
+            .running
+            .lock()
+            .unwrap()
+            .retain(|worker| !Arc::ptr_eq(worker, self));
+
+        if let Some(parent) = &self.parent {
+            parent.post_event(SharedObject::Worker(self.clone()), "workerState");
+        }
+        true
+    }
+}
+
+/// Forwards the traces of a background worker to the primordial worker.
+struct WorkerLogBackend(Arc<WorkerRegistry>);
+
+impl LogBackend for WorkerLogBackend {
+    fn avm_trace(&self, message: &str) {
+        self.0.traces.lock().unwrap().push(message.to_string());
+    }
+}
+
+/// A request of a background worker to the navigator of the primordial worker.
+enum NavigatorRequest {
+    Navigate {
+        url: String,
+        target: String,
+        vars_method: Option<(NavigationMethod, IndexMap<String, String>)>,
+    },
+    Fetch(Request, Sender<Result<WorkerResponse, FetchError>>),
+}
+
+/// A failed fetch, in a form that can be sent to another thread.
+struct FetchError {
+    url: String,
+
+    /// The fields of an `HttpNotOk` error, or the message of any other error.
+    error: Result<(String, u16, bool, u64), String>,
+}
+
+impl FetchError {
+    fn new(url: String, error: LoaderError) -> Self {
+        let error = match error {
+            LoaderError::HttpNotOk(message, status, redirected, length) => {
+                Ok((message, status, redirected, length))
+            }
+            error => Err(error.to_string()),
+        };
+        Self { url, error }
+    }
+
+    fn into_response(self) -> ErrorResponse {
+        let error = match self.error {
+            Ok((message, status, redirected, length)) => {
+                LoaderError::HttpNotOk(message, status, redirected, length)
+            }
+            Err(message) => LoaderError::FetchError(message),
+        };
+        ErrorResponse {
+            url: self.url,
+            error,
+        }
+    }
+}
+
+/// The response to a fetch made for a background worker, with its whole body.
+struct WorkerResponse {
+    url: String,
+    body: Option<Vec<u8>>,
+    text_encoding: Option<&'static Encoding>,
+    status: u16,
+    redirected: bool,
+}
+
+impl SuccessResponse for WorkerResponse {
+    fn url(&self) -> Cow<str> {
+        Cow::Borrowed(&self.url)
+    }
+
+    fn body(self: Box<Self>) -> OwnedFuture<Vec<u8>, LoaderError> {
+        Box::pin(async move { Ok(self.body.unwrap_or_default()) })
+    }
+
+    fn text_encoding(&self) -> Option<&'static Encoding> {
+        self.text_encoding
+    }
+
+    fn status(&self) -> u16 {
+        self.status
+    }
+
+    fn redirected(&self) -> bool {
+        self.redirected
+    }
+
+    fn next_chunk(&mut self) -> OwnedFuture<Option<Vec<u8>>, LoaderError> {
+        let chunk = self.body.take();
+        Box::pin(async move { Ok(chunk) })
+    }
+
+    fn expected_length(&self) -> Result<Option<u64>, LoaderError> {
+        Ok(self.body.as_ref().map(|body| body.len() as u64))
+    }
+}
+
+/// Forwards the navigation and fetches of a background worker to the navigator of the
+/// primordial worker. Sockets can't be used from workers.
+struct WorkerNavigatorBackend {
+    registry: Arc<WorkerRegistry>,
+
+    /// The URL of the movie that created the worker, which relative URLs are resolved against.
+    base_url: String,
+
+    spawner: NullSpawner,
+}
+
+impl NavigatorBackend for WorkerNavigatorBackend {
+    fn navigate_to_url(
+        &self,
+        url: &str,
+        target: &str,
+        vars_method: Option<(NavigationMethod, IndexMap<String, String>)>,
+    ) {
+        self.registry
+            .requests
+            .lock()
+            .unwrap()
+            .push(NavigatorRequest::Navigate {
+                url: url.to_string(),
+                target: target.to_string(),
+                vars_method,
+            });
+    }
+
+    fn fetch(&self, request: Request) -> OwnedFuture<Box<dyn SuccessResponse>, ErrorResponse> {
+        let url = request.url().to_string();
+        let (sender, receiver) = async_channel::bounded(1);
+        self.registry
+            .requests
+            .lock()
+            .unwrap()
+            .push(NavigatorRequest::Fetch(request, sender));
+        Box::pin(async move {
+            match receiver.recv().await {
+                Ok(Ok(response)) => Ok(Box::new(response) as Box<dyn SuccessResponse>),
+                Ok(Err(error)) => Err(error.into_response()),
+                // The primordial worker went away before answering.
+                Err(_) => Err(ErrorResponse {
+                    url,
+                    error: LoaderError::Cancelled,
+                }),
+            }
+        })
+    }
+
+    fn resolve_url(&self, url: &str) -> Result<Url, ParseError> {
+        url_from_relative_url(&self.base_url, url)
+    }
+
+    fn spawn_future(&mut self, future: OwnedFuture<(), LoaderError>) {
+        self.spawner.spawn_local(future);
+    }
+
+    fn pre_process_url(&self, url: Url) -> Url {
+        url
+    }
+
+    fn connect_socket(
+        &mut self,
+        _host: String,
+        _port: u16,
+        _timeout: Duration,
+        handle: SocketHandle,
+        _receiver: Receiver<Vec<u8>>,
+        sender: Sender<SocketAction>,
+    ) {
+        sender
+            .try_send(SocketAction::Connect(handle, ConnectionState::Failed))
+            .expect("working channel send");
+    }
+
+    fn bind_datagram_socket(
+        &mut self,
+        _host: String,
+        _port: u16,
+        _handle: DatagramSocketHandle,
+        _receiver: Receiver<Datagram>,
+        _sender: Sender<DatagramSocketAction>,
+    ) -> std::io::Result<SocketAddr> {
+        Err(std::io::ErrorKind::Unsupported.into())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChannelState {
+    Open,
+    Closing,
+    Closed,
+}
+
+impl ChannelState {
+    fn as_str(self) -> &'static str {
+        match self {
+            ChannelState::Open => "open",
+            ChannelState::Closing => "closing",
+            ChannelState::Closed => "closed",
+        }
+    }
+}
+
+struct ChannelQueue {
+    /// Messages sent but not yet received.
+    messages: VecDeque<WorkerMessage>,
+
+    state: ChannelState,
+}
+
+/// A `MessageChannel`, through which `sender` sends messages to `receiver`.
+pub struct SharedChannel {
+    sender: Arc<SharedWorker>,
+    receiver: Arc<SharedWorker>,
+    queue: Mutex<ChannelQueue>,
+
+    /// Notified whenever a message is sent or received, or the channel is closed.
+    changed: Condvar,
+}
+
+impl SharedChannel {
+    /// Queues a message, waiting first until at most `queue_limit` messages are queued.
+    ///
+    /// Returns `false` if the channel isn't open.
+    fn send(
+        self: &Arc<Self>,
+        message: WorkerMessage,
+        queue_limit: Option<usize>,
+        current: &SharedWorker,
+    ) -> bool {
+        let mut queue = self.queue.lock().unwrap();
+        if let Some(queue_limit) = queue_limit {
+            while queue.messages.len() > queue_limit
+                && queue.state == ChannelState::Open
+                && !self.receiver.is_terminated()
+                && !current.is_terminated()
+            {
+                queue = self
+                    .changed
+                    .wait_timeout(queue, BLOCKING_POLL_INTERVAL)
+                    .unwrap()
+                    .0;
+            }
+        }
+        if queue.state != ChannelState::Open {
+            return false;
+        }
+
+        queue.messages.push_back(message);
+        self.changed.notify_all();
+        drop(queue);
+
+        self.receiver
+            .post_event(SharedObject::Channel(self.clone()), "channelMessage");
+        true
+    }
+
+    /// Takes the oldest message off the queue, waiting for one if `block` is set.
+    fn receive(self: &Arc<Self>, block: bool, current: &SharedWorker) -> Option<WorkerMessage> {
+        let mut queue = self.queue.lock().unwrap();
+        let message = loop {
+            if let Some(message) = queue.messages.pop_front() {
+                break Some(message);
+            }
+            if !block
+                || queue.state != ChannelState::Open
+                || self.sender.is_terminated()
+                || current.is_terminated()
+            {
+                break None;
+            }
+            queue = self
+                .changed
+                .wait_timeout(queue, BLOCKING_POLL_INTERVAL)
+                .unwrap()
+                .0;
+        };
+
+        // A closing channel is closed once every queued message was received.
+        let closed = queue.state == ChannelState::Closing && queue.messages.is_empty();
+        if closed {
+            queue.state = ChannelState::Closed;
+        }
+        self.changed.notify_all();
+        drop(queue);
+
+        if closed {
+            self.post_state_event();
+        }
+        message
+    }
+
+    /// Stops the channel from accepting new messages.
+    fn close(self: &Arc<Self>) {
+        let mut queue = self.queue.lock().unwrap();
+        if queue.state != ChannelState::Open {
+            return;
+        }
+        queue.state = if queue.messages.is_empty() {
+            ChannelState::Closed
+        } else {
+            ChannelState::Closing
+        };
+        self.changed.notify_all();
+        drop(queue);
+
+        self.post_state_event();
+    }
+
+    fn post_state_event(self: &Arc<Self>) {
+        self.sender
+            .post_event(SharedObject::Channel(self.clone()), "channelState");
+        if !Arc::ptr_eq(&self.sender, &self.receiver) {
+            self.receiver
+                .post_event(SharedObject::Channel(self.clone()), "channelState");
+        }
+    }
+}
+
+#[derive(Default)]
+struct MutexOwner {
+    /// The thread of the worker holding the mutex.
+    thread: Option<ThreadId>,
+
+    /// How many times the owner locked the mutex.
+    count: u32,
+}
+
+/// A `flash.concurrent.Mutex`.
+///
+/// Every worker runs on its own thread, so the owning worker is identified by its thread.
+#[derive(Default)]
+pub struct SharedMutex {
+    owner: Mutex<MutexOwner>,
+
+    /// Notified whenever the mutex is released.
+    released: Condvar,
+}
+
+impl SharedMutex {
+    fn lock(&self, current: &SharedWorker) {
+        let thread = thread::current().id();
+        let mut owner = self.owner.lock().unwrap();
+        while owner.thread.is_some_and(|owner| owner != thread) {
+            if current.is_terminated() {
+                return;
+            }
+            owner = self
+                .released
+                .wait_timeout(owner, BLOCKING_POLL_INTERVAL)
+                .unwrap()
+                .0;
+        }
+        owner.thread = Some(thread);
+        owner.count += 1;
+    }
+
+    fn try_lock(&self) -> bool {
+        let thread = thread::current().id();
+        let mut owner = self.owner.lock().unwrap();
+        if owner.thread.is_some_and(|owner| owner != thread) {
+            return false;
+        }
+        owner.thread = Some(thread);
+        owner.count += 1;
+        true
+    }
+
+    /// Returns `false` if the mutex isn't locked by the current worker.
+    fn unlock(&self) -> bool {
+        let mut owner = self.owner.lock().unwrap();
+        if owner.thread != Some(thread::current().id()) {
+            return false;
+        }
+        owner.count -= 1;
+        if owner.count == 0 {
+            owner.thread = None;
+            self.released.notify_all();
+        }
+        true
+    }
+
+    fn is_owned_by_current_worker(&self) -> bool {
+        self.owner.lock().unwrap().thread == Some(thread::current().id())
+    }
+}
+
+#[derive(Default)]
+struct ConditionWaiters {
+    /// How many workers are waiting on the condition.
+    waiting: u32,
+
+    /// How many of the waiting workers were notified, but didn't wake up yet.
+    notified: u32,
+}
+
+/// A `flash.concurrent.Condition`.
+pub struct SharedCondition {
+    mutex: Arc<SharedMutex>,
+    waiters: Mutex<ConditionWaiters>,
+    notified: Condvar,
+}
+
+impl SharedCondition {
+    /// Releases the mutex until the condition is notified or `timeout` passed, then
+    /// locks the mutex again.
+    ///
+    /// Returns whether the condition was notified. The caller must own the mutex.
+    fn wait(&self, timeout: Option<Duration>, current: &SharedWorker) -> bool {
+        let thread = thread::current().id();
+
+        // Count ourselves as waiting before releasing the mutex, so that a notification
+        // sent as soon as it's released isn't lost.
+        let mut waiters = self.waiters.lock().unwrap();
+        waiters.waiting += 1;
+
+        // The mutex is released entirely, even if it was locked several times.
+        let count = {
+            let mut owner = self.mutex.owner.lock().unwrap();
+            let count = owner.count;
+            owner.thread = None;
+            owner.count = 0;
+            self.mutex.released.notify_all();
+            count
+        };
+
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let notified = loop {
+            if waiters.notified > 0 {
+                waiters.notified -= 1;
+                break true;
+            }
+            if current.is_terminated() {
+                break false;
+            }
+            let wait = match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        break false;
+                    }
+                    (deadline - now).min(BLOCKING_POLL_INTERVAL)
+                }
+                None => BLOCKING_POLL_INTERVAL,
+            };
+            waiters = self.notified.wait_timeout(waiters, wait).unwrap().0;
+        };
+        waiters.waiting -= 1;
+        waiters.notified = waiters.notified.min(waiters.waiting);
+        drop(waiters);
+
+        let mut owner = self.mutex.owner.lock().unwrap();
+        while owner.thread.is_some() && !current.is_terminated() {
+            owner = self
+                .mutex
+                .released
+                .wait_timeout(owner, BLOCKING_POLL_INTERVAL)
+                .unwrap()
+                .0;
+        }
+        owner.thread = Some(thread);
+        owner.count = count;
+
+        notified
+    }
+
+    fn notify(&self) {
+        let mut waiters = self.waiters.lock().unwrap();
+        if waiters.notified < waiters.waiting {
+            waiters.notified += 1;
+            self.notified.notify_all();
+        }
+    }
+
+    fn notify_all(&self) {
+        let mut waiters = self.waiters.lock().unwrap();
+        waiters.notified = waiters.waiting;
+        self.notified.notify_all();
+    }
+}
+
+/// The buffer of a shareable `ByteArray`.
+pub struct SharedBytes(Mutex<Vec<u8>>);
+
+/// An object that is passed by reference between workers.
+#[derive(Clone)]
+enum SharedObject {
+    ByteArray(Arc<SharedBytes>),
+    Worker(Arc<SharedWorker>),
+    Channel(Arc<SharedChannel>),
+    Mutex(Arc<SharedMutex>),
+    Condition(Arc<SharedCondition>),
+}
+
+impl SharedObject {
+    fn ptr_eq(&self, other: &SharedObject) -> bool {
+        match (self, other) {
+            (SharedObject::ByteArray(a), SharedObject::ByteArray(b)) => Arc::ptr_eq(a, b),
+            (SharedObject::Worker(a), SharedObject::Worker(b)) => Arc::ptr_eq(a, b),
+            (SharedObject::Channel(a), SharedObject::Channel(b)) => Arc::ptr_eq(a, b),
+            (SharedObject::Mutex(a), SharedObject::Mutex(b)) => Arc::ptr_eq(a, b),
+            (SharedObject::Condition(a), SharedObject::Condition(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+/// A value passed between workers, either through a `MessageChannel` or as a
+/// shared property.
+#[derive(Clone)]
+enum WorkerMessage {
+    /// A value serialized to AMF3, which gets copied into every worker receiving it.
+    Copied(Vec<u8>),
+
+    /// An object that is passed by reference.
+    Shared(SharedObject),
+}
+
+/// The object representing a shared object in this worker.
+#[derive(Collect)]
+#[collect(no_drop)]
+struct LocalObject<'gc> {
+    object: Avm2Object<'gc>,
+
+    #[collect(require_static)]
+    shared: SharedObject,
+
+    /// For shareable `ByteArray`s, the contents of the shared buffer when they were
+    /// last synchronized.
+    synced_bytes: Vec<u8>,
+}
+
+/// The background workers created by a player, terminated when it goes away.
+#[derive(Default)]
+struct ChildWorkers(Vec<Arc<SharedWorker>>);
+
+impl Drop for ChildWorkers {
+    fn drop(&mut self) {
+        for worker in &self.0 {
+            worker.terminate();
+        }
+    }
+}
+
+/// Manages the worker a player runs, and the objects it shares with other workers.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct Workers<'gc> {
+    /// The worker running in this player.
+    #[collect(require_static)]
+    current: Arc<SharedWorker>,
+
+    #[collect(require_static)]
+    children: ChildWorkers,
+
+    /// The `WorkerDomain.current` singleton.
+    worker_domain: Option<Avm2Object<'gc>>,
+
+    objects: Vec<LocalObject<'gc>>,
+}
+
+impl Default for Workers<'_> {
+    fn default() -> Self {
+        Self::new(SharedWorker::primordial())
+    }
+}
+
+impl<'gc> Workers<'gc> {
+    pub(crate) fn new(current: Arc<SharedWorker>) -> Self {
+        Self {
+            current,
+            children: ChildWorkers::default(),
+            worker_domain: None,
+            objects: Vec::new(),
+        }
+    }
+
+    /// Terminates every background worker created so far, and forgets every shared object.
+    pub fn reset(&mut self) {
+        self.children = ChildWorkers::default();
+        self.worker_domain = None;
+        self.objects.clear();
+    }
+
+    fn find(&self, shared: &SharedObject) -> Option<Avm2Object<'gc>> {
+        self.objects
+            .iter()
+            .find(|local| local.shared.ptr_eq(shared))
+            .map(|local| local.object)
+    }
+
+    fn shared(&self, object: Avm2Object<'gc>) -> Option<&SharedObject> {
+        self.objects
+            .iter()
+            .find(|local| Avm2Object::ptr_eq(local.object, object))
+            .map(|local| &local.shared)
+    }
+
+    fn worker(&self, object: Avm2Object<'gc>) -> Option<Arc<SharedWorker>> {
+        match self.shared(object) {
+            Some(SharedObject::Worker(worker)) => Some(worker.clone()),
+            _ => None,
+        }
+    }
+
+    fn channel(&self, object: Avm2Object<'gc>) -> Option<Arc<SharedChannel>> {
+        match self.shared(object) {
+            Some(SharedObject::Channel(channel)) => Some(channel.clone()),
+            _ => None,
+        }
+    }
+
+    fn mutex(&self, object: Avm2Object<'gc>) -> Option<Arc<SharedMutex>> {
+        match self.shared(object) {
+            Some(SharedObject::Mutex(mutex)) => Some(mutex.clone()),
+            _ => None,
+        }
+    }
+
+    fn condition(&self, object: Avm2Object<'gc>) -> Option<Arc<SharedCondition>> {
+        match self.shared(object) {
+            Some(SharedObject::Condition(condition)) => Some(condition.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the object representing a shared object in this worker, creating it
+    /// the first time the shared object is seen.
+    fn object_for(
+        activation: &mut Activation<'_, 'gc>,
+        shared: SharedObject,
+    ) -> Result<Avm2Object<'gc>, Avm2Error<'gc>> {
+        if let Some(object) = activation.context.workers.find(&shared) {
+            return Ok(object);
+        }
+
+        let classes = activation.avm2().classes();
+        let (worker, messagechannel, mutex, condition) = (
+            classes.worker,
+            classes.messagechannel,
+            classes.mutex,
+            classes.condition,
+        );
+        let mut synced_bytes = Vec::new();
+        let object = match &shared {
+            SharedObject::ByteArray(bytes) => {
+                synced_bytes = bytes.0.lock().unwrap().clone();
+                let mut storage = ByteArrayStorage::from_vec(synced_bytes.clone());
+                storage.set_shareable(true);
+                ByteArrayObject::from_storage(activation, storage)?
+            }
+            SharedObject::Worker(_) => Self::construct(activation, worker)?,
+            SharedObject::Channel(_) => Self::construct(activation, messagechannel)?,
+            // The constructors of these would create a new mutex or condition.
+            SharedObject::Mutex(_) => scriptobject_allocator(mutex, activation)?,
+            SharedObject::Condition(_) => scriptobject_allocator(condition, activation)?,
+        };
+
+        activation.context.workers.objects.push(LocalObject {
+            object,
+            shared,
+            synced_bytes,
+        });
+        Ok(object)
+    }
+
+    fn construct(
+        activation: &mut Activation<'_, 'gc>,
+        class: ClassObject<'gc>,
+    ) -> Result<Avm2Object<'gc>, Avm2Error<'gc>> {
+        let object = scriptobject_allocator(class, activation)?;
+        class.call_init(object.into(), &[], activation)?;
+        Ok(object)
+    }
+
+    fn register(&mut self, object: Avm2Object<'gc>, shared: SharedObject) {
+        self.objects.push(LocalObject {
+            object,
+            shared,
+            synced_bytes: Vec::new(),
+        });
+    }
+
+    /// Copies the shareable `ByteArray`s modified by this worker since the last
+    /// synchronization to their shared buffers.
+    fn sync_bytes_out(&mut self) {
+        for local in &mut self.objects {
+            let SharedObject::ByteArray(shared) = &local.shared else {
+                continue;
+            };
+            let Some(storage) = local.object.as_bytearray() else {
+                continue;
+            };
+            if storage.bytes() != local.synced_bytes.as_slice() {
+                local.synced_bytes = storage.bytes().to_vec();
+                *shared.0.lock().unwrap() = local.synced_bytes.clone();
+            }
+        }
+    }
+
+    /// Copies the shared buffers modified by other workers since the last
+    /// synchronization to the shareable `ByteArray`s of this worker.
+    fn sync_bytes_in(&mut self) {
+        for local in &mut self.objects {
+            let SharedObject::ByteArray(shared) = &local.shared else {
+                continue;
+            };
+            let bytes = shared.0.lock().unwrap();
+            if *bytes != local.synced_bytes {
+                if let Some(mut storage) = local.object.as_bytearray_mut() {
+                    storage.set_length(bytes.len());
+                    storage.bytes_mut().copy_from_slice(&bytes);
+                }
+                local.synced_bytes = bytes.clone();
+            }
+        }
+    }
+
+    fn message(
+        activation: &mut Activation<'_, 'gc>,
+        value: Avm2Value<'gc>,
+    ) -> Result<WorkerMessage, Avm2Error<'gc>> {
+        if let Some(object) = value.as_object() {
+            if let Some(shared) = Self::share(activation, object) {
+                return Ok(WorkerMessage::Shared(shared));
+            }
+        }
+
+        let amf = serialize_value(activation, value, AMFVersion::AMF3, &mut Default::default());
+        match amf.and_then(|amf| serialize_to_bytes(amf, AMFVersion::AMF3)) {
+            Some(bytes) => Ok(WorkerMessage::Copied(bytes)),
+            None => Err(make_error_2004(activation, Error2004Type::ArgumentError)),
+        }
+    }
+
+    /// Returns the shared object an object stands for, if it's passed by reference.
+    fn share(
+        activation: &mut Activation<'_, 'gc>,
+        object: Avm2Object<'gc>,
+    ) -> Option<SharedObject> {
+        let workers = &mut *activation.context.workers;
+        if let Some(shared) = workers.shared(object) {
+            return Some(shared.clone());
+        }
+
+        // Shareable `ByteArray`s get a shared buffer the first time they're passed on.
+        let bytes = {
+            let storage = object.as_bytearray()?;
+            if !storage.shareable() {
+                return None;
+            }
+            storage.bytes().to_vec()
+        };
+        let shared = SharedObject::ByteArray(Arc::new(SharedBytes(Mutex::new(bytes.clone()))));
+        workers.objects.push(LocalObject {
+            object,
+            shared: shared.clone(),
+            synced_bytes: bytes,
+        });
+        Some(shared)
+    }
+
+    fn message_to_value(
+        activation: &mut Activation<'_, 'gc>,
+        message: WorkerMessage,
+    ) -> Result<Avm2Value<'gc>, Avm2Error<'gc>> {
+        match message {
+            WorkerMessage::Copied(bytes) => {
+                let (_, amf) = AMF3Decoder::default()
+                    .parse_single_element(&bytes)
+                    .map_err(|_| "Error: Invalid worker message")?;
+                deserialize_value(activation, &amf)
+            }
+            WorkerMessage::Shared(shared) => Ok(Self::object_for(activation, shared)?.into()),
+        }
+    }
+
+    /// Returns the worker that the currently running code belongs to.
+    pub fn current(
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Avm2Object<'gc>, Avm2Error<'gc>> {
+        let current = activation.context.workers.current.clone();
+        Self::object_for(activation, SharedObject::Worker(current))
+    }
+
+    /// Returns the `WorkerDomain.current` singleton.
+    pub fn worker_domain(
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Avm2Object<'gc>, Avm2Error<'gc>> {
+        if let Some(worker_domain) = activation.context.workers.worker_domain {
+            return Ok(worker_domain);
+        }
+
+        // The constructor of WorkerDomain throws, and it has nothing to initialize.
+        let class = activation.avm2().classes().workerdomain;
+        let object = scriptobject_allocator(class, activation)?;
+        activation.context.workers.worker_domain = Some(object);
+        Ok(object)
+    }
+
+    /// Creates a new worker in the `new` state, which will run the given SWF once started.
+    pub fn create_worker(
+        activation: &mut Activation<'_, 'gc>,
+        swf: Vec<u8>,
+    ) -> Result<Avm2Object<'gc>, Avm2Error<'gc>> {
+        let parent = activation.context.workers.current.clone();
+        let worker = Arc::new(SharedWorker {
+            registry: parent.registry.clone(),
+            url: activation.caller_movie_or_root().url().to_string(),
+            parent: Some(parent),
+            swf,
+            state: Mutex::new(WorkerState::New),
+            shared_properties: Mutex::new(HashMap::new()),
+            events: Mutex::new(Vec::new()),
+        });
+        activation.context.workers.children.0.push(worker.clone());
+        Self::object_for(activation, SharedObject::Worker(worker))
+    }
+
+    /// Returns the primordial worker followed by every running background worker.
+    pub fn list(
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Vec<Avm2Object<'gc>>, Avm2Error<'gc>> {
+        let registry = activation.context.workers.current.registry.clone();
+        let running = registry.running.lock().unwrap().clone();
+        let mut list = vec![];
+        for worker in registry.primordial.upgrade().into_iter().chain(running) {
+            list.push(Self::object_for(activation, SharedObject::Worker(worker))?);
+        }
+        Ok(list)
+    }
+
+    pub fn is_primordial(&self, object: Avm2Object<'gc>) -> bool {
+        self.worker(object)
+            .is_some_and(|worker| worker.is_primordial())
+    }
+
+    pub fn worker_state(&self, object: Avm2Object<'gc>) -> &'static str {
+        self.worker(object)
+            .map_or(WorkerState::New, |worker| worker.state())
+            .as_str()
+    }
+
+    /// Starts a new worker. Returns `false` if it was started already.
+    pub fn start(&mut self, object: Avm2Object<'gc>) -> bool {
+        self.worker(object).is_some_and(|worker| worker.start())
+    }
+
+    /// Terminates a running background worker. Returns `false` if it wasn't running.
+    pub fn terminate(&mut self, object: Avm2Object<'gc>) -> bool {
+        self.worker(object).is_some_and(|worker| worker.terminate())
+    }
+
+    pub fn get_shared_property(
+        activation: &mut Activation<'_, 'gc>,
+        object: Avm2Object<'gc>,
+        key: &str,
+    ) -> Result<Avm2Value<'gc>, Avm2Error<'gc>> {
+        let message = activation
+            .context
+            .workers
+            .worker(object)
+            .and_then(|worker| worker.shared_properties.lock().unwrap().get(key).cloned());
+        activation.context.workers.sync_bytes_in();
+        match message {
+            Some(message) => Self::message_to_value(activation, message),
+            None => Ok(Avm2Value::Undefined),
+        }
+    }
+
+    pub fn set_shared_property(
+        activation: &mut Activation<'_, 'gc>,
+        object: Avm2Object<'gc>,
+        key: String,
+        value: Avm2Value<'gc>,
+    ) -> Result<(), Avm2Error<'gc>> {
+        let message = Self::message(activation, value)?;
+        let workers = &mut *activation.context.workers;
+        workers.sync_bytes_out();
+        if let Some(worker) = workers.worker(object) {
+            worker
+                .shared_properties
+                .lock()
+                .unwrap()
+                .insert(key, message);
+        }
+        Ok(())
+    }
+
+    /// Creates a channel through which `sender` can send messages to `receiver`.
+    pub fn create_channel(
+        activation: &mut Activation<'_, 'gc>,
+        sender: Avm2Object<'gc>,
+        receiver: Avm2Object<'gc>,
+    ) -> Result<Avm2Value<'gc>, Avm2Error<'gc>> {
+        let workers = &activation.context.workers;
+        let (Some(sender), Some(receiver)) = (workers.worker(sender), workers.worker(receiver))
+        else {
+            return Ok(Avm2Value::Null);
+        };
+
+        let channel = Arc::new(SharedChannel {
+            sender,
+            receiver,
+            queue: Mutex::new(ChannelQueue {
+                messages: VecDeque::new(),
+                state: ChannelState::Open,
+            }),
+            changed: Condvar::new(),
+        });
+        Ok(Self::object_for(activation, SharedObject::Channel(channel))?.into())
+    }
+
+    /// Queues a message on the channel, waiting first until at most `queue_limit`
+    /// messages are queued if it's not negative.
+    ///
+    /// Returns `false` if the channel isn't open.
+    pub fn send(
+        activation: &mut Activation<'_, 'gc>,
+        object: Avm2Object<'gc>,
+        value: Avm2Value<'gc>,
+        queue_limit: i32,
+    ) -> Result<bool, Avm2Error<'gc>> {
+        let Some(channel) = activation.context.workers.channel(object) else {
+            return Ok(false);
+        };
+        let message = Self::message(activation, value)?;
+
+        let workers = &mut *activation.context.workers;
+        workers.sync_bytes_out();
+        let queue_limit = usize::try_from(queue_limit).ok();
+        Ok(channel.send(message, queue_limit, &workers.current))
+    }
+
+    /// Takes the oldest message off the channel, waiting for one if `block` is set.
+    pub fn receive(
+        activation: &mut Activation<'_, 'gc>,
+        object: Avm2Object<'gc>,
+        block: bool,
+    ) -> Result<Avm2Value<'gc>, Avm2Error<'gc>> {
+        let workers = &mut *activation.context.workers;
+        let Some(channel) = workers.channel(object) else {
+            return Ok(Avm2Value::Null);
+        };
+
+        let message = channel.receive(block, &workers.current);
+        workers.sync_bytes_in();
+        match message {
+            Some(message) => Self::message_to_value(activation, message),
+            None => Ok(Avm2Value::Null),
+        }
+    }
+
+    pub fn message_available(&self, object: Avm2Object<'gc>) -> bool {
+        self.channel(object)
+            .is_some_and(|channel| !channel.queue.lock().unwrap().messages.is_empty())
+    }
+
+    pub fn channel_state(&self, object: Avm2Object<'gc>) -> &'static str {
+        self.channel(object)
+            .map_or(ChannelState::Closed, |channel| {
+                channel.queue.lock().unwrap().state
+            })
+            .as_str()
+    }
+
+    /// Stops the channel from accepting new messages.
+    pub fn close(&mut self, object: Avm2Object<'gc>) {
+        if let Some(channel) = self.channel(object) {
+            channel.close();
+        }
+    }
+
+    /// Creates the mutex of a newly constructed `Mutex` object.
+    pub fn create_mutex(&mut self, object: Avm2Object<'gc>) {
+        self.register(object, SharedObject::Mutex(Default::default()));
+    }
+
+    pub fn lock(&mut self, object: Avm2Object<'gc>) {
+        if let Some(mutex) = self.mutex(object) {
+            mutex.lock(&self.current);
+            self.sync_bytes_in();
+        }
+    }
+
+    pub fn try_lock(&mut self, object: Avm2Object<'gc>) -> bool {
+        let locked = self.mutex(object).is_some_and(|mutex| mutex.try_lock());
+        if locked {
+            self.sync_bytes_in();
+        }
+        locked
+    }
+
+    /// Returns `false` if the mutex isn't locked by the current worker.
+    pub fn unlock(&mut self, object: Avm2Object<'gc>) -> bool {
+        let Some(mutex) = self.mutex(object) else {
+            return false;
+        };
+        if !mutex.is_owned_by_current_worker() {
+            return false;
+        }
+        self.sync_bytes_out();
+        mutex.unlock()
+    }
+
+    /// Creates the condition of a newly constructed `Condition` object.
+    pub fn create_condition(&mut self, object: Avm2Object<'gc>, mutex: Avm2Object<'gc>) {
+        let Some(mutex) = self.mutex(mutex) else {
+            return;
+        };
+        let condition = SharedCondition {
+            mutex,
+            waiters: Default::default(),
+            notified: Condvar::new(),
+        };
+        self.register(object, SharedObject::Condition(Arc::new(condition)));
+    }
+
+    pub fn condition_mutex(
+        activation: &mut Activation<'_, 'gc>,
+        object: Avm2Object<'gc>,
+    ) -> Result<Avm2Value<'gc>, Avm2Error<'gc>> {
+        match activation.context.workers.condition(object) {
+            Some(condition) => {
+                let mutex = SharedObject::Mutex(condition.mutex.clone());
+                Ok(Self::object_for(activation, mutex)?.into())
+            }
+            None => Ok(Avm2Value::Null),
+        }
+    }
+
+    /// Returns whether the mutex of the condition is locked by the current worker.
+    pub fn owns_condition_mutex(&self, object: Avm2Object<'gc>) -> bool {
+        self.condition(object)
+            .is_some_and(|condition| condition.mutex.is_owned_by_current_worker())
+    }
+
+    /// Waits until the condition is notified, or `timeout` passed.
+    ///
+    /// Returns whether the condition was notified.
+    pub fn wait(&mut self, object: Avm2Object<'gc>, timeout: Option<Duration>) -> bool {
+        let Some(condition) = self.condition(object) else {
+            return false;
+        };
+        self.sync_bytes_out();
+        let notified = condition.wait(timeout, &self.current);
+        self.sync_bytes_in();
+        notified
+    }
+
+    pub fn notify(&self, object: Avm2Object<'gc>, all: bool) {
+        if let Some(condition) = self.condition(object) {
+            if all {
+                condition.notify_all();
+            } else {
+                condition.notify();
+            }
+        }
+    }
+
+    /// Makes a navigator request of a background worker with the navigator of this one.
+    fn handle_navigator_request(context: &mut UpdateContext<'gc>, request: NavigatorRequest) {
+        match request {
+            NavigatorRequest::Navigate {
+                url,
+                target,
+                vars_method,
+            } => context
+                .navigator
+                .navigate_to_url(&url, &target, vars_method),
+            NavigatorRequest::Fetch(request, sender) => {
+                let response = context.navigator.fetch(request);
+                context.navigator.spawn_future(Box::pin(async move {
+                    let result = match response.await {
+                        Ok(response) => {
+                            let url = response.url().into_owned();
+                            let text_encoding = response.text_encoding();
+                            let status = response.status();
+                            let redirected = response.redirected();
+                            match response.body().await {
+                                Ok(body) => Ok(WorkerResponse {
+                                    url,
+                                    body: Some(body),
+                                    text_encoding,
+                                    status,
+                                    redirected,
+                                }),
+                                Err(error) => Err(FetchError::new(url, error)),
+                            }
+                        }
+                        Err(response) => Err(FetchError::new(response.url, response.error)),
+                    };
+                    // The worker may have been terminated meanwhile.
+                    let _ = sender.send(result).await;
+                    Ok(())
+                }));
+            }
+        }
+    }
+
+    /// Delivers the events sent to this worker since the last tick, and synchronizes
+    /// shareable `ByteArray`s.
+    pub fn update_workers(context: &mut UpdateContext<'gc>) {
+        context.workers.sync_bytes_out();
+        context.workers.sync_bytes_in();
+
+        let current = context.workers.current.clone();
+        if current.is_primordial() {
+            let traces = std::mem::take(&mut *current.registry.traces.lock().unwrap());
+            for trace in traces {
+                context.log.avm_trace(&trace);
+            }
+
+            let requests = std::mem::take(&mut *current.registry.requests.lock().unwrap());
+            for request in requests {
+                Self::handle_navigator_request(context, request);
+            }
+        }
+
+        let events = std::mem::take(&mut *current.events.lock().unwrap());
+        for (target, event_type) in events {
+            // Only objects known to this worker can have listeners.
+            let Some(target) = context.workers.find(&target) else {
+                continue;
+            };
+            let event = EventObject::bare_default_event(context, event_type);
+            Avm2::dispatch_event(context, event, target);
+        }
+    }
+}
@@ -3,8 +3,9 @@ use crate::gui::{GuiController, MENU_HEIGHT};
 use crate::player::{LaunchOptions, PlayerController};
 use crate::preferences::GlobalPreferences;
 use crate::util::{
-    get_screen_size, gilrs_button_to_gamepad_button, parse_url, plot_stats_in_tracy,
-    winit_to_ruffle_key_code, winit_to_ruffle_text_control,
+    get_screen_size, gilrs_axis_to_gamepad_axis, gilrs_button_to_gamepad_axis,
+    gilrs_button_to_gamepad_button, parse_url, plot_stats_in_tracy, winit_to_ruffle_key_code,
+    winit_to_ruffle_text_control,
 };
 use anyhow::Error;
 use gilrs::{Event, EventType, Gilrs};
@@ -342,24 +343,52 @@ impl MainWindow {
         }
     }
 
+    /// Tell a newly loaded movie about the gamepads that are already connected.
+    fn connect_gamepads(&mut self, gilrs: &Gilrs) {
+        for (id, _) in gilrs.gamepads() {
+            let device = usize::from(id) as u32;
+            self.player
+                .handle_event(PlayerEvent::GamepadConnected { device });
+        }
+    }
+
     fn about_to_wait(&mut self, gilrs: Option<&mut Gilrs>) {
-        if let Some(Event { event, .. }) = gilrs.and_then(|gilrs| gilrs.next_event()) {
-            match event {
-                EventType::ButtonPressed(button, _) => {
-                    if let Some(button) = gilrs_button_to_gamepad_button(button) {
-                        self.player
-                            .handle_event(PlayerEvent::GamepadButtonDown { button });
-                        self.check_redraw();
+        if let Some(gilrs) = gilrs {
+            while let Some(Event { id, event, .. }) = gilrs.next_event() {
+                let device = usize::from(id) as u32;
+                let player_event = match event {
+                    EventType::Connected => Some(PlayerEvent::GamepadConnected { device }),
+                    EventType::Disconnected => Some(PlayerEvent::GamepadDisconnected { device }),
+                    EventType::ButtonPressed(button, _) => gilrs_button_to_gamepad_button(button)
+                        .map(|button| PlayerEvent::GamepadButtonDown { device, button }),
+                    EventType::ButtonReleased(button, _) => gilrs_button_to_gamepad_button(button)
+                        .map(|button| PlayerEvent::GamepadButtonUp { device, button }),
+                    // Triggers are reported as buttons with an analog value.
+                    EventType::ButtonChanged(button, value, _) => {
+                        gilrs_button_to_gamepad_axis(button).map(|axis| {
+                            PlayerEvent::GamepadAxisMotion {
+                                device,
+                                axis,
+                                value: value.into(),
+                            }
+                        })
                     }
-                }
-                EventType::ButtonReleased(button, _) => {
-                    if let Some(button) = gilrs_button_to_gamepad_button(button) {
-                        self.player
-                            .handle_event(PlayerEvent::GamepadButtonUp { button });
-                        self.check_redraw();
+                    EventType::AxisChanged(axis, value, _) => {
+                        gilrs_axis_to_gamepad_axis(axis, value).map(|(axis, value)| {
+                            PlayerEvent::GamepadAxisMotion {
+                                device,
+                                axis,
+                                value,
+                            }
+                        })
                     }
+                    _ => None,
+                };
+
+                if let Some(player_event) = player_event {
+                    self.player.handle_event(player_event);
+                    self.check_redraw();
                 }
-                _ => {}
             }
         }
 
@@ -371,12 +400,11 @@ impl MainWindow {
             let dt = new_time.duration_since(self.time).as_nanos();
             if dt > 0 {
                 self.time = new_time;
-                if let Some(mut player) = self.player.get() {
-                    player.tick(dt as f64 / 1_000_000.0);
-                    self.next_frame_time = Some(new_time + player.time_til_next_frame());
-                } else {
-                    self.next_frame_time = None;
-                }
+                self.player.tick(dt as f64 / 1_000_000.0);
+                self.next_frame_time = self
+                    .player
+                    .get()
+                    .map(|player| new_time + player.time_til_next_frame());
                 self.check_redraw();
             }
         }
@@ -528,7 +556,10 @@ impl ApplicationHandler<RuffleEvent> for App {
             (Some(main_window), RuffleEvent::TaskPoll) => main_window.player.poll(),
 
             (Some(main_window), RuffleEvent::OnMetadata(swf_header)) => {
-                main_window.on_metadata(swf_header)
+                main_window.on_metadata(swf_header);
+                if let Some(gilrs) = &self.gilrs {
+                    main_window.connect_gamepads(gilrs);
+                }
             }
 
             (Some(main_window), RuffleEvent::ContextMenuItemClicked(index)) => {
@@ -4,11 +4,11 @@ use anyhow::{anyhow, Error};
 use clap::{Parser, ValueEnum};
 use ruffle_core::backend::navigator::SocketMode;
 use ruffle_core::config::Letterbox;
-use ruffle_core::events::{GamepadButton, KeyCode};
+use ruffle_core::events::{GamepadButton, GamepadStick, KeyCode};
 use ruffle_core::{LoadBehavior, PlayerRuntime, StageAlign, StageScaleMode};
 use ruffle_render::quality::StageQuality;
 use ruffle_render_wgpu::clap::{GraphicsBackend, PowerPreference};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Duration;
 use url::Url;
@@ -151,6 +151,16 @@ pub struct Opt {
     #[clap(long = "tcp-connections")]
     pub tcp_connections: Option<SocketMode>,
 
+    /// Require a socket policy file to allow each TCP Socket connection, like Flash Player.
+    /// The policy file is requested from port 843 of the server, then from the destination port.
+    #[clap(long, action)]
+    pub socket_policy_files: bool,
+
+    /// Require a crossdomain.xml policy file to allow each cross-domain URL load, like Flash Player.
+    /// Images loaded without LoaderContext.checkPolicyFile can then not be drawn into a BitmapData.
+    #[clap(long, action)]
+    pub url_policy_files: bool,
+
     /// Replace all embedded HTTP URLs with HTTPS.
     #[clap(long, action)]
     pub upgrade_to_https: bool,
@@ -238,11 +248,56 @@ pub struct Opt {
     )]
     pub gamepad_button: Vec<(GamepadButton, KeyCode)>,
 
+    /// Maps the movement of a gamepad stick to other input.
+    /// This can be used to play mouse-only games with a gamepad, for example with
+    /// --gamepad-stick left=arrow-keys --gamepad-stick right=mouse.
+    ///
+    /// The sticks are `left` and `right`, and the possible mappings are:
+    /// - arrow-keys: holds down the arrow keys in the direction the stick is pushed
+    /// - mouse: moves a virtual mouse cursor, with the south button as the left mouse button
+    #[clap(
+        long,
+        value_parser(parse_gamepad_stick),
+        verbatim_doc_comment,
+        value_name = "STICK>=<MAPPING"
+    )]
+    pub gamepad_stick: Vec<(GamepadStick, GamepadStickMode)>,
+
+    /// How far a gamepad stick has to be moved, from 0.0 to 1.0,
+    /// before it has any effect when mapped with --gamepad-stick.
+    #[clap(
+        long,
+        default_value = "0.25",
+        value_name = "DEADZONE",
+        value_parser(parse_gamepad_stick_deadzone)
+    )]
+    pub gamepad_stick_deadzone: f64,
+
+    /// The maximum speed of the virtual mouse cursor moved with --gamepad-stick, in pixels per second.
+    #[clap(long, default_value = "800", value_name = "SPEED")]
+    pub virtual_mouse_speed: f64,
+
     /// Disable AVM2 optimizer.
     /// Note that some early opcode conversions
     /// (like inlining constant pool entries) can't be disabled.
     #[clap(long)]
     pub no_avm2_optimizer: bool,
+
+    /// Record the mouse, keyboard and text input given to the movie to the given file,
+    /// frame by frame, in the `input.json` format used by the regression tests.
+    #[clap(long, value_name = "FILE")]
+    pub record_input: Option<PathBuf>,
+
+    /// Replay input recorded with `--record-input` (or written for a regression test),
+    /// frame by frame. Input from the window is ignored until the replay is finished.
+    #[clap(long, value_name = "FILE")]
+    pub replay_input: Option<PathBuf>,
+
+    /// Run the movie deterministically, like the regression tests do.
+    /// Frames are run one at a time with a fixed time step, the clock only advances along
+    /// with the frames, and random numbers use a fixed seed.
+    #[clap(long)]
+    pub deterministic: bool,
 }
 
 fn parse_movie_file_or_url(path: &str) -> Result<Url, Error> {
@@ -253,33 +308,41 @@ fn parse_duration_seconds(value: &str) -> Result<Duration, Error> {
     Ok(Duration::from_secs_f64(value.parse()?))
 }
 
+fn parse_gamepad_stick_deadzone(value: &str) -> Result<f64, Error> {
+    let deadzone: f64 = value.parse()?;
+    if !(0.0..1.0).contains(&deadzone) {
+        return Err(anyhow!("deadzone must be at least 0.0 and less than 1.0"));
+    }
+    Ok(deadzone)
+}
+
 fn parse_align(value: &str) -> Result<StageAlign, Error> {
     value
         .parse()
         .map_err(|_| anyhow::anyhow!("Invalid stage alignment"))
 }
 
+fn to_aliases<T: ValueEnum>(variants: &[T]) -> String {
+    let aliases: Vec<String> = variants
+        .iter()
+        .map(|variant| {
+            variant
+                .to_possible_value()
+                .expect("Must have a PossibleValue")
+                .get_name_and_aliases()
+                .next()
+                .expect("Must have one alias")
+                .to_owned()
+        })
+        .collect();
+    aliases.join(", ")
+}
+
 fn parse_gamepad_button(mapping: &str) -> Result<(GamepadButton, KeyCode), Error> {
     let pos = mapping.find('=').ok_or_else(|| {
         anyhow!("invalid <gamepad button>=<key name>: no `=` found in `{mapping}`")
     })?;
 
-    fn to_aliases<T: ValueEnum>(variants: &[T]) -> String {
-        let aliases: Vec<String> = variants
-            .iter()
-            .map(|variant| {
-                variant
-                    .to_possible_value()
-                    .expect("Must have a PossibleValue")
-                    .get_name_and_aliases()
-                    .next()
-                    .expect("Must have one alias")
-                    .to_owned()
-            })
-            .collect();
-        aliases.join(", ")
-    }
-
     let button = <GamepadButton as ValueEnum>::from_str(&mapping[..pos], true).map_err(|err| {
         anyhow!(
             "Could not parse <gamepad button>: {err}\n  The possible values are: {}",
@@ -295,6 +358,26 @@ fn parse_gamepad_button(mapping: &str) -> Result<(GamepadButton, KeyCode), Error
     Ok((button, KeyCode::from_code(key_code as u32)))
 }
 
+fn parse_gamepad_stick(mapping: &str) -> Result<(GamepadStick, GamepadStickMode), Error> {
+    let (stick, mode) = mapping
+        .split_once('=')
+        .ok_or_else(|| anyhow!("invalid <stick>=<mapping>: no `=` found in `{mapping}`"))?;
+
+    let stick = <GamepadStick as ValueEnum>::from_str(stick, true).map_err(|err| {
+        anyhow!(
+            "Could not parse <stick>: {err}\n  The possible values are: {}",
+            to_aliases(GamepadStick::value_variants())
+        )
+    })?;
+    let mode = GamepadStickMode::from_str(mode, true).map_err(|err| {
+        anyhow!(
+            "Could not parse <mapping>: {err}\n  The possible values are: {}",
+            to_aliases(GamepadStickMode::value_variants())
+        )
+    })?;
+    Ok((stick, mode))
+}
+
 impl Opt {
     pub fn trace_path(&self) -> Option<&Path> {
         None
@@ -372,6 +455,13 @@ impl FromStr for OpenUrlMode {
     }
 }
 
+/// What a gamepad stick is mapped to with `--gamepad-stick`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum GamepadStickMode {
+    ArrowKeys,
+    Mouse,
+}
+
 // TODO The following enum exists in order to preserve
 //   the behavior of mapping gamepad buttons,
 //   We should probably do something smarter here.
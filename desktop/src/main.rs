@@ -15,6 +15,8 @@ mod gui;
 mod log;
 mod player;
 mod preferences;
+mod recorder;
+mod replayer;
 #[cfg(feature = "tracy")]
 mod tracy;
 mod util;
@@ -4,14 +4,17 @@ use crate::backends::{
 };
 use crate::cli::FilesystemAccessMode;
 use crate::cli::GameModePreference;
+use crate::cli::GamepadStickMode;
 use crate::custom_event::RuffleEvent;
 use crate::gui::{FilePicker, MovieView};
 use crate::preferences::GlobalPreferences;
+use crate::recorder::InputRecorder;
+use crate::replayer::InputReplayer;
 use crate::{CALLSTACK, RENDER_INFO, SWF_INFO};
 use anyhow::anyhow;
 use ruffle_core::backend::navigator::SocketMode;
 use ruffle_core::config::Letterbox;
-use ruffle_core::events::{GamepadButton, KeyCode};
+use ruffle_core::events::{GamepadButton, GamepadStick, GamepadStickMapping, KeyCode};
 use ruffle_core::{DefaultFont, LoadBehavior, Player, PlayerBuilder, PlayerEvent};
 use ruffle_frontend_utils::backends::audio::CpalAudioBackend;
 use ruffle_frontend_utils::backends::executor::{AsyncExecutor, PollRequester};
@@ -37,6 +40,9 @@ use url::Url;
 use winit::event_loop::EventLoopProxy;
 use winit::window::Window;
 
+/// The maximum number of frames to run at once when using a fixed time step.
+const MAX_FRAMES_PER_TICK: u32 = 5;
+
 /// Options used when creating a Player (& passed through to a PlayerBuilder).
 /// These may be primed by command line arguments.
 #[derive(Debug, Clone)]
@@ -45,12 +51,18 @@ pub struct LaunchOptions {
     pub proxy: Option<Url>,
     pub socket_allowed: HashSet<String>,
     pub tcp_connections: Option<SocketMode>,
+    pub socket_policy_files: bool,
+    pub url_policy_files: bool,
     pub fullscreen: bool,
     pub save_directory: PathBuf,
     pub cache_directory: PathBuf,
     pub filesystem_access_mode: FilesystemAccessMode,
     pub gamepad_button_mapping: HashMap<GamepadButton, KeyCode>,
+    pub gamepad_stick_mapping: HashMap<GamepadStick, GamepadStickMapping>,
     pub avm2_optimizer_enabled: bool,
+    pub record_input: Option<PathBuf>,
+    pub replay_input: Option<PathBuf>,
+    pub deterministic: bool,
 }
 
 impl From<&GlobalPreferences> for LaunchOptions {
@@ -99,8 +111,29 @@ impl From<&GlobalPreferences> for LaunchOptions {
             filesystem_access_mode: value.cli.filesystem_access_mode,
             socket_allowed: HashSet::from_iter(value.cli.socket_allow.iter().cloned()),
             tcp_connections: value.cli.tcp_connections,
+            socket_policy_files: value.cli.socket_policy_files,
+            url_policy_files: value.cli.url_policy_files,
             gamepad_button_mapping: HashMap::from_iter(value.cli.gamepad_button.iter().cloned()),
+            gamepad_stick_mapping: value
+                .cli
+                .gamepad_stick
+                .iter()
+                .map(|(stick, mode)| {
+                    let deadzone = value.cli.gamepad_stick_deadzone;
+                    let mapping = match mode {
+                        GamepadStickMode::ArrowKeys => GamepadStickMapping::ArrowKeys { deadzone },
+                        GamepadStickMode::Mouse => GamepadStickMapping::VirtualMouse {
+                            deadzone,
+                            speed: value.cli.virtual_mouse_speed,
+                        },
+                    };
+                    (*stick, mapping)
+                })
+                .collect(),
             avm2_optimizer_enabled: !value.cli.no_avm2_optimizer,
+            record_input: value.cli.record_input.clone(),
+            replay_input: value.cli.replay_input.clone(),
+            deterministic: value.cli.deterministic,
         }
     }
 }
@@ -121,6 +154,16 @@ impl PollRequester for WinitWaker {
 struct ActivePlayer {
     player: Arc<Mutex<Player>>,
     executor: Arc<AsyncExecutor<WinitWaker>>,
+    input_recorder: Option<InputRecorder>,
+    input_replayer: Option<InputReplayer>,
+
+    /// Whether frames are run one at a time with a fixed time step, instead of
+    /// letting the player catch up with the elapsed time however it sees fit.
+    fixed_time_step: bool,
+
+    /// The time, in milliseconds, that hasn't been spent on running frames yet.
+    /// Only used with a fixed time step.
+    frame_accumulator: f64,
 
     #[cfg(target_os = "linux")]
     _gamemode_session: crate::dbus::GameModeSession,
@@ -201,12 +244,18 @@ impl ActivePlayer {
                     proxy: opt.proxy.clone(),
                     socket_allowed: opt.socket_allowed.clone(),
                     tcp_connections: opt.tcp_connections,
+                    socket_policy_files: opt.socket_policy_files,
+                    url_policy_files: opt.url_policy_files,
                     fullscreen: opt.fullscreen,
                     save_directory: opt.save_directory.clone(),
                     cache_directory: opt.cache_directory.clone(),
                     filesystem_access_mode: opt.filesystem_access_mode,
                     gamepad_button_mapping: opt.gamepad_button_mapping.clone(),
+                    gamepad_stick_mapping: opt.gamepad_stick_mapping.clone(),
                     avm2_optimizer_enabled: opt.avm2_optimizer_enabled,
+                    record_input: opt.record_input.clone(),
+                    replay_input: opt.replay_input.clone(),
+                    deterministic: opt.deterministic,
                 })
             }
         };
@@ -290,6 +339,10 @@ impl ActivePlayer {
             builder = builder.with_gamepad_button_mapping(opt.gamepad_button_mapping.clone());
         }
 
+        if !opt.gamepad_stick_mapping.is_empty() {
+            builder = builder.with_gamepad_stick_mapping(opt.gamepad_stick_mapping.clone());
+        }
+
         builder = builder
             .with_navigator(navigator)
             .with_renderer(renderer)
@@ -326,7 +379,10 @@ impl ActivePlayer {
             .with_player_version(opt.player.player_version)
             .with_player_runtime(opt.player.player_runtime.unwrap_or_default())
             .with_frame_rate(opt.player.frame_rate)
-            .with_avm2_optimizer_enabled(opt.avm2_optimizer_enabled);
+            .with_avm2_optimizer_enabled(opt.avm2_optimizer_enabled)
+            .with_socket_policy_files(opt.socket_policy_files)
+            .with_url_policy_files(opt.url_policy_files)
+            .with_deterministic(opt.deterministic);
         let player = builder.build();
 
         window.set_title(&format!("Ruffle - {readable_name}"));
@@ -404,15 +460,45 @@ impl ActivePlayer {
             );
         }
 
+        let input_replayer =
+            opt.replay_input
+                .as_deref()
+                .and_then(|path| match InputReplayer::from_file(path) {
+                    Ok(replayer) => Some(replayer),
+                    Err(e) => {
+                        tracing::error!("Couldn't load input to replay from {path:?}: {e}");
+                        None
+                    }
+                });
+
         Self {
             player,
             executor,
+            input_recorder: opt.record_input.clone().map(InputRecorder::new),
+            fixed_time_step: opt.deterministic || input_replayer.is_some(),
+            input_replayer,
+            frame_accumulator: 0.0,
             #[cfg(target_os = "linux")]
             _gamemode_session: crate::dbus::GameModeSession::new(gamemode_enable),
         }
     }
 }
 
+impl Drop for ActivePlayer {
+    fn drop(&mut self) {
+        if let Some(recorder) = self.input_recorder.take() {
+            let frames_run = self
+                .player
+                .lock()
+                .map(|player| player.frames_run())
+                .unwrap_or_default();
+            if let Err(e) = recorder.finish(frames_run) {
+                tracing::error!("Couldn't save recorded input: {e}");
+            }
+        }
+    }
+}
+
 /// Owner of a Ruffle Player (via ActivePlayer),
 /// responsible for either creating, destroying or communicating with that player.
 pub struct PlayerController {
@@ -476,9 +562,20 @@ impl PlayerController {
         }
     }
 
-    pub fn handle_event(&self, event: PlayerEvent) -> bool {
-        if let Some(mut player) = self.get() {
-            if player.is_playing() {
+    pub fn handle_event(&mut self, event: PlayerEvent) -> bool {
+        if let Some(active_player) = &mut self.player {
+            let mut player = active_player
+                .player
+                .try_lock()
+                .expect("Player lock must be available");
+            let is_replaying = active_player
+                .input_replayer
+                .as_ref()
+                .is_some_and(|replayer| !replayer.is_finished());
+            if player.is_playing() && !is_replaying {
+                if let Some(recorder) = &mut active_player.input_recorder {
+                    recorder.record(&mut player, &event);
+                }
                 return player.handle_event(event);
             }
         }
@@ -486,6 +583,44 @@ impl PlayerController {
         false
     }
 
+    /// Runs the player for the given amount of time, in milliseconds.
+    pub fn tick(&mut self, dt: f64) {
+        let Some(active_player) = &mut self.player else {
+            return;
+        };
+        let mut player = active_player
+            .player
+            .try_lock()
+            .expect("Player lock must be available");
+
+        let frame_time = 1000.0 / player.frame_rate();
+        if !active_player.fixed_time_step || !frame_time.is_finite() {
+            player.tick(dt);
+            return;
+        }
+
+        // Give the player exactly one frame's worth of time at once, so that every frame
+        // runs the same way regardless of how fast we are.
+        active_player.frame_accumulator += dt;
+        let mut frames = 0;
+        while active_player.frame_accumulator >= frame_time && frames < MAX_FRAMES_PER_TICK {
+            let frames_run = player.frames_run();
+            player.tick(frame_time);
+            if player.frames_run() != frames_run {
+                if let Some(replayer) = &mut active_player.input_replayer {
+                    replayer.inject_frame(&mut player);
+                }
+            }
+            active_player.frame_accumulator -= frame_time;
+            frames += 1;
+        }
+
+        // Don't try to catch up if we've fallen too far behind.
+        if active_player.frame_accumulator >= frame_time {
+            active_player.frame_accumulator = 0.0;
+        }
+    }
+
     pub fn poll(&self) {
         if let Some(player) = &self.player {
             player.executor.poll_all()
@@ -0,0 +1,257 @@
+use anyhow::Error;
+use ruffle_core::events::MouseWheelDelta;
+use ruffle_core::{Player, PlayerEvent};
+use ruffle_input_format::{AutomatedEvent, MouseButton, MousePosition, TextControlCode};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+/// Records the input given to a player, frame by frame,
+/// in the format used by the `input.json` files of the regression tests.
+pub struct InputRecorder {
+    path: PathBuf,
+    events: Vec<AutomatedEvent>,
+
+    /// The number of `Wait` events recorded so far.
+    waits: u64,
+}
+
+impl InputRecorder {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            events: Vec::new(),
+            waits: 0,
+        }
+    }
+
+    /// Records an event that is about to be handled by the given player.
+    pub fn record(&mut self, player: &mut Player, event: &PlayerEvent) {
+        let Some(automated_event) = to_automated_event(event, |x, y| {
+            let (x, y) = player.viewport_to_stage_position(x, y);
+            MousePosition(x, y)
+        }) else {
+            return;
+        };
+
+        // Pasting depends on the contents of the clipboard, which have to be recorded too.
+        let clipboard = matches!(
+            automated_event,
+            AutomatedEvent::TextControl {
+                code: TextControlCode::Paste,
+            }
+        )
+        .then(|| player.ui_mut().clipboard_content());
+        self.push(player.frames_run(), automated_event, clipboard);
+    }
+
+    /// Writes all the recorded input to the file, up to the given frame.
+    pub fn finish(mut self, frames_run: u64) -> Result<(), Error> {
+        let writer = BufWriter::new(File::create(&self.path)?);
+        self.write(frames_run, writer)?;
+        tracing::info!("Saved recorded input to {}", self.path.display());
+        Ok(())
+    }
+
+    /// Records an event that happened after the given number of frames were run,
+    /// along with the clipboard contents it depends on, if any.
+    fn push(&mut self, frames_run: u64, event: AutomatedEvent, clipboard: Option<String>) {
+        self.wait_until(frames_run);
+        if let Some(text) = clipboard {
+            self.events.push(AutomatedEvent::SetClipboardText { text });
+        }
+        self.events.push(event);
+    }
+
+    /// Writes all the recorded input as JSON, up to the given frame.
+    fn write(&mut self, frames_run: u64, writer: impl Write) -> Result<(), Error> {
+        self.wait_until(frames_run);
+        serde_json::to_writer_pretty(writer, &self.events)?;
+        Ok(())
+    }
+
+    /// Adds `Wait` events, so that the next events are injected after the given frame.
+    ///
+    /// The tests inject the input of a frame after running it,
+    /// so events that happen before the first frame has run are injected after it.
+    fn wait_until(&mut self, frames_run: u64) {
+        let target = frames_run.saturating_sub(1);
+        while self.waits < target {
+            self.events.push(AutomatedEvent::Wait);
+            self.waits += 1;
+        }
+    }
+}
+
+/// Converts an event to its recorded form, using `position` to convert
+/// viewport coordinates to stage coordinates.
+fn to_automated_event(
+    event: &PlayerEvent,
+    mut position: impl FnMut(f64, f64) -> MousePosition,
+) -> Option<AutomatedEvent> {
+    Some(match *event {
+        PlayerEvent::MouseMove { x, y } => AutomatedEvent::MouseMove {
+            pos: position(x, y),
+        },
+        PlayerEvent::MouseDown {
+            x,
+            y,
+            button,
+            index,
+        } => AutomatedEvent::MouseDown {
+            pos: position(x, y),
+            btn: MouseButton::try_from(button).ok()?,
+            index,
+            assert_handled: None,
+        },
+        PlayerEvent::MouseUp { x, y, button } => AutomatedEvent::MouseUp {
+            pos: position(x, y),
+            btn: MouseButton::try_from(button).ok()?,
+        },
+        PlayerEvent::MouseWheel { delta } => match delta {
+            MouseWheelDelta::Lines(lines) => AutomatedEvent::MouseWheel {
+                lines: Some(lines),
+                pixels: None,
+            },
+            MouseWheelDelta::Pixels(pixels) => AutomatedEvent::MouseWheel {
+                lines: None,
+                pixels: Some(pixels),
+            },
+        },
+        PlayerEvent::KeyDown { key_code, .. } => AutomatedEvent::KeyDown {
+            key_code: key_code.value(),
+        },
+        PlayerEvent::KeyUp { key_code, .. } => AutomatedEvent::KeyUp {
+            key_code: key_code.value(),
+        },
+        PlayerEvent::TextInput { codepoint } => AutomatedEvent::TextInput { codepoint },
+        PlayerEvent::TextControl { code } => AutomatedEvent::TextControl { code: code.into() },
+        PlayerEvent::GamepadConnected { device } => AutomatedEvent::GamepadConnected { device },
+        PlayerEvent::GamepadDisconnected { device } => {
+            AutomatedEvent::GamepadDisconnected { device }
+        }
+        PlayerEvent::GamepadButtonDown { device, button } => AutomatedEvent::GamepadButtonDown {
+            device,
+            button: button.into(),
+        },
+        PlayerEvent::GamepadButtonUp { device, button } => AutomatedEvent::GamepadButtonUp {
+            device,
+            button: button.into(),
+        },
+        PlayerEvent::GamepadAxisMotion {
+            device,
+            axis,
+            value,
+        } => AutomatedEvent::GamepadAxisMotion {
+            device,
+            axis: axis.into(),
+            value,
+        },
+        PlayerEvent::FocusGained => AutomatedEvent::FocusGained,
+        PlayerEvent::FocusLost => AutomatedEvent::FocusLost,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replayer::to_player_event;
+    use ruffle_core::events::{
+        GamepadButton as RuffleGamepadButton, KeyCode, MouseButton as RuffleMouseButton,
+        TextControlCode as RuffleTextControlCode,
+    };
+    use ruffle_input_format::InputInjector;
+
+    #[test]
+    fn record_and_replay() {
+        let recorded = [
+            (0, PlayerEvent::MouseMove { x: 10.0, y: 20.0 }),
+            (
+                1,
+                PlayerEvent::MouseDown {
+                    x: 10.0,
+                    y: 20.0,
+                    button: RuffleMouseButton::Left,
+                    index: 1,
+                },
+            ),
+            (
+                1,
+                PlayerEvent::MouseUp {
+                    x: 12.5,
+                    y: 20.0,
+                    button: RuffleMouseButton::Left,
+                },
+            ),
+            (
+                3,
+                PlayerEvent::KeyDown {
+                    key_code: KeyCode::A,
+                    key_char: None,
+                },
+            ),
+            (3, PlayerEvent::TextInput { codepoint: 'a' }),
+            (
+                4,
+                PlayerEvent::TextControl {
+                    code: RuffleTextControlCode::Paste,
+                },
+            ),
+            (
+                4,
+                PlayerEvent::MouseWheel {
+                    delta: MouseWheelDelta::Lines(-3.0),
+                },
+            ),
+            (
+                6,
+                PlayerEvent::GamepadButtonDown {
+                    device: 0,
+                    button: RuffleGamepadButton::South,
+                },
+            ),
+            (6, PlayerEvent::FocusLost),
+        ];
+
+        let mut recorder = InputRecorder::new(PathBuf::new());
+        for (frames_run, event) in &recorded {
+            let automated_event = to_automated_event(event, |x, y| MousePosition(x, y))
+                .expect("Event should be recorded");
+            let clipboard = matches!(
+                automated_event,
+                AutomatedEvent::TextControl {
+                    code: TextControlCode::Paste,
+                }
+            )
+            .then(|| "pasted".to_string());
+            recorder.push(*frames_run, automated_event, clipboard);
+        }
+        let mut json = Vec::new();
+        recorder
+            .write(8, &mut json)
+            .expect("Input should be written");
+
+        let mut injector = InputInjector::from_reader(json.as_slice()).expect("Input should parse");
+        let mut replayed = Vec::new();
+        let mut clipboard = None;
+        for frames_run in 1..=8 {
+            injector.next(|event, _buttons| {
+                if let AutomatedEvent::SetClipboardText { text } = event {
+                    clipboard = Some(text.clone());
+                } else if let Some(event) = to_player_event(event, |x, y| (x, y)) {
+                    replayed.push((frames_run, event));
+                }
+            });
+        }
+        assert!(injector.is_finished());
+        assert_eq!(clipboard.as_deref(), Some("pasted"));
+
+        // Events that happen before the first frame has run are replayed after it.
+        let expected: Vec<_> = recorded
+            .iter()
+            .map(|(frames_run, event)| (u64::max(*frames_run, 1), *event))
+            .collect();
+        assert_eq!(format!("{expected:?}"), format!("{replayed:?}"));
+    }
+}
@@ -0,0 +1,120 @@
+use ruffle_core::events::{KeyCode, MouseWheelDelta};
+use ruffle_core::{Player, PlayerEvent};
+use ruffle_input_format::{AutomatedEvent, InputInjector};
+use std::io;
+use std::path::Path;
+
+/// Replays input recorded in the format used by the `input.json` files of the regression tests,
+/// frame by frame.
+pub struct InputReplayer {
+    injector: InputInjector,
+}
+
+impl InputReplayer {
+    pub fn from_file(path: &Path) -> Result<Self, io::Error> {
+        Ok(Self {
+            injector: InputInjector::from_file(path)?,
+        })
+    }
+
+    /// Whether all the recorded input has been replayed.
+    pub fn is_finished(&self) -> bool {
+        self.injector.is_finished()
+    }
+
+    /// Injects the input of the frame that the player has just run.
+    pub fn inject_frame(&mut self, player: &mut Player) {
+        self.injector.next(|event, _buttons| {
+            if let AutomatedEvent::SetClipboardText { text } = event {
+                player.ui_mut().set_clipboard_content(text.to_owned());
+            } else if let Some(event) =
+                to_player_event(event, |x, y| player.stage_to_viewport_position(x, y))
+            {
+                player.handle_event(event);
+            }
+        });
+    }
+}
+
+/// Converts a recorded event to a player event, using `position` to convert
+/// stage coordinates to viewport coordinates.
+pub(crate) fn to_player_event(
+    event: &AutomatedEvent,
+    mut position: impl FnMut(f64, f64) -> (f64, f64),
+) -> Option<PlayerEvent> {
+    Some(match event {
+        AutomatedEvent::MouseMove { pos } => {
+            let (x, y) = position(pos.0, pos.1);
+            PlayerEvent::MouseMove { x, y }
+        }
+        AutomatedEvent::MouseDown {
+            pos, btn, index, ..
+        } => {
+            let (x, y) = position(pos.0, pos.1);
+            PlayerEvent::MouseDown {
+                x,
+                y,
+                button: (*btn).into(),
+                index: *index,
+            }
+        }
+        AutomatedEvent::MouseUp { pos, btn } => {
+            let (x, y) = position(pos.0, pos.1);
+            PlayerEvent::MouseUp {
+                x,
+                y,
+                button: (*btn).into(),
+            }
+        }
+        AutomatedEvent::MouseWheel { lines, pixels } => PlayerEvent::MouseWheel {
+            delta: match (lines, pixels) {
+                (Some(lines), None) => MouseWheelDelta::Lines(*lines),
+                (None, Some(pixels)) => MouseWheelDelta::Pixels(*pixels),
+                _ => {
+                    tracing::warn!("MouseWheel: expected only one of 'lines' or 'pixels'");
+                    return None;
+                }
+            },
+        },
+        AutomatedEvent::KeyDown { key_code } => PlayerEvent::KeyDown {
+            key_code: KeyCode::from_code(*key_code),
+            key_char: None,
+        },
+        AutomatedEvent::KeyUp { key_code } => PlayerEvent::KeyUp {
+            key_code: KeyCode::from_code(*key_code),
+            key_char: None,
+        },
+        AutomatedEvent::TextInput { codepoint } => PlayerEvent::TextInput {
+            codepoint: *codepoint,
+        },
+        AutomatedEvent::TextControl { code } => PlayerEvent::TextControl {
+            code: (*code).into(),
+        },
+        AutomatedEvent::GamepadConnected { device } => {
+            PlayerEvent::GamepadConnected { device: *device }
+        }
+        AutomatedEvent::GamepadDisconnected { device } => {
+            PlayerEvent::GamepadDisconnected { device: *device }
+        }
+        AutomatedEvent::GamepadButtonDown { device, button } => PlayerEvent::GamepadButtonDown {
+            device: *device,
+            button: (*button).into(),
+        },
+        AutomatedEvent::GamepadButtonUp { device, button } => PlayerEvent::GamepadButtonUp {
+            device: *device,
+            button: (*button).into(),
+        },
+        AutomatedEvent::GamepadAxisMotion {
+            device,
+            axis,
+            value,
+        } => PlayerEvent::GamepadAxisMotion {
+            device: *device,
+            axis: (*axis).into(),
+            value: *value,
+        },
+        AutomatedEvent::FocusGained => PlayerEvent::FocusGained,
+        AutomatedEvent::FocusLost => PlayerEvent::FocusLost,
+        AutomatedEvent::Wait | AutomatedEvent::SetClipboardText { .. } => return None,
+    })
+}
@@ -1,6 +1,6 @@
 use anyhow::{anyhow, Error};
-use gilrs::Button;
-use ruffle_core::events::{GamepadButton, KeyCode, TextControlCode};
+use gilrs::{Axis, Button};
+use ruffle_core::events::{GamepadAxis, GamepadButton, KeyCode, TextControlCode};
 use std::path::Path;
 use url::Url;
 use winit::dpi::PhysicalSize;
@@ -200,6 +200,29 @@ pub fn gilrs_button_to_gamepad_button(button: Button) -> Option<GamepadButton> {
     }
 }
 
+/// Returns the analog axis of a button that reports how far it is pressed.
+pub fn gilrs_button_to_gamepad_axis(button: Button) -> Option<GamepadAxis> {
+    match button {
+        Button::LeftTrigger2 => Some(GamepadAxis::LeftTrigger2),
+        Button::RightTrigger2 => Some(GamepadAxis::RightTrigger2),
+        _ => None,
+    }
+}
+
+/// Converts a gilrs axis and its value.
+///
+/// GilRs reports up as positive Y, while Ruffle expects positive Y to point down.
+pub fn gilrs_axis_to_gamepad_axis(axis: Axis, value: f32) -> Option<(GamepadAxis, f64)> {
+    let value = f64::from(value);
+    match axis {
+        Axis::LeftStickX => Some((GamepadAxis::LeftStickX, value)),
+        Axis::LeftStickY => Some((GamepadAxis::LeftStickY, -value)),
+        Axis::RightStickX => Some((GamepadAxis::RightStickX, value)),
+        Axis::RightStickY => Some((GamepadAxis::RightStickY, -value)),
+        _ => None,
+    }
+}
+
 pub fn get_screen_size(window: &Window) -> PhysicalSize<u32> {
     let mut min_x = 0;
     let mut min_y = 0;
@@ -0,0 +1,330 @@
+use anyhow::{anyhow, Result};
+use image::RgbaImage;
+use ruffle_core::swf::Fixed8;
+use std::io::Write;
+
+/// The format to save captured frames in.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq, Default)]
+pub enum OutputFormat {
+    /// One PNG image per frame.
+    #[default]
+    Png,
+
+    /// A single animated PNG.
+    Apng,
+
+    /// A single animated GIF. Colors are quantized to a palette of 256 colors per frame.
+    Gif,
+
+    /// Raw YUV4MPEG2 video (4:2:0), which can be piped into an external encoder.
+    Y4m,
+
+    /// One SVG document per frame. Shapes are exported as vectors instead of being rasterized.
+    Svg,
+}
+
+impl OutputFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Png | OutputFormat::Apng => "png",
+            OutputFormat::Gif => "gif",
+            OutputFormat::Y4m => "y4m",
+            OutputFormat::Svg => "svg",
+        }
+    }
+
+    /// Whether all frames are written to a single file.
+    pub fn is_animated(self) -> bool {
+        !matches!(self, OutputFormat::Png | OutputFormat::Svg)
+    }
+}
+
+/// Encodes frames into a single animation as they are captured, so that they
+/// don't all have to be kept in memory.
+pub struct AnimationEncoder<W: Write> {
+    width: u32,
+    height: u32,
+    encoder: Encoder<W>,
+}
+
+enum Encoder<W: Write> {
+    Apng(png::Writer<W>),
+    Gif {
+        encoder: gif::Encoder<W>,
+        frame_rate: u64,
+        frame: u64,
+    },
+    Y4m {
+        writer: W,
+        planes: Vec<u8>,
+    },
+}
+
+impl<W: Write> AnimationEncoder<W> {
+    /// Starts an animation of `num_frames` frames of the given size, writing its header.
+    ///
+    /// `frame_rate` is the frame rate of the movie, which every frame is shown for.
+    pub fn new(
+        format: OutputFormat,
+        width: u32,
+        height: u32,
+        frame_rate: Fixed8,
+        num_frames: u32,
+        mut writer: W,
+    ) -> Result<Self> {
+        // SWF frame rates are unsigned 8.8 fixed point numbers.
+        let frame_rate = frame_rate.get() as u16;
+        if frame_rate == 0 {
+            return Err(anyhow!("Cannot export an animation with a frame rate of 0"));
+        }
+        if num_frames == 0 {
+            return Err(anyhow!("No frames to export"));
+        }
+
+        let encoder = match format {
+            OutputFormat::Png => return Err(anyhow!("PNG output is not a single animation")),
+            OutputFormat::Svg => return Err(anyhow!("SVG output is not a single animation")),
+            OutputFormat::Apng => {
+                let mut encoder = png::Encoder::new(writer, width, height);
+                encoder.set_color(png::ColorType::Rgba);
+                encoder.set_depth(png::BitDepth::Eight);
+                encoder.set_animated(num_frames, 0)?;
+                // Each frame lasts 256 / frame_rate seconds.
+                encoder.set_frame_delay(256, frame_rate)?;
+                Encoder::Apng(encoder.write_header()?)
+            }
+            OutputFormat::Gif => {
+                let (Ok(width), Ok(height)) = (u16::try_from(width), u16::try_from(height)) else {
+                    return Err(anyhow!("GIF images can be at most 65535x65535"));
+                };
+                let mut encoder = gif::Encoder::new(writer, width, height, &[])?;
+                encoder.set_repeat(gif::Repeat::Infinite)?;
+                Encoder::Gif {
+                    encoder,
+                    frame_rate: u64::from(frame_rate),
+                    frame: 0,
+                }
+            }
+            OutputFormat::Y4m => {
+                let divisor = gcd(frame_rate, 256);
+                writeln!(
+                    writer,
+                    "YUV4MPEG2 W{width} H{height} F{}:{} Ip A1:1 C420jpeg",
+                    frame_rate / divisor,
+                    256 / divisor
+                )?;
+                Encoder::Y4m {
+                    writer,
+                    planes: Vec::new(),
+                }
+            }
+        };
+
+        Ok(Self {
+            width,
+            height,
+            encoder,
+        })
+    }
+
+    /// Encodes the next frame of the animation.
+    pub fn write_frame(&mut self, frame: &RgbaImage) -> Result<()> {
+        if frame.dimensions() != (self.width, self.height) {
+            return Err(anyhow!(
+                "Frame is {}x{}, but the animation is {}x{}",
+                frame.width(),
+                frame.height(),
+                self.width,
+                self.height
+            ));
+        }
+
+        match &mut self.encoder {
+            Encoder::Apng(writer) => writer.write_image_data(frame.as_raw())?,
+            Encoder::Gif {
+                encoder,
+                frame_rate,
+                frame: index,
+            } => {
+                // GIF delays are in hundredths of a second, so compute them from the total
+                // elapsed time to avoid accumulating rounding errors.
+                let frame_rate = *frame_rate;
+                let elapsed = |frame: u64| (frame * 100 * 256 + frame_rate / 2) / frame_rate;
+
+                let mut pixels = frame.as_raw().clone();
+                let (width, height) = (self.width as u16, self.height as u16);
+                let mut frame = gif::Frame::from_rgba_speed(width, height, &mut pixels, 10);
+                frame.delay =
+                    u16::try_from(elapsed(*index + 1) - elapsed(*index)).unwrap_or(u16::MAX);
+                frame.dispose = gif::DisposalMethod::Background;
+                encoder.write_frame(&frame)?;
+                *index += 1;
+            }
+            Encoder::Y4m { writer, planes } => {
+                planes.clear();
+                rgba_to_yuv420(frame, planes);
+                writer.write_all(b"FRAME\n")?;
+                writer.write_all(planes)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Finishes the animation once all frames have been written.
+    pub fn finish(self) -> Result<()> {
+        match self.encoder {
+            Encoder::Apng(writer) => writer.finish()?,
+            Encoder::Gif { encoder, .. } => encoder.into_inner()?.flush()?,
+            Encoder::Y4m { mut writer, .. } => writer.flush()?,
+        }
+        Ok(())
+    }
+}
+
+fn gcd(mut a: u16, mut b: u16) -> u16 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Converts an image to planar BT.601 limited range YUV with 2x2 chroma subsampling,
+/// compositing it on top of black.
+fn rgba_to_yuv420(image: &RgbaImage, out: &mut Vec<u8>) {
+    let (width, height) = image.dimensions();
+    let rgb = |x: u32, y: u32| {
+        let [r, g, b, a] = image.get_pixel(x, y).0.map(i32::from);
+        [r * a / 255, g * a / 255, b * a / 255]
+    };
+
+    for y in 0..height {
+        for x in 0..width {
+            let [r, g, b] = rgb(x, y);
+            out.push((((66 * r + 129 * g + 25 * b + 128) >> 8) + 16) as u8);
+        }
+    }
+
+    let chroma_width = width.div_ceil(2);
+    let chroma_height = height.div_ceil(2);
+    let mut u_plane = Vec::with_capacity((chroma_width * chroma_height) as usize);
+    let mut v_plane = Vec::with_capacity((chroma_width * chroma_height) as usize);
+    for cy in 0..chroma_height {
+        for cx in 0..chroma_width {
+            let mut sum = [0; 3];
+            let mut count = 0;
+            for y in (cy * 2)..(cy * 2 + 2).min(height) {
+                for x in (cx * 2)..(cx * 2 + 2).min(width) {
+                    let pixel = rgb(x, y);
+                    for (sum, value) in sum.iter_mut().zip(pixel) {
+                        *sum += value;
+                    }
+                    count += 1;
+                }
+            }
+            let [r, g, b] = sum.map(|sum| (sum + count / 2) / count);
+            u_plane.push((((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128) as u8);
+            v_plane.push((((112 * r - 94 * g - 18 * b + 128) >> 8) + 128) as u8);
+        }
+    }
+    out.extend(u_plane);
+    out.extend(v_plane);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn encode_y4m(frames: &[RgbaImage], frame_rate: f32) -> Vec<u8> {
+        let (width, height) = frames[0].dimensions();
+        let mut out = Vec::new();
+        let mut encoder = AnimationEncoder::new(
+            OutputFormat::Y4m,
+            width,
+            height,
+            Fixed8::from_f32(frame_rate),
+            frames.len() as u32,
+            &mut out,
+        )
+        .unwrap();
+        for frame in frames {
+            encoder.write_frame(frame).unwrap();
+        }
+        encoder.finish().unwrap();
+        out
+    }
+
+    fn yuv420(pixels: &[[u8; 4]], width: u32, height: u32) -> Vec<u8> {
+        let image =
+            RgbaImage::from_fn(width, height, |x, y| Rgba(pixels[(y * width + x) as usize]));
+        let mut out = Vec::new();
+        rgba_to_yuv420(&image, &mut out);
+        out
+    }
+
+    #[test]
+    fn y4m_header_and_frames() {
+        let frames = [RgbaImage::new(5, 3), RgbaImage::new(5, 3)];
+        let out = encode_y4m(&frames, 24.0);
+
+        let header = b"YUV4MPEG2 W5 H3 F24:1 Ip A1:1 C420jpeg\n";
+        assert!(out.starts_with(header));
+
+        // A full resolution luma plane followed by two chroma planes rounded up to 3x2.
+        let frame_size = b"FRAME\n".len() + 5 * 3 + 2 * 3 * 2;
+        assert_eq!(out.len(), header.len() + 2 * frame_size);
+        for frame in out[header.len()..].chunks(frame_size) {
+            assert!(frame.starts_with(b"FRAME\n"));
+        }
+    }
+
+    #[test]
+    fn y4m_fractional_frame_rate() {
+        let out = encode_y4m(&[RgbaImage::new(2, 2)], 12.5);
+        assert!(out.starts_with(b"YUV4MPEG2 W2 H2 F25:2 Ip A1:1 C420jpeg\n"));
+    }
+
+    #[test]
+    fn rejects_frames_of_another_size() {
+        let mut encoder = AnimationEncoder::new(
+            OutputFormat::Y4m,
+            2,
+            2,
+            Fixed8::from_f32(30.0),
+            1,
+            Vec::new(),
+        )
+        .unwrap();
+        assert!(encoder.write_frame(&RgbaImage::new(4, 4)).is_err());
+    }
+
+    #[test]
+    fn rejects_zero_frame_rate() {
+        let encoder = AnimationEncoder::new(OutputFormat::Y4m, 2, 2, Fixed8::ZERO, 1, Vec::new());
+        assert!(encoder.is_err());
+    }
+
+    #[test]
+    fn yuv420_primaries() {
+        assert_eq!(yuv420(&[[0, 0, 0, 255]], 1, 1), [16, 128, 128]);
+        assert_eq!(yuv420(&[[255, 255, 255, 255]], 1, 1), [235, 128, 128]);
+        assert_eq!(yuv420(&[[255, 0, 0, 255]], 1, 1), [82, 90, 240]);
+        assert_eq!(yuv420(&[[0, 255, 0, 255]], 1, 1), [144, 54, 34]);
+        assert_eq!(yuv420(&[[0, 0, 255, 255]], 1, 1), [41, 240, 110]);
+    }
+
+    #[test]
+    fn yuv420_composites_on_black() {
+        assert_eq!(yuv420(&[[255, 255, 255, 0]], 1, 1), [16, 128, 128]);
+        assert_eq!(yuv420(&[[255, 255, 255, 128]], 1, 1), [126, 128, 128]);
+    }
+
+    #[test]
+    fn yuv420_averages_chroma() {
+        // One chroma sample covers both pixels of the 2x1 image, averaging red and black.
+        assert_eq!(
+            yuv420(&[[255, 0, 0, 255], [0, 0, 0, 255]], 2, 1),
+            [82, 16, 109, 184]
+        );
+    }
+}
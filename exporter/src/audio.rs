@@ -0,0 +1,104 @@
+use ruffle_core::backend::audio::{
+    swf, AudioBackend, AudioMixer, DecodeError, RegisterError, SoundHandle, SoundInstanceHandle,
+    SoundStreamInfo, SoundTransform,
+};
+use ruffle_core::impl_audio_mixer_backend;
+use std::io::{self, Write};
+
+/// An audio backend that mixes all sounds into memory, one frame at a time.
+///
+/// The number of samples mixed for each frame is derived from the total number of frames
+/// that have run so far, so that the captured audio never drifts away from the video.
+pub struct CaptureAudioBackend {
+    mixer: AudioMixer,
+    frame_rate: f64,
+    frames_mixed: u64,
+    samples_mixed: u64,
+    samples: Vec<i16>,
+}
+
+impl CaptureAudioBackend {
+    pub const NUM_CHANNELS: u8 = 2;
+    pub const SAMPLE_RATE: u32 = 44100;
+
+    pub fn new() -> Self {
+        Self {
+            mixer: AudioMixer::new(Self::NUM_CHANNELS, Self::SAMPLE_RATE),
+            frame_rate: 1.0,
+            frames_mixed: 0,
+            samples_mixed: 0,
+            samples: Vec::new(),
+        }
+    }
+
+    /// Discards all the audio captured so far, e.g. during skipped frames.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Writes the captured audio as a 16-bit PCM WAV file.
+    pub fn write_wav<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let channels = u16::from(Self::NUM_CHANNELS);
+        let block_align = channels * 2;
+        let byte_rate = Self::SAMPLE_RATE * u32::from(block_align);
+        let data_len = u32::try_from(self.samples.len() * 2)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Audio is too long"))?;
+
+        writer.write_all(b"RIFF")?;
+        writer.write_all(&(36 + data_len).to_le_bytes())?;
+        writer.write_all(b"WAVE")?;
+
+        writer.write_all(b"fmt ")?;
+        writer.write_all(&16u32.to_le_bytes())?;
+        writer.write_all(&1u16.to_le_bytes())?; // PCM
+        writer.write_all(&channels.to_le_bytes())?;
+        writer.write_all(&Self::SAMPLE_RATE.to_le_bytes())?;
+        writer.write_all(&byte_rate.to_le_bytes())?;
+        writer.write_all(&block_align.to_le_bytes())?;
+        writer.write_all(&16u16.to_le_bytes())?; // Bits per sample
+
+        writer.write_all(b"data")?;
+        writer.write_all(&data_len.to_le_bytes())?;
+        let bytes: Vec<u8> = self
+            .samples
+            .iter()
+            .flat_map(|sample| sample.to_le_bytes())
+            .collect();
+        writer.write_all(&bytes)?;
+        writer.flush()
+    }
+}
+
+impl Default for CaptureAudioBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioBackend for CaptureAudioBackend {
+    impl_audio_mixer_backend!(mixer);
+    fn play(&mut self) {}
+    fn pause(&mut self) {}
+
+    fn set_frame_rate(&mut self, frame_rate: f64) {
+        // Restart the frame count, so that a change in frame rate only affects the frames after it.
+        self.frame_rate = frame_rate;
+        self.frames_mixed = 0;
+        self.samples_mixed = 0;
+    }
+
+    fn tick(&mut self) {
+        self.frames_mixed += 1;
+        let end =
+            (self.frames_mixed as f64 * f64::from(Self::SAMPLE_RATE) / self.frame_rate).round();
+        let num_samples = (end as u64).saturating_sub(self.samples_mixed);
+        self.samples_mixed += num_samples;
+
+        let start = self.samples.len();
+        self.samples.resize(
+            start + num_samples as usize * usize::from(Self::NUM_CHANNELS),
+            0,
+        );
+        self.mixer.mix::<i16>(&mut self.samples[start..]);
+    }
+}
@@ -1,18 +1,25 @@
+mod animation;
+mod audio;
+
+use crate::animation::{AnimationEncoder, OutputFormat};
+use crate::audio::CaptureAudioBackend;
 use anyhow::{anyhow, Result};
 use clap::Parser;
-use image::RgbaImage;
 use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::*;
 use ruffle_core::limits::ExecutionLimit;
+use ruffle_core::swf::Fixed8;
 use ruffle_core::tag_utils::SwfMovie;
 use ruffle_core::PlayerBuilder;
+use ruffle_render_software::SoftwareRenderBackend;
+use ruffle_render_svg::SvgRenderBackend;
 use ruffle_render_wgpu::backend::{request_adapter_and_device, WgpuRenderBackend};
 use ruffle_render_wgpu::clap::{GraphicsBackend, PowerPreference};
 use ruffle_render_wgpu::descriptors::Descriptors;
 use ruffle_render_wgpu::target::TextureTarget;
 use ruffle_render_wgpu::wgpu;
-use std::fs::create_dir_all;
-use std::io::{self, Write};
+use std::fs::{create_dir_all, File};
+use std::io::{self, BufWriter, Write};
 use std::panic::catch_unwind;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -42,8 +49,9 @@ struct Opt {
 
     /// The file or directory (if multiple frames/files) to store the capture in.
     /// The default value will either be:
-    /// - If given one swf and one frame, the name of the swf + ".png"
+    /// - If given one swf and one frame, the name of the swf + the format's extension
     /// - If given one swf and multiple frames, the name of the swf as a directory
+    /// - If given one swf and an animated format, the name of the swf + the format's extension
     /// - If given multiple swfs, this field is required.
     #[clap(name = "output")]
     output_path: Option<PathBuf>,
@@ -56,6 +64,11 @@ struct Opt {
     #[clap(long = "skipframes", default_value = "0")]
     skipframes: u32,
 
+    /// The format to save the frames in. Animated formats store all frames of a swf in one file.
+    /// SVG output is rendered as vectors, ignoring the graphics options.
+    #[clap(long = "format", default_value = "png")]
+    format: OutputFormat,
+
     /// Don't show a progress bar
     #[clap(short, long, action)]
     silent: bool,
@@ -73,21 +86,164 @@ struct Opt {
     #[clap(long, short, default_value = "high")]
     power: PowerPreference,
 
+    /// Render on the CPU instead of the GPU. This is slower, but works on machines without a graphics device.
+    #[clap(long, action)]
+    software: bool,
+
     /// Skip unsupported movie types (currently AVM 2)
     #[clap(long, action)]
     skip_unsupported: bool,
+
+    /// Also capture the audio of the exported frames to the given WAV file.
+    /// Only supported when exporting a single swf.
+    #[clap(long = "audio")]
+    audio: Option<PathBuf>,
+}
+
+/// How the frames of a movie are rendered.
+enum Renderer {
+    Gpu(Arc<Descriptors>),
+    Software,
+    Svg,
+}
+
+/// A captured frame.
+enum Frame {
+    /// A rendered image, with straight alpha.
+    Image(RgbaImage),
+
+    /// An SVG document.
+    Svg(String),
+}
+
+impl Frame {
+    fn save(&self, path: &Path) -> Result<()> {
+        match self {
+            Frame::Image(image) => image.save(path)?,
+            Frame::Svg(svg) => std::fs::write(path, svg)?,
+        }
+        Ok(())
+    }
+
+    fn write_to<W: Write>(&self, mut writer: W) -> Result<()> {
+        match self {
+            Frame::Image(image) => {
+                let mut bytes: Vec<u8> = Vec::new();
+                image.write_to(&mut io::Cursor::new(&mut bytes), image::ImageFormat::Png)?;
+                writer.write_all(bytes.as_slice())?;
+            }
+            Frame::Svg(svg) => writer.write_all(svg.as_bytes())?,
+        }
+        Ok(())
+    }
+}
+
+/// Where captured frames are written to, as soon as each of them has been rendered.
+enum FrameSink {
+    /// Each frame is saved to its own file: `path` itself if only one frame is captured,
+    /// or `{index}.{extension}` inside the `path` directory otherwise.
+    Files {
+        path: PathBuf,
+        format: OutputFormat,
+        single: bool,
+    },
+
+    /// The only captured frame is written to stdout.
+    Stdout,
+
+    /// All frames are encoded into a single animation, saved to `path` or written to stdout.
+    /// Only images can be animated.
+    Animation {
+        format: OutputFormat,
+        path: Option<PathBuf>,
+        encoder: Option<AnimationEncoder<Box<dyn Write>>>,
+    },
+}
+
+impl FrameSink {
+    /// Creates the output file or directory, before the first frame is written.
+    fn start(&mut self, width: u32, height: u32, frame_rate: Fixed8, frames: u32) -> Result<()> {
+        match self {
+            FrameSink::Files { path, single, .. } => {
+                let directory = if *single {
+                    path.parent()
+                } else {
+                    Some(path.as_path())
+                };
+                if let Some(directory) = directory {
+                    let _ = create_dir_all(directory);
+                }
+            }
+            FrameSink::Stdout => {}
+            FrameSink::Animation {
+                format,
+                path,
+                encoder,
+            } => {
+                let writer: Box<dyn Write> = match path {
+                    Some(path) => {
+                        if let Some(parent) = path.parent() {
+                            let _ = create_dir_all(parent);
+                        }
+                        Box::new(BufWriter::new(File::create(path)?))
+                    }
+                    None => Box::new(io::stdout().lock()),
+                };
+                *encoder = Some(AnimationEncoder::new(
+                    *format, width, height, frame_rate, frames, writer,
+                )?);
+            }
+        }
+        Ok(())
+    }
+
+    fn write_frame(&mut self, index: u32, frame: Frame) -> Result<()> {
+        match self {
+            FrameSink::Files {
+                path, single: true, ..
+            } => frame.save(path),
+            FrameSink::Files { path, format, .. } => {
+                frame.save(&path.join(format!("{index}.{}", format.extension())))
+            }
+            FrameSink::Stdout => frame.write_to(io::stdout().lock()),
+            FrameSink::Animation { encoder, .. } => {
+                let encoder = encoder.as_mut().expect("Sink must be started first");
+                match frame {
+                    Frame::Image(image) => encoder.write_frame(&image),
+                    Frame::Svg(_) => Err(anyhow!("SVG frames cannot be animated")),
+                }
+            }
+        }
+    }
+
+    /// Finishes the output once all frames have been written.
+    fn finish(self) -> Result<()> {
+        match self {
+            FrameSink::Animation {
+                encoder: Some(encoder),
+                ..
+            } => encoder.finish(),
+            _ => Ok(()),
+        }
+    }
 }
 
-/// Captures a screenshot. The resulting image uses straight alpha
+/// Captures screenshots, either as images with straight alpha or as SVG documents,
+/// passing each of them to `sink` as soon as it has been rendered.
+///
+/// If `audio_path` is given, the audio played during the captured frames is written there.
+#[allow(clippy::too_many_arguments)]
 fn take_screenshot(
-    descriptors: Arc<Descriptors>,
+    renderer: &Renderer,
     swf_path: &Path,
     frames: u32,
     skipframes: u32,
     progress: &Option<ProgressBar>,
     size: SizeOpt,
     skip_unsupported: bool,
-) -> Result<Vec<RgbaImage>> {
+    audio_path: Option<&Path>,
+    sink: &mut FrameSink,
+) -> Result<()> {
     let movie = SwfMovie::from_path(swf_path, None).map_err(|e| anyhow!(e.to_string()))?;
 
     if movie.is_action_script_3() && skip_unsupported {
@@ -105,18 +261,31 @@ fn take_screenshot(
         .map(f64::from)
         .unwrap_or_else(|| movie.height().to_pixels());
     let height = (height * size.scale).round() as u32;
-
-    let target = TextureTarget::new(&descriptors.device, (width, height))
-        .map_err(|e| anyhow!(e.to_string()))?;
-    let player = PlayerBuilder::new()
-        .with_renderer(
-            WgpuRenderBackend::new(descriptors, target).map_err(|e| anyhow!(e.to_string()))?,
-        )
+    let frame_rate = movie.frame_rate();
+
+    let builder = match renderer {
+        Renderer::Gpu(descriptors) => {
+            let target = TextureTarget::new(&descriptors.device, (width, height))
+                .map_err(|e| anyhow!(e.to_string()))?;
+            PlayerBuilder::new().with_renderer(
+                WgpuRenderBackend::new(descriptors.clone(), target)
+                    .map_err(|e| anyhow!(e.to_string()))?,
+            )
+        }
+        Renderer::Software => {
+            PlayerBuilder::new().with_renderer(SoftwareRenderBackend::new(width, height))
+        }
+        Renderer::Svg => PlayerBuilder::new().with_renderer(SvgRenderBackend::new(width, height)),
+    };
+    let mut builder = builder
         .with_movie(movie)
-        .with_viewport_dimensions(width, height, size.scale)
-        .build();
+        .with_viewport_dimensions(width, height, size.scale);
+    if audio_path.is_some() {
+        builder = builder.with_audio(CaptureAudioBackend::new());
+    }
+    let player = builder.build();
+    sink.start(width, height, frame_rate, frames)?;
 
-    let mut result = Vec::new();
     let totalframes = frames + skipframes;
 
     for i in 0..totalframes {
@@ -131,18 +300,35 @@ fn take_screenshot(
         player.lock().unwrap().preload(&mut ExecutionLimit::none());
 
         player.lock().unwrap().run_frame();
+        if audio_path.is_some() {
+            let mut player = player.lock().unwrap();
+            let audio = player.audio_mut();
+            audio.tick();
+            if i < skipframes {
+                if let Some(audio) = audio.downcast_mut::<CaptureAudioBackend>() {
+                    audio.clear();
+                }
+            }
+        }
         if i >= skipframes {
             let image = || {
                 player.lock().unwrap().render();
                 let mut player = player.lock().unwrap();
-                let renderer = player
-                    .renderer_mut()
-                    .downcast_mut::<WgpuRenderBackend<TextureTarget>>()
-                    .unwrap();
-                renderer.capture_frame()
+                let renderer = player.renderer_mut();
+                if let Some(renderer) = renderer.downcast_ref::<SoftwareRenderBackend>() {
+                    renderer.capture_frame().map(Frame::Image)
+                } else if let Some(renderer) = renderer.downcast_ref::<SvgRenderBackend>() {
+                    Some(Frame::Svg(renderer.capture_frame().to_owned()))
+                } else {
+                    renderer
+                        .downcast_mut::<WgpuRenderBackend<TextureTarget>>()
+                        .unwrap()
+                        .capture_frame()
+                        .map(Frame::Image)
+                }
             };
             match catch_unwind(image) {
-                Ok(Some(image)) => result.push(image),
+                Ok(Some(frame)) => sink.write_frame(i - skipframes, frame)?,
                 Ok(None) => return Err(anyhow!("Unable to capture frame {} of {:?}", i, swf_path)),
                 Err(e) => {
                     return Err(anyhow!(
@@ -159,7 +345,17 @@ fn take_screenshot(
             progress.inc(1);
         }
     }
-    Ok(result)
+
+    if let Some(audio_path) = audio_path {
+        let mut player = player.lock().unwrap();
+        let audio = player
+            .audio_mut()
+            .downcast_mut::<CaptureAudioBackend>()
+            .unwrap();
+        audio.write_wav(BufWriter::new(File::create(audio_path)?))?;
+    }
+
+    Ok(())
 }
 
 fn find_files(root: &Path, with_progress: bool) -> Vec<DirEntry> {
@@ -192,19 +388,32 @@ fn find_files(root: &Path, with_progress: bool) -> Vec<DirEntry> {
     results
 }
 
-fn capture_single_swf(descriptors: Arc<Descriptors>, opt: &Opt) -> Result<()> {
+fn capture_single_swf(renderer: &Renderer, opt: &Opt) -> Result<()> {
     let output = opt.output_path.clone().unwrap_or_else(|| {
         let mut result = PathBuf::new();
         result.set_file_name(opt.swf.file_stem().unwrap());
-        if opt.frames == 1 {
-            result.set_extension("png");
+        if opt.frames == 1 || opt.format.is_animated() {
+            result.set_extension(opt.format.extension());
         }
         result
     });
 
-    if opt.frames > 1 {
-        let _ = create_dir_all(&output);
-    }
+    let to_stdout = opt.output_path == Some(PathBuf::from("-"));
+    let mut sink = if opt.format.is_animated() {
+        FrameSink::Animation {
+            format: opt.format,
+            path: (!to_stdout).then(|| output.clone()),
+            encoder: None,
+        }
+    } else if to_stdout && opt.frames == 1 {
+        FrameSink::Stdout
+    } else {
+        FrameSink::Files {
+            path: output.clone(),
+            format: opt.format,
+            single: opt.frames == 1,
+        }
+    };
 
     let progress = if !opt.silent {
         let progress = ProgressBar::new(opt.frames as u64);
@@ -220,73 +429,60 @@ fn capture_single_swf(descriptors: Arc<Descriptors>, opt: &Opt) -> Result<()> {
         None
     };
 
-    let frames = take_screenshot(
-        descriptors,
+    take_screenshot(
+        renderer,
         &opt.swf,
         opt.frames,
         opt.skipframes,
         &progress,
         opt.size,
         opt.skip_unsupported,
+        opt.audio.as_deref(),
+        &mut sink,
     )?;
+    sink.finish()?;
 
     if let Some(progress) = &progress {
         progress.set_message(opt.swf.file_stem().unwrap().to_string_lossy().into_owned());
     }
 
-    if frames.len() == 1 {
-        let image = frames.first().unwrap();
-        if opt.output_path == Some(PathBuf::from("-")) {
-            let mut bytes: Vec<u8> = Vec::new();
-            image
-                .write_to(&mut io::Cursor::new(&mut bytes), image::ImageFormat::Png)
-                .expect("Encoding failed");
-            io::stdout()
-                .write_all(bytes.as_slice())
-                .expect("Writing to stdout failed");
-        } else {
-            image.save(&output)?;
-        }
-    } else {
-        for (frame, image) in frames.iter().enumerate() {
-            let mut path: PathBuf = (&output).into();
-            path.push(format!("{frame}.png"));
-            image.save(&path)?;
-        }
-    }
-
-    let message = if frames.len() == 1 {
-        if !opt.silent {
-            Some(format!(
-                "Saved first frame of {} to {}",
-                opt.swf.to_string_lossy(),
-                output.to_string_lossy()
-            ))
-        } else {
-            None
-        }
+    // Anything printed to stdout would end up in the captured output.
+    let message = if opt.silent || to_stdout {
+        None
+    } else if opt.format.is_animated() {
+        Some(format!(
+            "Saved {} frames of {} to {}",
+            opt.frames,
+            opt.swf.to_string_lossy(),
+            output.to_string_lossy()
+        ))
+    } else if opt.frames == 1 {
+        Some(format!(
+            "Saved first frame of {} to {}",
+            opt.swf.to_string_lossy(),
+            output.to_string_lossy()
+        ))
     } else {
         Some(format!(
             "Saved first {} frames of {} to {}",
-            frames.len(),
+            opt.frames,
             opt.swf.to_string_lossy(),
             output.to_string_lossy()
         ))
     };
 
-    if let Some(message) = message {
-        if let Some(progress) = progress {
-            progress.finish_with_message(message);
-        } else {
-            println!("{message}");
-        }
+    match (message, progress) {
+        (Some(message), Some(progress)) => progress.finish_with_message(message),
+        (Some(message), None) => eprintln!("{message}"),
+        (None, Some(progress)) => progress.finish(),
+        (None, None) => {}
     }
 
     Ok(())
 }
 
 #[allow(clippy::branches_sharing_code)]
-fn capture_multiple_swfs(descriptors: Arc<Descriptors>, opt: &Opt) -> Result<()> {
+fn capture_multiple_swfs(renderer: &Renderer, opt: &Opt) -> Result<()> {
     let output = opt.output_path.clone().unwrap();
     let files = find_files(&opt.swf, !opt.silent);
 
@@ -314,46 +510,61 @@ fn capture_multiple_swfs(descriptors: Arc<Descriptors>, opt: &Opt) -> Result<()>
                     .into_owned(),
             );
         }
-        if let Ok(frames) = take_screenshot(
-            descriptors.clone(),
+        let mut relative_path = file
+            .path()
+            .strip_prefix(&opt.swf)
+            .unwrap_or_else(|_| file.path())
+            .to_path_buf();
+        let mut destination: PathBuf = (&output).into();
+        if opt.format.is_animated() || opt.frames == 1 {
+            relative_path.set_extension(opt.format.extension());
+        } else {
+            relative_path.set_extension("");
+        }
+        destination.push(relative_path);
+
+        let mut sink = if opt.format.is_animated() {
+            FrameSink::Animation {
+                format: opt.format,
+                path: Some(destination),
+                encoder: None,
+            }
+        } else {
+            FrameSink::Files {
+                path: destination,
+                format: opt.format,
+                single: opt.frames == 1,
+            }
+        };
+
+        // Movies that fail to be captured are skipped.
+        if take_screenshot(
+            renderer,
             file.path(),
             opt.frames,
             opt.skipframes,
             &progress,
             opt.size,
             opt.skip_unsupported,
-        ) {
-            let mut relative_path = file
-                .path()
-                .strip_prefix(&opt.swf)
-                .unwrap_or_else(|_| file.path())
-                .to_path_buf();
-
-            if frames.len() == 1 {
-                let mut destination: PathBuf = (&output).into();
-                relative_path.set_extension("png");
-                destination.push(relative_path);
-                if let Some(parent) = destination.parent() {
-                    let _ = create_dir_all(parent);
-                }
-                frames.first().unwrap().save(&destination)?;
-            } else {
-                let mut parent: PathBuf = (&output).into();
-                relative_path.set_extension("");
-                parent.push(&relative_path);
-                let _ = create_dir_all(&parent);
-                for (frame, image) in frames.iter().enumerate() {
-                    let mut destination = parent.clone();
-                    destination.push(format!("{frame}.png"));
-                    image.save(&destination)?;
-                }
-            }
+            None,
+            &mut sink,
+        )
+        .is_ok()
+        {
+            sink.finish()?;
         }
 
         Ok(())
     })?;
 
-    let message = if opt.frames == 1 {
+    let message = if opt.format.is_animated() {
+        format!(
+            "Saved {} frames of {} files to {}",
+            opt.frames,
+            files.len(),
+            output.to_string_lossy()
+        )
+    } else if opt.frames == 1 {
         format!(
             "Saved first frame of {} files to {}",
             files.len(),
@@ -383,27 +594,37 @@ fn trace_path(_opt: &Opt) -> Option<&Path> {
 
 fn main() -> Result<()> {
     let opt: Opt = Opt::parse();
-    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-        backends: opt.graphics.into(),
-        ..Default::default()
-    });
-    let (adapter, device, queue) = futures::executor::block_on(request_adapter_and_device(
-        opt.graphics.into(),
-        &instance,
-        None,
-        opt.power.into(),
-        trace_path(&opt),
-    ))
-    .map_err(|e| anyhow!(e.to_string()))?;
+    let renderer = if opt.format == OutputFormat::Svg {
+        Renderer::Svg
+    } else if opt.software {
+        Renderer::Software
+    } else {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: opt.graphics.into(),
+            ..Default::default()
+        });
+        let (adapter, device, queue) = futures::executor::block_on(request_adapter_and_device(
+            opt.graphics.into(),
+            &instance,
+            None,
+            opt.power.into(),
+            trace_path(&opt),
+        ))
+        .map_err(|e| anyhow!(e.to_string()))?;
 
-    let descriptors = Arc::new(Descriptors::new(instance, adapter, device, queue));
+        Renderer::Gpu(Arc::new(Descriptors::new(instance, adapter, device, queue)))
+    };
 
     if opt.swf.is_file() {
-        capture_single_swf(descriptors, &opt)?;
+        capture_single_swf(&renderer, &opt)?;
     } else if !opt.swf.is_dir() {
         return Err(anyhow!("Given path is not a file or directory."));
+    } else if opt.audio.is_some() {
+        return Err(anyhow!(
+            "Audio can only be captured when exporting a single file."
+        ));
     } else if opt.output_path.is_some() {
-        capture_multiple_swfs(descriptors, &opt)?;
+        capture_multiple_swfs(&renderer, &opt)?;
     } else {
         return Err(anyhow!(
             "Output directory is required when exporting multiple files."
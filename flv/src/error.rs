@@ -51,6 +51,12 @@ pub enum Error {
     #[error("the FLV contains a tag with unknown type {0}")]
     UnknownTagType(u8),
 
+    #[error("the FLV data is too large to fit in the field it is written to")]
+    DataTooLarge,
+
+    #[error("the FLV tag cannot be written as its data is invalid")]
+    InvalidTagData,
+
     #[error("IO error ({0}, {1})")]
     IoError(IoErrorKind, String),
 }
@@ -80,6 +86,8 @@ impl PartialEq for Error {
             (Self::UnknownVideoCommandType(s), Self::UnknownVideoCommandType(o)) => s == o,
             (Self::UnknownAvcPacketType(s), Self::UnknownAvcPacketType(o)) => s == o,
             (Self::UnknownTagType(s), Self::UnknownTagType(o)) => s == o,
+            (Self::DataTooLarge, Self::DataTooLarge) => true,
+            (Self::InvalidTagData, Self::InvalidTagData) => true,
             (Self::IoError(sk, ss), Self::IoError(ok, os)) => sk == ok && ss == os,
             _ => false,
         }
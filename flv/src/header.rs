@@ -1,7 +1,8 @@
 use crate::error::Error;
 use crate::reader::FlvReader;
+use crate::writer::FlvWriter;
 use bitflags::bitflags;
-use std::io::{Seek, SeekFrom};
+use std::io::{Seek, SeekFrom, Write};
 
 bitflags! {
     #[derive(PartialEq, Eq, Debug, Clone, Copy)]
@@ -19,6 +20,9 @@ pub struct Header {
 }
 
 impl Header {
+    /// The size of the header structure, which is the smallest valid data offset.
+    pub const SIZE: u32 = 9;
+
     /// Parse an FLV header.
     ///
     /// The header must, at a minimum, contain the FLV magic, version number,
@@ -56,12 +60,31 @@ impl Header {
             }
         }
     }
+
+    /// Write an FLV header.
+    ///
+    /// Any space between the end of the header and the data offset is padded
+    /// with zeroes, so that the first tag is written at the data offset.
+    pub fn write<W: Write>(&self, writer: &mut FlvWriter<W>) -> Result<(), Error> {
+        writer.write_u24(0x464C56)?;
+        writer.write_u8(self.version)?;
+        writer.write_u8(self.type_flags.bits())?;
+        writer.write_u32(self.data_offset)?;
+
+        for _ in Self::SIZE..self.data_offset {
+            writer.write_u8(0)?;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::header::{Header, TypeFlags};
     use crate::reader::FlvReader;
+    use crate::writer::FlvWriter;
+    use std::io::Seek;
 
     #[test]
     fn read_header() {
@@ -77,4 +100,43 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn write_header() {
+        let header = Header {
+            version: 1,
+            type_flags: TypeFlags::HAS_AUDIO | TypeFlags::HAS_VIDEO,
+            data_offset: Header::SIZE,
+        };
+        let mut writer = FlvWriter::new(vec![]);
+        header.write(&mut writer).unwrap();
+        let data = writer.into_inner();
+
+        assert_eq!(data, [0x46, 0x4C, 0x56, 0x01, 0x05, 0x00, 0x00, 0x00, 0x09]);
+        assert_eq!(
+            Header::parse(&mut FlvReader::from_source(&data)),
+            Ok(header)
+        );
+    }
+
+    #[test]
+    fn write_header_padded() {
+        let header = Header {
+            version: 1,
+            type_flags: TypeFlags::HAS_VIDEO,
+            data_offset: 12,
+        };
+        let mut writer = FlvWriter::new(vec![]);
+        header.write(&mut writer).unwrap();
+        let data = writer.into_inner();
+
+        assert_eq!(
+            data,
+            [0x46, 0x4C, 0x56, 0x01, 0x04, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x00, 0x00]
+        );
+
+        let mut reader = FlvReader::from_source(&data);
+        assert_eq!(Header::parse(&mut reader), Ok(header));
+        assert_eq!(reader.stream_position().unwrap(), 12);
+    }
 }
@@ -1,6 +1,7 @@
 use crate::error::Error;
 use crate::reader::FlvReader;
-use std::io::Seek;
+use crate::writer::FlvWriter;
+use std::io::{Seek, Write};
 
 fn parse_string<'a>(reader: &mut FlvReader<'a>, is_long_string: bool) -> Result<&'a [u8], Error> {
     let length = if is_long_string {
@@ -12,6 +13,24 @@ fn parse_string<'a>(reader: &mut FlvReader<'a>, is_long_string: bool) -> Result<
     reader.read(length as usize)
 }
 
+fn write_string<W: Write>(
+    writer: &mut FlvWriter<W>,
+    string: &[u8],
+    is_long_string: bool,
+) -> Result<(), Error> {
+    if is_long_string {
+        writer.write_u32(string.len().try_into().map_err(|_| Error::DataTooLarge)?)?;
+    } else {
+        writer.write_u16(string.len().try_into().map_err(|_| Error::DataTooLarge)?)?;
+    }
+
+    writer.write(string)
+}
+
+fn write_object_end<W: Write>(writer: &mut FlvWriter<W>) -> Result<(), Error> {
+    writer.write_u24(9)
+}
+
 #[repr(u8)]
 #[derive(PartialEq, Debug, Clone)]
 pub enum Value<'a> {
@@ -98,6 +117,79 @@ impl<'a> Value<'a> {
             _ => Err(Error::UnknownValueType),
         }
     }
+
+    /// Write a script value.
+    ///
+    /// ECMA arrays are written with their exact length as the length hint.
+    pub fn write<W: Write>(&self, writer: &mut FlvWriter<W>) -> Result<(), Error> {
+        match self {
+            Self::Number(value) => {
+                writer.write_u8(0)?;
+                writer.write_f64(*value)
+            }
+            Self::Boolean(value) => {
+                writer.write_u8(1)?;
+                writer.write_u8(*value as u8)
+            }
+            Self::String(string) => {
+                writer.write_u8(2)?;
+                write_string(writer, string, false)
+            }
+            Self::Object(variables) => {
+                writer.write_u8(3)?;
+                for variable in variables {
+                    variable.write(writer)?;
+                }
+
+                write_object_end(writer)
+            }
+            Self::MovieClip(path) => {
+                writer.write_u8(4)?;
+                write_string(writer, path, false)
+            }
+            Self::Null => writer.write_u8(5),
+            Self::Undefined => writer.write_u8(6),
+            Self::Reference(index) => {
+                writer.write_u8(7)?;
+                writer.write_u16(*index)
+            }
+            Self::EcmaArray(variables) => {
+                writer.write_u8(8)?;
+                writer.write_u32(
+                    variables
+                        .len()
+                        .try_into()
+                        .map_err(|_| Error::DataTooLarge)?,
+                )?;
+                for variable in variables {
+                    variable.write(writer)?;
+                }
+
+                write_object_end(writer)
+            }
+            Self::StrictArray(values) => {
+                writer.write_u8(10)?;
+                writer.write_u32(values.len().try_into().map_err(|_| Error::DataTooLarge)?)?;
+                for value in values {
+                    value.write(writer)?;
+                }
+
+                Ok(())
+            }
+            Self::Date {
+                unix_time,
+                local_offset,
+            } => {
+                writer.write_u8(11)?;
+                writer.write_f64(*unix_time)?;
+                writer.write_i16(*local_offset)
+            }
+            Self::LongString(string) => {
+                writer.write_u8(12)?;
+                write_string(writer, string, true)
+            }
+        }
+    }
 }
 
 /// An individual object in a ScriptData tag.
@@ -118,6 +210,11 @@ impl<'a> Variable<'a> {
             data: Value::parse(reader)?,
         })
     }
+
+    pub fn write<W: Write>(&self, writer: &mut FlvWriter<W>) -> Result<(), Error> {
+        write_string(writer, self.name, false)?;
+        self.data.write(writer)
+    }
 }
 
 #[derive(PartialEq, Debug, Clone)]
@@ -149,12 +246,36 @@ impl<'a> ScriptData<'a> {
             vars.push(Variable::parse(reader)?);
         }
     }
+
+    /// Write a script data structure.
+    ///
+    /// The leading byte that `parse` skips is written as the type of a string
+    /// value, so that the first variable reads as the usual `onMetaData`
+    /// string and value pair. No terminator is written, as the data size of
+    /// the tag marks the end of the structure.
+    pub fn write<W: Write>(&self, writer: &mut FlvWriter<W>) -> Result<(), Error> {
+        writer.write_u8(2)?;
+        for variable in &self.0 {
+            variable.write(writer)?;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::error::Error;
     use crate::reader::FlvReader;
-    use crate::script::{parse_string, ScriptData, Value, Variable};
+    use crate::script::{parse_string, write_string, ScriptData, Value, Variable};
+    use crate::writer::FlvWriter;
+    use std::io::Seek;
+
+    fn write_value(value: &Value<'_>) -> Vec<u8> {
+        let mut writer = FlvWriter::new(vec![]);
+        value.write(&mut writer).unwrap();
+        writer.into_inner()
+    }
 
     #[test]
     fn read_string() {
@@ -386,4 +507,113 @@ mod tests {
             ]))
         );
     }
+
+    #[test]
+    fn write_string_short() {
+        let mut writer = FlvWriter::new(vec![]);
+        write_string(&mut writer, &[0x01, 0x02, 0x03], false).unwrap();
+
+        assert_eq!(writer.into_inner(), [0x00, 0x03, 0x01, 0x02, 0x03]);
+    }
+
+    #[test]
+    fn write_string_too_long() {
+        let mut writer = FlvWriter::new(vec![]);
+
+        assert_eq!(
+            write_string(&mut writer, &[0; 0x10000], false),
+            Err(Error::DataTooLarge)
+        );
+    }
+
+    #[test]
+    fn write_value_roundtrip() {
+        let values = [
+            Value::Number(12.3),
+            Value::Boolean(true),
+            Value::String(&[0x01, 0x02, 0x03]),
+            Value::MovieClip(&[0x01, 0x02, 0x03]),
+            Value::LongString(&[0x01, 0x02, 0x03]),
+            Value::Null,
+            Value::Undefined,
+            Value::Reference(0x2438),
+            Value::Date {
+                unix_time: 12.3,
+                local_offset: -2,
+            },
+            Value::StrictArray(vec![Value::Undefined, Value::Null]),
+            Value::Object(vec![
+                Variable {
+                    name: &[0x01, 0x02, 0x03],
+                    data: Value::Undefined,
+                },
+                Variable {
+                    name: &[0x01, 0x02, 0x03],
+                    data: Value::StrictArray(vec![Value::Number(1.0)]),
+                },
+            ]),
+            Value::EcmaArray(vec![Variable {
+                name: &[0x01, 0x02, 0x03],
+                data: Value::Object(vec![]),
+            }]),
+        ];
+
+        for value in values {
+            let data = write_value(&value);
+            let mut reader = FlvReader::from_source(&data);
+
+            assert_eq!(Value::parse(&mut reader), Ok(value));
+            assert_eq!(reader.stream_position().unwrap(), data.len() as u64);
+        }
+    }
+
+    #[test]
+    fn write_value_ecmaarray() {
+        let value = Value::EcmaArray(vec![
+            Variable {
+                name: &[0x01, 0x02, 0x03],
+                data: Value::Undefined,
+            },
+            Variable {
+                name: &[0x01, 0x02, 0x03],
+                data: Value::Null,
+            },
+        ]);
+
+        assert_eq!(
+            write_value(&value),
+            [
+                0x08, 0x00, 0x00, 0x00, 0x02, 0x00, 0x03, 0x01, 0x02, 0x03, 0x06, 0x00, 0x03, 0x01,
+                0x02, 0x03, 0x05, 0x00, 0x00, 0x09,
+            ]
+        );
+    }
+
+    #[test]
+    fn write_scriptdata() {
+        let script_data = ScriptData(vec![
+            Variable {
+                name: &[0x01, 0x02, 0x03],
+                data: Value::Undefined,
+            },
+            Variable {
+                name: &[0x01, 0x02, 0x03],
+                data: Value::Null,
+            },
+        ]);
+        let mut writer = FlvWriter::new(vec![]);
+        script_data.write(&mut writer).unwrap();
+        let data = writer.into_inner();
+
+        assert_eq!(
+            data,
+            [0x02, 0x00, 0x03, 0x01, 0x02, 0x03, 0x06, 0x00, 0x03, 0x01, 0x02, 0x03, 0x05]
+        );
+
+        let mut reader = FlvReader::from_source(&data);
+        assert_eq!(
+            ScriptData::parse(&mut reader, data.len() as u32),
+            Ok(script_data)
+        );
+    }
 }
@@ -1,6 +1,7 @@
 use crate::error::Error;
+use crate::writer::FlvWriter;
 use crate::FlvReader;
-use std::io::Seek;
+use std::io::{Seek, Write};
 
 #[repr(u8)]
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
@@ -172,6 +173,25 @@ impl<'a> AudioData<'a> {
             data,
         })
     }
+
+    /// Write an audio data structure.
+    ///
+    /// As with `parse`, the AAC packet type is expected to be the first byte
+    /// of the payload of the `Aac*` variants.
+    pub fn write<W: Write>(&self, writer: &mut FlvWriter<W>) -> Result<(), Error> {
+        writer.write_u8(
+            ((self.format as u8) << 4)
+                | ((self.rate as u8) << 2)
+                | ((self.size as u8) << 1)
+                | (self.sound_type as u8),
+        )?;
+
+        match self.data {
+            AudioDataType::Raw(data)
+            | AudioDataType::AacSequenceHeader(data)
+            | AudioDataType::AacRaw(data) => writer.write(data),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -179,6 +199,7 @@ mod tests {
     use crate::error::Error;
     use crate::reader::FlvReader;
     use crate::sound::{AudioData, AudioDataType, SoundFormat, SoundRate, SoundSize, SoundType};
+    use crate::writer::FlvWriter;
 
     #[test]
     fn read_audiodata() {
@@ -252,4 +273,44 @@ mod tests {
             Err(Error::UnknownAacPacketType(2))
         );
     }
+
+    #[test]
+    fn write_audiodata() {
+        let audio_data = AudioData {
+            format: SoundFormat::Speex,
+            rate: SoundRate::R44_000,
+            size: SoundSize::Bits16,
+            sound_type: SoundType::Stereo,
+            data: AudioDataType::Raw(&[0x12, 0x34, 0x56, 0x78]),
+        };
+        let mut writer = FlvWriter::new(vec![]);
+        audio_data.write(&mut writer).unwrap();
+        let data = writer.into_inner();
+
+        assert_eq!(data, [0xBF, 0x12, 0x34, 0x56, 0x78]);
+        assert_eq!(
+            AudioData::parse(&mut FlvReader::from_source(&data), data.len() as u32),
+            Ok(audio_data)
+        );
+    }
+
+    #[test]
+    fn write_audiodata_aac() {
+        let audio_data = AudioData {
+            format: SoundFormat::Aac,
+            rate: SoundRate::R44_000,
+            size: SoundSize::Bits8,
+            sound_type: SoundType::Stereo,
+            data: AudioDataType::AacSequenceHeader(&[0x00, 0x12, 0x10]),
+        };
+        let mut writer = FlvWriter::new(vec![]);
+        audio_data.write(&mut writer).unwrap();
+        let data = writer.into_inner();
+
+        assert_eq!(data, [0xAD, 0x00, 0x12, 0x10]);
+        assert_eq!(
+            AudioData::parse(&mut FlvReader::from_source(&data), data.len() as u32),
+            Ok(audio_data)
+        );
+    }
 }
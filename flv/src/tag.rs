@@ -3,8 +3,9 @@ use crate::reader::FlvReader;
 use crate::script::ScriptData;
 use crate::sound::AudioData;
 use crate::video::VideoData;
+use crate::writer::FlvWriter;
 
-use std::io::{Seek, SeekFrom};
+use std::io::{Seek, SeekFrom, Write};
 
 #[repr(u8)]
 #[derive(PartialEq, Debug, Clone)]
@@ -28,6 +29,9 @@ pub struct Tag<'a> {
 }
 
 impl<'a> Tag<'a> {
+    /// The size of a tag structure without its data.
+    const HEADER_SIZE: u32 = 11;
+
     /// Parse a single FLV tag structure.
     ///
     /// FLV files are constructed as a list of tags. Back pointers to prior
@@ -114,6 +118,46 @@ impl<'a> Tag<'a> {
         }
     }
 
+    /// Write a single FLV tag structure.
+    ///
+    /// This is the inverse of `parse`: the back pointer to the prior tag is
+    /// written first, followed by the tag itself. `FlvWriter::finish` writes
+    /// the back pointer to the last tag at the end of the file.
+    ///
+    /// Tags with `TagData::Invalid` cannot be written.
+    pub fn write<W: Write>(&self, writer: &mut FlvWriter<W>) -> Result<(), Error> {
+        let mut data = FlvWriter::new(vec![]);
+        let tag_type = match &self.data {
+            TagData::Audio(audio) => {
+                audio.write(&mut data)?;
+                8
+            }
+            TagData::Video(video) => {
+                video.write(&mut data)?;
+                9
+            }
+            TagData::Script(script) => {
+                script.write(&mut data)?;
+                18
+            }
+            TagData::Invalid(_) => return Err(Error::InvalidTagData),
+        };
+        let data = data.into_inner();
+        let data_size = data.len().try_into().map_err(|_| Error::DataTooLarge)?;
+        let timestamp = self.timestamp as u32;
+
+        writer.write_u32(writer.previous_tag_size())?;
+        writer.write_u8(tag_type)?;
+        writer.write_u24(data_size)?;
+        writer.write_u24(timestamp & 0xFF_FFFF)?;
+        writer.write_u8((timestamp >> 24) as u8)?;
+        writer.write_u24(self.stream_id)?;
+        writer.write(&data)?;
+        writer.set_previous_tag_size(Self::HEADER_SIZE + data_size);
+
+        Ok(())
+    }
+
     /// Skip back to the prior tag in the FLV.
     ///
     /// FLV files are constructed as a list of tags. Back pointers to prior
@@ -146,6 +190,7 @@ mod tests {
     use crate::sound::{AudioData, AudioDataType, SoundFormat, SoundRate, SoundSize, SoundType};
     use crate::tag::{Tag, TagData};
     use crate::video::{CodecId, FrameType, VideoData, VideoPacket};
+    use crate::writer::FlvWriter;
 
     #[test]
     fn read_tag_sounddata() {
@@ -239,6 +284,95 @@ mod tests {
         )
     }
 
+    #[test]
+    fn write_tag_scriptdata() {
+        let tag = Tag {
+            timestamp: 0,
+            stream_id: 0x5000,
+            data: TagData::Script(ScriptData(vec![
+                Variable {
+                    name: &[0x01, 0x02, 0x03],
+                    data: Value::Undefined,
+                },
+                Variable {
+                    name: &[0x01, 0x02, 0x03],
+                    data: Value::Null,
+                },
+            ])),
+        };
+        let mut writer = FlvWriter::new(vec![]);
+        tag.write(&mut writer).unwrap();
+        let data = writer.finish().unwrap();
+
+        assert_eq!(
+            data,
+            [
+                0x00, 0x00, 0x00, 0x00, 0x12, 0x00, 0x00, 0x0D, 0x00, 0x00, 0x00, 0x00, 0x00, 0x50,
+                0x00, 0x02, 0x00, 0x03, 0x01, 0x02, 0x03, 0x06, 0x00, 0x03, 0x01, 0x02, 0x03, 0x05,
+                0x00, 0x00, 0x00, 0x18,
+            ]
+        );
+        assert_eq!(Tag::parse(&mut FlvReader::from_source(&data)), Ok(tag));
+    }
+
+    #[test]
+    fn write_tag_invalid() {
+        let tag = Tag {
+            timestamp: 0,
+            stream_id: 0,
+            data: TagData::Invalid(Error::UnknownTagType(0x45)),
+        };
+
+        assert_eq!(
+            tag.write(&mut FlvWriter::new(vec![])),
+            Err(Error::InvalidTagData)
+        );
+    }
+
+    #[test]
+    fn write_tags_roundtrip() {
+        let tags = [
+            Tag {
+                timestamp: 0,
+                stream_id: 0,
+                data: TagData::Video(VideoData {
+                    frame_type: FrameType::Keyframe,
+                    codec_id: CodecId::SorensonH263,
+                    data: VideoPacket::Data(&[0x12, 0x34, 0x56, 0x78]),
+                }),
+            },
+            Tag {
+                timestamp: 0x1234_5678,
+                stream_id: 0,
+                data: TagData::Audio(AudioData {
+                    format: SoundFormat::MP3,
+                    rate: SoundRate::R22_000,
+                    size: SoundSize::Bits16,
+                    sound_type: SoundType::Mono,
+                    data: AudioDataType::Raw(&[0x9A, 0xBC]),
+                }),
+            },
+        ];
+        let mut writer = FlvWriter::new(vec![]);
+        for tag in &tags {
+            tag.write(&mut writer).unwrap();
+        }
+        let data = writer.finish().unwrap();
+        let mut reader = FlvReader::from_source(&data);
+
+        for tag in &tags {
+            assert_eq!(Tag::parse(&mut reader).as_ref(), Ok(tag));
+        }
+        assert_eq!(Tag::parse(&mut reader), Err(Error::EndOfData));
+
+        // The back pointers allow walking the file in reverse.
+        for tag in tags.iter().rev() {
+            Tag::skip_back(&mut reader).unwrap();
+            assert_eq!(Tag::parse(&mut reader).as_ref(), Ok(tag));
+            Tag::skip_back(&mut reader).unwrap();
+        }
+    }
+
     #[test]
     fn read_tag_onmetadata() {
         let data = [
@@ -1,6 +1,7 @@
 use crate::error::Error;
 use crate::reader::FlvReader;
-use std::io::Seek;
+use crate::writer::FlvWriter;
+use std::io::{Seek, Write};
 
 #[repr(u8)]
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
@@ -161,13 +162,62 @@ impl<'a> VideoData<'a> {
             data: packet,
         })
     }
+
+    /// Write a video data structure.
+    ///
+    /// The packet is written as-is, regardless of whether it matches the
+    /// frame type and codec of the structure.
+    pub fn write<W: Write>(&self, writer: &mut FlvWriter<W>) -> Result<(), Error> {
+        writer.write_u8(((self.frame_type as u8) << 4) | (self.codec_id as u8))?;
+
+        match self.data {
+            VideoPacket::Data(data) => writer.write(data),
+            VideoPacket::Vp6Data {
+                hadjust,
+                vadjust,
+                data,
+            } => {
+                writer.write_u8(((vadjust & 0x0F) << 4) | (hadjust & 0x0F))?;
+                writer.write(data)
+            }
+            VideoPacket::AvcSequenceHeader(data) => {
+                writer.write_u8(0)?;
+                writer.write_u24(0)?;
+                writer.write(data)
+            }
+            VideoPacket::AvcNalu {
+                composition_time_offset,
+                data,
+            } => {
+                if !(-0x80_0000..0x80_0000).contains(&composition_time_offset) {
+                    return Err(Error::DataTooLarge);
+                }
+
+                writer.write_u8(1)?;
+                writer.write_u24(composition_time_offset as u32 & 0xFF_FFFF)?;
+                writer.write(data)
+            }
+            VideoPacket::AvcEndOfSequence => {
+                writer.write_u8(2)?;
+                writer.write_u24(0)
+            }
+            VideoPacket::CommandFrame(command) => writer.write_u8(command as u8),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::error::Error;
     use crate::reader::FlvReader;
-    use crate::video::{CodecId, FrameType, VideoData, VideoPacket};
+    use crate::video::{CodecId, CommandFrame, FrameType, VideoData, VideoPacket};
+    use crate::writer::FlvWriter;
+
+    fn write_videodata(video_data: &VideoData<'_>) -> Vec<u8> {
+        let mut writer = FlvWriter::new(vec![]);
+        video_data.write(&mut writer).unwrap();
+        writer.into_inner()
+    }
 
     #[test]
     fn read_videodata() {
@@ -324,4 +374,95 @@ mod tests {
             Err(Error::UnknownAvcPacketType(0xFF))
         );
     }
+
+    #[test]
+    fn write_videodata_vp6() {
+        let video_data = VideoData {
+            frame_type: FrameType::Keyframe,
+            codec_id: CodecId::On2Vp6,
+            data: VideoPacket::Vp6Data {
+                hadjust: 0x07,
+                vadjust: 0x03,
+                data: &[0x12, 0x34, 0x56, 0x78],
+            },
+        };
+        let data = write_videodata(&video_data);
+
+        assert_eq!(data, [0x14, 0x37, 0x12, 0x34, 0x56, 0x78]);
+        assert_eq!(
+            VideoData::parse(&mut FlvReader::from_source(&data), data.len() as u32),
+            Ok(video_data)
+        );
+    }
+
+    #[test]
+    fn write_videodata_avcnalu_negative() {
+        let video_data = VideoData {
+            frame_type: FrameType::Keyframe,
+            codec_id: CodecId::Avc,
+            data: VideoPacket::AvcNalu {
+                composition_time_offset: -2,
+                data: &[0x12, 0x34, 0x56, 0x78],
+            },
+        };
+        let data = write_videodata(&video_data);
+
+        assert_eq!(data, [0x17, 0x01, 0xFF, 0xFF, 0xFE, 0x12, 0x34, 0x56, 0x78]);
+        assert_eq!(
+            VideoData::parse(&mut FlvReader::from_source(&data), data.len() as u32),
+            Ok(video_data)
+        );
+    }
+
+    #[test]
+    fn write_videodata_roundtrip() {
+        let packets = [
+            VideoData {
+                frame_type: FrameType::Interframe,
+                codec_id: CodecId::SorensonH263,
+                data: VideoPacket::Data(&[0x12, 0x34, 0x56, 0x78]),
+            },
+            VideoData {
+                frame_type: FrameType::Keyframe,
+                codec_id: CodecId::Avc,
+                data: VideoPacket::AvcSequenceHeader(&[0x12, 0x34, 0x56, 0x78]),
+            },
+            VideoData {
+                frame_type: FrameType::Keyframe,
+                codec_id: CodecId::Avc,
+                data: VideoPacket::AvcEndOfSequence,
+            },
+            VideoData {
+                frame_type: FrameType::CommandFrame,
+                codec_id: CodecId::ScreenVideo,
+                data: VideoPacket::CommandFrame(CommandFrame::EndOfClientSideSeek),
+            },
+        ];
+
+        for video_data in packets {
+            let data = write_videodata(&video_data);
+
+            assert_eq!(
+                VideoData::parse(&mut FlvReader::from_source(&data), data.len() as u32),
+                Ok(video_data)
+            );
+        }
+    }
+
+    #[test]
+    fn write_videodata_avcnalu_out_of_range() {
+        let video_data = VideoData {
+            frame_type: FrameType::Keyframe,
+            codec_id: CodecId::Avc,
+            data: VideoPacket::AvcNalu {
+                composition_time_offset: 0x80_0000,
+                data: &[],
+            },
+        };
+
+        assert_eq!(
+            video_data.write(&mut FlvWriter::new(vec![])),
+            Err(Error::DataTooLarge)
+        );
+    }
 }
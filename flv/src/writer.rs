@@ -0,0 +1,110 @@
+use crate::error::Error as FlvError;
+use std::io::Write;
+
+/// A writer that allows muxing an FLV container.
+///
+/// Headers and tags are written with their respective `write` functions,
+/// which mirror the `parse` functions used to read them back.
+pub struct FlvWriter<W: Write> {
+    sink: W,
+
+    /// The size of the last tag written, which is stored before the next tag.
+    previous_tag_size: u32,
+}
+
+impl<W: Write> FlvWriter<W> {
+    pub fn new(sink: W) -> Self {
+        FlvWriter {
+            sink,
+            previous_tag_size: 0,
+        }
+    }
+
+    /// Finish the FLV by writing the size of the last tag, and return the sink.
+    pub fn finish(mut self) -> Result<W, FlvError> {
+        self.write_u32(self.previous_tag_size)?;
+        self.sink.flush()?;
+        Ok(self.sink)
+    }
+
+    /// Return the sink without writing the size of the last tag.
+    pub fn into_inner(self) -> W {
+        self.sink
+    }
+
+    pub(crate) fn previous_tag_size(&self) -> u32 {
+        self.previous_tag_size
+    }
+
+    pub(crate) fn set_previous_tag_size(&mut self, size: u32) {
+        self.previous_tag_size = size;
+    }
+
+    pub fn write(&mut self, data: &[u8]) -> Result<(), FlvError> {
+        self.sink.write_all(data)?;
+        Ok(())
+    }
+
+    pub fn write_u8(&mut self, value: u8) -> Result<(), FlvError> {
+        self.write(&[value])
+    }
+
+    pub fn write_u16(&mut self, value: u16) -> Result<(), FlvError> {
+        self.write(&value.to_be_bytes())
+    }
+
+    pub fn write_i16(&mut self, value: i16) -> Result<(), FlvError> {
+        self.write(&value.to_be_bytes())
+    }
+
+    /// Write the lower 24 bits of a value.
+    ///
+    /// Values that don't fit in 24 bits yield `DataTooLarge`.
+    pub fn write_u24(&mut self, value: u32) -> Result<(), FlvError> {
+        if value > 0xFF_FFFF {
+            return Err(FlvError::DataTooLarge);
+        }
+
+        self.write(&value.to_be_bytes()[1..])
+    }
+
+    pub fn write_u32(&mut self, value: u32) -> Result<(), FlvError> {
+        self.write(&value.to_be_bytes())
+    }
+
+    pub fn write_f64(&mut self, value: f64) -> Result<(), FlvError> {
+        self.write(&value.to_be_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::writer::FlvWriter;
+
+    #[test]
+    fn write_numbers() {
+        let mut writer = FlvWriter::new(vec![]);
+        writer.write_u8(0x12).unwrap();
+        writer.write_u16(0x3456).unwrap();
+        writer.write_i16(-2).unwrap();
+        writer.write_u24(0x789ABC).unwrap();
+        writer.write_u32(0xDEF01234).unwrap();
+        writer.write_f64(12.3).unwrap();
+
+        assert_eq!(
+            writer.finish().unwrap(),
+            vec![
+                0x12, 0x34, 0x56, 0xFF, 0xFE, 0x78, 0x9A, 0xBC, 0xDE, 0xF0, 0x12, 0x34, 0x40, 0x28,
+                0x99, 0x99, 0x99, 0x99, 0x99, 0x9a, 0x00, 0x00, 0x00, 0x00
+            ]
+        );
+    }
+
+    #[test]
+    fn write_u24_too_large() {
+        let mut writer = FlvWriter::new(vec![]);
+
+        assert_eq!(writer.write_u24(0x1000000), Err(Error::DataTooLarge));
+    }
+}
@@ -11,19 +11,21 @@ use ruffle_core::backend::navigator::{
     async_return, create_fetch_error, get_encoding, ErrorResponse, NavigationMethod,
     NavigatorBackend, OwnedFuture, Request, SocketMode, SuccessResponse,
 };
+use ruffle_core::datagram_socket::{Datagram, DatagramSocketAction, DatagramSocketHandle};
 use ruffle_core::indexmap::IndexMap;
 use ruffle_core::loader::Error;
 use ruffle_core::socket::{ConnectionState, SocketAction, SocketHandle};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io;
 use std::io::ErrorKind;
+use std::net::{SocketAddr, ToSocketAddrs};
 use std::path::Path;
 use std::rc::Rc;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::TcpStream;
+use tokio::net::{TcpStream, UdpSocket};
 use tracing::warn;
 use url::{ParseError, Url};
 
@@ -465,6 +467,205 @@ impl<F: FutureSpawner + 'static, I: NavigatorInterface> NavigatorBackend
 
         tokio::spawn(future);
     }
+
+    fn bind_datagram_socket(
+        &mut self,
+        host: String,
+        port: u16,
+        handle: DatagramSocketHandle,
+        receiver: Receiver<Datagram>,
+        sender: Sender<DatagramSocketAction>,
+    ) -> io::Result<SocketAddr> {
+        let bind_addr = format!("{}:{}", host, port);
+        let is_bind_allowed = self.socket_allowed.contains(&bind_addr);
+        if !is_bind_allowed && self.socket_mode == SocketMode::Deny {
+            warn!("SWF tried to bind a datagram socket to {bind_addr}, but this is not allowed");
+            return Err(ErrorKind::PermissionDenied.into());
+        }
+
+        // When the user has to be asked first, nothing is bound until they allow it.
+        // Until then, the SWF sees the address it asked for, and the socket is closed
+        // if the user doesn't allow it.
+        let needs_confirmation = !is_bind_allowed && self.socket_mode == SocketMode::Ask;
+        let (socket, local_addr) = if needs_confirmation {
+            let local_addr = (host.as_str(), port)
+                .to_socket_addrs()?
+                .next()
+                .ok_or(ErrorKind::AddrNotAvailable)?;
+            (None, local_addr)
+        } else {
+            let socket = std::net::UdpSocket::bind((host.as_str(), port))?;
+            socket.set_nonblocking(true)?;
+            let local_addr = socket.local_addr()?;
+            (Some(UdpSocket::from_std(socket)?), local_addr)
+        };
+
+        let socket_allowed = Arc::new(self.socket_allowed.clone());
+        let socket_mode = self.socket_mode;
+        let interface = self.interface.clone();
+
+        tokio::spawn(async move {
+            let socket = match socket {
+                Some(socket) => socket,
+                None => {
+                    if !interface.confirm_socket(&host, port).await {
+                        let _ = sender.send(DatagramSocketAction::Close(handle)).await;
+                        return;
+                    }
+                    match UdpSocket::bind(local_addr).await {
+                        Ok(socket) => socket,
+                        Err(e) => {
+                            warn!("Failed to bind datagram socket to {local_addr}: {e}");
+                            let _ = sender.send(DatagramSocketAction::Close(handle)).await;
+                            return;
+                        }
+                    }
+                }
+            };
+
+            run_datagram_socket(
+                Arc::new(socket),
+                handle,
+                receiver,
+                sender,
+                socket_allowed,
+                socket_mode,
+                interface,
+            )
+            .await;
+        });
+
+        Ok(local_addr)
+    }
+}
+
+/// Sends and receives the datagrams of a bound socket, until either the socket fails
+/// or the channel sender of `receiver` has been dropped.
+async fn run_datagram_socket(
+    socket: Arc<UdpSocket>,
+    handle: DatagramSocketHandle,
+    receiver: Receiver<Datagram>,
+    sender: Sender<DatagramSocketAction>,
+    socket_allowed: Arc<HashSet<String>>,
+    socket_mode: SocketMode,
+    interface: impl NavigatorInterface,
+) {
+    // Whether the user allowed the remote addresses they were asked about.
+    // Datagrams are only received from addresses that datagrams may be sent to.
+    let confirmed: Arc<Mutex<HashMap<String, bool>>> = Default::default();
+
+    let read = {
+        let socket = socket.clone();
+        let socket_allowed = socket_allowed.clone();
+        let confirmed = confirmed.clone();
+        let sender = sender.clone();
+        async move {
+            let mut buffer = vec![0; 65536];
+
+            loop {
+                match socket.recv_from(&mut buffer).await {
+                    Ok((read, addr)) => {
+                        let addr_string = addr.to_string();
+                        let is_allowed = match (socket_allowed.contains(&addr_string), socket_mode)
+                        {
+                            (false, SocketMode::Allow) | (true, _) => true,
+                            (false, SocketMode::Deny) => false,
+                            // Don't prompt for every incoming datagram, only accept
+                            // the ones from addresses the user allowed sending to.
+                            (false, SocketMode::Ask) => confirmed
+                                .lock()
+                                .expect("non-poisoned")
+                                .get(&addr_string)
+                                .copied()
+                                .unwrap_or(false),
+                        };
+                        if !is_allowed {
+                            continue;
+                        }
+
+                        let datagram = Datagram {
+                            data: buffer[..read].to_vec(),
+                            host: addr.ip().to_string(),
+                            port: addr.port(),
+                        };
+
+                        let action = DatagramSocketAction::Data(handle, datagram);
+                        if sender.send(action).await.is_err() {
+                            return;
+                        }
+                    }
+                    // Sending to a closed port may report an error on the next receive.
+                    Err(e) if e.kind() == ErrorKind::ConnectionReset => {}
+                    Err(e) => {
+                        warn!("Failed to receive datagram: {}", e);
+                        let _ = sender.send(DatagramSocketAction::Close(handle)).await;
+                        return;
+                    }
+                }
+            }
+        }
+    };
+
+    let write = {
+        let interface = interface.clone();
+        async move {
+            while let Ok(datagram) = receiver.recv().await {
+                let addr = format!("{}:{}", datagram.host, datagram.port);
+                let is_allowed = match (socket_allowed.contains(&addr), socket_mode) {
+                    (false, SocketMode::Allow) | (true, _) => true,
+                    (false, SocketMode::Deny) => false,
+                    (false, SocketMode::Ask) => {
+                        let known = confirmed.lock().expect("non-poisoned").get(&addr).copied();
+                        match known {
+                            Some(is_allowed) => is_allowed,
+                            None => {
+                                let is_allowed = interface
+                                    .confirm_socket(&datagram.host, datagram.port)
+                                    .await;
+                                // Replies come from the resolved address, so allow that too.
+                                let resolved: Vec<_> = tokio::net::lookup_host(&addr)
+                                    .await
+                                    .map(|resolved| resolved.collect())
+                                    .unwrap_or_default();
+                                let mut confirmed = confirmed.lock().expect("non-poisoned");
+                                for resolved in resolved {
+                                    confirmed.insert(resolved.to_string(), is_allowed);
+                                }
+                                confirmed.insert(addr.clone(), is_allowed);
+                                is_allowed
+                            }
+                        }
+                    }
+                };
+
+                if !is_allowed {
+                    warn!("SWF tried to send a datagram to {addr}, but this is not allowed");
+                    continue;
+                }
+
+                let target = (datagram.host.as_str(), datagram.port);
+                let mut result = socket.send_to(&datagram.data, target).await;
+
+                // Broadcasting is only enabled once the SWF actually sends a broadcast,
+                // which the OS rejects until then.
+                if matches!(&result, Err(e) if e.kind() == ErrorKind::PermissionDenied)
+                    && !socket.broadcast().unwrap_or(true)
+                    && socket.set_broadcast(true).is_ok()
+                {
+                    result = socket.send_to(&datagram.data, target).await;
+                }
+
+                if let Err(e) = result {
+                    warn!("Failed to send datagram to {addr}: {e}");
+                }
+            }
+        }
+    };
+
+    tokio::select! {
+       _ = read => {},
+       _ = write => {},
+    };
 }
 
 #[cfg(test)]
@@ -742,6 +943,133 @@ mod tests {
         assert_eq!(read_server(&mut server_socket).await, "from client 2");
     }
 
+    fn bind_test_datagram_socket(
+        socket_allow: bool,
+    ) -> io::Result<(SocketAddr, Sender<Datagram>, Receiver<DatagramSocketAction>)> {
+        let mut backend = new_test_backend(socket_allow);
+
+        let (write, receiver) = async_channel::unbounded();
+        let (sender, read) = async_channel::unbounded();
+
+        let addr = backend.bind_datagram_socket(
+            "127.0.0.1".to_string(),
+            0,
+            DatagramSocketHandle::default(),
+            receiver,
+            sender,
+        )?;
+
+        Ok((addr, write, read))
+    }
+
+    #[macro_rules_attribute::apply(async_test)]
+    async fn test_datagram_socket_communication() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let server_addr = server.local_addr().unwrap();
+        let (client_addr, client_write, client_read) =
+            bind_test_datagram_socket(true).expect("bind datagram socket");
+
+        client_write
+            .send(Datagram {
+                data: b"Hello from client".to_vec(),
+                host: server_addr.ip().to_string(),
+                port: server_addr.port(),
+            })
+            .await
+            .unwrap();
+
+        let mut buffer = [0; 4096];
+        let (read, addr) = server
+            .recv_from(&mut buffer)
+            .or(async_timeout!())
+            .await
+            .unwrap();
+        assert_eq!(&buffer[..read], b"Hello from client");
+        assert_eq!(addr, client_addr);
+
+        server.send_to(b"Hello World!", client_addr).await.unwrap();
+
+        assert_next_socket_actions!(
+            client_read;
+            DatagramSocketAction::Data(
+                DatagramSocketHandle::default(),
+                Datagram {
+                    data: b"Hello World!".to_vec(),
+                    host: server_addr.ip().to_string(),
+                    port: server_addr.port(),
+                },
+            ),
+        );
+    }
+
+    #[test]
+    fn test_datagram_socket_deny() {
+        let error = bind_test_datagram_socket(false).expect_err("bind should be denied");
+        assert_eq!(error.kind(), ErrorKind::PermissionDenied);
+    }
+
+    /// An interface whose user never allows sockets they're asked about.
+    #[derive(Clone)]
+    struct DenyingInterface;
+
+    impl NavigatorInterface for DenyingInterface {
+        fn navigate_to_website(&self, _url: Url) {}
+
+        async fn open_file(&self, path: &Path) -> io::Result<File> {
+            File::open(path)
+        }
+
+        async fn confirm_socket(&self, _host: &str, _port: u16) -> bool {
+            false
+        }
+    }
+
+    #[macro_rules_attribute::apply(async_test)]
+    async fn test_datagram_socket_ask_denied() {
+        let url = Url::parse("https://example.com/path/").unwrap();
+        let mut backend = ExternalNavigatorBackend::new(
+            url.clone(),
+            None,
+            None,
+            TestFutureSpawner,
+            None,
+            false,
+            Default::default(),
+            SocketMode::Ask,
+            Rc::new(PlayingContent::DirectFile(url)),
+            DenyingInterface,
+        );
+
+        let port = std::net::UdpSocket::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let (_client_write, receiver) = async_channel::unbounded();
+        let (sender, client_read) = async_channel::unbounded();
+        let addr = backend
+            .bind_datagram_socket(
+                "127.0.0.1".to_string(),
+                port,
+                DatagramSocketHandle::default(),
+                receiver,
+                sender,
+            )
+            .expect("bind should wait for the user");
+        assert_eq!(
+            addr,
+            SocketAddr::from_str(&format!("127.0.0.1:{port}")).unwrap()
+        );
+
+        // Nothing is bound before the user is asked.
+        std::net::UdpSocket::bind(addr).expect("port should still be free");
+
+        assert_next_socket_actions!(
+            client_read;
+            DatagramSocketAction::Close(DatagramSocketHandle::default()),
+        );
+    }
+
     #[macro_rules_attribute::apply(async_test)]
     async fn test_socket_flush_before_close() {
         let (accept_task, addr) = start_test_server().await;
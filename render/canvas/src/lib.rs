@@ -7,12 +7,16 @@ use ruffle_render::backend::{
     RenderBackend, ShapeHandle, ShapeHandleImpl, ViewportDimensions,
 };
 use ruffle_render::bitmap::{
-    Bitmap, BitmapHandle, BitmapHandleImpl, BitmapSource, PixelRegion, PixelSnapping, RgbaBufRead,
-    SyncHandle,
+    Bitmap, BitmapFormat, BitmapHandle, BitmapHandleImpl, BitmapSource, CpuSyncHandle, PixelRegion,
+    PixelSnapping, RgbaBufRead, SyncHandle,
 };
 use ruffle_render::commands::{CommandHandler, CommandList, RenderBlendMode};
 use ruffle_render::error::Error;
 use ruffle_render::matrix::Matrix;
+use ruffle_render::pixel_bender::interpreter::{self, CpuShaderBitmaps, PixelBenderImage};
+use ruffle_render::pixel_bender::{
+    PixelBenderShader, PixelBenderShaderArgument, PixelBenderShaderHandle,
+};
 use ruffle_render::quality::StageQuality;
 use ruffle_render::shape_utils::{DistilledShape, DrawCommand, LineScaleMode, LineScales};
 use ruffle_render::transform::Transform;
@@ -211,6 +215,16 @@ impl BitmapData {
             .into_js_result()?;
         Ok(())
     }
+
+    fn read_pixels(&self) -> Result<Vec<u8>, JsValue> {
+        let image_data = self.context.get_image_data(
+            0.0,
+            0.0,
+            self.canvas.width().into(),
+            self.canvas.height().into(),
+        )?;
+        Ok(image_data.data().0)
+    }
 }
 
 impl WebCanvasRenderBackend {
@@ -569,26 +583,27 @@ impl RenderBackend for WebCanvasRenderBackend {
 
     fn compile_pixelbender_shader(
         &mut self,
-        _shader: ruffle_render::pixel_bender::PixelBenderShader,
-    ) -> Result<ruffle_render::pixel_bender::PixelBenderShaderHandle, Error> {
-        Err(Error::Unimplemented("compile_pixelbender_shader".into()))
+        shader: PixelBenderShader,
+    ) -> Result<PixelBenderShaderHandle, Error> {
+        // Shaders are run on the CPU, so there's nothing to compile.
+        Ok(PixelBenderShaderHandle(Arc::new(shader)))
     }
 
     fn run_pixelbender_shader(
         &mut self,
-        _handle: ruffle_render::pixel_bender::PixelBenderShaderHandle,
-        _arguments: &[ruffle_render::pixel_bender::PixelBenderShaderArgument],
-        _target: &PixelBenderTarget,
+        handle: PixelBenderShaderHandle,
+        arguments: &[PixelBenderShaderArgument],
+        target: &PixelBenderTarget,
     ) -> Result<PixelBenderOutput, Error> {
-        Err(Error::Unimplemented("run_pixelbender_shader".into()))
+        interpreter::run_shader_job(self, handle.0.parsed_shader(), arguments, target)
     }
 
     fn resolve_sync_handle(
         &mut self,
-        _handle: Box<dyn SyncHandle>,
-        _with_rgba: RgbaBufRead,
+        handle: Box<dyn SyncHandle>,
+        with_rgba: RgbaBufRead,
     ) -> Result<(), Error> {
-        Err(Error::Unimplemented("Sync handle resolution".into()))
+        CpuSyncHandle::resolve(handle, with_rgba)
     }
 
     fn create_empty_texture(&mut self, width: u32, height: u32) -> Result<BitmapHandle, Error> {
@@ -597,6 +612,31 @@ impl RenderBackend for WebCanvasRenderBackend {
     }
 }
 
+impl CpuShaderBitmaps for WebCanvasRenderBackend {
+    fn bitmap_size(&self, handle: &BitmapHandle) -> (u32, u32) {
+        let bitmap = as_bitmap_data(handle);
+        (bitmap.canvas.width(), bitmap.canvas.height())
+    }
+
+    fn read_bitmap(&mut self, handle: &BitmapHandle) -> Result<PixelBenderImage, Error> {
+        let bitmap = as_bitmap_data(handle);
+        let pixels = bitmap.read_pixels().map_err(Error::JavascriptError)?;
+        Ok(PixelBenderImage::from_rgba8(
+            bitmap.canvas.width(),
+            bitmap.canvas.height(),
+            &pixels,
+        ))
+    }
+
+    fn write_bitmap(&mut self, handle: &BitmapHandle, rgba: Vec<u8>) -> Result<(), Error> {
+        let bitmap = as_bitmap_data(handle);
+        let (width, height) = (bitmap.canvas.width(), bitmap.canvas.height());
+        bitmap
+            .update_pixels(Bitmap::new(width, height, BitmapFormat::Rgba, rgba))
+            .map_err(Error::JavascriptError)
+    }
+}
+
 impl CommandHandler for WebCanvasRenderBackend {
     fn render_bitmap(
         &mut self,
@@ -0,0 +1,265 @@
+use crate::pixmap::Pixmap;
+use crate::rasterizer::Rasterizer;
+use crate::shape::Shape;
+use crate::{as_bitmap, Bitmap as SoftwareBitmap};
+use ruffle_render::backend::{
+    BitmapCacheEntry, Context3D, Context3DProfile, PixelBenderOutput, PixelBenderTarget,
+    RenderBackend, ShapeHandle, ViewportDimensions,
+};
+use ruffle_render::bitmap::{
+    Bitmap, BitmapHandle, BitmapSource, CpuSyncHandle, PixelRegion, RgbaBufRead, SyncHandle,
+};
+use ruffle_render::commands::CommandList;
+use ruffle_render::error::Error;
+use ruffle_render::filters::Filter;
+use ruffle_render::pixel_bender::interpreter::{self, CpuShaderBitmaps, PixelBenderImage};
+use ruffle_render::pixel_bender::{
+    PixelBenderShader, PixelBenderShaderArgument, PixelBenderShaderHandle,
+};
+use ruffle_render::quality::StageQuality;
+use ruffle_render::shape_utils::DistilledShape;
+use ruffle_render::tessellator::ShapeTessellator;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::sync::{Arc, RwLock};
+use swf::Color;
+
+/// A render backend that draws everything on the CPU into an in-memory frame.
+///
+/// This is useful on headless machines without a GPU; the rendered frame
+/// can be retrieved with [`SoftwareRenderBackend::capture_frame`].
+pub struct SoftwareRenderBackend {
+    dimensions: ViewportDimensions,
+    quality: StageQuality,
+    shape_tessellator: ShapeTessellator,
+    frame: Pixmap,
+    /// Unsupported features used since the last frame was submitted.
+    unsupported_features: RefCell<BTreeSet<&'static str>>,
+    /// Unsupported features used to draw the last submitted frame.
+    frame_unsupported_features: BTreeSet<&'static str>,
+}
+
+impl SoftwareRenderBackend {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            dimensions: ViewportDimensions {
+                width,
+                height,
+                scale_factor: 1.0,
+            },
+            quality: StageQuality::High,
+            shape_tessellator: ShapeTessellator::new(),
+            frame: Pixmap::new(width, height),
+            unsupported_features: RefCell::new(BTreeSet::new()),
+            frame_unsupported_features: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the last submitted frame, with straight (non-premultiplied) alpha.
+    pub fn capture_frame(&self) -> Option<image::RgbaImage> {
+        let mut data = self.frame.data().to_vec();
+        ruffle_render::utils::unmultiply_alpha_rgba(&mut data);
+        image::RgbaImage::from_raw(self.frame.width(), self.frame.height(), data)
+    }
+
+    /// Returns the features that were used for the last submitted frame but aren't supported
+    /// by this renderer, such as filters, Context3D or shader blend modes.
+    ///
+    /// This includes anything used since the frame before it, such as offscreen draws.
+    /// A frame drawn with them will not look the same as on a GPU backend.
+    pub fn unsupported_features(&self) -> BTreeSet<&'static str> {
+        self.frame_unsupported_features.clone()
+    }
+}
+
+impl RenderBackend for SoftwareRenderBackend {
+    fn viewport_dimensions(&self) -> ViewportDimensions {
+        self.dimensions
+    }
+
+    fn set_viewport_dimensions(&mut self, dimensions: ViewportDimensions) {
+        self.dimensions = dimensions;
+        self.frame = Pixmap::new(dimensions.width, dimensions.height);
+    }
+
+    fn register_shape(
+        &mut self,
+        shape: DistilledShape,
+        bitmap_source: &dyn BitmapSource,
+    ) -> ShapeHandle {
+        let mesh = self
+            .shape_tessellator
+            .tessellate_shape(shape, bitmap_source);
+        // Shapes hold on to their bitmap handles, which aren't `Send`.
+        #[allow(clippy::arc_with_non_send_sync)]
+        ShapeHandle(Arc::new(Shape::new(mesh, bitmap_source, self)))
+    }
+
+    fn render_offscreen(
+        &mut self,
+        handle: BitmapHandle,
+        commands: CommandList,
+        quality: StageQuality,
+        bounds: PixelRegion,
+    ) -> Option<Box<dyn SyncHandle>> {
+        let bitmap = as_bitmap(&handle);
+        // Draw onto a copy, as the commands may be sampling from this same bitmap.
+        let target = bitmap.read().clone();
+        let target =
+            Rasterizer::new(target, quality, self.unsupported_features.get_mut()).draw(commands);
+        let mut bounds = bounds;
+        bounds.clamp(target.width(), target.height());
+        let sync_handle = CpuSyncHandle {
+            data: target.read_region(bounds),
+            row_length: bounds.width() * 4,
+        };
+        *bitmap.write() = target;
+        Some(Box::new(sync_handle))
+    }
+
+    fn is_offscreen_supported(&self) -> bool {
+        true
+    }
+
+    fn is_filter_supported(&self, filter: &Filter) -> bool {
+        // Shader filters are run by the CPU interpreter in core, but other filters are
+        // just copied, so remember that this frame won't look right.
+        if !matches!(filter, Filter::ShaderFilter(_)) {
+            self.unsupported_features.borrow_mut().insert("filters");
+        }
+        false
+    }
+
+    fn submit_frame(
+        &mut self,
+        clear: Color,
+        commands: CommandList,
+        cache_entries: Vec<BitmapCacheEntry>,
+    ) {
+        // Filters are not supported yet, so cached bitmaps are drawn without them.
+        for entry in cache_entries {
+            if !entry.filters.is_empty() {
+                self.unsupported_features.get_mut().insert("filters");
+            }
+            let bitmap = as_bitmap(&entry.handle);
+            let mut target = {
+                let pixmap = bitmap.read();
+                Pixmap::new(pixmap.width(), pixmap.height())
+            };
+            target.fill(entry.clear);
+            *bitmap.write() =
+                Rasterizer::new(target, self.quality, self.unsupported_features.get_mut())
+                    .draw(entry.commands);
+        }
+
+        let mut frame = Pixmap::new(self.dimensions.width, self.dimensions.height);
+        frame.fill(clear);
+        self.frame = Rasterizer::new(frame, self.quality, self.unsupported_features.get_mut())
+            .draw(commands);
+        self.frame_unsupported_features = std::mem::take(self.unsupported_features.get_mut());
+    }
+
+    fn register_bitmap(&mut self, bitmap: Bitmap) -> Result<BitmapHandle, Error> {
+        Ok(BitmapHandle(Arc::new(SoftwareBitmap(RwLock::new(
+            Pixmap::from_bitmap(bitmap),
+        )))))
+    }
+
+    fn update_texture(
+        &mut self,
+        handle: &BitmapHandle,
+        bitmap: Bitmap,
+        mut region: PixelRegion,
+    ) -> Result<(), Error> {
+        let source = Pixmap::from_bitmap(bitmap);
+        let mut pixmap = as_bitmap(handle).write();
+        if source.width() != pixmap.width() || source.height() != pixmap.height() {
+            *pixmap = source;
+            return Ok(());
+        }
+        region.clamp(pixmap.width(), pixmap.height());
+        pixmap.copy_region_from(&source, region);
+        Ok(())
+    }
+
+    fn create_empty_texture(&mut self, width: u32, height: u32) -> Result<BitmapHandle, Error> {
+        Ok(BitmapHandle(Arc::new(SoftwareBitmap(RwLock::new(
+            Pixmap::new(width, height),
+        )))))
+    }
+
+    fn create_context3d(
+        &mut self,
+        _profile: Context3DProfile,
+    ) -> Result<Box<dyn Context3D>, Error> {
+        self.unsupported_features.get_mut().insert("Context3D");
+        Err(Error::Unimplemented("createContext3D".into()))
+    }
+
+    fn context3d_present(&mut self, _context: &mut dyn Context3D) -> Result<(), Error> {
+        Err(Error::Unimplemented("Context3D.present".into()))
+    }
+
+    fn debug_info(&self) -> Cow<'static, str> {
+        Cow::Owned(format!(
+            "Renderer: Software\nViewport: {}x{}\nQuality: {}",
+            self.dimensions.width, self.dimensions.height, self.quality
+        ))
+    }
+
+    fn name(&self) -> &'static str {
+        "software"
+    }
+
+    fn set_quality(&mut self, quality: StageQuality) {
+        self.quality = quality;
+    }
+
+    fn compile_pixelbender_shader(
+        &mut self,
+        shader: PixelBenderShader,
+    ) -> Result<PixelBenderShaderHandle, Error> {
+        // There's nothing to compile, as shaders are interpreted as-is.
+        Ok(PixelBenderShaderHandle(Arc::new(shader)))
+    }
+
+    fn run_pixelbender_shader(
+        &mut self,
+        shader: PixelBenderShaderHandle,
+        arguments: &[PixelBenderShaderArgument],
+        target: &PixelBenderTarget,
+    ) -> Result<PixelBenderOutput, Error> {
+        interpreter::run_shader_job(self, shader.0.parsed_shader(), arguments, target)
+    }
+
+    fn resolve_sync_handle(
+        &mut self,
+        handle: Box<dyn SyncHandle>,
+        with_rgba: RgbaBufRead,
+    ) -> Result<(), Error> {
+        CpuSyncHandle::resolve(handle, with_rgba)
+    }
+}
+
+impl CpuShaderBitmaps for SoftwareRenderBackend {
+    fn bitmap_size(&self, handle: &BitmapHandle) -> (u32, u32) {
+        let pixmap = as_bitmap(handle).read();
+        (pixmap.width(), pixmap.height())
+    }
+
+    fn read_bitmap(&mut self, handle: &BitmapHandle) -> Result<PixelBenderImage, Error> {
+        let pixmap = as_bitmap(handle).read();
+        Ok(PixelBenderImage::from_rgba8(
+            pixmap.width(),
+            pixmap.height(),
+            pixmap.data(),
+        ))
+    }
+
+    fn write_bitmap(&mut self, handle: &BitmapHandle, rgba: Vec<u8>) -> Result<(), Error> {
+        let mut pixmap = as_bitmap(handle).write();
+        *pixmap = Pixmap::from_rgba(pixmap.width(), pixmap.height(), rgba);
+        Ok(())
+    }
+}
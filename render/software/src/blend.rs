@@ -0,0 +1,129 @@
+use crate::pixmap::Rgba;
+use swf::BlendMode;
+
+/// Blends a premultiplied source color onto a premultiplied destination color.
+///
+/// These are the same formulas used by the blend shaders of the wgpu backend.
+pub fn blend(src: Rgba, dst: Rgba, mode: BlendMode) -> Rgba {
+    let (sa, da) = (src[3], dst[3]);
+    match mode {
+        BlendMode::Normal | BlendMode::Layer => over(src, dst),
+        BlendMode::Add => rgb(over_alpha(sa, da), |i| (src[i] + dst[i]).min(1.0)),
+        BlendMode::Subtract => rgb(over_alpha(sa, da), |i| (dst[i] - src[i]).max(0.0)),
+        BlendMode::Screen => rgb(over_alpha(sa, da), |i| src[i] + dst[i] * (1.0 - src[i])),
+        BlendMode::Alpha => {
+            if sa <= 0.0 {
+                return dst;
+            }
+            [dst[0] * sa, dst[1] * sa, dst[2] * sa, da * sa]
+        }
+        BlendMode::Erase => {
+            if sa <= 0.0 {
+                return dst;
+            }
+            let inv = 1.0 - sa;
+            [dst[0] * inv, dst[1] * inv, dst[2] * inv, da * inv]
+        }
+        BlendMode::Multiply
+        | BlendMode::Lighten
+        | BlendMode::Darken
+        | BlendMode::Difference
+        | BlendMode::Invert
+        | BlendMode::Overlay
+        | BlendMode::HardLight => {
+            if sa <= 0.0 {
+                return dst;
+            }
+            if mode == BlendMode::Multiply && da <= 0.0 {
+                return src;
+            }
+            let s = [src[0] / sa, src[1] / sa, src[2] / sa];
+            let d = if da > 0.0 {
+                [dst[0] / da, dst[1] / da, dst[2] / da]
+            } else {
+                [0.0; 3]
+            };
+            rgb(over_alpha(sa, da), |i| {
+                src[i] * (1.0 - da) + dst[i] * (1.0 - sa) + sa * da * separable(s[i], d[i], mode)
+            })
+        }
+    }
+}
+
+#[inline]
+fn over(src: Rgba, dst: Rgba) -> Rgba {
+    let inv = 1.0 - src[3];
+    [
+        src[0] + dst[0] * inv,
+        src[1] + dst[1] * inv,
+        src[2] + dst[2] * inv,
+        src[3] + dst[3] * inv,
+    ]
+}
+
+#[inline]
+fn over_alpha(sa: f32, da: f32) -> f32 {
+    sa + da * (1.0 - sa)
+}
+
+#[inline]
+fn rgb(alpha: f32, channel: impl Fn(usize) -> f32) -> Rgba {
+    [channel(0), channel(1), channel(2), alpha]
+}
+
+/// The blend function of a separable blend mode, on straight color channels.
+fn separable(s: f32, d: f32, mode: BlendMode) -> f32 {
+    match mode {
+        BlendMode::Multiply => s * d,
+        BlendMode::Lighten => s.max(d),
+        BlendMode::Darken => s.min(d),
+        BlendMode::Difference => (d - s).abs(),
+        BlendMode::Invert => 1.0 - d,
+        BlendMode::Overlay => {
+            if d <= 0.5 {
+                2.0 * s * d
+            } else {
+                1.0 - 2.0 * (1.0 - d) * (1.0 - s)
+            }
+        }
+        BlendMode::HardLight => {
+            if s <= 0.5 {
+                2.0 * s * d
+            } else {
+                1.0 - 2.0 * (1.0 - d) * (1.0 - s)
+            }
+        }
+        _ => s,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normal_is_source_over() {
+        let out = blend(
+            [0.25, 0.0, 0.0, 0.5],
+            [0.0, 0.0, 1.0, 1.0],
+            BlendMode::Normal,
+        );
+        assert_eq!(out, [0.25, 0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn erase_removes_destination_alpha() {
+        let out = blend([0.0, 0.0, 0.0, 1.0], [1.0, 1.0, 1.0, 1.0], BlendMode::Erase);
+        assert_eq!(out, [0.0; 4]);
+    }
+
+    #[test]
+    fn multiply_of_opaque_colors() {
+        let out = blend(
+            [0.5, 1.0, 0.0, 1.0],
+            [0.5, 0.5, 1.0, 1.0],
+            BlendMode::Multiply,
+        );
+        assert_eq!(out, [0.25, 0.5, 0.0, 1.0]);
+    }
+}
@@ -0,0 +1,39 @@
+//! A render backend that rasterizes on the CPU, for environments without a GPU.
+//!
+//! Shapes are tessellated with the same tessellator used by the GPU backends,
+//! and the resulting triangles are then rasterized with supersampled anti-aliasing.
+
+use crate::pixmap::Pixmap;
+use ruffle_render::bitmap::{BitmapHandle, BitmapHandleImpl};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+mod backend;
+mod blend;
+mod paint;
+mod pixmap;
+mod raster;
+mod rasterizer;
+mod shape;
+
+pub use backend::SoftwareRenderBackend;
+
+/// A bitmap kept in main memory, as premultiplied RGBA.
+#[derive(Debug)]
+struct Bitmap(RwLock<Pixmap>);
+
+impl BitmapHandleImpl for Bitmap {}
+
+impl Bitmap {
+    fn read(&self) -> RwLockReadGuard<'_, Pixmap> {
+        self.0.read().expect("Bitmap lock should not be poisoned")
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Pixmap> {
+        self.0.write().expect("Bitmap lock should not be poisoned")
+    }
+}
+
+fn as_bitmap(handle: &BitmapHandle) -> &Bitmap {
+    <dyn BitmapHandleImpl>::downcast_ref(&*handle.0)
+        .expect("BitmapHandle should be a software bitmap")
+}
@@ -0,0 +1,236 @@
+use crate::pixmap::{Pixmap, Rgba};
+use crate::raster::Affine;
+use ruffle_render::shape_utils::GradientType;
+use ruffle_render::tessellator::Gradient;
+use swf::{Color, ColorTransform, GradientInterpolation, GradientSpread};
+
+/// The number of entries in the lookup table of a gradient.
+const GRADIENT_RESOLUTION: usize = 256;
+
+/// How the pixels covered by a draw are colored.
+pub enum Paint<'a> {
+    /// A single premultiplied color.
+    Solid(Rgba),
+    Gradient(GradientPaint),
+    Bitmap(BitmapPaint<'a>),
+}
+
+impl Paint<'_> {
+    /// Creates a solid paint, applying the color transform the same way the GPU backends do.
+    pub fn solid(color: Color, color_transform: &ColorTransform) -> Paint<'static> {
+        let color = transform_color(
+            [
+                f32::from(color.r) / 255.0,
+                f32::from(color.g) / 255.0,
+                f32::from(color.b) / 255.0,
+                f32::from(color.a) / 255.0,
+            ],
+            color_transform,
+        );
+        Paint::Solid(premultiply(color))
+    }
+
+    /// Returns the premultiplied color of the paint at the given position in device space.
+    #[inline]
+    pub fn shade(&self, x: f32, y: f32) -> Rgba {
+        match self {
+            Paint::Solid(color) => *color,
+            Paint::Gradient(gradient) => gradient.shade(x, y),
+            Paint::Bitmap(bitmap) => bitmap.shade(x, y),
+        }
+    }
+}
+
+pub struct GradientPaint {
+    /// Maps device space to the gradient space, where the gradient spans from 0 to 1.
+    device_to_uv: Affine,
+    gradient_type: GradientType,
+    repeat_mode: GradientSpread,
+    focal_point: f32,
+    colors: Box<[Rgba; GRADIENT_RESOLUTION]>,
+}
+
+impl GradientPaint {
+    pub fn new(
+        gradient: &Gradient,
+        device_to_uv: Affine,
+        color_transform: &ColorTransform,
+    ) -> Self {
+        let stops: Vec<(f32, [f32; 4])> = gradient
+            .records
+            .iter()
+            .map(|record| {
+                let mut color = [
+                    f32::from(record.color.r) / 255.0,
+                    f32::from(record.color.g) / 255.0,
+                    f32::from(record.color.b) / 255.0,
+                    f32::from(record.color.a) / 255.0,
+                ];
+                if gradient.interpolation == GradientInterpolation::LinearRgb {
+                    for channel in &mut color[..3] {
+                        *channel = srgb_to_linear(*channel);
+                    }
+                }
+                (
+                    f32::from(record.ratio) / 255.0,
+                    transform_color(color, color_transform),
+                )
+            })
+            .collect();
+
+        let mut colors = Box::new([[0.0; 4]; GRADIENT_RESOLUTION]);
+        for (i, out) in colors.iter_mut().enumerate() {
+            let t = i as f32 / (GRADIENT_RESOLUTION - 1) as f32;
+            let mut color = interpolate_stops(&stops, t);
+            if gradient.interpolation == GradientInterpolation::LinearRgb {
+                for channel in &mut color[..3] {
+                    *channel = linear_to_srgb(*channel);
+                }
+            }
+            *out = premultiply(color);
+        }
+
+        Self {
+            device_to_uv,
+            gradient_type: gradient.gradient_type,
+            repeat_mode: gradient.repeat_mode,
+            focal_point: gradient.focal_point.to_f32().clamp(-0.98, 0.98),
+            colors,
+        }
+    }
+
+    fn shade(&self, x: f32, y: f32) -> Rgba {
+        let (u, v) = self.device_to_uv.apply(x, y);
+        let t = match self.gradient_type {
+            GradientType::Linear => u,
+            GradientType::Radial => {
+                let (u, v) = (u * 2.0 - 1.0, v * 2.0 - 1.0);
+                (u * u + v * v).sqrt()
+            }
+            GradientType::Focal => {
+                let (u, v) = (u * 2.0 - 1.0, v * 2.0 - 1.0);
+                let (dx, dy) = (self.focal_point - u, -v);
+                let length = (dx * dx + dy * dy).sqrt();
+                if length == 0.0 {
+                    0.0
+                } else {
+                    let (dx, dy) = (dx / length, dy / length);
+                    let focal = self.focal_point;
+                    length / ((1.0 - focal * focal * dy * dy).sqrt() + focal * dx)
+                }
+            }
+        };
+        let t = match self.repeat_mode {
+            GradientSpread::Pad => t.clamp(0.0, 1.0),
+            GradientSpread::Repeat => t.rem_euclid(1.0),
+            GradientSpread::Reflect => {
+                let t = t.abs().rem_euclid(2.0);
+                if t > 1.0 {
+                    2.0 - t
+                } else {
+                    t
+                }
+            }
+        };
+        if !t.is_finite() {
+            return [0.0; 4];
+        }
+        self.colors[(t * (GRADIENT_RESOLUTION - 1) as f32).round() as usize]
+    }
+}
+
+pub struct BitmapPaint<'a> {
+    pub pixmap: &'a Pixmap,
+    /// Maps device space to texel space of the pixmap.
+    pub device_to_texel: Affine,
+    pub smoothed: bool,
+    pub repeating: bool,
+    pub color_transform: ColorTransform,
+}
+
+impl BitmapPaint<'_> {
+    fn shade(&self, x: f32, y: f32) -> Rgba {
+        let (u, v) = self.device_to_texel.apply(x, y);
+        let color = self.pixmap.sample(u, v, self.smoothed, self.repeating);
+        if self.color_transform == ColorTransform::IDENTITY {
+            return color;
+        }
+        premultiply(transform_color(unmultiply(color), &self.color_transform))
+    }
+}
+
+/// Applies a color transform to a straight alpha color.
+fn transform_color(color: [f32; 4], color_transform: &ColorTransform) -> [f32; 4] {
+    let mult = color_transform.mult_rgba_normalized();
+    let add = color_transform.add_rgba_normalized();
+    [
+        (color[0] * mult[0] + add[0]).clamp(0.0, 1.0),
+        (color[1] * mult[1] + add[1]).clamp(0.0, 1.0),
+        (color[2] * mult[2] + add[2]).clamp(0.0, 1.0),
+        (color[3] * mult[3] + add[3]).clamp(0.0, 1.0),
+    ]
+}
+
+fn interpolate_stops(stops: &[(f32, [f32; 4])], t: f32) -> [f32; 4] {
+    let Some(first) = stops.first() else {
+        return [0.0; 4];
+    };
+    if t <= first.0 {
+        return first.1;
+    }
+    for pair in stops.windows(2) {
+        let ((ratio1, color1), (ratio2, color2)) = (pair[0], pair[1]);
+        if t <= ratio2 {
+            if ratio2 <= ratio1 {
+                return color2;
+            }
+            let a = (t - ratio1) / (ratio2 - ratio1);
+            return [
+                color1[0] + (color2[0] - color1[0]) * a,
+                color1[1] + (color2[1] - color1[1]) * a,
+                color1[2] + (color2[2] - color1[2]) * a,
+                color1[3] + (color2[3] - color1[3]) * a,
+            ];
+        }
+    }
+    stops[stops.len() - 1].1
+}
+
+#[inline]
+fn premultiply(color: [f32; 4]) -> Rgba {
+    [
+        color[0] * color[3],
+        color[1] * color[3],
+        color[2] * color[3],
+        color[3],
+    ]
+}
+
+#[inline]
+fn unmultiply(color: Rgba) -> [f32; 4] {
+    if color[3] <= 0.0 {
+        return [0.0; 4];
+    }
+    [
+        (color[0] / color[3]).min(1.0),
+        (color[1] / color[3]).min(1.0),
+        (color[2] / color[3]).min(1.0),
+        color[3],
+    ]
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
@@ -0,0 +1,162 @@
+use ruffle_render::bitmap::{Bitmap, PixelRegion};
+
+/// A premultiplied RGBA color with components from 0 to 1.
+pub type Rgba = [f32; 4];
+
+/// An image stored as premultiplied RGBA, 8 bits per channel.
+#[derive(Clone, Debug)]
+pub struct Pixmap {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+impl Pixmap {
+    /// Creates a fully transparent pixmap.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    /// Creates a pixmap from premultiplied RGBA pixels.
+    pub fn from_rgba(width: u32, height: u32, data: Vec<u8>) -> Self {
+        debug_assert_eq!(data.len(), width as usize * height as usize * 4);
+        Self {
+            width,
+            height,
+            data,
+        }
+    }
+
+    pub fn from_bitmap(bitmap: Bitmap) -> Self {
+        let bitmap = bitmap.to_rgba();
+        Self {
+            width: bitmap.width(),
+            height: bitmap.height(),
+            data: bitmap.data().to_vec(),
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Fills the whole pixmap with a single color.
+    pub fn fill(&mut self, color: swf::Color) {
+        let a = f32::from(color.a) / 255.0;
+        let premultiplied = [
+            (f32::from(color.r) * a) as u8,
+            (f32::from(color.g) * a) as u8,
+            (f32::from(color.b) * a) as u8,
+            color.a,
+        ];
+        for pixel in self.data.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&premultiplied);
+        }
+    }
+
+    #[inline]
+    pub fn get(&self, x: u32, y: u32) -> Rgba {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        let pixel = &self.data[i..i + 4];
+        [
+            f32::from(pixel[0]) / 255.0,
+            f32::from(pixel[1]) / 255.0,
+            f32::from(pixel[2]) / 255.0,
+            f32::from(pixel[3]) / 255.0,
+        ]
+    }
+
+    #[inline]
+    pub fn set(&mut self, x: u32, y: u32, color: Rgba) {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        let alpha = color[3].clamp(0.0, 1.0);
+        // Keep the color a valid premultiplied value, even after rounding errors.
+        let pixel = [
+            color[0].clamp(0.0, alpha),
+            color[1].clamp(0.0, alpha),
+            color[2].clamp(0.0, alpha),
+            alpha,
+        ];
+        for (out, value) in self.data[i..i + 4].iter_mut().zip(pixel) {
+            *out = (value * 255.0 + 0.5) as u8;
+        }
+    }
+
+    /// Samples the pixmap at the given position in texel space.
+    ///
+    /// Positions outside of the pixmap are wrapped when `repeating`, and clamped to the edge otherwise.
+    pub fn sample(&self, u: f32, v: f32, smoothed: bool, repeating: bool) -> Rgba {
+        if self.width == 0 || self.height == 0 || !u.is_finite() || !v.is_finite() {
+            return [0.0; 4];
+        }
+        let wrap = |coord: i64, size: u32| -> u32 {
+            if repeating {
+                coord.rem_euclid(i64::from(size)) as u32
+            } else {
+                coord.clamp(0, i64::from(size) - 1) as u32
+            }
+        };
+        if !smoothed {
+            return self.get(
+                wrap(u.floor() as i64, self.width),
+                wrap(v.floor() as i64, self.height),
+            );
+        }
+
+        let (u, v) = (u - 0.5, v - 0.5);
+        let (x0, y0) = (u.floor(), v.floor());
+        let (fx, fy) = (u - x0, v - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+        let (x1, y1) = (wrap(x0 + 1, self.width), wrap(y0 + 1, self.height));
+        let (x0, y0) = (wrap(x0, self.width), wrap(y0, self.height));
+
+        let top = lerp(self.get(x0, y0), self.get(x1, y0), fx);
+        let bottom = lerp(self.get(x0, y1), self.get(x1, y1), fx);
+        lerp(top, bottom, fy)
+    }
+
+    /// Copies the given region out of the pixmap as tightly packed rows.
+    pub fn read_region(&self, region: PixelRegion) -> Vec<u8> {
+        let row_len = region.width() as usize * 4;
+        let mut out = Vec::with_capacity(row_len * region.height() as usize);
+        for y in region.y_min..region.y_max {
+            let start = (y as usize * self.width as usize + region.x_min as usize) * 4;
+            out.extend_from_slice(&self.data[start..start + row_len]);
+        }
+        out
+    }
+
+    /// Copies the given region from another pixmap of the same size into this one.
+    pub fn copy_region_from(&mut self, other: &Pixmap, region: PixelRegion) {
+        let row_len = region.width() as usize * 4;
+        for y in region.y_min..region.y_max {
+            let start = (y as usize * self.width as usize + region.x_min as usize) * 4;
+            self.data[start..start + row_len].copy_from_slice(&other.data[start..start + row_len]);
+        }
+    }
+}
+
+#[inline]
+pub fn lerp(a: Rgba, b: Rgba, t: f32) -> Rgba {
+    [
+        a[0] + (b[0] - a[0]) * t,
+        a[1] + (b[1] - a[1]) * t,
+        a[2] + (b[2] - a[2]) * t,
+        a[3] + (b[3] - a[3]) * t,
+    ]
+}
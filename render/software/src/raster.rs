@@ -0,0 +1,320 @@
+//! Triangle rasterization into supersampled coverage masks.
+
+use ruffle_render::matrix::Matrix;
+use ruffle_render::quality::StageQuality;
+
+/// A 2D affine transformation in pixel units.
+///
+/// Unlike [`Matrix`], the translation is kept as a float, which avoids
+/// rounding to twips when composing the many small matrices of gradients and bitmaps.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Affine {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub tx: f32,
+    pub ty: f32,
+}
+
+impl Affine {
+    pub const fn scale(x: f32, y: f32) -> Self {
+        Self {
+            a: x,
+            b: 0.0,
+            c: 0.0,
+            d: y,
+            tx: 0.0,
+            ty: 0.0,
+        }
+    }
+
+    /// Creates an affine transformation from a column-major GL `mat3`, as used by the tessellator.
+    pub fn from_gl(m: &[[f32; 3]; 3]) -> Self {
+        Self {
+            a: m[0][0],
+            b: m[0][1],
+            c: m[1][0],
+            d: m[1][1],
+            tx: m[2][0],
+            ty: m[2][1],
+        }
+    }
+
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.a * self.d - self.b * self.c;
+        if det.abs() <= f32::EPSILON * f32::EPSILON || !det.is_finite() {
+            return None;
+        }
+        let a = self.d / det;
+        let b = -self.b / det;
+        let c = -self.c / det;
+        let d = self.a / det;
+        Some(Self {
+            a,
+            b,
+            c,
+            d,
+            tx: -(a * self.tx + c * self.ty),
+            ty: -(b * self.tx + d * self.ty),
+        })
+    }
+
+    /// Returns the transformation that applies `other` first, and then `self`.
+    pub fn then_apply(&self, other: &Self) -> Self {
+        Self {
+            a: self.a * other.a + self.c * other.b,
+            b: self.b * other.a + self.d * other.b,
+            c: self.a * other.c + self.c * other.d,
+            d: self.b * other.c + self.d * other.d,
+            tx: self.a * other.tx + self.c * other.ty + self.tx,
+            ty: self.b * other.tx + self.d * other.ty + self.ty,
+        }
+    }
+
+    #[inline]
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            self.a * x + self.c * y + self.tx,
+            self.b * x + self.d * y + self.ty,
+        )
+    }
+}
+
+impl From<Matrix> for Affine {
+    fn from(matrix: Matrix) -> Self {
+        Self {
+            a: matrix.a,
+            b: matrix.b,
+            c: matrix.c,
+            d: matrix.d,
+            tx: matrix.tx.to_pixels() as f32,
+            ty: matrix.ty.to_pixels() as f32,
+        }
+    }
+}
+
+/// The sub-pixel sample positions used for anti-aliasing.
+#[derive(Clone, Debug)]
+pub struct SamplePattern {
+    offsets: Vec<(f32, f32)>,
+}
+
+impl SamplePattern {
+    pub fn new(quality: StageQuality) -> Self {
+        let (columns, rows) = match quality.sample_count() {
+            0 | 1 => (1, 1),
+            2 => (2, 1),
+            3 | 4 => (2, 2),
+            5..=8 => (4, 2),
+            _ => (4, 4),
+        };
+        let mut offsets = Vec::with_capacity(columns * rows);
+        for row in 0..rows {
+            for column in 0..columns {
+                offsets.push((
+                    (column as f32 + 0.5) / columns as f32,
+                    (row as f32 + 0.5) / rows as f32,
+                ));
+            }
+        }
+        Self { offsets }
+    }
+
+    pub fn sample_count(&self) -> usize {
+        self.offsets.len()
+    }
+}
+
+/// The area covered by a set of triangles, clipped to a rectangle of pixels.
+///
+/// Each pixel stores a bit per sample of the [`SamplePattern`], so overlapping
+/// triangles (such as the joins of strokes) are only counted once.
+pub struct Coverage {
+    pub x_min: u32,
+    pub y_min: u32,
+    pub width: u32,
+    pub height: u32,
+    samples: Vec<u16>,
+    sample_count: u32,
+}
+
+impl Coverage {
+    fn empty(sample_count: u32) -> Self {
+        Self {
+            x_min: 0,
+            y_min: 0,
+            width: 0,
+            height: 0,
+            samples: Vec::new(),
+            sample_count,
+        }
+    }
+
+    /// Iterates over every covered pixel, along with how much of it is covered.
+    pub fn pixels(&self) -> impl Iterator<Item = (u32, u32, f32)> + '_ {
+        self.samples
+            .iter()
+            .enumerate()
+            .filter(|(_, samples)| **samples != 0)
+            .map(|(index, samples)| {
+                let index = index as u32;
+                (
+                    self.x_min + index % self.width,
+                    self.y_min + index / self.width,
+                    samples.count_ones() as f32 / self.sample_count as f32,
+                )
+            })
+    }
+}
+
+/// Rasterizes triangles in device space into a [`Coverage`] clipped to `(0, 0, clip_width, clip_height)`.
+pub fn rasterize_triangles(
+    points: &[(f32, f32)],
+    indices: &[u32],
+    pattern: &SamplePattern,
+    clip_width: u32,
+    clip_height: u32,
+) -> Coverage {
+    let sample_count = pattern.sample_count() as u32;
+    let triangles: Vec<[(f32, f32); 3]> = indices
+        .chunks_exact(3)
+        .filter_map(|tri| {
+            Some([
+                *points.get(tri[0] as usize)?,
+                *points.get(tri[1] as usize)?,
+                *points.get(tri[2] as usize)?,
+            ])
+        })
+        .filter(|tri| tri.iter().all(|(x, y)| x.is_finite() && y.is_finite()))
+        .collect();
+    if triangles.is_empty() {
+        return Coverage::empty(sample_count);
+    }
+
+    let (mut x_min, mut y_min, mut x_max, mut y_max) = (f32::MAX, f32::MAX, f32::MIN, f32::MIN);
+    for (x, y) in triangles.iter().flatten() {
+        x_min = x_min.min(*x);
+        y_min = y_min.min(*y);
+        x_max = x_max.max(*x);
+        y_max = y_max.max(*y);
+    }
+    let x_min = x_min.floor().clamp(0.0, clip_width as f32) as u32;
+    let y_min = y_min.floor().clamp(0.0, clip_height as f32) as u32;
+    let x_max = x_max.ceil().clamp(0.0, clip_width as f32) as u32;
+    let y_max = y_max.ceil().clamp(0.0, clip_height as f32) as u32;
+    if x_min >= x_max || y_min >= y_max {
+        return Coverage::empty(sample_count);
+    }
+
+    let mut coverage = Coverage {
+        x_min,
+        y_min,
+        width: x_max - x_min,
+        height: y_max - y_min,
+        samples: vec![0; ((x_max - x_min) * (y_max - y_min)) as usize],
+        sample_count,
+    };
+    for triangle in &triangles {
+        rasterize_triangle(&mut coverage, triangle, pattern);
+    }
+    coverage
+}
+
+fn rasterize_triangle(
+    coverage: &mut Coverage,
+    triangle: &[(f32, f32); 3],
+    pattern: &SamplePattern,
+) {
+    let [mut p0, p1, mut p2] = *triangle;
+    let area = edge(p0, p1, p2);
+    if area == 0.0 || !area.is_finite() {
+        return;
+    }
+    // Normalize the winding so that the inside of every edge is positive.
+    if area < 0.0 {
+        std::mem::swap(&mut p0, &mut p2);
+    }
+    let edges = [(p1, p2), (p2, p0), (p0, p1)];
+    let top_left = edges.map(|(a, b)| is_top_left(a, b));
+
+    let x_start = p0.0.min(p1.0).min(p2.0).floor().max(coverage.x_min as f32) as u32;
+    let y_start = p0.1.min(p1.1).min(p2.1).floor().max(coverage.y_min as f32) as u32;
+    let x_end = (p0.0.max(p1.0).max(p2.0).ceil() as u32).min(coverage.x_min + coverage.width);
+    let y_end = (p0.1.max(p1.1).max(p2.1).ceil() as u32).min(coverage.y_min + coverage.height);
+
+    for y in y_start..y_end {
+        let row = ((y - coverage.y_min) * coverage.width) as usize;
+        for x in x_start..x_end {
+            let mut bits = 0u16;
+            for (i, (ox, oy)) in pattern.offsets.iter().enumerate() {
+                let p = (x as f32 + ox, y as f32 + oy);
+                let inside = edges.iter().zip(top_left).all(|((a, b), top_left)| {
+                    let w = edge(*a, *b, p);
+                    w > 0.0 || (w == 0.0 && top_left)
+                });
+                if inside {
+                    bits |= 1 << i;
+                }
+            }
+            coverage.samples[row + (x - coverage.x_min) as usize] |= bits;
+        }
+    }
+}
+
+#[inline]
+fn edge(a: (f32, f32), b: (f32, f32), p: (f32, f32)) -> f32 {
+    (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0)
+}
+
+/// Whether an edge is a "top" or "left" edge, which own the samples lying exactly on them.
+#[inline]
+fn is_top_left(a: (f32, f32), b: (f32, f32)) -> bool {
+    (a.1 == b.1 && b.0 < a.0) || b.1 > a.1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adjacent_triangles_cover_each_sample_once() {
+        let pattern = SamplePattern::new(StageQuality::High);
+        let points = [(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)];
+        let coverage = rasterize_triangles(&points, &[0, 1, 2, 0, 2, 3], &pattern, 8, 8);
+        assert_eq!((coverage.width, coverage.height), (4, 4));
+        assert!(coverage.pixels().all(|(_, _, amount)| amount == 1.0));
+        assert_eq!(coverage.pixels().count(), 16);
+    }
+
+    #[test]
+    fn partial_pixels_are_antialiased() {
+        let pattern = SamplePattern::new(StageQuality::High16x16);
+        let points = [(0.0, 0.0), (2.0, 0.0), (2.0, 1.5), (0.0, 1.5)];
+        let coverage = rasterize_triangles(&points, &[0, 1, 2, 0, 2, 3], &pattern, 8, 8);
+        let amount_at = |x, y| {
+            coverage
+                .pixels()
+                .find(|pixel| (pixel.0, pixel.1) == (x, y))
+                .map(|pixel| pixel.2)
+        };
+        assert_eq!(amount_at(0, 0), Some(1.0));
+        assert_eq!(amount_at(1, 1), Some(0.5));
+    }
+
+    #[test]
+    fn affine_inverse_round_trips() {
+        let affine = Affine {
+            a: 2.0,
+            b: 0.5,
+            c: -1.0,
+            d: 3.0,
+            tx: 10.0,
+            ty: -4.0,
+        };
+        let inverse = affine.inverse().unwrap();
+        let (x, y) = affine.apply(3.0, 7.0);
+        let (x, y) = inverse.apply(x, y);
+        assert!((x - 3.0).abs() < 1e-4 && (y - 7.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,323 @@
+use crate::as_bitmap;
+use crate::blend::blend;
+use crate::paint::{BitmapPaint, GradientPaint, Paint};
+use crate::pixmap::{lerp, Pixmap};
+use crate::raster::{rasterize_triangles, Affine, Coverage, SamplePattern};
+use crate::shape::{DrawType, Shape};
+use ruffle_render::backend::{ShapeHandle, ShapeHandleImpl};
+use ruffle_render::bitmap::{BitmapHandle, PixelSnapping};
+use ruffle_render::commands::{CommandHandler, CommandList, RenderBlendMode};
+use ruffle_render::lines::{emulate_line, emulate_line_rect};
+use ruffle_render::matrix::Matrix;
+use ruffle_render::quality::StageQuality;
+use ruffle_render::transform::Transform;
+use std::collections::BTreeSet;
+use swf::{BlendMode, Color, ColorTransform};
+
+const UNIT_QUAD: [(f32, f32); 4] = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+const UNIT_QUAD_INDICES: [u32; 6] = [0, 1, 2, 0, 2, 3];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum MaskState {
+    NoMask,
+    DrawMask,
+    DrawMaskedContent,
+    ClearMask,
+}
+
+/// A surface that commands are drawn onto.
+///
+/// Each `blend` command draws its children into a new layer, which is then composited onto the layer below.
+struct Layer {
+    pixmap: Pixmap,
+    /// The coverage of every active mask, each already clipped by the masks before it.
+    masks: Vec<Vec<f32>>,
+    /// The mask currently being drawn.
+    pending_mask: Option<Vec<f32>>,
+    mask_state: MaskState,
+}
+
+impl Layer {
+    fn new(pixmap: Pixmap) -> Self {
+        Self {
+            pixmap,
+            masks: Vec::new(),
+            pending_mask: None,
+            mask_state: MaskState::NoMask,
+        }
+    }
+}
+
+/// Executes a [`CommandList`] by rasterizing it on the CPU.
+pub struct Rasterizer<'a> {
+    width: u32,
+    height: u32,
+    pattern: SamplePattern,
+    layers: Vec<Layer>,
+    /// Features that were drawn incorrectly, as the software renderer doesn't support them.
+    unsupported_features: &'a mut BTreeSet<&'static str>,
+}
+
+impl<'a> Rasterizer<'a> {
+    pub fn new(
+        target: Pixmap,
+        quality: StageQuality,
+        unsupported_features: &'a mut BTreeSet<&'static str>,
+    ) -> Self {
+        Self {
+            width: target.width(),
+            height: target.height(),
+            pattern: SamplePattern::new(quality),
+            layers: vec![Layer::new(target)],
+            unsupported_features,
+        }
+    }
+
+    /// Draws the given commands onto the target.
+    pub fn draw(mut self, commands: CommandList) -> Pixmap {
+        commands.execute(&mut self);
+        self.layers
+            .pop()
+            .expect("Rasterizer should always have a layer")
+            .pixmap
+    }
+
+    fn layer(&mut self) -> &mut Layer {
+        self.layers
+            .last_mut()
+            .expect("Rasterizer should always have a layer")
+    }
+
+    fn rasterize(&self, points: &[(f32, f32)], indices: &[u32]) -> Coverage {
+        rasterize_triangles(points, indices, &self.pattern, self.width, self.height)
+    }
+
+    fn rasterize_quad(&self, matrix: &Affine) -> Coverage {
+        let points = UNIT_QUAD.map(|(x, y)| matrix.apply(x, y));
+        self.rasterize(&points, &UNIT_QUAD_INDICES)
+    }
+
+    /// Fills the covered pixels with a paint, or adds them to the pending mask when drawing a mask.
+    fn fill(&mut self, coverage: &Coverage, paint: &Paint) {
+        let width = self.width;
+        let layer = self.layer();
+        let clip = layer.masks.last();
+        match layer.mask_state {
+            MaskState::ClearMask => {}
+            MaskState::DrawMask => {
+                // Like a stencil buffer, masks only care about which pixels are covered, not their color.
+                if let Some(pending_mask) = &mut layer.pending_mask {
+                    for (x, y, amount) in coverage.pixels() {
+                        let i = (y * width + x) as usize;
+                        let amount = amount * clip.map_or(1.0, |clip| clip[i]);
+                        pending_mask[i] = (pending_mask[i] + amount).min(1.0);
+                    }
+                }
+            }
+            MaskState::NoMask | MaskState::DrawMaskedContent => {
+                for (x, y, amount) in coverage.pixels() {
+                    let i = (y * width + x) as usize;
+                    let amount = amount * clip.map_or(1.0, |clip| clip[i]);
+                    if amount <= 0.0 {
+                        continue;
+                    }
+                    let color = paint.shade(x as f32 + 0.5, y as f32 + 0.5);
+                    if color[3] <= 0.0 {
+                        continue;
+                    }
+                    let color = color.map(|c| c * amount);
+                    let dst = layer.pixmap.get(x, y);
+                    layer.pixmap.set(x, y, blend(color, dst, BlendMode::Normal));
+                }
+            }
+        }
+    }
+}
+
+impl CommandHandler for Rasterizer<'_> {
+    fn render_bitmap(
+        &mut self,
+        bitmap: BitmapHandle,
+        transform: Transform,
+        smoothing: bool,
+        pixel_snapping: PixelSnapping,
+    ) {
+        let mut matrix = transform.matrix;
+        pixel_snapping.apply(&mut matrix);
+
+        let pixmap = as_bitmap(&bitmap).read();
+        let size = Affine::scale(pixmap.width() as f32, pixmap.height() as f32);
+        let world = Affine::from(matrix).then_apply(&size);
+        let Some(device_to_uv) = world.inverse() else {
+            return;
+        };
+        let coverage = self.rasterize_quad(&world);
+        self.fill(
+            &coverage,
+            &Paint::Bitmap(BitmapPaint {
+                pixmap: &pixmap,
+                device_to_texel: size.then_apply(&device_to_uv),
+                smoothed: smoothing,
+                repeating: false,
+                color_transform: transform.color_transform,
+            }),
+        );
+    }
+
+    fn render_stage3d(&mut self, bitmap: BitmapHandle, transform: Transform) {
+        self.render_bitmap(bitmap, transform, false, PixelSnapping::Never);
+    }
+
+    fn render_shape(&mut self, shape: ShapeHandle, transform: Transform) {
+        let shape = <dyn ShapeHandleImpl>::downcast_ref::<Shape>(&*shape.0)
+            .expect("ShapeHandle should be a software shape");
+        let world = Affine::from(transform.matrix);
+        let Some(device_to_local) = world.inverse() else {
+            return;
+        };
+        let drawing_mask = self.layer().mask_state == MaskState::DrawMask;
+
+        for draw in &shape.draws {
+            let points: Vec<_> = draw
+                .points
+                .iter()
+                .map(|(x, y)| world.apply(*x, *y))
+                .collect();
+            if drawing_mask {
+                let indices = &draw.indices[..draw.mask_index_count.min(draw.indices.len())];
+                let coverage = self.rasterize(&points, indices);
+                self.fill(&coverage, &Paint::Solid([1.0; 4]));
+                continue;
+            }
+
+            match &draw.draw_type {
+                DrawType::Color(runs) => {
+                    for run in runs {
+                        let coverage = self.rasterize(&points, &draw.indices[run.start..run.end]);
+                        self.fill(
+                            &coverage,
+                            &Paint::solid(run.color, &transform.color_transform),
+                        );
+                    }
+                }
+                DrawType::Gradient { matrix, gradient } => {
+                    let coverage = self.rasterize(&points, &draw.indices);
+                    let paint = GradientPaint::new(
+                        gradient,
+                        matrix.then_apply(&device_to_local),
+                        &transform.color_transform,
+                    );
+                    self.fill(&coverage, &Paint::Gradient(paint));
+                }
+                DrawType::Bitmap {
+                    bitmap,
+                    matrix,
+                    is_smoothed,
+                    is_repeating,
+                } => {
+                    let coverage = self.rasterize(&points, &draw.indices);
+                    let pixmap = as_bitmap(bitmap).read();
+                    let size = Affine::scale(pixmap.width() as f32, pixmap.height() as f32);
+                    self.fill(
+                        &coverage,
+                        &Paint::Bitmap(BitmapPaint {
+                            pixmap: &pixmap,
+                            device_to_texel: size.then_apply(matrix).then_apply(&device_to_local),
+                            smoothed: *is_smoothed,
+                            repeating: *is_repeating,
+                            color_transform: transform.color_transform,
+                        }),
+                    );
+                }
+            }
+        }
+    }
+
+    fn draw_rect(&mut self, color: Color, matrix: Matrix) {
+        let coverage = self.rasterize_quad(&Affine::from(matrix));
+        self.fill(&coverage, &Paint::solid(color, &ColorTransform::IDENTITY));
+    }
+
+    fn draw_line(&mut self, color: Color, matrix: Matrix) {
+        emulate_line(self, color, matrix);
+    }
+
+    fn draw_line_rect(&mut self, color: Color, matrix: Matrix) {
+        emulate_line_rect(self, color, matrix);
+    }
+
+    fn push_mask(&mut self) {
+        let size = (self.width * self.height) as usize;
+        let layer = self.layer();
+        layer.pending_mask = Some(vec![0.0; size]);
+        layer.mask_state = MaskState::DrawMask;
+    }
+
+    fn activate_mask(&mut self) {
+        let layer = self.layer();
+        if let Some(mask) = layer.pending_mask.take() {
+            layer.masks.push(mask);
+        }
+        layer.mask_state = MaskState::DrawMaskedContent;
+    }
+
+    fn deactivate_mask(&mut self) {
+        self.layer().mask_state = MaskState::ClearMask;
+    }
+
+    fn pop_mask(&mut self) {
+        let layer = self.layer();
+        layer.masks.pop();
+        layer.mask_state = if layer.masks.is_empty() {
+            MaskState::NoMask
+        } else {
+            MaskState::DrawMaskedContent
+        };
+    }
+
+    fn blend(&mut self, commands: CommandList, blend_mode: RenderBlendMode) {
+        let blend_mode = match blend_mode {
+            RenderBlendMode::Builtin(blend_mode) => blend_mode,
+            RenderBlendMode::Shader(_) => {
+                tracing::warn!("Shader blend modes are not supported by the software renderer");
+                self.unsupported_features.insert("shader blend modes");
+                BlendMode::Normal
+            }
+        };
+        if self.layer().mask_state == MaskState::ClearMask {
+            return;
+        }
+
+        self.layers
+            .push(Layer::new(Pixmap::new(self.width, self.height)));
+        commands.execute(self);
+        let source = self
+            .layers
+            .pop()
+            .expect("Blend layer should still exist")
+            .pixmap;
+
+        let width = self.width;
+        let layer = self.layer();
+        let clip = layer.masks.last();
+        for y in 0..source.height() {
+            for x in 0..source.width() {
+                let src = source.get(x, y);
+                if src[3] <= 0.0 {
+                    continue;
+                }
+                let i = (y * width + x) as usize;
+                let amount = clip.map_or(1.0, |clip| clip[i]);
+                if layer.mask_state == MaskState::DrawMask {
+                    if let Some(pending_mask) = &mut layer.pending_mask {
+                        pending_mask[i] = (pending_mask[i] + src[3] * amount).min(1.0);
+                    }
+                } else if amount > 0.0 {
+                    let dst = layer.pixmap.get(x, y);
+                    let blended = blend(src, dst, blend_mode);
+                    layer.pixmap.set(x, y, lerp(dst, blended, amount));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use crate::raster::Affine;
+use ruffle_render::backend::{RenderBackend, ShapeHandleImpl};
+use ruffle_render::bitmap::{BitmapHandle, BitmapSource};
+use ruffle_render::tessellator::{DrawType as TessDrawType, Gradient, Mesh};
+
+/// A tessellated shape, kept on the CPU.
+#[derive(Debug)]
+pub struct Shape {
+    pub draws: Vec<Draw>,
+}
+
+impl ShapeHandleImpl for Shape {}
+
+#[derive(Debug)]
+pub struct Draw {
+    pub draw_type: DrawType,
+    /// The vertices of the shape, in pixels.
+    pub points: Vec<(f32, f32)>,
+    pub indices: Vec<u32>,
+    /// The number of indices to use when drawing this as a mask (which excludes strokes).
+    pub mask_index_count: usize,
+}
+
+#[derive(Debug)]
+pub enum DrawType {
+    /// Solid colors, with consecutive triangles of the same color grouped together.
+    Color(Vec<ColorRun>),
+    Gradient {
+        /// Maps shape space to gradient space.
+        matrix: Affine,
+        gradient: Gradient,
+    },
+    Bitmap {
+        bitmap: BitmapHandle,
+        /// Maps shape space to the unit square of the bitmap.
+        matrix: Affine,
+        is_smoothed: bool,
+        is_repeating: bool,
+    },
+}
+
+/// A range of indices whose triangles all share the same color.
+#[derive(Debug)]
+pub struct ColorRun {
+    pub color: swf::Color,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Shape {
+    pub fn new(
+        mesh: Mesh,
+        bitmap_source: &dyn BitmapSource,
+        backend: &mut dyn RenderBackend,
+    ) -> Self {
+        let mut draws = Vec::with_capacity(mesh.draws.len());
+        for draw in mesh.draws {
+            let draw_type = match draw.draw_type {
+                TessDrawType::Color => {
+                    let mut runs: Vec<ColorRun> = Vec::new();
+                    for (i, triangle) in draw.indices.chunks_exact(3).enumerate() {
+                        let Some(vertex) = draw.vertices.get(triangle[0] as usize) else {
+                            continue;
+                        };
+                        match runs.last_mut() {
+                            Some(run) if run.color == vertex.color && run.end == i * 3 => {
+                                run.end += 3;
+                            }
+                            _ => runs.push(ColorRun {
+                                color: vertex.color,
+                                start: i * 3,
+                                end: i * 3 + 3,
+                            }),
+                        }
+                    }
+                    DrawType::Color(runs)
+                }
+                TessDrawType::Gradient { matrix, gradient } => DrawType::Gradient {
+                    matrix: Affine::from_gl(&matrix),
+                    gradient: mesh.gradients[gradient].clone(),
+                },
+                TessDrawType::Bitmap(bitmap) => {
+                    let Some(handle) = bitmap_source.bitmap_handle(bitmap.bitmap_id, backend)
+                    else {
+                        continue;
+                    };
+                    DrawType::Bitmap {
+                        bitmap: handle,
+                        matrix: Affine::from_gl(&bitmap.matrix),
+                        is_smoothed: bitmap.is_smoothed,
+                        is_repeating: bitmap.is_repeating,
+                    }
+                }
+            };
+            draws.push(Draw {
+                draw_type,
+                points: draw.vertices.iter().map(|v| (v.x, v.y)).collect(),
+                indices: draw.indices,
+                mask_index_count: draw.mask_index_count as usize,
+            });
+        }
+        Self { draws }
+    }
+}
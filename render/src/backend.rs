@@ -148,6 +148,14 @@ pub trait RawTexture: Downcast + Debug {
 }
 impl_downcast!(RawTexture);
 
+/// Backends without textures of their own, such as those that run shaders on the CPU,
+/// use their bitmaps as textures.
+impl RawTexture for BitmapHandle {
+    fn equals(&self, other: &dyn RawTexture) -> bool {
+        other.downcast_ref::<BitmapHandle>() == Some(self)
+    }
+}
+
 #[cfg(feature = "wgpu")]
 impl RawTexture for wgpu::Texture {
     fn equals(&self, other: &dyn RawTexture) -> bool {
@@ -1,15 +1,16 @@
 use std::borrow::Cow;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use crate::backend::{
     BitmapCacheEntry, RenderBackend, ShapeHandle, ShapeHandleImpl, ViewportDimensions,
 };
 use crate::bitmap::{
-    Bitmap, BitmapHandle, BitmapHandleImpl, BitmapSize, BitmapSource, PixelRegion, RgbaBufRead,
-    SyncHandle,
+    Bitmap, BitmapFormat, BitmapHandle, BitmapHandleImpl, BitmapSize, BitmapSource, CpuSyncHandle,
+    PixelRegion, RgbaBufRead, SyncHandle,
 };
 use crate::commands::CommandList;
 use crate::error::Error;
+use crate::pixel_bender::interpreter::{self, CpuShaderBitmaps, PixelBenderImage};
 use crate::pixel_bender::{PixelBenderShader, PixelBenderShaderArgument, PixelBenderShaderHandle};
 use crate::quality::StageQuality;
 use crate::shape_utils::DistilledShape;
@@ -38,10 +39,21 @@ impl NullRenderer {
     }
 }
 
-#[derive(Clone, Debug)]
-struct NullBitmapHandle;
+/// The pixels of a bitmap, which are only kept so that shaders can be run on them.
+#[derive(Debug)]
+struct NullBitmapHandle(Mutex<Bitmap>);
 impl BitmapHandleImpl for NullBitmapHandle {}
 
+fn null_bitmap_handle(bitmap: Bitmap) -> BitmapHandle {
+    BitmapHandle(Arc::new(NullBitmapHandle(Mutex::new(bitmap.to_rgba()))))
+}
+
+fn as_null_bitmap(handle: &BitmapHandle) -> &Mutex<Bitmap> {
+    let handle = <dyn BitmapHandleImpl>::downcast_ref::<NullBitmapHandle>(&*handle.0)
+        .expect("Bitmap handle must be a NullBitmapHandle");
+    &handle.0
+}
+
 #[derive(Clone, Debug)]
 struct NullShapeHandle;
 impl ShapeHandleImpl for NullShapeHandle {}
@@ -78,16 +90,17 @@ impl RenderBackend for NullRenderer {
         _cache_entries: Vec<BitmapCacheEntry>,
     ) {
     }
-    fn register_bitmap(&mut self, _bitmap: Bitmap) -> Result<BitmapHandle, Error> {
-        Ok(BitmapHandle(Arc::new(NullBitmapHandle)))
+    fn register_bitmap(&mut self, bitmap: Bitmap) -> Result<BitmapHandle, Error> {
+        Ok(null_bitmap_handle(bitmap))
     }
 
     fn update_texture(
         &mut self,
-        _handle: &BitmapHandle,
-        _bitmap: Bitmap,
+        handle: &BitmapHandle,
+        bitmap: Bitmap,
         _region: PixelRegion,
     ) -> Result<(), Error> {
+        *as_null_bitmap(handle).lock().expect("Bitmap lock poisoned") = bitmap.to_rgba();
         Ok(())
     }
 
@@ -114,31 +127,58 @@ impl RenderBackend for NullRenderer {
 
     fn run_pixelbender_shader(
         &mut self,
-        _shader: PixelBenderShaderHandle,
-        _arguments: &[PixelBenderShaderArgument],
-        _target: &PixelBenderTarget,
+        shader: PixelBenderShaderHandle,
+        arguments: &[PixelBenderShaderArgument],
+        target: &PixelBenderTarget,
     ) -> Result<PixelBenderOutput, Error> {
-        Err(Error::Unimplemented("Pixel bender shader".into()))
+        interpreter::run_shader_job(self, shader.0.parsed_shader(), arguments, target)
     }
 
     fn resolve_sync_handle(
         &mut self,
-        _handle: Box<dyn SyncHandle>,
-        _with_rgba: RgbaBufRead,
+        handle: Box<dyn SyncHandle>,
+        with_rgba: RgbaBufRead,
     ) -> Result<(), Error> {
-        Err(Error::Unimplemented("Sync handle resolution".into()))
+        CpuSyncHandle::resolve(handle, with_rgba)
     }
 
     fn compile_pixelbender_shader(
         &mut self,
-        _shader: PixelBenderShader,
+        shader: PixelBenderShader,
     ) -> Result<PixelBenderShaderHandle, Error> {
-        Err(Error::Unimplemented(
-            "Pixel bender shader compilation".into(),
+        // There's nothing to compile, as shaders are interpreted as-is.
+        Ok(PixelBenderShaderHandle(Arc::new(shader)))
+    }
+
+    fn create_empty_texture(&mut self, width: u32, height: u32) -> Result<BitmapHandle, Error> {
+        let data = vec![0; width as usize * height as usize * 4];
+        Ok(null_bitmap_handle(Bitmap::new(
+            width,
+            height,
+            BitmapFormat::Rgba,
+            data,
+        )))
+    }
+}
+
+impl CpuShaderBitmaps for NullRenderer {
+    fn bitmap_size(&self, handle: &BitmapHandle) -> (u32, u32) {
+        let bitmap = as_null_bitmap(handle).lock().expect("Bitmap lock poisoned");
+        (bitmap.width(), bitmap.height())
+    }
+
+    fn read_bitmap(&mut self, handle: &BitmapHandle) -> Result<PixelBenderImage, Error> {
+        let bitmap = as_null_bitmap(handle).lock().expect("Bitmap lock poisoned");
+        Ok(PixelBenderImage::from_rgba8(
+            bitmap.width(),
+            bitmap.height(),
+            bitmap.data(),
         ))
     }
 
-    fn create_empty_texture(&mut self, _width: u32, _height: u32) -> Result<BitmapHandle, Error> {
-        Ok(BitmapHandle(Arc::new(NullBitmapHandle)))
+    fn write_bitmap(&mut self, handle: &BitmapHandle, rgba: Vec<u8>) -> Result<(), Error> {
+        let mut bitmap = as_null_bitmap(handle).lock().expect("Bitmap lock poisoned");
+        *bitmap = Bitmap::new(bitmap.width(), bitmap.height(), BitmapFormat::Rgba, rgba);
+        Ok(())
     }
 }
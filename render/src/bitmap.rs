@@ -6,6 +6,7 @@ use downcast_rs::{impl_downcast, Downcast};
 use swf::{Rectangle, Twips};
 
 use crate::backend::RenderBackend;
+use crate::error::Error;
 use crate::matrix::Matrix;
 
 #[derive(Clone, Debug)]
@@ -54,6 +55,30 @@ impl Clone for Box<dyn SyncHandle> {
     }
 }
 
+/// A [`SyncHandle`] for pixels that are already on the CPU,
+/// such as those rendered by a backend without GPU access.
+#[derive(Debug)]
+pub struct CpuSyncHandle {
+    pub data: Vec<u8>,
+
+    /// The length of each row of `data`, in bytes.
+    pub row_length: u32,
+}
+
+impl SyncHandle for CpuSyncHandle {}
+
+impl CpuSyncHandle {
+    /// Passes the pixels of a `CpuSyncHandle` to `with_rgba`,
+    /// as [`RenderBackend::resolve_sync_handle`] does.
+    pub fn resolve(handle: Box<dyn SyncHandle>, with_rgba: RgbaBufRead) -> Result<(), Error> {
+        let handle = handle
+            .downcast::<CpuSyncHandle>()
+            .map_err(|_| Error::Unimplemented("Foreign sync handle".into()))?;
+        with_rgba(&handle.data, handle.row_length);
+        Ok(())
+    }
+}
+
 /// How bitmaps should snap to the pixel grid when rendering
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum PixelSnapping {
@@ -40,6 +40,9 @@ pub enum Error {
     #[error("Unknown handle {0:?}")]
     UnknownHandle(BitmapHandle),
 
+    #[error("Invalid Pixel Bender shader: {0}")]
+    InvalidPixelBenderShader(Cow<'static, str>),
+
     #[error("Not yet implemented: {0}")]
     Unimplemented(Cow<'static, str>),
 }
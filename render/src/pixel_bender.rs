@@ -1,6 +1,7 @@
 //! Pixel bender bytecode parsing code.
 //! This is heavily based on https://github.com/jamesward/pbjas and https://github.com/HaxeFoundation/format/tree/master/format/pbj
 
+pub mod interpreter;
 #[cfg(test)]
 mod tests;
 
@@ -33,6 +34,25 @@ pub trait PixelBenderShaderImpl: Downcast + Debug {
 }
 impl_downcast!(PixelBenderShaderImpl);
 
+/// A parsed shader can be used directly by backends that don't need to compile it,
+/// such as those running it with the [`interpreter`].
+impl PixelBenderShaderImpl for PixelBenderShader {
+    fn parsed_shader(&self) -> &PixelBenderShader {
+        self
+    }
+}
+
+/// How a shader is being run, which affects samples taken outside of its input images.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ShaderMode {
+    /// Run by a `ShaderJob`. Out-of-range samples seem to be clamped to the edge of the image
+    /// (despite what the docs describe).
+    ShaderJob,
+    /// Run by a `ShaderFilter`. Out-of-range samples are transparent black. This is easiest to
+    /// observe with `BitmapData.applyFilter` when the destination is larger than the source.
+    Filter,
+}
+
 #[repr(u8)]
 #[derive(Debug, Clone, PartialEq)]
 pub enum PixelBenderType {
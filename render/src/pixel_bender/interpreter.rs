@@ -0,0 +1,819 @@
+//! A CPU interpreter for Pixel Bender shaders.
+//!
+//! This runs a parsed [`PixelBenderShader`] one pixel at a time, following the same semantics
+//! as the `naga-pixelbender` translation used by the wgpu backend. Backends without shader
+//! support can use it to run shaders, and it doubles as a reference implementation
+//! to compare the GPU output against.
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::backend::{PixelBenderOutput, PixelBenderTarget};
+use crate::bitmap::{BitmapHandle, CpuSyncHandle};
+use crate::error::Error;
+use crate::pixel_bender::{
+    ImageInputTexture, Opcode, Operation, PixelBenderParam, PixelBenderParamQualifier,
+    PixelBenderReg, PixelBenderRegChannel, PixelBenderRegKind, PixelBenderShader,
+    PixelBenderShaderArgument, PixelBenderType, ShaderMode, OUT_COORD_NAME,
+};
+
+/// An image read or written by a shader, with floating point channels.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PixelBenderImage {
+    pub width: u32,
+    pub height: u32,
+    /// The number of channels of each pixel, from 1 to 4.
+    pub channels: u32,
+    /// The channels of every pixel, row by row.
+    pub data: Vec<f32>,
+}
+
+impl PixelBenderImage {
+    /// Creates an image where every channel is zero.
+    pub fn new(width: u32, height: u32, channels: u32) -> Self {
+        Self {
+            width,
+            height,
+            channels,
+            data: vec![0.0; width as usize * height as usize * channels as usize],
+        }
+    }
+
+    /// Creates an image from 8-bit RGBA pixels, such as the contents of a bitmap.
+    pub fn from_rgba8(width: u32, height: u32, rgba: &[u8]) -> Self {
+        Self {
+            width,
+            height,
+            channels: 4,
+            data: rgba.iter().map(|c| f32::from(*c) / 255.0).collect(),
+        }
+    }
+
+    /// Creates an image from little-endian `f32` channels, as used by `ShaderJob` byte inputs.
+    pub fn from_f32_bytes(width: u32, height: u32, channels: u32, bytes: &[u8]) -> Self {
+        Self {
+            width,
+            height,
+            channels,
+            data: bytes
+                .chunks_exact(4)
+                .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+                .collect(),
+        }
+    }
+
+    /// Converts the image to 8-bit RGBA pixels.
+    ///
+    /// Channels are clamped to 0-1, and missing channels are filled in as if they were sampled.
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        let mut rgba = Vec::with_capacity(self.width as usize * self.height as usize * 4);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let mut pixel = self.pixel(x, y);
+                if self.channels == 3 {
+                    pixel[3] = 1.0;
+                }
+                rgba.extend(pixel.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8));
+            }
+        }
+        rgba
+    }
+
+    /// Converts the image to little-endian `f32` channels, as expected by `ShaderJob` byte targets.
+    pub fn to_f32_bytes(&self) -> Vec<u8> {
+        self.data.iter().flat_map(|c| c.to_le_bytes()).collect()
+    }
+
+    /// Returns the channels of a pixel, padded out to four.
+    ///
+    /// The padding matches the textures that the wgpu backend uploads:
+    /// one and two channel images are opaque, while three channel images have a zero alpha.
+    fn pixel(&self, x: u32, y: u32) -> [f32; 4] {
+        let channels = self.channels as usize;
+        let i = (y as usize * self.width as usize + x as usize) * channels;
+        let pixel = &self.data[i..i + channels];
+        let mut out = [0.0, 0.0, 0.0, if channels == 3 { 0.0 } else { 1.0 }];
+        out[..channels].copy_from_slice(pixel);
+        out
+    }
+
+    fn set_pixel(&mut self, x: u32, y: u32, value: [f32; 4]) {
+        let channels = self.channels as usize;
+        let i = (y as usize * self.width as usize + x as usize) * channels;
+        self.data[i..i + channels].copy_from_slice(&value[..channels]);
+    }
+
+    /// Samples the texel containing the given position (in pixels), clamping to the edges.
+    fn sample_nearest(&self, x: f32, y: f32) -> [f32; 4] {
+        if self.width == 0 || self.height == 0 {
+            return [0.0; 4];
+        }
+        let x = (x.floor() as i64).clamp(0, i64::from(self.width) - 1);
+        let y = (y.floor() as i64).clamp(0, i64::from(self.height) - 1);
+        self.pixel(x as u32, y as u32)
+    }
+
+    /// Bilinearly samples the given position (in pixels), clamping to the edges.
+    fn sample_linear(&self, x: f32, y: f32) -> [f32; 4] {
+        if self.width == 0 || self.height == 0 {
+            return [0.0; 4];
+        }
+        let (x, y) = (x - 0.5, y - 0.5);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let clamp_x = |x: f32| (x as i64).clamp(0, i64::from(self.width) - 1) as u32;
+        let clamp_y = |y: f32| (y as i64).clamp(0, i64::from(self.height) - 1) as u32;
+        let (left, right) = (clamp_x(x0), clamp_x(x0 + 1.0));
+        let (top, bottom) = (clamp_y(y0), clamp_y(y0 + 1.0));
+        let lerp =
+            |a: [f32; 4], b: [f32; 4], t: f32| [0, 1, 2, 3].map(|i| a[i] + (b[i] - a[i]) * t);
+        lerp(
+            lerp(self.pixel(left, top), self.pixel(right, top), fx),
+            lerp(self.pixel(left, bottom), self.pixel(right, bottom), fx),
+            fy,
+        )
+    }
+}
+
+/// Runs a shader over every pixel of a `width` by `height` output.
+///
+/// Image inputs given as bytes are read directly; any other image input is passed to
+/// `load_image`, which should return the same pixels a GPU backend would sample from it.
+/// Missing image inputs are treated as transparent black.
+pub fn run_shader(
+    shader: &PixelBenderShader,
+    mode: ShaderMode,
+    arguments: &[PixelBenderShaderArgument],
+    width: u32,
+    height: u32,
+    load_image: impl FnMut(&ImageInputTexture) -> Result<PixelBenderImage, Error>,
+) -> Result<PixelBenderImage, Error> {
+    let images = load_images(arguments, load_image)?;
+    let interpreter = Interpreter::new(shader, mode, arguments, &images)?;
+    let mut output = PixelBenderImage::new(width, height, interpreter.output.channels.len() as u32);
+    interpreter.run_rows(&mut output, 0..height)?;
+    Ok(output)
+}
+
+/// Collects the images bound to each texture index.
+fn load_images(
+    arguments: &[PixelBenderShaderArgument],
+    mut load_image: impl FnMut(&ImageInputTexture) -> Result<PixelBenderImage, Error>,
+) -> Result<Vec<Option<PixelBenderImage>>, Error> {
+    let mut images = Vec::new();
+    for argument in arguments {
+        if let PixelBenderShaderArgument::ImageInput {
+            index,
+            texture: Some(texture),
+            ..
+        } = argument
+        {
+            let image = match texture {
+                ImageInputTexture::Bytes {
+                    width,
+                    height,
+                    channels,
+                    bytes,
+                } => PixelBenderImage::from_f32_bytes(*width, *height, *channels, bytes),
+                texture => load_image(texture)?,
+            };
+            let index = *index as usize;
+            if index >= images.len() {
+                images.resize(index + 1, None);
+            }
+            images[index] = Some(image);
+        }
+    }
+    Ok(images)
+}
+
+/// The bitmaps of a backend that runs shader jobs on the CPU, with [`run_shader_job`].
+pub trait CpuShaderBitmaps {
+    /// Returns the width and height of a bitmap.
+    fn bitmap_size(&self, handle: &BitmapHandle) -> (u32, u32);
+
+    /// Reads the premultiplied RGBA pixels of a bitmap.
+    fn read_bitmap(&mut self, handle: &BitmapHandle) -> Result<PixelBenderImage, Error>;
+
+    /// Replaces every pixel of a bitmap with the given premultiplied RGBA pixels.
+    fn write_bitmap(&mut self, handle: &BitmapHandle, rgba: Vec<u8>) -> Result<(), Error>;
+}
+
+/// Runs a `ShaderJob` on the CPU, as an implementation of `RenderBackend::run_pixelbender_shader`
+/// for backends that can't run shaders themselves.
+///
+/// Bitmap targets are written to right away, and their pixels are returned in a
+/// [`CpuSyncHandle`].
+pub fn run_shader_job(
+    bitmaps: &mut impl CpuShaderBitmaps,
+    shader: &PixelBenderShader,
+    arguments: &[PixelBenderShaderArgument],
+    target: &PixelBenderTarget,
+) -> Result<PixelBenderOutput, Error> {
+    let (width, height) = match target {
+        PixelBenderTarget::Bitmap(handle) => bitmaps.bitmap_size(handle),
+        PixelBenderTarget::Bytes { width, height } => (*width, *height),
+    };
+    let output = run_shader(
+        shader,
+        ShaderMode::ShaderJob,
+        arguments,
+        width,
+        height,
+        |texture| match texture {
+            ImageInputTexture::Bitmap(handle) => bitmaps.read_bitmap(handle),
+            ImageInputTexture::TextureRef(texture) => {
+                match texture.downcast_ref::<BitmapHandle>() {
+                    Some(handle) => bitmaps.read_bitmap(handle),
+                    None => Err(Error::Unimplemented(
+                        "Pixel bender shader input from another backend's texture".into(),
+                    )),
+                }
+            }
+            ImageInputTexture::Bytes { .. } => unreachable!("Bytes are loaded without a backend"),
+        },
+    )?;
+
+    match target {
+        PixelBenderTarget::Bitmap(handle) => {
+            let rgba = output.to_rgba8();
+            bitmaps.write_bitmap(handle, rgba.clone())?;
+            Ok(PixelBenderOutput::Bitmap(Box::new(CpuSyncHandle {
+                data: rgba,
+                row_length: width * 4,
+            })))
+        }
+        PixelBenderTarget::Bytes { .. } => Ok(PixelBenderOutput::Bytes(output.to_f32_bytes())),
+    }
+}
+
+/// The value of a register, or of an operation on registers.
+#[derive(Clone, Copy, Debug)]
+enum Value {
+    /// A vector, padded out to four components.
+    Vector([f32; 4]),
+    /// A square matrix of the given size, stored as columns.
+    Matrix(usize, [[f32; 4]; 4]),
+}
+
+impl Value {
+    fn splat(value: f32) -> Self {
+        Value::Vector([value; 4])
+    }
+
+    fn from_bool(value: bool) -> f32 {
+        if value {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    fn vector(self) -> Result<[f32; 4], Error> {
+        match self {
+            Value::Vector(vector) => Ok(vector),
+            Value::Matrix(..) => Err(invalid("Expected a vector, found a matrix")),
+        }
+    }
+
+    /// Applies a function to every component, including the components of a matrix.
+    fn map(self, f: impl Fn(f32) -> f32) -> Self {
+        match self {
+            Value::Vector(vector) => Value::Vector(vector.map(f)),
+            Value::Matrix(size, columns) => Value::Matrix(size, columns.map(|c| c.map(&f))),
+        }
+    }
+
+    /// Applies a function to every pair of components from two values of the same shape.
+    fn zip(self, other: Self, f: impl Fn(f32, f32) -> f32) -> Result<Self, Error> {
+        let zip = |a: [f32; 4], b: [f32; 4]| [0, 1, 2, 3].map(|i| f(a[i], b[i]));
+        match (self, other) {
+            (Value::Vector(a), Value::Vector(b)) => Ok(Value::Vector(zip(a, b))),
+            (Value::Matrix(size, a), Value::Matrix(other_size, b)) if size == other_size => {
+                Ok(Value::Matrix(size, [0, 1, 2, 3].map(|i| zip(a[i], b[i]))))
+            }
+            _ => Err(invalid("Mismatched operand types")),
+        }
+    }
+}
+
+/// The registers of a shader while it processes a single pixel.
+///
+/// Every register holds four components. Int registers are kept separately from float registers.
+#[derive(Default)]
+struct Registers {
+    floats: Vec<[f32; 4]>,
+    ints: Vec<[i32; 4]>,
+}
+
+impl Registers {
+    fn clear(&mut self) {
+        // Like the local variables they're translated to on the GPU, registers start out as zero.
+        self.floats.fill([0.0; 4]);
+        self.ints.fill([0; 4]);
+    }
+
+    fn get(&self, kind: PixelBenderRegKind, index: u32) -> [f32; 4] {
+        let index = index as usize;
+        match kind {
+            PixelBenderRegKind::Float => self.floats.get(index).copied().unwrap_or_default(),
+            PixelBenderRegKind::Int => self
+                .ints
+                .get(index)
+                .copied()
+                .unwrap_or_default()
+                .map(|c| c as f32),
+        }
+    }
+
+    fn set_component(
+        &mut self,
+        kind: PixelBenderRegKind,
+        index: u32,
+        component: usize,
+        value: f32,
+    ) {
+        let index = index as usize;
+        match kind {
+            PixelBenderRegKind::Float => {
+                if index >= self.floats.len() {
+                    self.floats.resize(index + 1, [0.0; 4]);
+                }
+                self.floats[index][component] = value;
+            }
+            PixelBenderRegKind::Int => {
+                if index >= self.ints.len() {
+                    self.ints.resize(index + 1, [0; 4]);
+                }
+                self.ints[index][component] = value as i32;
+            }
+        }
+    }
+
+    /// Loads a register, swizzled by its channels.
+    ///
+    /// Vectors with fewer than four channels are padded with the register's alpha component.
+    /// Whatever uses the result only looks at the components it asked for.
+    fn load(&self, reg: &PixelBenderReg) -> Result<Value, Error> {
+        if let Some(size) = matrix_size(reg) {
+            let mut columns = [[0.0; 4]; 4];
+            if size == 2 {
+                // A 2x2 matrix is stored in a single register.
+                let [a, b, c, d] = self.get(PixelBenderRegKind::Float, reg.index);
+                columns[0] = [a, b, 0.0, 0.0];
+                columns[1] = [c, d, 0.0, 0.0];
+            } else {
+                for (i, column) in columns.iter_mut().take(size).enumerate() {
+                    *column = self.get(PixelBenderRegKind::Float, reg.index + i as u32);
+                    column[size..].fill(0.0);
+                }
+            }
+            return Ok(Value::Matrix(size, columns));
+        }
+
+        let value = self.get(reg.kind, reg.index);
+        let mut out = [value[3]; 4];
+        for (out, channel) in out.iter_mut().zip(&reg.channels) {
+            *out = value[*channel as usize];
+        }
+        Ok(Value::Vector(out))
+    }
+
+    /// Stores a value to the channels of a register. Component `i` of the value is written
+    /// to the `i`th channel of the destination.
+    fn store(&mut self, reg: &PixelBenderReg, value: Value) -> Result<(), Error> {
+        if let Some(size) = matrix_size(reg) {
+            let Value::Matrix(value_size, columns) = value else {
+                return Err(invalid("Expected a matrix, found a vector"));
+            };
+            if value_size != size {
+                return Err(invalid("Mismatched matrix sizes"));
+            }
+            if size == 2 {
+                let values = [columns[0][0], columns[0][1], columns[1][0], columns[1][1]];
+                for (i, value) in values.into_iter().enumerate() {
+                    self.set_component(PixelBenderRegKind::Float, reg.index, i, value);
+                }
+            } else {
+                for (i, column) in columns.iter().take(size).enumerate() {
+                    for (component, value) in column.iter().take(size).enumerate() {
+                        self.set_component(
+                            PixelBenderRegKind::Float,
+                            reg.index + i as u32,
+                            component,
+                            *value,
+                        );
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        let value = value.vector()?;
+        for (value, channel) in value.iter().zip(&reg.channels) {
+            self.set_component(reg.kind, reg.index, *channel as usize, *value);
+        }
+        Ok(())
+    }
+}
+
+struct Interpreter<'a> {
+    shader: &'a PixelBenderShader,
+    mode: ShaderMode,
+    /// The images bound to each texture index.
+    images: &'a [Option<PixelBenderImage>],
+    /// The initial values of every input parameter.
+    parameters: Vec<(&'a PixelBenderReg, Value)>,
+    out_coord: Option<&'a PixelBenderReg>,
+    output: &'a PixelBenderReg,
+}
+
+impl<'a> Interpreter<'a> {
+    fn new(
+        shader: &'a PixelBenderShader,
+        mode: ShaderMode,
+        arguments: &[PixelBenderShaderArgument],
+        images: &'a [Option<PixelBenderImage>],
+    ) -> Result<Self, Error> {
+        let mut parameters = Vec::new();
+        let mut out_coord = None;
+        let mut output = None;
+        for (index, param) in shader.params.iter().enumerate() {
+            let PixelBenderParam::Normal {
+                qualifier,
+                param_type: _,
+                reg,
+                name,
+                metadata: _,
+            } = param
+            else {
+                continue;
+            };
+            if *qualifier == PixelBenderParamQualifier::Output {
+                output = Some(reg);
+            } else if name == OUT_COORD_NAME {
+                out_coord = Some(reg);
+            } else {
+                let value = arguments.iter().find_map(|argument| match argument {
+                    PixelBenderShaderArgument::ValueInput { index: i, value }
+                        if *i as usize == index =>
+                    {
+                        Some(value)
+                    }
+                    _ => None,
+                });
+                // Parameters that were never set are zero, like an untouched uniform buffer.
+                let value = match value {
+                    Some(PixelBenderType::TString(_)) => continue,
+                    Some(value) => parameter_value(value),
+                    None => match matrix_size(reg) {
+                        Some(size) => Value::Matrix(size, [[0.0; 4]; 4]),
+                        None => Value::splat(0.0),
+                    },
+                };
+                parameters.push((reg, value));
+            }
+        }
+        let output = output.ok_or_else(|| invalid("Missing output parameter"))?;
+        if !matches!(output.channels.len(), 3 | 4) || matrix_size(output).is_some() {
+            return Err(invalid("Output parameter must be a float3 or float4"));
+        }
+
+        let mut depth = 0usize;
+        for op in &shader.operations {
+            match op {
+                Operation::If { .. } => depth += 1,
+                Operation::Else if depth == 0 => return Err(invalid("Else without If")),
+                Operation::EndIf => {
+                    depth = depth
+                        .checked_sub(1)
+                        .ok_or_else(|| invalid("EndIf without If"))?
+                }
+                _ => {}
+            }
+        }
+        if depth != 0 {
+            return Err(invalid("Unterminated If"));
+        }
+
+        Ok(Self {
+            shader,
+            mode,
+            images,
+            parameters,
+            out_coord,
+            output,
+        })
+    }
+
+    /// Runs the shader for the pixel at the given coordinates, returning the output padded to four channels.
+    /// Runs the shader over every pixel of the given rows of `output`.
+    fn run_rows(&self, output: &mut PixelBenderImage, rows: Range<u32>) -> Result<(), Error> {
+        let mut registers = Registers::default();
+        for y in rows {
+            for x in 0..output.width {
+                registers.clear();
+                // Like a fragment shader, coordinates are at the center of each pixel.
+                let color = self.run(&mut registers, x as f32 + 0.5, y as f32 + 0.5)?;
+                output.set_pixel(x, y, color);
+            }
+        }
+        Ok(())
+    }
+
+    fn run(&self, registers: &mut Registers, x: f32, y: f32) -> Result<[f32; 4], Error> {
+        for (reg, value) in &self.parameters {
+            registers.store(reg, *value)?;
+        }
+        // This is set after the other parameters, as it may share a register with them.
+        if let Some(reg) = self.out_coord {
+            registers.store(reg, Value::Vector([x, y, 0.0, 1.0]))?;
+        }
+
+        // Each entry is whether the enclosing block was running, and whether the condition was true.
+        let mut blocks: Vec<(bool, bool)> = Vec::new();
+        let mut running = true;
+        for op in &self.shader.operations {
+            match op {
+                Operation::If { src } => {
+                    let condition = registers.load(src)?.vector()?[0] != 0.0;
+                    blocks.push((running, condition));
+                    running = running && condition;
+                }
+                Operation::Else => {
+                    if let Some((outer, condition)) = blocks.last() {
+                        running = *outer && !condition;
+                    }
+                }
+                Operation::EndIf => {
+                    if let Some((outer, _)) = blocks.pop() {
+                        running = outer;
+                    }
+                }
+                _ if !running => {}
+                Operation::Nop => {}
+                Operation::Normal { opcode, dst, src } => {
+                    self.run_normal(registers, *opcode, dst, src)?;
+                }
+                Operation::LoadInt { dst, val } => {
+                    registers.store(dst, Value::splat(*val as f32))?
+                }
+                Operation::LoadFloat { dst, val } => registers.store(dst, Value::splat(*val))?,
+                Operation::SampleNearest { dst, src, tf }
+                | Operation::SampleLinear { dst, src, tf } => {
+                    let coord = registers.load(src)?.vector()?;
+                    let linear = matches!(op, Operation::SampleLinear { .. });
+                    let color = self.sample(*tf, coord[0], coord[1], linear);
+                    registers.store(dst, Value::Vector(color))?;
+                }
+                Operation::Select {
+                    src1,
+                    src2,
+                    condition,
+                    dst,
+                } => {
+                    let condition = registers.load(condition)?.vector()?[0] != 0.0;
+                    let value = registers.load(if condition { src1 } else { src2 })?;
+                    registers.store(dst, value)?;
+                }
+            }
+        }
+
+        registers.load(self.output)?.vector()
+    }
+
+    fn sample(&self, texture: u8, x: f32, y: f32, linear: bool) -> [f32; 4] {
+        let Some(Some(image)) = self.images.get(texture as usize) else {
+            return [0.0; 4];
+        };
+        let (u, v) = (x / image.width as f32, y / image.height as f32);
+        let in_bounds = (0.0..=1.0).contains(&u) && (0.0..=1.0).contains(&v);
+        if self.mode == ShaderMode::Filter && !in_bounds {
+            return [0.0; 4];
+        }
+        if linear {
+            image.sample_linear(x, y)
+        } else {
+            image.sample_nearest(x, y)
+        }
+    }
+
+    fn run_normal(
+        &self,
+        registers: &mut Registers,
+        opcode: Opcode,
+        dst: &PixelBenderReg,
+        src: &PixelBenderReg,
+    ) -> Result<(), Error> {
+        // Most opcodes use the destination as their first operand, as in 'dst = dst <op> src'.
+        let left = || registers.load(dst);
+        let right = registers.load(src)?;
+        // The number of components that operations on whole vectors (such as `length`) look at.
+        let size = src.channels.len();
+        let is_int = dst.kind == PixelBenderRegKind::Int && src.kind == PixelBenderRegKind::Int;
+
+        let result = match opcode {
+            Opcode::Nop => return Ok(()),
+            Opcode::Mov | Opcode::IntToFloat | Opcode::BoolToFloat | Opcode::BoolToInt => right,
+            Opcode::FloatToInt => right.map(f32::trunc),
+            Opcode::FloatToBool | Opcode::IntToBool => right.map(|a| Value::from_bool(a != 0.0)),
+            Opcode::Add => left()?.zip(right, |a, b| a + b)?,
+            Opcode::Sub => left()?.zip(right, |a, b| a - b)?,
+            Opcode::Mul | Opcode::MatMatMul => multiply(left()?, right)?,
+            Opcode::Div if is_int => left()?.zip(right, |a, b| {
+                // Like WGSL, integer division by zero returns the dividend.
+                if b == 0.0 {
+                    a
+                } else {
+                    (a / b).trunc()
+                }
+            })?,
+            Opcode::Div => left()?.zip(right, |a, b| a / b)?,
+            // This is a truncating remainder, like WGSL's `%` operator.
+            Opcode::Mod => left()?.zip(right, |a, b| a % b)?,
+            Opcode::Rcp => right.map(|a| 1.0 / a),
+            Opcode::Min => left()?.zip(right, f32::min)?,
+            Opcode::Max => left()?.zip(right, f32::max)?,
+            Opcode::Pow => left()?.zip(right, f32::powf)?,
+            Opcode::Atan2 => left()?.zip(right, f32::atan2)?,
+            Opcode::Step => left()?.zip(right, |edge, a| Value::from_bool(a >= edge))?,
+            Opcode::Sin => right.map(f32::sin),
+            Opcode::Cos => right.map(f32::cos),
+            Opcode::Tan => right.map(f32::tan),
+            Opcode::Asin => right.map(f32::asin),
+            Opcode::Acos => right.map(f32::acos),
+            Opcode::Atan => right.map(f32::atan),
+            Opcode::Exp => right.map(f32::exp),
+            Opcode::Exp2 => right.map(f32::exp2),
+            Opcode::Log => right.map(f32::ln),
+            Opcode::Log2 => right.map(f32::log2),
+            Opcode::Sqrt => right.map(f32::sqrt),
+            Opcode::RSqrt => right.map(|a| 1.0 / a.sqrt()),
+            Opcode::Abs => right.map(f32::abs),
+            Opcode::Sign => right.map(|a| if a == 0.0 { 0.0 } else { a.signum() }),
+            Opcode::Floor => right.map(f32::floor),
+            Opcode::Ceil => right.map(f32::ceil),
+            Opcode::Fract => right.map(|a| a - a.floor()),
+            Opcode::Length => Value::splat(dot(right.vector()?, right.vector()?, size).sqrt()),
+            Opcode::Distance => {
+                let difference = left()?.zip(right, |a, b| a - b)?.vector()?;
+                Value::splat(dot(difference, difference, size).sqrt())
+            }
+            Opcode::Normalize => {
+                let vector = right.vector()?;
+                let length = dot(vector, vector, size).sqrt();
+                Value::Vector(vector.map(|a| a / length))
+            }
+            Opcode::DotProduct => Value::splat(dot(left()?.vector()?, right.vector()?, size)),
+            Opcode::CrossProduct => {
+                let (a, b) = (left()?.vector()?, right.vector()?);
+                Value::Vector([
+                    a[1] * b[2] - a[2] * b[1],
+                    a[2] * b[0] - a[0] * b[2],
+                    a[0] * b[1] - a[1] * b[0],
+                    0.0,
+                ])
+            }
+            Opcode::MatVecMul => multiply(right, left()?)?,
+            Opcode::VecMatMul => multiply(left()?, right)?,
+            Opcode::LogicalAnd => {
+                left()?.zip(right, |a, b| Value::from_bool(a != 0.0 && b != 0.0))?
+            }
+            Opcode::LogicalOr => {
+                left()?.zip(right, |a, b| Value::from_bool(a != 0.0 || b != 0.0))?
+            }
+            Opcode::LogicalXor => {
+                left()?.zip(right, |a, b| Value::from_bool((a != 0.0) != (b != 0.0)))?
+            }
+            Opcode::LogicalNot => right.map(|a| Value::from_bool(a == 0.0)),
+            Opcode::BoolAny => Value::splat(Value::from_bool(
+                right.vector()?[..size].iter().any(|a| *a != 0.0),
+            )),
+            Opcode::BoolAll => Value::splat(Value::from_bool(
+                right.vector()?[..size].iter().all(|a| *a != 0.0),
+            )),
+            Opcode::Equal
+            | Opcode::NotEqual
+            | Opcode::LessThan
+            | Opcode::LessThanEqual
+            | Opcode::VectorEqual
+            | Opcode::VectorNotEqual => {
+                let (a, b) = (left()?.vector()?, right.vector()?);
+                let result = match opcode {
+                    Opcode::Equal => a[0] == b[0],
+                    Opcode::NotEqual => a[0] != b[0],
+                    Opcode::LessThan => a[0] < b[0],
+                    Opcode::LessThanEqual => a[0] <= b[0],
+                    Opcode::VectorEqual => a[..size] == b[..size],
+                    _ => a[..size] != b[..size],
+                };
+                // Comparisons always write their result to the first component of int register 0.
+                let dst = PixelBenderReg {
+                    index: 0,
+                    channels: vec![PixelBenderRegChannel::R],
+                    kind: PixelBenderRegKind::Int,
+                };
+                return registers.store(&dst, Value::splat(Value::from_bool(result)));
+            }
+            Opcode::SampleNearest
+            | Opcode::SampleLinear
+            | Opcode::LoadIntOrFloat
+            | Opcode::Select
+            | Opcode::If
+            | Opcode::Else
+            | Opcode::EndIf
+            | Opcode::PBJMeta1
+            | Opcode::PBJParam
+            | Opcode::PBJMeta2
+            | Opcode::PBJParamTexture
+            | Opcode::Name
+            | Opcode::Version => {
+                return Err(Error::Unimplemented(
+                    format!("Pixel Bender opcode {opcode:?} as a normal operation").into(),
+                ))
+            }
+        };
+        registers.store(dst, result)
+    }
+}
+
+/// Multiplies two values, using matrix multiplication when either is a matrix.
+fn multiply(left: Value, right: Value) -> Result<Value, Error> {
+    match (left, right) {
+        (Value::Vector(_), Value::Vector(_)) => left.zip(right, |a, b| a * b),
+        (Value::Matrix(size, a), Value::Matrix(other_size, b)) if size == other_size => {
+            let mut columns = [[0.0; 4]; 4];
+            for (column, b) in columns.iter_mut().zip(b).take(size) {
+                *column = matrix_times_vector(size, &a, b);
+            }
+            Ok(Value::Matrix(size, columns))
+        }
+        (Value::Matrix(size, matrix), Value::Vector(vector)) => {
+            Ok(Value::Vector(matrix_times_vector(size, &matrix, vector)))
+        }
+        (Value::Vector(vector), Value::Matrix(size, matrix)) => {
+            let mut result = [0.0; 4];
+            for (out, column) in result.iter_mut().zip(matrix).take(size) {
+                *out = dot(vector, column, size);
+            }
+            Ok(Value::Vector(result))
+        }
+        _ => Err(invalid("Mismatched matrix sizes")),
+    }
+}
+
+fn matrix_times_vector(size: usize, columns: &[[f32; 4]; 4], vector: [f32; 4]) -> [f32; 4] {
+    let mut result = [0.0; 4];
+    for (column, scale) in columns.iter().zip(vector).take(size) {
+        for (out, value) in result.iter_mut().zip(column).take(size) {
+            *out += value * scale;
+        }
+    }
+    result
+}
+
+fn dot(a: [f32; 4], b: [f32; 4], size: usize) -> f32 {
+    a.iter().zip(b).take(size).map(|(a, b)| a * b).sum()
+}
+
+fn matrix_size(reg: &PixelBenderReg) -> Option<usize> {
+    match reg.channels.as_slice() {
+        [PixelBenderRegChannel::M2x2] => Some(2),
+        [PixelBenderRegChannel::M3x3] => Some(3),
+        [PixelBenderRegChannel::M4x4] => Some(4),
+        _ => None,
+    }
+}
+
+/// Converts the value of a parameter, with matrices given in column-major order.
+fn parameter_value(value: &PixelBenderType) -> Value {
+    let matrix = |size: usize, values: &[f32]| {
+        let mut columns = [[0.0; 4]; 4];
+        for (column, values) in columns.iter_mut().zip(values.chunks_exact(size)) {
+            column[..size].copy_from_slice(values);
+        }
+        Value::Matrix(size, columns)
+    };
+    match value {
+        PixelBenderType::TFloat(a) => Value::Vector([*a, 0.0, 0.0, 0.0]),
+        PixelBenderType::TFloat2(a, b) => Value::Vector([*a, *b, 0.0, 0.0]),
+        PixelBenderType::TFloat3(a, b, c) => Value::Vector([*a, *b, *c, 0.0]),
+        PixelBenderType::TFloat4(a, b, c, d) => Value::Vector([*a, *b, *c, *d]),
+        PixelBenderType::TFloat2x2(values) => matrix(2, values),
+        PixelBenderType::TFloat3x3(values) => matrix(3, values),
+        PixelBenderType::TFloat4x4(values) => matrix(4, values),
+        PixelBenderType::TInt(a) => Value::Vector([f32::from(*a), 0.0, 0.0, 0.0]),
+        PixelBenderType::TInt2(a, b) => Value::Vector([f32::from(*a), f32::from(*b), 0.0, 0.0]),
+        PixelBenderType::TInt3(a, b, c) => {
+            Value::Vector([f32::from(*a), f32::from(*b), f32::from(*c), 0.0])
+        }
+        PixelBenderType::TInt4(a, b, c, d) => {
+            Value::Vector([f32::from(*a), f32::from(*b), f32::from(*c), f32::from(*d)])
+        }
+        PixelBenderType::TString(_) => Value::splat(0.0),
+    }
+}
+
+fn invalid(message: &'static str) -> Error {
+    Error::InvalidPixelBenderShader(Cow::Borrowed(message))
+}
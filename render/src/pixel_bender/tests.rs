@@ -1,8 +1,16 @@
+use crate::backend::{PixelBenderOutput, PixelBenderTarget};
+use crate::bitmap::{BitmapHandle, BitmapHandleImpl};
+use crate::error::Error;
+use crate::pixel_bender::interpreter::{
+    run_shader, run_shader_job, CpuShaderBitmaps, PixelBenderImage,
+};
 use crate::pixel_bender::{
-    Opcode, Operation, PixelBenderMetadata, PixelBenderParam, PixelBenderParamQualifier,
-    PixelBenderReg, PixelBenderRegChannel, PixelBenderRegKind, PixelBenderShader, PixelBenderType,
-    PixelBenderTypeOpcode,
+    ImageInputTexture, Opcode, Operation, PixelBenderMetadata, PixelBenderParam,
+    PixelBenderParamQualifier, PixelBenderReg, PixelBenderRegChannel, PixelBenderRegKind,
+    PixelBenderShader, PixelBenderShaderArgument, PixelBenderType, PixelBenderTypeOpcode,
+    ShaderMode,
 };
+use std::sync::Arc;
 
 use super::parse_shader;
 
@@ -343,3 +351,230 @@ fn simple_shader() {
     let shader = parse_shader(shader).expect("Failed to parse shader");
     assert_eq!(shader, expected, "Shader parsed incorrectly!");
 }
+
+fn float_reg(index: u32, channels: &[PixelBenderRegChannel]) -> PixelBenderReg {
+    PixelBenderReg {
+        index,
+        channels: channels.to_vec(),
+        kind: PixelBenderRegKind::Float,
+    }
+}
+
+/// A shader with `_OutCoord` in f0.rg, an output `dst` in f1.rgba and a single texture input.
+fn interpreter_shader(operations: Vec<Operation>) -> PixelBenderShader {
+    use PixelBenderRegChannel::*;
+    PixelBenderShader {
+        name: "Test".to_string(),
+        version: 1,
+        params: vec![
+            PixelBenderParam::Normal {
+                qualifier: PixelBenderParamQualifier::Input,
+                param_type: PixelBenderTypeOpcode::TFloat2,
+                reg: float_reg(0, &[R, G]),
+                name: "_OutCoord".to_string(),
+                metadata: vec![],
+            },
+            PixelBenderParam::Texture {
+                index: 0,
+                channels: 4,
+                name: "src".to_string(),
+            },
+            PixelBenderParam::Normal {
+                qualifier: PixelBenderParamQualifier::Output,
+                param_type: PixelBenderTypeOpcode::TFloat4,
+                reg: float_reg(1, &PixelBenderRegChannel::RGBA),
+                name: "dst".to_string(),
+                metadata: vec![],
+            },
+            PixelBenderParam::Normal {
+                qualifier: PixelBenderParamQualifier::Input,
+                param_type: PixelBenderTypeOpcode::TFloat,
+                reg: float_reg(2, &[R]),
+                name: "amount".to_string(),
+                metadata: vec![],
+            },
+        ],
+        metadata: vec![],
+        operations,
+    }
+}
+
+fn run_interpreter(
+    shader: &PixelBenderShader,
+    mode: ShaderMode,
+    width: u32,
+    input: &[f32],
+) -> PixelBenderImage {
+    let arguments = [
+        PixelBenderShaderArgument::ImageInput {
+            index: 0,
+            channels: 4,
+            name: "src".to_string(),
+            texture: Some(ImageInputTexture::Bytes {
+                width: input.len() as u32 / 4,
+                height: 1,
+                channels: 4,
+                bytes: input.iter().flat_map(|c| c.to_le_bytes()).collect(),
+            }),
+        },
+        PixelBenderShaderArgument::ValueInput {
+            index: 3,
+            value: PixelBenderType::TFloat(0.25),
+        },
+    ];
+    run_shader(shader, mode, &arguments, width, 1, |_| {
+        panic!("Only byte inputs are used")
+    })
+    .expect("Shader should run")
+}
+
+#[test]
+fn interpreter_sample_and_add() {
+    use PixelBenderRegChannel::*;
+    let shader = interpreter_shader(vec![
+        Operation::SampleNearest {
+            dst: float_reg(1, &PixelBenderRegChannel::RGBA),
+            src: float_reg(0, &[R, G]),
+            tf: 0,
+        },
+        Operation::Normal {
+            opcode: Opcode::Add,
+            dst: float_reg(1, &[R]),
+            src: float_reg(2, &[R]),
+        },
+    ]);
+    let input = [0.125, 0.25, 0.375, 0.5, 0.5, 0.625, 0.75, 0.875];
+
+    // The third pixel is outside the input image.
+    let output = run_interpreter(&shader, ShaderMode::ShaderJob, 3, &input);
+    assert_eq!(
+        output.data,
+        vec![0.375, 0.25, 0.375, 0.5, 0.75, 0.625, 0.75, 0.875, 0.75, 0.625, 0.75, 0.875]
+    );
+
+    let output = run_interpreter(&shader, ShaderMode::Filter, 3, &input);
+    assert_eq!(
+        output.data,
+        vec![0.375, 0.25, 0.375, 0.5, 0.75, 0.625, 0.75, 0.875, 0.25, 0.0, 0.0, 0.0]
+    );
+}
+
+#[test]
+fn interpreter_if_else() {
+    use PixelBenderRegChannel::*;
+    let shader = interpreter_shader(vec![
+        Operation::LoadFloat {
+            dst: float_reg(3, &[R]),
+            val: 1.0,
+        },
+        Operation::Normal {
+            opcode: Opcode::LessThan,
+            dst: float_reg(0, &[R]),
+            src: float_reg(3, &[R]),
+        },
+        Operation::If {
+            src: PixelBenderReg {
+                index: 0,
+                channels: vec![R],
+                kind: PixelBenderRegKind::Int,
+            },
+        },
+        Operation::LoadFloat {
+            dst: float_reg(1, &PixelBenderRegChannel::RGBA),
+            val: 1.0,
+        },
+        Operation::Else,
+        Operation::LoadFloat {
+            dst: float_reg(1, &PixelBenderRegChannel::RGBA),
+            val: 0.5,
+        },
+        Operation::EndIf,
+    ]);
+
+    let output = run_interpreter(&shader, ShaderMode::ShaderJob, 2, &[]);
+    assert_eq!(
+        output.to_rgba8(),
+        vec![255, 255, 255, 255, 128, 128, 128, 128]
+    );
+}
+
+#[test]
+fn interpreter_rejects_unbalanced_if() {
+    use PixelBenderRegChannel::*;
+    let shader = interpreter_shader(vec![Operation::If {
+        src: float_reg(0, &[R]),
+    }]);
+    let result = run_shader(&shader, ShaderMode::ShaderJob, &[], 1, 1, |_| {
+        panic!("No inputs are used")
+    });
+    assert!(matches!(result, Err(Error::InvalidPixelBenderShader(_))));
+}
+
+/// A bitmap made of a single row of RGBA pixels.
+#[derive(Debug)]
+struct RowBitmap(Vec<u8>);
+
+impl BitmapHandleImpl for RowBitmap {}
+
+struct RowBitmaps;
+
+impl CpuShaderBitmaps for RowBitmaps {
+    fn bitmap_size(&self, handle: &BitmapHandle) -> (u32, u32) {
+        let bitmap = handle
+            .0
+            .downcast_ref::<RowBitmap>()
+            .expect("Bitmap should be a row");
+        (bitmap.0.len() as u32 / 4, 1)
+    }
+
+    fn read_bitmap(&mut self, handle: &BitmapHandle) -> Result<PixelBenderImage, Error> {
+        let (width, height) = self.bitmap_size(handle);
+        let bitmap = handle
+            .0
+            .downcast_ref::<RowBitmap>()
+            .expect("Bitmap should be a row");
+        Ok(PixelBenderImage::from_rgba8(width, height, &bitmap.0))
+    }
+
+    fn write_bitmap(&mut self, _handle: &BitmapHandle, _rgba: Vec<u8>) -> Result<(), Error> {
+        panic!("Only byte targets are used")
+    }
+}
+
+#[test]
+fn shader_job_reads_texture_inputs() {
+    use PixelBenderRegChannel::*;
+    let shader = interpreter_shader(vec![Operation::SampleNearest {
+        dst: float_reg(1, &PixelBenderRegChannel::RGBA),
+        src: float_reg(0, &[R, G]),
+        tf: 0,
+    }]);
+    let bitmap = BitmapHandle(Arc::new(RowBitmap(vec![
+        0, 51, 102, 255, 255, 204, 153, 255,
+    ])));
+    let arguments = [PixelBenderShaderArgument::ImageInput {
+        index: 0,
+        channels: 4,
+        name: "src".to_string(),
+        texture: Some(ImageInputTexture::TextureRef(&bitmap)),
+    }];
+
+    let output = run_shader_job(
+        &mut RowBitmaps,
+        &shader,
+        &arguments,
+        &PixelBenderTarget::Bytes {
+            width: 2,
+            height: 1,
+        },
+    )
+    .expect("Shader should run");
+    let PixelBenderOutput::Bytes(bytes) = output else {
+        panic!("Output should be bytes");
+    };
+    let data: Vec<f32> = bytes
+        .chunks_exact(4)
+        .map(|bytes| f32::from_le_bytes(bytes.try_into().expect("Chunks are 4 bytes")))
+        .collect();
+    assert_eq!(data, [0.0, 0.2, 0.4, 1.0, 1.0, 0.8, 0.6, 1.0]);
+}
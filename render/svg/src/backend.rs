@@ -0,0 +1,176 @@
+use crate::shape::Shape;
+use crate::writer::SvgWriter;
+use crate::{as_bitmap, Bitmap as SvgBitmap, BitmapData};
+use ruffle_render::backend::{
+    BitmapCacheEntry, Context3D, Context3DProfile, PixelBenderOutput, PixelBenderTarget,
+    RenderBackend, ShapeHandle, ViewportDimensions,
+};
+use ruffle_render::bitmap::{
+    Bitmap, BitmapHandle, BitmapSource, PixelRegion, RgbaBufRead, SyncHandle,
+};
+use ruffle_render::commands::CommandList;
+use ruffle_render::error::Error;
+use ruffle_render::pixel_bender::{
+    PixelBenderShader, PixelBenderShaderArgument, PixelBenderShaderHandle,
+};
+use ruffle_render::quality::StageQuality;
+use ruffle_render::shape_utils::DistilledShape;
+use std::borrow::Cow;
+use std::sync::{Arc, RwLock};
+use swf::Color;
+
+/// A render backend that turns every submitted frame into an SVG document.
+///
+/// The last frame can be retrieved with [`SvgRenderBackend::capture_frame`].
+/// Offscreen rendering and filters aren't supported, so `cacheAsBitmap`
+/// content is drawn as vectors and filters are ignored.
+pub struct SvgRenderBackend {
+    dimensions: ViewportDimensions,
+    quality: StageQuality,
+    frame: String,
+}
+
+impl SvgRenderBackend {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            dimensions: ViewportDimensions {
+                width,
+                height,
+                scale_factor: 1.0,
+            },
+            quality: StageQuality::High,
+            frame: String::new(),
+        }
+    }
+
+    /// Returns the last submitted frame as an SVG document.
+    pub fn capture_frame(&self) -> &str {
+        &self.frame
+    }
+}
+
+impl RenderBackend for SvgRenderBackend {
+    fn viewport_dimensions(&self) -> ViewportDimensions {
+        self.dimensions
+    }
+
+    fn set_viewport_dimensions(&mut self, dimensions: ViewportDimensions) {
+        self.dimensions = dimensions;
+    }
+
+    fn register_shape(
+        &mut self,
+        shape: DistilledShape,
+        bitmap_source: &dyn BitmapSource,
+    ) -> ShapeHandle {
+        // Shapes hold on to their bitmap handles, which aren't `Send`.
+        #[allow(clippy::arc_with_non_send_sync)]
+        ShapeHandle(Arc::new(Shape::new(shape, bitmap_source, self)))
+    }
+
+    fn render_offscreen(
+        &mut self,
+        _handle: BitmapHandle,
+        _commands: CommandList,
+        _quality: StageQuality,
+        _bounds: PixelRegion,
+    ) -> Option<Box<dyn SyncHandle>> {
+        None
+    }
+
+    fn submit_frame(
+        &mut self,
+        clear: Color,
+        commands: CommandList,
+        _cache_entries: Vec<BitmapCacheEntry>,
+    ) {
+        self.frame =
+            SvgWriter::new(self.dimensions.width, self.dimensions.height).draw(clear, commands);
+    }
+
+    fn register_bitmap(&mut self, bitmap: Bitmap) -> Result<BitmapHandle, Error> {
+        Ok(BitmapHandle(Arc::new(SvgBitmap(RwLock::new(
+            BitmapData::from(bitmap),
+        )))))
+    }
+
+    fn update_texture(
+        &mut self,
+        handle: &BitmapHandle,
+        bitmap: Bitmap,
+        _region: PixelRegion,
+    ) -> Result<(), Error> {
+        // The whole bitmap is always given, so there's no need to only copy the region.
+        *as_bitmap(handle).write() = BitmapData::from(bitmap);
+        Ok(())
+    }
+
+    fn create_empty_texture(&mut self, width: u32, height: u32) -> Result<BitmapHandle, Error> {
+        Ok(BitmapHandle(Arc::new(SvgBitmap(RwLock::new(BitmapData {
+            width,
+            height,
+            data: vec![0; width as usize * height as usize * 4],
+        })))))
+    }
+
+    fn create_context3d(
+        &mut self,
+        _profile: Context3DProfile,
+    ) -> Result<Box<dyn Context3D>, Error> {
+        Err(Error::Unimplemented("createContext3D".into()))
+    }
+
+    fn context3d_present(&mut self, _context: &mut dyn Context3D) -> Result<(), Error> {
+        Err(Error::Unimplemented("Context3D.present".into()))
+    }
+
+    fn debug_info(&self) -> Cow<'static, str> {
+        Cow::Owned(format!(
+            "Renderer: SVG\nViewport: {}x{}\nQuality: {}",
+            self.dimensions.width, self.dimensions.height, self.quality
+        ))
+    }
+
+    fn name(&self) -> &'static str {
+        "svg"
+    }
+
+    fn set_quality(&mut self, quality: StageQuality) {
+        self.quality = quality;
+    }
+
+    fn compile_pixelbender_shader(
+        &mut self,
+        _shader: PixelBenderShader,
+    ) -> Result<PixelBenderShaderHandle, Error> {
+        Err(Error::Unimplemented("compile_pixelbender_shader".into()))
+    }
+
+    fn run_pixelbender_shader(
+        &mut self,
+        _shader: PixelBenderShaderHandle,
+        _arguments: &[PixelBenderShaderArgument],
+        _target: &PixelBenderTarget,
+    ) -> Result<PixelBenderOutput, Error> {
+        Err(Error::Unimplemented("run_pixelbender_shader".into()))
+    }
+
+    fn resolve_sync_handle(
+        &mut self,
+        _handle: Box<dyn SyncHandle>,
+        _with_rgba: RgbaBufRead,
+    ) -> Result<(), Error> {
+        Err(Error::Unimplemented("Sync handle resolution".into()))
+    }
+}
+
+impl From<Bitmap> for BitmapData {
+    fn from(bitmap: Bitmap) -> Self {
+        let bitmap = bitmap.to_rgba();
+        Self {
+            width: bitmap.width(),
+            height: bitmap.height(),
+            data: bitmap.data().to_vec(),
+        }
+    }
+}
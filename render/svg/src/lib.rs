@@ -0,0 +1,42 @@
+//! A render backend that records frames as SVG documents, for resolution-independent exports.
+//!
+//! Shapes are kept as the vector paths they were defined with, rather than being tessellated.
+//! Gradients and bitmap fills become SVG paint servers, masks become clip paths,
+//! and bitmaps are embedded as PNG images.
+
+use ruffle_render::bitmap::{BitmapHandle, BitmapHandleImpl};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+mod backend;
+mod shape;
+mod writer;
+
+pub use backend::SvgRenderBackend;
+
+/// The pixels of a bitmap, as premultiplied RGBA.
+#[derive(Clone, Debug)]
+struct BitmapData {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+/// A bitmap kept in main memory, so that it can be embedded into the SVG.
+#[derive(Debug)]
+struct Bitmap(RwLock<BitmapData>);
+
+impl BitmapHandleImpl for Bitmap {}
+
+impl Bitmap {
+    fn read(&self) -> RwLockReadGuard<'_, BitmapData> {
+        self.0.read().expect("Bitmap lock should not be poisoned")
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, BitmapData> {
+        self.0.write().expect("Bitmap lock should not be poisoned")
+    }
+}
+
+fn as_bitmap(handle: &BitmapHandle) -> &Bitmap {
+    <dyn BitmapHandleImpl>::downcast_ref(&*handle.0).expect("BitmapHandle should be an SVG bitmap")
+}
@@ -0,0 +1,287 @@
+use ruffle_render::backend::{RenderBackend, ShapeHandleImpl};
+use ruffle_render::bitmap::{BitmapHandle, BitmapSource};
+use ruffle_render::matrix::Matrix;
+use ruffle_render::shape_utils::{DistilledShape, DrawCommand, DrawPath, FillRule};
+use ruffle_render::tessellator::{Mesh, ShapeTessellator};
+use std::fmt::Write;
+use swf::{Color, FillStyle, Gradient, LineCapStyle, LineJoinStyle, LineStyle};
+
+/// A shape, kept as SVG path data.
+#[derive(Debug)]
+pub struct Shape {
+    pub paths: Vec<Path>,
+}
+
+impl ShapeHandleImpl for Shape {}
+
+#[derive(Debug)]
+pub struct Path {
+    /// The `d` attribute of the path, in pixels.
+    pub data: String,
+    pub kind: PathKind,
+}
+
+#[derive(Debug)]
+pub enum PathKind {
+    Fill {
+        paint: Paint,
+        fill_rule: FillRule,
+    },
+    Stroke {
+        paint: Paint,
+        stroke: Stroke,
+        /// The area covered by the stroke, as path data made of triangles.
+        ///
+        /// Clip paths ignore strokes, so masks fill this instead.
+        outline: String,
+    },
+}
+
+#[derive(Debug)]
+pub enum Paint {
+    Color(Color),
+    Gradient {
+        kind: GradientKind,
+        gradient: Gradient,
+    },
+    Bitmap {
+        bitmap: BitmapHandle,
+        /// Maps bitmap pixels to the shape, in pixels.
+        matrix: Matrix,
+        is_smoothed: bool,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum GradientKind {
+    Linear,
+    Radial,
+    /// A radial gradient with its focal point moved along the x axis, from -1 to 1.
+    Focal(f32),
+}
+
+#[derive(Debug)]
+pub struct Stroke {
+    /// The width of the stroke in pixels, or zero for a hairline.
+    pub width: f64,
+    pub cap: LineCapStyle,
+    pub join: LineJoinStyle,
+    /// Whether the width is scaled along with the shape.
+    pub is_scaled: bool,
+}
+
+impl Shape {
+    pub fn new(
+        shape: DistilledShape,
+        bitmap_source: &dyn BitmapSource,
+        backend: &mut dyn RenderBackend,
+    ) -> Self {
+        let DistilledShape {
+            paths: draw_paths,
+            shape_bounds,
+            edge_bounds,
+            id,
+        } = shape;
+        let mut tessellator = ShapeTessellator::new();
+        let mut paths = Vec::with_capacity(draw_paths.len());
+        for path in draw_paths {
+            let path = match path {
+                DrawPath::Fill {
+                    style,
+                    commands,
+                    winding_rule,
+                } => {
+                    let Some(paint) = Paint::new(style, bitmap_source, backend) else {
+                        continue;
+                    };
+                    Path {
+                        data: path_data(&commands, false),
+                        kind: PathKind::Fill {
+                            paint,
+                            fill_rule: winding_rule,
+                        },
+                    }
+                }
+                DrawPath::Stroke {
+                    style,
+                    is_closed,
+                    commands,
+                } => {
+                    let Some(paint) = Paint::new(style.fill_style(), bitmap_source, backend) else {
+                        continue;
+                    };
+                    let data = path_data(&commands, is_closed);
+                    let mesh = tessellator.tessellate_shape(
+                        DistilledShape {
+                            paths: vec![DrawPath::Stroke {
+                                style,
+                                is_closed,
+                                commands,
+                            }],
+                            shape_bounds: shape_bounds.clone(),
+                            edge_bounds: edge_bounds.clone(),
+                            id,
+                        },
+                        bitmap_source,
+                    );
+                    Path {
+                        data,
+                        kind: PathKind::Stroke {
+                            paint,
+                            stroke: Stroke::new(style),
+                            outline: mesh_data(&mesh),
+                        },
+                    }
+                }
+            };
+            paths.push(path);
+        }
+        Self { paths }
+    }
+}
+
+impl Paint {
+    fn new(
+        style: &FillStyle,
+        bitmap_source: &dyn BitmapSource,
+        backend: &mut dyn RenderBackend,
+    ) -> Option<Self> {
+        Some(match style {
+            FillStyle::Color(color) => Paint::Color(*color),
+            FillStyle::LinearGradient(gradient) => Paint::Gradient {
+                kind: GradientKind::Linear,
+                gradient: gradient.clone(),
+            },
+            FillStyle::RadialGradient(gradient) => Paint::Gradient {
+                kind: GradientKind::Radial,
+                gradient: gradient.clone(),
+            },
+            FillStyle::FocalGradient {
+                gradient,
+                focal_point,
+            } => Paint::Gradient {
+                kind: GradientKind::Focal(focal_point.to_f32()),
+                gradient: gradient.clone(),
+            },
+            FillStyle::Bitmap {
+                id,
+                matrix,
+                is_smoothed,
+                is_repeating: _,
+            } => {
+                // Missing bitmap -- incorrect character ID in SWF?
+                let bitmap = bitmap_source.bitmap_handle(*id, backend)?;
+                // Bitmap fill matrices map bitmap pixels to twips.
+                let matrix = Matrix::from(*matrix) * Matrix::scale(0.05, 0.05);
+                Paint::Bitmap {
+                    bitmap,
+                    matrix,
+                    is_smoothed: *is_smoothed,
+                }
+            }
+        })
+    }
+}
+
+impl Stroke {
+    fn new(style: &LineStyle) -> Self {
+        Self {
+            width: style.width().to_pixels(),
+            cap: style.start_cap(),
+            join: style.join_style(),
+            is_scaled: style.allow_scale_x() || style.allow_scale_y(),
+        }
+    }
+}
+
+/// Converts draw commands to SVG path data, in pixels.
+fn path_data(commands: &[DrawCommand], is_closed: bool) -> String {
+    fn write_point(data: &mut String, command: char, points: &[swf::Point<swf::Twips>]) {
+        data.push(command);
+        for point in points {
+            let _ = write!(data, "{} {} ", point.x.to_pixels(), point.y.to_pixels());
+        }
+    }
+
+    let mut data = String::new();
+    for command in commands {
+        match command {
+            DrawCommand::MoveTo(point) => write_point(&mut data, 'M', &[*point]),
+            DrawCommand::LineTo(point) => write_point(&mut data, 'L', &[*point]),
+            DrawCommand::QuadraticCurveTo { control, anchor } => {
+                write_point(&mut data, 'Q', &[*control, *anchor])
+            }
+            DrawCommand::CubicCurveTo {
+                control_a,
+                control_b,
+                anchor,
+            } => write_point(&mut data, 'C', &[*control_a, *control_b, *anchor]),
+        }
+    }
+    if is_closed {
+        data.push('Z');
+    }
+    data.truncate(data.trim_end().len());
+    data
+}
+
+/// Converts the triangles of a tessellated shape to SVG path data, in pixels.
+fn mesh_data(mesh: &Mesh) -> String {
+    let mut data = String::new();
+    for draw in &mesh.draws {
+        for triangle in draw.indices.chunks_exact(3) {
+            let [a, mut b, mut c] = [0, 1, 2].map(|i| &draw.vertices[triangle[i] as usize]);
+            // Wind every triangle the same way, so that overlapping ones don't cancel out.
+            if (b.x - a.x) * (c.y - a.y) < (b.y - a.y) * (c.x - a.x) {
+                std::mem::swap(&mut b, &mut c);
+            }
+            let _ = write!(
+                data,
+                "M{} {} L{} {} L{} {} Z ",
+                a.x, a.y, b.x, b.y, c.x, c.y
+            );
+        }
+    }
+    data.truncate(data.trim_end().len());
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ruffle_render::tessellator::{Draw, DrawType, Vertex};
+    use swf::{Point, Twips};
+
+    #[test]
+    fn path_data_is_in_pixels() {
+        let commands = [
+            DrawCommand::MoveTo(Point::new(Twips::new(0), Twips::new(20))),
+            DrawCommand::LineTo(Point::new(Twips::new(30), Twips::new(20))),
+            DrawCommand::QuadraticCurveTo {
+                control: Point::new(Twips::new(40), Twips::new(40)),
+                anchor: Point::new(Twips::new(0), Twips::new(20)),
+            },
+        ];
+        assert_eq!(path_data(&commands, false), "M0 1 L1.5 1 Q2 2 0 1");
+        assert_eq!(path_data(&commands, true), "M0 1 L1.5 1 Q2 2 0 1 Z");
+    }
+
+    #[test]
+    fn mesh_triangles_have_the_same_winding() {
+        let vertex = |x, y| Vertex {
+            x,
+            y,
+            color: Color::WHITE,
+        };
+        let mesh = Mesh {
+            draws: vec![Draw {
+                draw_type: DrawType::Color,
+                vertices: vec![vertex(0.0, 0.0), vertex(2.0, 0.0), vertex(0.0, 2.0)],
+                indices: vec![0, 1, 2, 0, 2, 1],
+                mask_index_count: 6,
+            }],
+            gradients: vec![],
+        };
+        assert_eq!(mesh_data(&mesh), "M0 0 L2 0 L0 2 Z M0 0 L2 0 L0 2 Z");
+    }
+}
@@ -0,0 +1,636 @@
+use crate::shape::{GradientKind, Paint, PathKind, Shape};
+use crate::{as_bitmap, BitmapData};
+use base64::Engine;
+use ruffle_render::backend::ShapeHandle;
+use ruffle_render::backend::ShapeHandleImpl;
+use ruffle_render::bitmap::{BitmapHandle, PixelSnapping};
+use ruffle_render::commands::{CommandHandler, CommandList, RenderBlendMode};
+use ruffle_render::lines::{emulate_line, emulate_line_rect};
+use ruffle_render::matrix::Matrix;
+use ruffle_render::shape_utils::FillRule;
+use ruffle_render::transform::Transform;
+use std::fmt::Write;
+use swf::{
+    BlendMode, Color, ColorTransform, GradientInterpolation, GradientSpread, LineCapStyle,
+    LineJoinStyle,
+};
+
+/// Gradients span from -16384 to 16384 twips in gradient space.
+const GRADIENT_EXTENT: f64 = 16384.0 / 20.0;
+
+/// What the content of a [`Layer`] is used for.
+enum LayerKind {
+    /// Content that is drawn normally.
+    Normal,
+    /// A mask that is being drawn, which becomes a clip path.
+    Mask,
+    /// Content clipped by the clip path with the given ID.
+    Masked(String),
+    /// A mask being drawn again to remove it, which SVG has no need for.
+    ClearMask,
+    /// Content that is blended with what's below it.
+    Blend(Option<&'static str>),
+}
+
+struct Layer {
+    kind: LayerKind,
+    elements: Vec<String>,
+}
+
+impl Layer {
+    fn new(kind: LayerKind) -> Self {
+        Self {
+            kind,
+            elements: Vec::new(),
+        }
+    }
+}
+
+/// Turns a `CommandList` into an SVG document.
+pub struct SvgWriter {
+    width: u32,
+    height: u32,
+    /// Gradients, patterns and clip paths, which are referenced by ID.
+    defs: String,
+    next_id: u32,
+    layers: Vec<Layer>,
+}
+
+impl SvgWriter {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            defs: String::new(),
+            next_id: 0,
+            layers: vec![Layer::new(LayerKind::Normal)],
+        }
+    }
+
+    pub fn draw(mut self, clear: Color, commands: CommandList) -> String {
+        if clear.a > 0 {
+            let fill = paint_color("fill", clear);
+            self.push_element(format!("<rect width=\"100%\" height=\"100%\"{fill}/>"));
+        }
+        commands.execute(&mut self);
+
+        // Close anything that was left open by unbalanced mask commands.
+        while self.layers.len() > 1 {
+            self.pop_layer();
+        }
+        let content = self
+            .layers
+            .pop()
+            .map(|l| l.elements.concat())
+            .unwrap_or_default();
+
+        let mut svg = String::new();
+        let _ = write!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">",
+            self.width, self.height
+        );
+        if !self.defs.is_empty() {
+            let _ = write!(svg, "<defs>{}</defs>", self.defs);
+        }
+        svg.push_str(&content);
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{prefix}{}", self.next_id)
+    }
+
+    fn layer(&mut self) -> &mut Layer {
+        self.layers
+            .last_mut()
+            .expect("Root layer should always exist")
+    }
+
+    fn drawing_mask(&self) -> bool {
+        self.layers
+            .iter()
+            .any(|layer| matches!(layer.kind, LayerKind::Mask))
+    }
+
+    fn push_element(&mut self, element: String) {
+        if self
+            .layers
+            .iter()
+            .any(|layer| matches!(layer.kind, LayerKind::ClearMask))
+        {
+            return;
+        }
+        self.layer().elements.push(element);
+    }
+
+    /// The clip path of the masked content that is being drawn into a mask, if any.
+    ///
+    /// Clip paths can't contain groups, so this content is clipped shape by shape.
+    fn enclosing_clip(&self) -> Option<&str> {
+        self.layers
+            .iter()
+            .rev()
+            .take_while(|layer| !matches!(layer.kind, LayerKind::Mask))
+            .find_map(|layer| match &layer.kind {
+                LayerKind::Masked(id) => Some(id.as_str()),
+                _ => None,
+            })
+            .filter(|_| self.drawing_mask())
+    }
+
+    /// Closes the topmost layer, adding its content to the layer below.
+    fn pop_layer(&mut self) {
+        let Some(layer) = self.layers.pop() else {
+            return;
+        };
+        match layer.kind {
+            LayerKind::Normal | LayerKind::ClearMask => {}
+            LayerKind::Mask => {
+                let id = self.next_id("clip");
+                // A mask drawn inside another mask only covers what that mask lets through,
+                // so the shapes clipped by it don't need to be clipped again.
+                let clip = self
+                    .enclosing_clip()
+                    .map(|parent| format!(" clip-path=\"url(#{parent})\""))
+                    .unwrap_or_default();
+                let _ = write!(
+                    self.defs,
+                    "<clipPath id=\"{id}\"{clip}>{}</clipPath>",
+                    layer.elements.concat()
+                );
+                self.layers.push(Layer::new(LayerKind::Masked(id)));
+            }
+            LayerKind::Masked(id) => {
+                if self.drawing_mask() {
+                    for element in layer.elements {
+                        let element = match element.strip_suffix("/>") {
+                            Some(start) if !start.contains(" clip-path=") => {
+                                format!("{start} clip-path=\"url(#{id})\"/>")
+                            }
+                            _ => element,
+                        };
+                        self.push_element(element);
+                    }
+                } else if !layer.elements.is_empty() {
+                    self.push_element(format!(
+                        "<g clip-path=\"url(#{id})\">{}</g>",
+                        layer.elements.concat()
+                    ));
+                }
+            }
+            LayerKind::Blend(Some(mode)) if !self.drawing_mask() => {
+                if !layer.elements.is_empty() {
+                    self.push_element(format!(
+                        "<g style=\"mix-blend-mode:{mode}\">{}</g>",
+                        layer.elements.concat()
+                    ));
+                }
+            }
+            // Blending doesn't change the shape of a mask.
+            LayerKind::Blend(_) => {
+                for element in layer.elements {
+                    self.push_element(element);
+                }
+            }
+        }
+    }
+
+    /// Returns the attributes that paint with `paint`, where `name` is either `fill` or `stroke`.
+    fn paint(&mut self, name: &str, paint: &Paint, color_transform: &ColorTransform) -> String {
+        match paint {
+            Paint::Color(color) => paint_color(name, color_transform * *color),
+            Paint::Gradient { kind, gradient } => {
+                let id = self.next_id("gradient");
+                let matrix = matrix_attribute(&Matrix::from(gradient.matrix));
+                let spread = match gradient.spread {
+                    GradientSpread::Pad => "pad",
+                    GradientSpread::Reflect => "reflect",
+                    GradientSpread::Repeat => "repeat",
+                };
+                let interpolation = match gradient.interpolation {
+                    GradientInterpolation::Rgb => "",
+                    GradientInterpolation::LinearRgb => " color-interpolation=\"linearRGB\"",
+                };
+                let (element, geometry) = match kind {
+                    GradientKind::Linear => (
+                        "linearGradient",
+                        format!(
+                            "x1=\"{}\" y1=\"0\" x2=\"{GRADIENT_EXTENT}\" y2=\"0\"",
+                            -GRADIENT_EXTENT
+                        ),
+                    ),
+                    GradientKind::Radial => (
+                        "radialGradient",
+                        format!("cx=\"0\" cy=\"0\" r=\"{GRADIENT_EXTENT}\""),
+                    ),
+                    GradientKind::Focal(focal_point) => (
+                        "radialGradient",
+                        format!(
+                            "cx=\"0\" cy=\"0\" r=\"{GRADIENT_EXTENT}\" fx=\"{}\" fy=\"0\"",
+                            f64::from(*focal_point) * GRADIENT_EXTENT
+                        ),
+                    ),
+                };
+                let _ = write!(
+                    self.defs,
+                    "<{element} id=\"{id}\" gradientUnits=\"userSpaceOnUse\" {geometry} gradientTransform=\"{matrix}\" spreadMethod=\"{spread}\"{interpolation}>"
+                );
+                for record in &gradient.records {
+                    let color = color_transform * record.color;
+                    let _ = write!(
+                        self.defs,
+                        "<stop offset=\"{}\" stop-color=\"{}\" stop-opacity=\"{}\"/>",
+                        f32::from(record.ratio) / 255.0,
+                        hex_color(color),
+                        f32::from(color.a) / 255.0
+                    );
+                }
+                let _ = write!(self.defs, "</{element}>");
+                format!(" {name}=\"url(#{id})\"")
+            }
+            Paint::Bitmap {
+                bitmap,
+                matrix,
+                is_smoothed,
+            } => {
+                // SVG patterns always repeat, so non-repeating bitmap fills
+                // don't clamp to their edges like they do in Flash.
+                let id = self.next_id("pattern");
+                let bitmap = as_bitmap(bitmap).read();
+                let _ = write!(
+                    self.defs,
+                    "<pattern id=\"{id}\" patternUnits=\"userSpaceOnUse\" width=\"{}\" height=\"{}\" patternTransform=\"{}\">{}</pattern>",
+                    bitmap.width,
+                    bitmap.height,
+                    matrix_attribute(matrix),
+                    image_element(&bitmap, color_transform, *is_smoothed, "")
+                );
+                format!(" {name}=\"url(#{id})\"")
+            }
+        }
+    }
+}
+
+impl CommandHandler for SvgWriter {
+    fn render_bitmap(
+        &mut self,
+        bitmap: BitmapHandle,
+        transform: Transform,
+        smoothing: bool,
+        pixel_snapping: PixelSnapping,
+    ) {
+        let mut matrix = transform.matrix;
+        pixel_snapping.apply(&mut matrix);
+        let transform_attribute = format!(" transform=\"{}\"", matrix_attribute(&matrix));
+
+        let bitmap = as_bitmap(&bitmap).read();
+        let element = if self.drawing_mask() {
+            // Clip paths can only contain shapes, so use the bounds of the bitmap instead.
+            format!(
+                "<rect width=\"{}\" height=\"{}\"{transform_attribute}/>",
+                bitmap.width, bitmap.height
+            )
+        } else {
+            image_element(
+                &bitmap,
+                &transform.color_transform,
+                smoothing,
+                &transform_attribute,
+            )
+        };
+        self.push_element(element);
+    }
+
+    fn render_stage3d(&mut self, bitmap: BitmapHandle, transform: Transform) {
+        self.render_bitmap(bitmap, transform, false, PixelSnapping::Never);
+    }
+
+    fn render_shape(&mut self, shape: ShapeHandle, transform: Transform) {
+        let shape = <dyn ShapeHandleImpl>::downcast_ref::<Shape>(&*shape.0)
+            .expect("ShapeHandle should be an SVG shape");
+        let transform_attribute = format!(" transform=\"{}\"", matrix_attribute(&transform.matrix));
+        let drawing_mask = self.drawing_mask();
+
+        for path in &shape.paths {
+            let mut element = format!("<path d=\"{}\"{transform_attribute}", path.data);
+            match &path.kind {
+                PathKind::Fill { paint, fill_rule } => {
+                    let rule = match fill_rule {
+                        FillRule::EvenOdd => "evenodd",
+                        FillRule::NonZero => "nonzero",
+                    };
+                    if drawing_mask {
+                        let _ = write!(element, " clip-rule=\"{rule}\"");
+                    } else {
+                        let paint = self.paint("fill", paint, &transform.color_transform);
+                        let _ = write!(element, "{paint} fill-rule=\"{rule}\"");
+                    }
+                }
+                PathKind::Stroke {
+                    paint,
+                    stroke,
+                    outline,
+                } => {
+                    if drawing_mask {
+                        // Clip paths ignore strokes, so fill the area they cover instead.
+                        if outline.is_empty() {
+                            continue;
+                        }
+                        element = format!("<path d=\"{outline}\"{transform_attribute}/>");
+                        self.push_element(element);
+                        continue;
+                    }
+                    let paint = self.paint("stroke", paint, &transform.color_transform);
+                    let cap = match stroke.cap {
+                        LineCapStyle::Round => "round",
+                        LineCapStyle::None => "butt",
+                        LineCapStyle::Square => "square",
+                    };
+                    let _ = write!(element, " fill=\"none\"{paint} stroke-linecap=\"{cap}\"");
+                    match stroke.join {
+                        LineJoinStyle::Round => element.push_str(" stroke-linejoin=\"round\""),
+                        LineJoinStyle::Bevel => element.push_str(" stroke-linejoin=\"bevel\""),
+                        LineJoinStyle::Miter(limit) => {
+                            let _ = write!(
+                                element,
+                                " stroke-linejoin=\"miter\" stroke-miterlimit=\"{}\"",
+                                limit.to_f32().max(1.0)
+                            );
+                        }
+                    }
+                    // Hairlines are always one pixel wide, and unscaled strokes keep their width
+                    // no matter how the shape is transformed.
+                    if stroke.width == 0.0 || !stroke.is_scaled {
+                        let _ = write!(
+                            element,
+                            " stroke-width=\"{}\" vector-effect=\"non-scaling-stroke\"",
+                            stroke.width.max(1.0)
+                        );
+                    } else {
+                        let _ = write!(element, " stroke-width=\"{}\"", stroke.width);
+                    }
+                }
+            }
+            element.push_str("/>");
+            self.push_element(element);
+        }
+    }
+
+    fn draw_rect(&mut self, color: Color, matrix: Matrix) {
+        let fill = if self.drawing_mask() {
+            String::new()
+        } else {
+            paint_color("fill", color)
+        };
+        self.push_element(format!(
+            "<rect width=\"1\" height=\"1\" transform=\"{}\"{fill}/>",
+            matrix_attribute(&matrix)
+        ));
+    }
+
+    fn draw_line(&mut self, color: Color, matrix: Matrix) {
+        emulate_line(self, color, matrix);
+    }
+
+    fn draw_line_rect(&mut self, color: Color, matrix: Matrix) {
+        emulate_line_rect(self, color, matrix);
+    }
+
+    fn push_mask(&mut self) {
+        self.layers.push(Layer::new(LayerKind::Mask));
+    }
+
+    fn activate_mask(&mut self) {
+        if matches!(self.layer().kind, LayerKind::Mask) {
+            self.pop_layer();
+        }
+    }
+
+    fn deactivate_mask(&mut self) {
+        if matches!(self.layer().kind, LayerKind::Masked(_)) {
+            self.pop_layer();
+            self.layers.push(Layer::new(LayerKind::ClearMask));
+        }
+    }
+
+    fn pop_mask(&mut self) {
+        if matches!(self.layer().kind, LayerKind::ClearMask) {
+            self.pop_layer();
+        }
+    }
+
+    fn blend(&mut self, commands: CommandList, blend_mode: RenderBlendMode) {
+        let mode = match blend_mode {
+            RenderBlendMode::Builtin(blend_mode) => css_blend_mode(blend_mode),
+            RenderBlendMode::Shader(_) => {
+                tracing::warn!("Shader blend modes are not supported in SVG");
+                None
+            }
+        };
+        self.layers.push(Layer::new(LayerKind::Blend(mode)));
+        commands.execute(self);
+        self.pop_layer();
+    }
+}
+
+/// Returns the CSS `mix-blend-mode` for a blend mode.
+///
+/// Blend modes without an equivalent are drawn normally.
+fn css_blend_mode(blend_mode: BlendMode) -> Option<&'static str> {
+    match blend_mode {
+        BlendMode::Multiply => Some("multiply"),
+        BlendMode::Screen => Some("screen"),
+        BlendMode::Lighten => Some("lighten"),
+        BlendMode::Darken => Some("darken"),
+        BlendMode::Difference => Some("difference"),
+        BlendMode::Overlay => Some("overlay"),
+        BlendMode::HardLight => Some("hard-light"),
+        BlendMode::Normal | BlendMode::Layer => None,
+        BlendMode::Add
+        | BlendMode::Subtract
+        | BlendMode::Invert
+        | BlendMode::Alpha
+        | BlendMode::Erase => {
+            tracing::warn!("Blend mode {blend_mode:?} is not supported in SVG");
+            None
+        }
+    }
+}
+
+fn hex_color(color: Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
+
+fn paint_color(name: &str, color: Color) -> String {
+    if color.a == 255 {
+        format!(" {name}=\"{}\"", hex_color(color))
+    } else {
+        format!(
+            " {name}=\"{}\" {name}-opacity=\"{}\"",
+            hex_color(color),
+            f32::from(color.a) / 255.0
+        )
+    }
+}
+
+/// Formats a matrix as an SVG transform, in pixels.
+fn matrix_attribute(matrix: &Matrix) -> String {
+    format!(
+        "matrix({} {} {} {} {} {})",
+        matrix.a,
+        matrix.b,
+        matrix.c,
+        matrix.d,
+        matrix.tx.to_pixels(),
+        matrix.ty.to_pixels()
+    )
+}
+
+/// Returns an `<image>` element that embeds a bitmap as a PNG.
+fn image_element(
+    bitmap: &BitmapData,
+    color_transform: &ColorTransform,
+    smoothing: bool,
+    attributes: &str,
+) -> String {
+    let rendering = if smoothing {
+        ""
+    } else {
+        " style=\"image-rendering:pixelated\""
+    };
+    format!(
+        "<image width=\"{}\" height=\"{}\" href=\"data:image/png;base64,{}\"{rendering}{attributes}/>",
+        bitmap.width,
+        bitmap.height,
+        encode_png(bitmap, color_transform)
+    )
+}
+
+/// Encodes a bitmap as a base64 PNG, with straight alpha.
+fn encode_png(bitmap: &BitmapData, color_transform: &ColorTransform) -> String {
+    let mut pixels = bitmap.data.clone();
+    ruffle_render::utils::unmultiply_alpha_rgba(&mut pixels);
+    if *color_transform != ColorTransform::IDENTITY {
+        for pixel in pixels.chunks_exact_mut(4) {
+            let color = Color {
+                r: pixel[0],
+                g: pixel[1],
+                b: pixel[2],
+                a: pixel[3],
+            };
+            let color = color_transform * color;
+            pixel.copy_from_slice(&[color.r, color.g, color.b, color.a]);
+        }
+    }
+
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, bitmap.width, bitmap.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let result = encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&pixels));
+    if let Err(e) = result {
+        tracing::error!("Couldn't encode bitmap as PNG: {e}");
+    }
+    base64::engine::general_purpose::STANDARD.encode(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::{Path, Stroke};
+    use std::sync::Arc;
+    use swf::Twips;
+
+    #[test]
+    fn matrix_translation_is_in_pixels() {
+        let matrix = Matrix::translate(Twips::new(30), Twips::new(-40));
+        assert_eq!(matrix_attribute(&matrix), "matrix(1 0 0 1 1.5 -2)");
+    }
+
+    #[test]
+    fn masked_content_is_clipped() {
+        let mut writer = SvgWriter::new(10, 10);
+        writer.push_mask();
+        writer.draw_rect(Color::WHITE, Matrix::scale(5.0, 5.0));
+        writer.activate_mask();
+        writer.draw_rect(Color::RED, Matrix::scale(10.0, 10.0));
+        writer.deactivate_mask();
+        writer.draw_rect(Color::WHITE, Matrix::scale(5.0, 5.0));
+        writer.pop_mask();
+        let svg = writer.draw(Color::TRANSPARENT, CommandList::new());
+        assert_eq!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"10\" height=\"10\" viewBox=\"0 0 10 10\">\
+            <defs><clipPath id=\"clip1\"><rect width=\"1\" height=\"1\" transform=\"matrix(5 0 0 5 0 0)\"/></clipPath></defs>\
+            <g clip-path=\"url(#clip1)\"><rect width=\"1\" height=\"1\" transform=\"matrix(10 0 0 10 0 0)\" fill=\"#ff0000\"/></g>\
+            </svg>\n"
+        );
+    }
+
+    #[test]
+    fn clip_paths_contain_only_shapes() {
+        let mut writer = SvgWriter::new(10, 10);
+        writer.push_mask();
+        writer.push_mask();
+        writer.draw_rect(Color::WHITE, Matrix::scale(2.0, 2.0));
+        writer.activate_mask();
+        writer.draw_rect(Color::WHITE, Matrix::scale(4.0, 4.0));
+        let mut blended = CommandList::new();
+        blended.draw_rect(Color::WHITE, Matrix::scale(6.0, 6.0));
+        writer.blend(blended, RenderBlendMode::Builtin(BlendMode::Multiply));
+        writer.deactivate_mask();
+        writer.draw_rect(Color::WHITE, Matrix::scale(2.0, 2.0));
+        writer.pop_mask();
+        writer.activate_mask();
+        writer.draw_rect(Color::RED, Matrix::scale(10.0, 10.0));
+        writer.deactivate_mask();
+        writer.pop_mask();
+        let svg = writer.draw(Color::TRANSPARENT, CommandList::new());
+        assert_eq!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"10\" height=\"10\" viewBox=\"0 0 10 10\">\
+            <defs><clipPath id=\"clip1\"><rect width=\"1\" height=\"1\" transform=\"matrix(2 0 0 2 0 0)\"/></clipPath>\
+            <clipPath id=\"clip2\"><rect width=\"1\" height=\"1\" transform=\"matrix(4 0 0 4 0 0)\" clip-path=\"url(#clip1)\"/>\
+            <rect width=\"1\" height=\"1\" transform=\"matrix(6 0 0 6 0 0)\" clip-path=\"url(#clip1)\"/></clipPath></defs>\
+            <g clip-path=\"url(#clip2)\"><rect width=\"1\" height=\"1\" transform=\"matrix(10 0 0 10 0 0)\" fill=\"#ff0000\"/></g>\
+            </svg>\n"
+        );
+    }
+
+    #[test]
+    fn masks_include_strokes() {
+        let shape = Shape {
+            paths: vec![Path {
+                data: "M0 0 L10 0".to_string(),
+                kind: PathKind::Stroke {
+                    paint: Paint::Color(Color::WHITE),
+                    stroke: Stroke {
+                        width: 2.0,
+                        cap: LineCapStyle::None,
+                        join: LineJoinStyle::Round,
+                        is_scaled: true,
+                    },
+                    outline: "M0 -1 L10 -1 L10 1 Z M0 -1 L10 1 L0 1 Z".to_string(),
+                },
+            }],
+        };
+        let mut writer = SvgWriter::new(10, 10);
+        writer.push_mask();
+        #[allow(clippy::arc_with_non_send_sync)]
+        let shape = ShapeHandle(Arc::new(shape));
+        writer.render_shape(shape, Transform::default());
+        writer.activate_mask();
+        writer.draw_rect(Color::RED, Matrix::scale(10.0, 10.0));
+        writer.deactivate_mask();
+        writer.pop_mask();
+        let svg = writer.draw(Color::TRANSPARENT, CommandList::new());
+        assert!(svg.contains(
+            "<clipPath id=\"clip1\"><path d=\"M0 -1 L10 -1 L10 1 Z M0 -1 L10 1 L0 1 Z\" transform=\"matrix(1 0 0 1 0 0)\"/></clipPath>"
+        ));
+    }
+}
@@ -8,12 +8,16 @@ use ruffle_render::backend::{
     RenderBackend, ShapeHandle, ShapeHandleImpl, ViewportDimensions,
 };
 use ruffle_render::bitmap::{
-    Bitmap, BitmapFormat, BitmapHandle, BitmapHandleImpl, BitmapSource, PixelRegion, PixelSnapping,
-    RgbaBufRead, SyncHandle,
+    Bitmap, BitmapFormat, BitmapHandle, BitmapHandleImpl, BitmapSource, CpuSyncHandle, PixelRegion,
+    PixelSnapping, RgbaBufRead, SyncHandle,
 };
 use ruffle_render::commands::{CommandHandler, CommandList, RenderBlendMode};
 use ruffle_render::error::Error as BitmapError;
 use ruffle_render::matrix::Matrix;
+use ruffle_render::pixel_bender::interpreter::{self, CpuShaderBitmaps, PixelBenderImage};
+use ruffle_render::pixel_bender::{
+    PixelBenderShader, PixelBenderShaderArgument, PixelBenderShaderHandle,
+};
 use ruffle_render::quality::StageQuality;
 use ruffle_render::shape_utils::{DistilledShape, GradientType};
 use ruffle_render::tessellator::{
@@ -1197,30 +1201,27 @@ impl RenderBackend for WebGlRenderBackend {
 
     fn compile_pixelbender_shader(
         &mut self,
-        _shader: ruffle_render::pixel_bender::PixelBenderShader,
-    ) -> Result<ruffle_render::pixel_bender::PixelBenderShaderHandle, BitmapError> {
-        Err(BitmapError::Unimplemented(
-            "compile_pixelbender_shader".into(),
-        ))
+        shader: PixelBenderShader,
+    ) -> Result<PixelBenderShaderHandle, BitmapError> {
+        // Shaders are run on the CPU, so there's nothing to compile.
+        Ok(PixelBenderShaderHandle(Arc::new(shader)))
     }
 
     fn resolve_sync_handle(
         &mut self,
-        _handle: Box<dyn SyncHandle>,
-        _with_rgba: RgbaBufRead,
-    ) -> Result<(), ruffle_render::error::Error> {
-        Err(ruffle_render::error::Error::Unimplemented(
-            "Sync handle resolution".into(),
-        ))
+        handle: Box<dyn SyncHandle>,
+        with_rgba: RgbaBufRead,
+    ) -> Result<(), BitmapError> {
+        CpuSyncHandle::resolve(handle, with_rgba)
     }
 
     fn run_pixelbender_shader(
         &mut self,
-        _handle: ruffle_render::pixel_bender::PixelBenderShaderHandle,
-        _arguments: &[ruffle_render::pixel_bender::PixelBenderShaderArgument],
-        _target: &PixelBenderTarget,
+        handle: PixelBenderShaderHandle,
+        arguments: &[PixelBenderShaderArgument],
+        target: &PixelBenderTarget,
     ) -> Result<PixelBenderOutput, BitmapError> {
-        Err(BitmapError::Unimplemented("run_pixelbender_shader".into()))
+        interpreter::run_shader_job(self, handle.0.parsed_shader(), arguments, target)
     }
 
     fn create_empty_texture(
@@ -1253,6 +1254,60 @@ impl RenderBackend for WebGlRenderBackend {
     }
 }
 
+impl CpuShaderBitmaps for WebGlRenderBackend {
+    fn bitmap_size(&self, handle: &BitmapHandle) -> (u32, u32) {
+        let entry = as_registry_data(handle);
+        (entry.width, entry.height)
+    }
+
+    fn read_bitmap(&mut self, handle: &BitmapHandle) -> Result<PixelBenderImage, BitmapError> {
+        let entry = as_registry_data(handle);
+        let mut pixels = vec![0; entry.width as usize * entry.height as usize * 4];
+
+        // Textures can only be read back through a framebuffer.
+        let framebuffer = self
+            .gl
+            .create_framebuffer()
+            .ok_or_else(|| BitmapError::JavascriptError("Unable to create framebuffer".into()))?;
+        self.gl
+            .bind_framebuffer(Gl::FRAMEBUFFER, Some(&framebuffer));
+        self.gl.framebuffer_texture_2d(
+            Gl::FRAMEBUFFER,
+            Gl::COLOR_ATTACHMENT0,
+            Gl::TEXTURE_2D,
+            Some(&entry.texture),
+            0,
+        );
+        let result = self
+            .gl
+            .read_pixels_with_opt_u8_array(
+                0,
+                0,
+                entry.width as i32,
+                entry.height as i32,
+                Gl::RGBA,
+                Gl::UNSIGNED_BYTE,
+                Some(&mut pixels),
+            )
+            .into_js_result();
+        self.gl.bind_framebuffer(Gl::FRAMEBUFFER, None);
+        self.gl.delete_framebuffer(Some(&framebuffer));
+        result.map_err(|e| BitmapError::JavascriptError(e.into()))?;
+
+        Ok(PixelBenderImage::from_rgba8(
+            entry.width,
+            entry.height,
+            &pixels,
+        ))
+    }
+
+    fn write_bitmap(&mut self, handle: &BitmapHandle, rgba: Vec<u8>) -> Result<(), BitmapError> {
+        let (width, height) = self.bitmap_size(handle);
+        let bitmap = Bitmap::new(width, height, BitmapFormat::Rgba, rgba);
+        self.update_texture(handle, bitmap, PixelRegion::for_whole_size(width, height))
+    }
+}
+
 impl CommandHandler for WebGlRenderBackend {
     fn render_bitmap(
         &mut self,
@@ -0,0 +1,64 @@
+// NOTE: The `shader_filter_common.wgsl` source is prepended to this before compilation.
+
+struct Filter {
+    /// The matrix, row by row, packed four values at a time.
+    weights: array<vec4<f32>, 64>,
+    /// The unmultiplied color used for pixels outside of the source, when not clamping.
+    default_color: vec4<f32>,
+    /// The region of the texture that is being filtered, with an exclusive `source_max`.
+    source_min: vec2<i32>,
+    source_max: vec2<i32>,
+    /// The number of columns and rows of the matrix.
+    matrix_size: vec2<u32>,
+    divisor: f32,
+    bias: f32,
+    clamp_to_edge: u32,
+    preserve_alpha: u32,
+}
+
+@group(0) @binding(0) var texture: texture_2d<f32>;
+@group(0) @binding(1) var<uniform> filter_args: Filter;
+
+@vertex
+fn main_vertex(in: filter__VertexInput) -> filter__VertexOutput {
+    return filter__main_vertex(in);
+}
+
+/// Returns the unmultiplied color of the source at the given texel.
+fn load(coord: vec2<i32>) -> vec4<f32> {
+    var pos = coord;
+    if (any(pos < filter_args.source_min) || any(pos >= filter_args.source_max)) {
+        if (filter_args.clamp_to_edge == 0u) {
+            return filter_args.default_color;
+        }
+        pos = clamp(pos, filter_args.source_min, filter_args.source_max - vec2<i32>(1, 1));
+    }
+    let color = textureLoad(texture, pos, 0);
+    if (color.a == 0.0) {
+        return vec4<f32>(0.0);
+    }
+    return vec4<f32>(color.rgb / color.a, color.a);
+}
+
+@fragment
+fn main_fragment(in: filter__VertexOutput) -> @location(0) vec4<f32> {
+    let pos = filter_args.source_min + vec2<i32>(floor(in.position.xy));
+    let cols = filter_args.matrix_size.x;
+    let rows = filter_args.matrix_size.y;
+    let center = vec2<i32>(filter_args.matrix_size / 2u);
+
+    var sum = vec4<f32>(0.0);
+    for (var y = 0u; y < rows; y++) {
+        for (var x = 0u; x < cols; x++) {
+            let i = y * cols + x;
+            let weight = filter_args.weights[i / 4u][i % 4u];
+            sum += weight * load(pos + vec2<i32>(i32(x), i32(y)) - center);
+        }
+    }
+
+    var color = saturate(sum / filter_args.divisor + vec4<f32>(filter_args.bias / 255.0));
+    if (filter_args.preserve_alpha > 0u) {
+        color.a = textureLoad(texture, pos, 0).a;
+    }
+    return vec4<f32>(color.rgb * color.a, color.a);
+}
@@ -4,7 +4,7 @@ use crate::context3d::WgpuContext3D;
 use crate::dynamic_transforms::DynamicTransforms;
 use crate::filters::FilterSource;
 use crate::mesh::{CommonGradient, Mesh, PendingDraw};
-use crate::pixel_bender::{run_pixelbender_shader_impl, ShaderMode};
+use crate::pixel_bender::run_pixelbender_shader_impl;
 use crate::surface::{LayerRef, Surface};
 use crate::target::{MaybeOwnedBuffer, TextureTarget};
 use crate::target::{RenderTargetFrame, TextureBufferInfo};
@@ -26,7 +26,7 @@ use ruffle_render::error::Error as BitmapError;
 use ruffle_render::filters::Filter;
 use ruffle_render::pixel_bender::{
     PixelBenderParam, PixelBenderParamQualifier, PixelBenderShader, PixelBenderShaderArgument,
-    PixelBenderShaderHandle,
+    PixelBenderShaderHandle, ShaderMode,
 };
 use ruffle_render::quality::StageQuality;
 use ruffle_render::shape_utils::DistilledShape;
@@ -772,6 +772,9 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
                 | Filter::ColorMatrixFilter(_)
                 | Filter::ShaderFilter(_)
                 | Filter::BevelFilter(_)
+                | Filter::GradientGlowFilter(_)
+                | Filter::GradientBevelFilter(_)
+                | Filter::ConvolutionFilter(_)
                 | Filter::DisplacementMapFilter(_)
         )
     }
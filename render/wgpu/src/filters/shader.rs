@@ -1,14 +1,11 @@
 use ruffle_render::{
     filters::ShaderFilter as ShaderFilterArgs,
-    pixel_bender::{ImageInputTexture, PixelBenderShaderArgument},
+    pixel_bender::{ImageInputTexture, PixelBenderShaderArgument, ShaderMode},
 };
 
 use crate::{
-    backend::RenderTargetMode,
-    buffer_pool::TexturePool,
-    descriptors::Descriptors,
-    pixel_bender::{run_pixelbender_shader_impl, ShaderMode},
-    surface::target::CommandTarget,
+    backend::RenderTargetMode, buffer_pool::TexturePool, descriptors::Descriptors,
+    pixel_bender::run_pixelbender_shader_impl, surface::target::CommandTarget,
 };
 
 use super::FilterSource;
@@ -6,7 +6,7 @@ use std::{borrow::Cow, cell::Cell, sync::Arc};
 use indexmap::IndexMap;
 use ruffle_render::error::Error as BitmapError;
 use ruffle_render::pixel_bender::{
-    ImageInputTexture, PixelBenderShaderHandle, PixelBenderShaderImpl, PixelBenderType,
+    ImageInputTexture, PixelBenderShaderHandle, PixelBenderShaderImpl, PixelBenderType, ShaderMode,
     OUT_COORD_NAME,
 };
 use ruffle_render::{
@@ -374,11 +374,6 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
     }
 }
 
-pub enum ShaderMode {
-    ShaderJob,
-    Filter,
-}
-
 #[allow(clippy::too_many_arguments)]
 pub(super) fn run_pixelbender_shader_impl(
     descriptors: &Descriptors,
@@ -447,12 +442,7 @@ pub(super) fn run_pixelbender_shader_impl(
 
     // This would ideally be a single f32, but web requires at least 16 bytes
     zeroed_out_of_range_mode_slice.copy_from_slice(bytemuck::cast_slice(&[match mode {
-        // When a Shader is run via a ShaderJob, out-of-range texture sample coordinates
-        // seem to be clamped to the edge of the texture (despite what the docs describe)
         ShaderMode::ShaderJob => [0.0f32, 0.0f32, 0.0f32, 0.0f32],
-        // When a Shader is run through a ShaderFilter, out-of-range texture sample coordinates
-        // return transparent black (0.0, 0.0, 0.0, 0.0). This is easiest to observe with
-        // BitmapData.applyFilter when the BitampData destination is larger than the source.
         ShaderMode::Filter => [1.0f32, 1.0f32, 1.0f32, 1.0f32],
     }]));
     drop(zeroed_out_of_range_mode_slice);
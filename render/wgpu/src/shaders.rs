@@ -20,6 +20,8 @@ pub struct Shaders {
     pub blur_filter: wgpu::ShaderModule,
     pub glow_filter: wgpu::ShaderModule,
     pub bevel_filter: wgpu::ShaderModule,
+    pub gradient_filter: wgpu::ShaderModule,
+    pub convolution_filter: wgpu::ShaderModule,
     pub displacement_map_filter: wgpu::ShaderModule,
 }
 
@@ -57,6 +59,16 @@ impl Shaders {
             "filter/bevel.wgsl",
             include_str!("../shaders/filter/bevel.wgsl"),
         );
+        let gradient_filter = make_filter_shader(
+            device,
+            "filter/gradient.wgsl",
+            include_str!("../shaders/filter/gradient.wgsl"),
+        );
+        let convolution_filter = make_filter_shader(
+            device,
+            "filter/convolution.wgsl",
+            include_str!("../shaders/filter/convolution.wgsl"),
+        );
         let displacement_map_filter = make_filter_shader(
             device,
             "filter/displacement_map.wgsl",
@@ -91,6 +103,8 @@ impl Shaders {
             blur_filter,
             glow_filter,
             bevel_filter,
+            gradient_filter,
+            convolution_filter,
             displacement_map_filter,
         }
     }
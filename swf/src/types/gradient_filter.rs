@@ -1,4 +1,4 @@
-use crate::{BlurFilter, BlurFilterFlags, Fixed16, Fixed8, GradientRecord};
+use crate::{BlurFilter, BlurFilterFlags, Fixed16, Fixed8, GradientRecord, Rectangle, Twips};
 use bitflags::bitflags;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -28,6 +28,11 @@ impl GradientFilter {
         self.flags.contains(GradientFilterFlags::ON_TOP)
     }
 
+    #[inline]
+    pub fn composite_source(&self) -> bool {
+        self.flags.contains(GradientFilterFlags::COMPOSITE_SOURCE)
+    }
+
     #[inline]
     pub fn num_passes(&self) -> u8 {
         (self.flags & GradientFilterFlags::PASSES).bits()
@@ -46,6 +51,21 @@ impl GradientFilter {
             flags: BlurFilterFlags::from_passes(self.num_passes()),
         }
     }
+
+    pub fn calculate_dest_rect(&self, source_rect: Rectangle<Twips>) -> Rectangle<Twips> {
+        // Gradient bevels are offset in both directions, gradient glows only in one;
+        // growing both sides covers either.
+        let mut result = self.inner_blur_filter().calculate_dest_rect(source_rect);
+        let distance = self.distance.to_f64();
+        let angle = self.angle.to_f64();
+        let x = Twips::from_pixels((angle.cos() * distance).abs());
+        let y = Twips::from_pixels((angle.sin() * distance).abs());
+        result.x_min -= x;
+        result.x_max += x;
+        result.y_min -= y;
+        result.y_max += y;
+        result
+    }
 }
 
 bitflags! {
@@ -5,5 +5,5 @@ mod ui;
 
 pub use audio::TestAudioBackend;
 pub use log::TestLogBackend;
-pub use navigator::TestNavigatorBackend;
+pub use navigator::{FetchTicker, TestNavigatorBackend};
 pub use ui::TestUiBackend;
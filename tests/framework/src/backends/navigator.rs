@@ -7,20 +7,31 @@ use ruffle_core::backend::navigator::{
     async_return, create_fetch_error, ErrorResponse, NavigationMethod, NavigatorBackend,
     NullExecutor, NullSpawner, OwnedFuture, Request, SuccessResponse,
 };
+use ruffle_core::datagram_socket::{Datagram, DatagramSocketAction, DatagramSocketHandle};
 use ruffle_core::indexmap::IndexMap;
 use ruffle_core::loader::Error;
 use ruffle_core::socket::{ConnectionState, SocketAction, SocketHandle};
 use ruffle_core::swf::Encoding;
 use ruffle_socket_format::SocketEvent;
 use std::borrow::Cow;
+use std::net::{IpAddr, SocketAddr};
 use std::time::Duration;
 use url::{ParseError, Url};
 use vfs::VfsPath;
 
+/// Splits fetched bodies into chunks of `size` bytes, delivering at most one chunk per tick.
+#[derive(Clone)]
+struct FetchChunks {
+    size: usize,
+    ticks: Receiver<()>,
+}
+
 struct TestResponse {
     url: String,
     body: Vec<u8>,
     chunk_gotten: bool,
+    chunks: Option<FetchChunks>,
+    position: usize,
     status: u16,
     redirected: bool,
 }
@@ -47,6 +58,22 @@ impl SuccessResponse for TestResponse {
     }
 
     fn next_chunk(&mut self) -> OwnedFuture<Option<Vec<u8>>, Error> {
+        if let Some(chunks) = &self.chunks {
+            let start = self.position;
+            let end = (start + chunks.size).min(self.body.len());
+            self.position = end;
+            let chunk = self.body[start..end].to_vec();
+            let ticks = chunks.ticks.clone();
+            return Box::pin(async move {
+                if chunk.is_empty() {
+                    return Ok(None);
+                }
+                // Wait for the next tick, so that every chunk arrives in a different frame.
+                let _ = ticks.recv().await;
+                Ok(Some(chunk))
+            });
+        }
+
         if !self.chunk_gotten {
             self.chunk_gotten = true;
             let body = self.body.clone();
@@ -72,11 +99,18 @@ impl SuccessResponse for TestResponse {
 ///
 /// These are formatted as query params, rather than domains/whole URLs, so that real/real-invalid
 /// URLs can be used in Flash Player when writing tests
+///
+/// Datagram sockets receive every datagram they send back, as if its destination replied with it.
+///
+/// With a `fetch_chunk_size`, fetched files are loaded in chunks of that many bytes,
+/// one chunk per call to `tick`.
 pub struct TestNavigatorBackend {
     spawner: NullSpawner,
     relative_base_path: VfsPath,
     socket_events: Option<Vec<SocketEvent>>,
     log: Option<TestLogBackend>,
+    fetch_chunks: Option<FetchChunks>,
+    fetch_ticks: Sender<()>,
 }
 
 impl TestNavigatorBackend {
@@ -85,14 +119,37 @@ impl TestNavigatorBackend {
         executor: &NullExecutor,
         socket_events: Option<Vec<SocketEvent>>,
         log: Option<TestLogBackend>,
+        fetch_chunk_size: Option<usize>,
     ) -> Result<Self, std::io::Error> {
+        // A tick that isn't waited for only allows the next chunk, so ticks don't pile up.
+        let (fetch_ticks, ticks) = async_channel::bounded(1);
         Ok(Self {
             spawner: executor.spawner(),
             relative_base_path: path,
             socket_events,
             log,
+            fetch_chunks: fetch_chunk_size.map(|size| FetchChunks {
+                size: size.max(1),
+                ticks,
+            }),
+            fetch_ticks,
         })
     }
+
+    /// Returns a handle that allows the next chunk of every chunked fetch to load.
+    pub fn ticker(&self) -> FetchTicker {
+        FetchTicker(self.fetch_ticks.clone())
+    }
+}
+
+/// Allows chunked fetches of a `TestNavigatorBackend` to make progress.
+pub struct FetchTicker(Sender<()>);
+
+impl FetchTicker {
+    /// Allows the next chunk to load. Call this once per tick.
+    pub fn tick(&self) {
+        let _ = self.0.try_send(());
+    }
 }
 
 impl NavigatorBackend for TestNavigatorBackend {
@@ -123,6 +180,8 @@ impl NavigatorBackend for TestNavigatorBackend {
                     url: request.url().to_string(),
                     body: b"Hello, World!".to_vec(),
                     chunk_gotten: false,
+                    chunks: None,
+                    position: 0,
                     status: 200,
                     redirected: false,
                 });
@@ -181,6 +240,7 @@ impl NavigatorBackend for TestNavigatorBackend {
         };
 
         let base_path = self.relative_base_path.clone();
+        let chunks = self.fetch_chunks.clone();
 
         Box::pin(async move {
             let path = if url.scheme() == "file" {
@@ -237,6 +297,8 @@ impl NavigatorBackend for TestNavigatorBackend {
                 url: url.to_string(),
                 body,
                 chunk_gotten: false,
+                chunks,
+                position: 0,
                 status: 0,
                 redirected: false,
             });
@@ -322,4 +384,45 @@ impl NavigatorBackend for TestNavigatorBackend {
             }));
         }
     }
+
+    fn bind_datagram_socket(
+        &mut self,
+        host: String,
+        port: u16,
+        handle: DatagramSocketHandle,
+        receiver: Receiver<Datagram>,
+        sender: Sender<DatagramSocketAction>,
+    ) -> std::io::Result<SocketAddr> {
+        if let Some(log) = &self.log {
+            log.avm_trace("Navigator::bind_datagram_socket");
+            log.avm_trace(&format!("    Host: {}; Port: {}", host, port));
+        }
+
+        let ip: IpAddr = host
+            .parse()
+            .map_err(|_| std::io::Error::from(std::io::ErrorKind::AddrNotAvailable))?;
+        // Sockets bound to any port get the first dynamic port.
+        let port = if port == 0 { 49152 } else { port };
+
+        // Every datagram is echoed back, as if its destination replied with it.
+        let log = self.log.clone();
+        self.spawn_future(Box::pin(async move {
+            while let Ok(datagram) = receiver.recv().await {
+                if let Some(log) = &log {
+                    log.avm_trace("Navigator::send_datagram");
+                    log.avm_trace(&format!(
+                        "    Host: {}; Port: {}; Data: {:?}",
+                        datagram.host, datagram.port, datagram.data
+                    ));
+                }
+                sender
+                    .try_send(DatagramSocketAction::Data(handle, datagram))
+                    .expect("working channel send");
+            }
+
+            Ok(())
+        }));
+
+        Ok(SocketAddr::new(ip, port))
+    }
 }
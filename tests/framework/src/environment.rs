@@ -31,6 +31,13 @@ pub trait Environment {
     ) -> Option<(Box<dyn RenderInterface>, Box<dyn RenderBackend>)> {
         None
     }
+
+    /// Whether renderers are created with the software renderer instead of a GPU.
+    ///
+    /// Only tests whose image comparisons all give tolerances for it are run then.
+    fn uses_software_renderer(&self) -> bool {
+        false
+    }
 }
 
 pub trait RenderInterface {
@@ -43,4 +50,66 @@ pub trait RenderInterface {
     ///
     /// The provided backend is guaranteed to be the same one paired with this interface.
     fn capture(&self, renderer: &mut Box<dyn RenderBackend>) -> image::RgbaImage;
+
+    /// Lists the features the given render backend has been asked to draw but can't,
+    /// such as filters on a renderer that doesn't support them.
+    ///
+    /// Image comparisons fail when any were used, as the capture won't be accurate.
+    fn unsupported_features(&self, _renderer: &dyn RenderBackend) -> Vec<&'static str> {
+        vec![]
+    }
+
+    /// Whether this is the software renderer, which compares images with the tolerances
+    /// given for it and never saves its captures as expected images.
+    fn is_software_renderer(&self) -> bool {
+        false
+    }
+}
+
+/// A [RenderInterface] for the software renderer, which rasterizes on the CPU
+/// and so works on machines without a GPU.
+#[cfg(feature = "ruffle_render_software")]
+pub struct SoftwareRenderInterface;
+
+#[cfg(feature = "ruffle_render_software")]
+impl SoftwareRenderInterface {
+    pub fn create_pair(
+        width: u32,
+        height: u32,
+    ) -> (Box<dyn RenderInterface>, Box<dyn RenderBackend>) {
+        (
+            Box::new(Self),
+            Box::new(ruffle_render_software::SoftwareRenderBackend::new(
+                width, height,
+            )),
+        )
+    }
+}
+
+#[cfg(feature = "ruffle_render_software")]
+impl RenderInterface for SoftwareRenderInterface {
+    fn name(&self) -> String {
+        "software".to_string()
+    }
+
+    fn capture(&self, renderer: &mut Box<dyn RenderBackend>) -> image::RgbaImage {
+        renderer
+            .downcast_ref::<ruffle_render_software::SoftwareRenderBackend>()
+            .expect("Renderer must be the software renderer")
+            .capture_frame()
+            .expect("Failed to capture image")
+    }
+
+    fn unsupported_features(&self, renderer: &dyn RenderBackend) -> Vec<&'static str> {
+        renderer
+            .downcast_ref::<ruffle_render_software::SoftwareRenderBackend>()
+            .expect("Renderer must be the software renderer")
+            .unsupported_features()
+            .into_iter()
+            .collect()
+    }
+
+    fn is_software_renderer(&self) -> bool {
+        true
+    }
 }
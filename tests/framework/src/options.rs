@@ -6,6 +6,8 @@ use anyhow::{anyhow, Result};
 use approx::relative_eq;
 use image::ImageFormat;
 use regex::Regex;
+use ruffle_core::backend::audio::ToneAudioInputBackend;
+use ruffle_core::backend::camera::TestPatternCameraBackend;
 use ruffle_core::tag_utils::SwfMovie;
 use ruffle_core::{PlayerBuilder, PlayerRuntime, ViewportDimensions};
 use ruffle_render::backend::RenderBackend;
@@ -29,6 +31,7 @@ pub struct TestOptions {
     pub approximations: Option<Approximations>,
     pub player_options: PlayerOptions,
     pub log_fetch: bool,
+    pub fetch_chunk_size: Option<usize>,
     pub required_features: RequiredFeatures,
     pub fonts: HashMap<String, FontOptions>,
 }
@@ -47,6 +50,7 @@ impl Default for TestOptions {
             approximations: None,
             player_options: PlayerOptions::default(),
             log_fetch: false,
+            fetch_chunk_size: None,
             required_features: RequiredFeatures::default(),
             fonts: Default::default(),
         }
@@ -54,6 +58,16 @@ impl Default for TestOptions {
 }
 
 impl TestOptions {
+    /// Whether this test has images to compare and every comparison gives tolerances
+    /// for the software renderer.
+    pub fn supports_software_renderer(&self) -> bool {
+        !self.image_comparisons.is_empty()
+            && self
+                .image_comparisons
+                .values()
+                .all(ImageComparison::supports_software_renderer)
+    }
+
     pub fn read(path: &VfsPath) -> Result<Self> {
         let result: Self = toml::from_str(&path.read_to_string()?)?;
         result.validate()?;
@@ -149,6 +163,9 @@ pub struct PlayerOptions {
     with_renderer: Option<RenderOptions>,
     with_audio: bool,
     with_video: bool,
+    with_microphone: bool,
+    with_camera: bool,
+    url_policy_files: bool,
     runtime: PlayerRuntime,
 }
 
@@ -172,7 +189,18 @@ impl PlayerOptions {
             player_builder = player_builder.with_audio(TestAudioBackend::default());
         }
 
-        player_builder = player_builder.with_player_runtime(self.runtime);
+        if self.with_microphone {
+            player_builder =
+                player_builder.with_audio_input(ToneAudioInputBackend::new(440.0, 0.5));
+        }
+
+        if self.with_camera {
+            player_builder = player_builder.with_camera(TestPatternCameraBackend::new());
+        }
+
+        player_builder = player_builder
+            .with_player_runtime(self.runtime)
+            .with_url_policy_files(self.url_policy_files);
 
         if self.with_video {
             #[cfg(feature = "ruffle_video_external")]
@@ -242,6 +270,16 @@ pub struct ImageComparison {
     tolerance: u8,
     max_outliers: usize,
     pub trigger: ImageTrigger,
+    software: Option<SoftwareImageComparison>,
+}
+
+/// The tolerances of an image comparison when rendering with the software renderer,
+/// whose anti-aliasing doesn't exactly match wgpu's.
+#[derive(Deserialize, Default, Clone, Copy, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct SoftwareImageComparison {
+    tolerance: u8,
+    max_outliers: usize,
 }
 
 fn calc_difference(lhs: u8, rhs: u8) -> u8 {
@@ -249,6 +287,21 @@ fn calc_difference(lhs: u8, rhs: u8) -> u8 {
 }
 
 impl ImageComparison {
+    /// Whether this comparison can be checked with the software renderer.
+    pub fn supports_software_renderer(&self) -> bool {
+        self.software.is_some()
+    }
+
+    /// This comparison with the tolerances given for the software renderer.
+    pub fn for_software_renderer(&self) -> Option<Self> {
+        self.software.map(|software| Self {
+            tolerance: software.tolerance,
+            max_outliers: software.max_outliers,
+            trigger: self.trigger,
+            software: self.software,
+        })
+    }
+
     pub fn test(
         &self,
         name: &str,
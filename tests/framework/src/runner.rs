@@ -1,4 +1,4 @@
-use crate::backends::{TestLogBackend, TestNavigatorBackend, TestUiBackend};
+use crate::backends::{FetchTicker, TestLogBackend, TestNavigatorBackend, TestUiBackend};
 use crate::environment::RenderInterface;
 use crate::fs_commands::{FsCommand, TestFsCommandProvider};
 use crate::image_trigger::ImageTrigger;
@@ -9,15 +9,11 @@ use anyhow::{anyhow, Error, Result};
 use image::ImageFormat;
 use pretty_assertions::Comparison;
 use ruffle_core::backend::navigator::NullExecutor;
-use ruffle_core::events::{KeyCode, TextControlCode as RuffleTextControlCode};
-use ruffle_core::events::{MouseButton as RuffleMouseButton, MouseWheelDelta};
+use ruffle_core::events::{KeyCode, MouseWheelDelta};
 use ruffle_core::limits::ExecutionLimit;
 use ruffle_core::tag_utils::SwfMovie;
 use ruffle_core::{Player, PlayerBuilder, PlayerEvent};
-use ruffle_input_format::{
-    AutomatedEvent, InputInjector, MouseButton as InputMouseButton,
-    TextControlCode as InputTextControlCode,
-};
+use ruffle_input_format::{AutomatedEvent, InputInjector};
 use ruffle_render::backend::{RenderBackend, ViewportDimensions};
 use ruffle_socket_format::SocketEvent;
 use std::collections::HashMap;
@@ -39,6 +35,7 @@ pub struct TestRunner {
     player: Arc<Mutex<Player>>,
     injector: InputInjector,
     executor: NullExecutor,
+    fetch_ticker: FetchTicker,
     frame_time: f64,
     frame_time_duration: Duration,
     log: TestLogBackend,
@@ -80,7 +77,9 @@ impl TestRunner {
             &executor,
             socket_events,
             test.options.log_fetch.then(|| log.clone()),
+            test.options.fetch_chunk_size,
         )?;
+        let fetch_ticker = navigator.ticker();
 
         let mut builder = PlayerBuilder::new()
             .with_log(log.clone())
@@ -125,6 +124,7 @@ impl TestRunner {
             injector,
             render_interface,
             executor,
+            fetch_ticker,
             frame_time,
             frame_time_duration,
             log,
@@ -166,6 +166,7 @@ impl TestRunner {
         }
         self.remaining_iterations -= 1;
         self.current_iteration += 1;
+        self.fetch_ticker.tick();
         self.executor.run();
     }
 
@@ -209,11 +210,7 @@ impl TestRunner {
                 } => PlayerEvent::MouseDown {
                     x: pos.0,
                     y: pos.1,
-                    button: match btn {
-                        InputMouseButton::Left => RuffleMouseButton::Left,
-                        InputMouseButton::Middle => RuffleMouseButton::Middle,
-                        InputMouseButton::Right => RuffleMouseButton::Right,
-                    },
+                    button: (*btn).into(),
                     // None here means that the core will compute index automatically,
                     // however we do not want that in tests.
                     index: Some(index.unwrap_or_default()),
@@ -222,11 +219,7 @@ impl TestRunner {
                 AutomatedEvent::MouseUp { pos, btn } => PlayerEvent::MouseUp {
                     x: pos.0,
                     y: pos.1,
-                    button: match btn {
-                        InputMouseButton::Left => RuffleMouseButton::Left,
-                        InputMouseButton::Middle => RuffleMouseButton::Middle,
-                        InputMouseButton::Right => RuffleMouseButton::Right,
-                    },
+                    button: (*btn).into(),
                 },
                 AutomatedEvent::MouseWheel { lines, pixels } => PlayerEvent::MouseWheel {
                     delta: match (lines, pixels) {
@@ -247,47 +240,34 @@ impl TestRunner {
                     codepoint: *codepoint,
                 },
                 AutomatedEvent::TextControl { code } => PlayerEvent::TextControl {
-                    code: match code {
-                        InputTextControlCode::MoveLeft => RuffleTextControlCode::MoveLeft,
-                        InputTextControlCode::MoveLeftWord => RuffleTextControlCode::MoveLeftWord,
-                        InputTextControlCode::MoveLeftLine => RuffleTextControlCode::MoveLeftLine,
-                        InputTextControlCode::MoveLeftDocument => {
-                            RuffleTextControlCode::MoveLeftDocument
-                        }
-                        InputTextControlCode::MoveRight => RuffleTextControlCode::MoveRight,
-                        InputTextControlCode::MoveRightWord => RuffleTextControlCode::MoveRightWord,
-                        InputTextControlCode::MoveRightLine => RuffleTextControlCode::MoveRightLine,
-                        InputTextControlCode::MoveRightDocument => {
-                            RuffleTextControlCode::MoveRightDocument
-                        }
-                        InputTextControlCode::SelectLeft => RuffleTextControlCode::SelectLeft,
-                        InputTextControlCode::SelectLeftWord => {
-                            RuffleTextControlCode::SelectLeftWord
-                        }
-                        InputTextControlCode::SelectLeftLine => {
-                            RuffleTextControlCode::SelectLeftLine
-                        }
-                        InputTextControlCode::SelectLeftDocument => {
-                            RuffleTextControlCode::SelectLeftDocument
-                        }
-                        InputTextControlCode::SelectRight => RuffleTextControlCode::SelectRight,
-                        InputTextControlCode::SelectRightWord => {
-                            RuffleTextControlCode::SelectRightWord
-                        }
-                        InputTextControlCode::SelectRightLine => {
-                            RuffleTextControlCode::SelectRightLine
-                        }
-                        InputTextControlCode::SelectRightDocument => {
-                            RuffleTextControlCode::SelectRightDocument
-                        }
-                        InputTextControlCode::SelectAll => RuffleTextControlCode::SelectAll,
-                        InputTextControlCode::Copy => RuffleTextControlCode::Copy,
-                        InputTextControlCode::Paste => RuffleTextControlCode::Paste,
-                        InputTextControlCode::Cut => RuffleTextControlCode::Cut,
-                        InputTextControlCode::Backspace => RuffleTextControlCode::Backspace,
-                        InputTextControlCode::Enter => RuffleTextControlCode::Enter,
-                        InputTextControlCode::Delete => RuffleTextControlCode::Delete,
-                    },
+                    code: (*code).into(),
+                },
+                AutomatedEvent::GamepadConnected { device } => {
+                    PlayerEvent::GamepadConnected { device: *device }
+                }
+                AutomatedEvent::GamepadDisconnected { device } => {
+                    PlayerEvent::GamepadDisconnected { device: *device }
+                }
+                AutomatedEvent::GamepadButtonDown { device, button } => {
+                    PlayerEvent::GamepadButtonDown {
+                        device: *device,
+                        button: (*button).into(),
+                    }
+                }
+                AutomatedEvent::GamepadButtonUp { device, button } => {
+                    PlayerEvent::GamepadButtonUp {
+                        device: *device,
+                        button: (*button).into(),
+                    }
+                }
+                AutomatedEvent::GamepadAxisMotion {
+                    device,
+                    axis,
+                    value,
+                } => PlayerEvent::GamepadAxisMotion {
+                    device: *device,
+                    axis: (*axis).into(),
+                    value: *value,
                 },
                 AutomatedEvent::FocusGained => PlayerEvent::FocusGained,
                 AutomatedEvent::FocusLost => PlayerEvent::FocusLost,
@@ -509,6 +489,24 @@ fn capture_and_compare_image(
 
         let actual_image = render_interface.capture(player_lock.renderer_mut());
 
+        let unsupported_features =
+            render_interface.unsupported_features(player_lock.renderer_mut().as_ref());
+        if !unsupported_features.is_empty() {
+            return Err(anyhow!(
+                "Can't check image '{name}', as the {} renderer doesn't support: {}",
+                render_interface.name(),
+                unsupported_features.join(", ")
+            ));
+        }
+
+        let image_comparison = if render_interface.is_software_renderer() {
+            image_comparison.for_software_renderer().ok_or_else(|| {
+                anyhow!("Image '{name}' has no tolerances for the software renderer")
+            })?
+        } else {
+            image_comparison
+        };
+
         let expected_image_path = base_path.join(format!("{name}.expected.png"))?;
         if expected_image_path.is_file()? {
             let expected_image = image::load_from_memory(&read_bytes(&expected_image_path)?)
@@ -527,6 +525,11 @@ fn capture_and_compare_image(
             return Err(anyhow!(
                 "No image to compare to, pretending this failed since we don't know if it worked."
             ));
+        } else if render_interface.is_software_renderer() {
+            // Expected images should always come from wgpu.
+            return Err(anyhow!(
+                "No image to compare to, and the software renderer can't be used to create one."
+            ));
         } else {
             // If we're expecting this to be wrong, don't save a likely wrong image
             write_image(&expected_image_path, &actual_image, ImageFormat::Png)?;
@@ -108,6 +108,12 @@ impl Test {
         if self.options.ignore {
             return false;
         }
+        if check_renderer
+            && environment.uses_software_renderer()
+            && !self.options.supports_software_renderer()
+        {
+            return false;
+        }
         self.options.required_features.can_run()
             && self
                 .options
@@ -0,0 +1,103 @@
+//! Conversions between the input format and the events of `ruffle_core`.
+
+use crate::{GamepadAxis, GamepadButton, MouseButton, TextControlCode};
+use ruffle_core::events;
+
+/// Implements `From` in both directions for an enum that has the same variants in both crates.
+macro_rules! impl_conversions {
+    ($name:ident { $($variant:ident),* $(,)? }) => {
+        impl From<$name> for events::$name {
+            fn from(value: $name) -> Self {
+                match value {
+                    $($name::$variant => Self::$variant,)*
+                }
+            }
+        }
+
+        impl From<events::$name> for $name {
+            fn from(value: events::$name) -> Self {
+                match value {
+                    $(events::$name::$variant => Self::$variant,)*
+                }
+            }
+        }
+    };
+}
+
+impl_conversions!(GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftTrigger,
+    LeftTrigger2,
+    RightTrigger,
+    RightTrigger2,
+    Select,
+    Start,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+});
+
+impl_conversions!(GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    LeftTrigger2,
+    RightTrigger2,
+});
+
+impl_conversions!(TextControlCode {
+    MoveLeft,
+    MoveLeftWord,
+    MoveLeftLine,
+    MoveLeftDocument,
+    MoveRight,
+    MoveRightWord,
+    MoveRightLine,
+    MoveRightDocument,
+    SelectLeft,
+    SelectLeftWord,
+    SelectLeftLine,
+    SelectLeftDocument,
+    SelectRight,
+    SelectRightWord,
+    SelectRightLine,
+    SelectRightDocument,
+    SelectAll,
+    Copy,
+    Paste,
+    Cut,
+    Backspace,
+    BackspaceWord,
+    Enter,
+    Delete,
+    DeleteWord,
+});
+
+impl From<MouseButton> for events::MouseButton {
+    fn from(button: MouseButton) -> Self {
+        match button {
+            MouseButton::Left => Self::Left,
+            MouseButton::Middle => Self::Middle,
+            MouseButton::Right => Self::Right,
+        }
+    }
+}
+
+/// Unknown mouse buttons can't be recorded.
+impl TryFrom<events::MouseButton> for MouseButton {
+    type Error = ();
+
+    fn try_from(button: events::MouseButton) -> Result<Self, Self::Error> {
+        match button {
+            events::MouseButton::Left => Ok(Self::Left),
+            events::MouseButton::Middle => Ok(Self::Middle),
+            events::MouseButton::Right => Ok(Self::Right),
+            events::MouseButton::Unknown => Err(()),
+        }
+    }
+}
@@ -19,6 +19,36 @@ pub enum MouseButton {
     Right,
 }
 
+/// A digital button of a gamepad.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftTrigger,
+    LeftTrigger2,
+    RightTrigger,
+    RightTrigger2,
+    Select,
+    Start,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// An analog axis of a gamepad.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    LeftTrigger2,
+    RightTrigger2,
+}
+
 /// Control inputs to a text field
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TextControlCode {
@@ -43,8 +73,10 @@ pub enum TextControlCode {
     Paste,
     Cut,
     Backspace,
+    BackspaceWord,
     Enter,
     Delete,
+    DeleteWord,
 }
 
 /// All automated event types supported by FlashTAS.
@@ -65,7 +97,9 @@ pub enum AutomatedEvent {
     MouseDown {
         pos: MousePosition,
         btn: MouseButton,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         index: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         assert_handled: Option<EventHandledAssertion>,
     },
 
@@ -77,7 +111,9 @@ pub enum AutomatedEvent {
 
     /// Mouse scroll.
     MouseWheel {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         lines: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         pixels: Option<f64>,
     },
 
@@ -96,6 +132,25 @@ pub enum AutomatedEvent {
     /// Populate clipboard with the given text
     SetClipboardText { text: String },
 
+    /// Connect a gamepad with the given device id.
+    GamepadConnected { device: u32 },
+
+    /// Disconnect a previously connected gamepad.
+    GamepadDisconnected { device: u32 },
+
+    /// Press a gamepad button.
+    GamepadButtonDown { device: u32, button: GamepadButton },
+
+    /// Release a gamepad button.
+    GamepadButtonUp { device: u32, button: GamepadButton },
+
+    /// Move an analog axis of a gamepad.
+    GamepadAxisMotion {
+        device: u32,
+        axis: GamepadAxis,
+        value: f64,
+    },
+
     /// Inform the player that the focus has been gained (i.e. the window has been focused).
     FocusGained,
 
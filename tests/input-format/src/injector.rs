@@ -82,6 +82,11 @@ impl InputInjector {
         }
     }
 
+    /// Whether all the events have been injected.
+    pub fn is_finished(&self) -> bool {
+        self.pos >= self.items.len()
+    }
+
     /// Run the next frame's worth of events.
     pub fn next<Sink>(&mut self, mut event_sink: Sink)
     where
@@ -101,6 +106,11 @@ impl InputInjector {
                     | AutomatedEvent::TextControl { .. }
                     | AutomatedEvent::SetClipboardText { .. }
                     | AutomatedEvent::MouseWheel { .. }
+                    | AutomatedEvent::GamepadConnected { .. }
+                    | AutomatedEvent::GamepadDisconnected { .. }
+                    | AutomatedEvent::GamepadButtonDown { .. }
+                    | AutomatedEvent::GamepadButtonUp { .. }
+                    | AutomatedEvent::GamepadAxisMotion { .. }
                     | AutomatedEvent::FocusGained
                     | AutomatedEvent::FocusLost => {}
                     AutomatedEvent::MouseDown { btn, .. } => {
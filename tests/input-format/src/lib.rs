@@ -1,5 +1,9 @@
+#[cfg(feature = "ruffle_core")]
+mod conversions;
 mod format;
 mod injector;
 
-pub use format::{AutomatedEvent, MouseButton, TextControlCode};
+pub use format::{
+    AutomatedEvent, GamepadAxis, GamepadButton, MouseButton, MousePosition, TextControlCode,
+};
 pub use injector::{InputInjector, MouseButtons};
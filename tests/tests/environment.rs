@@ -8,6 +8,10 @@ impl Environment for NativeEnvironment {
         &self,
         requirements: &ruffle_test_framework::options::RenderOptions,
     ) -> bool {
+        if renderer::use_software_renderer() {
+            // The software renderer doesn't do any multisampling.
+            return requirements.sample_count <= 1;
+        }
         renderer::is_supported(requirements)
     }
 
@@ -20,8 +24,20 @@ impl Environment for NativeEnvironment {
         Box<dyn ruffle_test_framework::environment::RenderInterface>,
         Box<dyn ruffle_test_framework::environment::RenderBackend>,
     )> {
+        if renderer::use_software_renderer() {
+            return Some(
+                ruffle_test_framework::environment::SoftwareRenderInterface::create_pair(
+                    width, height,
+                ),
+            );
+        }
         renderer::NativeRenderInterface::create_pair(width, height)
     }
+
+    #[cfg(feature = "imgtests")]
+    fn uses_software_renderer(&self) -> bool {
+        renderer::use_software_renderer()
+    }
 }
 
 #[cfg(feature = "imgtests")]
@@ -80,6 +96,11 @@ mod renderer {
         descriptors().is_some()
     }
 
+    /// Whether `RUFFLE_TEST_RENDERER=software` asked for the CPU renderer instead of wgpu.
+    pub fn use_software_renderer() -> bool {
+        std::env::var("RUFFLE_TEST_RENDERER").is_ok_and(|renderer| renderer == "software")
+    }
+
     static WGPU: OnceLock<Option<Arc<Descriptors>>> = OnceLock::new();
 
     /*
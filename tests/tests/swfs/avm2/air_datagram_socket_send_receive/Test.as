@@ -0,0 +1,57 @@
+package {
+    import flash.display.Sprite;
+    import flash.events.DatagramSocketDataEvent;
+    import flash.net.DatagramSocket;
+    import flash.utils.ByteArray;
+
+    // The test navigator echoes every datagram back, as if its destination replied with it.
+    public class Test extends Sprite {
+        private var socket:DatagramSocket;
+        private var received:int = 0;
+
+        public function Test() {
+            socket = new DatagramSocket();
+            trace("bound: " + socket.bound);
+            socket.bind(0, "127.0.0.1");
+            trace("bound: " + socket.bound);
+            trace("localAddress: " + socket.localAddress);
+            trace("localPort: " + socket.localPort);
+            try {
+                socket.bind(0, "127.0.0.1");
+            } catch (e:Error) {
+                trace("bind again: " + e.name + " " + e.errorID);
+            }
+
+            socket.addEventListener(DatagramSocketDataEvent.DATA, onData);
+            socket.receive();
+            socket.send(bytes("Hello"), 0, 0, "127.0.0.1", 1234);
+        }
+
+        private function bytes(text:String):ByteArray {
+            var bytes:ByteArray = new ByteArray();
+            bytes.writeUTFBytes(text);
+            return bytes;
+        }
+
+        private function onData(event:DatagramSocketDataEvent):void {
+            trace("data from " + event.srcAddress + ":" + event.srcPort + " to " + event.dstAddress + ":" + event.dstPort + ": " + event.data);
+            received++;
+            if (received == 1) {
+                socket.connect("127.0.0.1", 1235);
+                trace("connected: " + socket.connected);
+                trace("remote: " + socket.remoteAddress + ":" + socket.remotePort);
+                try {
+                    socket.send(bytes("Elsewhere"), 0, 0, "127.0.0.1", 1236);
+                } catch (e:Error) {
+                    trace("send elsewhere: " + e.name + " " + e.errorID);
+                }
+                socket.send(bytes("Hello again"), 6);
+            } else {
+                socket.close();
+                trace("bound: " + socket.bound);
+                trace("localPort: " + socket.localPort);
+                trace("Done");
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+package {
+	import flash.display.BitmapData;
+	import flash.display.MovieClip;
+	import flash.events.ActivityEvent;
+	import flash.events.Event;
+	import flash.media.Camera;
+	import flash.media.Video;
+
+	public class Test extends MovieClip {
+		private var camera:Camera;
+		private var video1:Video = new Video(160, 120);
+		private var video2:Video = new Video(160, 120);
+		private var frame:int = 0;
+
+		public function Test() {
+			trace("Camera.names: " + Camera.names);
+			camera = Camera.getCamera();
+			trace("Camera.getCamera() == Camera.getCamera(\"0\"): " + (camera == Camera.getCamera("0")));
+			trace("Camera.getCamera(\"1\"): " + Camera.getCamera("1"));
+			camera.setMotionLevel(5, 100);
+			camera.addEventListener(ActivityEvent.ACTIVITY, onActivity);
+			printState("Before attaching");
+
+			addChild(video1);
+			addChild(video2);
+			addEventListener(Event.ENTER_FRAME, onEnterFrame);
+		}
+
+		private function printState(label:String):void {
+			trace(label + ": " + camera.width + "x" + camera.height + " fps = " + camera.fps +
+				" currentFPS = " + camera.currentFPS + " activityLevel = " + Math.round(camera.activityLevel));
+		}
+
+		private function onEnterFrame(e:Event):void {
+			frame++;
+			printState("Frame " + frame);
+
+			switch (frame) {
+				case 1:
+					trace("Attaching to video1");
+					video1.attachCamera(camera);
+					break;
+				case 3:
+					trace("Attaching to video2");
+					video2.attachCamera(camera);
+					break;
+				case 4:
+					trace("Detaching video1 with attachCamera(null)");
+					video1.attachCamera(null);
+					break;
+				case 6:
+					trace("Setting an oversized mode");
+					camera.setMode(4000, 3000, 10);
+					printState("After setMode");
+					break;
+				case 7:
+					trace("Detaching video2 with attachCamera(null)");
+					video2.attachCamera(null);
+					printState("After detaching all videos");
+					break;
+				case 9:
+					trace("Attaching to video1 again");
+					camera.setMode(320, 240, 10);
+					video1.attachCamera(camera);
+					printState("After attaching again");
+					break;
+			}
+
+			// The test pattern has eight vertical color bars, and a white block on one of
+			// four positions in the middle rows. video1 shows the camera at 160x120 on
+			// frame 2, and scaled down from 320x240 on frame 10.
+			if (frame == 2 || frame == 10) {
+				var pixels:BitmapData = new BitmapData(160, 120, false, 0x808080);
+				pixels.draw(video1);
+				var bars:Array = [];
+				for (var x:int = 10; x < 160; x += 20) {
+					bars.push(pixels.getPixel(x, 10).toString(16));
+				}
+				trace("Bars: " + bars.join(" "));
+				var blocks:int = 0;
+				for (x = 30; x < 160; x += 40) {
+					if (pixels.getPixel(x, 60) == 0xFFFFFF) {
+						blocks++;
+					}
+				}
+				trace("White block positions: " + blocks);
+			}
+		}
+
+		private function onActivity(e:ActivityEvent):void {
+			trace("activity: activating = " + e.activating);
+		}
+	}
+}
@@ -0,0 +1,54 @@
+import flash.display.BitmapData;
+import flash.display.Loader;
+import flash.events.Event;
+import flash.net.URLRequest;
+import flash.system.LoaderContext;
+
+var loader:Loader;
+
+function check(name:String, loader:Loader, drawLoader:Boolean):void {
+	try {
+		trace(name + ": getPixel " + loader.content.bitmapData.getPixel(0, 0).toString(16));
+	} catch (e:Error) {
+		trace(name + ": getPixel " + e.name + " " + e.errorID);
+	}
+
+	var target:BitmapData = new BitmapData(2, 2);
+	try {
+		target.draw(loader.content.bitmapData);
+		trace(name + ": draw " + target.getPixel(0, 0).toString(16));
+	} catch (e:Error) {
+		trace(name + ": draw " + e.name + " " + e.errorID);
+	}
+
+	if (drawLoader) {
+		try {
+			target.draw(loader);
+			trace(name + ": draw(loader) ok");
+		} catch (e:Error) {
+			trace(name + ": draw(loader) " + e.name + " " + e.errorID);
+		}
+	}
+}
+
+function load(url:String, context:LoaderContext, next:Function):void {
+	loader = new Loader();
+	loader.contentLoaderInfo.addEventListener(Event.COMPLETE, next);
+	loader.load(new URLRequest(url), context);
+}
+
+// granted.example/crossdomain.xml allows every domain.
+load("http://granted.example/image.png", new LoaderContext(true), function(e:Event):void {
+	check("granted", loader, false);
+
+	// denied.example/crossdomain.xml only allows www.other.example.
+	load("http://denied.example/image.png", new LoaderContext(true), function(e:Event):void {
+		check("not granted", loader, true);
+
+		// Without checkPolicyFile, no policy file is consulted at all.
+		load("http://granted.example/image.png", null, function(e:Event):void {
+			check("no checkPolicyFile", loader, true);
+			trace("Done");
+		});
+	});
+});
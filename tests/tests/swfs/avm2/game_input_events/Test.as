@@ -0,0 +1,58 @@
+package {
+	import flash.display.MovieClip;
+	import flash.events.Event;
+	import flash.events.GameInputEvent;
+	import flash.ui.GameInput;
+	import flash.ui.GameInputControl;
+	import flash.ui.GameInputDevice;
+
+	public class Test extends MovieClip {
+		private var gameInput:GameInput;
+		private var frame:int = 0;
+
+		public function Test() {
+			gameInput = new GameInput();
+			gameInput.addEventListener(GameInputEvent.DEVICE_ADDED, onDeviceAdded);
+			gameInput.addEventListener(GameInputEvent.DEVICE_REMOVED, onDeviceRemoved);
+			addEventListener(Event.ENTER_FRAME, onEnterFrame);
+		}
+
+		private function onEnterFrame(e:Event):void {
+			frame++;
+			if (GameInput.numDevices > 0) {
+				var device:GameInputDevice = GameInput.getDeviceAt(0);
+				var values:Array = [];
+				for (var i:int = 0; i < device.numControls; i++) {
+					values.push(device.getControlAt(i).value);
+				}
+				trace("Frame " + frame + ": " + values.join(","));
+			} else {
+				trace("Frame " + frame + ": no devices");
+			}
+		}
+
+		private function onDeviceAdded(e:GameInputEvent):void {
+			var device:GameInputDevice = e.device;
+			trace("deviceAdded: id=" + device.id + " name=" + device.name + " numControls=" + device.numControls + " enabled=" + device.enabled);
+			trace("GameInput.numDevices: " + GameInput.numDevices);
+			for (var i:int = 0; i < device.numControls; i++) {
+				var control:GameInputControl = device.getControlAt(i);
+				trace("  " + control.id + " [" + control.minValue + ", " + control.maxValue + "] = " + control.value);
+				control.addEventListener(Event.CHANGE, onChange);
+			}
+			if (device.id == "1") {
+				device.enabled = true;
+			}
+		}
+
+		private function onDeviceRemoved(e:GameInputEvent):void {
+			trace("deviceRemoved: id=" + e.device.id);
+			trace("GameInput.numDevices: " + GameInput.numDevices);
+		}
+
+		private function onChange(e:Event):void {
+			var control:GameInputControl = e.target as GameInputControl;
+			trace("change: device=" + control.device.id + " " + control.id + " = " + control.value);
+		}
+	}
+}
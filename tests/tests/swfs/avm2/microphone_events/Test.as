@@ -0,0 +1,72 @@
+package {
+	import flash.display.MovieClip;
+	import flash.events.ActivityEvent;
+	import flash.events.Event;
+	import flash.events.SampleDataEvent;
+	import flash.media.Microphone;
+
+	public class Test extends MovieClip {
+		private var mic:Microphone;
+		private var frame:int = 0;
+
+		public function Test() {
+			trace("Microphone.names: " + Microphone.names);
+			mic = Microphone.getMicrophone();
+			trace("name: " + mic.name + " rate: " + mic.rate + " gain: " + mic.gain);
+			trace("silenceLevel: " + mic.silenceLevel + " silenceTimeout: " + mic.silenceTimeout);
+			trace("activityLevel before capturing: " + mic.activityLevel);
+
+			// Listening for activity alone starts capturing.
+			mic.setSilenceLevel(10, 250);
+			mic.addEventListener(ActivityEvent.ACTIVITY, onActivity);
+			trace("activityLevel after adding an activity listener: " + mic.activityLevel);
+
+			addEventListener(Event.ENTER_FRAME, onEnterFrame);
+		}
+
+		private function onEnterFrame(e:Event):void {
+			frame++;
+			trace("Frame " + frame + ": activityLevel = " + Math.round(mic.activityLevel));
+
+			switch (frame) {
+				case 2:
+					trace("Muting with gain = 0");
+					mic.gain = 0;
+					break;
+				case 6:
+					trace("Unmuting with gain = 50");
+					mic.gain = 50;
+					break;
+				case 8:
+					trace("Adding a sampleData listener");
+					mic.addEventListener(SampleDataEvent.SAMPLE_DATA, onSampleData);
+					break;
+				case 10:
+					trace("Changing rate to 11");
+					mic.rate = 11;
+					break;
+				case 12:
+					trace("Removing the activity listener");
+					mic.removeEventListener(ActivityEvent.ACTIVITY, onActivity);
+					break;
+				case 13:
+					trace("Removing the sampleData listener");
+					mic.removeEventListener(SampleDataEvent.SAMPLE_DATA, onSampleData);
+					trace("activityLevel after removing all listeners: " + mic.activityLevel);
+					break;
+			}
+		}
+
+		private function onActivity(e:ActivityEvent):void {
+			trace("activity: activating = " + e.activating + " activityLevel = " + Math.round(mic.activityLevel));
+		}
+
+		private function onSampleData(e:SampleDataEvent):void {
+			var peak:Number = 0;
+			while (e.data.bytesAvailable > 0) {
+				peak = Math.max(peak, Math.abs(e.data.readFloat()));
+			}
+			trace("sampleData: position = " + e.position + " samples = " + (e.data.length / 4) + " peak = " + Math.round(peak * 100) / 100);
+		}
+	}
+}
@@ -0,0 +1,58 @@
+import flash.events.Event;
+import flash.events.NetStatusEvent;
+import flash.net.NetConnection;
+import flash.net.NetStream;
+import flash.net.NetStreamAppendBytesAction;
+import flash.net.URLLoader;
+import flash.net.URLLoaderDataFormat;
+import flash.net.URLRequest;
+import flash.utils.ByteArray;
+
+var bytes:ByteArray;
+var emptied:int = 0;
+
+var nc:NetConnection = new NetConnection();
+nc.connect(null);
+
+var ns:NetStream = new NetStream(nc);
+ns.client = {
+	onMetaData: function(info:Object):void {
+		trace("onMetaData");
+	}
+};
+ns.addEventListener(NetStatusEvent.NET_STATUS, function(e:NetStatusEvent):void {
+	trace(e.info.code);
+	if (e.info.code == "NetStream.Buffer.Empty") {
+		emptied++;
+		if (emptied == 1) {
+			// Appended bytes are discarded after seeking, until RESET_SEEK.
+			ns.seek(0);
+			ns.appendBytes(bytes);
+			trace("bufferLength after seeking: " + ns.bufferLength);
+		} else {
+			trace("Done");
+		}
+	} else if (e.info.code == "NetStream.Seek.Notify") {
+		// Continue from the first audio tag after the video frame.
+		ns.appendBytesAction(NetStreamAppendBytesAction.RESET_SEEK);
+		var tags:ByteArray = new ByteArray();
+		tags.writeBytes(bytes, 5567);
+		ns.appendBytes(tags);
+		trace("time after RESET_SEEK: " + ns.time);
+		trace("bufferLength after RESET_SEEK: " + ns.bufferLength);
+		ns.appendBytesAction(NetStreamAppendBytesAction.END_SEQUENCE);
+		ns.resume();
+	}
+});
+ns.play(null);
+
+var loader:URLLoader = new URLLoader();
+loader.dataFormat = URLLoaderDataFormat.BINARY;
+loader.addEventListener(Event.COMPLETE, function(e:Event):void {
+	bytes = loader.data;
+	ns.appendBytesAction(NetStreamAppendBytesAction.RESET_BEGIN);
+	ns.appendBytes(bytes);
+	trace("bufferLength after appending: " + ns.bufferLength);
+	ns.appendBytesAction(NetStreamAppendBytesAction.END_SEQUENCE);
+});
+loader.load(new URLRequest("test_video.flv"));
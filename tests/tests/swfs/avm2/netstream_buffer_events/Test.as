@@ -0,0 +1,52 @@
+import flash.events.Event;
+import flash.events.NetStatusEvent;
+import flash.net.NetConnection;
+import flash.net.NetStream;
+import flash.net.NetStreamAppendBytesAction;
+import flash.net.URLLoader;
+import flash.net.URLLoaderDataFormat;
+import flash.net.URLRequest;
+import flash.utils.ByteArray;
+
+var bytes:ByteArray;
+var emptied:int = 0;
+
+function append(offset:uint, length:uint):void {
+	var chunk:ByteArray = new ByteArray();
+	chunk.writeBytes(bytes, offset, length);
+	ns.appendBytes(chunk);
+	trace("Appended " + chunk.length + " bytes, bufferLength: " + ns.bufferLength);
+}
+
+var nc:NetConnection = new NetConnection();
+nc.connect(null);
+
+var ns:NetStream = new NetStream(nc);
+ns.bufferTime = 0.5;
+ns.addEventListener(NetStatusEvent.NET_STATUS, function(e:NetStatusEvent):void {
+	trace(e.info.code);
+	if (e.info.code == "NetStream.Buffer.Empty") {
+		emptied++;
+		if (emptied == 1) {
+			// Playback stalled at the end of the appended tags.
+			trace("time: " + ns.time + ", bufferLength: " + ns.bufferLength);
+			append(10067, 0);
+			ns.appendBytesAction(NetStreamAppendBytesAction.END_SEQUENCE);
+		} else {
+			trace("Done");
+		}
+	}
+});
+ns.play(null);
+
+var loader:URLLoader = new URLLoader();
+loader.dataFormat = URLLoaderDataFormat.BINARY;
+loader.addEventListener(Event.COMPLETE, function(e:Event):void {
+	bytes = loader.data;
+	ns.appendBytesAction(NetStreamAppendBytesAction.RESET_BEGIN);
+	// The header, metadata and the first video frame: 25ms of media.
+	append(0, 5567);
+	// Audio up to 522ms, which fills the buffer.
+	append(5567, 4500);
+});
+loader.load(new URLRequest("test_video.flv"));
@@ -0,0 +1,100 @@
+package {
+    import flash.display.BitmapData;
+    import flash.display.Shader;
+    import flash.display.ShaderJob;
+    import flash.display.Sprite;
+    import flash.events.Event;
+    import flash.events.ShaderEvent;
+    import flash.utils.ByteArray;
+    import flash.utils.Endian;
+
+    public class Test extends Sprite {
+        // The bytes of tintype.pbj
+        private static const TINTYPE:String = "a501000000a4070054696e54797065a00c6e616d6573706163650054696e5479706500a00c76656e646f720050617065726c65737320506f737400a00876657273696f6e000100a1010200000c5f4f7574436f6f726400a3000473726300a1020401000f64737400a10106020003677261795363616c6500a20664656661756c7456616c7565003e9e00d23f1c01a33da7ef9e3e9e00d23f1c01a33da7ef9e3e9e00d23f1c01a33da7ef9ea10101000002636f6e747261737400a2016d696e56616c75650000000000a2016d617856616c75650040400000a20164656661756c7456616c7565003f800000a101010000016d696400a2016d696e56616c75650000000000a2016d617856616c7565003f800000a20164656661756c7456616c7565003f0000001d0500c100001000300600f1050010001d0100f306001b001d0600e201001800210600e8020000001d0100e2060018001d02001001000000020200100000c0001d0300100200c00003030010000080001d0200100300c000010200100000c0001d0100800200c0001d02001001004000020200100000c0001d0300100200c00003030010000080001d0200100300c000010200100000c0001d0100400200c0001d02001001008000020200100000c0001d0300100200c00003030010000080001d0200100300c000010200100000c0001d0100200200c000";
+
+        private var frame:int = 0;
+        private var bitmapJob:ShaderJob;
+        private var bitmapTarget:BitmapData;
+        private var cancelledJob:ShaderJob;
+        private var cancelledTarget:ByteArray;
+
+        public function Test() {
+            var input:BitmapData = new BitmapData(256, 256, true, 0xFF806040);
+
+            trace("/// start(true)");
+            var syncTarget:BitmapData = new BitmapData(256, 256, true, 0);
+            var syncJob:ShaderJob = new ShaderJob(tintype(input), syncTarget);
+            syncJob.start(true);
+            trace("progress: " + syncJob.progress);
+            trace("pixel: " + syncTarget.getPixel32(0, 0).toString(16));
+
+            trace("/// start(false) with a BitmapData");
+            bitmapTarget = new BitmapData(256, 256, true, 0);
+            bitmapJob = new ShaderJob(tintype(input), bitmapTarget);
+            bitmapJob.addEventListener(Event.COMPLETE, onComplete);
+            bitmapJob.start(false);
+            trace("progress: " + bitmapJob.progress);
+            trace("pixel: " + bitmapTarget.getPixel32(0, 0).toString(16));
+
+            trace("/// start(false) with a Vector.<Number>");
+            var vectorTarget:Vector.<Number> = new Vector.<Number>();
+            var vectorJob:ShaderJob = new ShaderJob(tintype(input), vectorTarget, 2, 1);
+            vectorJob.addEventListener(Event.COMPLETE, onComplete);
+            vectorJob.start();
+            trace("length: " + vectorTarget.length);
+
+            trace("/// start(false) with a ByteArray, cancelled right away");
+            cancelledTarget = new ByteArray();
+            cancelledTarget.endian = Endian.LITTLE_ENDIAN;
+            cancelledJob = new ShaderJob(tintype(input), cancelledTarget, 256, 256);
+            cancelledJob.addEventListener(Event.COMPLETE, onComplete);
+            cancelledJob.start(false);
+            cancelledJob.cancel();
+            trace("cancelled");
+
+            addEventListener(Event.ENTER_FRAME, onEnterFrame);
+        }
+
+        private function tintype(input:BitmapData):Shader {
+            var bytes:ByteArray = new ByteArray();
+            for (var i:int = 0; i < TINTYPE.length; i += 2) {
+                bytes.writeByte(parseInt(TINTYPE.substr(i, 2), 16));
+            }
+            var shader:Shader = new Shader(bytes);
+            shader.data.grayScale.value = [1, 0, 0, 0, 1, 0, 0, 0, 1];
+            shader.data.contrast.value = [2];
+            shader.data.mid.value = [0.5];
+            shader.data.src.input = input;
+            return shader;
+        }
+
+        private function onEnterFrame(event:Event):void {
+            frame++;
+            trace("/// frame " + frame);
+            trace("bitmap job progress: " + bitmapJob.progress);
+            trace("bitmap target pixel: " + bitmapTarget.getPixel32(0, 0).toString(16));
+            trace("cancelled job progress: " + cancelledJob.progress);
+            trace("cancelled target length: " + cancelledTarget.length);
+        }
+
+        private function onComplete(event:ShaderEvent):void {
+            trace("/// complete on frame " + frame);
+            trace("target: " + event.target);
+            trace("progress: " + ShaderJob(event.target).progress);
+            trace("bitmapData: " + (event.bitmapData == bitmapTarget));
+            trace("byteArray: " + event.byteArray);
+            if (event.vector) {
+                trace("vector: " + event.vector);
+            }
+            if (event.bitmapData) {
+                trace("pixel: " + event.bitmapData.getPixel32(0, 0).toString(16));
+            }
+
+            var clone:ShaderEvent = ShaderEvent(event.clone());
+            trace("clone type: " + clone.type);
+            trace("clone bitmapData: " + (clone.bitmapData == event.bitmapData));
+            trace("clone byteArray: " + (clone.byteArray == event.byteArray));
+            trace("clone vector: " + (clone.vector == event.vector));
+        }
+    }
+}
@@ -0,0 +1,108 @@
+package {
+    import flash.display.Sprite;
+    import flash.events.Event;
+    import flash.media.Sound;
+    import flash.net.URLRequest;
+    import flash.utils.ByteArray;
+
+    // silence_22050.mp3 has 20 frames of 576 samples at 22050 Hz,
+    // so it extracts to about 23040 samples at 44100 Hz.
+    public class Test extends Sprite {
+        private var sound:Sound;
+
+        public function Test() {
+            sound = new Sound();
+            sound.addEventListener(Event.COMPLETE, onComplete);
+            sound.load(new URLRequest("silence_22050.mp3"));
+        }
+
+        private function onComplete(e:Event):void {
+            trace("/// MP3 at 22050 Hz");
+            var bytes:ByteArray = new ByteArray();
+            trace("extract(bytes, 4410, 0): " + sound.extract(bytes, 4410, 0));
+            trace("bytes.length: " + bytes.length);
+            trace("extract(bytes, 4410): " + sound.extract(bytes, 4410));
+            trace("bytes.length: " + bytes.length);
+            trace("silent: " + isSilent(bytes));
+
+            var all:ByteArray = new ByteArray();
+            var total:Number = sound.extract(all, 100000, 0);
+            trace("extract(all, 100000, 0) resampled to 44100 Hz: " + (Math.abs(total - 23040) <= 1152));
+            trace("all.length == total * 8: " + (all.length == total * 8));
+            trace("extract(bytes, 4410) at the end: " + sound.extract(bytes, 4410));
+            trace("extract(bytes, 10, 1000000): " + sound.extract(bytes, 10, 1000000));
+
+            loadPcm();
+            trace("Done");
+        }
+
+        private function loadPcm():void {
+            trace("/// loadPCMFromByteArray");
+            var pcm:Sound = new Sound();
+            try {
+                pcm.loadPCMFromByteArray(floats(100, 0.5, -0.25), 100, "int", true, 44100);
+                trace("format \"int\": no error");
+            } catch (e:Error) {
+                trace("format \"int\": " + e.name + " " + e.errorID);
+            }
+            try {
+                pcm.loadPCMFromByteArray(floats(100, 0.5, -0.25), 100, "float", true, 48000);
+                trace("sampleRate 48000: no error");
+            } catch (e:Error) {
+                trace("sampleRate 48000: " + e.name + " " + e.errorID);
+            }
+            try {
+                pcm.loadPCMFromByteArray(floats(10, 0.5, -0.25), 100, "float", true, 44100);
+                trace("short ByteArray: no error");
+            } catch (e:Error) {
+                trace("short ByteArray: " + e.name + " " + e.errorID);
+            }
+
+            pcm.loadPCMFromByteArray(floats(100, 0.5, -0.25), 100, "float", true, 44100);
+            var out:ByteArray = new ByteArray();
+            trace("float stereo 44100 Hz: extract " + pcm.extract(out, 1000, 0));
+            out.position = 400;
+            trace("float stereo 44100 Hz: sample 50 " + round3(out.readFloat()) + ", " + round3(out.readFloat()));
+
+            pcm.loadPCMFromByteArray(shorts(100, 16384), 100, "short", false, 22050);
+            out = new ByteArray();
+            var count:Number = pcm.extract(out, 1000, 0);
+            trace("short mono 22050 Hz: resampled to 44100 Hz " + (Math.abs(count - 200) <= 2));
+            out.position = 400;
+            trace("short mono 22050 Hz: sample 50 " + round3(out.readFloat()) + ", " + round3(out.readFloat()));
+        }
+
+        private function isSilent(bytes:ByteArray):Boolean {
+            bytes.position = 0;
+            while (bytes.bytesAvailable > 0) {
+                if (bytes.readFloat() != 0) {
+                    return false;
+                }
+            }
+            return true;
+        }
+
+        private function round3(value:Number):Number {
+            return Math.round(value * 1000) / 1000;
+        }
+
+        private function floats(count:int, left:Number, right:Number):ByteArray {
+            var bytes:ByteArray = new ByteArray();
+            for (var i:int = 0; i < count; i++) {
+                bytes.writeFloat(left);
+                bytes.writeFloat(right);
+            }
+            bytes.position = 0;
+            return bytes;
+        }
+
+        private function shorts(count:int, value:int):ByteArray {
+            var bytes:ByteArray = new ByteArray();
+            for (var i:int = 0; i < count; i++) {
+                bytes.writeShort(value);
+            }
+            bytes.position = 0;
+            return bytes;
+        }
+    }
+}
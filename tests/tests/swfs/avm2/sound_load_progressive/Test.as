@@ -0,0 +1,72 @@
+package {
+    import flash.display.Sprite;
+    import flash.events.Event;
+    import flash.events.ProgressEvent;
+    import flash.media.Sound;
+    import flash.media.SoundChannel;
+    import flash.media.SoundLoaderContext;
+    import flash.net.URLRequest;
+    import flash.utils.ByteArray;
+
+    // silence_44100.mp3 has 40 frames of 1152 samples at 44100 Hz, and the test
+    // loads one frame per tick. Playback is faster than that, so after starting
+    // with 250 ms buffered, it runs out of data and buffers again.
+    public class Test extends Sprite {
+        private var sound:Sound;
+        private var channel:SoundChannel;
+        private var buffering:Boolean = false;
+        private var changes:String = "";
+        private var started:Boolean = false;
+
+        public function Test() {
+            sound = new Sound();
+            sound.addEventListener(Event.OPEN, onOpen);
+            sound.addEventListener(ProgressEvent.PROGRESS, onProgress);
+            sound.addEventListener(Event.COMPLETE, onComplete);
+            sound.load(new URLRequest("silence_44100.mp3"), new SoundLoaderContext(250));
+            trace("isBuffering before open: " + sound.isBuffering);
+            channel = sound.play();
+            trace("play() before the sound loads: " + (channel != null));
+            channel.addEventListener(Event.SOUND_COMPLETE, onSoundComplete);
+        }
+
+        private function onOpen(e:Event):void {
+            trace("open: " + sound.bytesLoaded + "/" + sound.bytesTotal);
+            checkBuffering();
+        }
+
+        private function onProgress(e:ProgressEvent):void {
+            trace("progress: " + e.bytesLoaded + "/" + e.bytesTotal);
+            checkBuffering();
+            if (!started && !buffering) {
+                started = true;
+                trace("playback started at " + sound.bytesLoaded + " bytes");
+                trace("channel.position: " + channel.position);
+                trace("extract: about " + extractedFrames() + " frames");
+            }
+        }
+
+        private function onComplete(e:Event):void {
+            trace("complete: " + sound.bytesLoaded + "/" + sound.bytesTotal);
+            checkBuffering();
+            trace("isBuffering changes:" + changes);
+            trace("channel.position > 0: " + (channel.position > 0));
+            trace("extract: about " + extractedFrames() + " frames");
+        }
+
+        private function onSoundComplete(e:Event):void {
+            trace("soundComplete");
+        }
+
+        private function checkBuffering():void {
+            if (sound.isBuffering != buffering) {
+                buffering = sound.isBuffering;
+                changes += " " + buffering;
+            }
+        }
+
+        private function extractedFrames():Number {
+            return Math.round(sound.extract(new ByteArray(), 1000000, 0) / 1152);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+package {
+    import flash.concurrent.Condition;
+    import flash.concurrent.Mutex;
+    import flash.display.Sprite;
+    import flash.events.Event;
+    import flash.system.MessageChannel;
+    import flash.system.Worker;
+    import flash.system.WorkerDomain;
+    import flash.utils.ByteArray;
+
+    public class Test extends Sprite {
+        private var worker:Worker;
+        private var frames:int = 0;
+
+        public function Test() {
+            if (Worker.current.isPrimordial) {
+                primordial();
+            } else {
+                background();
+            }
+        }
+
+        private function primordial():void {
+            trace("isPrimordial: " + Worker.current.isPrimordial);
+            trace("state: " + Worker.current.state);
+
+            worker = WorkerDomain.current.createWorker(loaderInfo.bytes);
+            trace("new worker state: " + worker.state);
+            trace("listWorkers: " + WorkerDomain.current.listWorkers().length);
+
+            var toWorker:MessageChannel = Worker.current.createMessageChannel(worker);
+            var fromWorker:MessageChannel = worker.createMessageChannel(Worker.current);
+            var mutex:Mutex = new Mutex();
+            var condition:Condition = new Condition(mutex);
+            trace("condition.mutex == mutex: " + (condition.mutex == mutex));
+
+            var bytes:ByteArray = new ByteArray();
+            bytes.shareable = true;
+            bytes.writeUTFBytes("hello");
+
+            worker.setSharedProperty("toWorker", toWorker);
+            worker.setSharedProperty("fromWorker", fromWorker);
+            worker.setSharedProperty("condition", condition);
+            worker.setSharedProperty("bytes", bytes);
+            worker.setSharedProperty("value", {a: 1, b: [2, 3]});
+            trace("getSharedProperty(toWorker) == toWorker: " + (worker.getSharedProperty("toWorker") == toWorker));
+            trace("getSharedProperty(missing): " + worker.getSharedProperty("missing"));
+
+            try {
+                toWorker.send(function():void {});
+            } catch (e:Error) {
+                trace("send(function): " + e.errorID);
+            }
+
+            worker.addEventListener(Event.WORKER_STATE, function(e:Event):void {
+                trace("workerState event: " + worker.state);
+            });
+
+            mutex.lock();
+            worker.start();
+            trace("started worker state: " + worker.state);
+            try {
+                worker.start();
+            } catch (e:Error) {
+                trace("start() again: " + e.errorID);
+            }
+
+            trace("worker says: " + fromWorker.receive(true));
+            trace("worker says: " + fromWorker.receive(true));
+            trace("worker says: " + fromWorker.receive(true));
+
+            // The worker locks the mutex as soon as we start waiting, and notifies us.
+            trace("notified: " + condition.wait());
+            bytes.position = 0;
+            trace("bytes: " + bytes.readUTFBytes(bytes.length));
+            // Nothing notifies the condition now, and a timeout of 0 doesn't depend on how fast the worker runs.
+            trace("timed out: " + !condition.wait(0));
+            mutex.unlock();
+
+            toWorker.send("go");
+            for (var i:int = 0; i < 4; i++) {
+                trace("worker says: " + fromWorker.receive(true));
+            }
+            trace("messageAvailable: " + fromWorker.messageAvailable);
+            trace("receive(false): " + fromWorker.receive(false));
+
+            toWorker.close();
+            trace("closed channel state: " + toWorker.state);
+            trace("listWorkers: " + WorkerDomain.current.listWorkers().length);
+
+            addEventListener(Event.ENTER_FRAME, onEnterFrame);
+        }
+
+        private function onEnterFrame(e:Event):void {
+            frames++;
+            if (frames == 2) {
+                trace("terminate(): " + worker.terminate());
+                trace("terminated worker state: " + worker.state);
+                trace("terminate() again: " + worker.terminate());
+                trace("Worker.current.terminate(): " + Worker.current.terminate());
+                trace("listWorkers: " + WorkerDomain.current.listWorkers().length);
+            }
+        }
+
+        private function background():void {
+            var toWorker:MessageChannel = Worker.current.getSharedProperty("toWorker");
+            var fromWorker:MessageChannel = Worker.current.getSharedProperty("fromWorker");
+            var condition:Condition = Worker.current.getSharedProperty("condition");
+            var bytes:ByteArray = Worker.current.getSharedProperty("bytes");
+            var value:Object = Worker.current.getSharedProperty("value");
+
+            fromWorker.send("isPrimordial: " + Worker.current.isPrimordial + ", state: " + Worker.current.state);
+            fromWorker.send("tryLock: " + condition.mutex.tryLock() + ", bytes.shareable: " + bytes.shareable);
+            try {
+                condition.notify();
+            } catch (e:Error) {
+                fromWorker.send("notify() without the mutex: " + e.errorID);
+            }
+
+            condition.mutex.lock();
+            bytes.position = 0;
+            bytes.writeUTFBytes("hello from the worker");
+            condition.notify();
+            condition.mutex.unlock();
+
+            fromWorker.send("received: " + toWorker.receive(true));
+            // Each send waits until the previous message was received.
+            fromWorker.send("value.a: " + value.a, 0);
+            fromWorker.send("value.b: " + value.b, 0);
+            try {
+                condition.mutex.unlock();
+            } catch (e:Error) {
+                fromWorker.send("unlock() without the lock: " + e.errorID, 0);
+            }
+        }
+    }
+}
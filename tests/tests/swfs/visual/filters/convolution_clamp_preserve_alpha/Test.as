@@ -0,0 +1,68 @@
+package {
+
+import flash.display.Bitmap;
+import flash.display.BitmapData;
+import flash.display.MovieClip;
+import flash.display.Shape;
+import flash.filters.ConvolutionFilter;
+import flash.geom.Point;
+import flash.geom.Rectangle;
+
+public class Test extends MovieClip {
+	public function Test() {
+		// Top row: BitmapData.applyFilter, where the edges of the source are sampled.
+		addBitmap(0, 0, new ConvolutionFilter(5, 5, blurKernel(), 25, 0, false, true));
+		addBitmap(135, 0, new ConvolutionFilter(5, 5, blurKernel(), 25, 0, false, false, 0x0000FF, 1));
+		addBitmap(270, 0, new ConvolutionFilter(5, 5, blurKernel(), 25, 0, true, true));
+		addBitmap(405, 0, new ConvolutionFilter(5, 5, blurKernel(), 25, 0, true, false, 0x0000FF, 0.5));
+
+		// Bottom row: the same filters on display objects, with a bias and an edge detection kernel.
+		addShape(0, 200, new ConvolutionFilter(3, 3, [-1, -1, -1, -1, 8, -1, -1, -1, -1], 1, 64, false));
+		addShape(135, 200, new ConvolutionFilter(3, 3, [-1, -1, -1, -1, 8, -1, -1, -1, -1], 1, 64, true));
+		addShape(270, 200, new ConvolutionFilter(5, 5, blurKernel(), 25, 0, false, false, 0xFF00FF, 1));
+		addShape(405, 200, new ConvolutionFilter(5, 5, blurKernel(), 25, 0, true, false, 0xFF00FF, 1));
+	}
+
+	private function blurKernel():Array {
+		var kernel:Array = [];
+		for (var i:int = 0; i < 25; i++) {
+			kernel.push(1);
+		}
+		return kernel;
+	}
+
+	private function source():BitmapData {
+		var data:BitmapData = new BitmapData(120, 120, true, 0x00000000);
+		data.fillRect(new Rectangle(0, 0, 120, 40), 0xFFFF0000);
+		data.fillRect(new Rectangle(0, 40, 60, 80), 0x8000FF00);
+		data.fillRect(new Rectangle(60, 40, 60, 80), 0xFF00FF00);
+		data.fillRect(new Rectangle(30, 70, 60, 20), 0x00000000);
+		return data;
+	}
+
+	private function addBitmap(x:Number, y:Number, filter:ConvolutionFilter):void {
+		var data:BitmapData = source();
+		var output:BitmapData = new BitmapData(120, 120, true, 0xFFFFFFFF);
+		output.applyFilter(data, data.rect, new Point(0, 0), filter);
+		var bitmap:Bitmap = new Bitmap(output);
+		bitmap.x = x + 5;
+		bitmap.y = y + 5;
+		addChild(bitmap);
+	}
+
+	private function addShape(x:Number, y:Number, filter:ConvolutionFilter):void {
+		var shape:Shape = new Shape();
+		shape.graphics.beginFill(0xFF8000, 1);
+		shape.graphics.drawCircle(60, 60, 40);
+		shape.graphics.endFill();
+		shape.graphics.beginFill(0x0080FF, 0.5);
+		shape.graphics.drawRect(20, 20, 80, 30);
+		shape.graphics.endFill();
+		shape.x = x + 5;
+		shape.y = y + 5;
+		shape.filters = [filter];
+		addChild(shape);
+	}
+}
+
+}
@@ -0,0 +1,46 @@
+package {
+
+import flash.display.MovieClip;
+import flash.display.Shape;
+import flash.filters.BitmapFilterQuality;
+import flash.filters.BitmapFilterType;
+import flash.filters.GradientGlowFilter;
+import flash.text.TextField;
+
+public class Test extends MovieClip {
+	public function Test() {
+		var types:Array = [BitmapFilterType.OUTER, BitmapFilterType.INNER, BitmapFilterType.FULL];
+		for (var i:int = 0; i < types.length; i++) {
+			addShape(20 + i * 180, 20, types[i], false);
+			addShape(20 + i * 180, 220, types[i], true);
+		}
+	}
+
+	private function addShape(x:Number, y:Number, type:String, knockout:Boolean):void {
+		var shape:Shape = new Shape();
+		shape.graphics.beginFill(0x808080, 1);
+		shape.graphics.drawRoundRect(20, 20, 100, 100, 30, 30);
+		shape.graphics.endFill();
+		shape.graphics.beginFill(0xFFFFFF, 0.5);
+		shape.graphics.drawCircle(70, 70, 25);
+		shape.graphics.endFill();
+		shape.x = x;
+		shape.y = y;
+		// The ratios are uneven, to check that the gradient is sampled at each ratio rather than spread evenly.
+		shape.filters = [new GradientGlowFilter(
+			8, 45,
+			[0xFF0000, 0xFFFF00, 0x00FF00, 0x0000FF],
+			[0, 1, 0.75, 1],
+			[0, 32, 200, 255],
+			16, 16, 2, BitmapFilterQuality.MEDIUM, type, knockout)];
+		addChild(shape);
+
+		var label:TextField = new TextField();
+		label.text = type + (knockout ? ", knockout" : "");
+		label.x = x;
+		label.y = y + 150;
+		addChild(label);
+	}
+}
+
+}
@@ -46,6 +46,14 @@ impl ExternalVideoBackend {
             return Ok(decoder);
         }
 
+        #[cfg(feature = "h264")]
+        {
+            // Fall back to the built-in decoder when OpenH264 is unavailable.
+            let decoder = Box::new(ruffle_video_software::decoder::h264::H264Decoder::new());
+            return Ok(decoder);
+        }
+
+        #[cfg_attr(feature = "h264", allow(unreachable_code))]
         Err(Error::DecoderError("No OpenH264".into()))
     }
 
@@ -46,6 +46,12 @@ impl VideoBackend for SoftwareVideoBackend {
             VideoCodec::Vp6WithAlpha => Box::new(crate::decoder::vp6::Vp6Decoder::new(true, size)),
             #[cfg(feature = "screenvideo")]
             VideoCodec::ScreenVideo => Box::new(crate::decoder::screen::ScreenVideoDecoder::new()),
+            #[cfg(feature = "screenvideo")]
+            VideoCodec::ScreenVideoV2 => {
+                Box::new(crate::decoder::screen::ScreenVideoDecoder::new_v2())
+            }
+            #[cfg(feature = "h264")]
+            VideoCodec::H264 => Box::new(crate::decoder::h264::H264Decoder::new()),
             other => return Err(Error::UnsupportedCodec(other)),
         };
         let stream = VideoStream::new(decoder);
@@ -68,11 +74,15 @@ impl VideoBackend for SoftwareVideoBackend {
 
     fn configure_video_stream_decoder(
         &mut self,
-        _stream: VideoStreamHandle,
-        _configuration_data: &[u8],
+        stream: VideoStreamHandle,
+        configuration_data: &[u8],
     ) -> Result<(), Error> {
-        // None of the software decoders require configuration.
-        Ok(())
+        let stream = self
+            .streams
+            .get_mut(stream)
+            .ok_or(Error::VideoStreamIsNotRegistered)?;
+
+        stream.decoder.configure_decoder(configuration_data)
     }
 
     fn decode_video_stream_frame(
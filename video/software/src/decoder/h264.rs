@@ -0,0 +1,862 @@
+//! A software decoder for H.264 (AVC) video, as used in FLV files.
+//!
+//! Supports progressive 8-bit 4:2:0 Baseline profile streams, which use CAVLC entropy coding.
+//! B-slices and weighted prediction are decoded as well, but Main and High profile streams are
+//! rejected when configuring the decoder with `Error::UnsupportedCodec`, as nearly all of them
+//! use CABAC. Parameter sets needing CABAC, interlacing, the 8x8 transform, or other High profile
+//! features are rejected with `H264Error::Unsupported`.
+
+mod bitreader;
+mod cavlc;
+mod deblock;
+mod inter;
+mod intra;
+mod macroblock;
+mod mvpred;
+mod params;
+mod picture;
+mod slice;
+mod transform;
+
+use crate::decoder::VideoDecoder;
+use bitreader::{unescape_rbsp, BitReader};
+use macroblock::{CurrentPicture, SliceContext};
+use params::{Pps, Sps};
+use picture::{Picture, ReferencePictures};
+use ruffle_render::bitmap::BitmapFormat;
+use ruffle_video::error::Error;
+use ruffle_video::frame::{DecodedFrame, EncodedFrame, FrameDependency};
+use slice::{Mmco, SliceHeader};
+use std::rc::Rc;
+use swf::VideoCodec;
+
+#[derive(thiserror::Error, Debug)]
+pub enum H264Error {
+    #[error("Decoder not configured")]
+    NotConfigured,
+
+    #[error("Unexpected end of data")]
+    UnexpectedEndOfData,
+
+    #[error("Invalid data: {0}")]
+    InvalidData(&'static str),
+
+    #[error("Unsupported H.264 feature: {0}")]
+    Unsupported(&'static str),
+
+    #[error("Missing parameter set")]
+    MissingParameterSet,
+
+    #[error("Missing reference picture")]
+    MissingReference,
+
+    #[error("No output frame produced by the decoder")]
+    NoOutputFrame,
+}
+
+impl From<H264Error> for Error {
+    fn from(error: H264Error) -> Self {
+        Error::DecoderError(Box::new(error))
+    }
+}
+
+const NAL_SLICE: u8 = 1;
+const NAL_SLICE_DATA_PARTITION_A: u8 = 2;
+const NAL_SLICE_DATA_PARTITION_C: u8 = 4;
+const NAL_SLICE_IDR: u8 = 5;
+const NAL_SPS: u8 = 7;
+const NAL_PPS: u8 = 8;
+const NAL_END_OF_SEQUENCE: u8 = 10;
+
+/// State carried between pictures for picture order count decoding (8.2.1).
+#[derive(Default)]
+struct PocState {
+    prev_poc_msb: i32,
+    prev_poc_lsb: i32,
+    prev_frame_num_offset: i32,
+    prev_frame_num: u32,
+}
+
+/// The picture currently being decoded, along with what's needed to finish it.
+struct PendingPicture {
+    current: CurrentPicture,
+    header: SliceHeader,
+    sps: Sps,
+    poc_msb: i32,
+    frame_num_offset: i32,
+}
+
+/// A decoded picture waiting to be output in display order.
+struct OutputPicture {
+    /// Incremented with each IDR picture, which resets the picture order count.
+    epoch: u32,
+    poc: i32,
+    picture: Rc<Picture>,
+    crop: (u32, u32, u32, u32),
+}
+
+/// H.264 video decoder.
+pub struct H264Decoder {
+    /// How many bytes are used to store the length of the NALU (1, 2, 3, or 4).
+    length_size: usize,
+
+    sps: Vec<Option<Sps>>,
+    pps: Vec<Option<Pps>>,
+
+    references: ReferencePictures,
+    pending: Option<PendingPicture>,
+    poc_state: PocState,
+    next_picture_id: u32,
+
+    output_queue: Vec<OutputPicture>,
+    epoch: u32,
+    /// How many pictures may precede another one in decoding order, but follow it in output order.
+    reorder_depth: usize,
+    last_frame: Option<DecodedFrame>,
+}
+
+impl Default for H264Decoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl H264Decoder {
+    pub fn new() -> Self {
+        Self {
+            length_size: 0,
+            sps: vec![None; 32],
+            pps: vec![None; 256],
+            references: ReferencePictures::default(),
+            pending: None,
+            poc_state: PocState::default(),
+            next_picture_id: 0,
+            output_queue: Vec::new(),
+            epoch: 0,
+            reorder_depth: 0,
+            last_frame: None,
+        }
+    }
+
+    /// Splits the length-prefixed NAL units of a frame.
+    fn nal_units<'a>(&self, mut data: &'a [u8]) -> Result<Vec<&'a [u8]>, H264Error> {
+        if self.length_size == 0 {
+            return Err(H264Error::NotConfigured);
+        }
+        let mut units = Vec::new();
+        while !data.is_empty() {
+            if data.len() < self.length_size {
+                return Err(H264Error::UnexpectedEndOfData);
+            }
+            let length = data[..self.length_size]
+                .iter()
+                .fold(0usize, |length, &byte| (length << 8) | byte as usize);
+            data = &data[self.length_size..];
+            if length > data.len() {
+                return Err(H264Error::UnexpectedEndOfData);
+            }
+            if length > 0 {
+                units.push(&data[..length]);
+            }
+            data = &data[length..];
+        }
+        Ok(units)
+    }
+
+    fn parse_parameter_set(&mut self, nal_unit: &[u8]) -> Result<(), H264Error> {
+        let nal_unit_type = nal_unit[0] & 0x1F;
+        let rbsp = unescape_rbsp(&nal_unit[1..]);
+        match nal_unit_type {
+            NAL_SPS => {
+                let sps = Sps::parse(&rbsp)?;
+                let id = sps.sps_id as usize;
+                self.sps[id] = Some(sps);
+            }
+            NAL_PPS => {
+                let pps = Pps::parse(&rbsp, &self.sps)?;
+                let id = pps.pps_id as usize;
+                self.pps[id] = Some(pps);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn decode_nal_unit(&mut self, nal_unit: &[u8]) -> Result<(), H264Error> {
+        let nal_ref_idc = (nal_unit[0] >> 5) & 3;
+        let nal_unit_type = nal_unit[0] & 0x1F;
+        match nal_unit_type {
+            NAL_SLICE | NAL_SLICE_IDR => self.decode_slice(nal_unit, nal_unit_type, nal_ref_idc),
+            NAL_SLICE_DATA_PARTITION_A..=NAL_SLICE_DATA_PARTITION_C => {
+                Err(H264Error::Unsupported("Slice data partitioning"))
+            }
+            NAL_SPS | NAL_PPS => self.parse_parameter_set(nal_unit),
+            NAL_END_OF_SEQUENCE => {
+                self.finish_picture();
+                Ok(())
+            }
+            // SEI, access unit delimiters, filler data, and so on.
+            _ => Ok(()),
+        }
+    }
+
+    fn decode_slice(
+        &mut self,
+        nal_unit: &[u8],
+        nal_unit_type: u8,
+        nal_ref_idc: u8,
+    ) -> Result<(), H264Error> {
+        let rbsp = unescape_rbsp(&nal_unit[1..]);
+        let pps_id = SliceHeader::peek_pps_id(&rbsp)?;
+        let pps = self
+            .pps
+            .get(pps_id as usize)
+            .and_then(|pps| pps.clone())
+            .ok_or(H264Error::MissingParameterSet)?;
+        let sps = self.sps[pps.sps_id as usize]
+            .clone()
+            .ok_or(H264Error::MissingParameterSet)?;
+        sps.check_supported()?;
+        pps.check_supported()?;
+
+        let mut r = BitReader::new(&rbsp);
+        let header = SliceHeader::parse(&mut r, nal_unit_type, nal_ref_idc, &sps, &pps)?;
+
+        if header.first_mb_in_slice == 0 || self.pending.is_none() {
+            self.finish_picture();
+            self.start_picture(&header, &sps);
+        }
+        let Some(pending) = &mut self.pending else {
+            unreachable!("A picture was just started");
+        };
+
+        let lists = self.references.build_lists(
+            &header,
+            pending.current.picture.poc,
+            sps.max_frame_num(),
+        )?;
+        let ctx = SliceContext {
+            header: &header,
+            sps: &sps,
+            pps: &pps,
+            lists,
+            level_scale: transform::level_scale(&pps.scaling_4x4),
+        };
+        pending.current.decode_slice(&mut r, &ctx)
+    }
+
+    /// Computes the picture order count of a new picture (8.2.1),
+    /// returning it along with `PicOrderCntMsb` and `FrameNumOffset`.
+    fn picture_order_count(&self, header: &SliceHeader, sps: &Sps) -> (i32, i32, i32) {
+        let state = &self.poc_state;
+        let max_frame_num = sps.max_frame_num() as i32;
+        let frame_num = header.frame_num as i32;
+        let frame_num_offset = if header.idr {
+            0
+        } else if state.prev_frame_num > header.frame_num {
+            state.prev_frame_num_offset + max_frame_num
+        } else {
+            state.prev_frame_num_offset
+        };
+
+        match sps.pic_order_cnt_type {
+            0 => {
+                let (prev_msb, prev_lsb) = if header.idr {
+                    (0, 0)
+                } else {
+                    (state.prev_poc_msb, state.prev_poc_lsb)
+                };
+                let max_lsb = 1 << sps.log2_max_pic_order_cnt_lsb;
+                let lsb = header.pic_order_cnt_lsb as i32;
+                let msb = if lsb < prev_lsb && prev_lsb - lsb >= max_lsb / 2 {
+                    prev_msb + max_lsb
+                } else if lsb > prev_lsb && lsb - prev_lsb > max_lsb / 2 {
+                    prev_msb - max_lsb
+                } else {
+                    prev_msb
+                };
+                let top = msb + lsb;
+                let bottom = top + header.delta_pic_order_cnt_bottom;
+                (top.min(bottom), msb, frame_num_offset)
+            }
+            1 => {
+                let cycle = &sps.offset_for_ref_frame;
+                let mut abs_frame_num = if cycle.is_empty() {
+                    0
+                } else {
+                    frame_num_offset + frame_num
+                };
+                if header.nal_ref_idc == 0 && abs_frame_num > 0 {
+                    abs_frame_num -= 1;
+                }
+                let mut expected = 0;
+                if abs_frame_num > 0 {
+                    let cycle_count = (abs_frame_num - 1) / cycle.len() as i32;
+                    let frame_in_cycle = ((abs_frame_num - 1) % cycle.len() as i32) as usize;
+                    let delta_per_cycle: i32 = cycle.iter().sum();
+                    expected = cycle_count * delta_per_cycle
+                        + cycle[..=frame_in_cycle].iter().sum::<i32>();
+                }
+                if header.nal_ref_idc == 0 {
+                    expected += sps.offset_for_non_ref_pic;
+                }
+                let top = expected + header.delta_pic_order_cnt[0];
+                let bottom =
+                    top + sps.offset_for_top_to_bottom_field + header.delta_pic_order_cnt[1];
+                (top.min(bottom), 0, frame_num_offset)
+            }
+            _ => {
+                let poc = if header.idr {
+                    0
+                } else if header.nal_ref_idc == 0 {
+                    2 * (frame_num_offset + frame_num) - 1
+                } else {
+                    2 * (frame_num_offset + frame_num)
+                };
+                (poc, 0, frame_num_offset)
+            }
+        }
+    }
+
+    fn start_picture(&mut self, header: &SliceHeader, sps: &Sps) {
+        let (poc, poc_msb, frame_num_offset) = self.picture_order_count(header, sps);
+        let picture = Picture::new(
+            self.next_picture_id,
+            sps.width_in_mbs as usize,
+            sps.height_in_mbs as usize,
+            poc,
+        );
+        self.next_picture_id = self.next_picture_id.wrapping_add(1);
+        if header.idr {
+            if header.no_output_of_prior_pics {
+                self.output_queue.clear();
+            }
+            self.epoch += 1;
+            self.reorder_depth = sps.max_num_reorder_frames as usize;
+        }
+        self.pending = Some(PendingPicture {
+            current: CurrentPicture::new(picture),
+            header: header.clone(),
+            sps: sps.clone(),
+            poc_msb,
+            frame_num_offset,
+        });
+    }
+
+    /// Finishes decoding the current picture: filters it, marks it for reference,
+    /// and queues it for output.
+    fn finish_picture(&mut self) {
+        let Some(pending) = self.pending.take() else {
+            return;
+        };
+        let PendingPicture {
+            mut current,
+            header,
+            sps,
+            poc_msb,
+            frame_num_offset,
+        } = pending;
+        current.deblock();
+
+        let mut picture = current.picture;
+        let has_mmco5 = header
+            .mmcos
+            .as_ref()
+            .is_some_and(|ops| ops.iter().any(|op| matches!(op, Mmco::ForgetAll)));
+        if has_mmco5 {
+            // The picture order count is reset after it (8.2.1).
+            self.epoch += 1;
+            picture.poc = 0;
+        }
+        let poc = picture.poc;
+        let picture = Rc::new(picture);
+
+        if header.nal_ref_idc != 0 {
+            self.references.mark(
+                picture.clone(),
+                &header,
+                sps.max_num_ref_frames,
+                sps.max_frame_num(),
+            );
+            if has_mmco5 {
+                self.poc_state.prev_poc_msb = 0;
+                self.poc_state.prev_poc_lsb = 0;
+            } else {
+                self.poc_state.prev_poc_msb = poc_msb;
+                self.poc_state.prev_poc_lsb = header.pic_order_cnt_lsb as i32;
+            }
+        }
+        if has_mmco5 {
+            self.poc_state.prev_frame_num_offset = 0;
+            self.poc_state.prev_frame_num = 0;
+        } else {
+            self.poc_state.prev_frame_num_offset = frame_num_offset;
+            self.poc_state.prev_frame_num = header.frame_num;
+        }
+
+        self.output_queue.push(OutputPicture {
+            epoch: self.epoch,
+            poc,
+            picture,
+            crop: sps.crop,
+        });
+    }
+
+    /// Outputs the pictures that can't be reordered anymore, returning the last one.
+    fn bump_output(&mut self) -> Option<DecodedFrame> {
+        let mut frame = None;
+        while self.output_queue.len() > self.reorder_depth {
+            let index = self
+                .output_queue
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, p)| (p.epoch, p.poc))
+                .map(|(i, _)| i)?;
+            frame = Some(self.output_queue.remove(index));
+        }
+        frame.map(|output| to_decoded_frame(&output.picture, output.crop))
+    }
+}
+
+/// Converts a decoded picture into a cropped YUV 4:2:0 frame.
+fn to_decoded_frame(picture: &Picture, crop: (u32, u32, u32, u32)) -> DecodedFrame {
+    let (left, right, top, bottom) = (
+        crop.0 as usize,
+        crop.1 as usize,
+        crop.2 as usize,
+        crop.3 as usize,
+    );
+    let full_width = picture.width_in_mbs * 16;
+    let full_height = picture.height_in_mbs * 16;
+    let width = full_width - left - right;
+    let height = full_height - top - bottom;
+
+    let mut yuv = Vec::with_capacity(width * height * 3 / 2);
+    for y in top..top + height {
+        yuv.extend_from_slice(&picture.luma[y * full_width + left..][..width]);
+    }
+    for plane in [&picture.cb, &picture.cr] {
+        for y in top / 2..(top + height) / 2 {
+            yuv.extend_from_slice(&plane[y * full_width / 2 + left / 2..][..width / 2]);
+        }
+    }
+
+    // NOTE: This will always use the BT.601 coefficients, just like the OpenH264 decoder.
+    DecodedFrame::new(width as u32, height as u32, BitmapFormat::Yuv420p, yuv)
+}
+
+impl VideoDecoder for H264Decoder {
+    fn configure_decoder(&mut self, configuration_data: &[u8]) -> Result<(), Error> {
+        // This is an AVCDecoderConfigurationRecord:
+        // [0]: configuration version, always 1
+        // [1]: profile
+        // [2]: compatibility
+        // [3]: level
+        // [4]: 6 reserved bits | NALU length size - 1
+        // [5]: 3 reserved bits | number of SPS
+        // Then each SPS and PPS prefixed by its 16-bit length,
+        // with the number of PPS in between.
+        if configuration_data.len() < 7 || configuration_data[0] != 1 {
+            return Err(H264Error::InvalidData("Invalid decoder configuration record").into());
+        }
+        // Only Baseline profile streams are supported, or those constrained to it.
+        if configuration_data[1] != 66 && configuration_data[2] & 0b1000_0000 == 0 {
+            return Err(Error::UnsupportedCodec(VideoCodec::H264));
+        }
+        self.length_size = (configuration_data[4] & 0b0000_0011) as usize + 1;
+
+        let mut data = &configuration_data[5..];
+        let mut read_parameter_sets = |data: &mut &[u8], count: usize| -> Result<(), H264Error> {
+            for _ in 0..count {
+                if data.len() < 2 {
+                    return Err(H264Error::UnexpectedEndOfData);
+                }
+                let length = ((data[0] as usize) << 8) | data[1] as usize;
+                if data.len() < 2 + length {
+                    return Err(H264Error::UnexpectedEndOfData);
+                }
+                if length > 0 {
+                    self.parse_parameter_set(&data[2..2 + length])?;
+                }
+                *data = &data[2 + length..];
+            }
+            Ok(())
+        };
+        let num_sps = (data[0] & 0x1F) as usize;
+        data = &data[1..];
+        read_parameter_sets(&mut data, num_sps)?;
+        let num_pps = *data.first().ok_or(H264Error::UnexpectedEndOfData)? as usize;
+        data = &data[1..];
+        read_parameter_sets(&mut data, num_pps)?;
+        Ok(())
+    }
+
+    fn preload_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<FrameDependency, Error> {
+        let nal_units = self.nal_units(encoded_frame.data)?;
+
+        // 3.62 instantaneous decoding refresh (IDR) picture:
+        // After the decoding of an IDR picture all following coded pictures in decoding order can
+        // be decoded without inter prediction from any picture decoded prior to the IDR picture.
+        if nal_units
+            .iter()
+            .any(|nal_unit| nal_unit[0] & 0x1F == NAL_SLICE_IDR)
+        {
+            Ok(FrameDependency::None)
+        } else {
+            Ok(FrameDependency::Past)
+        }
+    }
+
+    fn decode_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<DecodedFrame, Error> {
+        let nal_units = self.nal_units(encoded_frame.data)?;
+
+        // Keep going after a broken slice, so that the rest of the picture,
+        // and the pictures referring to it, still have a chance to look right.
+        let mut result = Ok(());
+        for nal_unit in nal_units {
+            if let Err(e) = self.decode_nal_unit(nal_unit) {
+                if let H264Error::Unsupported(_) | H264Error::MissingParameterSet = e {
+                    return Err(e.into());
+                }
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+        // Each frame of an FLV stream holds a single access unit.
+        self.finish_picture();
+        result?;
+
+        if let Some(frame) = self.bump_output() {
+            self.last_frame = Some(frame.clone());
+            return Ok(frame);
+        }
+        self.last_frame
+            .clone()
+            .ok_or_else(|| H264Error::NoOutputFrame.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes the bits of a test bitstream, MSB first.
+    #[derive(Default)]
+    struct BitWriter {
+        data: Vec<u8>,
+        bits: usize,
+    }
+
+    impl BitWriter {
+        fn bit(&mut self, bit: bool) {
+            if self.bits & 7 == 0 {
+                self.data.push(0);
+            }
+            if bit {
+                *self.data.last_mut().unwrap() |= 0x80 >> (self.bits % 8);
+            }
+            self.bits += 1;
+        }
+
+        fn bits(&mut self, count: u32, value: u32) {
+            for i in (0..count).rev() {
+                self.bit((value >> i) & 1 != 0);
+            }
+        }
+
+        fn ue(&mut self, value: u32) {
+            let value = value + 1;
+            let len = 32 - value.leading_zeros();
+            self.bits(len - 1, 0);
+            self.bits(len, value);
+        }
+
+        fn se(&mut self, value: i32) {
+            self.ue(if value > 0 {
+                2 * value as u32 - 1
+            } else {
+                (-2 * value) as u32
+            });
+        }
+
+        fn align_zero(&mut self) {
+            while self.bits & 7 != 0 {
+                self.bit(false);
+            }
+        }
+
+        /// Appends the `rbsp_trailing_bits()` and returns the NAL unit,
+        /// with emulation prevention bytes inserted.
+        fn finish_nal(mut self, header: u8) -> Vec<u8> {
+            self.bit(true);
+            self.align_zero();
+            let mut nal = vec![header];
+            let mut zeros = 0;
+            for byte in self.data {
+                if zeros >= 2 && byte <= 3 {
+                    nal.push(3);
+                    zeros = 0;
+                }
+                zeros = if byte == 0 { zeros + 1 } else { 0 };
+                nal.push(byte);
+            }
+            nal
+        }
+    }
+
+    const WIDTH_IN_MBS: u32 = 2;
+    const HEIGHT_IN_MBS: u32 = 2;
+
+    /// A Baseline profile SPS, with the given `max_num_reorder_frames`
+    /// and `max_dec_frame_buffering` in its VUI.
+    fn sps(pic_order_cnt_type: u32, bitstream_restriction: Option<(u32, u32)>) -> Vec<u8> {
+        let mut sps = BitWriter::default();
+        sps.bits(8, 66); // profile_idc
+        sps.bits(8, 0); // constraint flags
+        sps.bits(8, 30); // level_idc
+        sps.ue(0); // seq_parameter_set_id
+        sps.ue(0); // log2_max_frame_num_minus4
+        sps.ue(pic_order_cnt_type);
+        if pic_order_cnt_type == 0 {
+            sps.ue(0); // log2_max_pic_order_cnt_lsb_minus4
+        }
+        sps.ue(1); // max_num_ref_frames
+        sps.bit(false); // gaps_in_frame_num_value_allowed_flag
+        sps.ue(WIDTH_IN_MBS - 1);
+        sps.ue(HEIGHT_IN_MBS - 1);
+        sps.bit(true); // frame_mbs_only_flag
+        sps.bit(true); // direct_8x8_inference_flag
+        sps.bit(true); // frame_cropping_flag
+        sps.ue(0);
+        sps.ue(2); // Crop 4 pixels from the right.
+        sps.ue(0);
+        sps.ue(0);
+        sps.bit(bitstream_restriction.is_some()); // vui_parameters_present_flag
+        if let Some((max_num_reorder_frames, max_dec_frame_buffering)) = bitstream_restriction {
+            sps.bits(5, 0); // No aspect ratio, overscan, video signal, chroma or timing info
+            sps.bits(3, 0); // No HRD parameters nor pic_struct_present_flag
+            sps.bit(true); // bitstream_restriction_flag
+            sps.bit(true); // motion_vectors_over_pic_boundaries_flag
+            sps.ue(2); // max_bytes_per_pic_denom
+            sps.ue(1); // max_bits_per_mb_denom
+            sps.ue(16); // log2_max_mv_length_horizontal
+            sps.ue(16); // log2_max_mv_length_vertical
+            sps.ue(max_num_reorder_frames);
+            sps.ue(max_dec_frame_buffering);
+        }
+        sps.finish_nal(0x67)
+    }
+
+    /// A Baseline profile SPS and PPS, in an AVCDecoderConfigurationRecord.
+    fn configuration() -> Vec<u8> {
+        let sps = sps(2, None);
+
+        let mut pps = BitWriter::default();
+        pps.ue(0); // pic_parameter_set_id
+        pps.ue(0); // seq_parameter_set_id
+        pps.bit(false); // entropy_coding_mode_flag
+        pps.bit(false); // bottom_field_pic_order_in_frame_present_flag
+        pps.ue(0); // num_slice_groups_minus1
+        pps.ue(0); // num_ref_idx_l0_default_active_minus1
+        pps.ue(0); // num_ref_idx_l1_default_active_minus1
+        pps.bit(false); // weighted_pred_flag
+        pps.bits(2, 0); // weighted_bipred_idc
+        pps.se(0); // pic_init_qp_minus26
+        pps.se(0); // pic_init_qs_minus26
+        pps.se(0); // chroma_qp_index_offset
+        pps.bit(true); // deblocking_filter_control_present_flag
+        pps.bit(false); // constrained_intra_pred_flag
+        pps.bit(false); // redundant_pic_cnt_present_flag
+        let pps = pps.finish_nal(0x68);
+
+        let mut config = vec![1, 66, 0, 30, 0xFF, 0xE1];
+        config.extend_from_slice(&(sps.len() as u16).to_be_bytes());
+        config.extend_from_slice(&sps);
+        config.push(1);
+        config.extend_from_slice(&(pps.len() as u16).to_be_bytes());
+        config.extend_from_slice(&pps);
+        config
+    }
+
+    fn slice_header(w: &mut BitWriter, slice_type: u32, idr: bool, frame_num: u32) {
+        w.ue(0); // first_mb_in_slice
+        w.ue(slice_type);
+        w.ue(0); // pic_parameter_set_id
+        w.bits(4, frame_num);
+        if idr {
+            w.ue(0); // idr_pic_id
+        }
+        if slice_type == 0 {
+            w.bit(false); // num_ref_idx_active_override_flag
+            w.bit(false); // ref_pic_list_modification_flag_l0
+        }
+        // dec_ref_pic_marking()
+        if idr {
+            w.bit(false); // no_output_of_prior_pics_flag
+            w.bit(false); // long_term_reference_flag
+        } else {
+            w.bit(false); // adaptive_ref_pic_marking_mode_flag
+        }
+        w.se(0); // slice_qp_delta
+        w.ue(1); // disable_deblocking_filter_idc
+    }
+
+    /// Wraps NAL units into a length-prefixed FLV frame.
+    fn frame(nal_units: &[Vec<u8>]) -> Vec<u8> {
+        let mut data = Vec::new();
+        for nal_unit in nal_units {
+            data.extend_from_slice(&(nal_unit.len() as u32).to_be_bytes());
+            data.extend_from_slice(nal_unit);
+        }
+        data
+    }
+
+    fn sample(x: u32, y: u32) -> u8 {
+        (x * 7 + y * 3) as u8
+    }
+
+    /// An IDR frame made of I_PCM macroblocks.
+    fn pcm_frame() -> Vec<u8> {
+        let mut w = BitWriter::default();
+        slice_header(&mut w, 2, true, 0);
+        for mb in 0..WIDTH_IN_MBS * HEIGHT_IN_MBS {
+            let (mb_x, mb_y) = (mb % WIDTH_IN_MBS, mb / WIDTH_IN_MBS);
+            w.ue(25); // mb_type: I_PCM
+            w.align_zero();
+            for y in 0..16 {
+                for x in 0..16 {
+                    w.bits(8, sample(mb_x * 16 + x, mb_y * 16 + y) as u32);
+                }
+            }
+            for chroma in [100, 200] {
+                for _ in 0..64 {
+                    w.bits(8, chroma);
+                }
+            }
+        }
+        frame(&[w.finish_nal(0x65)])
+    }
+
+    fn encoded(data: &[u8], frame_id: u32) -> EncodedFrame<'_> {
+        EncodedFrame {
+            codec: swf::VideoCodec::H264,
+            data,
+            frame_id,
+        }
+    }
+
+    fn decoder() -> H264Decoder {
+        let mut decoder = H264Decoder::new();
+        decoder
+            .configure_decoder(&configuration())
+            .expect("Valid configuration");
+        decoder
+    }
+
+    #[test]
+    fn decodes_pcm_frame() {
+        let mut decoder = decoder();
+        let data = pcm_frame();
+        assert!(matches!(
+            decoder.preload_frame(encoded(&data, 0)),
+            Ok(FrameDependency::None)
+        ));
+        let frame = decoder
+            .decode_frame(encoded(&data, 0))
+            .expect("Decodable frame");
+
+        assert_eq!((frame.width(), frame.height()), (28, 32));
+        let data = frame.data();
+        for y in 0..32 {
+            for x in 0..28 {
+                assert_eq!(data[(y * 28 + x) as usize], sample(x, y));
+            }
+        }
+        let luma_size = 28 * 32;
+        assert!(data[luma_size..luma_size + 14 * 16]
+            .iter()
+            .all(|&s| s == 100));
+        assert!(data[luma_size + 14 * 16..].iter().all(|&s| s == 200));
+    }
+
+    #[test]
+    fn decodes_intra_16x16_frame() {
+        let mut decoder = decoder();
+        let mut w = BitWriter::default();
+        slice_header(&mut w, 2, true, 0);
+        for _ in 0..WIDTH_IN_MBS * HEIGHT_IN_MBS {
+            w.ue(3); // mb_type: I_16x16_2_0_0, DC prediction without residual
+            w.ue(0); // intra_chroma_pred_mode: DC
+            w.se(0); // mb_qp_delta
+            w.bit(true); // coeff_token of Intra16x16DCLevel: no coefficients
+        }
+        let data = frame(&[w.finish_nal(0x65)]);
+        let frame = decoder
+            .decode_frame(encoded(&data, 0))
+            .expect("Decodable frame");
+
+        // Without any neighbours, DC prediction falls back to the middle value.
+        assert!(frame.data().iter().all(|&s| s == 128));
+    }
+
+    #[test]
+    fn decodes_skipped_p_frame() {
+        let mut decoder = decoder();
+        let data = pcm_frame();
+        let first = decoder
+            .decode_frame(encoded(&data, 0))
+            .expect("Decodable frame");
+
+        // A P frame with every macroblock skipped is a copy of the previous one.
+        let mut w = BitWriter::default();
+        slice_header(&mut w, 0, false, 1);
+        w.ue(WIDTH_IN_MBS * HEIGHT_IN_MBS); // mb_skip_run
+        let data = frame(&[w.finish_nal(0x41)]);
+        assert!(matches!(
+            decoder.preload_frame(encoded(&data, 1)),
+            Ok(FrameDependency::Past)
+        ));
+        let second = decoder
+            .decode_frame(encoded(&data, 1))
+            .expect("Decodable frame");
+        assert_eq!(first.data(), second.data());
+    }
+
+    #[test]
+    fn reorder_depth() {
+        let parse = |nal: Vec<u8>| Sps::parse(&unescape_rbsp(&nal[1..])).expect("Valid SPS");
+
+        // Pictures are output in decoding order.
+        assert_eq!(parse(sps(2, None)).max_num_reorder_frames, 0);
+        // Otherwise, as many as the level allows, which is 16 for such a small picture.
+        assert_eq!(parse(sps(0, None)).max_num_reorder_frames, 16);
+        // Unless the VUI says how many there are.
+        assert_eq!(parse(sps(0, Some((2, 4)))).max_num_reorder_frames, 2);
+        assert_eq!(parse(sps(0, Some((3, 1)))).max_num_reorder_frames, 1);
+    }
+
+    #[test]
+    fn rejects_main_and_high_profiles() {
+        let mut decoder = H264Decoder::new();
+        // The configuration of tests/swfs/visual/video/h264/hsv.flv, a High profile stream.
+        let config = [
+            0x01, 0x64, 0x00, 0x0b, 0xff, 0xe1, 0x00, 0x18, 0x67, 0x64, 0x00, 0x0b, 0xac, 0xd9,
+            0x41, 0x01, 0x5a, 0x10, 0x00, 0x00, 0x03, 0x00, 0x10, 0x00, 0x00, 0x03, 0x00, 0x40,
+            0xf1, 0x42, 0x99, 0x60, 0x01, 0x00, 0x06, 0x68, 0xeb, 0xe3, 0xcb, 0x22, 0xc0, 0xfd,
+            0xf8, 0xf8, 0x00,
+        ];
+        assert!(matches!(
+            decoder.configure_decoder(&config),
+            Err(Error::UnsupportedCodec(VideoCodec::H264))
+        ));
+
+        // Its parameter sets are still rejected when they're sent along with the frames.
+        let sps = Sps::parse(&unescape_rbsp(&config[9..32])).expect("Valid SPS");
+        assert_eq!((sps.width_in_mbs, sps.height_in_mbs), (16, 10));
+        assert!(matches!(
+            sps.check_supported(),
+            Err(H264Error::Unsupported(_))
+        ));
+    }
+}
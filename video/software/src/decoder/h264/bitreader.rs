@@ -0,0 +1,139 @@
+use super::H264Error;
+
+/// Converts the payload of a NAL unit into its raw byte sequence payload,
+/// by removing all the "emulation prevention" bytes (the `0x03` in `0x000003`).
+pub fn unescape_rbsp(data: &[u8]) -> Vec<u8> {
+    let mut rbsp = Vec::with_capacity(data.len());
+    let mut zeros = 0;
+    for &byte in data {
+        if zeros >= 2 && byte == 3 {
+            zeros = 0;
+            continue;
+        }
+        if byte == 0 {
+            zeros += 1;
+        } else {
+            zeros = 0;
+        }
+        rbsp.push(byte);
+    }
+    rbsp
+}
+
+/// MSB-first bit reader over an RBSP, with Exp-Golomb helpers.
+pub struct BitReader<'a> {
+    data: &'a [u8],
+    /// Current position, in bits.
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub fn bits_left(&self) -> usize {
+        (self.data.len() * 8).saturating_sub(self.pos)
+    }
+
+    pub fn align(&mut self) {
+        self.pos = self.pos.div_ceil(8) * 8;
+    }
+
+    pub fn read_bit(&mut self) -> Result<bool, H264Error> {
+        let byte = *self
+            .data
+            .get(self.pos / 8)
+            .ok_or(H264Error::UnexpectedEndOfData)?;
+        let bit = (byte >> (7 - (self.pos % 8))) & 1;
+        self.pos += 1;
+        Ok(bit != 0)
+    }
+
+    pub fn read_bits(&mut self, count: u32) -> Result<u32, H264Error> {
+        debug_assert!(count <= 32);
+        let mut value = 0u32;
+        for _ in 0..count {
+            value = (value << 1) | self.read_bit()? as u32;
+        }
+        Ok(value)
+    }
+
+    /// Returns the next `count` bits without consuming them,
+    /// padding with zeroes past the end of the data.
+    pub fn peek_bits(&self, count: u32) -> u32 {
+        let mut value = 0u32;
+        for i in 0..count as usize {
+            let pos = self.pos + i;
+            let bit = self
+                .data
+                .get(pos / 8)
+                .map(|byte| (byte >> (7 - (pos % 8))) & 1)
+                .unwrap_or(0);
+            value = (value << 1) | bit as u32;
+        }
+        value
+    }
+
+    pub fn skip_bits(&mut self, count: usize) -> Result<(), H264Error> {
+        if count > self.bits_left() {
+            return Err(H264Error::UnexpectedEndOfData);
+        }
+        self.pos += count;
+        Ok(())
+    }
+
+    pub fn read_u8(&mut self, count: u32) -> Result<u8, H264Error> {
+        Ok(self.read_bits(count)? as u8)
+    }
+
+    pub fn read_flag(&mut self) -> Result<bool, H264Error> {
+        self.read_bit()
+    }
+
+    /// Reads an unsigned Exp-Golomb code, `ue(v)`.
+    pub fn read_ue(&mut self) -> Result<u32, H264Error> {
+        let mut leading_zeros = 0;
+        while !self.read_bit()? {
+            leading_zeros += 1;
+            if leading_zeros > 31 {
+                return Err(H264Error::InvalidData("Exp-Golomb code is too long"));
+            }
+        }
+        if leading_zeros == 0 {
+            return Ok(0);
+        }
+        let value = (1u64 << leading_zeros) - 1 + self.read_bits(leading_zeros)? as u64;
+        u32::try_from(value).map_err(|_| H264Error::InvalidData("Exp-Golomb code is too long"))
+    }
+
+    /// Reads a signed Exp-Golomb code, `se(v)`.
+    pub fn read_se(&mut self) -> Result<i32, H264Error> {
+        let code = self.read_ue()? as i64;
+        let value = if code & 1 == 1 {
+            (code + 1) / 2
+        } else {
+            -(code / 2)
+        };
+        Ok(value as i32)
+    }
+
+    /// Reads a truncated Exp-Golomb code, `te(v)`, with the given maximum value.
+    pub fn read_te(&mut self, max: u32) -> Result<u32, H264Error> {
+        if max > 1 {
+            self.read_ue()
+        } else {
+            Ok(!self.read_bit()? as u32)
+        }
+    }
+
+    /// Whether there is any more data before the `rbsp_stop_one_bit`.
+    pub fn more_rbsp_data(&self) -> bool {
+        let Some(last_byte_index) = self.data.iter().rposition(|&b| b != 0) else {
+            return false;
+        };
+        let last_byte = self.data[last_byte_index];
+        let stop_bit_pos = last_byte_index * 8 + 7 - last_byte.trailing_zeros() as usize;
+        self.pos < stop_bit_pos
+    }
+}
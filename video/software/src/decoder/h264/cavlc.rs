@@ -0,0 +1,305 @@
+//! Context-adaptive variable length coding of residual blocks (9.2).
+
+use super::bitreader::BitReader;
+use super::H264Error;
+use std::sync::OnceLock;
+
+/// Code lengths of `coeff_token` (Table 9-5), indexed by `total_coeff * 4 + trailing_ones`,
+/// for each of the four `nC` ranges.
+#[rustfmt::skip]
+const COEFF_TOKEN_LEN: [[u8; 68]; 4] = [[
+     1, 0, 0, 0,
+     6, 2, 0, 0,     8, 6, 3, 0,     9, 8, 7, 5,    10, 9, 8, 6,
+    11,10, 9, 7,    13,11,10, 8,    13,13,11, 9,    13,13,13,10,
+    14,14,13,11,    14,14,14,13,    15,15,14,14,    15,15,15,14,
+    16,15,15,15,    16,16,16,15,    16,16,16,16,    16,16,16,16,
+],[
+     2, 0, 0, 0,
+     6, 2, 0, 0,     6, 5, 3, 0,     7, 6, 6, 4,     8, 6, 6, 4,
+     8, 7, 7, 5,     9, 8, 8, 6,    11, 9, 9, 6,    11,11,11, 7,
+    12,11,11, 9,    12,12,12,11,    12,12,12,11,    13,13,13,12,
+    13,13,13,13,    13,14,13,13,    14,14,14,13,    14,14,14,14,
+],[
+     4, 0, 0, 0,
+     6, 4, 0, 0,     6, 5, 4, 0,     6, 5, 5, 4,     7, 5, 5, 4,
+     7, 5, 5, 4,     7, 6, 6, 4,     7, 6, 6, 4,     8, 7, 7, 5,
+     8, 8, 7, 6,     9, 8, 8, 7,     9, 9, 8, 8,     9, 9, 9, 8,
+    10, 9, 9, 9,    10,10,10,10,    10,10,10,10,    10,10,10,10,
+],[
+     6, 0, 0, 0,
+     6, 6, 0, 0,     6, 6, 6, 0,     6, 6, 6, 6,     6, 6, 6, 6,
+     6, 6, 6, 6,     6, 6, 6, 6,     6, 6, 6, 6,     6, 6, 6, 6,
+     6, 6, 6, 6,     6, 6, 6, 6,     6, 6, 6, 6,     6, 6, 6, 6,
+     6, 6, 6, 6,     6, 6, 6, 6,     6, 6, 6, 6,     6, 6, 6, 6,
+]];
+
+#[rustfmt::skip]
+const COEFF_TOKEN_CODE: [[u8; 68]; 4] = [[
+     1, 0, 0, 0,
+     5, 1, 0, 0,     7, 4, 1, 0,     7, 6, 5, 3,     7, 6, 5, 3,
+     7, 6, 5, 4,    15, 6, 5, 4,    11,14, 5, 4,     8,10,13, 4,
+    15,14, 9, 4,    11,10,13,12,    15,14, 9,12,    11,10,13, 8,
+    15, 1, 9,12,    11,14,13, 8,     7,10, 9,12,     4, 6, 5, 8,
+],[
+     3, 0, 0, 0,
+    11, 2, 0, 0,     7, 7, 3, 0,     7,10, 9, 5,     7, 6, 5, 4,
+     4, 6, 5, 6,     7, 6, 5, 8,    15, 6, 5, 4,    11,14,13, 4,
+    15,10, 9, 4,    11,14,13,12,     8,10, 9, 8,    15,14,13,12,
+    11,10, 9,12,     7,11, 6, 8,     9, 8,10, 1,     7, 6, 5, 4,
+],[
+    15, 0, 0, 0,
+    15,14, 0, 0,    11,15,13, 0,     8,12,14,12,    15,10,11,11,
+    11, 8, 9,10,     9,14,13, 9,     8,10, 9, 8,    15,14,13,13,
+    11,14,10,12,    15,10,13,12,    11,14, 9,12,     8,10,13, 8,
+    13, 7, 9,12,     9,12,11,10,     5, 8, 7, 6,     1, 4, 3, 2,
+],[
+     3, 0, 0, 0,
+     0, 1, 0, 0,     4, 5, 6, 0,     8, 9,10,11,    12,13,14,15,
+    16,17,18,19,    20,21,22,23,    24,25,26,27,    28,29,30,31,
+    32,33,34,35,    36,37,38,39,    40,41,42,43,    44,45,46,47,
+    48,49,50,51,    52,53,54,55,    56,57,58,59,    60,61,62,63,
+]];
+
+/// `coeff_token` for chroma DC blocks (`nC == -1`).
+const CHROMA_DC_COEFF_TOKEN_LEN: [u8; 20] =
+    [2, 0, 0, 0, 6, 1, 0, 0, 6, 6, 3, 0, 6, 7, 7, 6, 6, 8, 8, 7];
+const CHROMA_DC_COEFF_TOKEN_CODE: [u8; 20] =
+    [1, 0, 0, 0, 7, 1, 0, 0, 4, 6, 1, 0, 3, 3, 2, 5, 2, 3, 2, 0];
+
+/// `total_zeros` for 4x4 blocks (Tables 9-7 and 9-8), indexed by `total_coeff - 1`.
+#[rustfmt::skip]
+const TOTAL_ZEROS_LEN: [&[u8]; 15] = [
+    &[1, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 9],
+    &[3, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 6, 6, 6, 6],
+    &[4, 3, 3, 3, 4, 4, 3, 3, 4, 5, 5, 6, 5, 6],
+    &[5, 3, 4, 4, 3, 3, 3, 4, 3, 4, 5, 5, 5],
+    &[4, 4, 4, 3, 3, 3, 3, 3, 4, 5, 4, 5],
+    &[6, 5, 3, 3, 3, 3, 3, 3, 4, 3, 6],
+    &[6, 5, 3, 3, 3, 2, 3, 4, 3, 6],
+    &[6, 4, 5, 3, 2, 2, 3, 3, 6],
+    &[6, 6, 4, 2, 2, 3, 2, 5],
+    &[5, 5, 3, 2, 2, 2, 4],
+    &[4, 4, 3, 3, 1, 3],
+    &[4, 4, 2, 1, 3],
+    &[3, 3, 1, 2],
+    &[2, 2, 1],
+    &[1, 1],
+];
+
+#[rustfmt::skip]
+const TOTAL_ZEROS_CODE: [&[u8]; 15] = [
+    &[1, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 3, 2, 1],
+    &[7, 6, 5, 4, 3, 5, 4, 3, 2, 3, 2, 3, 2, 1, 0],
+    &[5, 7, 6, 5, 4, 3, 4, 3, 2, 3, 2, 1, 1, 0],
+    &[3, 7, 5, 4, 6, 5, 4, 3, 3, 2, 2, 1, 0],
+    &[5, 4, 3, 7, 6, 5, 4, 3, 2, 1, 1, 0],
+    &[1, 1, 7, 6, 5, 4, 3, 2, 1, 1, 0],
+    &[1, 1, 5, 4, 3, 3, 2, 1, 1, 0],
+    &[1, 1, 1, 3, 3, 2, 2, 1, 0],
+    &[1, 0, 1, 3, 2, 1, 1, 1],
+    &[1, 0, 1, 3, 2, 1, 1],
+    &[0, 1, 1, 2, 1, 3],
+    &[0, 1, 1, 1, 1],
+    &[0, 1, 1, 1],
+    &[0, 1, 1],
+    &[0, 1],
+];
+
+/// `total_zeros` for 2x2 chroma DC blocks (Table 9-9a).
+const CHROMA_DC_TOTAL_ZEROS_LEN: [&[u8]; 3] = [&[1, 2, 3, 3], &[1, 2, 2], &[1, 1]];
+const CHROMA_DC_TOTAL_ZEROS_CODE: [&[u8]; 3] = [&[1, 1, 1, 0], &[1, 1, 0], &[1, 0]];
+
+/// `run_before` (Table 9-10), indexed by `min(zeros_left, 7) - 1`.
+#[rustfmt::skip]
+const RUN_BEFORE_LEN: [&[u8]; 7] = [
+    &[1, 1],
+    &[1, 2, 2],
+    &[2, 2, 2, 2],
+    &[2, 2, 2, 3, 3],
+    &[2, 2, 3, 3, 3, 3],
+    &[2, 3, 3, 3, 3, 3, 3],
+    &[3, 3, 3, 3, 3, 3, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+];
+
+#[rustfmt::skip]
+const RUN_BEFORE_CODE: [&[u8]; 7] = [
+    &[1, 0],
+    &[1, 1, 0],
+    &[3, 2, 1, 0],
+    &[3, 2, 1, 1, 0],
+    &[3, 2, 3, 2, 1, 0],
+    &[3, 0, 1, 3, 2, 5, 4],
+    &[7, 6, 5, 4, 3, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1],
+];
+
+/// A lookup table for a prefix code, indexed by the next `max_len` bits of the stream.
+struct Vlc {
+    max_len: u32,
+    /// `(length, symbol)` pairs, zero length marking invalid codes.
+    entries: Vec<(u8, u8)>,
+}
+
+impl Vlc {
+    fn new(lengths: &[u8], codes: &[u8]) -> Self {
+        let max_len = *lengths.iter().max().unwrap_or(&1) as u32;
+        let mut entries = vec![(0, 0); 1 << max_len];
+        for (symbol, (&len, &code)) in lengths.iter().zip(codes).enumerate() {
+            if len == 0 {
+                continue;
+            }
+            let shift = max_len - len as u32;
+            let start = (code as usize) << shift;
+            for entry in &mut entries[start..start + (1 << shift)] {
+                *entry = (len, symbol as u8);
+            }
+        }
+        Self { max_len, entries }
+    }
+
+    fn read(&self, r: &mut BitReader) -> Result<u8, H264Error> {
+        let (len, symbol) = self.entries[r.peek_bits(self.max_len) as usize];
+        if len == 0 {
+            return Err(H264Error::InvalidData("Invalid variable length code"));
+        }
+        r.skip_bits(len as usize)?;
+        Ok(symbol)
+    }
+}
+
+struct Tables {
+    coeff_token: [Vlc; 4],
+    chroma_dc_coeff_token: Vlc,
+    total_zeros: Vec<Vlc>,
+    chroma_dc_total_zeros: Vec<Vlc>,
+    run_before: Vec<Vlc>,
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| Tables {
+        coeff_token: std::array::from_fn(|i| Vlc::new(&COEFF_TOKEN_LEN[i], &COEFF_TOKEN_CODE[i])),
+        chroma_dc_coeff_token: Vlc::new(&CHROMA_DC_COEFF_TOKEN_LEN, &CHROMA_DC_COEFF_TOKEN_CODE),
+        total_zeros: (0..15)
+            .map(|i| Vlc::new(TOTAL_ZEROS_LEN[i], TOTAL_ZEROS_CODE[i]))
+            .collect(),
+        chroma_dc_total_zeros: (0..3)
+            .map(|i| Vlc::new(CHROMA_DC_TOTAL_ZEROS_LEN[i], CHROMA_DC_TOTAL_ZEROS_CODE[i]))
+            .collect(),
+        run_before: (0..7)
+            .map(|i| Vlc::new(RUN_BEFORE_LEN[i], RUN_BEFORE_CODE[i]))
+            .collect(),
+    })
+}
+
+/// Reads a `residual_block_cavlc()` into `coeffs` (in scan order), returning `total_coeff`.
+///
+/// `nc` selects the `coeff_token` table, with `-1` meaning a 2x2 chroma DC block.
+/// `coeffs.len()` is the maximum number of coefficients of the block (4, 15, or 16).
+pub fn read_residual_block(
+    r: &mut BitReader,
+    nc: i32,
+    coeffs: &mut [i32],
+) -> Result<u8, H264Error> {
+    let tables = tables();
+    let max_num_coeff = coeffs.len();
+    coeffs.fill(0);
+
+    let token = match nc {
+        -1 => tables.chroma_dc_coeff_token.read(r)?,
+        0..=1 => tables.coeff_token[0].read(r)?,
+        2..=3 => tables.coeff_token[1].read(r)?,
+        4..=7 => tables.coeff_token[2].read(r)?,
+        _ => tables.coeff_token[3].read(r)?,
+    };
+    let total_coeff = (token >> 2) as usize;
+    let trailing_ones = (token & 3) as usize;
+    if total_coeff == 0 {
+        return Ok(0);
+    }
+    if total_coeff > max_num_coeff {
+        return Err(H264Error::InvalidData("Too many coefficients in block"));
+    }
+
+    let mut levels = [0i32; 16];
+    let mut suffix_length = if total_coeff > 10 && trailing_ones < 3 {
+        1
+    } else {
+        0
+    };
+    for (i, level) in levels.iter_mut().enumerate().take(total_coeff) {
+        if i < trailing_ones {
+            *level = if r.read_bit()? { -1 } else { 1 };
+            continue;
+        }
+
+        let mut level_prefix = 0;
+        while !r.read_bit()? {
+            level_prefix += 1;
+            if level_prefix > 24 {
+                return Err(H264Error::InvalidData("Level prefix is too long"));
+            }
+        }
+        let mut level_code = (level_prefix.min(15) as i32) << suffix_length;
+        let level_suffix_size = if level_prefix == 14 && suffix_length == 0 {
+            4
+        } else if level_prefix >= 15 {
+            level_prefix - 3
+        } else {
+            suffix_length
+        };
+        if level_suffix_size > 0 {
+            level_code += r.read_bits(level_suffix_size)? as i32;
+        }
+        if level_prefix >= 15 && suffix_length == 0 {
+            level_code += 15;
+        }
+        if level_prefix >= 16 {
+            level_code += (1 << (level_prefix - 3)) - 4096;
+        }
+        if i == trailing_ones && trailing_ones < 3 {
+            level_code += 2;
+        }
+        *level = if level_code % 2 == 0 {
+            (level_code + 2) >> 1
+        } else {
+            (-level_code - 1) >> 1
+        };
+
+        if suffix_length == 0 {
+            suffix_length = 1;
+        }
+        if level.abs() > (3 << (suffix_length - 1)) && suffix_length < 6 {
+            suffix_length += 1;
+        }
+    }
+
+    let mut zeros_left = if total_coeff < max_num_coeff {
+        if nc == -1 {
+            tables.chroma_dc_total_zeros[total_coeff - 1].read(r)? as usize
+        } else {
+            tables.total_zeros[total_coeff - 1].read(r)? as usize
+        }
+    } else {
+        0
+    };
+    if total_coeff + zeros_left > max_num_coeff {
+        return Err(H264Error::InvalidData("Too many zeros in block"));
+    }
+
+    // Levels are stored in reverse scan order, starting from the highest frequency.
+    let mut position = total_coeff + zeros_left;
+    for (i, &level) in levels.iter().enumerate().take(total_coeff) {
+        position -= 1;
+        coeffs[position] = level;
+        if i + 1 < total_coeff && zeros_left > 0 {
+            let run_before = tables.run_before[zeros_left.min(7) - 1].read(r)? as usize;
+            if run_before > zeros_left {
+                return Err(H264Error::InvalidData("Invalid run of zeros"));
+            }
+            zeros_left -= run_before;
+            position -= run_before;
+        }
+    }
+
+    Ok(total_coeff as u8)
+}
@@ -0,0 +1,286 @@
+//! Deblocking filter process (8.7).
+
+use super::macroblock::{chroma_qp, CurrentPicture};
+use super::picture::BlockMotion;
+
+/// `α'` indexed by `indexA` (Table 8-16).
+#[rustfmt::skip]
+const ALPHA: [u8; 52] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    4, 4, 5, 6, 7, 8, 9, 10, 12, 13, 15, 17, 20, 22, 25, 28,
+    32, 36, 40, 45, 50, 56, 63, 71, 80, 90, 101, 113, 127, 144, 162, 182,
+    203, 226, 255, 255,
+];
+
+/// `β'` indexed by `indexB` (Table 8-16).
+#[rustfmt::skip]
+const BETA: [u8; 52] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 6, 6, 7, 7, 8, 8,
+    9, 9, 10, 10, 11, 11, 12, 12, 13, 13, 14, 14, 15, 15, 16, 16,
+    17, 17, 18, 18,
+];
+
+/// `tC0'` indexed by `indexA` and `bS - 1` (Table 8-17).
+#[rustfmt::skip]
+const TC0: [[u8; 3]; 52] = [
+    [0, 0, 0], [0, 0, 0], [0, 0, 0], [0, 0, 0], [0, 0, 0], [0, 0, 0], [0, 0, 0], [0, 0, 0],
+    [0, 0, 0], [0, 0, 0], [0, 0, 0], [0, 0, 0], [0, 0, 0], [0, 0, 0], [0, 0, 0], [0, 0, 0],
+    [0, 0, 0], [0, 0, 1], [0, 0, 1], [0, 0, 1], [0, 0, 1], [0, 1, 1], [0, 1, 1], [1, 1, 1],
+    [1, 1, 1], [1, 1, 1], [1, 1, 1], [1, 1, 2], [1, 1, 2], [1, 1, 2], [1, 1, 2], [1, 2, 3],
+    [1, 2, 3], [2, 2, 3], [2, 2, 4], [2, 3, 4], [2, 3, 4], [3, 3, 5], [3, 4, 6], [3, 4, 6],
+    [4, 5, 7], [4, 5, 8], [4, 6, 9], [5, 7, 10], [6, 8, 11], [6, 8, 13], [7, 10, 14], [8, 11, 16],
+    [9, 12, 18], [10, 13, 20], [11, 15, 23], [13, 17, 25],
+];
+
+/// Whether two blocks are predicted differently enough for their edge to be filtered with `bS = 1`.
+fn motion_differs(p: &BlockMotion, q: &BlockMotion) -> bool {
+    let refs = |m: &BlockMotion| {
+        [0, 1].map(|list| (m.ref_idx[list] >= 0).then_some((m.ref_id[list], m.mv[list])))
+    };
+    let far = |a: [i16; 2], b: [i16; 2]| {
+        (a[0] as i32 - b[0] as i32).abs() >= 4 || (a[1] as i32 - b[1] as i32).abs() >= 4
+    };
+    match (refs(p), refs(q)) {
+        ([Some(p0), None], [Some(q0), None])
+        | ([Some(p0), None], [None, Some(q0)])
+        | ([None, Some(p0)], [Some(q0), None])
+        | ([None, Some(p0)], [None, Some(q0)]) => p0.0 != q0.0 || far(p0.1, q0.1),
+        ([Some(p0), Some(p1)], [Some(q0), Some(q1)]) => {
+            if p0.0 == p1.0 {
+                // Both motion vectors reference the same picture.
+                if q0.0 != p0.0 || q1.0 != p0.0 {
+                    return true;
+                }
+                (far(p0.1, q0.1) || far(p1.1, q1.1)) && (far(p0.1, q1.1) || far(p1.1, q0.1))
+            } else if p0.0 == q0.0 && p1.0 == q1.0 {
+                far(p0.1, q0.1) || far(p1.1, q1.1)
+            } else if p0.0 == q1.0 && p1.0 == q0.0 {
+                far(p0.1, q1.1) || far(p1.1, q0.1)
+            } else {
+                true
+            }
+        }
+        _ => true,
+    }
+}
+
+impl CurrentPicture {
+    /// Boundary filtering strength between luma blocks `p` and `q` (8.7.2.1), given as
+    /// macroblock addresses and block positions within the picture.
+    fn boundary_strength(
+        &self,
+        (p_mb, p_block): (usize, (usize, usize)),
+        (q_mb, q_block): (usize, (usize, usize)),
+        mb_edge: bool,
+    ) -> u8 {
+        let p_info = &self.mbs[p_mb];
+        let q_info = &self.mbs[q_mb];
+        if p_info.intra || q_info.intra {
+            return if mb_edge { 4 } else { 3 };
+        }
+        let local = |(x, y): (usize, usize)| (y % 4) * 4 + x % 4;
+        if p_info.total_coeff[local(p_block)] != 0 || q_info.total_coeff[local(q_block)] != 0 {
+            return 2;
+        }
+        let p_motion = self.picture.motion_at(p_block.0, p_block.1);
+        let q_motion = self.picture.motion_at(q_block.0, q_block.1);
+        motion_differs(p_motion, q_motion) as u8
+    }
+
+    /// Applies the deblocking filter to the whole picture.
+    pub fn deblock(&mut self) {
+        let width = self.picture.width_in_mbs;
+        let height = self.picture.height_in_mbs;
+        for mb_y in 0..height {
+            for mb_x in 0..width {
+                self.deblock_mb(mb_x, mb_y);
+            }
+        }
+    }
+
+    fn deblock_mb(&mut self, mb_x: usize, mb_y: usize) {
+        let width = self.picture.width_in_mbs;
+        let mb_addr = mb_y * width + mb_x;
+        let info = self.mbs[mb_addr];
+        let Some(params) = self.slices.get(info.slice_num as usize).copied() else {
+            // Not decoded at all.
+            return;
+        };
+        if params.disable_deblocking_filter_idc == 1 {
+            return;
+        }
+        let same_slice_only = params.disable_deblocking_filter_idc == 2;
+        let filter_left =
+            mb_x > 0 && !(same_slice_only && self.mbs[mb_addr - 1].slice_num != info.slice_num);
+        let filter_top =
+            mb_y > 0 && !(same_slice_only && self.mbs[mb_addr - width].slice_num != info.slice_num);
+
+        // dir 0: vertical edges, dir 1: horizontal edges.
+        for dir in 0..2 {
+            for edge in 0..4 {
+                if edge == 0 && !(if dir == 0 { filter_left } else { filter_top }) {
+                    continue;
+                }
+                let p_mb = match (edge, dir) {
+                    (0, 0) => mb_addr - 1,
+                    (0, _) => mb_addr - width,
+                    _ => mb_addr,
+                };
+                let mut bs = [0u8; 4];
+                for (i, bs) in bs.iter_mut().enumerate() {
+                    let (qx, qy) = if dir == 0 { (edge, i) } else { (i, edge) };
+                    let q_block = (mb_x * 4 + qx, mb_y * 4 + qy);
+                    let p_block = if dir == 0 {
+                        (q_block.0 - 1, q_block.1)
+                    } else {
+                        (q_block.0, q_block.1 - 1)
+                    };
+                    *bs = self.boundary_strength((p_mb, p_block), (mb_addr, q_block), edge == 0);
+                }
+                if bs == [0; 4] {
+                    continue;
+                }
+
+                let p_info = self.mbs[p_mb];
+                let luma_qp = (p_info.qp as i32 + info.qp as i32 + 1) >> 1;
+                let stride = width * 16;
+                let (x, y) = (mb_x * 16, mb_y * 16);
+                let (q0, step, along) = if dir == 0 {
+                    (y * stride + x + edge * 4, 1, stride)
+                } else {
+                    ((y + edge * 4) * stride + x, stride, 1)
+                };
+                let line_bs: [u8; 16] = std::array::from_fn(|i| bs[i / 4]);
+                filter_edge(
+                    &mut self.picture.luma,
+                    q0,
+                    step,
+                    along,
+                    &line_bs,
+                    luma_qp,
+                    &params,
+                    false,
+                );
+
+                // For 4:2:0, only every other luma edge has a corresponding chroma edge.
+                if edge % 2 == 1 {
+                    continue;
+                }
+                let chroma_stride = width * 8;
+                let (cx, cy) = (mb_x * 8, mb_y * 8);
+                let (q0, step, along) = if dir == 0 {
+                    (cy * chroma_stride + cx + edge * 2, 1, chroma_stride)
+                } else {
+                    ((cy + edge * 2) * chroma_stride + cx, chroma_stride, 1)
+                };
+                let line_bs: [u8; 8] = std::array::from_fn(|i| bs[i / 2]);
+                for component in 0..2 {
+                    let offset = params.chroma_qp_index_offset[component];
+                    let qp = (chroma_qp(p_info.qp as i32, offset)
+                        + chroma_qp(info.qp as i32, offset)
+                        + 1)
+                        >> 1;
+                    let plane = if component == 0 {
+                        &mut self.picture.cb
+                    } else {
+                        &mut self.picture.cr
+                    };
+                    filter_edge(plane, q0, step, along, &line_bs, qp, &params, true);
+                }
+            }
+        }
+    }
+}
+
+/// Filters the sample lines crossing an edge (8.7.2.3 and 8.7.2.4).
+///
+/// `q0` is the index of the first `q0` sample, `step` the distance between samples across
+/// the edge, and `along` the distance between successive lines.
+#[allow(clippy::too_many_arguments)]
+fn filter_edge(
+    plane: &mut [u8],
+    q0: usize,
+    step: usize,
+    along: usize,
+    line_bs: &[u8],
+    qp_avg: i32,
+    params: &super::macroblock::DeblockParams,
+    chroma: bool,
+) {
+    let index_a = (qp_avg + params.alpha_c0_offset).clamp(0, 51) as usize;
+    let index_b = (qp_avg + params.beta_offset).clamp(0, 51) as usize;
+    let alpha = ALPHA[index_a] as i32;
+    let beta = BETA[index_b] as i32;
+    if alpha == 0 || beta == 0 {
+        return;
+    }
+
+    for (line, &bs) in line_bs.iter().enumerate() {
+        if bs == 0 {
+            continue;
+        }
+        let base = q0 + line * along;
+        let at = |i: isize| (base as isize + i * step as isize) as usize;
+        let sample = |plane: &[u8], i: isize| plane[at(i)] as i32;
+        let (p0, p1, q0v, q1) = (
+            sample(plane, -1),
+            sample(plane, -2),
+            sample(plane, 0),
+            sample(plane, 1),
+        );
+        if (p0 - q0v).abs() >= alpha || (p1 - p0).abs() >= beta || (q1 - q0v).abs() >= beta {
+            continue;
+        }
+
+        if chroma {
+            if bs == 4 {
+                plane[at(-1)] = ((2 * p1 + p0 + q1 + 2) >> 2) as u8;
+                plane[at(0)] = ((2 * q1 + q0v + p1 + 2) >> 2) as u8;
+            } else {
+                let tc = TC0[index_a][bs as usize - 1] as i32 + 1;
+                let delta = ((((q0v - p0) << 2) + (p1 - q1) + 4) >> 3).clamp(-tc, tc);
+                plane[at(-1)] = (p0 + delta).clamp(0, 255) as u8;
+                plane[at(0)] = (q0v - delta).clamp(0, 255) as u8;
+            }
+            continue;
+        }
+
+        let (p2, q2) = (sample(plane, -3), sample(plane, 2));
+        let ap = (p2 - p0).abs();
+        let aq = (q2 - q0v).abs();
+        if bs == 4 {
+            let strong = (p0 - q0v).abs() < (alpha >> 2) + 2;
+            if ap < beta && strong {
+                let p3 = sample(plane, -4);
+                plane[at(-1)] = ((p2 + 2 * p1 + 2 * p0 + 2 * q0v + q1 + 4) >> 3) as u8;
+                plane[at(-2)] = ((p2 + p1 + p0 + q0v + 2) >> 2) as u8;
+                plane[at(-3)] = ((2 * p3 + 3 * p2 + p1 + p0 + q0v + 4) >> 3) as u8;
+            } else {
+                plane[at(-1)] = ((2 * p1 + p0 + q1 + 2) >> 2) as u8;
+            }
+            if aq < beta && strong {
+                let q3 = sample(plane, 3);
+                plane[at(0)] = ((p1 + 2 * p0 + 2 * q0v + 2 * q1 + q2 + 4) >> 3) as u8;
+                plane[at(1)] = ((p0 + q0v + q1 + q2 + 2) >> 2) as u8;
+                plane[at(2)] = ((2 * q3 + 3 * q2 + q1 + q0v + p0 + 4) >> 3) as u8;
+            } else {
+                plane[at(0)] = ((2 * q1 + q0v + p1 + 2) >> 2) as u8;
+            }
+        } else {
+            let tc0 = TC0[index_a][bs as usize - 1] as i32;
+            let tc = tc0 + (ap < beta) as i32 + (aq < beta) as i32;
+            let delta = ((((q0v - p0) << 2) + (p1 - q1) + 4) >> 3).clamp(-tc, tc);
+            plane[at(-1)] = (p0 + delta).clamp(0, 255) as u8;
+            plane[at(0)] = (q0v - delta).clamp(0, 255) as u8;
+            if ap < beta {
+                let delta = ((p2 + ((p0 + q0v + 1) >> 1) - (p1 << 1)) >> 1).clamp(-tc0, tc0);
+                plane[at(-2)] = (p1 + delta) as u8;
+            }
+            if aq < beta {
+                let delta = ((q2 + ((p0 + q0v + 1) >> 1) - (q1 << 1)) >> 1).clamp(-tc0, tc0);
+                plane[at(1)] = (q1 + delta) as u8;
+            }
+        }
+    }
+}
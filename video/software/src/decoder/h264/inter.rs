@@ -0,0 +1,182 @@
+//! Fractional sample interpolation and weighted sample prediction (8.4.2).
+
+/// A read-only view of one plane of a reference picture.
+pub struct Plane<'a> {
+    pub data: &'a [u8],
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Plane<'_> {
+    /// Sample at the given position, with coordinates clamped to the picture (8.4.2.2.1).
+    fn at(&self, x: i32, y: i32) -> i32 {
+        let x = x.clamp(0, self.width as i32 - 1) as usize;
+        let y = y.clamp(0, self.height as i32 - 1) as usize;
+        self.data[y * self.width + x] as i32
+    }
+
+    fn tap6(&self, x: i32, y: i32, dx: i32, dy: i32) -> i32 {
+        let s = |i: i32| self.at(x + i * dx, y + i * dy);
+        s(-2) - 5 * s(-1) + 20 * s(0) + 20 * s(1) - 5 * s(2) + s(3)
+    }
+
+    /// Intermediate value of the horizontal half sample to the right of `(x, y)`.
+    fn b1(&self, x: i32, y: i32) -> i32 {
+        self.tap6(x, y, 1, 0)
+    }
+
+    fn b(&self, x: i32, y: i32) -> i32 {
+        clip((self.b1(x, y) + 16) >> 5)
+    }
+
+    /// The vertical half sample below `(x, y)`.
+    fn h(&self, x: i32, y: i32) -> i32 {
+        clip((self.tap6(x, y, 0, 1) + 16) >> 5)
+    }
+
+    /// The center half sample, below and to the right of `(x, y)`.
+    fn j(&self, x: i32, y: i32) -> i32 {
+        let b1 = |i: i32| self.b1(x, y + i);
+        let j1 = b1(-2) - 5 * b1(-1) + 20 * b1(0) + 20 * b1(1) - 5 * b1(2) + b1(3);
+        clip((j1 + 512) >> 10)
+    }
+}
+
+fn clip(value: i32) -> i32 {
+    value.clamp(0, 255)
+}
+
+/// Luma sample interpolation (8.4.2.2.1) of a `width` x `height` block, whose top left
+/// full sample is at `(x, y)`, offset by the given quarter sample fractions.
+#[allow(clippy::too_many_arguments)]
+pub fn predict_luma(
+    plane: &Plane,
+    x: i32,
+    y: i32,
+    frac_x: i32,
+    frac_y: i32,
+    width: usize,
+    height: usize,
+    out: &mut [u8],
+) {
+    for row in 0..height {
+        for column in 0..width {
+            let (x, y) = (x + column as i32, y + row as i32);
+            let g = || plane.at(x, y);
+            let value = match (frac_x, frac_y) {
+                (0, 0) => g(),
+                (1, 0) => (g() + plane.b(x, y) + 1) >> 1,
+                (2, 0) => plane.b(x, y),
+                (3, 0) => (plane.at(x + 1, y) + plane.b(x, y) + 1) >> 1,
+                (0, 1) => (g() + plane.h(x, y) + 1) >> 1,
+                (0, 2) => plane.h(x, y),
+                (0, 3) => (plane.at(x, y + 1) + plane.h(x, y) + 1) >> 1,
+                (1, 1) => (plane.b(x, y) + plane.h(x, y) + 1) >> 1,
+                (3, 1) => (plane.b(x, y) + plane.h(x + 1, y) + 1) >> 1,
+                (1, 3) => (plane.h(x, y) + plane.b(x, y + 1) + 1) >> 1,
+                (3, 3) => (plane.h(x + 1, y) + plane.b(x, y + 1) + 1) >> 1,
+                (2, 1) => (plane.b(x, y) + plane.j(x, y) + 1) >> 1,
+                (2, 2) => plane.j(x, y),
+                (2, 3) => (plane.j(x, y) + plane.b(x, y + 1) + 1) >> 1,
+                (1, 2) => (plane.h(x, y) + plane.j(x, y) + 1) >> 1,
+                (3, 2) => (plane.j(x, y) + plane.h(x + 1, y) + 1) >> 1,
+                _ => unreachable!("Invalid quarter sample fraction"),
+            };
+            out[row * width + column] = value as u8;
+        }
+    }
+}
+
+/// Chroma sample interpolation (8.4.2.2.2), with eighth sample fractions.
+#[allow(clippy::too_many_arguments)]
+pub fn predict_chroma(
+    plane: &Plane,
+    x: i32,
+    y: i32,
+    frac_x: i32,
+    frac_y: i32,
+    width: usize,
+    height: usize,
+    out: &mut [u8],
+) {
+    for row in 0..height {
+        for column in 0..width {
+            let (x, y) = (x + column as i32, y + row as i32);
+            let value = ((8 - frac_x) * (8 - frac_y) * plane.at(x, y)
+                + frac_x * (8 - frac_y) * plane.at(x + 1, y)
+                + (8 - frac_x) * frac_y * plane.at(x, y + 1)
+                + frac_x * frac_y * plane.at(x + 1, y + 1)
+                + 32)
+                >> 6;
+            out[row * width + column] = value as u8;
+        }
+    }
+}
+
+/// How the predictions from each reference list are combined (8.4.2.3).
+#[derive(Clone, Copy, Debug)]
+pub enum Weighting {
+    Default,
+    /// Explicit or implicit weights, with `log2_denom`, and `(weight, offset)` for each list.
+    Weighted {
+        log2_denom: u32,
+        weights: [(i32, i32); 2],
+    },
+}
+
+/// Combines the predictions of list 0 and/or list 1 into `out`.
+pub fn weighted_prediction(
+    weighting: Weighting,
+    l0: Option<&[u8]>,
+    l1: Option<&[u8]>,
+    out: &mut [u8],
+) {
+    match (weighting, l0, l1) {
+        (Weighting::Default, Some(l0), Some(l1)) => {
+            for ((out, &a), &b) in out.iter_mut().zip(l0).zip(l1) {
+                *out = ((a as u32 + b as u32 + 1) >> 1) as u8;
+            }
+        }
+        (Weighting::Default, Some(single), None) | (Weighting::Default, None, Some(single)) => {
+            out.copy_from_slice(&single[..out.len()]);
+        }
+        (
+            Weighting::Weighted {
+                log2_denom,
+                weights: [(w0, o0), (w1, o1)],
+            },
+            Some(l0),
+            Some(l1),
+        ) => {
+            let round = 1 << log2_denom;
+            for ((out, &a), &b) in out.iter_mut().zip(l0).zip(l1) {
+                let value = ((a as i32 * w0 + b as i32 * w1 + round) >> (log2_denom + 1))
+                    + ((o0 + o1 + 1) >> 1);
+                *out = clip(value) as u8;
+            }
+        }
+        (
+            Weighting::Weighted {
+                log2_denom,
+                weights,
+            },
+            l0,
+            l1,
+        ) => {
+            let (single, (w, o)) = match (l0, l1) {
+                (Some(l0), _) => (l0, weights[0]),
+                (None, Some(l1)) => (l1, weights[1]),
+                (None, None) => return,
+            };
+            for (out, &a) in out.iter_mut().zip(single) {
+                let value = if log2_denom >= 1 {
+                    ((a as i32 * w + (1 << (log2_denom - 1))) >> log2_denom) + o
+                } else {
+                    a as i32 * w + o
+                };
+                *out = clip(value) as u8;
+            }
+        }
+        (Weighting::Default, None, None) => {}
+    }
+}
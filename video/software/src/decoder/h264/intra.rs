@@ -0,0 +1,201 @@
+//! Intra prediction (8.3).
+
+/// The neighbouring samples of a block being predicted.
+pub struct Neighbors<'a> {
+    /// The samples above the block, including the ones above and to the right
+    /// (already substituted if those weren't available).
+    pub top: Option<&'a [u8]>,
+    pub left: Option<&'a [u8]>,
+    pub top_left: Option<u8>,
+}
+
+impl Neighbors<'_> {
+    /// Sample at `(x, -1)` or `(-1, y)`, as used in the equations of the specification.
+    fn p(&self, x: i32, y: i32) -> i32 {
+        if x == -1 && y == -1 {
+            self.top_left.unwrap_or(128) as i32
+        } else if y == -1 {
+            self.top.map(|top| top[x as usize]).unwrap_or(128) as i32
+        } else {
+            self.left.map(|left| left[y as usize]).unwrap_or(128) as i32
+        }
+    }
+}
+
+fn sum(samples: &[u8]) -> u32 {
+    samples.iter().map(|&s| s as u32).sum()
+}
+
+/// Predicts a 4x4 luma block (8.3.1.2). `top` must hold 8 samples.
+pub fn predict_4x4(mode: u8, n: &Neighbors, out: &mut [u8; 16]) {
+    let p = |x: i32, y: i32| n.p(x, y);
+    for y in 0..4i32 {
+        for x in 0..4i32 {
+            let value = match mode {
+                0 => p(x, -1),
+                1 => p(-1, y),
+                2 => match (n.top, n.left) {
+                    (Some(top), Some(left)) => ((sum(&top[..4]) + sum(left) + 4) >> 3) as i32,
+                    (None, Some(left)) => ((sum(left) + 2) >> 2) as i32,
+                    (Some(top), None) => ((sum(&top[..4]) + 2) >> 2) as i32,
+                    (None, None) => 128,
+                },
+                3 => {
+                    if x == 3 && y == 3 {
+                        (p(6, -1) + 3 * p(7, -1) + 2) >> 2
+                    } else {
+                        (p(x + y, -1) + 2 * p(x + y + 1, -1) + p(x + y + 2, -1) + 2) >> 2
+                    }
+                }
+                4 => {
+                    if x > y {
+                        (p(x - y - 2, -1) + 2 * p(x - y - 1, -1) + p(x - y, -1) + 2) >> 2
+                    } else if x < y {
+                        (p(-1, y - x - 2) + 2 * p(-1, y - x - 1) + p(-1, y - x) + 2) >> 2
+                    } else {
+                        (p(0, -1) + 2 * p(-1, -1) + p(-1, 0) + 2) >> 2
+                    }
+                }
+                5 => {
+                    let z = 2 * x - y;
+                    if z >= 0 && z % 2 == 0 {
+                        (p(x - (y >> 1) - 1, -1) + p(x - (y >> 1), -1) + 1) >> 1
+                    } else if z > 0 {
+                        (p(x - (y >> 1) - 2, -1)
+                            + 2 * p(x - (y >> 1) - 1, -1)
+                            + p(x - (y >> 1), -1)
+                            + 2)
+                            >> 2
+                    } else if z == -1 {
+                        (p(-1, 0) + 2 * p(-1, -1) + p(0, -1) + 2) >> 2
+                    } else {
+                        (p(-1, y - 1) + 2 * p(-1, y - 2) + p(-1, y - 3) + 2) >> 2
+                    }
+                }
+                6 => {
+                    let z = 2 * y - x;
+                    if z >= 0 && z % 2 == 0 {
+                        (p(-1, y - (x >> 1) - 1) + p(-1, y - (x >> 1)) + 1) >> 1
+                    } else if z > 0 {
+                        (p(-1, y - (x >> 1) - 2)
+                            + 2 * p(-1, y - (x >> 1) - 1)
+                            + p(-1, y - (x >> 1))
+                            + 2)
+                            >> 2
+                    } else if z == -1 {
+                        (p(-1, 0) + 2 * p(-1, -1) + p(0, -1) + 2) >> 2
+                    } else {
+                        (p(x - 1, -1) + 2 * p(x - 2, -1) + p(x - 3, -1) + 2) >> 2
+                    }
+                }
+                7 => {
+                    if y % 2 == 0 {
+                        (p(x + (y >> 1), -1) + p(x + (y >> 1) + 1, -1) + 1) >> 1
+                    } else {
+                        (p(x + (y >> 1), -1)
+                            + 2 * p(x + (y >> 1) + 1, -1)
+                            + p(x + (y >> 1) + 2, -1)
+                            + 2)
+                            >> 2
+                    }
+                }
+                _ => {
+                    let z = x + 2 * y;
+                    if z < 5 && z % 2 == 0 {
+                        (p(-1, y + (x >> 1)) + p(-1, y + (x >> 1) + 1) + 1) >> 1
+                    } else if z < 5 {
+                        (p(-1, y + (x >> 1))
+                            + 2 * p(-1, y + (x >> 1) + 1)
+                            + p(-1, y + (x >> 1) + 2)
+                            + 2)
+                            >> 2
+                    } else if z == 5 {
+                        (p(-1, 2) + 3 * p(-1, 3) + 2) >> 2
+                    } else {
+                        p(-1, 3)
+                    }
+                }
+            };
+            out[(y * 4 + x) as usize] = value as u8;
+        }
+    }
+}
+
+/// Predicts a 16x16 luma block (8.3.3), or an 8x8 chroma block (8.3.4) if `size` is 8.
+///
+/// Modes are given in the luma numbering: 0 vertical, 1 horizontal, 2 DC, 3 plane.
+pub fn predict_square(mode: u8, size: usize, n: &Neighbors, out: &mut [u8], stride: usize) {
+    let p = |x: i32, y: i32| n.p(x, y);
+    match mode {
+        0 => {
+            for y in 0..size {
+                for x in 0..size {
+                    out[y * stride + x] = p(x as i32, -1) as u8;
+                }
+            }
+        }
+        1 => {
+            for y in 0..size {
+                for x in 0..size {
+                    out[y * stride + x] = p(-1, y as i32) as u8;
+                }
+            }
+        }
+        2 if size == 16 => {
+            let dc = match (n.top, n.left) {
+                (Some(top), Some(left)) => (sum(&top[..16]) + sum(&left[..16]) + 16) >> 5,
+                (None, Some(left)) => (sum(&left[..16]) + 8) >> 4,
+                (Some(top), None) => (sum(&top[..16]) + 8) >> 4,
+                (None, None) => 128,
+            };
+            for y in 0..16 {
+                out[y * stride..y * stride + 16].fill(dc as u8);
+            }
+        }
+        2 => predict_chroma_dc(n, out, stride),
+        _ => {
+            let half = size as i32 / 2;
+            let mut h = 0;
+            let mut v = 0;
+            for i in 0..half {
+                h += (i + 1) * (p(half + i, -1) - p(half - 2 - i, -1));
+                v += (i + 1) * (p(-1, half + i) - p(-1, half - 2 - i));
+            }
+            let last = size as i32 - 1;
+            let a = 16 * (p(-1, last) + p(last, -1));
+            let (b, c) = if size == 16 {
+                ((5 * h + 32) >> 6, (5 * v + 32) >> 6)
+            } else {
+                ((34 * h + 32) >> 6, (34 * v + 32) >> 6)
+            };
+            let center = half - 1;
+            for y in 0..size as i32 {
+                for x in 0..size as i32 {
+                    let value = (a + b * (x - center) + c * (y - center) + 16) >> 5;
+                    out[y as usize * stride + x as usize] = value.clamp(0, 255) as u8;
+                }
+            }
+        }
+    }
+}
+
+/// DC prediction of the four 4x4 blocks of an 8x8 chroma block (8.3.4.1 to 8.3.4.3).
+fn predict_chroma_dc(n: &Neighbors, out: &mut [u8], stride: usize) {
+    for (block_y, block_x) in [(0, 0), (0, 4), (4, 0), (4, 4)] {
+        let top = n.top.map(|top| sum(&top[block_x..block_x + 4]));
+        let left = n.left.map(|left| sum(&left[block_y..block_y + 4]));
+        let dc = match (block_x, block_y, top, left) {
+            (4, 0, Some(top), _) => (top + 2) >> 2,
+            (0, 4, _, Some(left)) => (left + 2) >> 2,
+            (4, 0, None, Some(sum)) | (0, 4, Some(sum), None) => (sum + 2) >> 2,
+            (_, _, Some(top), Some(left)) => (top + left + 4) >> 3,
+            (_, _, None, Some(left)) => (left + 2) >> 2,
+            (_, _, Some(top), None) => (top + 2) >> 2,
+            (_, _, None, None) => 128,
+        };
+        for y in 0..4 {
+            let row = (block_y + y) * stride + block_x;
+            out[row..row + 4].fill(dc as u8);
+        }
+    }
+}
@@ -0,0 +1,1159 @@
+//! Macroblock layer parsing (7.3.5) and reconstruction of the decoded samples.
+
+use super::bitreader::BitReader;
+use super::cavlc::read_residual_block;
+use super::inter::{predict_chroma, predict_luma, weighted_prediction, Weighting};
+use super::intra::{predict_4x4, predict_square, Neighbors};
+use super::mvpred::Shape;
+use super::params::{Pps, Sps};
+use super::picture::{BlockMotion, ListEntry, Picture};
+use super::slice::{SliceHeader, SliceType};
+use super::transform::{
+    chroma_dc_dequant, dequant_4x4, idct_4x4_add, luma_dc_dequant, LevelScale, ZIGZAG_4X4,
+};
+use super::H264Error;
+
+/// Maps `coded_block_pattern` codes to values, for Intra 4x4 macroblocks (Table 9-4).
+const GOLOMB_TO_INTRA_CBP: [u8; 48] = [
+    47, 31, 15, 0, 23, 27, 29, 30, 7, 11, 13, 14, 39, 43, 45, 46, 16, 3, 5, 10, 12, 19, 21, 26, 28,
+    35, 37, 42, 44, 1, 2, 4, 8, 17, 18, 20, 24, 6, 9, 22, 25, 32, 33, 34, 36, 40, 38, 41,
+];
+
+/// Maps `coded_block_pattern` codes to values, for inter macroblocks (Table 9-4).
+const GOLOMB_TO_INTER_CBP: [u8; 48] = [
+    0, 16, 1, 2, 4, 8, 32, 3, 5, 10, 12, 15, 47, 7, 11, 13, 14, 6, 9, 31, 35, 37, 42, 44, 33, 34,
+    36, 40, 39, 43, 45, 46, 17, 18, 20, 24, 19, 21, 26, 28, 23, 27, 29, 30, 22, 25, 38, 41,
+];
+
+/// `QPc` as a function of `qPi` (Table 8-15), for `qPi >= 30`.
+const CHROMA_QP: [u8; 22] = [
+    29, 30, 31, 32, 32, 33, 34, 34, 35, 35, 36, 36, 37, 37, 37, 38, 38, 38, 39, 39, 39, 39,
+];
+
+pub fn chroma_qp(qp: i32, offset: i32) -> i32 {
+    let qpi = (qp + offset).clamp(0, 51);
+    if qpi < 30 {
+        qpi
+    } else {
+        CHROMA_QP[qpi as usize - 30] as i32
+    }
+}
+
+/// Index of the 4x4 luma block at the given position in decoding order (6.4.3).
+pub fn block_index(bx: usize, by: usize) -> usize {
+    (by / 2) * 8 + (bx / 2) * 4 + (by % 2) * 2 + bx % 2
+}
+
+/// Position of the 4x4 luma block with the given index, in units of 4x4 blocks.
+pub fn block_position(index: usize) -> (usize, usize) {
+    let (quadrant, sub) = (index / 4, index % 4);
+    ((quadrant % 2) * 2 + sub % 2, (quadrant / 2) * 2 + sub / 2)
+}
+
+/// Marks macroblocks of the current picture that haven't been decoded yet.
+pub const NOT_DECODED: u32 = u32::MAX;
+
+/// Information about a decoded macroblock, needed by its neighbours and the deblocking filter.
+#[derive(Clone, Copy, Debug)]
+pub struct MbInfo {
+    pub slice_num: u32,
+    pub intra: bool,
+    pub intra_4x4: bool,
+    pub pcm: bool,
+    /// `QPy`, or 0 for I_PCM macroblocks.
+    pub qp: u8,
+    /// `total_coeff` of the 16 luma blocks (in raster order), then the 4 Cb and 4 Cr blocks.
+    pub total_coeff: [u8; 24],
+    /// `Intra4x4PredMode` of each block (in raster order).
+    pub intra_modes: [u8; 16],
+}
+
+impl Default for MbInfo {
+    fn default() -> Self {
+        Self {
+            slice_num: NOT_DECODED,
+            intra: false,
+            intra_4x4: false,
+            pcm: false,
+            qp: 0,
+            total_coeff: [0; 24],
+            intra_modes: [2; 16],
+        }
+    }
+}
+
+/// Deblocking filter settings of a slice (7.4.3).
+#[derive(Clone, Copy, Debug)]
+pub struct DeblockParams {
+    pub disable_deblocking_filter_idc: u32,
+    pub alpha_c0_offset: i32,
+    pub beta_offset: i32,
+    pub chroma_qp_index_offset: [i32; 2],
+}
+
+/// Everything needed to decode the macroblocks of a slice.
+pub struct SliceContext<'a> {
+    pub header: &'a SliceHeader,
+    pub sps: &'a Sps,
+    pub pps: &'a Pps,
+    pub lists: [Vec<Option<ListEntry>>; 2],
+    pub level_scale: LevelScale,
+}
+
+impl SliceContext<'_> {
+    pub fn reference(&self, list: usize, ref_idx: i8) -> Result<&ListEntry, H264Error> {
+        self.lists[list]
+            .get(ref_idx as usize)
+            .and_then(|entry| entry.as_ref())
+            .ok_or(H264Error::MissingReference)
+    }
+
+    /// How list 0 and list 1 predictions are combined for the given reference indices.
+    fn weighting(&self, ref_idx: [i8; 2], current_poc: i32, chroma: Option<usize>) -> Weighting {
+        let pred_l0 = ref_idx[0] >= 0;
+        let pred_l1 = ref_idx[1] >= 0;
+        if let Some(table) = &self.header.pred_weight_table {
+            let log2_denom = match chroma {
+                None => table.luma_log2_denom,
+                Some(_) => table.chroma_log2_denom,
+            };
+            let weights = [0, 1].map(|list| {
+                let weights = (ref_idx[list] >= 0)
+                    .then(|| table.weights[list].get(ref_idx[list] as usize))
+                    .flatten();
+                match (weights, chroma) {
+                    (Some(w), None) => (w.luma_weight, w.luma_offset),
+                    (Some(w), Some(c)) => (w.chroma_weight[c], w.chroma_offset[c]),
+                    (None, _) => (1 << log2_denom, 0),
+                }
+            });
+            return Weighting::Weighted {
+                log2_denom,
+                weights,
+            };
+        }
+
+        if self.header.slice_type == SliceType::B
+            && self.pps.weighted_bipred_idc == 2
+            && pred_l0
+            && pred_l1
+        {
+            // Implicit weights (8.4.2.3.1), based on the temporal distances.
+            let (Ok(ref0), Ok(ref1)) =
+                (self.reference(0, ref_idx[0]), self.reference(1, ref_idx[1]))
+            else {
+                return Weighting::Default;
+            };
+            let poc0 = ref0.picture.poc;
+            let poc1 = ref1.picture.poc;
+            let mut w1 = 32;
+            if poc1 != poc0 && !ref0.long_term && !ref1.long_term {
+                let scale = distance_scale_factor(current_poc, poc0, poc1) >> 2;
+                if (-64..=128).contains(&scale) {
+                    w1 = scale;
+                }
+            }
+            return Weighting::Weighted {
+                log2_denom: 5,
+                weights: [(64 - w1, 0), (w1, 0)],
+            };
+        }
+        Weighting::Default
+    }
+}
+
+/// `DistScaleFactor` (8.4.1.2.3).
+pub fn distance_scale_factor(current_poc: i32, poc0: i32, poc1: i32) -> i32 {
+    let tb = (current_poc - poc0).clamp(-128, 127);
+    let td = (poc1 - poc0).clamp(-128, 127);
+    if td == 0 {
+        return 256;
+    }
+    let tx = (16384 + (td / 2).abs()) / td;
+    ((tb * tx + 32) >> 6).clamp(-1024, 1023)
+}
+
+/// The residual coefficients of a macroblock, in raster order within each block.
+#[derive(Default)]
+struct Residual {
+    luma: [[i32; 16]; 16],
+    luma_dc: Option<[i32; 16]>,
+    chroma_dc: [[i32; 4]; 2],
+    chroma_ac: [[[i32; 16]; 4]; 2],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum IntraKind {
+    Intra4x4,
+    Intra16x16 { mode: u8 },
+}
+
+/// A motion partition of an inter macroblock, in units of 4x4 blocks.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Partition {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+    /// Bit 0 for list 0 and bit 1 for list 1 prediction.
+    pub pred: u8,
+    /// Whether this is a direct predicted 8x8 sub-macroblock.
+    pub direct: bool,
+    pub ref_idx: [i8; 2],
+    pub mvd: [[i32; 2]; 2],
+    pub shape: Shape,
+}
+
+/// The macroblock being decoded, not yet stored in the picture.
+pub struct CurrentMb {
+    pub x: usize,
+    pub y: usize,
+    pub info: MbInfo,
+    /// Whether the motion of each 4x4 block (in raster order) was already derived.
+    pub motion_done: [bool; 16],
+}
+
+/// A picture that is being decoded, together with the state of all its macroblocks.
+pub struct CurrentPicture {
+    pub picture: Picture,
+    pub mbs: Vec<MbInfo>,
+    pub slices: Vec<DeblockParams>,
+}
+
+impl CurrentPicture {
+    pub fn new(picture: Picture) -> Self {
+        let mb_count = picture.width_in_mbs * picture.height_in_mbs;
+        Self {
+            picture,
+            mbs: vec![MbInfo::default(); mb_count],
+            slices: Vec::new(),
+        }
+    }
+
+    /// The neighbouring macroblock at the given offset, if it's available (6.4.8).
+    pub fn neighbor_mb(&self, mb: &CurrentMb, dx: isize, dy: isize) -> Option<&MbInfo> {
+        let x = mb.x.checked_add_signed(dx)?;
+        let y = mb.y.checked_add_signed(dy)?;
+        if x >= self.picture.width_in_mbs || y >= self.picture.height_in_mbs {
+            return None;
+        }
+        let info = &self.mbs[y * self.picture.width_in_mbs + x];
+        (info.slice_num == mb.info.slice_num).then_some(info)
+    }
+
+    /// The neighbouring macroblock at the given offset, if it's available for intra prediction.
+    fn intra_neighbor_mb(
+        &self,
+        mb: &CurrentMb,
+        dx: isize,
+        dy: isize,
+        constrained_intra_pred: bool,
+    ) -> Option<&MbInfo> {
+        self.neighbor_mb(mb, dx, dy)
+            .filter(|info| info.intra || !constrained_intra_pred)
+    }
+
+    pub fn decode_slice(&mut self, r: &mut BitReader, ctx: &SliceContext) -> Result<(), H264Error> {
+        let mb_count = self.mbs.len();
+        let mut mb_addr = ctx.header.first_mb_in_slice as usize;
+        let slice_num = self.slices.len() as u32;
+        self.slices.push(DeblockParams {
+            disable_deblocking_filter_idc: ctx.header.disable_deblocking_filter_idc,
+            alpha_c0_offset: ctx.header.slice_alpha_c0_offset,
+            beta_offset: ctx.header.slice_beta_offset,
+            chroma_qp_index_offset: [
+                ctx.pps.chroma_qp_index_offset,
+                ctx.pps.second_chroma_qp_index_offset,
+            ],
+        });
+
+        let mut qp = ctx.header.slice_qp;
+        let mut more_data = true;
+        while more_data {
+            if ctx.header.slice_type != SliceType::I {
+                let skip_run = r.read_ue()? as usize;
+                for _ in 0..skip_run {
+                    if mb_addr >= mb_count {
+                        return Err(H264Error::InvalidData("Too many macroblocks in slice"));
+                    }
+                    let mut mb = self.begin_mb(mb_addr, slice_num, qp);
+                    self.decode_skip(&mut mb, ctx)?;
+                    self.finish_mb(mb);
+                    mb_addr += 1;
+                }
+                if skip_run > 0 && !r.more_rbsp_data() {
+                    break;
+                }
+            }
+            if mb_addr >= mb_count {
+                return Err(H264Error::InvalidData("Too many macroblocks in slice"));
+            }
+            let mut mb = self.begin_mb(mb_addr, slice_num, qp);
+            self.decode_macroblock(r, &mut mb, ctx, &mut qp)?;
+            self.finish_mb(mb);
+            mb_addr += 1;
+            more_data = r.more_rbsp_data();
+        }
+        Ok(())
+    }
+
+    fn begin_mb(&self, mb_addr: usize, slice_num: u32, qp: i32) -> CurrentMb {
+        let width = self.picture.width_in_mbs;
+        CurrentMb {
+            x: mb_addr % width,
+            y: mb_addr / width,
+            info: MbInfo {
+                slice_num,
+                qp: qp as u8,
+                ..Default::default()
+            },
+            motion_done: [false; 16],
+        }
+    }
+
+    fn finish_mb(&mut self, mb: CurrentMb) {
+        let mb_addr = mb.y * self.picture.width_in_mbs + mb.x;
+        self.mbs[mb_addr] = mb.info;
+        self.picture.mb_intra[mb_addr] = mb.info.intra;
+    }
+
+    fn set_intra_motion(&mut self, mb: &mut CurrentMb) {
+        let intra = BlockMotion {
+            ref_idx: [-1, -1],
+            ..Default::default()
+        };
+        for by in 0..4 {
+            for bx in 0..4 {
+                self.set_block_motion(mb, bx, by, intra);
+            }
+        }
+    }
+
+    pub fn set_block_motion(
+        &mut self,
+        mb: &mut CurrentMb,
+        bx: usize,
+        by: usize,
+        motion: BlockMotion,
+    ) {
+        let stride = self.picture.width_in_mbs * 4;
+        self.picture.motion[(mb.y * 4 + by) * stride + mb.x * 4 + bx] = motion;
+        mb.motion_done[by * 4 + bx] = true;
+    }
+
+    fn decode_skip(&mut self, mb: &mut CurrentMb, ctx: &SliceContext) -> Result<(), H264Error> {
+        if ctx.header.slice_type == SliceType::B {
+            self.derive_direct_motion(mb, ctx, None)?;
+        } else {
+            self.derive_p_skip_motion(mb, ctx)?;
+        }
+        self.predict_inter(mb, ctx)
+    }
+
+    fn decode_macroblock(
+        &mut self,
+        r: &mut BitReader,
+        mb: &mut CurrentMb,
+        ctx: &SliceContext,
+        qp: &mut i32,
+    ) -> Result<(), H264Error> {
+        let mb_type = r.read_ue()?;
+        let intra_mb_type = match ctx.header.slice_type {
+            SliceType::I => Some(mb_type),
+            SliceType::P => mb_type.checked_sub(5),
+            SliceType::B => mb_type.checked_sub(23),
+        };
+
+        let Some(intra_mb_type) = intra_mb_type else {
+            return self.decode_inter_macroblock(r, mb, ctx, mb_type, qp);
+        };
+
+        mb.info.intra = true;
+        self.set_intra_motion(mb);
+        let (kind, cbp) = match intra_mb_type {
+            0 => (IntraKind::Intra4x4, None),
+            1..=24 => {
+                let t = intra_mb_type - 1;
+                let cbp_luma = if t >= 12 { 15 } else { 0 };
+                let cbp_chroma = (t / 4) % 3;
+                (
+                    IntraKind::Intra16x16 {
+                        mode: (t % 4) as u8,
+                    },
+                    Some((cbp_luma | (cbp_chroma << 4)) as u8),
+                )
+            }
+            25 => return self.decode_pcm(r, mb),
+            _ => return Err(H264Error::InvalidData("Invalid macroblock type")),
+        };
+
+        if kind == IntraKind::Intra4x4 {
+            mb.info.intra_4x4 = true;
+            self.read_intra_4x4_modes(r, mb, ctx.pps.constrained_intra_pred)?;
+        }
+        let chroma_mode = r.read_ue()?;
+        if chroma_mode > 3 {
+            return Err(H264Error::InvalidData("Invalid chroma prediction mode"));
+        }
+        let cbp = match cbp {
+            Some(cbp) => cbp,
+            None => read_cbp(r, &GOLOMB_TO_INTRA_CBP)?,
+        };
+
+        let mut residual = Residual::default();
+        let is_16x16 = matches!(kind, IntraKind::Intra16x16 { .. });
+        if cbp != 0 || is_16x16 {
+            self.read_qp_delta(r, qp)?;
+            mb.info.qp = *qp as u8;
+            self.read_residual(r, mb, cbp, is_16x16, &mut residual)?;
+        }
+
+        let constrained = ctx.pps.constrained_intra_pred;
+        match kind {
+            IntraKind::Intra4x4 => {
+                for index in 0..16 {
+                    let (bx, by) = block_position(index);
+                    self.predict_intra_4x4(mb, bx, by, constrained);
+                    self.add_luma_residual(mb, ctx, &mut residual, bx, by, true);
+                }
+            }
+            IntraKind::Intra16x16 { mode } => {
+                self.predict_intra_16x16(mb, mode, constrained)?;
+                self.reconstruct_luma(mb, ctx, &mut residual, true);
+            }
+        }
+        // intra_chroma_pred_mode is DC, horizontal, vertical, plane.
+        let chroma_mode = [2, 1, 0, 3][chroma_mode as usize];
+        self.predict_intra_chroma(mb, chroma_mode, constrained)?;
+        self.reconstruct_chroma(mb, ctx, &mut residual, true);
+        Ok(())
+    }
+
+    fn decode_pcm(&mut self, r: &mut BitReader, mb: &mut CurrentMb) -> Result<(), H264Error> {
+        r.align();
+        let stride = self.picture.width_in_mbs * 16;
+        for y in 0..16 {
+            for x in 0..16 {
+                self.picture.luma[(mb.y * 16 + y) * stride + mb.x * 16 + x] = r.read_u8(8)?;
+            }
+        }
+        let chroma_stride = stride / 2;
+        for plane in [&mut self.picture.cb, &mut self.picture.cr] {
+            for y in 0..8 {
+                for x in 0..8 {
+                    plane[(mb.y * 8 + y) * chroma_stride + mb.x * 8 + x] = r.read_u8(8)?;
+                }
+            }
+        }
+        mb.info.pcm = true;
+        mb.info.qp = 0;
+        mb.info.total_coeff = [16; 24];
+        Ok(())
+    }
+
+    fn read_qp_delta(&self, r: &mut BitReader, qp: &mut i32) -> Result<(), H264Error> {
+        let delta = r.read_se()?;
+        if !(-26..=25).contains(&delta) {
+            return Err(H264Error::InvalidData("Invalid QP delta"));
+        }
+        *qp = (*qp + delta + 52) % 52;
+        Ok(())
+    }
+
+    fn read_intra_4x4_modes(
+        &self,
+        r: &mut BitReader,
+        mb: &mut CurrentMb,
+        constrained_intra_pred: bool,
+    ) -> Result<(), H264Error> {
+        for index in 0..16 {
+            let (bx, by) = block_position(index);
+            let neighbor_mode = |dx: isize, dy: isize, local: usize| -> Option<u8> {
+                let info = if (dx < 0 && bx == 0) || (dy < 0 && by == 0) {
+                    let info = self.neighbor_mb(mb, dx, dy)?;
+                    if !info.intra && constrained_intra_pred {
+                        return None;
+                    }
+                    info
+                } else {
+                    &mb.info
+                };
+                Some(if info.intra_4x4 {
+                    info.intra_modes[local]
+                } else {
+                    2
+                })
+            };
+            let left = neighbor_mode(-1, 0, by * 4 + (bx + 3) % 4);
+            let top = neighbor_mode(0, -1, ((by + 3) % 4) * 4 + bx);
+            let predicted = match (left, top) {
+                (Some(left), Some(top)) => left.min(top),
+                _ => 2,
+            };
+            let mode = if r.read_flag()? {
+                predicted
+            } else {
+                let rem = r.read_u8(3)?;
+                if rem < predicted {
+                    rem
+                } else {
+                    rem + 1
+                }
+            };
+            mb.info.intra_modes[by * 4 + bx] = mode;
+        }
+        Ok(())
+    }
+
+    fn decode_inter_macroblock(
+        &mut self,
+        r: &mut BitReader,
+        mb: &mut CurrentMb,
+        ctx: &SliceContext,
+        mb_type: u32,
+        qp: &mut i32,
+    ) -> Result<(), H264Error> {
+        let partitions = self.read_inter_prediction(r, ctx, mb_type)?;
+        let cbp = read_cbp(r, &GOLOMB_TO_INTER_CBP)?;
+
+        match &partitions {
+            None => self.derive_direct_motion(mb, ctx, None)?,
+            Some(partitions) => {
+                for partition in partitions {
+                    if partition.direct {
+                        let quadrant = (partition.y / 2) * 2 + partition.x / 2;
+                        self.derive_direct_motion(mb, ctx, Some(quadrant))?;
+                    } else {
+                        self.derive_partition_motion(mb, ctx, partition)?;
+                    }
+                }
+            }
+        }
+
+        let mut residual = Residual::default();
+        if cbp != 0 {
+            self.read_qp_delta(r, qp)?;
+            mb.info.qp = *qp as u8;
+            self.read_residual(r, mb, cbp, false, &mut residual)?;
+        }
+
+        self.predict_inter(mb, ctx)?;
+        self.reconstruct_luma(mb, ctx, &mut residual, false);
+        self.reconstruct_chroma(mb, ctx, &mut residual, false);
+        Ok(())
+    }
+
+    /// Reads `mb_pred()` or `sub_mb_pred()` of an inter macroblock.
+    /// Returns `None` for B_Direct_16x16 macroblocks.
+    fn read_inter_prediction(
+        &self,
+        r: &mut BitReader,
+        ctx: &SliceContext,
+        mb_type: u32,
+    ) -> Result<Option<Vec<Partition>>, H264Error> {
+        let is_b = ctx.header.slice_type == SliceType::B;
+        let partition = |x, y, width, height, pred, shape| Partition {
+            x,
+            y,
+            width,
+            height,
+            pred,
+            shape,
+            ..Default::default()
+        };
+
+        let mut partitions: Vec<Partition>;
+        let mut sub_mb_ref_read = [true; 4];
+        let mut is_8x8 = false;
+        if !is_b {
+            partitions = match mb_type {
+                0 => vec![partition(0, 0, 4, 4, 1, Shape::Other)],
+                1 => vec![
+                    partition(0, 0, 4, 2, 1, Shape::Upper16x8),
+                    partition(0, 2, 4, 2, 1, Shape::Lower16x8),
+                ],
+                2 => vec![
+                    partition(0, 0, 2, 4, 1, Shape::Left8x16),
+                    partition(2, 0, 2, 4, 1, Shape::Right8x16),
+                ],
+                3 | 4 => {
+                    is_8x8 = true;
+                    if mb_type == 4 {
+                        sub_mb_ref_read = [false; 4];
+                    }
+                    Vec::new()
+                }
+                _ => return Err(H264Error::InvalidData("Invalid macroblock type")),
+            };
+        } else {
+            partitions = match mb_type {
+                0 => return Ok(None),
+                1..=3 => vec![partition(0, 0, 4, 4, mb_type as u8, Shape::Other)],
+                4..=21 => {
+                    const PREDS: [(u8, u8); 9] = [
+                        (1, 1),
+                        (2, 2),
+                        (1, 2),
+                        (2, 1),
+                        (1, 3),
+                        (2, 3),
+                        (3, 1),
+                        (3, 2),
+                        (3, 3),
+                    ];
+                    let (pred0, pred1) = PREDS[(mb_type as usize - 4) / 2];
+                    if mb_type & 1 == 0 {
+                        vec![
+                            partition(0, 0, 4, 2, pred0, Shape::Upper16x8),
+                            partition(0, 2, 4, 2, pred1, Shape::Lower16x8),
+                        ]
+                    } else {
+                        vec![
+                            partition(0, 0, 2, 4, pred0, Shape::Left8x16),
+                            partition(2, 0, 2, 4, pred1, Shape::Right8x16),
+                        ]
+                    }
+                }
+                22 => {
+                    is_8x8 = true;
+                    Vec::new()
+                }
+                _ => return Err(H264Error::InvalidData("Invalid macroblock type")),
+            };
+        }
+
+        let num_active = ctx.header.num_ref_idx_active;
+        if is_8x8 {
+            // sub_mb_pred()
+            let mut sub_partitions: [Vec<Partition>; 4] = Default::default();
+            for (i, sub) in sub_partitions.iter_mut().enumerate() {
+                let (x, y) = ((i % 2) * 2, (i / 2) * 2);
+                let sub_mb_type = r.read_ue()?;
+                let (pred, shape) = if is_b {
+                    match sub_mb_type {
+                        0 => {
+                            *sub = vec![Partition {
+                                x,
+                                y,
+                                width: 2,
+                                height: 2,
+                                direct: true,
+                                ..Default::default()
+                            }];
+                            continue;
+                        }
+                        1..=3 => (sub_mb_type as u8, (2, 2)),
+                        4..=9 => (
+                            [1, 1, 2, 2, 3, 3][sub_mb_type as usize - 4],
+                            if sub_mb_type % 2 == 0 { (2, 1) } else { (1, 2) },
+                        ),
+                        10..=12 => (sub_mb_type as u8 - 9, (1, 1)),
+                        _ => return Err(H264Error::InvalidData("Invalid sub-macroblock type")),
+                    }
+                } else {
+                    match sub_mb_type {
+                        0 => (1, (2, 2)),
+                        1 => (1, (2, 1)),
+                        2 => (1, (1, 2)),
+                        3 => (1, (1, 1)),
+                        _ => return Err(H264Error::InvalidData("Invalid sub-macroblock type")),
+                    }
+                };
+                let (width, height) = shape;
+                for sub_y in (0..2).step_by(height) {
+                    for sub_x in (0..2).step_by(width) {
+                        sub.push(partition(
+                            x + sub_x,
+                            y + sub_y,
+                            width,
+                            height,
+                            pred,
+                            Shape::Other,
+                        ));
+                    }
+                }
+            }
+
+            for (list, &num_active) in num_active.iter().enumerate() {
+                for (i, sub) in sub_partitions.iter_mut().enumerate() {
+                    let Some(first) = sub.first() else {
+                        continue;
+                    };
+                    if first.direct || first.pred & (1 << list) == 0 {
+                        continue;
+                    }
+                    let ref_idx = if num_active > 1 && sub_mb_ref_read[i] {
+                        read_ref_idx(r, num_active)?
+                    } else {
+                        0
+                    };
+                    for partition in sub.iter_mut() {
+                        partition.ref_idx[list] = ref_idx;
+                    }
+                }
+            }
+            partitions = sub_partitions.into_iter().flatten().collect();
+        } else {
+            for (list, &num_active) in num_active.iter().enumerate() {
+                for partition in &mut partitions {
+                    if partition.pred & (1 << list) != 0 && num_active > 1 {
+                        partition.ref_idx[list] = read_ref_idx(r, num_active)?;
+                    }
+                }
+            }
+        }
+
+        for list in 0..2 {
+            for partition in &mut partitions {
+                if !partition.direct && partition.pred & (1 << list) != 0 {
+                    partition.mvd[list] = [r.read_se()?, r.read_se()?];
+                }
+            }
+        }
+        for partition in &mut partitions {
+            for list in 0..2 {
+                if partition.direct || partition.pred & (1 << list) == 0 {
+                    partition.ref_idx[list] = -1;
+                }
+            }
+        }
+        Ok(Some(partitions))
+    }
+
+    /// `nC` for the `coeff_token` of a block (9.2.1). `offset` selects the luma
+    /// (0), Cb (16) or Cr (20) blocks, whose grid is `size` blocks wide.
+    fn predict_nc(&self, mb: &CurrentMb, offset: usize, size: usize, bx: usize, by: usize) -> i32 {
+        let count = |dx: isize, dy: isize, x: usize, y: usize| -> Option<i32> {
+            let info = if (dx < 0 && bx == 0) || (dy < 0 && by == 0) {
+                self.neighbor_mb(mb, dx, dy)?
+            } else {
+                &mb.info
+            };
+            Some(info.total_coeff[offset + y * size + x] as i32)
+        };
+        let left = count(-1, 0, (bx + size - 1) % size, by);
+        let top = count(0, -1, bx, (by + size - 1) % size);
+        match (left, top) {
+            (Some(left), Some(top)) => (left + top + 1) >> 1,
+            (Some(n), None) | (None, Some(n)) => n,
+            (None, None) => 0,
+        }
+    }
+
+    fn read_residual(
+        &self,
+        r: &mut BitReader,
+        mb: &mut CurrentMb,
+        cbp: u8,
+        is_16x16: bool,
+        residual: &mut Residual,
+    ) -> Result<(), H264Error> {
+        let mut coeffs = [0; 16];
+        if is_16x16 {
+            let nc = self.predict_nc(mb, 0, 4, 0, 0);
+            read_residual_block(r, nc, &mut coeffs)?;
+            let mut dc = [0; 16];
+            for (scan_pos, &coeff) in coeffs.iter().enumerate() {
+                dc[ZIGZAG_4X4[scan_pos]] = coeff;
+            }
+            residual.luma_dc = Some(dc);
+        }
+
+        for index in 0..16 {
+            let (bx, by) = block_position(index);
+            if cbp & (1 << (index / 4)) == 0 {
+                continue;
+            }
+            let nc = self.predict_nc(mb, 0, 4, bx, by);
+            let block = &mut residual.luma[by * 4 + bx];
+            let total_coeff = if is_16x16 {
+                let total_coeff = read_residual_block(r, nc, &mut coeffs[..15])?;
+                for (scan_pos, &coeff) in coeffs[..15].iter().enumerate() {
+                    block[ZIGZAG_4X4[scan_pos + 1]] = coeff;
+                }
+                total_coeff
+            } else {
+                let total_coeff = read_residual_block(r, nc, &mut coeffs)?;
+                for (scan_pos, &coeff) in coeffs.iter().enumerate() {
+                    block[ZIGZAG_4X4[scan_pos]] = coeff;
+                }
+                total_coeff
+            };
+            mb.info.total_coeff[by * 4 + bx] = total_coeff;
+        }
+
+        let cbp_chroma = cbp >> 4;
+        if cbp_chroma > 2 {
+            return Err(H264Error::InvalidData("Invalid coded block pattern"));
+        }
+        if cbp_chroma != 0 {
+            for component in 0..2 {
+                read_residual_block(r, -1, &mut residual.chroma_dc[component])?;
+            }
+        }
+        if cbp_chroma == 2 {
+            for component in 0..2 {
+                for index in 0..4 {
+                    let (bx, by) = (index % 2, index / 2);
+                    let offset = 16 + component * 4;
+                    let nc = self.predict_nc(mb, offset, 2, bx, by);
+                    let total_coeff = read_residual_block(r, nc, &mut coeffs[..15])?;
+                    let block = &mut residual.chroma_ac[component][index];
+                    for (scan_pos, &coeff) in coeffs[..15].iter().enumerate() {
+                        block[ZIGZAG_4X4[scan_pos + 1]] = coeff;
+                    }
+                    mb.info.total_coeff[offset + index] = total_coeff;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Scales the residual of a 4x4 luma block and adds it to the predicted samples.
+    fn add_luma_residual(
+        &mut self,
+        mb: &CurrentMb,
+        ctx: &SliceContext,
+        residual: &mut Residual,
+        bx: usize,
+        by: usize,
+        intra: bool,
+    ) {
+        let block = &mut residual.luma[by * 4 + bx];
+        let dc = residual.luma_dc.map(|dc| dc[by * 4 + bx]);
+        if dc.unwrap_or(0) == 0 && block.iter().all(|&c| c == 0) {
+            return;
+        }
+        let list = if intra { 0 } else { 3 };
+        let qp = mb.info.qp as i32;
+        dequant_4x4(
+            block,
+            &ctx.level_scale[list][qp as usize % 6],
+            qp,
+            dc.is_some(),
+        );
+        if let Some(dc) = dc {
+            block[0] = dc;
+        }
+        let stride = self.picture.width_in_mbs * 16;
+        let offset = (mb.y * 16 + by * 4) * stride + mb.x * 16 + bx * 4;
+        idct_4x4_add(block, &mut self.picture.luma[offset..], stride);
+    }
+
+    fn reconstruct_luma(
+        &mut self,
+        mb: &CurrentMb,
+        ctx: &SliceContext,
+        residual: &mut Residual,
+        intra: bool,
+    ) {
+        if let Some(dc) = &residual.luma_dc {
+            let qp = mb.info.qp as i32;
+            let scale = ctx.level_scale[0][qp as usize % 6][0];
+            residual.luma_dc = Some(luma_dc_dequant(dc, scale, qp));
+        }
+        for by in 0..4 {
+            for bx in 0..4 {
+                self.add_luma_residual(mb, ctx, residual, bx, by, intra);
+            }
+        }
+    }
+
+    fn reconstruct_chroma(
+        &mut self,
+        mb: &CurrentMb,
+        ctx: &SliceContext,
+        residual: &mut Residual,
+        intra: bool,
+    ) {
+        let stride = self.picture.width_in_mbs * 8;
+        let offsets = [
+            ctx.pps.chroma_qp_index_offset,
+            ctx.pps.second_chroma_qp_index_offset,
+        ];
+        for (component, offset) in offsets.into_iter().enumerate() {
+            let qp = chroma_qp(mb.info.qp as i32, offset);
+            let list = component + if intra { 1 } else { 4 };
+            let scale = &ctx.level_scale[list][qp as usize % 6];
+            let dc = chroma_dc_dequant(&residual.chroma_dc[component], scale[0], qp);
+            for (index, block) in residual.chroma_ac[component].iter_mut().enumerate() {
+                if dc[index] == 0 && block.iter().all(|&c| c == 0) {
+                    continue;
+                }
+                dequant_4x4(block, scale, qp, true);
+                block[0] = dc[index];
+                let (bx, by) = (index % 2, index / 2);
+                let offset = (mb.y * 8 + by * 4) * stride + mb.x * 8 + bx * 4;
+                let plane = if component == 0 {
+                    &mut self.picture.cb
+                } else {
+                    &mut self.picture.cr
+                };
+                idct_4x4_add(block, &mut plane[offset..], stride);
+            }
+        }
+    }
+
+    fn predict_intra_4x4(&mut self, mb: &CurrentMb, bx: usize, by: usize, constrained: bool) {
+        let left_mb = bx > 0 || self.intra_neighbor_mb(mb, -1, 0, constrained).is_some();
+        let top_mb = by > 0 || self.intra_neighbor_mb(mb, 0, -1, constrained).is_some();
+        let top_left_mb = match (bx > 0, by > 0) {
+            (true, true) => true,
+            (false, true) => left_mb,
+            (true, false) => top_mb,
+            (false, false) => self.intra_neighbor_mb(mb, -1, -1, constrained).is_some(),
+        };
+        let top_right_mb = if by == 0 {
+            if bx < 3 {
+                top_mb
+            } else {
+                self.intra_neighbor_mb(mb, 1, -1, constrained).is_some()
+            }
+        } else {
+            bx < 3 && block_index(bx + 1, by - 1) < block_index(bx, by)
+        };
+
+        let stride = self.picture.width_in_mbs * 16;
+        let x = mb.x * 16 + bx * 4;
+        let y = mb.y * 16 + by * 4;
+        let luma = &self.picture.luma;
+        let mut top = [0; 8];
+        let mut left = [0; 4];
+        if top_mb {
+            let row = (y - 1) * stride + x;
+            top[..4].copy_from_slice(&luma[row..row + 4]);
+            if top_right_mb {
+                top[4..].copy_from_slice(&luma[row + 4..row + 8]);
+            } else {
+                let last = top[3];
+                top[4..].fill(last);
+            }
+        }
+        if left_mb {
+            for (i, sample) in left.iter_mut().enumerate() {
+                *sample = luma[(y + i) * stride + x - 1];
+            }
+        }
+        let top_left = top_left_mb.then(|| luma[(y - 1) * stride + x - 1]);
+
+        let mut out = [0; 16];
+        let mode = mb.info.intra_modes[by * 4 + bx];
+        predict_4x4(
+            mode,
+            &Neighbors {
+                top: top_mb.then_some(&top[..]),
+                left: left_mb.then_some(&left[..]),
+                top_left,
+            },
+            &mut out,
+        );
+        for row in 0..4 {
+            let offset = (y + row) * stride + x;
+            self.picture.luma[offset..offset + 4].copy_from_slice(&out[row * 4..row * 4 + 4]);
+        }
+    }
+
+    /// Gathers the samples around a macroblock-sized block of a plane, for intra prediction.
+    #[allow(clippy::type_complexity)]
+    fn intra_square_neighbors(
+        &self,
+        mb: &CurrentMb,
+        plane: &[u8],
+        size: usize,
+        constrained: bool,
+    ) -> (Option<Vec<u8>>, Option<Vec<u8>>, Option<u8>) {
+        let stride = self.picture.width_in_mbs * size;
+        let x = mb.x * size;
+        let y = mb.y * size;
+        let top = self.intra_neighbor_mb(mb, 0, -1, constrained).map(|_| {
+            let row = (y - 1) * stride + x;
+            plane[row..row + size].to_vec()
+        });
+        let left = self
+            .intra_neighbor_mb(mb, -1, 0, constrained)
+            .map(|_| (0..size).map(|i| plane[(y + i) * stride + x - 1]).collect());
+        let top_left = self
+            .intra_neighbor_mb(mb, -1, -1, constrained)
+            .map(|_| plane[(y - 1) * stride + x - 1]);
+        (top, left, top_left)
+    }
+
+    fn predict_intra_16x16(
+        &mut self,
+        mb: &CurrentMb,
+        mode: u8,
+        constrained: bool,
+    ) -> Result<(), H264Error> {
+        let (top, left, top_left) =
+            self.intra_square_neighbors(mb, &self.picture.luma, 16, constrained);
+        check_intra_availability(mode, top.is_some(), left.is_some(), top_left.is_some())?;
+        let stride = self.picture.width_in_mbs * 16;
+        let offset = mb.y * 16 * stride + mb.x * 16;
+        predict_square(
+            mode,
+            16,
+            &Neighbors {
+                top: top.as_deref(),
+                left: left.as_deref(),
+                top_left,
+            },
+            &mut self.picture.luma[offset..],
+            stride,
+        );
+        Ok(())
+    }
+
+    fn predict_intra_chroma(
+        &mut self,
+        mb: &CurrentMb,
+        mode: u8,
+        constrained: bool,
+    ) -> Result<(), H264Error> {
+        let stride = self.picture.width_in_mbs * 8;
+        let offset = mb.y * 8 * stride + mb.x * 8;
+        for component in 0..2 {
+            let plane = if component == 0 {
+                &self.picture.cb
+            } else {
+                &self.picture.cr
+            };
+            let (top, left, top_left) = self.intra_square_neighbors(mb, plane, 8, constrained);
+            check_intra_availability(mode, top.is_some(), left.is_some(), top_left.is_some())?;
+            let plane = if component == 0 {
+                &mut self.picture.cb
+            } else {
+                &mut self.picture.cr
+            };
+            predict_square(
+                mode,
+                8,
+                &Neighbors {
+                    top: top.as_deref(),
+                    left: left.as_deref(),
+                    top_left,
+                },
+                &mut plane[offset..],
+                stride,
+            );
+        }
+        Ok(())
+    }
+
+    /// Motion compensated prediction of all the blocks of an inter macroblock.
+    fn predict_inter(&mut self, mb: &CurrentMb, ctx: &SliceContext) -> Result<(), H264Error> {
+        let luma_stride = self.picture.width_in_mbs * 16;
+        let chroma_stride = self.picture.width_in_mbs * 8;
+        let poc = self.picture.poc;
+        for by in 0..4 {
+            for bx in 0..4 {
+                let motion = *self.picture.motion_at(mb.x * 4 + bx, mb.y * 4 + by);
+                let mut luma = [[0u8; 16]; 2];
+                // Indexed by component, then by list.
+                let mut chroma = [[[0u8; 4]; 2]; 2];
+                for list in 0..2 {
+                    if motion.ref_idx[list] < 0 {
+                        continue;
+                    }
+                    let reference = &ctx.reference(list, motion.ref_idx[list])?.picture;
+                    let [mv_x, mv_y] = motion.mv[list].map(|v| v as i32);
+                    let x = (mb.x * 16 + bx * 4) as i32;
+                    let y = (mb.y * 16 + by * 4) as i32;
+                    predict_luma(
+                        &reference.luma_plane(),
+                        x + (mv_x >> 2),
+                        y + (mv_y >> 2),
+                        mv_x & 3,
+                        mv_y & 3,
+                        4,
+                        4,
+                        &mut luma[list],
+                    );
+                    for (component, out) in chroma.iter_mut().enumerate() {
+                        predict_chroma(
+                            &reference.chroma_plane(component),
+                            x / 2 + (mv_x >> 3),
+                            y / 2 + (mv_y >> 3),
+                            mv_x & 7,
+                            mv_y & 7,
+                            2,
+                            2,
+                            &mut out[list],
+                        );
+                    }
+                }
+
+                let l0 = (motion.ref_idx[0] >= 0).then_some(0);
+                let l1 = (motion.ref_idx[1] >= 0).then_some(1);
+                let mut out = [0u8; 16];
+                weighted_prediction(
+                    ctx.weighting(motion.ref_idx, poc, None),
+                    l0.map(|l| &luma[l][..]),
+                    l1.map(|l| &luma[l][..]),
+                    &mut out,
+                );
+                for row in 0..4 {
+                    let offset = (mb.y * 16 + by * 4 + row) * luma_stride + mb.x * 16 + bx * 4;
+                    self.picture.luma[offset..offset + 4]
+                        .copy_from_slice(&out[row * 4..row * 4 + 4]);
+                }
+                for (component, chroma) in chroma.iter().enumerate() {
+                    let mut out = [0u8; 4];
+                    weighted_prediction(
+                        ctx.weighting(motion.ref_idx, poc, Some(component)),
+                        l0.map(|l| &chroma[l][..]),
+                        l1.map(|l| &chroma[l][..]),
+                        &mut out,
+                    );
+                    let plane = if component == 0 {
+                        &mut self.picture.cb
+                    } else {
+                        &mut self.picture.cr
+                    };
+                    for row in 0..2 {
+                        let offset = (mb.y * 8 + by * 2 + row) * chroma_stride + mb.x * 8 + bx * 2;
+                        plane[offset..offset + 2].copy_from_slice(&out[row * 2..row * 2 + 2]);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn read_cbp(r: &mut BitReader, table: &[u8; 48]) -> Result<u8, H264Error> {
+    let code = r.read_ue()? as usize;
+    table
+        .get(code)
+        .copied()
+        .ok_or(H264Error::InvalidData("Invalid coded block pattern"))
+}
+
+fn read_ref_idx(r: &mut BitReader, num_active: u32) -> Result<i8, H264Error> {
+    let ref_idx = r.read_te(num_active - 1)?;
+    if ref_idx >= num_active {
+        return Err(H264Error::InvalidData("Reference index out of range"));
+    }
+    Ok(ref_idx as i8)
+}
+
+/// Rejects intra prediction modes that need samples which aren't available.
+fn check_intra_availability(
+    mode: u8,
+    top: bool,
+    left: bool,
+    top_left: bool,
+) -> Result<(), H264Error> {
+    let available = match mode {
+        0 => top,
+        1 => left,
+        2 => true,
+        _ => top && left && top_left,
+    };
+    if available {
+        Ok(())
+    } else {
+        Err(H264Error::InvalidData(
+            "Intra prediction from unavailable samples",
+        ))
+    }
+}
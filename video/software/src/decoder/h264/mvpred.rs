@@ -0,0 +1,321 @@
+//! Derivation of motion vectors and reference indices (8.4.1).
+
+use super::macroblock::{
+    distance_scale_factor, CurrentMb, CurrentPicture, Partition, SliceContext,
+};
+use super::picture::{BlockMotion, Picture};
+use super::H264Error;
+
+/// Partition shapes with their own motion vector prediction rules (8.4.1.3).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Shape {
+    #[default]
+    Other,
+    Upper16x8,
+    Lower16x8,
+    Left8x16,
+    Right8x16,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct NeighborMotion {
+    available: bool,
+    ref_idx: i8,
+    mv: [i32; 2],
+}
+
+const UNAVAILABLE: NeighborMotion = NeighborMotion {
+    available: false,
+    ref_idx: -1,
+    mv: [0, 0],
+};
+
+fn min_positive(a: i8, b: i8) -> i8 {
+    if a >= 0 && b >= 0 {
+        a.min(b)
+    } else {
+        a.max(b)
+    }
+}
+
+fn median(a: i32, b: i32, c: i32) -> i32 {
+    a.max(b).min(a.min(b).max(c))
+}
+
+fn to_mv(mv: [i32; 2]) -> [i16; 2] {
+    mv.map(|v| v as i16)
+}
+
+impl CurrentPicture {
+    /// Motion of the 4x4 block at the given position relative to the top left block
+    /// of the current macroblock (6.4.11.7).
+    fn neighbor_motion(&self, mb: &CurrentMb, x: isize, y: isize, list: usize) -> NeighborMotion {
+        if (0..4).contains(&x) && (0..4).contains(&y) {
+            if !mb.motion_done[y as usize * 4 + x as usize] {
+                return UNAVAILABLE;
+            }
+        } else {
+            if x >= 4 && y >= 0 {
+                return UNAVAILABLE;
+            }
+            let dx = if x < 0 { -1 } else { (x >= 4) as isize };
+            let dy = if y < 0 { -1 } else { 0 };
+            if self.neighbor_mb(mb, dx, dy).is_none() {
+                return UNAVAILABLE;
+            }
+        }
+        let motion = self.picture.motion_at(
+            (mb.x as isize * 4 + x) as usize,
+            (mb.y as isize * 4 + y) as usize,
+        );
+        let ref_idx = motion.ref_idx[list];
+        NeighborMotion {
+            available: true,
+            ref_idx,
+            mv: if ref_idx >= 0 {
+                motion.mv[list].map(|v| v as i32)
+            } else {
+                [0, 0]
+            },
+        }
+    }
+
+    /// Neighbouring partitions A, B and C of a partition, with C replaced by D if needed.
+    fn neighbors(
+        &self,
+        mb: &CurrentMb,
+        x: usize,
+        y: usize,
+        width: usize,
+        list: usize,
+    ) -> [NeighborMotion; 3] {
+        let (x, y) = (x as isize, y as isize);
+        let a = self.neighbor_motion(mb, x - 1, y, list);
+        let b = self.neighbor_motion(mb, x, y - 1, list);
+        let mut c = self.neighbor_motion(mb, x + width as isize, y - 1, list);
+        if !c.available {
+            c = self.neighbor_motion(mb, x - 1, y - 1, list);
+        }
+        [a, b, c]
+    }
+
+    /// Luma motion vector prediction (8.4.1.3).
+    #[allow(clippy::too_many_arguments)]
+    fn predict_mv(
+        &self,
+        mb: &CurrentMb,
+        x: usize,
+        y: usize,
+        width: usize,
+        list: usize,
+        ref_idx: i8,
+        shape: Shape,
+    ) -> [i32; 2] {
+        let [a, b, c] = self.neighbors(mb, x, y, width, list);
+        match shape {
+            Shape::Upper16x8 if b.ref_idx == ref_idx => return b.mv,
+            Shape::Lower16x8 | Shape::Left8x16 if a.ref_idx == ref_idx => return a.mv,
+            Shape::Right8x16 if c.ref_idx == ref_idx => return c.mv,
+            _ => {}
+        }
+
+        if !b.available && !c.available && a.available {
+            return a.mv;
+        }
+        let matching: Vec<&NeighborMotion> = [&a, &b, &c]
+            .into_iter()
+            .filter(|n| n.ref_idx == ref_idx)
+            .collect();
+        if let [single] = matching[..] {
+            return single.mv;
+        }
+        [0, 1].map(|i| median(a.mv[i], b.mv[i], c.mv[i]))
+    }
+
+    pub fn derive_partition_motion(
+        &mut self,
+        mb: &mut CurrentMb,
+        ctx: &SliceContext,
+        partition: &Partition,
+    ) -> Result<(), H264Error> {
+        let mut motion = BlockMotion {
+            ref_idx: [-1, -1],
+            ..Default::default()
+        };
+        for list in 0..2 {
+            let ref_idx = partition.ref_idx[list];
+            if ref_idx < 0 {
+                continue;
+            }
+            let mvp = self.predict_mv(
+                mb,
+                partition.x,
+                partition.y,
+                partition.width,
+                list,
+                ref_idx,
+                partition.shape,
+            );
+            let mvd = partition.mvd[list];
+            motion.mv[list] = to_mv([mvp[0] + mvd[0], mvp[1] + mvd[1]]);
+            motion.ref_idx[list] = ref_idx;
+            motion.ref_id[list] = ctx.reference(list, ref_idx)?.picture.id;
+        }
+        for by in partition.y..partition.y + partition.height {
+            for bx in partition.x..partition.x + partition.width {
+                self.set_block_motion(mb, bx, by, motion);
+            }
+        }
+        Ok(())
+    }
+
+    /// Motion of a P_Skip macroblock (8.4.1.1).
+    pub fn derive_p_skip_motion(
+        &mut self,
+        mb: &mut CurrentMb,
+        ctx: &SliceContext,
+    ) -> Result<(), H264Error> {
+        let a = self.neighbor_motion(mb, -1, 0, 0);
+        let b = self.neighbor_motion(mb, 0, -1, 0);
+        let is_zero = |n: &NeighborMotion| n.ref_idx == 0 && n.mv == [0, 0];
+        let mv = if !a.available || !b.available || is_zero(&a) || is_zero(&b) {
+            [0, 0]
+        } else {
+            self.predict_mv(mb, 0, 0, 4, 0, 0, Shape::Other)
+        };
+        let motion = BlockMotion {
+            mv: [to_mv(mv), [0, 0]],
+            ref_idx: [0, -1],
+            ref_id: [ctx.reference(0, 0)?.picture.id, 0],
+        };
+        for by in 0..4 {
+            for bx in 0..4 {
+                self.set_block_motion(mb, bx, by, motion);
+            }
+        }
+        Ok(())
+    }
+
+    /// Motion of a direct predicted macroblock, or of one of its 8x8 quadrants (8.4.1.2).
+    pub fn derive_direct_motion(
+        &mut self,
+        mb: &mut CurrentMb,
+        ctx: &SliceContext,
+        quadrant: Option<usize>,
+    ) -> Result<(), H264Error> {
+        let colocated = ctx.reference(1, 0)?;
+        let blocks: Vec<(usize, usize)> = match quadrant {
+            Some(q) => {
+                let (x, y) = ((q % 2) * 2, (q / 2) * 2);
+                vec![(x, y), (x + 1, y), (x, y + 1), (x + 1, y + 1)]
+            }
+            None => (0..16).map(|i| (i % 4, i / 4)).collect(),
+        };
+        let inference = ctx.sps.direct_8x8_inference;
+
+        if ctx.header.direct_spatial_mv_pred {
+            // Spatial direct prediction (8.4.1.2.2).
+            let mut ref_idx = [-1i8; 2];
+            let mut mvp = [[0i32; 2]; 2];
+            for (list, ref_idx) in ref_idx.iter_mut().enumerate() {
+                let [a, b, c] = self.neighbors(mb, 0, 0, 4, list);
+                *ref_idx = min_positive(a.ref_idx, min_positive(b.ref_idx, c.ref_idx));
+            }
+            let direct_zero = ref_idx[0] < 0 && ref_idx[1] < 0;
+            if direct_zero {
+                ref_idx = [0, 0];
+            } else {
+                for list in 0..2 {
+                    if ref_idx[list] >= 0 {
+                        mvp[list] = self.predict_mv(mb, 0, 0, 4, list, ref_idx[list], Shape::Other);
+                    }
+                }
+            }
+            let mut ref_id = [0; 2];
+            for list in 0..2 {
+                if ref_idx[list] >= 0 {
+                    ref_id[list] = ctx.reference(list, ref_idx[list])?.picture.id;
+                }
+            }
+
+            for (bx, by) in blocks {
+                let (mv_col, ref_idx_col, _) =
+                    colocated_motion(&colocated.picture, mb, bx, by, inference);
+                let col_zero = !colocated.long_term
+                    && ref_idx_col == 0
+                    && mv_col.iter().all(|v| (-1..=1).contains(v));
+                let mut motion = BlockMotion {
+                    ref_idx,
+                    ref_id,
+                    ..Default::default()
+                };
+                for list in 0..2 {
+                    let zero = direct_zero || (ref_idx[list] == 0 && col_zero);
+                    if ref_idx[list] >= 0 && !zero {
+                        motion.mv[list] = to_mv(mvp[list]);
+                    }
+                }
+                self.set_block_motion(mb, bx, by, motion);
+            }
+        } else {
+            // Temporal direct prediction (8.4.1.2.3).
+            let poc = self.picture.poc;
+            for (bx, by) in blocks {
+                let (mv_col, ref_idx_col, ref_id_col) =
+                    colocated_motion(&colocated.picture, mb, bx, by, inference);
+                let ref_idx_l0 = if ref_idx_col < 0 {
+                    0
+                } else {
+                    ctx.lists[0]
+                        .iter()
+                        .position(|entry| {
+                            entry.as_ref().is_some_and(|e| e.picture.id == ref_id_col)
+                        })
+                        .unwrap_or(0) as i8
+                };
+                let reference = ctx.reference(0, ref_idx_l0)?;
+                let poc0 = reference.picture.poc;
+                let poc1 = colocated.picture.poc;
+                let (mv_l0, mv_l1) = if reference.long_term || poc1 == poc0 {
+                    (mv_col, [0, 0])
+                } else {
+                    let scale = distance_scale_factor(poc, poc0, poc1);
+                    let mv_l0 = mv_col.map(|v| (scale * v + 128) >> 8);
+                    (mv_l0, [mv_l0[0] - mv_col[0], mv_l0[1] - mv_col[1]])
+                };
+                let motion = BlockMotion {
+                    mv: [to_mv(mv_l0), to_mv(mv_l1)],
+                    ref_idx: [ref_idx_l0, 0],
+                    ref_id: [reference.picture.id, colocated.picture.id],
+                };
+                self.set_block_motion(mb, bx, by, motion);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Motion vector, reference index and referenced picture of the co-located block (8.4.1.2.1).
+fn colocated_motion(
+    colocated: &Picture,
+    mb: &CurrentMb,
+    bx: usize,
+    by: usize,
+    direct_8x8_inference: bool,
+) -> ([i32; 2], i8, u32) {
+    if colocated.mb_intra[mb.y * colocated.width_in_mbs + mb.x] {
+        return ([0, 0], -1, 0);
+    }
+    let (bx, by) = if direct_8x8_inference {
+        ((bx / 2) * 3, (by / 2) * 3)
+    } else {
+        (bx, by)
+    };
+    let motion = colocated.motion_at(mb.x * 4 + bx, mb.y * 4 + by);
+    let list = if motion.ref_idx[0] >= 0 { 0 } else { 1 };
+    (
+        motion.mv[list].map(|v| v as i32),
+        motion.ref_idx[list],
+        motion.ref_id[list],
+    )
+}
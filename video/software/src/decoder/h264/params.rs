@@ -0,0 +1,478 @@
+//! Sequence and picture parameter sets (7.3.2.1 and 7.3.2.2).
+
+use super::bitreader::BitReader;
+use super::H264Error;
+
+const DEFAULT_4X4_INTRA: [u8; 16] = [
+    6, 13, 13, 20, 20, 20, 28, 28, 28, 28, 32, 32, 32, 37, 37, 42,
+];
+const DEFAULT_4X4_INTER: [u8; 16] = [
+    10, 14, 14, 20, 20, 20, 24, 24, 24, 24, 27, 27, 27, 30, 30, 34,
+];
+const FLAT_4X4: [u8; 16] = [16; 16];
+
+/// The six 4x4 scaling lists (Intra Y, Cb, Cr, then Inter Y, Cb, Cr), in zig-zag order.
+pub type ScalingLists4x4 = [[u8; 16]; 6];
+
+#[derive(Clone, Debug)]
+pub struct Sps {
+    /// Whether the stream only uses Baseline profile features, from `profile_idc` or
+    /// `constraint_set0_flag`.
+    pub baseline_compatible: bool,
+    pub sps_id: u32,
+    pub chroma_format_idc: u32,
+    pub bit_depth_luma: u32,
+    pub bit_depth_chroma: u32,
+    pub scaling_4x4: ScalingLists4x4,
+    pub has_scaling_matrix: bool,
+    pub log2_max_frame_num: u32,
+    pub pic_order_cnt_type: u32,
+    pub log2_max_pic_order_cnt_lsb: u32,
+    pub delta_pic_order_always_zero: bool,
+    pub offset_for_non_ref_pic: i32,
+    pub offset_for_top_to_bottom_field: i32,
+    pub offset_for_ref_frame: Vec<i32>,
+    pub max_num_ref_frames: u32,
+    pub width_in_mbs: u32,
+    pub height_in_mbs: u32,
+    pub frame_mbs_only: bool,
+    pub direct_8x8_inference: bool,
+    /// Left, right, top and bottom cropping, in luma samples.
+    pub crop: (u32, u32, u32, u32),
+    /// How many frames may precede a frame in decoding order but follow it in output order.
+    ///
+    /// This is `max_num_reorder_frames` from the VUI, or inferred like E.2.1 says when absent.
+    pub max_num_reorder_frames: u32,
+}
+
+impl Sps {
+    pub fn parse(data: &[u8]) -> Result<Self, H264Error> {
+        let mut r = BitReader::new(data);
+        let profile_idc = r.read_u8(8)?;
+        let constraint_flags = r.read_u8(8)?;
+        let level_idc = r.read_u8(8)?;
+        let sps_id = r.read_ue()?;
+        if sps_id > 31 {
+            return Err(H264Error::InvalidData("SPS id out of range"));
+        }
+
+        let mut chroma_format_idc = 1;
+        let mut bit_depth_luma = 8;
+        let mut bit_depth_chroma = 8;
+        let mut scaling_4x4 = [FLAT_4X4; 6];
+        let mut has_scaling_matrix = false;
+        if matches!(
+            profile_idc,
+            100 | 110 | 122 | 244 | 44 | 83 | 86 | 118 | 128 | 138 | 139 | 134 | 135
+        ) {
+            chroma_format_idc = r.read_ue()?;
+            if chroma_format_idc == 3 {
+                let _separate_colour_plane = r.read_flag()?;
+            }
+            bit_depth_luma = r.read_ue()? + 8;
+            bit_depth_chroma = r.read_ue()? + 8;
+            let _qpprime_y_zero_transform_bypass = r.read_flag()?;
+            if r.read_flag()? {
+                has_scaling_matrix = true;
+                let count = if chroma_format_idc != 3 { 8 } else { 12 };
+                read_scaling_lists(&mut r, count, &mut scaling_4x4, None)?;
+            }
+        }
+
+        let log2_max_frame_num = r.read_ue()? + 4;
+        let pic_order_cnt_type = r.read_ue()?;
+        let mut log2_max_pic_order_cnt_lsb = 0;
+        let mut delta_pic_order_always_zero = false;
+        let mut offset_for_non_ref_pic = 0;
+        let mut offset_for_top_to_bottom_field = 0;
+        let mut offset_for_ref_frame = Vec::new();
+        match pic_order_cnt_type {
+            0 => log2_max_pic_order_cnt_lsb = r.read_ue()? + 4,
+            1 => {
+                delta_pic_order_always_zero = r.read_flag()?;
+                offset_for_non_ref_pic = r.read_se()?;
+                offset_for_top_to_bottom_field = r.read_se()?;
+                let count = r.read_ue()?;
+                if count > 255 {
+                    return Err(H264Error::InvalidData(
+                        "Too many reference frames in POC cycle",
+                    ));
+                }
+                for _ in 0..count {
+                    offset_for_ref_frame.push(r.read_se()?);
+                }
+            }
+            2 => {}
+            _ => return Err(H264Error::InvalidData("Invalid picture order count type")),
+        }
+        if log2_max_frame_num > 16 || log2_max_pic_order_cnt_lsb > 16 {
+            return Err(H264Error::InvalidData("Invalid frame number or POC size"));
+        }
+
+        let max_num_ref_frames = r.read_ue()?;
+        let _gaps_in_frame_num_allowed = r.read_flag()?;
+        let width_in_mbs = r.read_ue()? + 1;
+        let height_in_map_units = r.read_ue()? + 1;
+        let frame_mbs_only = r.read_flag()?;
+        if !frame_mbs_only {
+            let _mb_adaptive_frame_field = r.read_flag()?;
+        }
+        let height_in_mbs = if frame_mbs_only {
+            height_in_map_units
+        } else {
+            height_in_map_units * 2
+        };
+        let direct_8x8_inference = r.read_flag()?;
+
+        let mut crop = (0, 0, 0, 0);
+        if r.read_flag()? {
+            let (crop_unit_x, crop_unit_y) = match chroma_format_idc {
+                0 | 3 => (1, 2 - frame_mbs_only as u32),
+                1 => (2, 2 * (2 - frame_mbs_only as u32)),
+                _ => (2, 2 - frame_mbs_only as u32),
+            };
+            crop = (
+                r.read_ue()? * crop_unit_x,
+                r.read_ue()? * crop_unit_x,
+                r.read_ue()? * crop_unit_y,
+                r.read_ue()? * crop_unit_y,
+            );
+        }
+
+        let bitstream_restriction = if r.read_flag()? {
+            parse_vui(&mut r)?
+        } else {
+            None
+        };
+        let constraint_set3 = constraint_flags & 0b0001_0000 != 0;
+        let max_num_reorder_frames = match bitstream_restriction {
+            Some((max_num_reorder_frames, max_dec_frame_buffering)) => {
+                max_num_reorder_frames.min(max_dec_frame_buffering)
+            }
+            // Output order is the same as decoding order (8.2.1.3).
+            None if pic_order_cnt_type == 2 => 0,
+            // Intra profiles.
+            None if constraint_set3 && matches!(profile_idc, 44 | 86 | 100 | 110 | 122 | 244) => 0,
+            None => {
+                let level_1b = level_idc == 9 || (level_idc == 11 && constraint_set3);
+                max_dpb_frames(level_idc, level_1b, width_in_mbs * height_in_mbs)
+            }
+        }
+        .min(16);
+
+        if width_in_mbs > 1024 || height_in_mbs > 1024 {
+            return Err(H264Error::InvalidData("Picture size out of range"));
+        }
+        if crop.0 + crop.1 >= width_in_mbs * 16 || crop.2 + crop.3 >= height_in_mbs * 16 {
+            return Err(H264Error::InvalidData("Invalid cropping rectangle"));
+        }
+
+        Ok(Self {
+            baseline_compatible: profile_idc == 66 || constraint_flags & 0b1000_0000 != 0,
+            sps_id,
+            chroma_format_idc,
+            bit_depth_luma,
+            bit_depth_chroma,
+            scaling_4x4,
+            has_scaling_matrix,
+            log2_max_frame_num,
+            pic_order_cnt_type,
+            log2_max_pic_order_cnt_lsb,
+            delta_pic_order_always_zero,
+            offset_for_non_ref_pic,
+            offset_for_top_to_bottom_field,
+            offset_for_ref_frame,
+            max_num_ref_frames,
+            width_in_mbs,
+            height_in_mbs,
+            frame_mbs_only,
+            direct_8x8_inference,
+            crop,
+            max_num_reorder_frames,
+        })
+    }
+
+    /// Checks whether this decoder is able to handle the stream described by this SPS.
+    pub fn check_supported(&self) -> Result<(), H264Error> {
+        if !self.baseline_compatible {
+            return Err(H264Error::Unsupported("Profiles other than Baseline"));
+        }
+        if self.chroma_format_idc != 1 {
+            return Err(H264Error::Unsupported("Chroma formats other than 4:2:0"));
+        }
+        if self.bit_depth_luma != 8 || self.bit_depth_chroma != 8 {
+            return Err(H264Error::Unsupported("Bit depths other than 8"));
+        }
+        if !self.frame_mbs_only {
+            return Err(H264Error::Unsupported("Interlaced video"));
+        }
+        Ok(())
+    }
+
+    pub fn max_frame_num(&self) -> u32 {
+        1 << self.log2_max_frame_num
+    }
+}
+
+/// The largest number of frames the decoded picture buffer can hold for a picture size and level
+/// (A.3.1, with `MaxDpbMbs` from Table A-1), or 16 for unknown levels.
+fn max_dpb_frames(level_idc: u8, level_1b: bool, frame_size_in_mbs: u32) -> u32 {
+    let max_dpb_mbs = match level_idc {
+        _ if level_1b => 396,
+        10 => 396,
+        11 => 900,
+        12 | 13 | 20 => 2376,
+        21 => 4752,
+        22 | 30 => 8100,
+        31 => 18000,
+        32 => 20480,
+        40 | 41 => 32768,
+        42 => 34816,
+        50 => 110400,
+        51 | 52 => 184320,
+        60..=62 => 696320,
+        _ => return 16,
+    };
+    (max_dpb_mbs / frame_size_in_mbs).min(16)
+}
+
+/// Parses the VUI parameters (E.1.1), returning `max_num_reorder_frames`
+/// and `max_dec_frame_buffering` if present.
+fn parse_vui(r: &mut BitReader) -> Result<Option<(u32, u32)>, H264Error> {
+    if r.read_flag()? {
+        // aspect_ratio_info_present_flag
+        let aspect_ratio_idc = r.read_u8(8)?;
+        if aspect_ratio_idc == 255 {
+            r.skip_bits(32)?; // sar_width, sar_height
+        }
+    }
+    if r.read_flag()? {
+        // overscan_info_present_flag
+        r.skip_bits(1)?;
+    }
+    if r.read_flag()? {
+        // video_signal_type_present_flag
+        r.skip_bits(4)?;
+        if r.read_flag()? {
+            // colour_description_present_flag
+            r.skip_bits(24)?;
+        }
+    }
+    if r.read_flag()? {
+        // chroma_loc_info_present_flag
+        r.read_ue()?;
+        r.read_ue()?;
+    }
+    if r.read_flag()? {
+        // timing_info_present_flag
+        r.skip_bits(65)?;
+    }
+    let nal_hrd = r.read_flag()?;
+    if nal_hrd {
+        skip_hrd_parameters(r)?;
+    }
+    let vcl_hrd = r.read_flag()?;
+    if vcl_hrd {
+        skip_hrd_parameters(r)?;
+    }
+    if nal_hrd || vcl_hrd {
+        r.skip_bits(1)?; // low_delay_hrd_flag
+    }
+    r.skip_bits(1)?; // pic_struct_present_flag
+    if r.read_flag()? {
+        // bitstream_restriction_flag
+        r.skip_bits(1)?; // motion_vectors_over_pic_boundaries_flag
+        r.read_ue()?; // max_bytes_per_pic_denom
+        r.read_ue()?; // max_bits_per_mb_denom
+        r.read_ue()?; // log2_max_mv_length_horizontal
+        r.read_ue()?; // log2_max_mv_length_vertical
+        let max_num_reorder_frames = r.read_ue()?;
+        let max_dec_frame_buffering = r.read_ue()?;
+        return Ok(Some((max_num_reorder_frames, max_dec_frame_buffering)));
+    }
+    Ok(None)
+}
+
+fn skip_hrd_parameters(r: &mut BitReader) -> Result<(), H264Error> {
+    let cpb_cnt = r.read_ue()? + 1;
+    if cpb_cnt > 32 {
+        return Err(H264Error::InvalidData("Too many CPB specifications"));
+    }
+    r.skip_bits(8)?; // bit_rate_scale, cpb_size_scale
+    for _ in 0..cpb_cnt {
+        r.read_ue()?; // bit_rate_value_minus1
+        r.read_ue()?; // cpb_size_value_minus1
+        r.skip_bits(1)?; // cbr_flag
+    }
+    r.skip_bits(20)?; // The four delay and offset lengths.
+    Ok(())
+}
+
+/// Reads `count` scaling lists (7.3.2.1.1.1), storing the 4x4 ones, and
+/// applying either fall-back rule A (without `fallback`) or B (with the lists of the SPS).
+fn read_scaling_lists(
+    r: &mut BitReader,
+    count: usize,
+    lists: &mut ScalingLists4x4,
+    fallback: Option<&ScalingLists4x4>,
+) -> Result<(), H264Error> {
+    for i in 0..count {
+        let present = r.read_flag()?;
+        if i >= 6 {
+            // 8x8 lists are only used by the 8x8 transform, which we don't support.
+            if present {
+                let mut list = [0; 64];
+                read_scaling_list(r, &mut list)?;
+            }
+            continue;
+        }
+
+        let default = if i < 3 {
+            DEFAULT_4X4_INTRA
+        } else {
+            DEFAULT_4X4_INTER
+        };
+        if present {
+            if !read_scaling_list(r, &mut lists[i])? {
+                lists[i] = default;
+            }
+        } else {
+            lists[i] = match (i, fallback) {
+                (0 | 3, Some(fallback)) => fallback[i],
+                (0 | 3, None) => default,
+                _ => lists[i - 1],
+            };
+        }
+    }
+    Ok(())
+}
+
+/// Reads a single scaling list, returning `false` if the default one should be used instead.
+fn read_scaling_list(r: &mut BitReader, list: &mut [u8]) -> Result<bool, H264Error> {
+    let mut last_scale = 8i32;
+    let mut next_scale = 8i32;
+    for (j, scale) in list.iter_mut().enumerate() {
+        if next_scale != 0 {
+            let delta_scale = r.read_se()?;
+            next_scale = (last_scale + delta_scale + 256).rem_euclid(256);
+            if j == 0 && next_scale == 0 {
+                return Ok(false);
+            }
+        }
+        if next_scale != 0 {
+            last_scale = next_scale;
+        }
+        *scale = last_scale as u8;
+    }
+    Ok(true)
+}
+
+#[derive(Clone, Debug)]
+pub struct Pps {
+    pub pps_id: u32,
+    pub sps_id: u32,
+    pub entropy_coding_mode: bool,
+    pub bottom_field_pic_order_in_frame_present: bool,
+    pub num_ref_idx_l0_default_active: u32,
+    pub num_ref_idx_l1_default_active: u32,
+    pub weighted_pred: bool,
+    pub weighted_bipred_idc: u32,
+    pub pic_init_qp: i32,
+    pub chroma_qp_index_offset: i32,
+    pub second_chroma_qp_index_offset: i32,
+    pub deblocking_filter_control_present: bool,
+    pub constrained_intra_pred: bool,
+    pub redundant_pic_cnt_present: bool,
+    pub transform_8x8_mode: bool,
+    /// The scaling lists to use for pictures referring to this PPS.
+    pub scaling_4x4: ScalingLists4x4,
+}
+
+impl Pps {
+    /// Parses a PPS. The SPS it refers to is needed to resolve its scaling lists.
+    pub fn parse(data: &[u8], sps_list: &[Option<Sps>]) -> Result<Self, H264Error> {
+        let mut r = BitReader::new(data);
+        let pps_id = r.read_ue()?;
+        let sps_id = r.read_ue()?;
+        if pps_id > 255 || sps_id > 31 {
+            return Err(H264Error::InvalidData("PPS or SPS id out of range"));
+        }
+        let sps = sps_list
+            .get(sps_id as usize)
+            .and_then(|sps| sps.as_ref())
+            .ok_or(H264Error::MissingParameterSet)?;
+
+        let entropy_coding_mode = r.read_flag()?;
+        let bottom_field_pic_order_in_frame_present = r.read_flag()?;
+        let num_slice_groups = r.read_ue()? + 1;
+        if num_slice_groups > 1 {
+            return Err(H264Error::Unsupported("Flexible macroblock ordering"));
+        }
+        let num_ref_idx_l0_default_active = r.read_ue()? + 1;
+        let num_ref_idx_l1_default_active = r.read_ue()? + 1;
+        if num_ref_idx_l0_default_active > 32 || num_ref_idx_l1_default_active > 32 {
+            return Err(H264Error::InvalidData("Too many reference indices"));
+        }
+        let weighted_pred = r.read_flag()?;
+        let weighted_bipred_idc = r.read_bits(2)?;
+        let pic_init_qp = 26 + r.read_se()?;
+        let _pic_init_qs = 26 + r.read_se()?;
+        let chroma_qp_index_offset = r.read_se()?;
+        let deblocking_filter_control_present = r.read_flag()?;
+        let constrained_intra_pred = r.read_flag()?;
+        let redundant_pic_cnt_present = r.read_flag()?;
+
+        let mut transform_8x8_mode = false;
+        let mut scaling_4x4 = sps.scaling_4x4;
+        let mut second_chroma_qp_index_offset = chroma_qp_index_offset;
+        if r.more_rbsp_data() {
+            transform_8x8_mode = r.read_flag()?;
+            if r.read_flag()? {
+                let count_8x8 = if sps.chroma_format_idc != 3 { 2 } else { 6 };
+                let count = 6 + count_8x8 * transform_8x8_mode as usize;
+                let fallback = sps.has_scaling_matrix.then_some(&sps.scaling_4x4);
+                read_scaling_lists(&mut r, count, &mut scaling_4x4, fallback)?;
+            }
+            second_chroma_qp_index_offset = r.read_se()?;
+        }
+
+        if !(0..=51).contains(&pic_init_qp)
+            || !(-12..=12).contains(&chroma_qp_index_offset)
+            || !(-12..=12).contains(&second_chroma_qp_index_offset)
+        {
+            return Err(H264Error::InvalidData(
+                "Quantization parameter out of range",
+            ));
+        }
+
+        Ok(Self {
+            pps_id,
+            sps_id,
+            entropy_coding_mode,
+            bottom_field_pic_order_in_frame_present,
+            num_ref_idx_l0_default_active,
+            num_ref_idx_l1_default_active,
+            weighted_pred,
+            weighted_bipred_idc,
+            pic_init_qp,
+            chroma_qp_index_offset,
+            second_chroma_qp_index_offset,
+            deblocking_filter_control_present,
+            constrained_intra_pred,
+            redundant_pic_cnt_present,
+            transform_8x8_mode,
+            scaling_4x4,
+        })
+    }
+
+    /// Checks whether this decoder is able to handle the slices referring to this PPS.
+    pub fn check_supported(&self) -> Result<(), H264Error> {
+        if self.entropy_coding_mode {
+            return Err(H264Error::Unsupported("CABAC entropy coding"));
+        }
+        if self.transform_8x8_mode {
+            return Err(H264Error::Unsupported("8x8 transform"));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,376 @@
+//! Decoded pictures, reference picture marking (8.2.5) and list construction (8.2.4).
+
+use super::inter::Plane;
+use super::slice::{Mmco, RefPicListModification, SliceHeader, SliceType};
+use super::H264Error;
+use std::rc::Rc;
+
+/// Motion information of a single 4x4 luma block, as used by later pictures for direct prediction.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BlockMotion {
+    pub mv: [[i16; 2]; 2],
+    /// Reference index into each list, or -1 if the list isn't used.
+    pub ref_idx: [i8; 2],
+    /// Unique ids of the referenced pictures.
+    pub ref_id: [u32; 2],
+}
+
+#[derive(Clone, Debug)]
+pub struct Picture {
+    /// Unique id of the picture, used to tell reference pictures apart.
+    pub id: u32,
+    pub width_in_mbs: usize,
+    pub height_in_mbs: usize,
+    pub luma: Vec<u8>,
+    pub cb: Vec<u8>,
+    pub cr: Vec<u8>,
+    pub poc: i32,
+    /// Motion of every 4x4 block, in raster order.
+    pub motion: Vec<BlockMotion>,
+    /// Whether each macroblock was intra coded.
+    pub mb_intra: Vec<bool>,
+}
+
+impl Picture {
+    pub fn new(id: u32, width_in_mbs: usize, height_in_mbs: usize, poc: i32) -> Self {
+        let luma_size = width_in_mbs * height_in_mbs * 256;
+        Self {
+            id,
+            width_in_mbs,
+            height_in_mbs,
+            luma: vec![0; luma_size],
+            cb: vec![128; luma_size / 4],
+            cr: vec![128; luma_size / 4],
+            poc,
+            motion: vec![BlockMotion::default(); width_in_mbs * height_in_mbs * 16],
+            mb_intra: vec![true; width_in_mbs * height_in_mbs],
+        }
+    }
+
+    pub fn luma_plane(&self) -> Plane<'_> {
+        Plane {
+            data: &self.luma,
+            width: self.width_in_mbs * 16,
+            height: self.height_in_mbs * 16,
+        }
+    }
+
+    pub fn chroma_plane(&self, component: usize) -> Plane<'_> {
+        Plane {
+            data: if component == 0 { &self.cb } else { &self.cr },
+            width: self.width_in_mbs * 8,
+            height: self.height_in_mbs * 8,
+        }
+    }
+
+    /// Motion of the 4x4 block at the given position, in units of 4x4 blocks.
+    pub fn motion_at(&self, block_x: usize, block_y: usize) -> &BlockMotion {
+        &self.motion[block_y * self.width_in_mbs * 4 + block_x]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reference {
+    ShortTerm,
+    LongTerm(u32),
+}
+
+/// A picture in the decoded picture buffer that is used for reference.
+#[derive(Clone, Debug)]
+pub struct RefPicture {
+    pub picture: Rc<Picture>,
+    pub frame_num: u32,
+    pub reference: Reference,
+}
+
+impl RefPicture {
+    pub fn is_long_term(&self) -> bool {
+        matches!(self.reference, Reference::LongTerm(_))
+    }
+
+    /// `FrameNumWrap`, which is also `PicNum` for frames.
+    fn pic_num(&self, current_frame_num: u32, max_frame_num: u32) -> i32 {
+        if self.frame_num > current_frame_num {
+            self.frame_num as i32 - max_frame_num as i32
+        } else {
+            self.frame_num as i32
+        }
+    }
+
+    fn long_term_pic_num(&self) -> Option<u32> {
+        match self.reference {
+            Reference::LongTerm(idx) => Some(idx),
+            Reference::ShortTerm => None,
+        }
+    }
+}
+
+/// An entry of a reference picture list.
+#[derive(Clone, Debug)]
+pub struct ListEntry {
+    pub picture: Rc<Picture>,
+    pub long_term: bool,
+}
+
+/// The set of pictures marked as "used for reference".
+#[derive(Default)]
+pub struct ReferencePictures {
+    pictures: Vec<RefPicture>,
+    /// `MaxLongTermFrameIdx`, `None` meaning "no long-term frame indices".
+    max_long_term_frame_idx: Option<u32>,
+}
+
+impl ReferencePictures {
+    /// Builds the initial reference picture lists for a slice (8.2.4.2), then applies
+    /// the modifications from the slice header (8.2.4.3).
+    ///
+    /// Entries may be missing when the stream refers to more pictures than it has sent.
+    pub fn build_lists(
+        &self,
+        header: &SliceHeader,
+        current_poc: i32,
+        max_frame_num: u32,
+    ) -> Result<[Vec<Option<ListEntry>>; 2], H264Error> {
+        let frame_num = header.frame_num;
+        let short_term = || self.pictures.iter().filter(|p| !p.is_long_term());
+        let mut long_term: Vec<&RefPicture> =
+            self.pictures.iter().filter(|p| p.is_long_term()).collect();
+        long_term.sort_by_key(|p| p.long_term_pic_num());
+
+        let mut lists: [Vec<&RefPicture>; 2] = [Vec::new(), Vec::new()];
+        match header.slice_type {
+            SliceType::I => return Ok([Vec::new(), Vec::new()]),
+            SliceType::P => {
+                let mut list: Vec<&RefPicture> = short_term().collect();
+                list.sort_by_key(|p| -p.pic_num(frame_num, max_frame_num));
+                list.extend(&long_term);
+                lists[0] = list;
+            }
+            SliceType::B => {
+                let mut before: Vec<&RefPicture> = short_term()
+                    .filter(|p| p.picture.poc < current_poc)
+                    .collect();
+                before.sort_by_key(|p| -p.picture.poc);
+                let mut after: Vec<&RefPicture> = short_term()
+                    .filter(|p| p.picture.poc > current_poc)
+                    .collect();
+                after.sort_by_key(|p| p.picture.poc);
+
+                lists[0] = before
+                    .iter()
+                    .chain(&after)
+                    .chain(&long_term)
+                    .copied()
+                    .collect();
+                lists[1] = after
+                    .iter()
+                    .chain(&before)
+                    .chain(&long_term)
+                    .copied()
+                    .collect();
+                if lists[1].len() > 1
+                    && lists[0]
+                        .iter()
+                        .zip(&lists[1])
+                        .all(|(a, b)| a.picture.id == b.picture.id)
+                {
+                    lists[1].swap(0, 1);
+                }
+            }
+        }
+
+        let mut result = [Vec::new(), Vec::new()];
+        for (list_index, list) in lists.into_iter().enumerate() {
+            let num_active = header.num_ref_idx_active[list_index] as usize;
+            let mut list: Vec<Option<&RefPicture>> = list.into_iter().map(Some).collect();
+            list.resize(num_active, None);
+            self.modify_list(
+                &mut list,
+                &header.ref_pic_list_modifications[list_index],
+                frame_num,
+                max_frame_num,
+            )?;
+            result[list_index] = list
+                .into_iter()
+                .map(|p| {
+                    p.map(|p| ListEntry {
+                        picture: p.picture.clone(),
+                        long_term: p.is_long_term(),
+                    })
+                })
+                .collect();
+        }
+        Ok(result)
+    }
+
+    fn modify_list<'a>(
+        &'a self,
+        list: &mut Vec<Option<&'a RefPicture>>,
+        modifications: &[RefPicListModification],
+        frame_num: u32,
+        max_frame_num: u32,
+    ) -> Result<(), H264Error> {
+        let num_active = list.len();
+        let max_pic_num = max_frame_num as i32;
+        let curr_pic_num = frame_num as i32;
+        let mut pic_num_pred = curr_pic_num;
+        for (ref_idx, modification) in modifications.iter().enumerate() {
+            if ref_idx >= num_active {
+                return Err(H264Error::InvalidData(
+                    "Too many reference list modifications",
+                ));
+            }
+            let picture = match *modification {
+                RefPicListModification::ShortTermSubtract(abs_diff)
+                | RefPicListModification::ShortTermAdd(abs_diff) => {
+                    let abs_diff = abs_diff as i32;
+                    let mut no_wrap =
+                        if matches!(modification, RefPicListModification::ShortTermSubtract(_)) {
+                            pic_num_pred - abs_diff
+                        } else {
+                            pic_num_pred + abs_diff
+                        };
+                    if no_wrap < 0 {
+                        no_wrap += max_pic_num;
+                    } else if no_wrap >= max_pic_num {
+                        no_wrap -= max_pic_num;
+                    }
+                    pic_num_pred = no_wrap;
+                    let pic_num = if no_wrap > curr_pic_num {
+                        no_wrap - max_pic_num
+                    } else {
+                        no_wrap
+                    };
+                    self.pictures.iter().find(|p| {
+                        !p.is_long_term() && p.pic_num(frame_num, max_frame_num) == pic_num
+                    })
+                }
+                RefPicListModification::LongTerm(long_term_pic_num) => self
+                    .pictures
+                    .iter()
+                    .find(|p| p.long_term_pic_num() == Some(long_term_pic_num)),
+            };
+            let picture = picture.ok_or(H264Error::MissingReference)?;
+            list.insert(ref_idx, Some(picture));
+            // Remove the later duplicate of the picture that was just inserted.
+            let mut index = ref_idx + 1;
+            while index < list.len() {
+                if list[index].is_some_and(|p| p.picture.id == picture.picture.id) {
+                    list.remove(index);
+                } else {
+                    index += 1;
+                }
+            }
+            list.truncate(num_active);
+            list.resize(num_active, None);
+        }
+        Ok(())
+    }
+
+    /// Marks the just decoded reference picture, applying the marking operations
+    /// of its slice header (8.2.5). Returns whether a `memory_management_control_operation`
+    /// equal to 5 was executed.
+    pub fn mark(
+        &mut self,
+        picture: Rc<Picture>,
+        header: &SliceHeader,
+        max_num_ref_frames: u32,
+        max_frame_num: u32,
+    ) -> bool {
+        let frame_num = header.frame_num;
+        let mut reference = Reference::ShortTerm;
+        let mut had_mmco5 = false;
+
+        if header.idr {
+            self.pictures.clear();
+            if header.long_term_reference {
+                reference = Reference::LongTerm(0);
+                self.max_long_term_frame_idx = Some(0);
+            } else {
+                self.max_long_term_frame_idx = None;
+            }
+        } else if let Some(operations) = &header.mmcos {
+            for operation in operations {
+                match *operation {
+                    Mmco::ForgetShortTerm {
+                        difference_of_pic_nums,
+                    } => {
+                        let pic_num = frame_num as i32 - difference_of_pic_nums as i32;
+                        self.pictures.retain(|p| {
+                            p.is_long_term() || p.pic_num(frame_num, max_frame_num) != pic_num
+                        });
+                    }
+                    Mmco::ForgetLongTerm { long_term_pic_num } => {
+                        self.pictures
+                            .retain(|p| p.long_term_pic_num() != Some(long_term_pic_num));
+                    }
+                    Mmco::ShortTermToLongTerm {
+                        difference_of_pic_nums,
+                        long_term_frame_idx,
+                    } => {
+                        let pic_num = frame_num as i32 - difference_of_pic_nums as i32;
+                        self.pictures
+                            .retain(|p| p.long_term_pic_num() != Some(long_term_frame_idx));
+                        if let Some(p) = self.pictures.iter_mut().find(|p| {
+                            !p.is_long_term() && p.pic_num(frame_num, max_frame_num) == pic_num
+                        }) {
+                            p.reference = Reference::LongTerm(long_term_frame_idx);
+                        }
+                    }
+                    Mmco::SetMaxLongTermFrameIdx {
+                        max_long_term_frame_idx_plus1,
+                    } => {
+                        self.max_long_term_frame_idx = max_long_term_frame_idx_plus1.checked_sub(1);
+                        let max = self.max_long_term_frame_idx;
+                        self.pictures.retain(|p| match p.long_term_pic_num() {
+                            Some(idx) => max.is_some_and(|max| idx <= max),
+                            None => true,
+                        });
+                    }
+                    Mmco::ForgetAll => {
+                        self.pictures.clear();
+                        self.max_long_term_frame_idx = None;
+                        had_mmco5 = true;
+                    }
+                    Mmco::CurrentToLongTerm {
+                        long_term_frame_idx,
+                    } => {
+                        self.pictures
+                            .retain(|p| p.long_term_pic_num() != Some(long_term_frame_idx));
+                        reference = Reference::LongTerm(long_term_frame_idx);
+                    }
+                }
+            }
+        } else {
+            // Sliding window (8.2.5.3).
+            let max = max_num_ref_frames.max(1) as usize;
+            while self.pictures.len() >= max {
+                let oldest = self
+                    .pictures
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, p)| !p.is_long_term())
+                    .min_by_key(|(_, p)| p.pic_num(frame_num, max_frame_num))
+                    .map(|(i, _)| i);
+                match oldest {
+                    Some(index) => {
+                        self.pictures.remove(index);
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        self.pictures.push(RefPicture {
+            picture,
+            frame_num: if had_mmco5 { 0 } else { frame_num },
+            reference,
+        });
+        // Don't let broken streams make the buffer grow indefinitely.
+        let max = max_num_ref_frames.max(1) as usize;
+        while self.pictures.len() > max {
+            self.pictures.remove(0);
+        }
+        had_mmco5
+    }
+}
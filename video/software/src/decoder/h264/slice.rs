@@ -0,0 +1,333 @@
+//! Slice headers (7.3.3).
+
+use super::bitreader::BitReader;
+use super::params::{Pps, Sps};
+use super::H264Error;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SliceType {
+    P,
+    B,
+    I,
+}
+
+/// A single `modification_of_pic_nums_idc` operation (7.4.3.1).
+#[derive(Clone, Copy, Debug)]
+pub enum RefPicListModification {
+    ShortTermSubtract(u32),
+    ShortTermAdd(u32),
+    LongTerm(u32),
+}
+
+/// A memory management control operation (7.4.3.3).
+#[derive(Clone, Copy, Debug)]
+pub enum Mmco {
+    ForgetShortTerm {
+        difference_of_pic_nums: u32,
+    },
+    ForgetLongTerm {
+        long_term_pic_num: u32,
+    },
+    ShortTermToLongTerm {
+        difference_of_pic_nums: u32,
+        long_term_frame_idx: u32,
+    },
+    SetMaxLongTermFrameIdx {
+        max_long_term_frame_idx_plus1: u32,
+    },
+    ForgetAll,
+    CurrentToLongTerm {
+        long_term_frame_idx: u32,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Weights {
+    pub luma_weight: i32,
+    pub luma_offset: i32,
+    pub chroma_weight: [i32; 2],
+    pub chroma_offset: [i32; 2],
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PredWeightTable {
+    pub luma_log2_denom: u32,
+    pub chroma_log2_denom: u32,
+    pub weights: [Vec<Weights>; 2],
+}
+
+#[derive(Clone, Debug)]
+pub struct SliceHeader {
+    pub first_mb_in_slice: u32,
+    pub slice_type: SliceType,
+    pub frame_num: u32,
+    pub idr: bool,
+    pub nal_ref_idc: u8,
+    pub pic_order_cnt_lsb: u32,
+    pub delta_pic_order_cnt_bottom: i32,
+    pub delta_pic_order_cnt: [i32; 2],
+    pub direct_spatial_mv_pred: bool,
+    pub num_ref_idx_active: [u32; 2],
+    pub ref_pic_list_modifications: [Vec<RefPicListModification>; 2],
+    pub pred_weight_table: Option<PredWeightTable>,
+    pub no_output_of_prior_pics: bool,
+    pub long_term_reference: bool,
+    pub mmcos: Option<Vec<Mmco>>,
+    pub slice_qp: i32,
+    pub disable_deblocking_filter_idc: u32,
+    pub slice_alpha_c0_offset: i32,
+    pub slice_beta_offset: i32,
+}
+
+impl SliceHeader {
+    /// Reads the PPS id of the slice, needed to know which parameter sets to parse the rest with.
+    pub fn peek_pps_id(data: &[u8]) -> Result<u32, H264Error> {
+        let mut r = BitReader::new(data);
+        r.read_ue()?; // first_mb_in_slice
+        r.read_ue()?; // slice_type
+        r.read_ue()
+    }
+
+    pub fn parse(
+        r: &mut BitReader,
+        nal_unit_type: u8,
+        nal_ref_idc: u8,
+        sps: &Sps,
+        pps: &Pps,
+    ) -> Result<Self, H264Error> {
+        let idr = nal_unit_type == 5;
+        let first_mb_in_slice = r.read_ue()?;
+        let slice_type = match r.read_ue()? % 5 {
+            0 => SliceType::P,
+            1 => SliceType::B,
+            2 => SliceType::I,
+            3 | 4 => return Err(H264Error::Unsupported("Switching slices")),
+            _ => unreachable!(),
+        };
+        if idr && slice_type != SliceType::I {
+            return Err(H264Error::InvalidData("IDR picture with inter slices"));
+        }
+        let _pps_id = r.read_ue()?;
+        let frame_num = r.read_bits(sps.log2_max_frame_num)?;
+        if idr {
+            let _idr_pic_id = r.read_ue()?;
+        }
+
+        let mut pic_order_cnt_lsb = 0;
+        let mut delta_pic_order_cnt_bottom = 0;
+        let mut delta_pic_order_cnt = [0; 2];
+        if sps.pic_order_cnt_type == 0 {
+            pic_order_cnt_lsb = r.read_bits(sps.log2_max_pic_order_cnt_lsb)?;
+            if pps.bottom_field_pic_order_in_frame_present {
+                delta_pic_order_cnt_bottom = r.read_se()?;
+            }
+        }
+        if sps.pic_order_cnt_type == 1 && !sps.delta_pic_order_always_zero {
+            delta_pic_order_cnt[0] = r.read_se()?;
+            if pps.bottom_field_pic_order_in_frame_present {
+                delta_pic_order_cnt[1] = r.read_se()?;
+            }
+        }
+        if pps.redundant_pic_cnt_present && r.read_ue()? != 0 {
+            return Err(H264Error::Unsupported("Redundant pictures"));
+        }
+
+        let direct_spatial_mv_pred = slice_type == SliceType::B && r.read_flag()?;
+
+        let mut num_ref_idx_active = [0, 0];
+        if slice_type != SliceType::I {
+            num_ref_idx_active = [
+                pps.num_ref_idx_l0_default_active,
+                pps.num_ref_idx_l1_default_active,
+            ];
+            if r.read_flag()? {
+                num_ref_idx_active[0] = r.read_ue()? + 1;
+                if slice_type == SliceType::B {
+                    num_ref_idx_active[1] = r.read_ue()? + 1;
+                }
+            }
+            if slice_type != SliceType::B {
+                num_ref_idx_active[1] = 0;
+            }
+            if num_ref_idx_active[0] > 32 || num_ref_idx_active[1] > 32 {
+                return Err(H264Error::InvalidData("Too many active reference indices"));
+            }
+        }
+
+        let mut ref_pic_list_modifications = [Vec::new(), Vec::new()];
+        let num_lists = match slice_type {
+            SliceType::I => 0,
+            SliceType::P => 1,
+            SliceType::B => 2,
+        };
+        for modifications in ref_pic_list_modifications.iter_mut().take(num_lists) {
+            if r.read_flag()? {
+                loop {
+                    let modification = match r.read_ue()? {
+                        0 => RefPicListModification::ShortTermSubtract(r.read_ue()? + 1),
+                        1 => RefPicListModification::ShortTermAdd(r.read_ue()? + 1),
+                        2 => RefPicListModification::LongTerm(r.read_ue()?),
+                        3 => break,
+                        _ => {
+                            return Err(H264Error::InvalidData(
+                                "Invalid reference list modification",
+                            ));
+                        }
+                    };
+                    if modifications.len() > 32 {
+                        return Err(H264Error::InvalidData(
+                            "Too many reference list modifications",
+                        ));
+                    }
+                    modifications.push(modification);
+                }
+            }
+        }
+
+        let pred_weight_table = if (pps.weighted_pred && slice_type == SliceType::P)
+            || (pps.weighted_bipred_idc == 1 && slice_type == SliceType::B)
+        {
+            Some(parse_pred_weight_table(
+                r,
+                &num_ref_idx_active[..num_lists],
+            )?)
+        } else {
+            None
+        };
+
+        let mut no_output_of_prior_pics = false;
+        let mut long_term_reference = false;
+        let mut mmcos = None;
+        if nal_ref_idc != 0 {
+            if idr {
+                no_output_of_prior_pics = r.read_flag()?;
+                long_term_reference = r.read_flag()?;
+            } else if r.read_flag()? {
+                let mut operations = Vec::new();
+                loop {
+                    let operation = match r.read_ue()? {
+                        0 => break,
+                        1 => Mmco::ForgetShortTerm {
+                            difference_of_pic_nums: r.read_ue()? + 1,
+                        },
+                        2 => Mmco::ForgetLongTerm {
+                            long_term_pic_num: r.read_ue()?,
+                        },
+                        3 => Mmco::ShortTermToLongTerm {
+                            difference_of_pic_nums: r.read_ue()? + 1,
+                            long_term_frame_idx: r.read_ue()?,
+                        },
+                        4 => Mmco::SetMaxLongTermFrameIdx {
+                            max_long_term_frame_idx_plus1: r.read_ue()?,
+                        },
+                        5 => Mmco::ForgetAll,
+                        6 => Mmco::CurrentToLongTerm {
+                            long_term_frame_idx: r.read_ue()?,
+                        },
+                        _ => {
+                            return Err(H264Error::InvalidData(
+                                "Invalid memory management operation",
+                            ));
+                        }
+                    };
+                    if operations.len() > 66 {
+                        return Err(H264Error::InvalidData(
+                            "Too many memory management operations",
+                        ));
+                    }
+                    operations.push(operation);
+                }
+                mmcos = Some(operations);
+            }
+        }
+
+        if pps.entropy_coding_mode && slice_type != SliceType::I {
+            let _cabac_init_idc = r.read_ue()?;
+        }
+        let slice_qp = pps.pic_init_qp + r.read_se()?;
+        if !(0..=51).contains(&slice_qp) {
+            return Err(H264Error::InvalidData("Slice QP out of range"));
+        }
+
+        let mut disable_deblocking_filter_idc = 0;
+        let mut slice_alpha_c0_offset = 0;
+        let mut slice_beta_offset = 0;
+        if pps.deblocking_filter_control_present {
+            disable_deblocking_filter_idc = r.read_ue()?;
+            if disable_deblocking_filter_idc > 2 {
+                return Err(H264Error::InvalidData("Invalid deblocking filter mode"));
+            }
+            if disable_deblocking_filter_idc != 1 {
+                slice_alpha_c0_offset = r.read_se()? * 2;
+                slice_beta_offset = r.read_se()? * 2;
+                if !(-12..=12).contains(&slice_alpha_c0_offset)
+                    || !(-12..=12).contains(&slice_beta_offset)
+                {
+                    return Err(H264Error::InvalidData(
+                        "Deblocking filter offset out of range",
+                    ));
+                }
+            }
+        }
+
+        Ok(Self {
+            first_mb_in_slice,
+            slice_type,
+            frame_num,
+            idr,
+            nal_ref_idc,
+            pic_order_cnt_lsb,
+            delta_pic_order_cnt_bottom,
+            delta_pic_order_cnt,
+            direct_spatial_mv_pred,
+            num_ref_idx_active,
+            ref_pic_list_modifications,
+            pred_weight_table,
+            no_output_of_prior_pics,
+            long_term_reference,
+            mmcos,
+            slice_qp,
+            disable_deblocking_filter_idc,
+            slice_alpha_c0_offset,
+            slice_beta_offset,
+        })
+    }
+}
+
+fn parse_pred_weight_table(
+    r: &mut BitReader,
+    num_ref_idx_active: &[u32],
+) -> Result<PredWeightTable, H264Error> {
+    let luma_log2_denom = r.read_ue()?;
+    let chroma_log2_denom = r.read_ue()?;
+    if luma_log2_denom > 7 || chroma_log2_denom > 7 {
+        return Err(H264Error::InvalidData("Weight denominator out of range"));
+    }
+    let mut table = PredWeightTable {
+        luma_log2_denom,
+        chroma_log2_denom,
+        weights: [Vec::new(), Vec::new()],
+    };
+    for (list, &count) in num_ref_idx_active.iter().enumerate() {
+        for _ in 0..count {
+            let mut weights = Weights {
+                luma_weight: 1 << luma_log2_denom,
+                luma_offset: 0,
+                chroma_weight: [1 << chroma_log2_denom; 2],
+                chroma_offset: [0; 2],
+            };
+            if r.read_flag()? {
+                weights.luma_weight = r.read_se()?;
+                weights.luma_offset = r.read_se()?;
+            }
+            if r.read_flag()? {
+                for i in 0..2 {
+                    weights.chroma_weight[i] = r.read_se()?;
+                    weights.chroma_offset[i] = r.read_se()?;
+                }
+            }
+            table.weights[list].push(weights);
+        }
+    }
+    Ok(table)
+}
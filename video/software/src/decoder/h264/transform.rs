@@ -0,0 +1,129 @@
+//! Scaling and inverse transform of residual blocks (8.5.12).
+
+use super::params::ScalingLists4x4;
+
+/// Maps the zig-zag scan position of a 4x4 block to its raster position.
+pub const ZIGZAG_4X4: [usize; 16] = [0, 1, 4, 8, 5, 2, 3, 6, 9, 12, 13, 10, 7, 11, 14, 15];
+
+const NORM_ADJUST_4X4: [[i32; 3]; 6] = [
+    [10, 16, 13],
+    [11, 18, 14],
+    [13, 20, 16],
+    [14, 23, 18],
+    [16, 25, 20],
+    [18, 29, 23],
+];
+
+/// `LevelScale4x4` for each of the six scaling lists and each `qP % 6`, in raster order.
+pub type LevelScale = [[[i32; 16]; 6]; 6];
+
+pub fn level_scale(lists: &ScalingLists4x4) -> LevelScale {
+    let mut scale = [[[0; 16]; 6]; 6];
+    for (list_scale, list) in scale.iter_mut().zip(lists) {
+        for (m, m_scale) in list_scale.iter_mut().enumerate() {
+            for (scan_pos, &weight) in list.iter().enumerate() {
+                let pos = ZIGZAG_4X4[scan_pos];
+                let (x, y) = (pos % 4, pos / 4);
+                let norm = match (x % 2, y % 2) {
+                    (0, 0) => NORM_ADJUST_4X4[m][0],
+                    (1, 1) => NORM_ADJUST_4X4[m][1],
+                    _ => NORM_ADJUST_4X4[m][2],
+                };
+                m_scale[pos] = weight as i32 * norm;
+            }
+        }
+    }
+    scale
+}
+
+/// Scales the coefficients of a 4x4 block in place (8.5.12.1).
+/// The DC coefficient is left untouched if it was already scaled separately.
+pub fn dequant_4x4(block: &mut [i32; 16], scale: &[i32; 16], qp: i32, skip_dc: bool) {
+    let start = skip_dc as usize;
+    if qp >= 24 {
+        let shift = qp / 6 - 4;
+        for i in start..16 {
+            block[i] = (block[i] * scale[i]) << shift;
+        }
+    } else {
+        let shift = 4 - qp / 6;
+        let round = 1 << (shift - 1);
+        for i in start..16 {
+            block[i] = (block[i] * scale[i] + round) >> shift;
+        }
+    }
+}
+
+/// Inverse transform and scaling of the Intra 16x16 luma DC coefficients (8.5.10).
+/// Both input and output are in raster order, one entry per 4x4 block.
+pub fn luma_dc_dequant(c: &[i32; 16], scale_dc: i32, qp: i32) -> [i32; 16] {
+    let mut tmp = [0; 16];
+    for i in 0..4 {
+        let row = &c[i * 4..i * 4 + 4];
+        let s01 = row[0] + row[1];
+        let d01 = row[0] - row[1];
+        let s23 = row[2] + row[3];
+        let d23 = row[2] - row[3];
+        tmp[i * 4] = s01 + s23;
+        tmp[i * 4 + 1] = s01 - s23;
+        tmp[i * 4 + 2] = d01 - d23;
+        tmp[i * 4 + 3] = d01 + d23;
+    }
+    let mut f = [0; 16];
+    for j in 0..4 {
+        let s01 = tmp[j] + tmp[4 + j];
+        let d01 = tmp[j] - tmp[4 + j];
+        let s23 = tmp[8 + j] + tmp[12 + j];
+        let d23 = tmp[8 + j] - tmp[12 + j];
+        f[j] = s01 + s23;
+        f[4 + j] = s01 - s23;
+        f[8 + j] = d01 - d23;
+        f[12 + j] = d01 + d23;
+    }
+    if qp >= 36 {
+        let shift = qp / 6 - 6;
+        f.map(|v| (v * scale_dc) << shift)
+    } else {
+        let shift = 6 - qp / 6;
+        let round = 1 << (shift - 1);
+        f.map(|v| (v * scale_dc + round) >> shift)
+    }
+}
+
+/// Inverse transform and scaling of the 2x2 chroma DC coefficients (8.5.11).
+pub fn chroma_dc_dequant(c: &[i32; 4], scale_dc: i32, qp: i32) -> [i32; 4] {
+    let f = [
+        c[0] + c[1] + c[2] + c[3],
+        c[0] - c[1] + c[2] - c[3],
+        c[0] + c[1] - c[2] - c[3],
+        c[0] - c[1] - c[2] + c[3],
+    ];
+    f.map(|v| ((v * scale_dc) << (qp / 6)) >> 5)
+}
+
+/// Applies the inverse 4x4 transform to `block` and adds the result to the prediction in `dst`.
+pub fn idct_4x4_add(block: &[i32; 16], dst: &mut [u8], stride: usize) {
+    let mut tmp = [0; 16];
+    for i in 0..4 {
+        let d = &block[i * 4..i * 4 + 4];
+        let e0 = d[0] + d[2];
+        let e1 = d[0] - d[2];
+        let e2 = (d[1] >> 1) - d[3];
+        let e3 = d[1] + (d[3] >> 1);
+        tmp[i * 4] = e0 + e3;
+        tmp[i * 4 + 1] = e1 + e2;
+        tmp[i * 4 + 2] = e1 - e2;
+        tmp[i * 4 + 3] = e0 - e3;
+    }
+    for j in 0..4 {
+        let e0 = tmp[j] + tmp[8 + j];
+        let e1 = tmp[j] - tmp[8 + j];
+        let e2 = (tmp[4 + j] >> 1) - tmp[12 + j];
+        let e3 = tmp[4 + j] + (tmp[12 + j] >> 1);
+        let column = [e0 + e3, e1 + e2, e1 - e2, e0 - e3];
+        for (i, value) in column.into_iter().enumerate() {
+            let sample = &mut dst[i * stride + j];
+            *sample = (*sample as i32 + ((value + 32) >> 6)).clamp(0, 255) as u8;
+        }
+    }
+}
@@ -5,7 +5,7 @@ use crate::decoder::VideoDecoder;
 use ruffle_render::bitmap::BitmapFormat;
 use ruffle_video::error::Error;
 
-use flate2::Decompress;
+use flate2::{Decompress, FlushDecompress};
 use ruffle_video::frame::{DecodedFrame, EncodedFrame, FrameDependency};
 
 #[derive(thiserror::Error, Debug)]
@@ -24,6 +24,15 @@ pub enum ScreenError {
 
     #[error("Not all blocks were updated by a supposed keyframe")]
     KeyframeInvalid,
+
+    #[error("Invalid color depth: {0}")]
+    InvalidColorDepth(u8),
+
+    #[error("Invalid image block diff")]
+    InvalidDiffBlock,
+
+    #[error("Invalid zlib prime block")]
+    InvalidPrimeBlock,
 }
 
 impl From<ScreenError> for Error {
@@ -32,8 +41,51 @@ impl From<ScreenError> for Error {
     }
 }
 
-/// Screen Video (V1 only) decoder.
+/// The palette of the hybrid color mode of Screen Video V2,
+/// used until the stream provides its own. In RGB order.
+#[rustfmt::skip]
+const DEFAULT_PALETTE: [[u8; 3]; 128] = [
+    [0x00, 0x00, 0x00], [0x33, 0x33, 0x33], [0x66, 0x66, 0x66], [0x99, 0x99, 0x99],
+    [0xCC, 0xCC, 0xCC], [0xFF, 0xFF, 0xFF], [0x33, 0x00, 0x00], [0x66, 0x00, 0x00],
+    [0x99, 0x00, 0x00], [0xCC, 0x00, 0x00], [0xFF, 0x00, 0x00], [0x00, 0x33, 0x00],
+    [0x00, 0x66, 0x00], [0x00, 0x99, 0x00], [0x00, 0xCC, 0x00], [0x00, 0xFF, 0x00],
+    [0x00, 0x00, 0x33], [0x00, 0x00, 0x66], [0x00, 0x00, 0x99], [0x00, 0x00, 0xCC],
+    [0x00, 0x00, 0xFF], [0x33, 0x33, 0x00], [0x66, 0x66, 0x00], [0x99, 0x99, 0x00],
+    [0xCC, 0xCC, 0x00], [0xFF, 0xFF, 0x00], [0x00, 0x33, 0x33], [0x00, 0x66, 0x66],
+    [0x00, 0x99, 0x99], [0x00, 0xCC, 0xCC], [0x00, 0xFF, 0xFF], [0x33, 0x00, 0x33],
+    [0x66, 0x00, 0x66], [0x99, 0x00, 0x99], [0xCC, 0x00, 0xCC], [0xFF, 0x00, 0xFF],
+    [0xFF, 0xFF, 0x33], [0xFF, 0xFF, 0x66], [0xFF, 0xFF, 0x99], [0xFF, 0xFF, 0xCC],
+    [0xFF, 0x33, 0xFF], [0xFF, 0x66, 0xFF], [0xFF, 0x99, 0xFF], [0xFF, 0xCC, 0xFF],
+    [0x33, 0xFF, 0xFF], [0x66, 0xFF, 0xFF], [0x99, 0xFF, 0xFF], [0xCC, 0xFF, 0xFF],
+    [0xCC, 0xCC, 0x33], [0xCC, 0xCC, 0x66], [0xCC, 0xCC, 0x99], [0xCC, 0xCC, 0xFF],
+    [0xCC, 0x33, 0xCC], [0xCC, 0x66, 0xCC], [0xCC, 0x99, 0xCC], [0xCC, 0xFF, 0xCC],
+    [0x33, 0xCC, 0xCC], [0x66, 0xCC, 0xCC], [0x99, 0xCC, 0xCC], [0xFF, 0xCC, 0xCC],
+    [0x99, 0x99, 0x33], [0x99, 0x99, 0x66], [0x99, 0x99, 0xCC], [0x99, 0x99, 0xFF],
+    [0x99, 0x33, 0x99], [0x99, 0x66, 0x99], [0x99, 0xCC, 0x99], [0x99, 0xFF, 0x99],
+    [0x33, 0x99, 0x99], [0x66, 0x99, 0x99], [0xCC, 0x99, 0x99], [0xFF, 0x99, 0x99],
+    [0x66, 0x66, 0x33], [0x66, 0x66, 0x99], [0x66, 0x66, 0xCC], [0x66, 0x66, 0xFF],
+    [0x66, 0x33, 0x66], [0x66, 0x99, 0x66], [0x66, 0xCC, 0x66], [0x66, 0xFF, 0x66],
+    [0x33, 0x66, 0x66], [0x99, 0x66, 0x66], [0xCC, 0x66, 0x66], [0xFF, 0x66, 0x66],
+    [0x33, 0x33, 0x66], [0x33, 0x33, 0x99], [0x33, 0x33, 0xCC], [0x33, 0x33, 0xFF],
+    [0x33, 0x66, 0x33], [0x33, 0x99, 0x33], [0x33, 0xCC, 0x33], [0x33, 0xFF, 0x33],
+    [0x66, 0x33, 0x33], [0x99, 0x33, 0x33], [0xCC, 0x33, 0x33], [0xFF, 0x33, 0x33],
+    [0x00, 0x33, 0x66], [0x33, 0x66, 0x00], [0x66, 0x00, 0x33], [0x00, 0x66, 0x33],
+    [0x33, 0x00, 0x66], [0x66, 0x33, 0x00], [0x33, 0x66, 0x99], [0x66, 0x99, 0x33],
+    [0x99, 0x33, 0x66], [0x33, 0x99, 0x66], [0x66, 0x33, 0x99], [0x99, 0x66, 0x33],
+    [0x66, 0x99, 0xCC], [0x99, 0xCC, 0x66], [0xCC, 0x66, 0x99], [0x66, 0xCC, 0x99],
+    [0x99, 0x66, 0xCC], [0xCC, 0x99, 0x66], [0x99, 0xCC, 0xFF], [0xCC, 0xFF, 0x99],
+    [0xFF, 0x99, 0xCC], [0x99, 0xFF, 0xCC], [0xCC, 0x99, 0xFF], [0xFF, 0xCC, 0x99],
+    [0x11, 0x11, 0x11], [0x22, 0x22, 0x22], [0x44, 0x44, 0x44], [0x55, 0x55, 0x55],
+    [0xAA, 0xAA, 0xAA], [0xBB, 0xBB, 0xBB], [0xDD, 0xDD, 0xDD], [0xEE, 0xEE, 0xEE],
+];
+
+/// The size of the zlib sliding window, which is all that matters for priming.
+const ZLIB_WINDOW_SIZE: usize = 32768;
+
+/// Screen Video (V1 and V2) decoder.
 pub struct ScreenVideoDecoder {
+    version: u8,
+
     w: usize,
     h: usize,
     block_w: usize,
@@ -42,6 +94,35 @@ pub struct ScreenVideoDecoder {
     tile: Vec<u8>, // acts as a scratch buffer
 
     last_frame: Option<Vec<u8>>,
+
+    // The following are only used by V2.
+    palette: [[u8; 3]; 128],
+
+    /// The last keyframe (or explicit I-frame image), which diff blocks are applied on.
+    golden_frame: Option<Vec<u8>>,
+
+    /// The decompressed data of each block of the golden frame, used for zlib priming.
+    golden_blocks: Vec<Vec<u8>>,
+
+    /// The decompressed data of each block of the current frame, used for zlib priming.
+    current_blocks: Vec<Vec<u8>>,
+}
+
+/// The header of a Screen Video V2 image block.
+struct BlockV2Header {
+    hybrid: bool,
+    diff_start: usize,
+    diff_height: usize,
+    prime: Option<PrimeSource>,
+}
+
+/// Which already decoded block the zlib stream of a block is primed with.
+enum PrimeSource {
+    /// A block of the current frame, at the given column and row.
+    Current(usize, usize),
+
+    /// A block of the golden frame, at the given column and row.
+    Golden(usize, usize),
 }
 
 struct ByteReader<'a> {
@@ -81,13 +162,26 @@ impl<'a> ByteReader<'a> {
 
 impl ScreenVideoDecoder {
     pub fn new() -> Self {
+        Self::with_version(1)
+    }
+
+    pub fn new_v2() -> Self {
+        Self::with_version(2)
+    }
+
+    fn with_version(version: u8) -> Self {
         Self {
+            version,
             w: 0,
             h: 0,
             block_w: 0,
             block_h: 0,
             tile: vec![],
             last_frame: None,
+            palette: DEFAULT_PALETTE,
+            golden_frame: None,
+            golden_blocks: vec![],
+            current_blocks: vec![],
         }
     }
 
@@ -127,9 +221,201 @@ impl ScreenVideoDecoder {
         Ok(is_intra)
     }
 
+    /// Decodes a set of image blocks into `data`, storing the decompressed data of each
+    /// of them in `blocks`, which is also what blocks primed from the current image use.
+    fn decode_v2(
+        &mut self,
+        src: &mut ByteReader,
+        data: &mut [u8],
+        stride: usize,
+        blocks: &mut [Vec<u8>],
+    ) -> Result<bool, Error> {
+        let blocks_per_row = self.w.div_ceil(self.block_w);
+        let mut is_intra = true;
+        for (yy, row) in data.chunks_mut(stride * self.block_h).enumerate() {
+            let cur_h = (self.h - yy * self.block_h).min(self.block_h);
+            for (xx, x) in (0..self.w).step_by(self.block_w).enumerate() {
+                let cur_w = (self.w - x).min(self.block_w);
+                let block_index = yy * blocks_per_row + xx;
+
+                let data_size = src.read_u16be()? as usize;
+                if data_size == 0 {
+                    is_intra = false;
+                    continue;
+                }
+                let mut block = ByteReader::new(src.read_buf_ref(data_size)?);
+                let header = Self::read_block_v2_header(&mut block, cur_h, xx, yy)?;
+
+                if header.diff_start != 0 || header.diff_height != cur_h {
+                    // Only some rows are updated; the rest come from the golden frame.
+                    let golden = self
+                        .golden_frame
+                        .as_ref()
+                        .ok_or(ScreenError::MissingReferenceFrame)?;
+                    let golden_rows = golden[yy * stride * self.block_h..].chunks(stride);
+                    for (dst, src) in row.chunks_mut(stride).zip(golden_rows).take(cur_h) {
+                        dst[x * 3..(x + cur_w) * 3].copy_from_slice(&src[x * 3..(x + cur_w) * 3]);
+                    }
+                }
+
+                let prime_block = match header.prime {
+                    None => None,
+                    Some(PrimeSource::Current(col, row)) => {
+                        Some(blocks.get(row * blocks_per_row + col))
+                    }
+                    Some(PrimeSource::Golden(col, row)) => {
+                        Some(self.golden_blocks.get(row * blocks_per_row + col))
+                    }
+                };
+                let dictionary = match prime_block {
+                    None => None,
+                    Some(Some(block)) if !block.is_empty() => Some(block.as_slice()),
+                    Some(_) => return Err(ScreenError::InvalidPrimeBlock.into()),
+                };
+                let length = decompress(&block.data[block.pos..], dictionary, &mut self.tile)?;
+                let decompressed = &self.tile[..length];
+
+                let rows = row
+                    .chunks_mut(stride)
+                    .skip(header.diff_start)
+                    .take(header.diff_height);
+                if header.hybrid {
+                    let mut src = ByteReader::new(decompressed);
+                    for dst in rows {
+                        for pixel in dst[x * 3..(x + cur_w) * 3].chunks_mut(3) {
+                            let byte = src.read_byte()?;
+                            let [r, g, b] = if byte & 0x80 != 0 {
+                                // A 15-bit RGB color.
+                                let color = ((byte as u16 & 0x7F) << 8) | src.read_byte()? as u16;
+                                let expand = |c: u16| ((c << 3) | (c >> 2)) as u8;
+                                [
+                                    expand(color >> 10),
+                                    expand((color >> 5) & 0x1F),
+                                    expand(color & 0x1F),
+                                ]
+                            } else {
+                                // An index into the palette.
+                                self.palette[byte as usize]
+                            };
+                            pixel.copy_from_slice(&[b, g, r]);
+                        }
+                    }
+                } else {
+                    let mut lines = decompressed.chunks_exact(cur_w * 3);
+                    for dst in rows {
+                        let line = lines.next().ok_or(ScreenError::UnexpectedEOF)?;
+                        dst[x * 3..(x + cur_w) * 3].copy_from_slice(line);
+                    }
+                }
+
+                blocks[block_index].clear();
+                blocks[block_index].extend_from_slice(decompressed);
+            }
+        }
+        Ok(is_intra)
+    }
+
+    fn read_block_v2_header(
+        block: &mut ByteReader,
+        cur_h: usize,
+        col: usize,
+        row: usize,
+    ) -> Result<BlockV2Header, ScreenError> {
+        // Reserved (3 bits), color depth (2 bits), HasDiffBlocks,
+        // ZlibPrimeCompressCurrent, ZlibPrimeCompressPrevious
+        let flags = block.read_byte()?;
+        let color_depth = (flags >> 3) & 0b11;
+        let hybrid = match color_depth {
+            0 => false,
+            2 => true,
+            _ => return Err(ScreenError::InvalidColorDepth(color_depth)),
+        };
+        let has_diff = flags & 0b100 != 0;
+        let prime_current = flags & 0b10 != 0;
+        let prime_previous = flags & 0b1 != 0;
+
+        let (diff_start, diff_height) = if has_diff {
+            let start = block.read_byte()? as usize;
+            let height = block.read_byte()? as usize;
+            if start + height > cur_h {
+                return Err(ScreenError::InvalidDiffBlock);
+            }
+            (start, height)
+        } else {
+            (0, cur_h)
+        };
+
+        let prime = match (prime_current, prime_previous) {
+            (true, prime_previous) => {
+                let col = block.read_byte()? as usize;
+                let row = block.read_byte()? as usize;
+                if prime_previous {
+                    Some(PrimeSource::Golden(col, row))
+                } else {
+                    Some(PrimeSource::Current(col, row))
+                }
+            }
+            // Without a position, the block at the same position in the golden frame is used.
+            (false, true) => Some(PrimeSource::Golden(col, row)),
+            (false, false) => None,
+        };
+
+        Ok(BlockV2Header {
+            hybrid,
+            diff_start,
+            diff_height,
+            prime,
+        })
+    }
+
+    /// Reads a custom palette for hybrid blocks, made of up to 128 zlib compressed RGB colors.
+    fn read_palette(&mut self, src: &mut ByteReader) -> Result<(), Error> {
+        let size = src.read_u16be()? as usize;
+        let mut palette = [0; 128 * 3];
+        let length = decompress(src.read_buf_ref(size)?, None, &mut palette)?;
+        for (entry, color) in self
+            .palette
+            .iter_mut()
+            .zip(palette[..length].chunks_exact(3))
+        {
+            entry.copy_from_slice(color);
+        }
+        Ok(())
+    }
+
     fn flush(&mut self) {
         self.last_frame = None;
+        self.golden_frame = None;
+        self.golden_blocks.clear();
+        self.current_blocks.clear();
+    }
+}
+
+/// Decompresses a zlib stream into `out`, returning the number of bytes written.
+///
+/// If a dictionary is given, the stream is treated as the continuation of another one,
+/// which has already produced the dictionary, as with the zlib priming of Screen Video V2.
+fn decompress(
+    data: &[u8],
+    dictionary: Option<&[u8]>,
+    out: &mut [u8],
+) -> Result<usize, ScreenError> {
+    let mut stream = Decompress::new(dictionary.is_none());
+    if let Some(dictionary) = dictionary {
+        // Feed the dictionary as a non-final stored deflate block to fill the window.
+        let dictionary = &dictionary[dictionary.len().saturating_sub(ZLIB_WINDOW_SIZE)..];
+        let length = dictionary.len() as u16;
+        let mut stored = Vec::with_capacity(dictionary.len() + 5);
+        stored.push(0);
+        stored.extend_from_slice(&length.to_le_bytes());
+        stored.extend_from_slice(&(!length).to_le_bytes());
+        stored.extend_from_slice(dictionary);
+        let mut scratch = vec![0; dictionary.len()];
+        stream.decompress(&stored, &mut scratch, FlushDecompress::Sync)?;
     }
+    let before = stream.total_out();
+    stream.decompress(data, out, FlushDecompress::Finish)?;
+    Ok((stream.total_out() - before) as usize)
 }
 
 impl VideoDecoder for ScreenVideoDecoder {
@@ -139,7 +425,7 @@ impl VideoDecoder for ScreenVideoDecoder {
         // in FLV. This is super helpful, because it encodes whether the frame is a keyframe or not.
 
         // Just a quick sanity check for codec IDs...
-        debug_assert!(encoded_frame.data[0] & 0xF == 3);
+        debug_assert!(encoded_frame.data[0] & 0xF == if self.version == 2 { 6 } else { 3 });
 
         match encoded_frame.data[0] >> 4 {
             1 => Ok(FrameDependency::None),
@@ -175,6 +461,20 @@ impl VideoDecoder for ScreenVideoDecoder {
             self.h = h;
             self.block_w = blk_w;
             self.block_h = blk_h;
+            if self.version == 2 {
+                let num_blocks = w.div_ceil(blk_w) * h.div_ceil(blk_h);
+                self.current_blocks.resize(num_blocks, vec![]);
+            }
+        }
+
+        let mut has_iframe_image = false;
+        if self.version == 2 {
+            // Reserved (6 bits), HasIFrameImage, HasPaletteInfo
+            let flags = br.read_byte()?;
+            has_iframe_image = flags & 0b10 != 0;
+            if flags & 0b1 != 0 {
+                self.read_palette(&mut br)?;
+            }
         }
 
         let mut data = self
@@ -184,12 +484,38 @@ impl VideoDecoder for ScreenVideoDecoder {
 
         let stride = w * 3;
 
-        let is_intra = self.decode_v1(&mut br, data.as_mut_slice(), stride)?;
+        let is_intra = if self.version == 2 {
+            let mut blocks = std::mem::take(&mut self.current_blocks);
+            let result = self.decode_v2(&mut br, data.as_mut_slice(), stride, &mut blocks);
+            self.current_blocks = blocks;
+            result?
+        } else {
+            self.decode_v1(&mut br, data.as_mut_slice(), stride)?
+        };
 
         if is_intra != is_keyframe {
             return Err(ScreenError::KeyframeInvalid.into());
         }
 
+        if is_keyframe {
+            // This frame is what later diff blocks and zlib priming will refer to.
+            self.golden_frame = Some(data.clone());
+            self.golden_blocks.clone_from(&self.current_blocks);
+        }
+
+        if has_iframe_image {
+            // Unless the encoder sends its own, in the same format, after the displayed image.
+            // Blocks it leaves out are kept from the previous one.
+            let mut golden = self
+                .golden_frame
+                .clone()
+                .ok_or(ScreenError::MissingReferenceFrame)?;
+            let mut blocks = self.golden_blocks.clone();
+            self.decode_v2(&mut br, golden.as_mut_slice(), stride, &mut blocks)?;
+            self.golden_frame = Some(golden);
+            self.golden_blocks = blocks;
+        }
+
         let mut rgb = vec![0u8; w * h * 3];
 
         // convert from BGR to RGB and flip Y
@@ -218,3 +544,183 @@ impl Default for ScreenVideoDecoder {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{Compress, Compression, FlushCompress};
+
+    const WIDTH: usize = 32;
+    const HEIGHT: usize = 16;
+
+    fn compress(data: &[u8], prime: Option<&[u8]>) -> Vec<u8> {
+        let mut stream = Compress::new(Compression::default(), true);
+        let mut out = Vec::with_capacity(data.len() * 2 + 64);
+        if let Some(prime) = prime {
+            stream
+                .compress_vec(prime, &mut out, FlushCompress::Sync)
+                .unwrap();
+            // Only the data compressed after the priming is transmitted.
+            out.clear();
+        }
+        stream
+            .compress_vec(data, &mut out, FlushCompress::Finish)
+            .unwrap();
+        out
+    }
+
+    fn push_blocks(data: &mut Vec<u8>, blocks: &[Option<Vec<u8>>]) {
+        for block in blocks {
+            let block = block.as_deref().unwrap_or_default();
+            data.extend_from_slice(&(block.len() as u16).to_be_bytes());
+            data.extend_from_slice(block);
+        }
+    }
+
+    fn frame(keyframe: bool, blocks: &[Option<Vec<u8>>]) -> Vec<u8> {
+        frame_with_iframe_image(keyframe, blocks, None)
+    }
+
+    fn frame_with_iframe_image(
+        keyframe: bool,
+        blocks: &[Option<Vec<u8>>],
+        iframe_blocks: Option<&[Option<Vec<u8>>]>,
+    ) -> Vec<u8> {
+        let mut data = vec![if keyframe { 0x16 } else { 0x26 }];
+        data.extend_from_slice(&(WIDTH as u16).to_be_bytes());
+        data.extend_from_slice(&(HEIGHT as u16).to_be_bytes());
+        data.push(if iframe_blocks.is_some() { 0b10 } else { 0 });
+        push_blocks(&mut data, blocks);
+        if let Some(iframe_blocks) = iframe_blocks {
+            push_blocks(&mut data, iframe_blocks);
+        }
+        data
+    }
+
+    /// A hybrid block where every pixel is the given palette entry.
+    fn hybrid_block(index: u8) -> Vec<u8> {
+        vec![index; 16 * 16]
+    }
+
+    /// A keyframe with a white hybrid block on the left and a black one on the right.
+    fn keyframe() -> Vec<u8> {
+        let blocks = [5, 0].map(|index| {
+            let mut block = vec![0b10000];
+            block.extend(compress(&hybrid_block(index), None));
+            Some(block)
+        });
+        frame(true, &blocks)
+    }
+
+    fn decode(decoder: &mut ScreenVideoDecoder, data: &[u8]) -> Vec<u8> {
+        let frame = decoder
+            .decode_frame(EncodedFrame {
+                codec: swf::VideoCodec::ScreenVideoV2,
+                data,
+                frame_id: 0,
+            })
+            .expect("Decodable frame");
+        assert_eq!(
+            (frame.width(), frame.height()),
+            (WIDTH as u32, HEIGHT as u32)
+        );
+        frame.data().to_vec()
+    }
+
+    /// The RGB color of a pixel in the decoded (top-down) frame.
+    fn pixel(frame: &[u8], x: usize, y: usize) -> [u8; 3] {
+        let offset = (y * WIDTH + x) * 3;
+        frame[offset..offset + 3].try_into().unwrap()
+    }
+
+    #[test]
+    fn decodes_v2_blocks() {
+        let mut decoder = ScreenVideoDecoder::new_v2();
+
+        // The left block is 24-bit BGR, with a gradient going up.
+        let bgr: Vec<u8> = (0..16 * 16)
+            .flat_map(|i| [0, 0, (i / 16 * 10) as u8])
+            .collect();
+        let mut left = vec![0];
+        left.extend(compress(&bgr, None));
+
+        // The right block is hybrid: white from the palette, with a 15-bit green bottom row.
+        let mut hybrid: Vec<u8> = (0..16).flat_map(|_| [0x83, 0xE0]).collect();
+        hybrid.extend(std::iter::repeat_n(5, 16 * 15));
+        let mut right = vec![0b10000];
+        right.extend(compress(&hybrid, None));
+
+        let keyframe = decode(&mut decoder, &frame(true, &[Some(left), Some(right)]));
+        assert_eq!(pixel(&keyframe, 0, 15), [0, 0, 0]);
+        assert_eq!(pixel(&keyframe, 3, 0), [150, 0, 0]);
+        assert_eq!(pixel(&keyframe, 16, 15), [0, 0xFF, 0]);
+        assert_eq!(pixel(&keyframe, 31, 0), [0xFF, 0xFF, 0xFF]);
+
+        // Only update two rows of the right block, primed with the golden frame's block.
+        let mut diff = hybrid[16 * 2..16 * 2 + 2 * 16].to_vec();
+        diff.fill(1);
+        let mut right = vec![0b10111, 4, 2, 1, 0];
+        right.extend(compress(&diff, Some(&hybrid)));
+
+        let interframe = decode(&mut decoder, &frame(false, &[None, Some(right)]));
+        assert_eq!(pixel(&interframe, 3, 0), [150, 0, 0]);
+        assert_eq!(pixel(&interframe, 16, 15), [0, 0xFF, 0]);
+        assert_eq!(pixel(&interframe, 20, 11), [0x33, 0x33, 0x33]);
+        assert_eq!(pixel(&interframe, 20, 10), [0x33, 0x33, 0x33]);
+        assert_eq!(pixel(&interframe, 20, 9), [0xFF, 0xFF, 0xFF]);
+        assert_eq!(pixel(&interframe, 20, 12), [0xFF, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn primes_previous_block_at_same_position() {
+        let mut decoder = ScreenVideoDecoder::new_v2();
+        decode(&mut decoder, &keyframe());
+
+        // Only ZlibPrimeCompressPrevious is set, so no position follows the flags, and the
+        // right block is primed with the golden frame's right block.
+        let mut right = vec![0b10001];
+        right.extend(compress(&hybrid_block(1), Some(&hybrid_block(0))));
+
+        let interframe = decode(&mut decoder, &frame(false, &[None, Some(right)]));
+        assert_eq!(pixel(&interframe, 0, 0), [0xFF, 0xFF, 0xFF]);
+        assert_eq!(pixel(&interframe, 16, 0), [0x33, 0x33, 0x33]);
+        assert_eq!(pixel(&interframe, 31, 15), [0x33, 0x33, 0x33]);
+    }
+
+    #[test]
+    fn uses_iframe_image_as_reference() {
+        let mut decoder = ScreenVideoDecoder::new_v2();
+        decode(&mut decoder, &keyframe());
+
+        // The right block is displayed gray, but the I-frame image makes it light gray
+        // for the frames that follow.
+        let mut right = vec![0b10000];
+        right.extend(compress(&hybrid_block(1), None));
+        let mut iframe_right = vec![0b10000];
+        iframe_right.extend(compress(&hybrid_block(4), None));
+
+        let interframe = decode(
+            &mut decoder,
+            &frame_with_iframe_image(
+                false,
+                &[None, Some(right)],
+                Some(&[None, Some(iframe_right)]),
+            ),
+        );
+        assert_eq!(pixel(&interframe, 0, 0), [0xFF, 0xFF, 0xFF]);
+        assert_eq!(pixel(&interframe, 16, 0), [0x33, 0x33, 0x33]);
+
+        // Update two rows of the right block, primed with the right block of the I-frame image,
+        // keeping the other rows from it as well.
+        let diff = vec![2; 16 * 2];
+        let mut right = vec![0b10101, 4, 2];
+        right.extend(compress(&diff, Some(&hybrid_block(4))));
+
+        let interframe = decode(&mut decoder, &frame(false, &[None, Some(right)]));
+        assert_eq!(pixel(&interframe, 0, 0), [0xFF, 0xFF, 0xFF]);
+        assert_eq!(pixel(&interframe, 20, 11), [0x66, 0x66, 0x66]);
+        assert_eq!(pixel(&interframe, 20, 10), [0x66, 0x66, 0x66]);
+        assert_eq!(pixel(&interframe, 20, 9), [0xCC, 0xCC, 0xCC]);
+        assert_eq!(pixel(&interframe, 20, 12), [0xCC, 0xCC, 0xCC]);
+    }
+}
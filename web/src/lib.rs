@@ -18,7 +18,9 @@ use input::{web_key_to_codepoint, web_to_ruffle_key_code, web_to_ruffle_text_con
 use js_sys::{Error as JsError, Uint8Array};
 use ruffle_core::context::UpdateContext;
 use ruffle_core::context_menu::ContextMenuCallback;
-use ruffle_core::events::{GamepadButton, MouseButton, MouseWheelDelta, TextControlCode};
+use ruffle_core::events::{
+    GamepadAxis, GamepadButton, MouseButton, MouseWheelDelta, TextControlCode,
+};
 use ruffle_core::tag_utils::SwfMovie;
 use ruffle_core::{Player, PlayerEvent, StaticCallstack, ViewportDimensions};
 use ruffle_web_common::JsResult;
@@ -140,7 +142,35 @@ struct RuffleInstance {
     has_focus: bool,
     trace_observer: Rc<RefCell<JsValue>>,
     log_subscriber: Arc<Layered<WASMLayer, Registry>>,
+    gamepads: Vec<GamepadState>,
+}
+
+/// The analog axes of a gamepad using the standard mapping,
+/// in the order they're stored in `GamepadState::axis_values`.
+const GAMEPAD_AXES: [GamepadAxis; 6] = [
+    GamepadAxis::LeftStickX,
+    GamepadAxis::LeftStickY,
+    GamepadAxis::RightStickX,
+    GamepadAxis::RightStickY,
+    GamepadAxis::LeftTrigger2,
+    GamepadAxis::RightTrigger2,
+];
+
+/// The last known state of a connected gamepad.
+struct GamepadState {
+    device: u32,
     pressed_buttons: Vec<GamepadButton>,
+    axis_values: [f64; GAMEPAD_AXES.len()],
+}
+
+impl GamepadState {
+    fn new(device: u32) -> Self {
+        Self {
+            device,
+            gamepads: vec![],
+            axis_values: [0.0; GAMEPAD_AXES.len()],
+        }
+    }
 }
 
 #[wasm_bindgen(raw_module = "./internal/player/inner")]
@@ -1017,7 +1047,7 @@ impl RuffleHandle {
     fn tick(&mut self, timestamp: f64) {
         let mut dt = 0.0;
         let mut new_dimensions = None;
-        let mut gamepad_button_events = Vec::new();
+        let mut gamepad_events = Vec::new();
         let _ = self.with_instance_mut(|instance| {
             // Check for canvas resize.
             let canvas_width = instance.canvas.client_width();
@@ -1047,12 +1077,27 @@ impl RuffleHandle {
             }
 
             if let Ok(gamepads) = instance.window.navigator().get_gamepads() {
-                if let Some(gamepad) = gamepads
+                let mut connected = Vec::new();
+
+                for gamepad in gamepads
                     .into_iter()
-                    .next()
-                    .and_then(|gamepad| gamepad.dyn_into::<WebGamepad>().ok())
+                    .filter_map(|gamepad| gamepad.dyn_into::<WebGamepad>().ok())
+                    .filter(|gamepad| gamepad.connected())
                 {
+                    let device = gamepad.index();
+                    connected.push(device);
+
+                    let state = match instance.gamepads.iter().position(|s| s.device == device) {
+                        Some(index) => &mut instance.gamepads[index],
+                        None => {
+                            gamepad_events.push(PlayerEvent::GamepadConnected { device });
+                            instance.gamepads.push(GamepadState::new(device));
+                            instance.gamepads.last_mut().expect("Just pushed a gamepad")
+                        }
+                    };
+
                     let mut pressed_buttons = Vec::new();
+                    let mut axis_values = [0.0; GAMEPAD_AXES.len()];
 
                     let buttons = gamepad.buttons();
                     for (index, button) in buttons.into_iter().enumerate() {
@@ -1060,16 +1105,24 @@ impl RuffleHandle {
                             continue;
                         };
 
-                        if !button.pressed() {
-                            continue;
-                        }
-
                         // See https://w3c.github.io/gamepad/#remapping
                         let gamepad_button = match index {
                             0 => GamepadButton::South,
                             1 => GamepadButton::East,
                             2 => GamepadButton::West,
                             3 => GamepadButton::North,
+                            4 => GamepadButton::LeftTrigger,
+                            5 => GamepadButton::RightTrigger,
+                            6 => {
+                                axis_values[4] = button.value();
+                                GamepadButton::LeftTrigger2
+                            }
+                            7 => {
+                                axis_values[5] = button.value();
+                                GamepadButton::RightTrigger2
+                            }
+                            8 => GamepadButton::Select,
+                            9 => GamepadButton::Start,
                             12 => GamepadButton::DPadUp,
                             13 => GamepadButton::DPadDown,
                             14 => GamepadButton::DPadLeft,
@@ -1077,27 +1130,60 @@ impl RuffleHandle {
                             _ => continue,
                         };
 
-                        pressed_buttons.push(gamepad_button);
+                        if button.pressed() {
+                            pressed_buttons.push(gamepad_button);
+                        }
+                    }
+
+                    // The standard mapping already has positive Y pointing down.
+                    for (index, axis) in gamepad.axes().into_iter().take(4).enumerate() {
+                        axis_values[index] = axis.as_f64().unwrap_or_default();
                     }
 
-                    if pressed_buttons != instance.pressed_buttons {
+                    if pressed_buttons != state.pressed_buttons {
                         for button in pressed_buttons.iter() {
-                            if !instance.pressed_buttons.contains(button) {
-                                gamepad_button_events
-                                    .push(PlayerEvent::GamepadButtonDown { button: *button });
+                            if !state.pressed_buttons.contains(button) {
+                                gamepad_events.push(PlayerEvent::GamepadButtonDown {
+                                    device,
+                                    button: *button,
+                                });
                             }
                         }
 
-                        for button in instance.pressed_buttons.iter() {
+                        for button in state.pressed_buttons.iter() {
                             if !pressed_buttons.contains(button) {
-                                gamepad_button_events
-                                    .push(PlayerEvent::GamepadButtonUp { button: *button });
+                                gamepad_events.push(PlayerEvent::GamepadButtonUp {
+                                    device,
+                                    button: *button,
+                                });
                             }
                         }
 
-                        instance.pressed_buttons = pressed_buttons;
+                        state.pressed_buttons = pressed_buttons;
+                    }
+
+                    for (index, axis) in GAMEPAD_AXES.into_iter().enumerate() {
+                        let value = axis_values[index];
+                        if value != state.axis_values[index] {
+                            gamepad_events.push(PlayerEvent::GamepadAxisMotion {
+                                device,
+                                axis,
+                                value,
+                            });
+                        }
                     }
+                    state.axis_values = axis_values;
                 }
+
+                instance.gamepads.retain(|state| {
+                    let is_connected = connected.contains(&state.device);
+                    if !is_connected {
+                        gamepad_events.push(PlayerEvent::GamepadDisconnected {
+                            device: state.device,
+                        });
+                    }
+                    is_connected
+                });
             }
 
             // Request next animation frame.
@@ -1122,7 +1208,7 @@ impl RuffleHandle {
 
         // Tick the Ruffle core.
         let _ = self.with_core_mut(|core| {
-            for event in gamepad_button_events {
+            for event in gamepad_events {
                 core.handle_event(event);
             }
 
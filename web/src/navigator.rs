@@ -10,6 +10,7 @@ use ruffle_core::backend::navigator::{
     NavigationMethod, NavigatorBackend, OwnedFuture, Request, SuccessResponse,
 };
 use ruffle_core::config::NetworkingAccessMode;
+use ruffle_core::datagram_socket::{Datagram, DatagramSocketAction, DatagramSocketHandle};
 use ruffle_core::indexmap::IndexMap;
 use ruffle_core::loader::Error;
 use ruffle_core::socket::{ConnectionState, SocketAction, SocketHandle};
@@ -17,6 +18,7 @@ use ruffle_core::swf::Encoding;
 use ruffle_core::Player;
 use std::borrow::Cow;
 use std::cell::RefCell;
+use std::net::SocketAddr;
 use std::rc::Rc;
 use std::sync::{Arc, Mutex, Weak};
 use std::time::Duration;
@@ -559,6 +561,19 @@ impl NavigatorBackend for WebNavigatorBackend {
             Ok(())
         }));
     }
+
+    fn bind_datagram_socket(
+        &mut self,
+        host: String,
+        port: u16,
+        _handle: DatagramSocketHandle,
+        _receiver: Receiver<Datagram>,
+        _sender: Sender<DatagramSocketAction>,
+    ) -> std::io::Result<SocketAddr> {
+        // Browsers don't expose UDP, and WebSocket proxies are stream based.
+        tracing::warn!("Can't bind DatagramSocket to {}:{} on web", host, port);
+        Err(std::io::ErrorKind::Unsupported.into())
+    }
 }
 
 struct WebResponseWrapper {